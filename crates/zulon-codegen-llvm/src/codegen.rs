@@ -10,16 +10,19 @@ use crate::error::{CodegenError, Result};
 use crate::enum_layout::{EnumLayout, EnumLayoutCache};
 use crate::layout::{LayoutCache, StructLayout};
 use crate::ty::LlvmType;
+use crate::vtable::VTable;
+use crate::global::Global;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::Write;
 use std::sync::Arc;
 use zulon_lir::{LirBlock, LirFunction, LirInstruction, LirOperand, LirTerminator};
 
-/// String constant data
+/// String constant data: the bytes of a string, ending in a null byte, or
+/// of a byte string
 struct StringConstant {
     name: String,
-    value: String,
-    len: usize,
+    bytes: Vec<u8>,
 }
 
 /// LLVM IR code generator
@@ -35,12 +38,19 @@ pub struct CodeGenerator<W: Write> {
     struct_types: HashMap<String, StructLayout>,
     /// Enum type declarations
     enum_types: HashMap<String, EnumLayout>,
+    /// Vtables of trait objects, by symbol name
+    vtables: HashMap<String, VTable>,
+    /// Globals of `static` items, by name
+    globals: HashMap<String, Global>,
     /// Calling convention to use
     calling_convention: CallingConvention,
     /// String constants to emit at module level
     string_constants: Vec<StringConstant>,
-    /// Mapping from vreg to string constant index
+    /// Mapping from vreg to string constant index, for the function being
+    /// generated
     string_vreg_map: HashMap<usize, usize>,
+    /// The mappings from vreg to string constant index of each function
+    function_string_maps: HashMap<String, HashMap<usize, usize>>,
     /// Temporary register counter for error returns
     temp_reg_counter: usize,
 }
@@ -55,9 +65,12 @@ impl<W: Write> CodeGenerator<W> {
             enum_cache: Arc::new(EnumLayoutCache::new()),
             struct_types: HashMap::new(),
             enum_types: HashMap::new(),
+            vtables: HashMap::new(),
+            globals: HashMap::new(),
             calling_convention: CallingConvention::SystemVAMD64, // Default
             string_constants: Vec::new(),
             string_vreg_map: HashMap::new(),
+            function_string_maps: HashMap::new(),
             temp_reg_counter: 1000, // Start from 1000 to avoid conflicts with LIR vregs
         }
     }
@@ -75,9 +88,12 @@ impl<W: Write> CodeGenerator<W> {
             enum_cache,
             struct_types: HashMap::new(),
             enum_types: HashMap::new(),
+            vtables: HashMap::new(),
+            globals: HashMap::new(),
             calling_convention: CallingConvention::SystemVAMD64,
             string_constants: Vec::new(),
             string_vreg_map: HashMap::new(),
+            function_string_maps: HashMap::new(),
             temp_reg_counter: 1000,
         }
     }
@@ -100,6 +116,18 @@ impl<W: Write> CodeGenerator<W> {
         self.enum_types.insert(name, layout);
     }
 
+    /// Register a vtable
+    pub fn register_vtable(&mut self, vtable: VTable) {
+        let name = vtable.name.clone();
+        self.vtables.insert(name, vtable);
+    }
+
+    /// Register the global of a `static` item
+    pub fn register_global(&mut self, global: Global) {
+        let name = global.name.clone();
+        self.globals.insert(name, global);
+    }
+
     /// Get the layout cache
     pub fn layout_cache(&self) -> &LayoutCache {
         &self.layout_cache
@@ -107,6 +135,9 @@ impl<W: Write> CodeGenerator<W> {
 
     /// Generate LLVM IR for a function
     pub fn generate_function(&mut self, func: &LirFunction) -> Result<()> {
+        // Vregs of different functions may hold different string constants
+        self.string_vreg_map = self.function_string_maps.get(&func.name).cloned().unwrap_or_default();
+
         // Function declaration
         self.write_function_header(func)?;
         writeln!(self.writer, " {{").unwrap();
@@ -137,7 +168,7 @@ impl<W: Write> CodeGenerator<W> {
         write!(self.writer, "define {}", return_type.to_llvm_ref()).unwrap();
 
         // Function name
-        write!(self.writer, " @{}", func.name).unwrap();
+        write!(self.writer, " @{}", symbol(&func.name)).unwrap();
 
         // Parameters
         write!(self.writer, "(").unwrap();
@@ -166,6 +197,10 @@ impl<W: Write> CodeGenerator<W> {
             self.generate_phi(vreg, phi)?;
         }
 
+        if block.id == func.entry_block {
+            self.generate_enum_slots(func)?;
+        }
+
         // Instructions
         for instr in &block.instructions {
             self.generate_instruction(instr)?;
@@ -187,11 +222,10 @@ impl<W: Write> CodeGenerator<W> {
 
         let mut sources = phi.sources.iter().peekable();
         while let Some((reg, block_id)) = sources.next() {
-            // Special case: vreg 0 represents undef (no value from this predecessor)
-            if *reg == 0 {
-                write!(self.writer, "[ undef, %block{} ]", block_id).unwrap();
-            } else {
-                write!(self.writer, "[ %v{}, %block{} ]", reg, block_id).unwrap();
+            match reg {
+                Some(reg) => write!(self.writer, "[ %v{}, %block{} ]", reg, block_id).unwrap(),
+                // No value from this predecessor
+                None => write!(self.writer, "[ undef, %block{} ]", block_id).unwrap(),
             }
             if sources.peek().is_some() {
                 write!(self.writer, ", ").unwrap();
@@ -213,16 +247,39 @@ impl<W: Write> CodeGenerator<W> {
                 self.generate_const(*dest, value, ty)?;
             }
 
-            LirInstruction::Copy { dest, src, ty: _ } => {
-                let ty = LlvmType::Integer(32);  // Placeholder
-                writeln!(
-                    self.writer,
-                    "{}  %v{} = add {} %v{}, 0",
-                    "  ".repeat(self.indent),
-                    dest,
-                    ty.to_llvm_ir(),
-                    src
-                ).unwrap();
+            LirInstruction::Copy { dest, src, ty } => {
+                use zulon_lir::LirTy;
+
+                // Integers are copied by adding zero, other values through a
+                // `select` that always picks them
+                match ty {
+                    LirTy::F32 | LirTy::F64 | LirTy::Ptr(_) | LirTy::Array { .. } | LirTy::Struct { .. } | LirTy::Enum { .. } => {
+                        let ty = LlvmType::from(ty.clone()).to_llvm_ir();
+                        writeln!(
+                            self.writer,
+                            "{}  %v{} = select i1 true, {} %v{}, {} %v{}",
+                            "  ".repeat(self.indent),
+                            dest,
+                            ty,
+                            src,
+                            ty,
+                            src
+                        ).unwrap();
+                    }
+                    LirTy::Unit | LirTy::Never => {
+                        writeln!(self.writer, "{}  %v{} = add i32 %v{}, 0", "  ".repeat(self.indent), dest, src).unwrap();
+                    }
+                    _ => {
+                        writeln!(
+                            self.writer,
+                            "{}  %v{} = add {} %v{}, 0",
+                            "  ".repeat(self.indent),
+                            dest,
+                            LlvmType::from(ty.clone()).to_llvm_ir(),
+                            src
+                        ).unwrap();
+                    }
+                }
             }
 
             LirInstruction::BinaryOp { dest, op, left, right, ty } => {
@@ -245,19 +302,77 @@ impl<W: Write> CodeGenerator<W> {
                 self.generate_gep(*dest, *base, indices, ty)?;
             }
 
-            LirInstruction::Call { dest, func: _func_vreg, args, return_type } => {
-                // Placeholder: use function name "unknown"
-                self.generate_call(*dest, "unknown", args, return_type, &[])?;
+            LirInstruction::InsertValue { dest, aggregate, value, value_ty, index, ty } => {
+                let aggregate = match aggregate {
+                    Some(vreg) => format!("%v{}", vreg),
+                    None => "undef".to_string(),
+                };
+                writeln!(
+                    self.writer,
+                    "{}  %v{} = insertvalue {} {}, {} %v{}, {}",
+                    "  ".repeat(self.indent),
+                    dest,
+                    LlvmType::from(ty.clone()).to_llvm_ref(),
+                    aggregate,
+                    LlvmType::from(value_ty.clone()).to_llvm_ref(),
+                    value,
+                    index
+                ).unwrap();
+            }
+
+            LirInstruction::ExtractValue { dest, aggregate, index, ty, field_ty: _ } => {
+                writeln!(
+                    self.writer,
+                    "{}  %v{} = extractvalue {} %v{}, {}",
+                    "  ".repeat(self.indent),
+                    dest,
+                    LlvmType::from(ty.clone()).to_llvm_ref(),
+                    aggregate,
+                    index
+                ).unwrap();
+            }
+
+            LirInstruction::EnumVariant { dest, variant, fields, ty } => {
+                self.generate_enum_variant(*dest, variant, fields, ty)?;
+            }
+
+            LirInstruction::Discriminant { dest, value, ty } => {
+                let layout = self.enum_layout(ty)?;
+                let discriminant = LlvmType::from(layout.discriminant_type.clone()).to_llvm_ir();
+                let indent = "  ".repeat(self.indent);
+                let enum_ty = LlvmType::from(ty.clone()).to_llvm_ir();
+                writeln!(self.writer, "{}  store {} %v{}, ptr %v{}.slot", indent, enum_ty, value, dest).unwrap();
+                writeln!(self.writer, "{}  %v{} = load {}, ptr %v{}.slot", indent, dest, discriminant, dest).unwrap();
+            }
+
+            LirInstruction::VariantField { dest, value, variant, index, ty, field_ty } => {
+                let layout = self.enum_layout(ty)?;
+                let variant = layout.variant_by_name(variant).ok_or_else(|| {
+                    CodegenError::TypeError(format!("enum `{}` has no variant `{}`", layout.name, variant))
+                })?;
+                let variant_ty = layout.variant_llvm_type(variant);
+                let indent = "  ".repeat(self.indent);
+                let enum_ty = LlvmType::from(ty.clone()).to_llvm_ir();
+                let field_ty = LlvmType::from(field_ty.clone()).to_llvm_ir();
+                writeln!(self.writer, "{}  store {} %v{}, ptr %v{}.slot", indent, enum_ty, value, dest).unwrap();
+                writeln!(
+                    self.writer,
+                    "{}  %v{}.field = getelementptr inbounds {}, ptr %v{}.slot, i32 0, i32 1, i32 {}",
+                    indent, dest, variant_ty, dest, index
+                ).unwrap();
+                writeln!(self.writer, "{}  %v{} = load {}, ptr %v{}.field", indent, dest, field_ty, dest).unwrap();
+            }
+
+            LirInstruction::Call { dest, func, args, arg_types, return_type } => {
+                self.generate_indirect_call(*dest, *func, args, return_type, arg_types)?;
             }
 
             LirInstruction::CallExternal { dest, func_name, args, arg_types, return_type } => {
                 self.generate_call(*dest, func_name, args, return_type, arg_types)?;
             }
 
-            LirInstruction::Cmp { dest, op, left, right } => {
-                // Assume i32 for now
-                let ty = zulon_lir::LirTy::I32;
-                self.generate_cmp(*dest, op, *left, *right, &ty)?;
+            LirInstruction::Cmp { dest, op, left, right, ty } => {
+                self.generate_cmp(*dest, op, *left, *right, ty)?;
             }
 
             LirInstruction::Cast { dest, src, from, to } => {
@@ -276,6 +391,92 @@ impl<W: Write> CodeGenerator<W> {
         Ok(())
     }
 
+    /// The layout of the enum type `ty`
+    fn enum_layout(&self, ty: &zulon_lir::LirTy) -> Result<&EnumLayout> {
+        match ty {
+            zulon_lir::LirTy::Enum { name } => self.enum_types.get(name)
+                .ok_or_else(|| CodegenError::TypeError(format!("enum `{}` has no layout", name))),
+            _ => Err(CodegenError::TypeError(format!("`{}` is not an enum", ty))),
+        }
+    }
+
+    /// Allocate the stack slot through which each enum value of `func` is
+    /// built or read, in its entry block so loops reuse them
+    fn generate_enum_slots(&mut self, func: &LirFunction) -> Result<()> {
+        let mut slots = Vec::new();
+        for block in func.blocks.values() {
+            for instr in &block.instructions {
+                match instr {
+                    LirInstruction::EnumVariant { dest, ty, .. }
+                    | LirInstruction::Discriminant { dest, ty, .. }
+                    | LirInstruction::VariantField { dest, ty, .. } => slots.push((*dest, ty)),
+                    _ => {}
+                }
+            }
+        }
+        slots.sort_by_key(|(dest, _)| *dest);
+
+        for (dest, ty) in slots {
+            let align = self.enum_layout(ty)?.align;
+            writeln!(
+                self.writer,
+                "{}  %v{}.slot = alloca {}, align {}",
+                "  ".repeat(self.indent),
+                dest,
+                LlvmType::from(ty.clone()).to_llvm_ir(),
+                align
+            ).unwrap();
+        }
+        Ok(())
+    }
+
+    /// Generate a value of an enum variant: the discriminant and the
+    /// fields are stored at their offsets in a stack slot, which is then
+    /// loaded as a whole
+    fn generate_enum_variant(
+        &mut self,
+        dest: zulon_lir::VReg,
+        variant: &str,
+        fields: &[(zulon_lir::VReg, zulon_lir::LirTy)],
+        ty: &zulon_lir::LirTy,
+    ) -> Result<()> {
+        let layout = self.enum_layout(ty)?;
+        let variant = layout.variant_by_name(variant).ok_or_else(|| {
+            CodegenError::TypeError(format!("enum `{}` has no variant `{}`", layout.name, variant))
+        })?;
+        let discriminant_ty = LlvmType::from(layout.discriminant_type.clone()).to_llvm_ir();
+        let variant_ty = layout.variant_llvm_type(variant);
+        let discriminant = variant.discriminant;
+        let indent = "  ".repeat(self.indent);
+
+        writeln!(self.writer, "{}  store {} {}, ptr %v{}.slot", indent, discriminant_ty, discriminant, dest).unwrap();
+        for (index, (field, field_ty)) in fields.iter().enumerate() {
+            writeln!(
+                self.writer,
+                "{}  %v{}.field{} = getelementptr inbounds {}, ptr %v{}.slot, i32 0, i32 1, i32 {}",
+                indent, dest, index, variant_ty, dest, index
+            ).unwrap();
+            writeln!(
+                self.writer,
+                "{}  store {} %v{}, ptr %v{}.field{}",
+                indent,
+                LlvmType::from(field_ty.clone()).to_llvm_ir(),
+                field,
+                dest,
+                index
+            ).unwrap();
+        }
+        writeln!(
+            self.writer,
+            "{}  %v{} = load {}, ptr %v{}.slot",
+            indent,
+            dest,
+            LlvmType::from(ty.clone()).to_llvm_ir(),
+            dest
+        ).unwrap();
+        Ok(())
+    }
+
     /// Generate alloca instruction
     fn generate_alloca(&mut self, alloca: &zulon_lir::LirAlloca) -> Result<()> {
         let llvm_ty: LlvmType = alloca.ty.clone().into();
@@ -315,13 +516,20 @@ impl<W: Write> CodeGenerator<W> {
                         val
                     ).unwrap();
                 } else {
+                    // Constants hold the bit pattern; print it signed so
+                    // negative values fit their type
+                    let signed = if matches!(ty, zulon_lir::LirTy::I128 | zulon_lir::LirTy::U128) {
+                        *val as i128
+                    } else {
+                        *val as i64 as i128
+                    };
                     writeln!(
                         self.writer,
                         "{}  %v{} = add {} 0, {}",
                         "  ".repeat(self.indent),
                         dest,
                         llvm_ty.to_llvm_ir(),
-                        val
+                        signed
                     ).unwrap();
                 }
             }
@@ -342,7 +550,7 @@ impl<W: Write> CodeGenerator<W> {
                 // Get pointer to first character with getelementptr
                 let str_id = self.string_vreg_map.get(&(dest as usize)).unwrap();
                 let global_name = format!("@.str{}", str_id);
-                let str_len = self.string_constants[*str_id].len;
+                let str_len = self.string_constants[*str_id].bytes.len();
 
                 // Get pointer to string data: getelementptr [N x i8], ptr @.strX, i64 0, i64 0
                 writeln!(
@@ -355,6 +563,20 @@ impl<W: Write> CodeGenerator<W> {
                 ).unwrap();
             }
 
+            zulon_lir::LirConstant::ByteString(bytes) => {
+                // Byte strings are collected at module level too, and
+                // loaded as array values
+                let str_id = self.string_vreg_map.get(&(dest as usize)).unwrap();
+                writeln!(
+                    self.writer,
+                    "{}  %v{} = load [{} x i8], ptr @.str{}",
+                    "  ".repeat(self.indent),
+                    dest,
+                    bytes.len(),
+                    str_id
+                ).unwrap();
+            }
+
             zulon_lir::LirConstant::Bool(val) => {
                 writeln!(
                     self.writer,
@@ -369,49 +591,74 @@ impl<W: Write> CodeGenerator<W> {
             zulon_lir::LirConstant::Unit => {
                 writeln!(self.writer, "{}  %v{} = add {} 0, 0", "  ".repeat(self.indent), dest, llvm_ty.to_llvm_ir()).unwrap();
             }
+
+            zulon_lir::LirConstant::VTable(name) => {
+                writeln!(
+                    self.writer,
+                    "{}  %v{} = getelementptr i8, ptr @{}, i64 0",
+                    "  ".repeat(self.indent),
+                    dest,
+                    symbol(name)
+                ).unwrap();
+            }
+
+            zulon_lir::LirConstant::Global(name) => {
+                writeln!(
+                    self.writer,
+                    "{}  %v{} = getelementptr i8, ptr @{}, i64 0",
+                    "  ".repeat(self.indent),
+                    dest,
+                    symbol(name)
+                ).unwrap();
+            }
         }
 
         Ok(())
     }
 
-    /// Collect string constants from a function
+    /// Collect string and byte string constants from a function
     fn collect_string_constants(&mut self, func: &LirFunction) {
         for (_block_id, block) in &func.blocks {
             for instr in &block.instructions {
                 if let zulon_lir::LirInstruction::Const { dest, value, .. } = instr {
-                    if let zulon_lir::LirConstant::String(s) = value {
-                        // Find next available string ID
-                        let str_id = self.string_constants.len();
-
-                        self.string_constants.push(StringConstant {
-                            name: format!(".str{}", str_id),
-                            value: s.clone(),
-                            len: s.len() + 1, // +1 for null terminator
-                        });
-
-                        // Map vreg to string constant index
-                        self.string_vreg_map.insert(*dest as usize, str_id);
-                    }
+                    let bytes = match value {
+                        zulon_lir::LirConstant::String(s) => [s.as_bytes(), &[0]].concat(),
+                        zulon_lir::LirConstant::ByteString(bytes) => bytes.clone(),
+                        _ => continue,
+                    };
+
+                    // Find next available string ID
+                    let str_id = self.string_constants.len();
+
+                    self.string_constants.push(StringConstant {
+                        name: format!(".str{}", str_id),
+                        bytes,
+                    });
+
+                    // Map vreg to string constant index
+                    self.function_string_maps
+                        .entry(func.name.clone())
+                        .or_default()
+                        .insert(*dest as usize, str_id);
                 }
             }
         }
     }
 
-    fn escape_string_for_llvm(s: &str) -> String {
+    /// Bytes as an LLVM string, printable characters as they are and other
+    /// bytes as hex escapes
+    fn escape_bytes_for_llvm(bytes: &[u8]) -> String {
         let mut result = String::from("\"");
 
-        for c in s.chars() {
-            match c {
-                '\\' => result.push_str("\\\\"),
-                '"' => result.push_str("\\\""),
-                '\n' => result.push_str("\\0A"),
-                '\r' => result.push_str("\\0D"),
-                '\t' => result.push_str("\\09"),
-                _ => result.push(c),
+        for &byte in bytes {
+            match byte {
+                b'"' | b'\\' => result.push_str(&format!("\\{:02X}", byte)),
+                b' '..=b'~' => result.push(byte as char),
+                _ => result.push_str(&format!("\\{:02X}", byte)),
             }
         }
 
-        result.push_str("\\00\"");
+        result.push('"');
         result
     }
 
@@ -521,11 +768,11 @@ impl<W: Write> CodeGenerator<W> {
 
         writeln!(
             self.writer,
-            "{}  %v{} = load {}, {}* {}",
+            "{}  %v{} = load {}, {} {}",
             "  ".repeat(self.indent),
             dest,
             type_str,
-            type_str,
+            pointer_to(&type_str),
             src_str
         ).unwrap();
 
@@ -551,11 +798,11 @@ impl<W: Write> CodeGenerator<W> {
 
         writeln!(
             self.writer,
-            "{}  store {} %v{}, {}* {}",
+            "{}  store {} %v{}, {} {}",
             "  ".repeat(self.indent),
             type_str,
             src,
-            type_str,
+            pointer_to(&type_str),
             dest_str
         ).unwrap();
 
@@ -599,7 +846,7 @@ impl<W: Write> CodeGenerator<W> {
                 // Use struct type reference for GEP on structs
                 llvm_type.to_llvm_ref() + "*"
             }
-            _ => format!("{}*", type_str),
+            _ => pointer_to(&type_str),
         };
 
         writeln!(
@@ -681,7 +928,7 @@ impl<W: Write> CodeGenerator<W> {
                 "  ".repeat(self.indent),
                 dest_vreg,
                 func_type,
-                func_name,
+                symbol(func_name),
                 args_str.join(", ")
             ).unwrap();
         } else {
@@ -690,7 +937,7 @@ impl<W: Write> CodeGenerator<W> {
                 "{}  call {} @{}({})",
                 "  ".repeat(self.indent),
                 func_type,
-                func_name,
+                symbol(func_name),
                 args_str.join(", ")
             ).unwrap();
         }
@@ -698,6 +945,37 @@ impl<W: Write> CodeGenerator<W> {
         Ok(())
     }
 
+    /// Generate a call through the function pointer in `func`
+    fn generate_indirect_call(
+        &mut self,
+        dest: Option<zulon_lir::VReg>,
+        func: zulon_lir::VReg,
+        args: &[zulon_lir::VReg],
+        return_ty: &zulon_lir::LirTy,
+        arg_types: &[zulon_lir::LirTy],
+    ) -> Result<()> {
+        let return_type_str = LlvmType::from(return_ty.clone()).to_llvm_ref();
+        let args_str: Vec<String> = args.iter().zip(arg_types).map(|(arg_reg, arg_ty)| {
+            format!("{} noundef %v{}", LlvmType::from(arg_ty.clone()).to_llvm_ref(), arg_reg)
+        }).collect();
+
+        let assign = match dest {
+            Some(dest_vreg) => format!("%v{} = ", dest_vreg),
+            None => String::new(),
+        };
+        writeln!(
+            self.writer,
+            "{}  {}call {} %v{}({})",
+            "  ".repeat(self.indent),
+            assign,
+            return_type_str,
+            func,
+            args_str.join(", ")
+        ).unwrap();
+
+        Ok(())
+    }
+
     /// Check if an external function is variadic
     fn is_external_variadic(&self, func_name: &str) -> bool {
         // Known variadic functions from C standard library
@@ -777,11 +1055,41 @@ impl<W: Write> CodeGenerator<W> {
         from: &zulon_lir::LirTy,
         to: &zulon_lir::LirTy,
     ) -> Result<()> {
+        use zulon_lir::LirTy;
+
         let from_llvm: LlvmType = from.clone().into();
         let to_llvm: LlvmType = to.clone().into();
 
-        // Simple cast logic (placeholder)
-        let op = "bitcast";
+        // Width in bits and signedness of an integer type
+        let int = |ty: &LirTy| match ty {
+            LirTy::Bool => Some((1, false)),
+            LirTy::I8 => Some((8, true)),
+            LirTy::U8 => Some((8, false)),
+            LirTy::I16 => Some((16, true)),
+            LirTy::U16 => Some((16, false)),
+            LirTy::I32 => Some((32, true)),
+            LirTy::U32 => Some((32, false)),
+            LirTy::I64 | LirTy::ISize => Some((64, true)),
+            LirTy::U64 | LirTy::USize => Some((64, false)),
+            LirTy::I128 => Some((128, true)),
+            LirTy::U128 => Some((128, false)),
+            _ => None,
+        };
+
+        // A value whose type does not change is copied by a no-op bitcast
+        let op = match (int(from), int(to)) {
+            (Some((from_bits, signed)), Some((to_bits, _))) if from_bits < to_bits => {
+                if signed { "sext" } else { "zext" }
+            }
+            (Some((from_bits, _)), Some((to_bits, _))) if from_bits > to_bits => "trunc",
+            (Some((_, signed)), None) if to.is_float() => if signed { "sitofp" } else { "uitofp" },
+            (None, Some((_, signed))) if from.is_float() => if signed { "fptosi" } else { "fptoui" },
+            _ => match (from, to) {
+                (LirTy::F32, LirTy::F64) => "fpext",
+                (LirTy::F64, LirTy::F32) => "fptrunc",
+                _ => "bitcast",
+            },
+        };
 
         writeln!(
             self.writer,
@@ -915,11 +1223,13 @@ impl<W: Write> CodeGenerator<W> {
                 ).unwrap();
             }
 
-            LirTerminator::Switch { scrutinee, targets, default } => {
+            LirTerminator::Switch { scrutinee, ty, targets, default } => {
+                let ty = LlvmType::from(ty.clone()).to_llvm_ir();
                 writeln!(
                     self.writer,
-                    "{}  switch i32 %v{}, label %block{} [",
+                    "{}  switch {} %v{}, label %block{} [",
                     "  ".repeat(self.indent),
+                    ty,
                     scrutinee,
                     default
                 ).unwrap();
@@ -928,8 +1238,9 @@ impl<W: Write> CodeGenerator<W> {
                 for (val, block) in targets {
                     writeln!(
                         self.writer,
-                        "{}    i32 {}, label %block{}",
+                        "{}    {} {}, label %block{}",
                         "  ".repeat(self.indent),
+                        ty,
                         val,
                         block
                     ).unwrap();
@@ -994,8 +1305,71 @@ impl<W: Write> CodeGenerator<W> {
         }
     }
 
-    /// Generate module-level type declarations
+    /// Lay out each enum again now that every struct and enum is
+    /// registered, so fields of those types have their real sizes
+    fn layout_enums(&mut self) -> Result<()> {
+        let mut layouts = HashMap::new();
+        for (name, layout) in &self.enum_types {
+            layouts.insert(name.clone(), self.relay_enum(layout)?);
+        }
+        self.enum_types = layouts;
+        Ok(())
+    }
+
+    /// `layout` with the fields of its variants sized by `size_align`
+    fn relay_enum(&self, layout: &EnumLayout) -> Result<EnumLayout> {
+        let mut relaid = EnumLayout::new(layout.name.clone(), layout.discriminant_type.clone());
+        for variant in &layout.variants {
+            relaid.add_variant_with(
+                variant.name.clone(),
+                variant.discriminant,
+                variant.fields.clone(),
+                |ty| self.size_align(ty),
+            )?;
+        }
+        relaid.finalize();
+        Ok(relaid)
+    }
+
+    /// The size and alignment LLVM gives `ty`, looking up the fields of
+    /// registered structs and the variants of registered enums
+    fn size_align(&self, ty: &zulon_lir::LirTy) -> (u64, u64) {
+        use zulon_lir::LirTy;
+
+        let fields_size_align = |fields: &mut dyn Iterator<Item = &LirTy>| {
+            let mut layout = StructLayout::new(String::new());
+            for field in fields {
+                let (size, align) = self.size_align(field);
+                let _ = layout.add_sized_field(String::new(), field.clone(), size, align);
+            }
+            layout.finalize();
+            (layout.size, layout.align)
+        };
+        match ty {
+            LirTy::Struct { name, fields, .. } if fields.is_empty() => match self.struct_types.get(name) {
+                Some(layout) => fields_size_align(&mut layout.fields.iter().map(|field| &field.ty)),
+                None => (1, 1),
+            },
+            LirTy::Struct { fields, .. } => fields_size_align(&mut fields.iter()),
+            LirTy::Enum { name } => match self.enum_types.get(name).map(|layout| self.relay_enum(layout)) {
+                Some(Ok(layout)) => (layout.size, layout.align),
+                _ => (ty.size(), ty.align()),
+            },
+            LirTy::Array { inner, len } => {
+                let (size, align) = self.size_align(inner);
+                (size * len, align)
+            }
+            // Unit values are `i32`s
+            LirTy::Unit => (4, 4),
+            _ => (ty.size(), ty.align()),
+        }
+    }
+
+    /// Generate module-level type declarations, followed by the vtables
+    /// and the globals of statics
     pub fn generate_type_declarations(&mut self) -> Result<()> {
+        self.layout_enums()?;
+
         // Declare struct types
         for (_name, layout) in &self.struct_types {
             writeln!(self.writer, "{}", layout.to_llvm_definition())
@@ -1011,6 +1385,30 @@ impl<W: Write> CodeGenerator<W> {
         writeln!(self.writer)
             .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
 
+        // Define vtables, in a stable order
+        if !self.vtables.is_empty() {
+            let mut vtables: Vec<&VTable> = self.vtables.values().collect();
+            vtables.sort_by(|a, b| a.name.cmp(&b.name));
+            for vtable in vtables {
+                writeln!(self.writer, "{}", vtable.to_llvm_definition())
+                    .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
+            }
+            writeln!(self.writer)
+                .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
+        }
+
+        // Define globals, in a stable order
+        if !self.globals.is_empty() {
+            let mut globals: Vec<&Global> = self.globals.values().collect();
+            globals.sort_by(|a, b| a.name.cmp(&b.name));
+            for global in globals {
+                writeln!(self.writer, "{}", global.to_llvm_definition(&self.struct_types))
+                    .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
+            }
+            writeln!(self.writer)
+                .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
+        }
+
         Ok(())
     }
 
@@ -1076,8 +1474,8 @@ impl<W: Write> CodeGenerator<W> {
                     self.writer,
                     "@{} = private unnamed_addr constant [{} x i8] c{}",
                     sc.name,
-                    sc.len,
-                    Self::escape_string_for_llvm(&sc.value)
+                    sc.bytes.len(),
+                    Self::escape_bytes_for_llvm(&sc.bytes)
                 )
                 .map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
             }
@@ -1099,7 +1497,7 @@ impl<W: Write> CodeGenerator<W> {
 
         write!(self.writer, "declare {} @{}(",
             return_llvm_ty.to_llvm_ir(),
-            external.name
+            symbol(&external.name)
         ).map_err(|e| CodegenError::InstructionError(format!("IO error: {}", e)))?;
 
         // Parameter types with noundef attribute (matches Clang)
@@ -1397,6 +1795,30 @@ impl<W: Write> CodeGenerator<W> {
     }
 }
 
+/// A function name as an LLVM global identifier, without the `@`
+///
+/// Names such as `Point::norm` or `<Circle as Shape>::area` contain
+/// characters that are not allowed in bare identifiers and are quoted.
+pub(crate) fn symbol(name: &str) -> Cow<'_, str> {
+    let is_bare = |c: char| c.is_ascii_alphanumeric() || matches!(c, '$' | '.' | '_' | '-');
+    if name.chars().all(is_bare) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("\"{}\"", name))
+    }
+}
+
+/// The type of a pointer to a value of LLVM type `pointee`
+///
+/// Pointers are opaque, so a pointer to a pointer is `ptr`, not `ptr*`.
+fn pointer_to(pointee: &str) -> String {
+    if pointee == "ptr" {
+        pointee.to_string()
+    } else {
+        format!("{}*", pointee)
+    }
+}
+
 /// Get the target triple for the current host
 fn get_target_triple() -> String {
     use std::env;
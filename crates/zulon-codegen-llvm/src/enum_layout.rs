@@ -20,6 +20,8 @@ pub struct VariantInfo {
     pub fields: Vec<(String, zulon_lir::LirTy)>,
     /// Variant layout (if data variant)
     pub layout: Option<StructLayout>,
+    /// Offset of the variant's data, after the discriminant (in bytes)
+    pub offset: u64,
     /// Variant size (without discriminant)
     pub size: u64,
     /// Variant alignment
@@ -71,6 +73,18 @@ impl EnumLayout {
         name: String,
         discriminant: u64,
         fields: Vec<(String, zulon_lir::LirTy)>,
+    ) -> Result<()> {
+        self.add_variant_with(name, discriminant, fields, |ty| (ty.size(), ty.align()))
+    }
+
+    /// Add a variant to the enum, with the size and alignment of each
+    /// field's type given by `size_align`
+    pub fn add_variant_with(
+        &mut self,
+        name: String,
+        discriminant: u64,
+        fields: Vec<(String, zulon_lir::LirTy)>,
+        size_align: impl Fn(&zulon_lir::LirTy) -> (u64, u64),
     ) -> Result<()> {
         let (size, align, layout) = if fields.is_empty() {
             // Unit-like variant - no data
@@ -79,13 +93,17 @@ impl EnumLayout {
             // Data variant - compute layout
             let mut struct_layout = StructLayout::new(format!("{}_{}", self.name, name));
             for (field_name, field_ty) in &fields {
-                struct_layout.add_field(field_name.clone(), field_ty.clone())?;
+                let (field_size, field_align) = size_align(field_ty);
+                struct_layout.add_sized_field(field_name.clone(), field_ty.clone(), field_size, field_align)?;
             }
             struct_layout.finalize();
 
             (struct_layout.size, struct_layout.align, Some(struct_layout))
         };
 
+        // The data follows the discriminant, aligned for its fields
+        let offset = self.data_offset.div_ceil(align) * align;
+
         // Update alignment (max of discriminant and all variants)
         // But start with discriminant alignment
         let disc_align = self.discriminant_type.align();
@@ -97,12 +115,13 @@ impl EnumLayout {
             name: name.clone(),
             fields,
             layout,
+            offset,
             size,
             align,
         });
 
         // Update size
-        let variant_size = self.data_offset + size;
+        let variant_size = offset + size;
         self.size = self.size.max(variant_size);
 
         Ok(())
@@ -165,6 +184,17 @@ impl EnumLayout {
         }
     }
 
+    /// The LLVM type of the enum when it holds `variant`: the discriminant
+    /// followed by the variant's fields, which puts each field at its
+    /// offset in the layout
+    pub fn variant_llvm_type(&self, variant: &VariantInfo) -> String {
+        let discriminant: crate::ty::LlvmType = self.discriminant_type.clone().into();
+        let fields: Vec<String> = variant.fields.iter()
+            .map(|(_, ty)| crate::ty::LlvmType::from(ty.clone()).to_llvm_ir())
+            .collect();
+        format!("{{ {}, {{ {} }} }}", discriminant.to_llvm_ir(), fields.join(", "))
+    }
+
     /// Generate discriminant access GEP indices
     pub fn discriminant_gep_indices(&self) -> Vec<u64> {
         vec![0, 0]  // [struct_ptr, field_0]
@@ -320,4 +350,24 @@ mod tests {
         // Size should be aligned to 8
         assert_eq!(layout.size % 8, 0);
     }
+
+    #[test]
+    fn test_variant_data_alignment() {
+        // enum Shape { Circle(i64), Tag(u8) }
+        let mut layout = EnumLayout::new("Shape".to_string(), zulon_lir::LirTy::I32);
+
+        layout.add_variant("Circle".to_string(), 0, vec![
+            ("0".to_string(), zulon_lir::LirTy::I64)
+        ]).unwrap();
+        layout.add_variant("Tag".to_string(), 1, vec![
+            ("0".to_string(), zulon_lir::LirTy::U8)
+        ]).unwrap();
+        layout.finalize();
+
+        // Each variant's data starts at its own alignment after the i32
+        assert_eq!(layout.variants[0].offset, 8);
+        assert_eq!(layout.variants[1].offset, 4);
+        assert_eq!(layout.size, 16);
+        assert_eq!(layout.variant_llvm_type(&layout.variants[0]), "{ i32, { i64 } }");
+    }
 }
@@ -0,0 +1,184 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Module-level globals for `static` items
+//!
+//! A static's initializer is evaluated at compile time, so it is emitted
+//! as a constant LLVM initializer. Immutable statics become `constant`
+//! globals, `static mut` items writable ones.
+
+use crate::codegen::symbol;
+use crate::layout::StructLayout;
+use crate::ty::LlvmType;
+use std::collections::HashMap;
+use zulon_lir::LirTy;
+use zulon_typeck::ConstValue;
+
+/// A static emitted as a module-level global
+#[derive(Debug, Clone)]
+pub struct Global {
+    /// Global symbol name
+    pub name: String,
+    /// Type of the value
+    pub ty: LirTy,
+    /// Initial value
+    pub value: ConstValue,
+    /// Whether the program may write to it
+    pub mutable: bool,
+}
+
+impl Global {
+    /// Create a global
+    pub fn new(name: String, ty: LirTy, value: ConstValue, mutable: bool) -> Self {
+        Self { name, ty, value, mutable }
+    }
+
+    /// Get the LLVM global definition, preceded by the definitions of any
+    /// string data it points to; struct fields are typed using `structs`
+    pub fn to_llvm_definition(&self, structs: &HashMap<String, StructLayout>) -> String {
+        let mut init = Initializer {
+            name: &self.name,
+            structs,
+            strings: Vec::new(),
+        };
+        let value = init.constant(&self.ty, &self.value);
+
+        let mut definition = String::new();
+        for (i, bytes) in init.strings.iter().enumerate() {
+            definition.push_str(&format!(
+                "@{} = private unnamed_addr constant [{} x i8] c\"{}\"\n",
+                string_symbol(&self.name, i),
+                bytes.len(),
+                escape(bytes)
+            ));
+        }
+        definition.push_str(&format!(
+            "@{} = internal {} {} {}",
+            symbol(&self.name),
+            if self.mutable { "global" } else { "constant" },
+            llvm_type(&self.ty),
+            value
+        ));
+        definition
+    }
+}
+
+/// Builds the initializer of one global
+struct Initializer<'a> {
+    name: &'a str,
+    structs: &'a HashMap<String, StructLayout>,
+    /// NUL-terminated string data referenced by the initializer
+    strings: Vec<Vec<u8>>,
+}
+
+impl Initializer<'_> {
+    /// Render `value` as an LLVM constant of type `ty`, without the type
+    fn constant(&mut self, ty: &LirTy, value: &ConstValue) -> String {
+        match (ty, value) {
+            (LirTy::Bool, ConstValue::Bool(b)) => b.to_string(),
+            (_, ConstValue::Int(int)) if is_integer(ty) => {
+                // Sign-extend from the type's width, so unsigned values
+                // above the signed maximum are written as their bit pattern
+                let bits = ty.size() as u32 * 8;
+                let shift = 128 - bits;
+                ((int << shift) >> shift).to_string()
+            }
+            (_, ConstValue::Char(c)) if is_integer(ty) => (*c as u32).to_string(),
+            (LirTy::F32, ConstValue::Float(f)) => float(*f as f32 as f64),
+            (LirTy::F64, ConstValue::Float(f)) => float(*f),
+            (LirTy::Unit, ConstValue::Unit) => "0".to_string(),
+            (LirTy::Ptr(_), ConstValue::Str(s)) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0);
+                self.strings.push(bytes);
+                format!("@{}", string_symbol(self.name, self.strings.len() - 1))
+            }
+            (LirTy::Array { inner, .. }, ConstValue::Array(elements)) => {
+                let elements = self.elements(elements.iter().map(|element| (inner.as_ref(), element)));
+                format!("[{}]", elements)
+            }
+            (LirTy::Struct { name, fields, .. }, ConstValue::Struct { fields: values, .. }) => {
+                let field_tys: Vec<LirTy> = match self.structs.get(name) {
+                    Some(layout) => layout.fields.iter().map(|field| field.ty.clone()).collect(),
+                    None => fields.clone(),
+                };
+                if field_tys.len() != values.len() {
+                    return "zeroinitializer".to_string();
+                }
+                let fields = self.elements(field_tys.iter().zip(values.iter().map(|(_, value)| value)));
+                format!("{{ {} }}", fields)
+            }
+            (LirTy::Struct { fields, .. }, ConstValue::Tuple(values)) if fields.len() == values.len() => {
+                let fields = self.elements(fields.iter().zip(values));
+                format!("{{ {} }}", fields)
+            }
+            _ => "zeroinitializer".to_string(),
+        }
+    }
+
+    /// Render typed aggregate elements as a comma-separated list
+    fn elements<'v>(&mut self, elements: impl Iterator<Item = (&'v LirTy, &'v ConstValue)>) -> String {
+        elements
+            .map(|(ty, value)| format!("{} {}", llvm_type(ty), self.constant(ty, value)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+fn llvm_type(ty: &LirTy) -> String {
+    LlvmType::from(ty.clone()).to_llvm_ir()
+}
+
+fn is_integer(ty: &LirTy) -> bool {
+    matches!(
+        ty,
+        LirTy::I8 | LirTy::I16 | LirTy::I32 | LirTy::I64 | LirTy::I128 | LirTy::ISize
+            | LirTy::U8 | LirTy::U16 | LirTy::U32 | LirTy::U64 | LirTy::U128 | LirTy::USize
+    )
+}
+
+/// LLVM writes floating-point constants exactly as the hex bits of a double
+fn float(value: f64) -> String {
+    format!("0x{:016X}", value.to_bits())
+}
+
+fn string_symbol(global: &str, index: usize) -> String {
+    symbol(&format!("{}.str.{}", global, index)).into_owned()
+}
+
+fn escape(bytes: &[u8]) -> String {
+    bytes.iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() && b != b'"' && b != b'\\' || b == b' ' {
+                (b as char).to_string()
+            } else {
+                format!("\\{:02X}", b)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_definition() {
+        let global = Global::new(
+            "LIMITS".to_string(),
+            LirTy::Array { inner: Box::new(LirTy::U8), len: 2 },
+            ConstValue::Array(vec![ConstValue::Int(255), ConstValue::Int(1)]),
+            false,
+        );
+        assert_eq!(
+            global.to_llvm_definition(&HashMap::new()),
+            "@LIMITS = internal constant [2 x i8] [i8 -1, i8 1]"
+        );
+
+        let counter = Global::new("COUNTER".to_string(), LirTy::I64, ConstValue::Int(7), true);
+        assert_eq!(
+            counter.to_llvm_definition(&HashMap::new()),
+            "@COUNTER = internal global i64 7"
+        );
+    }
+}
@@ -52,8 +52,13 @@ impl StructLayout {
 
     /// Add a field to the struct
     pub fn add_field(&mut self, name: String, ty: zulon_lir::LirTy) -> Result<()> {
-        let field_size = ty.size();
-        let field_align = ty.align();
+        let (size, align) = (ty.size(), ty.align());
+        self.add_sized_field(name, ty, size, align)
+    }
+
+    /// Add a field to the struct whose type has the given size and
+    /// alignment, for types the LIR type only names
+    pub fn add_sized_field(&mut self, name: String, ty: zulon_lir::LirTy, field_size: u64, field_align: u64) -> Result<()> {
 
         // Update struct alignment (max of all field alignments)
         self.align = self.align.max(field_align);
@@ -22,6 +22,8 @@ pub mod codegen;
 pub mod error;
 pub mod layout;
 pub mod enum_layout;
+pub mod vtable;
+pub mod global;
 pub mod abi;
 pub mod optimize;
 
@@ -30,5 +32,7 @@ pub use codegen::CodeGenerator;
 pub use error::{CodegenError, Result};
 pub use layout::{StructLayout, LayoutCache, FieldInfo};
 pub use enum_layout::{EnumLayout, EnumLayoutCache, VariantInfo};
+pub use vtable::VTable;
+pub use global::Global;
 pub use abi::{CallingConvention, CallInfo, ArgLocation};
 pub use optimize::{OptPassManager, OptConfig};
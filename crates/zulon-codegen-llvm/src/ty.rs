@@ -34,6 +34,11 @@ pub enum LlvmType {
         fields: Vec<LlvmType>,
     },
 
+    /// Enum type, declared by its layout
+    Enum {
+        name: String,
+    },
+
     /// Function type
     Function {
         params: Vec<LlvmType>,
@@ -67,13 +72,11 @@ impl LlvmType {
                 format!("[{} x {}]", len, inner.to_llvm_ir())
             }
 
-            LlvmType::Struct { name, fields } => {
-                let field_str = fields.iter()
-                    .map(|t| t.to_llvm_ir())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                format!("%struct.{} {{ {} }}", name, field_str)
-            }
+            // Struct types are declared once at module level and always
+            // referred to by name
+            LlvmType::Struct { name, .. } => format!("%struct.{}", name),
+
+            LlvmType::Enum { name } => format!("%enum.{}", name),
 
             LlvmType::Function { params, return_type, is_varargs } => {
                 let param_str = params.iter()
@@ -152,6 +155,9 @@ impl From<LirTy> for LlvmType {
                     fields: llvm_fields,
                 }
             }
+
+            // Enums
+            LirTy::Enum { name } => LlvmType::Enum { name },
         }
     }
 }
@@ -0,0 +1,60 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Vtables for trait objects
+//!
+//! A vtable is a constant array of pointers to the functions implementing
+//! a trait's methods for one type, one slot per method. A trait object
+//! reference pairs a pointer to the data with a pointer to its vtable.
+
+use crate::codegen::symbol;
+
+/// A vtable emitted as a module-level constant
+#[derive(Debug, Clone)]
+pub struct VTable {
+    /// Global symbol name
+    pub name: String,
+    /// The function in each slot
+    pub functions: Vec<String>,
+}
+
+impl VTable {
+    /// Create a vtable
+    pub fn new(name: String, functions: Vec<String>) -> Self {
+        Self { name, functions }
+    }
+
+    /// Get the LLVM global definition
+    pub fn to_llvm_definition(&self) -> String {
+        let entries: Vec<String> = self.functions
+            .iter()
+            .map(|func| format!("ptr @{}", symbol(func)))
+            .collect();
+
+        format!(
+            "@{} = private unnamed_addr constant [{} x ptr] [{}]",
+            symbol(&self.name),
+            entries.len(),
+            entries.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vtable_definition() {
+        let vtable = VTable::new(
+            "vtable.<Circle as Shape>".to_string(),
+            vec!["<Circle as Shape>::area".to_string(), "describe".to_string()],
+        );
+
+        assert_eq!(
+            vtable.to_llvm_definition(),
+            "@\"vtable.<Circle as Shape>\" = private unnamed_addr constant [2 x ptr] \
+             [ptr @\"<Circle as Shape>::area\", ptr @describe]"
+        );
+    }
+}
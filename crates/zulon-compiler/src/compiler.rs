@@ -4,14 +4,19 @@
 //! Compiler implementation
 
 use std::path::{Path, PathBuf};
-use zulon_parser::{Lexer, Parser};
+use zulon_parser::{Parser, SourceMap};
+use zulon_parser::cfg::{strip_unconfigured, CfgOptions};
 use zulon_parser::ast::{ItemKind, Type as AstType};
+use zulon_macros::expand_derives;
 use zulon_typeck::TypeChecker;
-use zulon_hir::SimpleLoweringContext;
+use zulon_hir::{HirItem, HirTy, SimpleLoweringContext};
 use zulon_mir::MirLoweringContext;
 use zulon_lir::{LirLoweringContext, LirExternal, LirTy};
-use zulon_codegen_llvm::{CodeGenerator, StructLayout};
+use zulon_codegen_llvm::{CodeGenerator, EnumLayout, Global, StructLayout, VTable};
 use crate::macro_expander::MacroExpander;
+use crate::modules;
+use crate::prelude;
+use zulon_macros::SpanMap;
 
 use crate::error::{CompilerError, Result as CompilerResult};
 
@@ -26,6 +31,10 @@ pub struct CompilerConfig {
     pub keep_intermediates: bool,
     /// Target triple
     pub target: Option<String>,
+    /// Compile with `cfg(test)` set
+    pub test: bool,
+    /// Enabled `cfg(feature = "...")` names
+    pub features: Vec<String>,
 }
 
 impl Default for CompilerConfig {
@@ -35,10 +44,28 @@ impl Default for CompilerConfig {
             output: None,
             keep_intermediates: false,
             target: None,
+            test: false,
+            features: Vec::new(),
         }
     }
 }
 
+impl CompilerConfig {
+    /// The `#[cfg]` configuration for this compilation
+    ///
+    /// Target predicates come from the target triple, or from the host when
+    /// no target is given.
+    pub fn cfg_options(&self) -> CfgOptions {
+        let options = match &self.target {
+            Some(triple) => CfgOptions::for_target(triple),
+            None => CfgOptions::host(),
+        };
+        self.features
+            .iter()
+            .fold(options.with_test(self.test), |options, feature| options.with_feature(feature.as_str()))
+    }
+}
+
 /// ZULON compiler
 pub struct Compiler {
     #[allow(dead_code)]
@@ -58,7 +85,7 @@ impl Compiler {
             .map_err(|e| CompilerError::Io(e))?;
 
         // Compile source to LLVM IR
-        self.compile_source(&source, input)?;
+        self.compile_source(source, input)?;
 
         // Try to compile to executable if LLVM tools are available
         let ll_path = input.with_extension("ll");
@@ -245,79 +272,162 @@ impl Compiler {
     }
 
     /// Compile ZULON source code
-    fn compile_source(&self, source: &str, input_path: &Path) -> CompilerResult<()> {
+    fn compile_source(&self, source: String, input_path: &Path) -> CompilerResult<()> {
         println!("🔨 Compiling: {}", input_path.display());
 
-        // Step -1: Inject standard prelude
-        // Note: printf and scanf are auto-injected in extract_extern_functions
-        // So we don't need to declare them here
-        let source_with_prelude = source;
-
-        // Step 0: Macro expansion
-        println!("  [0/8] Macro expansion...");
-        let expander = MacroExpander::new();
-        let expanded_source = expander.expand_source(&source_with_prelude).map_err(|e| {
-            CompilerError::macro_expansion(format!("Macro expansion failed: {}", e))
-        })?;
-
-        // Check if any macros were expanded
-        if expanded_source != source_with_prelude {
-            println!("    ✅ Macros expanded");
-        } else {
-            println!("    ✅ No macros to expand");
-        }
-
-        // Step 1: Lexical analysis
+        // Step 1: Lexical analysis, loading the files of `mod name;` items
         println!("  [1/8] Lexical analysis...");
-        let lexer = Lexer::new(&expanded_source);
-        let (tokens, lex_errors) = lexer.lex_all();
+        let loaded = modules::load_crate(input_path, source)?;
+        let source_map = &loaded.source_map;
+        let tokens = loaded.tokens;
 
-        if !lex_errors.is_empty() {
-            for err in &lex_errors {
-                eprintln!("    Lexical error: {:?}", err);
-            }
-            return Err(CompilerError::lexical(format!(
-                "{} lexical errors",
-                lex_errors.len()
-            )));
+        println!("    ✅ {} tokens generated", tokens.len());
+        if source_map.files().len() > 1 {
+            println!("    📂 Loaded {} module file(s)", source_map.files().len() - 1);
         }
 
-        // Filter out comment tokens (they're not needed for parsing)
-        let tokens: Vec<_> = tokens
-            .into_iter()
-            .filter(|t| !matches!(t.kind, zulon_parser::TokenKind::Comment))
+        // Step 1.5: Macro expansion
+        println!("  [1.5/8] Macro expansion...");
+        let expansion = MacroExpander::new().expand_crate(tokens, source_map);
+        if !expansion.errors.is_empty() {
+            let messages: Vec<String> = expansion
+                .errors
+                .iter()
+                .map(|e| match e {
+                    zulon_macros::MacroError::Format(error) => self.format_format_error(error, source_map),
+                    _ => format!("{}: {}", source_map.location(&e.span()), e),
+                })
+                .collect();
+            return Err(CompilerError::macro_expansion(messages.join("\n")));
+        }
+        if expansion.span_map.is_empty() {
+            println!("    ✅ No macros to expand");
+        } else {
+            println!("    ✅ {} macro invocation(s) expanded", expansion.span_map.len());
+        }
+        let span_map = expansion.span_map;
+        // Module files come first, then what macros read
+        let dependencies: Vec<PathBuf> = source_map.files()[1..]
+            .iter()
+            .map(|file| file.path.clone())
+            .chain(expansion.dependencies)
             .collect();
-
-        println!("    ✅ {} tokens generated", tokens.len());
+        let tokens = expansion.tokens;
 
         // Step 2: Parsing
         println!("  [2/8] Parsing...");
         let mut parser = Parser::new(tokens);
-        let ast = parser.parse().map_err(|e| {
-            let error_msg = self.format_parse_error(&e, input_path);
-            CompilerError::parse(error_msg)
-        })?;
+        let (mut ast, parse_errors) = parser.parse_with_recovery();
+        match parse_errors.as_slice() {
+            [] => {}
+            [error] => {
+                return Err(CompilerError::parse(self.format_parse_error(error, &span_map, source_map)));
+            }
+            errors => {
+                let messages: Vec<String> = errors
+                    .iter()
+                    .map(|e| self.format_parse_error(e, &span_map, source_map))
+                    .collect();
+                return Err(CompilerError::Multiple(format!(
+                    "{}\n{} parse errors",
+                    messages.join("\n"),
+                    errors.len()
+                )));
+            }
+        }
         println!("    ✅ AST parsed");
 
+        // Remove items disabled by #[cfg(...)]
+        let cfg_errors = strip_unconfigured(&mut ast, &self.config.cfg_options());
+        if !cfg_errors.is_empty() {
+            let messages: Vec<String> = cfg_errors
+                .iter()
+                .map(|e| format!("{}: {}", source_map.location(&e.span), e))
+                .collect();
+            return Err(CompilerError::parse(messages.join("\n")));
+        }
+
+        // `mod name;` without a file is fine if #[cfg(...)] removed it
+        let missing = modules::unloaded_modules(&ast, &loaded.missing);
+        if !missing.is_empty() {
+            let messages: Vec<String> = missing.iter().map(|module| module.message(source_map)).collect();
+            return Err(CompilerError::parse(messages.join("\n")));
+        }
+
+        // Add the impls requested by #[derive(...)]
+        let derive_errors = expand_derives(&mut ast);
+        if !derive_errors.is_empty() {
+            let messages: Vec<String> = derive_errors
+                .iter()
+                .map(|e| format!("{}: {}", source_map.location(&e.span()), e))
+                .collect();
+            return Err(CompilerError::parse(messages.join("\n")));
+        }
+
+        // The prelude's lines come after the crate's files
+        let mut source_map = loaded.source_map;
+        let prelude = if prelude::is_disabled(&ast) {
+            None
+        } else {
+            Some(prelude::parse(&mut source_map)?)
+        };
+        let source_map = &source_map;
+
+        // Step 3: Name resolution and type checking
+        println!("  [3/8] Type checking...");
+        let (resolutions, resolve_errors) = match &prelude {
+            Some(prelude) => zulon_resolver::resolve_with_prelude(&ast, prelude),
+            None => zulon_resolver::resolve(&ast),
+        };
+        if !resolve_errors.is_empty() {
+            return Err(CompilerError::resolve(self.format_resolve_errors(&resolve_errors, source_map)));
+        }
+        // Code a macro expanded to is the macro's business
+        let warnings: Vec<_> = zulon_resolver::lint(&ast, &resolutions)
+            .into_iter()
+            .filter(|warning| span_map.expansion_of(warning.span()).is_none())
+            .collect();
+        for warning in &warnings {
+            eprintln!("{}", self.display_diagnostic(warning.to_diagnostic(source_map.text()), source_map));
+        }
+        if !warnings.is_empty() {
+            println!("    ⚠️  {} warning(s)", warnings.len());
+        }
+        // Later stages see the items of every module, and of the prelude
+        // after them, at the crate root
+        if let Some(prelude) = prelude {
+            ast.items.extend(prelude.items);
+        }
+        zulon_resolver::flatten_modules(&mut ast, &resolutions);
+
         // Extract extern function declarations
         let extern_functions = self.extract_extern_functions(&ast);
         if !extern_functions.is_empty() {
             println!("    📦 Found {} extern function(s)", extern_functions.len());
         }
 
-        // Step 3: Type checking
-        println!("  [3/8] Type checking...");
         let mut typeck = TypeChecker::new();
         typeck.check(&ast).map_err(|e| {
-            let error_msg = self.format_typeck_error(&e, input_path);
+            let error_msg = self.format_typeck_error(&e, source_map);
             CompilerError::type_check(error_msg)
         })?;
+        let warnings: Vec<_> = typeck.warnings()
+            .iter()
+            .filter(|warning| span_map.expansion_of(warning.span()).is_none())
+            .collect();
+        for warning in &warnings {
+            eprintln!("{}", self.display_diagnostic(warning.to_diagnostic(source_map.text()), source_map));
+        }
+        if !warnings.is_empty() {
+            println!("    ⚠️  {} warning(s)", warnings.len());
+        }
         println!("    ✅ Type checked");
 
         // Step 4: HIR lowering
         println!("  [4/8] HIR lowering...");
-        let mut hir_lowerer = SimpleLoweringContext::new();
+        let mut hir_lowerer = SimpleLoweringContext::with_type_checker(typeck);
         let hir_crate = hir_lowerer.lower_ast(&ast)
+            .and_then(|hir_crate| zulon_hir::monomorphize(&hir_crate))
             .map_err(|e| CompilerError::HirLowering(format!("{:?}", e)))?;
         println!("    ✅ HIR generated ({} items)", hir_crate.items.len());
 
@@ -407,6 +517,48 @@ impl Compiler {
         use std::collections::HashSet;
         let mut registered_structs = HashSet::new();
 
+        // Structs and enums declared in source, including one instance per
+        // use of a generic type, have their real field names and types
+        fn lir_ty(ty: &HirTy) -> LirTy {
+            zulon_mir::MirTy::from(ty.clone()).into()
+        }
+        for item in &hir_crate.items {
+            match item {
+                HirItem::Struct(def) => {
+                    let mut layout = StructLayout::new(def.name.clone());
+                    for field in &def.fields {
+                        let _ = layout.add_field(field.name.clone(), lir_ty(&field.ty));
+                    }
+                    layout.finalize();
+                    codegen.register_struct(layout);
+                    registered_structs.insert(def.name.clone());
+                }
+                HirItem::Enum(def) => {
+                    let mut layout = EnumLayout::new(def.name.clone(), LirTy::I32);
+                    for variant in &def.variants {
+                        let fields = variant.fields.iter()
+                            .map(|field| (field.name.clone(), lir_ty(&field.ty)))
+                            .collect();
+                        let _ = layout.add_variant(variant.name.clone(), variant.discriminant as u64, fields);
+                    }
+                    layout.finalize();
+                    codegen.register_enum(layout);
+                }
+                HirItem::VTable(vtable) => {
+                    codegen.register_vtable(VTable::new(vtable.name.clone(), vtable.methods.clone()));
+                }
+                HirItem::Static(def) => {
+                    codegen.register_global(Global::new(
+                        def.name.clone(),
+                        lir_ty(&def.ty),
+                        def.value.clone(),
+                        def.is_mutable,
+                    ));
+                }
+                _ => {}
+            }
+        }
+
         // Helper function to recursively register struct types
         // This handles nested pointer types by unwrapping them
         fn register_struct_recursive(
@@ -452,7 +604,9 @@ impl Compiler {
                         zulon_lir::LirInstruction::Store { ty, .. } => {
                             register_struct_recursive(ty, &mut registered_structs, &mut codegen);
                         }
-                        zulon_lir::LirInstruction::BinaryOp { ty, .. } => {
+                        zulon_lir::LirInstruction::BinaryOp { ty, .. }
+                        | zulon_lir::LirInstruction::InsertValue { ty, .. }
+                        | zulon_lir::LirInstruction::ExtractValue { ty, .. } => {
                             register_struct_recursive(ty, &mut registered_structs, &mut codegen);
                         }
                         zulon_lir::LirInstruction::Call { return_type, .. } => {
@@ -484,6 +638,7 @@ impl Compiler {
         ).map_err(|e| CompilerError::CodeGen(format!("{:?}", e)))?;
 
         println!("    ✅ Generated LLVM IR: {}", output_path.display());
+        self.write_dep_info(&output_path, input_path, &dependencies)?;
         println!();
         println!("✅ Compilation successful!");
         println!("   LLVM IR saved to: {}", output_path.display());
@@ -494,13 +649,43 @@ impl Compiler {
         Ok(())
     }
 
+    /// Write a Makefile-style `.d` file listing what the output was built from
+    ///
+    /// Besides the source file, this names every file embedded with
+    /// `include_str!` or `include_bytes!`, so build tools can rebuild when
+    /// one of them changes. Each dependency also gets an empty rule, so
+    /// deleting one doesn't break the build.
+    fn write_dep_info(&self, output_path: &Path, input_path: &Path, dependencies: &[PathBuf]) -> CompilerResult<()> {
+        fn escape(path: &Path) -> String {
+            path.display().to_string().replace(' ', "\\ ")
+        }
+
+        let mut contents = format!("{}: {}", escape(output_path), escape(input_path));
+        for dependency in dependencies {
+            contents.push(' ');
+            contents.push_str(&escape(dependency));
+        }
+        contents.push('\n');
+        for dependency in dependencies {
+            contents.push_str(&format!("\n{}:\n", escape(dependency)));
+        }
+
+        std::fs::write(output_path.with_extension("d"), contents).map_err(CompilerError::Io)
+    }
+
     /// Extract extern function declarations from the AST
+    ///
+    /// The runtime's functions are declared by the prelude. A crate may
+    /// declare one of them itself; the first declaration of a name wins.
     fn extract_extern_functions(&self, ast: &zulon_parser::ast::Ast) -> Vec<LirExternal> {
-        let mut externs = Vec::new();
+        let mut externs: Vec<LirExternal> = Vec::new();
 
-        // First, collect explicitly declared extern functions from source code
         for item in &ast.items {
             if let ItemKind::ExternFunction(func) = &item.kind {
+                if externs.iter().any(|e| e.name == func.name.name) {
+                    continue;
+                }
+
                 // Convert parameter types
                 let param_types: Vec<LirTy> = func.params.iter()
                     .filter_map(|p| p.type_annotation.as_ref())
@@ -512,156 +697,15 @@ impl Compiler {
                     .map(|ty| self.ast_type_to_lir_type(ty))
                     .unwrap_or(LirTy::Unit);
 
-                // Mark known variadic C functions
-                let is_variadice = matches!(func.name.name.as_str(), "printf" | "scanf");
-
                 externs.push(LirExternal {
                     name: func.name.name.clone(),
                     param_types,
                     return_type,
-                    variadic: is_variadice,
+                    variadic: func.is_variadic,
                 });
             }
         }
 
-        // CRITICAL FIX: Auto-inject common C standard library functions
-        // These are used implicitly by ZULON programs (e.g., printf for debugging)
-        // This allows users to call printf() without explicit extern declarations
-
-        // Check if printf is already declared
-        let has_printf = externs.iter().any(|e| e.name == "printf");
-        if !has_printf {
-            // Inject printf declaration: extern fn printf(format: *u8, ...) -> i32
-            externs.push(LirExternal {
-                name: "printf".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::U8))], // format: *u8
-                return_type: LirTy::I32,
-                variadic: true, // printf accepts variable arguments
-            });
-        }
-
-        // Check if scanf is needed (could check function calls, but inject for now)
-        let has_scanf = externs.iter().any(|e| e.name == "scanf");
-        if !has_scanf {
-            // Inject scanf declaration: extern fn scanf(format: *u8, ...) -> i32
-            externs.push(LirExternal {
-                name: "scanf".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::U8))], // format: *u8
-                return_type: LirTy::I32,
-                variadic: true, // scanf accepts variable arguments
-            });
-        }
-
-        // Inject string_concat for template string interpolation
-        // This is used by the compiler to implement template string interpolation
-        let has_string_concat = externs.iter().any(|e| e.name == "string_concat");
-        if !has_string_concat {
-            // Inject string_concat declaration: extern fn string_concat(str1: *u8, str2: *u8) -> *u8
-            externs.push(LirExternal {
-                name: "string_concat".to_string(),
-                param_types: vec![
-                    LirTy::Ptr(Box::new(LirTy::U8)), // str1: *u8
-                    LirTy::Ptr(Box::new(LirTy::U8)), // str2: *u8
-                ],
-                return_type: LirTy::Ptr(Box::new(LirTy::U8)), // returns *u8
-                variadic: false,
-            });
-        }
-
-        // Phase 2.2: Inject async scheduler extern functions
-        // These are used by ZULON's async runtime for non-blocking operations
-        println!("  [DEBUG] Checking async_scheduler externs, current count: {}", externs.len());
-
-        // Inject async_scheduler_create: extern fn async_scheduler_create() -> *AsyncScheduler
-        let has_async_scheduler_create = externs.iter().any(|e| e.name == "async_scheduler_create");
-        if !has_async_scheduler_create {
-            println!("  [DEBUG] Injecting async_scheduler_create");
-            externs.push(LirExternal {
-                name: "async_scheduler_create".to_string(),
-                param_types: vec![],
-                return_type: LirTy::Ptr(Box::new(LirTy::I8)), // *AsyncScheduler (opaque pointer)
-                variadic: false,
-            });
-        }
-
-        // Inject async_scheduler_run: extern fn async_scheduler_run(scheduler: *AsyncScheduler) -> i32
-        let has_async_scheduler_run = externs.iter().any(|e| e.name == "async_scheduler_run");
-        if !has_async_scheduler_run {
-            externs.push(LirExternal {
-                name: "async_scheduler_run".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::I8))], // scheduler: *AsyncScheduler
-                return_type: LirTy::I32,
-                variadic: false,
-            });
-        }
-
-        // Inject async_scheduler_stop: extern fn async_scheduler_stop(scheduler: *AsyncScheduler)
-        let has_async_scheduler_stop = externs.iter().any(|e| e.name == "async_scheduler_stop");
-        if !has_async_scheduler_stop {
-            externs.push(LirExternal {
-                name: "async_scheduler_stop".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::I8))], // scheduler: *AsyncScheduler
-                return_type: LirTy::Unit,
-                variadic: false,
-            });
-        }
-
-        // Inject async_scheduler_destroy: extern fn async_scheduler_destroy(scheduler: *AsyncScheduler)
-        let has_async_scheduler_destroy = externs.iter().any(|e| e.name == "async_scheduler_destroy");
-        if !has_async_scheduler_destroy {
-            externs.push(LirExternal {
-                name: "async_scheduler_destroy".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::I8))], // scheduler: *AsyncScheduler
-                return_type: LirTy::Unit,
-                variadic: false,
-            });
-        }
-
-        // Inject async_sleep: extern fn async_sleep(scheduler: *AsyncScheduler, duration_ms: i64) -> i32
-        let has_async_sleep = externs.iter().any(|e| e.name == "async_sleep");
-        if !has_async_sleep {
-            externs.push(LirExternal {
-                name: "async_sleep".to_string(),
-                param_types: vec![
-                    LirTy::Ptr(Box::new(LirTy::I8)), // scheduler: *AsyncScheduler
-                    LirTy::I64,                       // duration_ms: i64
-                ],
-                return_type: LirTy::I32,
-                variadic: false,
-            });
-        }
-
-        // Inject async_scheduler_get_event_loop: extern fn async_scheduler_get_event_loop(scheduler: *AsyncScheduler) -> *EventLoop
-        let has_async_scheduler_get_event_loop = externs.iter().any(|e| e.name == "async_scheduler_get_event_loop");
-        if !has_async_scheduler_get_event_loop {
-            externs.push(LirExternal {
-                name: "async_scheduler_get_event_loop".to_string(),
-                param_types: vec![LirTy::Ptr(Box::new(LirTy::I8))], // scheduler: *AsyncScheduler
-                return_type: LirTy::Ptr(Box::new(LirTy::I8)), // *EventLoop (opaque pointer)
-                variadic: false,
-            });
-        }
-
-        // Inject event_loop_add_timer: extern fn event_loop_add_timer(loop: *EventLoop, delay_ms: i64, callback: *u8, data: *u8) -> i32
-        let has_event_loop_add_timer = externs.iter().any(|e| e.name == "event_loop_add_timer");
-        if !has_event_loop_add_timer {
-            externs.push(LirExternal {
-                name: "event_loop_add_timer".to_string(),
-                param_types: vec![
-                    LirTy::Ptr(Box::new(LirTy::I8)), // loop: *EventLoop
-                    LirTy::I64,                       // delay_ms: i64
-                    LirTy::Ptr(Box::new(LirTy::U8)), // callback: *u8 (function pointer)
-                    LirTy::Ptr(Box::new(LirTy::U8)), // data: *u8
-                ],
-                return_type: LirTy::I32,
-                variadic: false,
-            });
-        }
-
-        eprintln!("DEBUG: extract_extern_functions returning {} externs", externs.len());
-        for (i, ext) in externs.iter().enumerate() {
-            eprintln!("DEBUG:   [{}] {} -> {:?}", i, ext.name, ext.return_type);
-        }
         externs
     }
 
@@ -672,6 +716,7 @@ impl Compiler {
                 match ident.name.as_str() {
                     "i32" => LirTy::I32,
                     "i64" => LirTy::I64,
+                    "i8" => LirTy::I8,
                     "u8" => LirTy::U8,
                     "u32" => LirTy::U32,
                     "u64" => LirTy::U64,
@@ -682,23 +727,24 @@ impl Compiler {
                     _ => LirTy::I32, // Default to i32 for unknown types
                 }
             }
-            AstType::Ref(base, _mut) => {
+            AstType::Ref(base, _mut) | AstType::Pointer(base, _mut) => {
                 LirTy::Ptr(Box::new(self.ast_type_to_lir_type(base)))
             }
+            AstType::Unit => LirTy::Unit,
             _ => LirTy::I32, // Default to i32 for complex types
         }
     }
 
     /// Format parse errors with helpful context
-    fn format_parse_error(&self, error: &zulon_parser::ParseError, file_path: &Path) -> String {
+    fn format_parse_error(&self, error: &zulon_parser::ParseError, span_map: &SpanMap, source_map: &SourceMap) -> String {
         use zulon_parser::ParseError;
         use std::fmt::Write;
 
         let mut msg = String::new();
 
-        match error {
+        let span = match error {
             ParseError::UnexpectedToken { expected, found, span } => {
-                writeln!(msg, "Parse error: {}", self.format_location(span, file_path)).unwrap_or(());
+                writeln!(msg, "Parse error: {}", source_map.location(span)).unwrap_or(());
                 writeln!(msg, "  Expected: {}", expected).unwrap_or(());
                 writeln!(msg, "  Found: {:?}", found).unwrap_or(());
 
@@ -709,49 +755,64 @@ impl Compiler {
                     writeln!(msg, "  Example:").unwrap_or(());
                     writeln!(msg, "    let x = 10;  ← Add semicolon here").unwrap_or(());
                 }
+                span
             }
             ParseError::UnexpectedEof { span } => {
-                writeln!(msg, "Parse error: {}", self.format_location(span, file_path)).unwrap_or(());
+                writeln!(msg, "Parse error: {}", source_map.location(span)).unwrap_or(());
                 writeln!(msg, "  Unexpected end of file").unwrap_or(());
                 writeln!(msg).unwrap_or(());
                 writeln!(msg, "  💡 Hint: Check that all braces, parentheses, and brackets are properly closed").unwrap_or(());
+                span
             }
             ParseError::InvalidSyntax { message, span } => {
-                writeln!(msg, "Parse error: {}", self.format_location(span, file_path)).unwrap_or(());
+                writeln!(msg, "Parse error: {}", source_map.location(span)).unwrap_or(());
                 writeln!(msg, "  {}", message).unwrap_or(());
+                span
             }
             ParseError::ModuleError { source } => {
-                return self.format_parse_error(source, file_path);
+                return self.format_parse_error(source, span_map, source_map);
             }
+            ParseError::Format(error) => {
+                msg.push_str(&self.format_format_error(error, source_map));
+                &error.span
+            }
+        };
+
+        for note in MacroExpander::expansion_notes(span_map, *span) {
+            writeln!(msg, "  note: {}", note).unwrap_or(());
         }
 
         msg
     }
 
+    /// Format bad format strings and specifiers using the diagnostic system
+    fn format_format_error(&self, error: &zulon_parser::FormatError, source_map: &SourceMap) -> String {
+        self.display_diagnostic(error.to_diagnostic(source_map.text()), source_map)
+    }
+
     /// Format type check errors with helpful context using the diagnostic system
-    fn format_typeck_error(&self, error: &zulon_typeck::TypeError, file_path: &Path) -> String {
-        // Read source file for diagnostics
-        let source = std::fs::read_to_string(file_path)
-            .unwrap_or_else(|_| "".to_string());
+    fn format_typeck_error(&self, error: &zulon_typeck::TypeError, source_map: &SourceMap) -> String {
+        self.display_diagnostic(error.to_diagnostic(source_map.text()), source_map)
+    }
 
-        // Convert TypeError to Diagnostic and display with context
-        let diagnostic = error.to_diagnostic(&source);
+    /// Format name resolution errors with source context
+    fn format_resolve_errors(&self, errors: &[zulon_resolver::ResolveError], source_map: &SourceMap) -> String {
+        errors
+            .iter()
+            .map(|error| self.display_diagnostic(error.to_diagnostic(source_map.text()), source_map))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
+    /// Display a diagnostic made against the text of every file, with the
+    /// source of the file it points into
+    fn display_diagnostic(&self, diagnostic: zulon_diagnostic::Diagnostic, source_map: &SourceMap) -> String {
         // Use colors if terminal supports it
         let use_colors = std::env::var("NO_COLOR").is_err() && atty::is(atty::Stream::Stderr);
 
-        diagnostic.display_with_context(&source, use_colors)
-    }
-
-    /// Format error location with file context
-    fn format_location(&self, span: &zulon_parser::Span, file_path: &Path) -> String {
-        format!("{}:{}:{} to {}:{}",
-            file_path.display(),
-            span.start.line,
-            span.start.column,
-            span.end.line,
-            span.end.column
-        )
+        // Labels may be in other files than the primary span
+        let (diagnostic, _) = source_map.localize(diagnostic);
+        diagnostic.display_with_sources(&|file| source_map.source(file), use_colors)
     }
 }
 
@@ -760,3 +821,28 @@ impl Default for Compiler {
         Self::new(CompilerConfig::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cfg_options_from_config() {
+        let config = CompilerConfig {
+            target: Some("x86_64-unknown-linux-gnu".to_string()),
+            test: true,
+            features: vec!["simd".to_string()],
+            ..CompilerConfig::default()
+        };
+
+        let cfg = config.cfg_options();
+        assert!(cfg.has_name("test"));
+        assert!(cfg.has_name("unix"));
+        assert!(cfg.has_value("target_os", "linux"));
+        assert!(cfg.has_value("feature", "simd"));
+
+        let cfg = CompilerConfig::default().cfg_options();
+        assert!(!cfg.has_name("test"));
+        assert!(cfg.has_value("target_os", std::env::consts::OS));
+    }
+}
@@ -24,6 +24,10 @@ pub enum CompilerError {
     #[error("Parse error: {0}")]
     Parse(String),
 
+    /// Name resolution error
+    #[error("Name resolution error: {0}")]
+    Resolve(String),
+
     /// Type checking error
     #[error("Type error: {0}")]
     TypeCheck(String),
@@ -80,6 +84,11 @@ impl CompilerError {
         Self::Parse(msg.into())
     }
 
+    /// Create a name resolution error
+    pub fn resolve(msg: impl Into<String>) -> Self {
+        Self::Resolve(msg.into())
+    }
+
     /// Create a type checking error
     pub fn type_check(msg: impl Into<String>) -> Self {
         Self::TypeCheck(msg.into())
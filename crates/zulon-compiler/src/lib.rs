@@ -43,6 +43,8 @@
 pub mod compiler;
 pub mod error;
 pub mod macro_expander;
+pub mod modules;
+pub mod prelude;
 
 pub use compiler::{Compiler, CompilerConfig};
 pub use error::{CompilerError, Result};
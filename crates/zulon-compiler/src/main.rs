@@ -34,6 +34,14 @@ struct Args {
     /// Target triple (e.g., "x86_64-unknown-linux-gnu")
     #[arg(long, value_name = "TRIPLE")]
     target: Option<String>,
+
+    /// Compile with `cfg(test)` enabled
+    #[arg(long)]
+    test: bool,
+
+    /// Features to enable for `cfg(feature = "...")` (comma separated)
+    #[arg(long, value_name = "FEATURES", value_delimiter = ',')]
+    features: Vec<String>,
 }
 
 fn main() -> Result<()> {
@@ -56,6 +64,8 @@ fn main() -> Result<()> {
         output: args.output,
         keep_intermediates: args.keep_intermediates,
         target: args.target,
+        test: args.test,
+        features: args.features,
     };
 
     // Run compiler
@@ -0,0 +1,265 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Loading the files of `mod name;` declarations
+//!
+//! The modules of the root file live in its directory. `mod name;` in a
+//! module whose modules live in `dir` loads `dir/name.zl` or
+//! `dir/name/mod.zl`, and the modules of the loaded file live in
+//! `dir/name`; an inline `mod inner { ... }` adds `inner` to the
+//! directory. Files are loaded as tokens, before macro expansion, so the
+//! macros defined above a `mod name;` can be used in the file just as in
+//! an inline module. A [`SourceMap`] records which lines are whose.
+
+use crate::error::{CompilerError, Result};
+use std::path::{Path, PathBuf};
+use zulon_parser::ast::{Ast, Item, ItemKind};
+use zulon_parser::{Lexer, SourceMap, Span, Token, TokenKind};
+
+/// The tokens of a crate's files, with each `mod name;` replaced by
+/// `mod name { ... }`
+pub struct LoadedCrate {
+    pub tokens: Vec<Token>,
+    /// The files loaded, the root first
+    pub source_map: SourceMap,
+    /// `mod name;` declarations with no file, left as written
+    ///
+    /// These are only an error if `#[cfg]` does not remove them.
+    pub missing: Vec<MissingModule>,
+}
+
+/// A `mod name;` whose file does not exist
+#[derive(Debug, Clone)]
+pub struct MissingModule {
+    pub name: String,
+    /// Span of the module's name
+    pub span: Span,
+    /// The files that were looked for
+    pub candidates: [PathBuf; 2],
+}
+
+/// Load the root file of a crate and every module file it declares
+pub fn load_crate(root: &Path, source: String) -> Result<LoadedCrate> {
+    let mut loader = Loader {
+        source_map: SourceMap::new(),
+        missing: Vec::new(),
+    };
+    let module_dir = root.parent().map(Path::to_path_buf).unwrap_or_default();
+    let tokens = loader.load_file(root.to_path_buf(), source, &module_dir)?;
+    Ok(LoadedCrate {
+        tokens,
+        source_map: loader.source_map,
+        missing: loader.missing,
+    })
+}
+
+struct Loader {
+    source_map: SourceMap,
+    missing: Vec<MissingModule>,
+}
+
+impl Loader {
+    /// Lex a file, and load the modules it declares in place
+    fn load_file(&mut self, path: PathBuf, source: String, module_dir: &Path) -> Result<Vec<Token>> {
+        let (tokens, lex_errors) = Lexer::new(&source).lex_all();
+        if !lex_errors.is_empty() {
+            for err in &lex_errors {
+                eprintln!("    Lexical error in {}: {:?}", path.display(), err);
+            }
+            return Err(CompilerError::lexical(format!("{} lexical errors", lex_errors.len())));
+        }
+
+        // Filter out comment tokens (they're not needed for parsing)
+        let mut tokens: Vec<_> = tokens
+            .into_iter()
+            .filter(|t| !matches!(t.kind, TokenKind::Comment))
+            .collect();
+        self.source_map.add_file(path, source).shift(&mut tokens);
+
+        let mut output = Vec::with_capacity(tokens.len());
+        // Inline modules being scanned, with the brace depth outside each
+        let mut inline: Vec<(String, usize)> = Vec::new();
+        let mut depth = 0;
+        let mut pos = 0;
+        while pos < tokens.len() {
+            match (&tokens[pos].kind, tokens.get(pos + 1).map(|t| &t.kind), tokens.get(pos + 2).map(|t| &t.kind)) {
+                (TokenKind::Mod, Some(TokenKind::Ident(name)), Some(TokenKind::LeftBrace)) => {
+                    inline.push((name.to_string(), depth));
+                }
+                (TokenKind::Mod, Some(TokenKind::Ident(name)), Some(TokenKind::Semicolon)) => {
+                    let dir = inline.iter().fold(module_dir.to_path_buf(), |dir, (inner, _)| dir.join(inner));
+                    let name_span = tokens[pos + 1].span;
+                    let semicolon = tokens[pos + 2].span;
+                    output.extend_from_slice(&tokens[pos..pos + 2]);
+                    pos += 2;
+                    // Without a file, the `;` is kept
+                    if let Some(module_tokens) = self.load_module(name, name_span, &dir)? {
+                        output.push(Token { kind: TokenKind::LeftBrace, span: semicolon });
+                        output.extend(module_tokens);
+                        output.push(Token { kind: TokenKind::RightBrace, span: semicolon });
+                        pos += 1;
+                    }
+                    continue;
+                }
+                (TokenKind::LeftBrace, _, _) => depth += 1,
+                (TokenKind::RightBrace, _, _) => {
+                    depth = depth.saturating_sub(1);
+                    if inline.last().is_some_and(|(_, outside)| *outside == depth) {
+                        inline.pop();
+                    }
+                }
+                _ => {}
+            }
+            output.push(tokens[pos].clone());
+            pos += 1;
+        }
+        Ok(output)
+    }
+
+    /// The tokens of the file for `mod name;`, or `None` if there is none
+    fn load_module(&mut self, name: &str, span: Span, dir: &Path) -> Result<Option<Vec<Token>>> {
+        let candidates = [dir.join(format!("{}.zl", name)), dir.join(name).join("mod.zl")];
+        let location = self.source_map.location(&span);
+        let path = match candidates.iter().filter(|path| path.is_file()).collect::<Vec<_>>()[..] {
+            [] => {
+                self.missing.push(MissingModule {
+                    name: name.to_string(),
+                    span,
+                    candidates,
+                });
+                return Ok(None);
+            }
+            [path] => path.clone(),
+            _ => {
+                return Err(CompilerError::parse(format!(
+                    "{}: file for module `{}` found at both \"{}\" and \"{}\"\n  💡 Hint: delete or rename one of them",
+                    location,
+                    name,
+                    candidates[0].display(),
+                    candidates[1].display()
+                )));
+            }
+        };
+        if self.source_map.files().iter().any(|file| file.path == path) {
+            return Err(CompilerError::parse(format!(
+                "{}: module `{}` loads \"{}\", which is already loaded",
+                location,
+                name,
+                path.display()
+            )));
+        }
+
+        let source = std::fs::read_to_string(&path).map_err(CompilerError::Io)?;
+        self.load_file(path, source, &dir.join(name)).map(Some)
+    }
+}
+
+/// The missing modules that are still in the AST
+pub fn unloaded_modules<'a>(ast: &Ast, missing: &'a [MissingModule]) -> Vec<&'a MissingModule> {
+    fn visit(items: &mut dyn Iterator<Item = &Item>, found: &mut Vec<Span>) {
+        for item in items {
+            if let ItemKind::Module(module) = &item.kind {
+                match &module.items {
+                    Some(items) => visit(&mut items.iter().map(|item| &**item), found),
+                    None => found.push(module.name.span),
+                }
+            }
+        }
+    }
+
+    let mut unloaded = Vec::new();
+    visit(&mut ast.items.iter(), &mut unloaded);
+    missing.iter().filter(|module| unloaded.contains(&module.span)).collect()
+}
+
+impl MissingModule {
+    /// The error for a missing module that `#[cfg]` kept
+    pub fn message(&self, source_map: &SourceMap) -> String {
+        format!(
+            "{}: file not found for module `{}`\n  💡 Hint: to create the module `{}`, create file \"{}\" or \"{}\"",
+            source_map.location(&self.span),
+            self.name,
+            self.name,
+            self.candidates[0].display(),
+            self.candidates[1].display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use zulon_parser::Parser;
+
+    fn write(dir: &Path, path: &str, source: &str) {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+
+    fn load(dir: &Path, source: &str) -> Result<LoadedCrate> {
+        load_crate(&dir.join("main.zl"), source.to_string())
+    }
+
+    #[test]
+    fn test_module_files_are_spliced_in() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "util.zl", "pub fn helper() -> i32 { 1 }\n");
+        write(dir.path(), "shapes/mod.zl", "pub mod circle;\n");
+        write(dir.path(), "shapes/circle.zl", "pub fn area() -> i32 { 3 }\n");
+        write(dir.path(), "outer/inner/deep.zl", "pub fn deep() {}\n");
+
+        let source = "mod util;\nmod shapes;\nmod outer { pub mod inner { pub mod deep; } }\n";
+        let loaded = load(dir.path(), source).unwrap();
+        assert!(loaded.missing.is_empty());
+
+        let paths: Vec<_> = loaded
+            .source_map
+            .files()
+            .iter()
+            .map(|file| file.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        let expected: Vec<PathBuf> = ["main.zl", "util.zl", "shapes/mod.zl", "shapes/circle.zl", "outer/inner/deep.zl"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(paths, expected);
+
+        let ast = Parser::new(loaded.tokens).parse().unwrap();
+        let ItemKind::Module(util) = &ast.items[0].kind else { panic!("expected a module") };
+        let helper = &util.items.as_ref().expect("a loaded module")[0];
+
+        // Tokens of a module file point into its own lines
+        let file = loaded.source_map.lookup(helper.span.start.line).unwrap();
+        assert_eq!(file.path, dir.path().join("util.zl"));
+        assert_eq!(helper.span.start.line - file.line_offset, 1);
+    }
+
+    #[test]
+    fn test_missing_module_is_reported_unless_removed() {
+        let dir = TempDir::new().unwrap();
+        let source = "mod gone;\n#[cfg(any())]\nmod also_gone;\n";
+        let loaded = load(dir.path(), source).unwrap();
+        assert_eq!(loaded.missing.len(), 2);
+
+        let mut ast = Parser::new(loaded.tokens).parse().unwrap();
+        ast.items.remove(1);
+        let unloaded = unloaded_modules(&ast, &loaded.missing);
+        assert_eq!(unloaded.len(), 1);
+        let message = unloaded[0].message(&loaded.source_map);
+        assert!(message.contains("main.zl:1:5"), "{}", message);
+        assert!(message.contains("file not found for module `gone`"), "{}", message);
+        assert!(message.contains("gone.zl"), "{}", message);
+    }
+
+    #[test]
+    fn test_module_file_in_two_places_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        write(dir.path(), "util.zl", "");
+        write(dir.path(), "util/mod.zl", "");
+
+        let error = load(dir.path(), "mod util;\n").err().expect("an error");
+        assert!(error.to_string().contains("found at both"), "{}", error);
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The prelude: items every crate can use without importing them
+//!
+//! The prelude is ZULON source, `prelude.zl`, built into the compiler. It
+//! declares the runtime's extern functions, `Option`, `Result` and
+//! `Ordering`, and the traits `#[derive(...)]` implements. It is resolved
+//! as a module outside the crate (see `zulon_resolver::resolve_with_prelude`)
+//! and its items are added to the crate's before type checking.
+//!
+//! A crate whose root file has the inner attribute `#![no_prelude]` gets no
+//! prelude, and has to declare everything it uses, `printf` included.
+
+use crate::error::{CompilerError, Result};
+use zulon_parser::ast::Ast;
+use zulon_parser::{Lexer, Parser, SourceMap, TokenKind};
+
+/// The prelude's source
+pub const SOURCE: &str = include_str!("prelude.zl");
+
+/// The name diagnostics give the prelude's file
+pub const PATH: &str = "<prelude>";
+
+/// Whether a crate opted out of the prelude with `#![no_prelude]`
+pub fn is_disabled(ast: &Ast) -> bool {
+    ast.attributes.iter().any(|attribute| attribute.name.name == "no_prelude")
+}
+
+/// Parse the prelude, placing its lines after the files in `source_map`
+pub fn parse(source_map: &mut SourceMap) -> Result<Ast> {
+    let (tokens, lex_errors) = Lexer::new(SOURCE).lex_all();
+    if !lex_errors.is_empty() {
+        return Err(CompilerError::lexical(format!("the prelude has lexical errors: {:?}", lex_errors)));
+    }
+    let mut tokens: Vec<_> = tokens
+        .into_iter()
+        .filter(|t| !matches!(t.kind, TokenKind::Comment))
+        .collect();
+    source_map.add_file(PATH.into(), SOURCE.to_string()).shift(&mut tokens);
+
+    Parser::new(tokens)
+        .parse()
+        .map_err(|e| CompilerError::parse(format!("the prelude does not parse: {:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prelude_parses_and_resolves() {
+        let mut source_map = SourceMap::new();
+        source_map.add_file("main.zl".into(), "fn main() {}".to_string());
+        let prelude = parse(&mut source_map).unwrap();
+        assert_eq!(source_map.files()[1].path.to_str(), Some(PATH));
+
+        let ast = Parser::from_source("fn main() {}").parse().unwrap();
+        let (_, errors) = zulon_resolver::resolve_with_prelude(&ast, &prelude);
+        assert_eq!(errors, Vec::new());
+    }
+
+    #[test]
+    fn test_no_prelude_attribute() {
+        let ast = Parser::from_source("#![no_prelude]\nfn main() {}").parse().unwrap();
+        assert!(is_disabled(&ast));
+        let ast = Parser::from_source("fn main() {}").parse().unwrap();
+        assert!(!is_disabled(&ast));
+    }
+}
@@ -13,7 +13,7 @@ use tempfile::TempDir;
 /// Helper struct to manage test compilation
 struct TestCompilation {
     /// Temporary directory for test files
-    _temp_dir: TempDir,
+    temp_dir: TempDir,
     /// Path to the source file
     source_path: PathBuf,
     /// Path to the LLVM IR output file
@@ -32,12 +32,21 @@ impl TestCompilation {
         file.write_all(source.as_bytes()).expect("Failed to write source");
 
         TestCompilation {
-            _temp_dir: temp_dir,
+            temp_dir,
             source_path,
             ir_path,
         }
     }
 
+    /// Add another file of the crate, such as a module's, at `path` in the
+    /// crate's directory
+    fn with_file(self, path: &str, source: &str) -> Self {
+        let path = self.temp_dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).expect("Failed to create module directory");
+        fs::write(&path, source).expect("Failed to write module file");
+        self
+    }
+
     /// Compile the source code and return the LLVM IR
     fn compile(&self) -> Result<String, Box<dyn std::error::Error>> {
         // Run the compiler
@@ -60,6 +69,32 @@ impl TestCompilation {
         Ok(ir)
     }
 
+    /// Compile the source code and run the LLVM IR with `lli`, loading the
+    /// C runtime, and return the exit code and standard output
+    fn run(&self) -> Result<(i32, String), Box<dyn std::error::Error>> {
+        self.compile()?;
+
+        let runtime_source = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../zulon-runtime-core/c/zulon_entry.c");
+        let runtime = self.temp_dir.path().join("runtime.so");
+        let status = std::process::Command::new("cc")
+            .args(["-shared", "-fPIC", "-o"])
+            .arg(&runtime)
+            .arg(&runtime_source)
+            .status()?;
+        if !status.success() {
+            return Err("Building the runtime failed".into());
+        }
+
+        let output = std::process::Command::new("lli")
+            .arg("-opaque-pointers")
+            .arg(format!("-load={}", runtime.display()))
+            .arg(&self.ir_path)
+            .output()?;
+        let code = output.status.code().ok_or("lli was killed by a signal")?;
+        Ok((code, String::from_utf8_lossy(&output.stdout).into_owned()))
+    }
+
     /// Check if the LLVM IR contains a specific string
     #[allow(dead_code)]
     fn contains(&self, pattern: &str) -> Result<bool, Box<dyn std::error::Error>> {
@@ -345,6 +380,102 @@ fn test_multiple_template_strings() {
             "LLVM IR should contain all template strings");
 }
 
+#[test]
+fn test_template_string_formatting() {
+    let source = r#"
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        impl Display for Point {
+            fn fmt(self: &Self) -> str {
+                `(${self.x}, ${self.y})`
+            }
+        }
+
+        fn show<T: Display>(value: T) -> str {
+            `<${value}>`
+        }
+
+        fn main() -> i32 {
+            let p = Point { x: 3, y: 4 };
+            let small = 200u8;
+            let neg = -7i16;
+            let ratio = 1.5f32;
+            println!("{} {} {:>4} {:.2} {} {}", p, small, neg, ratio, show(p), show(5));
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("define ptr @\"<Point as Display>::fmt\"("), "LLVM IR should contain the impl's fmt:\n{}", ir);
+    assert!(ir.contains("call ptr (ptr) @\"<Point as Display>::fmt\"(ptr"), "Points should be formatted by the impl");
+    assert!(ir.contains("define ptr @show$LT$Point$GT$("), "LLVM IR should contain the Point instance");
+    assert!(ir.contains("define ptr @show$LT$i32$GT$("), "LLVM IR should contain the i32 instance");
+    assert!(!ir.contains("@Display::fmt"), "No bare trait method should be called");
+    assert!(ir.contains("zext i8 %v"), "u8 should be widened for zulon_format_u32");
+    assert!(ir.contains("sext i16 %v"), "i16 should be widened for zulon_format_i32");
+    assert!(ir.contains("fpext float %v"), "f32 should be widened for zulon_format_f64");
+}
+
+#[test]
+fn test_format_macros_in_a_row_run() {
+    let source = r#"
+        fn main() -> i32 {
+            println!("{}", 1);
+            println!("{}", true);
+            println!("{} {}", 1 + 1, format!("{}", 7 * 3));
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let (code, stdout) = test_comp.run().expect("Running failed");
+
+    // Each expansion's argument locals are its own
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "1\ntrue\n2 21\n");
+}
+
+#[test]
+fn test_debug_formatting_quotes_strings() {
+    let source = r#"
+        #[derive(Debug)]
+        struct User {
+            name: str,
+            id: i32,
+        }
+
+        struct Tag {
+            id: i32,
+        }
+
+        impl Debug for Tag {
+            fn fmt(self: &Self) -> str {
+                `#${self.id}`
+            }
+        }
+
+        fn main() -> i32 {
+            let s = "qs";
+            let user = User { name: `ann`, id: 7 };
+            let tag = Tag { id: 3 };
+            println!("{:?} {} {:?} {:?} {:>4?}|", s, s, "a\"b", user, tag);
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let (code, stdout) = test_comp.run().expect("Running failed");
+
+    // Strings a `fmt` impl returns are only padded
+    assert_eq!(code, 0);
+    assert_eq!(stdout, "\"qs\" qs \"a\\\"b\" User { name: \"ann\", id: 7 }   #3|\n");
+}
+
 //
 // Arithmetic Tests
 //
@@ -389,6 +520,47 @@ fn test_comparison_operations() {
     assert!(ir.contains("icmp") || ir.contains("cmp"), "LLVM IR should contain comparison instructions");
 }
 
+#[test]
+fn test_literals_use_inferred_width() {
+    let source = r#"
+        fn wide(x: i64) -> i64 {
+            x * 3000000000
+        }
+
+        fn main() -> i32 {
+            let small: u8 = 200;
+            let big = 7i64;
+            if wide(2) > big { 1 } else { 0 }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("add i64 0, 3000000000"), "literal should take the i64 of its context");
+    assert!(ir.contains("add i64 0, 7"), "suffixed literal should keep its suffix type");
+    assert!(ir.contains("add i8 0, 200"), "annotated literal should take the annotated type");
+    assert!(ir.contains("mul i64"), "arithmetic should use the operands' type");
+    assert!(ir.contains("icmp sgt i64"), "comparison should use the operands' type");
+}
+
+#[test]
+fn test_wide_literals_keep_every_bit() {
+    let source = r#"
+        fn main() -> i32 {
+            let big = 0x1_0000_0000_0000_0001u128;
+            let max = 340282366920938463463374607431768211455u128;
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("add i128 0, 18446744073709551617"), "u128 literal should not be truncated");
+    assert!(ir.contains("add i128 0, -1"), "u128::MAX should keep all 128 bits");
+}
+
 //
 // Complex Integration Tests
 //
@@ -482,6 +654,50 @@ fn test_complex_match() {
     assert!(ir.contains("br") || ir.contains("switch"), "LLVM IR should contain branches for match");
 }
 
+#[test]
+fn test_match_ranges_and_guards() {
+    let source = r#"
+        fn classify(x: i32) -> i32 {
+            match x {
+                0 => 1,
+                1 | 2 => 2,
+                3..=9 => 3,
+                n if n < 0 => n,
+                _ => 5,
+            }
+        }
+
+        fn main() -> i32 {
+            classify(7)
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    // Ranges compare against both bounds, and arms are tested in order
+    assert!(ir.contains("icmp sge"), "range pattern should test its lower bound");
+    assert!(ir.contains("icmp sle"), "range pattern should test its upper bound");
+    assert!(ir.contains("icmp slt"), "guard should be evaluated");
+    assert!(ir.contains("phi"), "arm values should join in a phi");
+}
+
+#[test]
+fn test_non_exhaustive_match_rejected() {
+    let source = r#"
+        fn main() -> i32 {
+            let x = 3;
+            match x {
+                0 => 1,
+                1..=9 => 2,
+            }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    assert!(test_comp.compile().is_err(), "non-exhaustive match should not compile");
+}
+
 //
 // Extern Function Tests
 //
@@ -531,6 +747,58 @@ fn test_extern_function_with_multiple_args() {
     assert!(ir.contains("declare"), "LLVM IR should declare extern function");
 }
 
+#[test]
+fn test_prelude_declares_runtime_functions() {
+    let source = r#"
+        #[derive(Clone, PartialEq)]
+        struct Point { x: i32 }
+
+        fn main() -> i32 {
+            printf("%d\n", 42);
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("declare i32 @printf(ptr"), "LLVM IR should declare printf from the prelude");
+    assert_eq!(ir.matches("declare i32 @printf(").count(), 1, "printf should be declared once");
+    assert!(ir.contains("declare ptr @string_concat"), "LLVM IR should declare string_concat from the prelude");
+}
+
+#[test]
+fn test_no_prelude_leaves_printf_undeclared() {
+    let source = r#"
+        #![no_prelude]
+
+        fn main() -> i32 {
+            printf("%d\n", 42);
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let error = test_comp.compile().expect_err("printf should not be declared");
+    assert!(error.to_string().contains("cannot find function `printf`"), "{}", error);
+}
+
+#[test]
+fn test_unused_code_warnings_do_not_fail_compilation() {
+    let source = r#"
+        fn helper() -> i32 { 1 }
+
+        fn main() -> i32 {
+            let unused = 5;
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("warnings should not fail compilation");
+    assert!(ir.contains("define i32 @main"), "{}", ir);
+}
+
 //
 // Return Statement Tests
 //
@@ -622,10 +890,70 @@ fn test_variable_shadowing() {
     // when it's implemented
 }
 
+#[test]
+fn test_byte_strings_are_constants() {
+    let source = r#"
+        fn main() -> i32 {
+            let greeting = b"hi \"\\\n\xff";
+            let copy = greeting;
+            println!("{}", "after \"bytes\"");
+            7
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+    assert!(ir.contains(r#"constant [7 x i8] c"hi \22\5C\0A\FF""#), "{}", ir);
+
+    let (code, stdout) = test_comp.run().expect("Running failed");
+    assert_eq!(stdout, "after \"bytes\"\n");
+    assert_eq!(code, 7);
+}
+
+#[test]
+fn test_shadowed_locals_keep_their_own_values() {
+    let source = r#"
+        fn main() -> i32 {
+            let a = 5;
+            {
+                let a = true;
+                if a {
+                    println!("inner {}", a);
+                }
+            }
+            let b = a + 1;
+            let b = b * 10;
+            println!("outer {} {}", a, b);
+            a + b
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let (code, stdout) = test_comp.run().expect("Running failed");
+
+    assert_eq!(stdout, "inner true\nouter 5 60\n");
+    assert_eq!(code, 65);
+}
+
 //
 // Error Handling Tests
 //
 
+#[test]
+fn test_labels_show_lines_of_their_own_file() {
+    let source = "mod util;\n\nfn main() -> i32 {\n    util::helpers::hidden()\n}\n";
+
+    let test_comp = TestCompilation::new(source)
+        .with_file("util.zl", "pub mod helpers;\n")
+        .with_file("util/helpers.zl", "// helpers\nfn hidden() -> i32 {\n    1\n}\n");
+    let error = test_comp.compile().expect_err("hidden is private").to_string();
+
+    assert!(error.contains("function `hidden` is private"), "{}", error);
+    assert!(error.contains("  4 |     util::helpers::hidden()"), "{}", error);
+    assert!(error.contains("helpers.zl:2:4\n  2 | fn hidden() -> i32 {\n"), "{}", error);
+    assert!(!error.contains("2 | mod util;"), "{}", error);
+}
+
 #[test]
 fn test_undefined_variable_error() {
     let source = r#"
@@ -686,6 +1014,583 @@ fn test_enum_declaration() {
     // Note: Enum variant construction has issues, so we just test compilation
 }
 
+#[test]
+fn test_enum_payload_patterns() {
+    let source = r#"
+        enum Shape {
+            Circle(i64),
+            Rect(i64, i64),
+            Ring { inner: i64, outer: i64 },
+            Empty,
+        }
+
+        fn area(shape: Shape) -> i64 {
+            match shape {
+                Shape::Circle(r) => r * r * 3,
+                Shape::Rect(w, h) => w * h,
+                Shape::Ring { inner, outer } => outer - inner,
+                Shape::Empty => 0,
+            }
+        }
+
+        fn main() -> i32 {
+            let rect = Shape::Rect(2, 3);
+            let ring = Shape::Ring { inner: 1, outer: 4 };
+            (area(rect) + area(ring) + area(Shape::Empty)) as i32
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("%enum.Shape = type [24 x i8]"), "enums should be laid out from their variants");
+    assert!(ir.contains("define i64 @area(%enum.Shape %v0)"), "enum values should have the enum's type");
+    assert!(!ir.contains("Shape::Rect"), "variants should be built in place, not called");
+    assert!(ir.contains("store i32 1, ptr"), "`Rect` should store its discriminant");
+    assert!(ir.contains("store i32 2, ptr"), "`Ring` should store its discriminant");
+    assert!(!ir.contains("icmp eq %enum.Shape"), "patterns should compare discriminants, not whole values");
+    assert!(
+        ir.contains("getelementptr inbounds { i32, { i64, i64 } }, ptr")
+            && ir.contains(", i32 0, i32 1, i32 1\n"),
+        "patterns should read the second payload field of `Rect` and `Ring`"
+    );
+    assert!(
+        ir.contains("load i64, ptr %v") && ir.contains("store i64 %v"),
+        "payload fields should be stored in their bindings"
+    );
+}
+
+//
+// Pattern Binding Tests
+//
+
+#[test]
+fn test_let_else_and_if_let_bindings() {
+    let source = r#"
+        extern fn puts(s: string) -> i32;
+
+        fn split(total: i64) -> (i64, bool) {
+            (total, total > 0)
+        }
+
+        fn half(pair: (f64, bool)) -> f64 {
+            if let (x, true) = pair { x / 2.0 } else { 0.0 }
+        }
+
+        fn main() -> i32 {
+            let (count, positive) = split(5) else {
+                puts("no match");
+                return 1;
+            };
+            let mut big: i64 = count else {
+                return 2;
+            };
+            big = big + 1;
+            if positive { 0 } else { 3 }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    // The bindings keep the types of the values they match
+    assert!(ir.contains("alloca i1"), "bool binding should have an i1 slot:\n{}", ir);
+    assert!(ir.contains("select i1 true, double"), "f64 binding should be copied as a double:\n{}", ir);
+    assert!(ir.contains("phi double"), "if-let should produce an f64:\n{}", ir);
+    assert!(!ir.contains("add i32 %v"), "no value should be copied as an i32:\n{}", ir);
+
+    // The else block is lowered once, not once per binding
+    assert_eq!(ir.matches("call i32 (ptr) @puts(").count(), 1);
+}
+
+//
+// Generics Tests
+//
+
+#[test]
+fn test_generic_function_instances() {
+    let source = r#"
+        fn identity<T>(x: T) -> T {
+            x
+        }
+
+        fn main() -> i32 {
+            let flag = identity(true);
+            identity(42)
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("define i32 @identity$LT$i32$GT$(i32"), "LLVM IR should contain the i32 instance");
+    assert!(ir.contains("define i1 @identity$LT$bool$GT$(i1"), "LLVM IR should contain the bool instance");
+    assert!(!ir.contains("@identity("), "The generic function itself should not be emitted");
+}
+
+#[test]
+fn test_generic_struct_layout() {
+    let source = r#"
+        struct Pair<T> {
+            first: T,
+            second: T,
+        }
+
+        fn first<T>(p: Pair<T>) -> T {
+            p.first
+        }
+
+        fn main() -> i32 {
+            let p = Pair { first: 1, second: 2 };
+            first(p)
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("%struct.Pair$LT$i32$GT$ = type { i32, i32 }"), "LLVM IR should declare the struct instance");
+    assert!(ir.contains("define i32 @first$LT$i32$GT$(%struct.Pair$LT$i32$GT$"), "LLVM IR should contain the function instance");
+    assert!(ir.contains("extractvalue %struct.Pair$LT$i32$GT$"), "Field access should read the struct value");
+}
+
+#[test]
+fn test_generic_instance_locals_and_matches() {
+    let source = r#"
+        fn pick<T>(first: T, second: T, use_first: bool) -> T {
+            let chosen = if use_first { first } else { second };
+            let result = match use_first {
+                true => chosen,
+                false => second,
+            };
+            result
+        }
+
+        fn main() -> i32 {
+            let x = pick(1.5, 2.5, true);
+            x as i32
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    let start = ir.find("define double @pick$LT$f64$GT$(double %v0, double %v1, i1 %v2)").expect("LLVM IR should contain the f64 instance");
+    let body = &ir[start..start + ir[start..].find("\n}").unwrap()];
+    assert!(!body.contains("i32"), "Locals and values of the f64 instance should not be i32:\n{}", body);
+    assert!(body.contains("= alloca double"), "Locals should have the type argument's type");
+    assert!(body.contains("phi double") && body.contains("[ %v0, %block"), "The if should yield the first parameter");
+    assert!(!body.contains("undef"), "Both branches of the if produce a value");
+    assert!(body.contains("switch i1 %v2"), "The match should switch on the bool scrutinee");
+}
+
+#[test]
+fn test_generic_enum_instance_match() {
+    let source = r#"
+        enum Maybe<T> {
+            Just(T),
+            Nothing,
+        }
+
+        fn unwrap_or<T>(value: Maybe<T>, default: T) -> T {
+            match value {
+                Maybe::Just(inner) => inner,
+                Maybe::Nothing => default,
+            }
+        }
+
+        fn main() -> i32 {
+            let some: Maybe<i64> = Maybe::Just(40i64);
+            let none: Maybe<i64> = Maybe::Nothing;
+            let total = unwrap_or(some, 1i64) + unwrap_or(none, 2i64);
+            total as i32
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("%enum.Maybe$LT$i64$GT$ = type [16 x i8]"), "LLVM IR should lay out the enum instance");
+    let start = ir.find("define i64 @unwrap_or$LT$i64$GT$(%enum.Maybe$LT$i64$GT$ %v0, i64 %v1)").expect("LLVM IR should contain the i64 instance");
+    let body = &ir[start..start + ir[start..].find("\n}").unwrap()];
+    assert!(body.contains("getelementptr inbounds { i32, { i64 } }"), "The payload should be read at its i64 layout:\n{}", body);
+    assert!(body.contains("load i64, ptr %v"), "The payload binding should be an i64");
+    assert!(body.contains("phi i64["), "The match should yield an i64");
+    assert!(!body.contains("i32 %v1"), "The default should not be treated as an i32");
+}
+
+//
+// Trait and Impl Tests
+//
+
+#[test]
+fn test_impl_methods_and_trait_calls() {
+    let source = r#"
+        trait Shape {
+            fn area(self) -> i32;
+
+            fn double_area(self) -> i32 {
+                self.area() * 2
+            }
+        }
+
+        struct Square {
+            side: i32,
+        }
+
+        impl Square {
+            fn new(side: i32) -> Self {
+                Square { side: side }
+            }
+        }
+
+        impl Shape for Square {
+            fn area(self) -> i32 {
+                self.side * self.side
+            }
+        }
+
+        fn total<T: Shape>(shape: T) -> i32 {
+            shape.double_area()
+        }
+
+        fn main() -> i32 {
+            let s = Square::new(3);
+            total(s)
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("define %struct.Square @\"Square::new\"(i32"), "LLVM IR should contain the associated function");
+    assert!(ir.contains("define i32 @\"<Square as Shape>::area\"("), "LLVM IR should contain the trait method");
+    assert!(
+        ir.contains("define i32 @\"<Square as Shape>::double_area\"("),
+        "The default method should be generated for the impl"
+    );
+    assert!(
+        ir.contains("call i32 (%struct.Square) @\"<Square as Shape>::double_area\""),
+        "The call through the bound should resolve to the impl"
+    );
+}
+
+#[test]
+fn test_trait_object_dispatch() {
+    let source = r#"
+        trait Named {
+            fn id(&self) -> i32;
+        }
+
+        trait Shape: Named {
+            fn area(&self) -> i32;
+        }
+
+        struct Square { side: i32 }
+        struct Circle { radius: i32 }
+
+        impl Named for Square {
+            fn id(&self) -> i32 { 1 }
+        }
+
+        impl Shape for Square {
+            fn area(&self) -> i32 { self.side * self.side }
+        }
+
+        impl Named for Circle {
+            fn id(&self) -> i32 { 2 }
+        }
+
+        impl Shape for Circle {
+            fn area(&self) -> i32 { 3 * self.radius * self.radius }
+        }
+
+        fn measure(shape: &dyn Shape) -> i32 {
+            shape.area() + shape.id()
+        }
+
+        fn main() -> i32 {
+            let square = Square { side: 3 };
+            let circle = Circle { radius: 2 };
+            measure(&square) + measure(&circle)
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("%struct.dyn = type { ptr, ptr }"), "Trait object references should be fat pointers");
+    assert!(
+        ir.contains(
+            "@\"vtable.<Square as Shape>\" = private unnamed_addr constant [2 x ptr] \
+             [ptr @\"<Square as Named>::id\", ptr @\"<Square as Shape>::area\"]"
+        ),
+        "The vtable should hold the supertrait's methods first"
+    );
+    assert!(ir.contains("@\"vtable.<Circle as Shape>\""), "Each coerced type should get a vtable");
+    assert!(ir.contains("define i32 @measure(%struct.dyn"), "The trait object should be passed as a fat pointer");
+    assert!(ir.contains("getelementptr ptr, ptr %v2, i32 1"), "`area` should be loaded from its vtable slot");
+    assert!(ir.contains("= call i32 %v"), "Trait object methods should be called indirectly");
+}
+
+#[test]
+fn test_associated_types_are_normalized() {
+    let source = r#"
+        trait Source {
+            type Item;
+            fn take(&self) -> Self::Item;
+        }
+
+        struct Numbers { start: i64 }
+
+        impl Source for Numbers {
+            type Item = i64;
+            fn take(&self) -> Self::Item { self.start }
+        }
+
+        fn take_twice<S>(source: S, extra: S::Item) -> S::Item where S: Source {
+            extra
+        }
+
+        fn main() -> i32 {
+            let numbers = Numbers { start: 4 };
+            let total: i64 = take_twice(numbers, 3);
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("define i64 @\"<Numbers as Source>::take\""), "`Self::Item` should be the impl's type");
+    assert!(
+        ir.contains("define i64 @take_twice$LT$Numbers$GT$(%struct.Numbers %v0, i64 %v1)"),
+        "`S::Item` should be normalized in the instantiation"
+    );
+}
+
+#[test]
+fn test_derived_traits() {
+    let source = r#"
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        enum Shape {
+            Dot,
+            Circle(i64),
+            Rect { w: i32, h: i32 },
+        }
+
+        fn main() -> i32 {
+            let a = Point { x: 1, y: 2 };
+            let b = a.clone();
+            let s = Shape::Circle(5i64);
+            let t = Shape::Rect { w: 2, h: 3 };
+            println!("{:?} {:?} {:?}", a, s, t);
+            if a.eq(&b) && s.lt(&t) && a.hash() == b.hash() {
+                match s.cmp(&t) {
+                    Ordering::Less => 0,
+                    _ => 1,
+                }
+            } else {
+                2
+            }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    for ty in ["Point", "Shape"] {
+        for (trait_name, method) in [
+            ("Clone", "clone"),
+            ("PartialEq", "eq"),
+            ("PartialOrd", "partial_cmp"),
+            ("PartialOrd", "lt"),
+            ("Ord", "cmp"),
+            ("Hash", "hash"),
+            ("Debug", "fmt"),
+        ] {
+            let name = format!("@\"<{} as {}>::{}\"(", ty, trait_name, method);
+            let defined = ir.lines().any(|line| line.starts_with("define ") && line.contains(&name));
+            assert!(defined, "LLVM IR should define {}", name);
+        }
+    }
+    assert!(ir.contains("call ptr (ptr) @\"<Shape as Debug>::fmt\"(ptr"), "Shapes should be formatted by the derived impl");
+    assert!(ir.contains("call i64 (ptr) @\"<Point as Hash>::hash\"(ptr"), "hash should call the derived impl");
+    assert!(ir.contains("xor i64"), "Fields should be mixed into the hash");
+    assert!(ir.contains("load %enum.Shape, %enum.Shape* %v0"), "Enums should be matched through `&self`");
+    assert!(!ir.contains("@Hash::hash") && !ir.contains("@PartialEq::eq"), "No bare trait method should be called");
+}
+
+#[test]
+fn test_derived_comparisons_through_operators() {
+    let source = r#"
+        #[derive(PartialEq, PartialOrd)]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        #[derive(PartialEq, PartialOrd)]
+        enum Shape {
+            Circle(i32),
+            Dot,
+        }
+
+        fn same<T: PartialEq>(a: T, b: T) -> bool {
+            a == b
+        }
+
+        fn main() -> i32 {
+            let p = Point { x: 1, y: 2 };
+            let q = Point { x: 1, y: 3 };
+            println!("{} {} {} {} {} {}", p == q, p != q, p < q, p > q, p <= q, p >= q);
+            println!("{} {} {}", p <= p, Shape::Circle(4) < Shape::Dot, Shape::Dot == Shape::Dot);
+            if same(p, q) { 1 } else { 0 }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let (code, stdout) = test_comp.run().expect("Running failed");
+
+    assert_eq!(stdout, "false true true false true false\ntrue true true\n");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn test_comparing_values_without_an_impl() {
+    let source = r#"
+        struct Point {
+            x: i32,
+        }
+
+        fn main() -> i32 {
+            let p = Point { x: 1 };
+            if p == p { 1 } else { 0 }
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let error = test_comp.compile().expect_err("Point does not implement PartialEq");
+    assert!(error.to_string().contains("the trait `PartialEq` is not implemented for `Point`"), "{}", error);
+}
+
+#[test]
+fn test_derived_clone_of_string_fields() {
+    let source = r#"
+        #[derive(Clone)]
+        struct User {
+            name: str,
+            id: u16,
+        }
+
+        fn main() -> i32 {
+            let user = User { name: `ann`, id: 7 };
+            let copy = user.clone();
+            println!("{} {}", copy.name, copy.id);
+            0
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let (_, stdout) = test_comp.run().expect("Running failed");
+
+    assert_eq!(stdout, "ann 7\n");
+}
+
+#[test]
+fn test_derive_errors_point_at_the_field() {
+    let source = r#"#[derive(Clone)]
+struct Outer {
+    inner: Inner,
+}
+
+struct Inner {}
+
+fn main() -> i32 {
+    0
+}
+"#;
+
+    let test_comp = TestCompilation::new(source);
+    let error = test_comp.compile().expect_err("Inner does not implement Clone").to_string();
+    assert!(error.contains("no method named `clone` found for type `Inner`"), "{}", error);
+    assert!(error.contains(":3:5"), "{}", error);
+    assert!(error.contains("  3 |     inner: Inner,\n   |     ^^^^^ "), "{}", error);
+}
+
+#[test]
+fn test_constants_and_statics() {
+    let source = r#"
+        const fn square(x: i32) -> i32 {
+            x * x
+        }
+
+        const AREA: i32 = square(7);
+        static mut COUNTER: i64 = 10;
+
+        enum Level {
+            Low = 20,
+            Mid,
+        }
+
+        fn bump() -> i64 {
+            COUNTER = COUNTER + 1;
+            COUNTER
+        }
+
+        fn main() -> i32 {
+            let level = Level::Mid;
+            bump();
+            AREA
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("add i32 0, 49"), "constants should be evaluated and inlined");
+    assert!(ir.contains("@COUNTER = internal global i64 10"), "statics should become globals");
+    assert!(ir.contains("getelementptr i8, ptr @COUNTER, i64 0"), "statics should be accessed in place");
+    assert!(ir.contains("store i32 21, ptr"), "implicit discriminants should follow explicit ones");
+}
+
+#[test]
+fn test_casts() {
+    let source = r#"
+        const N: i32 = 2;
+        const SMALL: u8 = 300 as u8;
+
+        fn first(values: [i32; N as usize]) -> i32 { values[0] }
+        fn wide(x: i32) -> i64 { x as i64 }
+        fn narrow(x: i64) -> u8 { x as u8 }
+        fn real(x: u32) -> f64 { x as f64 }
+        fn whole(x: f64) -> i32 { x as i32 }
+        fn single(x: f64) -> f32 { x as f32 }
+
+        fn main() -> i32 {
+            first([SMALL as i32, true as i32])
+        }
+    "#;
+
+    let test_comp = TestCompilation::new(source);
+    let ir = test_comp.compile().expect("Compilation failed");
+
+    assert!(ir.contains("define i32 @first([2 x i32]"), "array lengths should be evaluated through casts");
+    assert!(ir.contains("add i8 0, 44"), "constant casts should truncate");
+    assert!(ir.contains("sext i32 %v0 to i64"));
+    assert!(ir.contains("trunc i64 %v0 to i8"));
+    assert!(ir.contains("uitofp i32 %v0 to double"));
+    assert!(ir.contains("fptosi double %v0 to i32"));
+    assert!(ir.contains("fptrunc double %v0 to float"));
+    assert!(ir.contains("zext i8 %v") && ir.contains("zext i1 %v"), "unsigned values should be zero-extended");
+}
+
 //
 // Edge Cases
 //
@@ -5,12 +5,18 @@
 
 use crate::diagnostic::Diagnostic;
 use crate::severity::Severity;
-use crate::span::Span;
+use crate::span::{FileId, Span};
 use std::collections::HashMap;
 
 impl Diagnostic {
     /// Display this diagnostic with full source code context
     pub fn display_with_context(&self, source: &str, use_colors: bool) -> String {
+        self.display_with_sources(&|_| source, use_colors)
+    }
+
+    /// Display this diagnostic with full source code context, for spans in
+    /// several files: `sources` gives the source of the file a span is in
+    pub fn display_with_sources<'s>(&self, sources: &dyn Fn(Option<&FileId>) -> &'s str, use_colors: bool) -> String {
         let mut output = String::new();
 
         // Print severity and code
@@ -24,14 +30,19 @@ impl Diagnostic {
         // Print source code snippet with context
         if let Some(span) = &self.span {
             if !span.is_dummy() {
-                self.print_source_snippet_with_context(&mut output, source, span, use_colors);
+                self.print_source_snippet_with_context(&mut output, sources(span.lo.file.as_ref()), span, use_colors);
             }
         }
 
-        // Print labels for additional spans
+        // Print labels for additional spans, with the location of those in
+        // another file than the primary span
         let primary_span = self.span.clone();
         for label in &self.labels {
-            if primary_span.as_ref().map_or(false, |ps| label.span != *ps) {
+            if let Some(primary_span) = primary_span.as_ref().filter(|ps| label.span != **ps) {
+                if label.span.lo.file != primary_span.lo.file && !label.span.is_dummy() {
+                    output.push_str(&format!("  ::: {}\n", label.span));
+                }
+                let source = sources(label.span.lo.file.as_ref());
                 self.print_labeled_span(&mut output, &label.span, &label.message, source, use_colors);
             }
         }
@@ -43,13 +54,14 @@ impl Diagnostic {
 
         // Print suggestions
         for suggestion in &self.suggestions {
+            let source = sources(suggestion.span.lo.file.as_ref());
             self.print_suggestion_with_code(&mut output, suggestion, source, use_colors);
         }
 
         // Print related diagnostics
         for related in &self.related {
             output.push_str("\n");
-            output.push_str(&related.display_with_context(source, use_colors));
+            output.push_str(&related.display_with_sources(sources, use_colors));
         }
 
         output
@@ -85,14 +97,14 @@ impl Diagnostic {
 
         // Determine context window (show 1 line before and after)
         let context_lines = 1;
-        let start_line = span.lo.line.saturating_sub(context_lines);
+        let start_line = span.lo.line.saturating_sub(context_lines).max(1);
         let end_line = (span.hi.line + context_lines).min(lines.len());
 
         // Group spans by line for multi-span marking
         let mut line_spans: HashMap<usize, Vec<(Span, Option<&str>)>> = HashMap::new();
         line_spans.insert(span.lo.line, vec![(span.clone(), Some("primary"))]);
 
-        for label in &self.labels {
+        for label in self.labels.iter().filter(|label| label.span.lo.file == span.lo.file) {
             line_spans.entry(label.span.lo.line)
                 .or_insert_with(Vec::new)
                 .push((label.span.clone(), Some(label.message.as_str())));
@@ -4,7 +4,7 @@
 //! Source code span and location types
 
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// A file identifier (can be shared across Locs)
@@ -16,6 +16,11 @@ impl FileId {
     pub fn new(path: PathBuf) -> Self {
         Self(Arc::new(path))
     }
+
+    /// The path of the file
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
 }
 
 impl fmt::Display for FileId {
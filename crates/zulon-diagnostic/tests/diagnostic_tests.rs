@@ -152,6 +152,26 @@ fn test_diagnostic_display() {
     assert!(output.contains("test error"));
 }
 
+#[test]
+fn test_diagnostic_display_on_first_line() {
+    let file = Some(PathBuf::from("test.zl"));
+    let span = Span::new(
+        Loc::new(file.clone(), 1, 10, 9),
+        Loc::new(file, 1, 15, 14),
+    );
+
+    let diagnostic = Diagnostic::error()
+        .message("test error")
+        .span(span)
+        .build();
+
+    let source = "#[derive(Clone)]\nstruct S {}\n";
+    let output = diagnostic.display_with_context(source, false);
+
+    assert!(output.contains("  1 | #[derive(Clone)]"));
+    assert!(!output.contains("  0 | "));
+}
+
 #[test]
 fn test_severity_display() {
     assert_eq!(Severity::Error.name(), "error");
@@ -151,6 +151,11 @@ impl<'a, E: Environment> CaptureAnalyzer<'a, E> {
                 self.walk_expression(operand);
             }
 
+            // Cast - walk the converted value
+            HirExpression::Cast { value, .. } => {
+                self.walk_expression(value);
+            }
+
             // Function call - walk function and arguments
             HirExpression::Call { func, args, .. } => {
                 self.walk_expression(func);
@@ -6,6 +6,8 @@
 //! HIR nodes are typed, desugared representations of AST nodes.
 
 use zulon_parser::ast::Span;
+use zulon_parser::format::FormatSpec;
+use zulon_typeck::ConstValue;
 
 use super::ty::HirTy;
 
@@ -22,12 +24,17 @@ pub struct HirCrate {
 /// Top-level items
 #[derive(Debug, Clone)]
 pub enum HirItem {
-    Function(HirFunction),
+    Function(Box<HirFunction>),
     Struct(HirStruct),
     Enum(HirEnum),
     Trait(HirTrait),
     Impl(HirImpl),
     Mod(HirMod),
+    /// A vtable, added by monomorphization for each type converted to a
+    /// trait object
+    VTable(HirVTable),
+    /// A `static` item; `const` items are inlined where they are used
+    Static(HirStatic),
 }
 
 /// Function definition
@@ -148,19 +155,39 @@ pub enum HirExpression {
         span: Span,
     },
 
+    /// Conversion between primitive types: `value as ty`
+    Cast {
+        value: Box<HirExpression>,
+        ty: HirTy,
+        span: Span,
+    },
+
     /// Function call
     Call {
         func: Box<HirExpression>,
         args: Vec<HirExpression>,
+        /// Type arguments of a generic callee, in the order of its type
+        /// parameters; empty otherwise
+        generic_args: Vec<HirTy>,
         ty: HirTy,
         span: Span,
     },
 
-    /// Method call (desugared to function call)
+    /// Call of a trait method through a type parameter, `x.method(..)` or
+    /// `T::method(..)`
+    ///
+    /// Calls whose impl is known are lowered to [`Call`](Self::Call)s of
+    /// the impl's function; these are replaced by one during
+    /// monomorphization, once `self_ty` is known. For methods, the first
+    /// argument is the receiver, already borrowed or dereferenced to be
+    /// the `self` argument.
     MethodCall {
-        receiver: Box<HirExpression>,
         method_name: String,
+        trait_name: String,
+        self_ty: HirTy,
         args: Vec<HirExpression>,
+        /// Type arguments of the method's own type parameters
+        generic_args: Vec<HirTy>,
         ty: HirTy,
         span: Span,
     },
@@ -287,6 +314,21 @@ pub enum HirExpression {
         ty: HirTy,
         span: Span,
     },
+
+    /// Conversion of a reference to a concrete type into a trait object
+    /// reference, `&dyn Trait`, pairing it with the type's vtable
+    TraitObject {
+        value: Box<HirExpression>,
+        ty: HirTy,
+        span: Span,
+    },
+
+    /// A `static` item, read or assigned in place
+    Static {
+        name: String,
+        ty: HirTy,
+        span: Span,
+    },
 }
 
 /// Template string part
@@ -294,8 +336,16 @@ pub enum HirExpression {
 pub enum HirTemplateStringPart {
     /// Static string literal
     Static(String),
-    /// Interpolated expression
-    Expr(Box<HirExpression>),
+    /// Interpolated expression, formatted according to `spec`
+    ///
+    /// Widths and precisions taken from variables are lowered into `width`
+    /// and `precision`; literal ones stay in the spec.
+    Expr {
+        expr: Box<HirExpression>,
+        spec: FormatSpec,
+        width: Option<Box<HirExpression>>,
+        precision: Option<Box<HirExpression>>,
+    },
 }
 
 impl HirExpression {
@@ -306,6 +356,7 @@ impl HirExpression {
             HirExpression::Variable(_, _, ty, _) => ty,
             HirExpression::BinaryOp { ty, .. } => ty,
             HirExpression::UnaryOp { ty, .. } => ty,
+            HirExpression::Cast { ty, .. } => ty,
             HirExpression::Call { ty, .. } => ty,
             HirExpression::MethodCall { ty, .. } => ty,
             HirExpression::If { ty, .. } => ty,
@@ -326,6 +377,8 @@ impl HirExpression {
             HirExpression::Try(try_block) => &try_block.try_block.ty,
             HirExpression::TemplateString { ty, .. } => ty,
             HirExpression::Await { ty, .. } => ty,  // await returns the Future's Output type
+            HirExpression::TraitObject { ty, .. } => ty,
+            HirExpression::Static { ty, .. } => ty,
         }
     }
 
@@ -336,6 +389,7 @@ impl HirExpression {
             HirExpression::Variable(_, _, _, span) => span,
             HirExpression::BinaryOp { span, .. } => span,
             HirExpression::UnaryOp { span, .. } => span,
+            HirExpression::Cast { span, .. } => span,
             HirExpression::Call { span, .. } => span,
             HirExpression::MethodCall { span, .. } => span,
             HirExpression::If { span, .. } => span,
@@ -358,6 +412,8 @@ impl HirExpression {
             HirExpression::Try(try_block) => &try_block.span,
             HirExpression::TemplateString { span, .. } => span,
             HirExpression::Await { span, .. } => span,
+            HirExpression::TraitObject { span, .. } => span,
+            HirExpression::Static { span, .. } => span,
         }
     }
 }
@@ -366,10 +422,13 @@ impl HirExpression {
 #[derive(Debug, Clone)]
 pub enum HirLiteral {
     Bool(bool),
-    Integer(u64),
+    /// The bit pattern, wide enough for every integer type
+    Integer(u128),
     Float(f64),
     Char(char),
     String(String),
+    /// A byte string, of type `[u8; N]`
+    ByteString(Vec<u8>),
     Unit,
 }
 
@@ -417,10 +476,10 @@ pub enum HirPattern {
     /// Tuple pattern
     Tuple(Vec<HirPattern>, Span),
 
-    /// Struct pattern
+    /// Struct pattern, with a pattern for each field it names
     Struct {
         name: String,
-        fields: Vec<String>,
+        fields: Vec<(String, HirPattern)>,
         ty: HirTy,
         span: Span,
     },
@@ -433,6 +492,19 @@ pub enum HirPattern {
         ty: HirTy,
         span: Span,
     },
+
+    /// Range pattern: `lo..=hi`, or `lo..hi` when not `inclusive`
+    Range {
+        start: HirLiteral,
+        end: HirLiteral,
+        inclusive: bool,
+        ty: HirTy,
+        span: Span,
+    },
+
+    /// Or pattern: matches when any alternative does; each binds the same
+    /// names
+    Or(Vec<HirPattern>, Span),
 }
 
 /// Match arm
@@ -477,6 +549,9 @@ pub struct HirEnum {
 pub struct HirVariant {
     pub name: String,
     pub fields: Vec<HirField>,
+    /// The value identifying the variant, explicit or counted up from the
+    /// previous variant's
+    pub discriminant: i128,
     pub span: Span,
 }
 
@@ -486,6 +561,7 @@ pub struct HirTrait {
     pub id: NodeId,
     pub name: String,
     pub generics: Vec<HirGenericParam>,
+    pub super_traits: Vec<String>,
     pub items: Vec<HirTraitItem>,
     pub span: Span,
 }
@@ -520,10 +596,40 @@ pub struct HirImpl {
     pub generics: Vec<HirGenericParam>,
     pub target_trait: Option<String>,
     pub target_type: HirTy,
+    /// Associated type definitions, in terms of the impl's type parameters
+    pub assoc_types: Vec<(String, HirTy)>,
     pub items: Vec<HirItem>,
     pub span: Span,
 }
 
+/// The vtable of a type's impl of a trait: the functions implementing the
+/// trait's methods, in the order of [`HirCrate::vtable_slots`]
+#[derive(Debug, Clone)]
+pub struct HirVTable {
+    pub name: String,
+    pub trait_name: String,
+    pub self_ty: HirTy,
+    pub methods: Vec<String>,
+    pub span: Span,
+}
+
+/// A `static` item, with the initial value the type checker evaluated
+#[derive(Debug, Clone)]
+pub struct HirStatic {
+    pub name: String,
+    pub ty: HirTy,
+    pub value: ConstValue,
+    pub is_mutable: bool,
+    pub span: Span,
+}
+
+impl HirVTable {
+    /// The name of the vtable of `self_ty`'s impl of `trait_name`
+    pub fn symbol(self_ty: &HirTy, trait_name: &str) -> String {
+        format!("vtable.<{} as {}>", self_ty.display_name(), trait_name)
+    }
+}
+
 /// Module
 #[derive(Debug, Clone)]
 pub struct HirMod {
@@ -589,7 +695,7 @@ impl HirFunction {
             .filter_map(|item| {
                 if let HirItem::Function(func) = item {
                     if func.is_test() {
-                        Some(func)
+                        Some(func.as_ref())
                     } else {
                         None
                     }
@@ -611,6 +717,39 @@ impl HirItem {
             HirItem::Trait(t) => &t.span,
             HirItem::Impl(i) => &i.span,
             HirItem::Mod(m) => &m.span,
+            HirItem::VTable(v) => &v.span,
+            HirItem::Static(s) => &s.span,
+        }
+    }
+}
+
+impl HirCrate {
+    /// The methods callable on a `dyn trait_name`, as the trait declaring
+    /// each and the method's name, in vtable order: those of the
+    /// supertraits first, then the trait's own
+    pub fn vtable_slots(&self, trait_name: &str) -> Vec<(String, String)> {
+        fn collect(items: &[HirItem], trait_name: &str, slots: &mut Vec<(String, String)>) {
+            let Some(trait_def) = items.iter().find_map(|item| match item {
+                HirItem::Trait(trait_def) if trait_def.name == trait_name => Some(trait_def),
+                _ => None,
+            }) else {
+                return;
+            };
+            for super_trait in &trait_def.super_traits {
+                collect(items, super_trait, slots);
+            }
+            for item in &trait_def.items {
+                if let HirTraitItem::Method { name, .. } = item {
+                    let slot = (trait_def.name.clone(), name.clone());
+                    if !slots.contains(&slot) {
+                        slots.push(slot);
+                    }
+                }
+            }
         }
+
+        let mut slots = Vec::new();
+        collect(&self.items, trait_name, &mut slots);
+        slots
     }
 }
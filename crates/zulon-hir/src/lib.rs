@@ -32,6 +32,7 @@ pub mod error;
 pub mod capture;
 // pub mod lower;  // TEMPORARILY DISABLED - has compilation errors, using simple_lower instead
 pub mod simple_lower;
+pub mod mono;
 pub mod test_discovery;
 pub mod test_main_gen;
 
@@ -41,5 +42,6 @@ pub use error::{LoweringError, Result};
 pub use capture::{CaptureAnalyzer, CaptureAnalysis, analyze_captures, Environment, SimpleEnvironment};
 // pub use lower::{LoweringContext, lower_ast};  // TEMPORARILY DISABLED
 pub use simple_lower::{SimpleLoweringContext, lower_ast_simple};
+pub use mono::{monomorphize, mangle};
 pub use test_discovery::{discover_tests, DiscoveredTest};
 pub use test_main_gen::{generate_test_main_source, generate_test_file};
@@ -71,7 +71,7 @@ impl LoweringContext {
     fn lower_item(&mut self, item: &ast::Item) -> Result<HirItem> {
         match &item.kind {
             ast::ItemKind::Function(func) => {
-                Ok(HirItem::Function(self.lower_function(func)?))
+                Ok(HirItem::Function(Box::new(self.lower_function(func)?)))
             }
 
             ast::ItemKind::Struct(struct_def) => {
@@ -497,10 +497,15 @@ impl LoweringContext {
     fn lower_literal(&mut self, lit: &ast::Literal) -> Result<HirLiteral> {
         match lit {
             ast::Literal::Bool(b) => Ok(HirLiteral::Bool(*b)),
-            ast::Literal::Int(n) => Ok(HirLiteral::Integer(*n as u64)),
-            ast::Literal::Float(f) => Ok(HirLiteral::Float(*f)),
+            ast::Literal::Int(n, _) => Ok(HirLiteral::Integer(*n)),
+            ast::Literal::Float(f, _) => Ok(HirLiteral::Float(*f)),
             ast::Literal::Char(c) => Ok(HirLiteral::Char(*c)),
             ast::Literal::String(s) => Ok(HirLiteral::String(s.clone())),
+            ast::Literal::Byte(b) => Ok(HirLiteral::Integer(*b as u128)),
+            ast::Literal::ByteString(_) => Err(LoweringError::UnsupportedFeature {
+                feature: "byte string literal".to_string(),
+                span: dummy_span(),
+            }),
             ast::Literal::Null => Ok(HirLiteral::Unit),
         }
     }
@@ -688,6 +693,7 @@ impl LoweringContext {
             id: self.alloc_id(),
             name: trait_def.name.name.clone(),
             generics: Vec::new(),
+            super_traits: Vec::new(),
             items,
             span: trait_def.name.span.clone(),
         })
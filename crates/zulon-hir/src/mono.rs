@@ -0,0 +1,991 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Monomorphization
+//!
+//! Generic functions, structs and enums are copied once for every list of
+//! type arguments they are used with, starting from the non-generic
+//! functions of the crate. Each copy is named by mangling its type
+//! arguments into the item name, so `identity<i32>` becomes
+//! `identity$LT$i32$GT$`. The generic originals are dropped: MIR and the
+//! backends only ever see concrete items.
+//!
+//! The methods of impl blocks become top-level functions, generic over the
+//! impl's type parameters followed by their own. A call of a trait method
+//! through a type parameter is pointed at the function of the impl whose
+//! type matches once the parameter is substituted, and an associated type
+//! of a type parameter, `<I as Iterator>::Item`, becomes the type that
+//! impl defines for it. Primitive scalar types
+//! implement `Clone`, `PartialEq`, `PartialOrd` and `Ord` without impl
+//! blocks; calls of those methods become the operations they stand for.
+//!
+//! Calls of trait methods on trait objects are left in place, to be
+//! dispatched through a vtable at run time. Each conversion of a reference
+//! to a trait object adds the vtable of the referenced type's impl of the
+//! trait, listing the functions for the slots of
+//! [`HirCrate::vtable_slots`].
+
+use std::collections::{HashMap, HashSet};
+
+use zulon_parser::ast::Span;
+use zulon_parser::FormatSpec;
+
+use super::error::{LoweringError, Result};
+use super::hir::*;
+use super::ty::HirTy;
+
+/// Upper bound on the number of instances, to stop polymorphic recursion
+/// such as `fn f<T>(x: T) { f((x, x)) }`
+const INSTANCE_LIMIT: usize = 4096;
+
+/// Replace every generic item of `krate` by its concrete instances
+pub fn monomorphize(krate: &HirCrate) -> Result<HirCrate> {
+    let mut mono = Monomorphizer::new(krate);
+    let mut items = Vec::new();
+
+    for item in &krate.items {
+        match item {
+            HirItem::Function(func) if func.generics.is_empty() => {
+                items.push(HirItem::Function(Box::new(mono.instantiate_function(func, &HashMap::new())?)));
+            }
+            HirItem::Struct(def) if def.generics.is_empty() => {
+                items.push(HirItem::Struct(mono.concrete_struct(def, &HashMap::new())?));
+            }
+            HirItem::Enum(def) if def.generics.is_empty() => {
+                items.push(HirItem::Enum(mono.concrete_enum(def, &HashMap::new())?));
+            }
+            HirItem::Impl(imp) => {
+                for item in &imp.items {
+                    if let HirItem::Function(func) = item {
+                        if func.generics.is_empty() {
+                            items.push(HirItem::Function(Box::new(mono.instantiate_function(func, &HashMap::new())?)));
+                        }
+                    }
+                }
+            }
+            HirItem::Function(_) | HirItem::Struct(_) | HirItem::Enum(_) => {}
+            other => items.push(other.clone()),
+        }
+    }
+
+    while let Some((name, args)) = mono.pending.pop() {
+        let func = mono.functions[name.as_str()];
+        let params = bind_params(&func.generics, &args);
+        let mut instance = mono.instantiate_function(func, &params)?;
+        instance.name = mangle(&name, &args);
+        instance.generics.clear();
+        items.push(HirItem::Function(Box::new(instance)));
+    }
+    items.append(&mut mono.adt_instances);
+    items.append(&mut mono.vtables);
+
+    Ok(HirCrate {
+        items,
+        span: krate.span,
+    })
+}
+
+/// The symbol name of `name` instantiated with `args`
+///
+/// The display form `name<A, B>` is escaped so it is a valid identifier:
+/// `<`, `>`, `,`, `&` and `*` become `$LT$`, `$GT$`, `$C$`, `$RF$` and
+/// `$BP$`, spaces are dropped and any other punctuation becomes `$u<hex>$`.
+pub fn mangle(name: &str, args: &[HirTy]) -> String {
+    if args.is_empty() {
+        return name.to_string();
+    }
+
+    let args = args.iter().map(HirTy::display_name).collect::<Vec<_>>().join(",");
+    let mut mangled = name.to_string();
+    for c in format!("<{}>", args).chars() {
+        match c {
+            '<' => mangled.push_str("$LT$"),
+            '>' => mangled.push_str("$GT$"),
+            ',' => mangled.push_str("$C$"),
+            '&' => mangled.push_str("$RF$"),
+            '*' => mangled.push_str("$BP$"),
+            ' ' => {}
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.' => mangled.push(c),
+            c => mangled.push_str(&format!("$u{:x}$", c as u32)),
+        }
+    }
+    mangled
+}
+
+/// Map each type parameter to its argument
+fn bind_params(generics: &[HirGenericParam], args: &[HirTy]) -> HashMap<String, HirTy> {
+    generics.iter()
+        .map(|param| param.name.clone())
+        .zip(args.iter().cloned())
+        .collect()
+}
+
+/// Whether `ty` is an instance of `pattern`, binding the type parameters
+/// of `pattern` to make them equal
+fn match_ty(pattern: &HirTy, ty: &HirTy, bindings: &mut HashMap<String, HirTy>) -> bool {
+    let match_all = |patterns: &[HirTy], tys: &[HirTy], bindings: &mut HashMap<String, HirTy>| {
+        patterns.len() == tys.len() && patterns.iter().zip(tys).all(|(pattern, ty)| match_ty(pattern, ty, bindings))
+    };
+    match (pattern, ty) {
+        (HirTy::Param(name), _) => match bindings.get(name) {
+            Some(bound) => bound == ty,
+            None => {
+                bindings.insert(name.clone(), ty.clone());
+                true
+            }
+        },
+        (HirTy::Struct { name, generics }, HirTy::Struct { name: ty_name, generics: ty_generics })
+        | (HirTy::Enum { name, generics }, HirTy::Enum { name: ty_name, generics: ty_generics }) => {
+            name == ty_name && match_all(generics, ty_generics, bindings)
+        }
+        (HirTy::Ref { inner, mutable }, HirTy::Ref { inner: ty_inner, mutable: ty_mutable })
+        | (HirTy::Ptr { inner, mutable }, HirTy::Ptr { inner: ty_inner, mutable: ty_mutable }) => {
+            mutable == ty_mutable && match_ty(inner, ty_inner, bindings)
+        }
+        (HirTy::Array { inner, len }, HirTy::Array { inner: ty_inner, len: ty_len }) => {
+            len == ty_len && match_ty(inner, ty_inner, bindings)
+        }
+        (HirTy::Slice(inner), HirTy::Slice(ty_inner)) | (HirTy::Optional(inner), HirTy::Optional(ty_inner)) => {
+            match_ty(inner, ty_inner, bindings)
+        }
+        (HirTy::Tuple(tys), HirTy::Tuple(ty_tys)) => match_all(tys, ty_tys, bindings),
+        (HirTy::Function { params, return_type }, HirTy::Function { params: ty_params, return_type: ty_return }) => {
+            match_all(params, ty_params, bindings) && match_ty(return_type, ty_return, bindings)
+        }
+        _ => pattern == ty,
+    }
+}
+
+/// A call of a method of a builtin impl for the primitive type `self_ty`,
+/// as the operation the method performs on the values `args` point to
+///
+/// Returns `None`, leaving `args` alone, for any other call.
+fn builtin_method_call(
+    trait_name: &str,
+    method: &str,
+    self_ty: &HirTy,
+    args: &mut Vec<HirExpression>,
+    ty: &HirTy,
+    span: Span,
+) -> Option<HirExpression> {
+    if method == "fmt" {
+        return builtin_fmt_call(trait_name, self_ty, args, span);
+    }
+    let is_scalar = self_ty.is_numeric() || matches!(self_ty, HirTy::Bool | HirTy::Char);
+    let arity = match method {
+        "clone" | "hash" => 1,
+        "eq" | "lt" | "cmp" | "partial_cmp" => 2,
+        _ => return None,
+    };
+    if !is_scalar || args.len() != arity {
+        return None;
+    }
+
+    let mut operands = std::mem::take(args).into_iter().map(|arg| HirExpression::UnaryOp {
+        op: HirUnaryOp::Deref,
+        operand: Box::new(arg),
+        ty: self_ty.clone(),
+        span,
+    });
+    let left = operands.next()?;
+    match method {
+        "clone" => return Some(left),
+        // A scalar hashes to its value
+        "hash" => {
+            return Some(HirExpression::Cast {
+                value: Box::new(left),
+                ty: HirTy::U64,
+                span,
+            });
+        }
+        _ => {}
+    }
+    let right = operands.next()?;
+    let compare = |op, left: &HirExpression, right: &HirExpression| HirExpression::BinaryOp {
+        op,
+        left: Box::new(left.clone()),
+        right: Box::new(right.clone()),
+        ty: HirTy::Bool,
+        span,
+    };
+    match method {
+        "eq" => return Some(compare(HirBinOp::Eq, &left, &right)),
+        "lt" => return Some(compare(HirBinOp::Less, &left, &right)),
+        _ => {}
+    }
+
+    // { let l = *a; let r = *b; if l < r { Less } else if l > r { Greater } else { Equal } }
+    let ordering_ty = HirTy::Enum { name: "Ordering".to_string(), generics: Vec::new() };
+    let block = |statements, trailing_expr: HirExpression| HirBlock {
+        id: 0,
+        statements,
+        ty: trailing_expr.ty().clone(),
+        trailing_expr: Some(trailing_expr),
+        span,
+    };
+    let ordering = |variant: &str| {
+        HirExpression::Variable(format!("Ordering::{}", variant), 0, ordering_ty.clone(), span)
+    };
+    let local = |name: &str, init| HirStatement::Local(HirLocal {
+        id: 0,
+        name: name.to_string(),
+        ty: self_ty.clone(),
+        init: Some(init),
+        span,
+    });
+    let (l, r) = (
+        HirExpression::Variable("__cmp_left".to_string(), 0, self_ty.clone(), span),
+        HirExpression::Variable("__cmp_right".to_string(), 0, self_ty.clone(), span),
+    );
+    let greater_or_equal = HirExpression::If {
+        condition: Box::new(compare(HirBinOp::Greater, &l, &r)),
+        then_block: Box::new(block(Vec::new(), ordering("Greater"))),
+        else_block: Some(Box::new(block(Vec::new(), ordering("Equal")))),
+        ty: ordering_ty.clone(),
+        span,
+    };
+    let mut result = HirExpression::If {
+        condition: Box::new(compare(HirBinOp::Less, &l, &r)),
+        then_block: Box::new(block(Vec::new(), ordering("Less"))),
+        else_block: Some(Box::new(block(Vec::new(), greater_or_equal))),
+        ty: ordering_ty.clone(),
+        span,
+    };
+    if method == "partial_cmp" {
+        let some_ty = HirTy::Function { params: vec![ordering_ty], return_type: Box::new(ty.clone()) };
+        result = HirExpression::Call {
+            func: Box::new(HirExpression::Variable("Option::Some".to_string(), 0, some_ty, span)),
+            args: vec![result],
+            generic_args: Vec::new(),
+            ty: ty.clone(),
+            span,
+        };
+    }
+    let statements = vec![local("__cmp_left", left), local("__cmp_right", right)];
+    Some(HirExpression::Block(Box::new(block(statements, result))))
+}
+
+/// A call of `fmt` of the builtin `Display` or `Debug` impl for `self_ty`,
+/// as a template string formatting the value `args` points to
+fn builtin_fmt_call(trait_name: &str, self_ty: &HirTy, args: &mut Vec<HirExpression>, span: Span) -> Option<HirExpression> {
+    let spec = match trait_name {
+        "Display" => FormatSpec::default(),
+        "Debug" => FormatSpec::debug(),
+        _ => return None,
+    };
+    if !self_ty.is_formatted_by_runtime() || args.len() != 1 {
+        return None;
+    }
+    let value = HirExpression::UnaryOp {
+        op: HirUnaryOp::Deref,
+        operand: Box::new(args.pop()?),
+        ty: self_ty.clone(),
+        span,
+    };
+    Some(HirExpression::TemplateString {
+        parts: vec![HirTemplateStringPart::Expr {
+            expr: Box::new(value),
+            spec,
+            width: None,
+            precision: None,
+        }],
+        ty: HirTy::String,
+        span,
+    })
+}
+
+struct Monomorphizer<'a> {
+    krate: &'a HirCrate,
+    /// Generic functions by name, including the methods of generic impls
+    functions: HashMap<&'a str, &'a HirFunction>,
+    /// Trait impls, for resolving calls of trait methods
+    trait_impls: Vec<&'a HirImpl>,
+    /// Generic structs and enums by name
+    adts: HashMap<&'a str, &'a HirItem>,
+    /// Mangled names of every function and type instance created so far
+    instances: HashSet<String>,
+    /// Function instances still to be generated, with their type
+    /// arguments
+    ///
+    /// Type arguments here and in the parameter maps below name generic
+    /// structs and enums unmangled, as `Pair<i32>`, so that they can be
+    /// matched against the types of impls.
+    pending: Vec<(String, Vec<HirTy>)>,
+    /// Generated struct and enum instances
+    adt_instances: Vec<HirItem>,
+    /// Generated vtables
+    vtables: Vec<HirItem>,
+}
+
+impl<'a> Monomorphizer<'a> {
+    fn new(krate: &'a HirCrate) -> Self {
+        let mut functions = HashMap::new();
+        let mut trait_impls = Vec::new();
+        let mut adts = HashMap::new();
+        for item in &krate.items {
+            match item {
+                HirItem::Function(func) if !func.generics.is_empty() => {
+                    functions.insert(func.name.as_str(), func.as_ref());
+                }
+                HirItem::Impl(imp) => {
+                    for item in &imp.items {
+                        if let HirItem::Function(func) = item {
+                            if !func.generics.is_empty() {
+                                functions.insert(func.name.as_str(), func.as_ref());
+                            }
+                        }
+                    }
+                    if imp.target_trait.is_some() {
+                        trait_impls.push(imp);
+                    }
+                }
+                HirItem::Struct(def) if !def.generics.is_empty() => {
+                    adts.insert(def.name.as_str(), item);
+                }
+                HirItem::Enum(def) if !def.generics.is_empty() => {
+                    adts.insert(def.name.as_str(), item);
+                }
+                _ => {}
+            }
+        }
+
+        Monomorphizer {
+            krate,
+            functions,
+            trait_impls,
+            adts,
+            instances: HashSet::new(),
+            pending: Vec::new(),
+            adt_instances: Vec::new(),
+            vtables: Vec::new(),
+        }
+    }
+
+    /// Substitute `params` into `ty`, leaving struct and enum names as
+    /// they are, and replace each projection whose base then matches an
+    /// impl of its trait by the impl's associated type
+    fn subst_params(&self, ty: &HirTy, params: &HashMap<String, HirTy>) -> HirTy {
+        let subst_all = |tys: &[HirTy]| tys.iter().map(|ty| self.subst_params(ty, params)).collect();
+        match ty {
+            HirTy::Param(name) => params.get(name).cloned().unwrap_or_else(|| ty.clone()),
+            HirTy::Projection { base, trait_name, name } => {
+                let base = self.subst_params(base, params);
+                match self.assoc_type(&base, trait_name, name) {
+                    Some(ty) => ty,
+                    None => HirTy::Projection { base: Box::new(base), trait_name: trait_name.clone(), name: name.clone() },
+                }
+            }
+            HirTy::Ref { inner, mutable } => HirTy::Ref { inner: Box::new(self.subst_params(inner, params)), mutable: *mutable },
+            HirTy::Ptr { inner, mutable } => HirTy::Ptr { inner: Box::new(self.subst_params(inner, params)), mutable: *mutable },
+            HirTy::Array { inner, len } => HirTy::Array { inner: Box::new(self.subst_params(inner, params)), len: *len },
+            HirTy::Slice(inner) => HirTy::Slice(Box::new(self.subst_params(inner, params))),
+            HirTy::Optional(inner) => HirTy::Optional(Box::new(self.subst_params(inner, params))),
+            HirTy::Tuple(tys) => HirTy::Tuple(subst_all(tys)),
+            HirTy::Function { params: fn_params, return_type } => HirTy::Function {
+                params: subst_all(fn_params),
+                return_type: Box::new(self.subst_params(return_type, params)),
+            },
+            HirTy::Struct { name, generics } => HirTy::Struct { name: name.clone(), generics: subst_all(generics) },
+            HirTy::Enum { name, generics } => HirTy::Enum { name: name.clone(), generics: subst_all(generics) },
+            _ => ty.clone(),
+        }
+    }
+
+    /// The associated type `name` of the impl of `trait_name` for `base`,
+    /// in terms of the type arguments `base` gives the impl
+    fn assoc_type(&self, base: &HirTy, trait_name: &str, name: &str) -> Option<HirTy> {
+        self.trait_impls.iter().find_map(|imp| {
+            let mut bindings = HashMap::new();
+            if imp.target_trait.as_deref() != Some(trait_name) || !match_ty(&imp.target_type, base, &mut bindings) {
+                return None;
+            }
+            let (_, ty) = imp.assoc_types.iter().find(|(assoc, _)| assoc == name)?;
+            Some(self.subst_params(ty, &bindings))
+        })
+    }
+
+    /// Record a new instance, failing once there are too many
+    fn record_instance(&mut self, mangled: &str, span: Span) -> Result<bool> {
+        if self.instances.contains(mangled) {
+            return Ok(false);
+        }
+        if self.instances.len() >= INSTANCE_LIMIT {
+            return Err(LoweringError::InvalidConstruction {
+                message: format!("reached the instantiation limit while instantiating `{}`", mangled),
+                span,
+            });
+        }
+        self.instances.insert(mangled.to_string());
+        Ok(true)
+    }
+
+    /// Substitute `params` into `ty`, replacing generic structs and enums
+    /// by their instances
+    fn concrete_ty(&mut self, ty: &HirTy, params: &HashMap<String, HirTy>, span: Span) -> Result<HirTy> {
+        Ok(match ty {
+            HirTy::Param(name) => match params.get(name) {
+                Some(ty) => self.concrete_ty(ty, &HashMap::new(), span)?,
+                None => {
+                    return Err(LoweringError::InvalidConstruction {
+                        message: format!("type parameter `{}` is not bound", name),
+                        span,
+                    });
+                }
+            },
+            HirTy::Projection { .. } => match self.subst_params(ty, params) {
+                HirTy::Projection { base, trait_name, name } => {
+                    return Err(LoweringError::InvalidConstruction {
+                        message: format!("no impl of `{}` for `{}` defines `{}`", trait_name, base.display_name(), name),
+                        span,
+                    });
+                }
+                ty => self.concrete_ty(&ty, &HashMap::new(), span)?,
+            },
+            HirTy::Ref { inner, mutable } => HirTy::Ref {
+                inner: Box::new(self.concrete_ty(inner, params, span)?),
+                mutable: *mutable,
+            },
+            HirTy::Ptr { inner, mutable } => HirTy::Ptr {
+                inner: Box::new(self.concrete_ty(inner, params, span)?),
+                mutable: *mutable,
+            },
+            HirTy::Array { inner, len } => HirTy::Array {
+                inner: Box::new(self.concrete_ty(inner, params, span)?),
+                len: *len,
+            },
+            HirTy::Slice(inner) => HirTy::Slice(Box::new(self.concrete_ty(inner, params, span)?)),
+            HirTy::Optional(inner) => HirTy::Optional(Box::new(self.concrete_ty(inner, params, span)?)),
+            HirTy::Tuple(tys) => HirTy::Tuple(self.concrete_tys(tys, params, span)?),
+            HirTy::Function { params: fn_params, return_type } => HirTy::Function {
+                params: self.concrete_tys(fn_params, params, span)?,
+                return_type: Box::new(self.concrete_ty(return_type, params, span)?),
+            },
+            HirTy::Struct { name, generics } => {
+                let generics = self.concrete_tys(generics, params, span)?;
+                HirTy::Struct {
+                    name: self.instantiate_adt(name, &generics, span)?,
+                    generics: Vec::new(),
+                }
+            }
+            HirTy::Enum { name, generics } => {
+                let generics = self.concrete_tys(generics, params, span)?;
+                HirTy::Enum {
+                    name: self.instantiate_adt(name, &generics, span)?,
+                    generics: Vec::new(),
+                }
+            }
+            _ => ty.clone(),
+        })
+    }
+
+    fn concrete_tys(&mut self, tys: &[HirTy], params: &HashMap<String, HirTy>, span: Span) -> Result<Vec<HirTy>> {
+        tys.iter().map(|ty| self.concrete_ty(ty, params, span)).collect()
+    }
+
+    /// The name of the instance of struct or enum `name` for `args`,
+    /// generating the instance on first use
+    fn instantiate_adt(&mut self, name: &str, args: &[HirTy], span: Span) -> Result<String> {
+        let Some(&item) = self.adts.get(name) else {
+            return Ok(name.to_string());
+        };
+        let mangled = mangle(name, args);
+        if !self.record_instance(&mangled, span)? {
+            return Ok(mangled);
+        }
+
+        let instance = match item {
+            HirItem::Struct(def) => {
+                let mut instance = self.concrete_struct(def, &bind_params(&def.generics, args))?;
+                instance.name = mangled.clone();
+                instance.generics.clear();
+                HirItem::Struct(instance)
+            }
+            HirItem::Enum(def) => {
+                let mut instance = self.concrete_enum(def, &bind_params(&def.generics, args))?;
+                instance.name = mangled.clone();
+                instance.generics.clear();
+                HirItem::Enum(instance)
+            }
+            _ => unreachable!("only structs and enums are recorded as generic types"),
+        };
+        self.adt_instances.push(instance);
+        Ok(mangled)
+    }
+
+    fn concrete_fields(&mut self, fields: &[HirField], params: &HashMap<String, HirTy>) -> Result<Vec<HirField>> {
+        fields.iter()
+            .map(|field| {
+                Ok(HirField {
+                    ty: self.concrete_ty(&field.ty, params, field.span)?,
+                    ..field.clone()
+                })
+            })
+            .collect()
+    }
+
+    fn concrete_struct(&mut self, def: &HirStruct, params: &HashMap<String, HirTy>) -> Result<HirStruct> {
+        Ok(HirStruct {
+            fields: self.concrete_fields(&def.fields, params)?,
+            ..def.clone()
+        })
+    }
+
+    fn concrete_enum(&mut self, def: &HirEnum, params: &HashMap<String, HirTy>) -> Result<HirEnum> {
+        let variants = def.variants.iter()
+            .map(|variant| {
+                Ok(HirVariant {
+                    fields: self.concrete_fields(&variant.fields, params)?,
+                    ..variant.clone()
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(HirEnum {
+            variants,
+            ..def.clone()
+        })
+    }
+
+    /// Copy `func` with `params` substituted throughout its signature and body
+    fn instantiate_function(&mut self, func: &HirFunction, params: &HashMap<String, HirTy>) -> Result<HirFunction> {
+        let mut instance = func.clone();
+        for param in &mut instance.params {
+            param.ty = self.concrete_ty(&param.ty, params, param.span)?;
+        }
+        instance.return_type = self.concrete_ty(&func.return_type, params, func.span)?;
+        if let Some(error_type) = &func.error_type {
+            instance.error_type = Some(self.concrete_ty(error_type, params, func.span)?);
+        }
+        self.rewrite_block(&mut instance.body, params)?;
+        Ok(instance)
+    }
+
+    /// The name of the instance of the generic function `callee` for
+    /// `args`, queueing the instance on first use
+    fn instantiate_callee(&mut self, callee: &HirFunction, args: Vec<HirTy>, span: Span) -> Result<String> {
+        let concrete_args = self.concrete_tys(&args, &HashMap::new(), span)?;
+        let mangled = mangle(&callee.name, &concrete_args);
+        if self.record_instance(&mangled, span)? {
+            self.pending.push((callee.name.clone(), args));
+        }
+        Ok(mangled)
+    }
+
+    /// The function implementing `method` of `trait_name` for `self_ty`,
+    /// with the type arguments of its impl
+    fn resolve_trait_method(
+        &self,
+        trait_name: &str,
+        self_ty: &HirTy,
+        method: &str,
+        span: Span,
+    ) -> Result<(&'a HirFunction, Vec<HirTy>)> {
+        let suffix = format!("::{}", method);
+        for imp in &self.trait_impls {
+            let mut bindings = HashMap::new();
+            if imp.target_trait.as_deref() != Some(trait_name) || !match_ty(&imp.target_type, self_ty, &mut bindings) {
+                continue;
+            }
+            let function = imp.items.iter().find_map(|item| match item {
+                HirItem::Function(func) if func.name.ends_with(&suffix) => Some(func),
+                _ => None,
+            });
+            let args = imp.generics.iter().map(|param| bindings.get(&param.name).cloned()).collect();
+            if let (Some(function), Some(args)) = (function, args) {
+                return Ok((function, args));
+            }
+        }
+        Err(LoweringError::InvalidConstruction {
+            message: format!("no impl of `{}::{}` for `{}`", trait_name, method, self_ty.display_name()),
+            span,
+        })
+    }
+
+    /// Generate the vtable of `self_ty`'s impl of `trait_name` on first
+    /// use, instantiating the generic functions it points at
+    fn instantiate_vtable(&mut self, trait_name: &str, self_ty: &HirTy, span: Span) -> Result<()> {
+        let concrete = self.concrete_ty(self_ty, &HashMap::new(), span)?;
+        let name = HirVTable::symbol(&concrete, trait_name);
+        if !self.record_instance(&name, span)? {
+            return Ok(());
+        }
+
+        let mut methods = Vec::new();
+        for (declaring_trait, method) in self.krate.vtable_slots(trait_name) {
+            let (callee, args) = self.resolve_trait_method(&declaring_trait, self_ty, &method, span)?;
+            methods.push(if callee.generics.is_empty() {
+                callee.name.clone()
+            } else {
+                self.instantiate_callee(callee, args, span)?
+            });
+        }
+        self.vtables.push(HirItem::VTable(HirVTable {
+            name,
+            trait_name: trait_name.to_string(),
+            self_ty: concrete,
+            methods,
+            span,
+        }));
+        Ok(())
+    }
+
+    fn rewrite_block(&mut self, block: &mut HirBlock, params: &HashMap<String, HirTy>) -> Result<()> {
+        for stmt in &mut block.statements {
+            self.rewrite_statement(stmt, params)?;
+        }
+        if let Some(expr) = &mut block.trailing_expr {
+            self.rewrite_expr(expr, params)?;
+        }
+        block.ty = self.concrete_ty(&block.ty, params, block.span)?;
+        Ok(())
+    }
+
+    fn rewrite_statement(&mut self, stmt: &mut HirStatement, params: &HashMap<String, HirTy>) -> Result<()> {
+        match stmt {
+            HirStatement::Local(local) => {
+                local.ty = self.concrete_ty(&local.ty, params, local.span)?;
+                if let Some(init) = &mut local.init {
+                    self.rewrite_expr(init, params)?;
+                }
+            }
+            HirStatement::Expression(expr) | HirStatement::Semi(expr) => self.rewrite_expr(expr, params)?,
+            HirStatement::Defer(stmt) => self.rewrite_statement(stmt, params)?,
+            HirStatement::Item(_) => {}
+        }
+        Ok(())
+    }
+
+    fn rewrite_pattern(&mut self, pattern: &mut HirPattern, params: &HashMap<String, HirTy>) -> Result<()> {
+        match pattern {
+            HirPattern::Binding(_, ty, span) => *ty = self.concrete_ty(ty, params, *span)?,
+            HirPattern::Tuple(patterns, _) => {
+                for pattern in patterns {
+                    self.rewrite_pattern(pattern, params)?;
+                }
+            }
+            HirPattern::Struct { fields, ty, span, .. } => {
+                *ty = self.concrete_ty(ty, params, *span)?;
+                for (_, pattern) in fields {
+                    self.rewrite_pattern(pattern, params)?;
+                }
+            }
+            HirPattern::Range { ty, span, .. } => *ty = self.concrete_ty(ty, params, *span)?,
+            HirPattern::Or(patterns, _) => {
+                for pattern in patterns {
+                    self.rewrite_pattern(pattern, params)?;
+                }
+            }
+            HirPattern::EnumVariant { inner, ty, span, .. } => {
+                *ty = self.concrete_ty(ty, params, *span)?;
+                if let Some(inner) = inner {
+                    self.rewrite_pattern(inner, params)?;
+                }
+            }
+            HirPattern::Wildcard(_) | HirPattern::Literal(..) => {}
+        }
+        Ok(())
+    }
+
+    fn rewrite_exprs(&mut self, exprs: &mut [HirExpression], params: &HashMap<String, HirTy>) -> Result<()> {
+        for expr in exprs {
+            self.rewrite_expr(expr, params)?;
+        }
+        Ok(())
+    }
+
+    /// Substitute `params` into the types of `expr`, and point calls of
+    /// generic functions at the instance for their type arguments
+    fn rewrite_expr(&mut self, expr: &mut HirExpression, params: &HashMap<String, HirTy>) -> Result<()> {
+        let span = *expr.span();
+        match expr {
+            HirExpression::Literal(_, _, ty, _) | HirExpression::Variable(_, _, ty, _) => {
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::BinaryOp { left, right, ty, .. } => {
+                self.rewrite_expr(left, params)?;
+                self.rewrite_expr(right, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::UnaryOp { operand, ty, .. } => {
+                self.rewrite_expr(operand, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Cast { value, ty, .. } => {
+                self.rewrite_expr(value, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Call { func, args, generic_args, ty, .. } => {
+                self.rewrite_exprs(args, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+                let generic_callee = match func.as_ref() {
+                    HirExpression::Variable(name, ..) => self.functions.get(name.as_str()).copied(),
+                    _ => None,
+                };
+                match generic_callee {
+                    Some(callee) => {
+                        if generic_args.len() != callee.generics.len() {
+                            return Err(LoweringError::InvalidConstruction {
+                                message: format!("cannot infer the type arguments of `{}`", callee.name),
+                                span,
+                            });
+                        }
+                        let args = generic_args.iter().map(|ty| self.subst_params(ty, params)).collect();
+                        if let HirExpression::Variable(name, ..) = func.as_mut() {
+                            *name = self.instantiate_callee(callee, args, span)?;
+                        }
+                        generic_args.clear();
+                    }
+                    None => self.rewrite_expr(func, params)?,
+                }
+            }
+            HirExpression::MethodCall { method_name, trait_name, self_ty, args, generic_args, ty, .. } => {
+                self.rewrite_exprs(args, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+
+                let self_ty = self.subst_params(self_ty, params);
+                if let HirTy::TraitObject(_) = self_ty {
+                    return Ok(());
+                }
+                if let Some(call) = builtin_method_call(trait_name, method_name, &self_ty, args, ty, span) {
+                    *expr = call;
+                    return Ok(());
+                }
+                let (callee, mut callee_args) = self.resolve_trait_method(trait_name, &self_ty, method_name, span)?;
+                callee_args.extend(generic_args.iter().map(|ty| self.subst_params(ty, params)));
+                let name = if callee.generics.is_empty() {
+                    callee.name.clone()
+                } else {
+                    self.instantiate_callee(callee, callee_args, span)?
+                };
+
+                let func_ty = HirTy::Function {
+                    params: args.iter().map(|arg| arg.ty().clone()).collect(),
+                    return_type: Box::new(ty.clone()),
+                };
+                *expr = HirExpression::Call {
+                    func: Box::new(HirExpression::Variable(name, 0, func_ty, span)),
+                    args: std::mem::take(args),
+                    generic_args: Vec::new(),
+                    ty: ty.clone(),
+                    span,
+                };
+            }
+            HirExpression::If { condition, then_block, else_block, ty, .. } => {
+                self.rewrite_expr(condition, params)?;
+                self.rewrite_block(then_block, params)?;
+                if let Some(else_block) = else_block {
+                    self.rewrite_block(else_block, params)?;
+                }
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Loop { body, ty, .. } => {
+                self.rewrite_block(body, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::While { condition, body, .. } => {
+                self.rewrite_expr(condition, params)?;
+                self.rewrite_block(body, params)?;
+            }
+            HirExpression::For { pattern, iter, body, .. } => {
+                self.rewrite_pattern(pattern, params)?;
+                self.rewrite_expr(iter, params)?;
+                self.rewrite_block(body, params)?;
+            }
+            HirExpression::Block(block) => self.rewrite_block(block, params)?,
+            HirExpression::Match { scrutinee, arms, ty, .. } => {
+                self.rewrite_expr(scrutinee, params)?;
+                for arm in arms {
+                    self.rewrite_pattern(&mut arm.pattern, params)?;
+                    if let Some(guard) = &mut arm.guard {
+                        self.rewrite_expr(guard, params)?;
+                    }
+                    self.rewrite_expr(&mut arm.body, params)?;
+                }
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Tuple(elements, ty, _) | HirExpression::Array { elements, ty, .. } => {
+                self.rewrite_exprs(elements, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Index { base, index, ty, .. } => {
+                self.rewrite_expr(base, params)?;
+                self.rewrite_expr(index, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Field { base, ty, .. } => {
+                self.rewrite_expr(base, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Return(value, _) | HirExpression::Break(value, _) => {
+                if let Some(value) = value {
+                    self.rewrite_expr(value, params)?;
+                }
+            }
+            HirExpression::Continue(_) => {}
+            // Statics cannot mention type parameters
+            HirExpression::Static { .. } => {}
+            HirExpression::Closure { params: closure_params, return_ty, body, captures, ty, .. } => {
+                for param in closure_params {
+                    param.ty = self.concrete_ty(&param.ty, params, param.span)?;
+                }
+                for capture in captures {
+                    capture.ty = self.concrete_ty(&capture.ty, params, capture.span)?;
+                }
+                *return_ty = self.concrete_ty(return_ty, params, span)?;
+                self.rewrite_expr(body, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Struct { name, fields, ty, .. } => {
+                for (_, value) in fields {
+                    self.rewrite_expr(value, params)?;
+                }
+                *ty = self.concrete_ty(ty, params, span)?;
+                if let HirTy::Struct { name: instance, .. } = ty {
+                    *name = instance.clone();
+                }
+            }
+            HirExpression::Throw(value, _) => self.rewrite_expr(value, params)?,
+            HirExpression::QuestionMark(value, ty, _) => {
+                self.rewrite_expr(value, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::Try(try_block) => {
+                self.rewrite_block(&mut try_block.try_block, params)?;
+                for handler in &mut try_block.handlers {
+                    for method in &mut handler.methods {
+                        for param in &mut method.params {
+                            param.ty = self.concrete_ty(&param.ty, params, param.span)?;
+                        }
+                        method.return_type = self.concrete_ty(&method.return_type, params, method.span)?;
+                        self.rewrite_block(&mut method.body, params)?;
+                    }
+                }
+            }
+            HirExpression::TemplateString { parts, .. } => {
+                for part in parts {
+                    if let HirTemplateStringPart::Expr { expr, width, precision, .. } = part {
+                        self.rewrite_expr(expr, params)?;
+                        for count in [width, precision].into_iter().flatten() {
+                            self.rewrite_expr(count, params)?;
+                        }
+                    }
+                }
+            }
+            HirExpression::Await { future, ty, .. } => {
+                self.rewrite_expr(future, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+            }
+            HirExpression::TraitObject { value, ty, .. } => {
+                let self_ty = match value.ty() {
+                    HirTy::Ref { inner, .. } | HirTy::Ptr { inner, .. } => self.subst_params(inner, params),
+                    other => self.subst_params(other, params),
+                };
+                self.rewrite_expr(value, params)?;
+                *ty = self.concrete_ty(ty, params, span)?;
+                if let HirTy::Ref { inner, .. } = ty {
+                    if let HirTy::TraitObject(traits) = inner.as_ref() {
+                        for trait_name in traits.clone() {
+                            self.instantiate_vtable(&trait_name, &self_ty, span)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zulon_parser::Parser;
+    use zulon_typeck::TypeChecker;
+    use crate::SimpleLoweringContext;
+
+    fn lower(source: &str) -> HirCrate {
+        let ast = Parser::from_source(source).parse().expect("failed to parse");
+        let mut checker = TypeChecker::new();
+        checker.check(&ast).expect("failed to type check");
+        let hir = SimpleLoweringContext::with_type_checker(checker)
+            .lower_ast(&ast)
+            .expect("failed to lower");
+        monomorphize(&hir).expect("failed to monomorphize")
+    }
+
+    fn function<'a>(krate: &'a HirCrate, name: &str) -> Option<&'a HirFunction> {
+        krate.items.iter().find_map(|item| match item {
+            HirItem::Function(func) if func.name == name => Some(func.as_ref()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_mangle() {
+        assert_eq!(mangle("identity", &[]), "identity");
+        assert_eq!(mangle("identity", &[HirTy::I32]), "identity$LT$i32$GT$");
+        assert_eq!(
+            mangle("Pair", &[HirTy::Ref { inner: Box::new(HirTy::Bool), mutable: false }, HirTy::Tuple(vec![])]),
+            "Pair$LT$$RF$bool$C$$u28$$u29$$GT$"
+        );
+    }
+
+    #[test]
+    fn test_one_instance_per_type_argument_list() {
+        let krate = lower(
+            "fn identity<T>(x: T) -> T { x }
+             fn main() -> i32 { let a = identity(1); let b = identity(true); identity(2) }",
+        );
+
+        assert!(function(&krate, "identity").is_none());
+        let instance = function(&krate, "identity$LT$i32$GT$").expect("missing i32 instance");
+        assert_eq!(instance.params[0].ty, HirTy::I32);
+        assert_eq!(instance.return_type, HirTy::I32);
+        assert!(function(&krate, "identity$LT$bool$GT$").is_some());
+        let functions = krate.items.iter().filter(|item| matches!(item, HirItem::Function(_))).count();
+        assert_eq!(functions, 3);
+    }
+
+    #[test]
+    fn test_generic_struct_instance() {
+        let krate = lower(
+            "struct Pair<T> { first: T, second: T }
+             fn first<T>(p: Pair<T>) -> T { p.first }
+             fn main() -> i64 { let p = Pair { first: 1i64, second: 2i64 }; first(p) }",
+        );
+
+        let pair = krate.items.iter().find_map(|item| match item {
+            HirItem::Struct(def) => Some(def),
+            _ => None,
+        });
+        let pair = pair.expect("missing struct instance");
+        assert_eq!(pair.name, "Pair$LT$i64$GT$");
+        assert!(pair.fields.iter().all(|field| field.ty == HirTy::I64));
+
+        let instance = function(&krate, "first$LT$i64$GT$").expect("missing function instance");
+        assert_eq!(
+            instance.params[0].ty,
+            HirTy::Struct { name: "Pair$LT$i64$GT$".to_string(), generics: Vec::new() }
+        );
+    }
+
+    #[test]
+    fn test_trait_method_call_resolves_to_impl() {
+        let krate = lower(
+            "trait Size { fn size(self) -> i64; }
+             struct Wrapper<T> { value: T }
+             impl<T> Size for Wrapper<T> { fn size(self) -> i64 { 1i64 } }
+             fn measure<S: Size>(s: S) -> i64 { s.size() }
+             fn main() -> i64 { measure(Wrapper { value: true }) }",
+        );
+
+        assert!(!krate.items.iter().any(|item| matches!(item, HirItem::Impl(_))));
+        let instance = function(&krate, "measure$LT$Wrapper$LT$bool$GT$$GT$").expect("missing function instance");
+        match &instance.body.trailing_expr {
+            Some(HirExpression::Call { func, .. }) => {
+                assert!(matches!(func.as_ref(), HirExpression::Variable(name, ..)
+                    if name == "<Wrapper<T> as Size>::size$LT$bool$GT$"));
+            }
+            other => panic!("expected call, found {:?}", other),
+        }
+        assert!(function(&krate, "<Wrapper<T> as Size>::size$LT$bool$GT$").is_some());
+    }
+}
@@ -6,8 +6,11 @@
 //! This is a simplified version that demonstrates the core concepts
 //! without handling all edge cases.
 
+use std::collections::HashMap;
+
 use zulon_parser::ast;
-use zulon_typeck::TypeChecker;
+use zulon_parser::format::{Argument, Count, FormatKind, FormatSpec};
+use zulon_typeck::{ConstValue, MethodCallee, TypeChecker};
 
 use super::hir::*;
 use super::ty::HirTy;
@@ -17,14 +20,30 @@ use super::error::{LoweringError, Result};
 pub struct SimpleLoweringContext {
     typeck: TypeChecker,  // Type checker for closure type inference
     next_id: NodeId,
+    /// Type parameters of the item being lowered
+    generic_params: Vec<String>,
+    /// Enums declared in the crate with their variants' names, so that
+    /// their names lower to `HirTy::Enum`
+    enums: HashMap<String, Vec<String>>,
+    /// The type `Self` stands for, inside an impl block
+    self_ty: Option<zulon_typeck::Ty>,
 }
 
 impl SimpleLoweringContext {
     /// Create a new lowering context
     pub fn new() -> Self {
+        Self::with_type_checker(TypeChecker::new())
+    }
+
+    /// Create a lowering context that reads expression types and generic
+    /// call arguments from a type checker that has already checked the AST
+    pub fn with_type_checker(typeck: TypeChecker) -> Self {
         SimpleLoweringContext {
-            typeck: TypeChecker::new(),
+            typeck,
             next_id: 0,
+            generic_params: Vec::new(),
+            enums: HashMap::new(),
+            self_ty: None,
         }
     }
 
@@ -39,10 +58,38 @@ impl SimpleLoweringContext {
     pub fn lower_ast(&mut self, ast: &ast::Ast) -> Result<HirCrate> {
         let mut items = Vec::new();
 
+        self.enums = ast.items.iter()
+            .filter_map(|item| match &item.kind {
+                ast::ItemKind::Enum(enum_def) => Some((
+                    enum_def.name.name.clone(),
+                    enum_def.variants.iter().map(|variant| variant.name.name.clone()).collect(),
+                )),
+                _ => None,
+            })
+            .collect();
+        let traits: HashMap<&str, &ast::Trait> = ast.items.iter()
+            .filter_map(|item| match &item.kind {
+                ast::ItemKind::Trait(trait_def) => Some((trait_def.name.name.as_str(), trait_def)),
+                _ => None,
+            })
+            .collect();
+
         for item in &ast.items {
             match &item.kind {
                 ast::ItemKind::Function(func) => {
-                    items.push(HirItem::Function(self.lower_function(func)?));
+                    // Outer attributes such as `#[test]` belong to the item
+                    let mut function = self.lower_function(func)?;
+                    function.attributes.extend(item.attributes.iter().cloned());
+                    items.push(HirItem::Function(Box::new(function)));
+                }
+                ast::ItemKind::Trait(trait_def) => {
+                    items.push(HirItem::Trait(self.lower_trait(trait_def)?));
+                }
+                ast::ItemKind::Impl(impl_block) => {
+                    let trait_def = impl_block.trait_name.as_ref()
+                        .and_then(|trait_name| traits.get(type_name(trait_name)?))
+                        .copied();
+                    items.push(HirItem::Impl(self.lower_impl(impl_block, trait_def)?));
                 }
                 ast::ItemKind::Struct(struct_def) => {
                     items.push(HirItem::Struct(self.lower_struct(struct_def)?));
@@ -50,6 +97,18 @@ impl SimpleLoweringContext {
                 ast::ItemKind::Enum(enum_def) => {
                     items.push(HirItem::Enum(self.lower_enum(enum_def)?));
                 }
+                // Constants are inlined where they are used
+                ast::ItemKind::Static(static_def) => {
+                    if let Some(value) = self.typeck.static_value(&static_def.name.name) {
+                        items.push(HirItem::Static(HirStatic {
+                            name: static_def.name.name.clone(),
+                            ty: self.lower_type(Some(&static_def.type_annotation))?,
+                            value: value.clone(),
+                            is_mutable: static_def.is_mutable,
+                            span: static_def.name.span,
+                        }));
+                    }
+                }
                 _ => {
                     // Skip other items for now
                     continue;
@@ -74,15 +133,33 @@ impl SimpleLoweringContext {
 
     /// Lower a function (simplified)
     fn lower_function(&mut self, func: &ast::Function) -> Result<HirFunction> {
-        // For now, just use I32 for all params and return type
+        let generics = self.lower_generics(&func.generics);
+        let outer_params = std::mem::replace(
+            &mut self.generic_params,
+            generics.iter().map(|param| param.name.clone()).collect(),
+        );
+        let result = self.lower_function_in_scope(func, generics);
+        self.generic_params = outer_params;
+        result
+    }
+
+    /// Lower a function once its type parameters are in scope
+    fn lower_function_in_scope(&mut self, func: &ast::Function, generics: Vec<HirGenericParam>) -> Result<HirFunction> {
         let mut params = Vec::new();
         for param in &func.params {
             params.push(HirParam {
                 name: param.name.name.clone(),
-                ty: HirTy::I32,  // TODO: Get actual type
+                ty: match &param.type_annotation {
+                    Some(ty) => self.lower_type(Some(ty))?,
+                    None => HirTy::I32,
+                },
                 span: param.span.clone(),
             });
         }
+        let return_type = match &func.return_type {
+            Some(ty) => self.lower_type(Some(ty))?,
+            None => HirTy::I32,  // TODO: Unit once codegen handles void functions
+        };
 
         // Lower function body
         let body = self.lower_block(&func.body)?;
@@ -127,9 +204,9 @@ impl SimpleLoweringContext {
         Ok(HirFunction {
             id: self.alloc_id(),
             name: func.name.name.clone(),
-            generics: Vec::new(),
+            generics,
             params,
-            return_type: HirTy::I32,  // TODO: Get actual return type
+            return_type,
             error_type,
             effects,
             attributes,
@@ -140,51 +217,313 @@ impl SimpleLoweringContext {
         })
     }
 
+    /// Lower a trait declaration; default method bodies are lowered as
+    /// part of each impl that does not override them
+    fn lower_trait(&mut self, trait_def: &ast::Trait) -> Result<HirTrait> {
+        let generics = self.lower_generics(&trait_def.generics);
+        let mut items = Vec::new();
+
+        for item in &trait_def.items {
+            let (ast::TraitItemKind::Method(func) | ast::TraitItemKind::RequiredMethod(func)) = &item.kind else {
+                continue;
+            };
+            let method_generics = self.lower_generics(&func.generics);
+            let mut scope: Vec<String> = generics.iter().map(|param| param.name.clone()).collect();
+            scope.extend(method_generics.iter().map(|param| param.name.clone()));
+            scope.push("Self".to_string());
+
+            let outer_params = std::mem::replace(&mut self.generic_params, scope);
+            let params = func.params.iter()
+                .map(|param| {
+                    Ok(HirParam {
+                        name: param.name.name.clone(),
+                        ty: self.lower_type(param.type_annotation.as_ref())?,
+                        span: param.span,
+                    })
+                })
+                .collect::<Result<_>>();
+            let return_type = self.lower_type(func.return_type.as_ref());
+            self.generic_params = outer_params;
+
+            items.push(HirTraitItem::Method {
+                name: func.name.name.clone(),
+                sig: HirFunctionSig {
+                    generics: method_generics,
+                    params: params?,
+                    return_type: return_type?,
+                },
+                span: func.name.span,
+            });
+        }
+
+        Ok(HirTrait {
+            id: self.alloc_id(),
+            name: trait_def.name.name.clone(),
+            generics,
+            super_traits: trait_def.super_traits.iter().filter_map(type_name).map(str::to_string).collect(),
+            items,
+            span: trait_def.name.span,
+        })
+    }
+
+    /// Lower an impl block; each method becomes a function named by the
+    /// type checker, generic over the impl's type parameters followed by
+    /// its own
+    ///
+    /// A trait impl also gets a copy of every default method of
+    /// `trait_def` it does not override, with `Self` the impl's type.
+    fn lower_impl(&mut self, impl_block: &ast::Impl, trait_def: Option<&ast::Trait>) -> Result<HirImpl> {
+        let Some(self_ty) = self.typeck.impl_self_ty(&impl_block.impl_span).cloned() else {
+            return Err(LoweringError::InvalidConstruction {
+                message: "impl block was not type checked".to_string(),
+                span: impl_block.impl_span,
+            });
+        };
+        let generics = self.lower_generics(&impl_block.generics);
+
+        let defaults = trait_def.iter()
+            .flat_map(|trait_def| &trait_def.items)
+            .filter_map(|item| match &item.kind {
+                ast::TraitItemKind::Method(func) => Some(func),
+                _ => None,
+            })
+            .filter(|func| !impl_block.items.iter().any(|method| method.name.name == func.name.name));
+
+        let outer_self_ty = self.self_ty.replace(self_ty.clone());
+        let items = impl_block.items.iter()
+            .chain(defaults)
+            .map(|func| {
+                let name = self.typeck.impl_function_name(&impl_block.impl_span, &func.name.name)
+                    .unwrap_or_else(|| func.name.name.clone());
+                self.lower_method(func, &generics, name).map(|func| HirItem::Function(Box::new(func)))
+            })
+            .collect::<Result<_>>();
+        self.self_ty = outer_self_ty;
+
+        Ok(HirImpl {
+            id: self.alloc_id(),
+            generics,
+            target_trait: impl_block.trait_name.as_ref().and_then(type_name).map(str::to_string),
+            target_type: HirTy::from_inferred(&self_ty),
+            assoc_types: self.typeck.impl_assoc_types(&impl_block.impl_span).iter()
+                .map(|(name, ty)| (name.clone(), HirTy::from_inferred(ty)))
+                .collect(),
+            items: items?,
+            span: impl_block.impl_span,
+        })
+    }
+
+    /// Lower a method of an impl block as a function named `name`
+    fn lower_method(
+        &mut self,
+        func: &ast::Function,
+        impl_generics: &[HirGenericParam],
+        name: String,
+    ) -> Result<HirFunction> {
+        let mut generics = impl_generics.to_vec();
+        generics.extend(self.lower_generics(&func.generics));
+        let outer_params = std::mem::replace(
+            &mut self.generic_params,
+            generics.iter().map(|param| param.name.clone()).collect(),
+        );
+        let result = self.lower_function_in_scope(func, generics);
+        self.generic_params = outer_params;
+        Ok(HirFunction { name, ..result? })
+    }
+
     /// Lower a struct definition
     fn lower_struct(&mut self, struct_def: &ast::Struct) -> Result<HirStruct> {
+        let generics = self.lower_generics(&struct_def.generics);
+        let outer_params = std::mem::replace(
+            &mut self.generic_params,
+            generics.iter().map(|param| param.name.clone()).collect(),
+        );
+
         // Lower struct fields
-        let fields: Vec<HirField> = struct_def.fields.iter()
+        let fields: Result<Vec<HirField>> = struct_def.fields.iter()
             .map(|field| {
-                let ty = HirTy::I32;  // TODO: Get actual type from field annotation
                 Ok(HirField {
                     name: field.name.name.clone(),
-                    ty,
+                    ty: self.lower_type(Some(&field.type_annotation))?,
                     span: field.name.span.clone(),
                 })
             })
-            .collect::<Result<_>>()?;
+            .collect();
+        self.generic_params = outer_params;
 
         Ok(HirStruct {
             id: self.alloc_id(),
             name: struct_def.name.name.clone(),
-            generics: Vec::new(),  // TODO: Handle generics
-            fields,
+            generics,
+            fields: fields?,
             span: struct_def.name.span.clone(),
         })
     }
 
     /// Lower an enum definition
     fn lower_enum(&mut self, enum_def: &ast::Enum) -> Result<HirEnum> {
-        // Lower enum variants
-        let variants: Vec<HirVariant> = enum_def.variants.iter()
-            .map(|variant| {
+        let generics = self.lower_generics(&enum_def.generics);
+        let outer_params = std::mem::replace(
+            &mut self.generic_params,
+            generics.iter().map(|param| param.name.clone()).collect(),
+        );
+
+        // Lower enum variants; tuple-style fields are named by position
+        let discriminants = self.typeck.enum_discriminants(&enum_def.name.name).map(<[i128]>::to_vec);
+        let variants: Result<Vec<HirVariant>> = enum_def.variants.iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let fields = variant.fields.iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let (name, ty) = match field {
+                            ast::VariantField::Named(name, ty) => (name.name.clone(), ty),
+                            ast::VariantField::Unnamed(ty) => (i.to_string(), ty),
+                        };
+                        Ok(HirField {
+                            name,
+                            ty: self.lower_type(Some(ty))?,
+                            span: variant.span,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
                 Ok(HirVariant {
                     name: variant.name.name.clone(),
-                    fields: Vec::new(),  // TODO: Handle variant data fields
+                    fields,
+                    discriminant: discriminants.as_ref()
+                        .and_then(|discriminants| discriminants.get(index).copied())
+                        .unwrap_or(index as i128),
                     span: variant.name.span.clone(),
                 })
             })
-            .collect::<Result<_>>()?;
+            .collect();
+        self.generic_params = outer_params;
 
         Ok(HirEnum {
             id: self.alloc_id(),
             name: enum_def.name.name.clone(),
-            generics: Vec::new(),  // TODO: Handle generics
-            variants,
+            generics,
+            variants: variants?,
             span: enum_def.name.span.clone(),
         })
     }
 
+    /// Lower the type parameters of an item, with the traits bounding
+    /// them; const and lifetime parameters are not kept in HIR
+    fn lower_generics(&self, generics: &Option<ast::Generics>) -> Vec<HirGenericParam> {
+        generics.iter()
+            .flat_map(|generics| generics.params.iter().map(move |param| (param, &generics.where_clause)))
+            .filter_map(|(param, where_clause)| match param {
+                ast::GenericParam::Type(name) => Some(HirGenericParam {
+                    name: name.name.clone(),
+                    bounds: where_clause.iter()
+                        .filter(|clause| clause.type_param.name == name.name)
+                        .flat_map(|clause| &clause.bounds)
+                        .filter_map(|bound| match bound {
+                            ast::TraitBound::Trait(ty) => type_name(ty).map(str::to_string),
+                            ast::TraitBound::Lifetime(_) => None,
+                        })
+                        .collect(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Convert a type recorded by the type checker, with `Self` replaced
+    /// by the type of the impl being lowered
+    fn inferred_type(&self, ty: &zulon_typeck::Ty) -> HirTy {
+        match &self.self_ty {
+            Some(self_ty) => {
+                let args = HashMap::from([("Self".to_string(), self_ty.clone())]);
+                HirTy::from_inferred(&zulon_typeck::subst_params(&args, ty))
+            }
+            None => HirTy::from_inferred(ty),
+        }
+    }
+
+    /// A use of the `const` or `static` item `name`: a constant's value,
+    /// or the static itself
+    fn lower_global(&mut self, name: &str, expr: &ast::Expression) -> HirExpression {
+        match (self.typeck.const_value(name), self.typeck.expr_type(&expr.span)) {
+            (Some(value), Some(ty)) => {
+                let (value, ty) = (value.clone(), ty.clone());
+                self.const_expression(&value, &ty, expr.span)
+            }
+            _ => HirExpression::Static {
+                name: name.to_string(),
+                ty: self.expr_type(expr),
+                span: expr.span,
+            },
+        }
+    }
+
+    /// The expression building the constant `value` of type `ty`
+    fn const_expression(&mut self, value: &ConstValue, ty: &zulon_typeck::Ty, span: ast::Span) -> HirExpression {
+        use zulon_typeck::Ty;
+
+        let hir_ty = self.inferred_type(ty);
+        let literal = match value {
+            // Integers are stored as their bit pattern
+            ConstValue::Int(value) => HirLiteral::Integer(*value as u128),
+            ConstValue::Float(value) => HirLiteral::Float(*value),
+            ConstValue::Bool(value) => HirLiteral::Bool(*value),
+            ConstValue::Char(value) => HirLiteral::Char(*value),
+            ConstValue::Str(value) => HirLiteral::String(value.clone()),
+            ConstValue::Unit => HirLiteral::Unit,
+            ConstValue::Tuple(values) => {
+                let tys = match ty {
+                    Ty::Tuple(tys) => tys.clone(),
+                    _ => Vec::new(),
+                };
+                let elements = values.iter()
+                    .zip(&tys)
+                    .map(|(value, ty)| self.const_expression(value, ty, span))
+                    .collect();
+                return HirExpression::Tuple(elements, hir_ty, span);
+            }
+            ConstValue::Array(values) => {
+                let inner = match ty {
+                    Ty::Array { inner, .. } => inner.as_ref().clone(),
+                    _ => Ty::Unit,
+                };
+                let elements = values.iter()
+                    .map(|value| self.const_expression(value, &inner, span))
+                    .collect();
+                return HirExpression::Array { elements, ty: hir_ty, span };
+            }
+            ConstValue::Struct { name, fields } => {
+                let field_tys = self.typeck.struct_field_types(ty).unwrap_or_default();
+                let fields = fields.iter()
+                    .filter_map(|(field, value)| {
+                        let (_, ty) = field_tys.iter().find(|(name, _)| name == field)?;
+                        Some((field.clone(), self.const_expression(value, ty, span)))
+                    })
+                    .collect();
+                return HirExpression::Struct { name: name.clone(), fields, ty: hir_ty, span };
+            }
+        };
+        HirExpression::Literal(literal, self.alloc_id(), hir_ty, span)
+    }
+
+    /// The type the type checker recorded for `expr`, or `i32` when the
+    /// context was created without one
+    fn expr_type(&self, expr: &ast::Expression) -> HirTy {
+        self.typeck.expr_type(&expr.span)
+            .map(|ty| self.inferred_type(ty))
+            .unwrap_or(HirTy::I32)
+    }
+
+    /// The type of a `let` binding: its annotation, else the type of its
+    /// initializer
+    fn local_type(&self, local: &ast::Local) -> Result<HirTy> {
+        match (&local.type_annotation, &local.init) {
+            (Some(ty), _) => self.lower_type(Some(ty)),
+            (None, Some(init)) => Ok(self.expr_type(init)),
+            (None, None) => Ok(HirTy::I32),
+        }
+    }
+
     /// Lower a block (simplified)
     fn lower_block(&mut self, block: &ast::Block) -> Result<HirBlock> {
         let mut statements = Vec::new();
@@ -193,7 +532,7 @@ impl SimpleLoweringContext {
         for stmt in &block.statements {
             match &stmt.kind {
                 ast::StatementKind::Local(local) => {
-                    let local_ty = HirTy::I32;  // TODO: Get actual type
+                    let local_ty = self.local_type(local)?;
 
                     let init = if let Some(init) = &local.init {
                         Some(self.lower_expression(init)?)
@@ -226,7 +565,7 @@ impl SimpleLoweringContext {
                             HirStatement::Semi(lowered_expr)
                         }
                         ast::StatementKind::Local(local) => {
-                            let local_ty = HirTy::I32;  // TODO: Get actual type
+                            let local_ty = self.local_type(local)?;
                             let init = if let Some(init) = &local.init {
                                 Some(self.lower_expression(init)?)
                             } else {
@@ -251,8 +590,12 @@ impl SimpleLoweringContext {
                     statements.push(HirStatement::Defer(Box::new(lowered_stmt)));
                 }
 
-                ast::StatementKind::Empty => {
-                    // Skip empty statements
+                ast::StatementKind::LetElse(let_else) => {
+                    self.lower_let_else(let_else, &mut statements)?;
+                }
+
+                ast::StatementKind::Empty | ast::StatementKind::Error => {
+                    // Skip empty statements and recovered parse errors
                 }
 
                 ast::StatementKind::Item(_) => {
@@ -268,21 +611,54 @@ impl SimpleLoweringContext {
             None
         };
 
+        // The block has the type of its trailing expression
+        let ty = trailing_expr.as_ref().map_or(HirTy::Unit, |expr| expr.ty().clone());
         Ok(HirBlock {
             id: self.alloc_id(),
             statements,
             trailing_expr,
-            ty: HirTy::Unit,  // TODO: Get actual block type
+            ty,
             span: block.span.clone(),
         })
     }
 
-    /// Lower an expression (simplified)
+    /// Lower an expression, converting it to a trait object reference
+    /// where the type checker coerced it to one
     fn lower_expression(&mut self, expr: &ast::Expression) -> Result<HirExpression> {
+        let lowered = self.lower_expression_kind(expr)?;
+        match self.typeck.coercion(&expr.span) {
+            Some(target) if !matches!(lowered.ty(), HirTy::Ref { inner, .. } if matches!(**inner, HirTy::TraitObject(_))) => {
+                Ok(HirExpression::TraitObject {
+                    value: Box::new(lowered),
+                    ty: self.inferred_type(target),
+                    span: expr.span,
+                })
+            }
+            _ => Ok(lowered),
+        }
+    }
+
+    /// Lower an expression (simplified)
+    fn lower_expression_kind(&mut self, expr: &ast::Expression) -> Result<HirExpression> {
         match &expr.kind {
-            ast::ExpressionKind::Literal(lit) => {
-                let hir_lit = self.lower_literal(lit)?;
+            // Byte strings are `[u8; N]` constants
+            ast::ExpressionKind::Literal(ast::Literal::ByteString(bytes)) => {
+                let hir_lit = HirLiteral::ByteString(bytes.clone());
                 let ty = self.literal_type(&hir_lit);
+                Ok(HirExpression::Literal(hir_lit, self.alloc_id(), ty, expr.span))
+            }
+
+            ast::ExpressionKind::Literal(lit) => {
+                let hir_lit = self.lower_literal(lit, &expr.span)?;
+                // Numbers have the type inferred from their context
+                let inferred = match lit {
+                    ast::Literal::Int(..) | ast::Literal::Float(..) => self.typeck.expr_type(&expr.span),
+                    _ => None,
+                };
+                let ty = match inferred {
+                    Some(ty) => self.inferred_type(ty),
+                    None => self.literal_suffix_type(lit).unwrap_or_else(|| self.literal_type(&hir_lit)),
+                };
 
                 Ok(HirExpression::Literal(
                     hir_lit,
@@ -295,33 +671,39 @@ impl SimpleLoweringContext {
             ast::ExpressionKind::Path(path) => {
                 // Handle both simple variables and qualified paths (e.g., Enum::Variant)
                 if path.len() == 1 {
+                    if let Some(name) = self.typeck.global_ref(&path[0].span) {
+                        let name = name.to_string();
+                        return Ok(self.lower_global(&name, expr));
+                    }
+
                     // Simple variable reference
                     Ok(HirExpression::Variable(
                         path[0].name.clone(),
                         self.alloc_id(),
-                        HirTy::I32,  // Placeholder - will be fixed when proper type propagation is implemented
+                        self.expr_type(expr),
                         expr.span.clone(),
                     ))
                 } else {
-                    // Qualified path (e.g., Enum::Variant)
-                    // For now, convert to a string with "::" separator and treat as variable
-                    // This is a simplified approach - a full implementation would have proper enum support
+                    // Qualified path (e.g., Enum::Variant), named in full
                     let full_name = path.iter().map(|id| id.name.as_str()).collect::<Vec<_>>().join("::");
 
                     Ok(HirExpression::Variable(
                         full_name,
                         self.alloc_id(),
-                        HirTy::I32,  // Placeholder - will be fixed when proper type propagation is implemented
+                        self.expr_type(expr),
                         expr.span.clone(),
                     ))
                 }
             }
 
             ast::ExpressionKind::Binary(op, left, right) => {
+                if let Some(resolution) = self.typeck.method_resolution(&expr.span).cloned() {
+                    return self.lower_comparison(op, left, right, resolution.callee, expr);
+                }
                 let left_expr = self.lower_expression(left)?;
                 let right_expr = self.lower_expression(right)?;
                 let hir_op = self.lower_bin_op(op)?;
-                let ty = HirTy::I32;  // TODO: Infer actual type
+                let ty = self.expr_type(expr);
 
                 Ok(HirExpression::BinaryOp {
                     op: hir_op,
@@ -335,7 +717,7 @@ impl SimpleLoweringContext {
             ast::ExpressionKind::Unary(op, operand) => {
                 let operand_expr = self.lower_expression(operand)?;
                 let hir_op = self.lower_unary_op(op)?;
-                let ty = HirTy::I32;  // TODO: Infer actual type
+                let ty = self.expr_type(expr);
 
                 Ok(HirExpression::UnaryOp {
                     op: hir_op,
@@ -345,12 +727,18 @@ impl SimpleLoweringContext {
                 })
             }
 
+            ast::ExpressionKind::Cast(value, _) => Ok(HirExpression::Cast {
+                value: Box::new(self.lower_expression(value)?),
+                ty: self.expr_type(expr),
+                span: expr.span,
+            }),
+
             ast::ExpressionKind::Assign(target, value) => {
                 // Assignment: target = value
                 // Lower both sides, then represent as a BinaryOp with Assign operator
                 let target_expr = self.lower_expression(target)?;
                 let value_expr = self.lower_expression(value)?;
-                let ty = HirTy::I32;  // TODO: Infer actual type
+                let ty = value_expr.ty().clone();
 
                 Ok(HirExpression::BinaryOp {
                     op: HirBinOp::Assign,
@@ -467,26 +855,41 @@ impl SimpleLoweringContext {
             }
 
             ast::ExpressionKind::Call(func, args) => {
-                let func_expr = self.lower_expression(func)?;
                 let lowered_args: Result<Vec<_>> = args.iter()
                     .map(|arg| self.lower_expression(arg))
                     .collect();
                 let args = lowered_args?;
-                let ty = HirTy::I32;  // TODO: Get actual return type
+
+                // `Type::function(..)`
+                if let Some(resolution) = self.typeck.method_resolution(&expr.span) {
+                    return Ok(self.lower_resolved_call(resolution.callee.clone(), args, expr));
+                }
+
+                let func_expr = self.lower_expression(func)?;
+                let generic_args = self.typeck.call_generic_args(&expr.span)
+                    .map(|tys| tys.iter().map(|ty| self.inferred_type(ty)).collect())
+                    .unwrap_or_default();
 
                 Ok(HirExpression::Call {
                     func: Box::new(func_expr),
                     args,
-                    ty,
+                    generic_args,
+                    ty: self.expr_type(expr),
                     span: expr.span.clone(),
                 })
             }
 
+            ast::ExpressionKind::MethodCall(receiver, _, args) => self.lower_method_call(receiver, args, expr),
+
             ast::ExpressionKind::Block(block) => {
                 let lowered_block = self.lower_block(block)?;
                 Ok(HirExpression::Block(Box::new(lowered_block)))
             }
 
+            ast::ExpressionKind::If(condition, then_block, else_block) if matches!(condition.kind, ast::ExpressionKind::Let(..)) => {
+                self.lower_if_let(condition, then_block, else_block, &expr.span)
+            }
+
             ast::ExpressionKind::If(condition, then_block, else_block) => {
                 let condition_expr = self.lower_expression(condition)?;
                 let then_lowered = self.lower_block(then_block)?;
@@ -503,7 +906,7 @@ impl SimpleLoweringContext {
                 let ty = if then_is_stmt && else_is_stmt {
                     HirTy::Unit  // Both branches are statements
                 } else {
-                    HirTy::I32  // At least one branch produces a value
+                    self.expr_type(expr)
                 };
 
                 Ok(HirExpression::If {
@@ -543,7 +946,7 @@ impl SimpleLoweringContext {
             ast::ExpressionKind::QuestionMark(inner_expr) => {
                 // Question mark operator: `expr?`
                 let lowered_inner = Box::new(self.lower_expression(inner_expr)?);
-                let ty = HirTy::I32;  // TODO: Infer actual success type
+                let ty = self.expr_type(expr);
                 Ok(HirExpression::QuestionMark(lowered_inner, ty, expr.span.clone()))
             }
 
@@ -556,9 +959,18 @@ impl SimpleLoweringContext {
                         ast::TemplateStringPart::Static(s) => {
                             parts.push(super::hir::HirTemplateStringPart::Static(s.clone()));
                         }
-                        ast::TemplateStringPart::Expr(e) => {
-                            let lowered_expr = Box::new(self.lower_expression(e)?);
-                            parts.push(super::hir::HirTemplateStringPart::Expr(lowered_expr));
+                        ast::TemplateStringPart::Expr(e, spec) => {
+                            let value = self.lower_expression(e)?;
+                            let mut spec = spec.clone();
+                            let lowered_expr = Box::new(self.format_value(value, &mut spec, &e.span));
+                            let width = self.lower_format_count(&spec.width, &e.span);
+                            let precision = self.lower_format_count(&spec.precision, &e.span);
+                            parts.push(super::hir::HirTemplateStringPart::Expr {
+                                expr: lowered_expr,
+                                spec,
+                                width,
+                                precision,
+                            });
                         }
                     }
                 }
@@ -578,6 +990,10 @@ impl SimpleLoweringContext {
                 })
             }
 
+            ast::ExpressionKind::While(condition, body, _label) if matches!(condition.kind, ast::ExpressionKind::Let(..)) => {
+                self.lower_while_let(condition, body, &expr.span)
+            }
+
             ast::ExpressionKind::While(condition, body, _label) => {
                 let lowered_condition = Box::new(self.lower_expression(condition)?);
                 let lowered_body = Box::new(self.lower_block(body)?);
@@ -669,13 +1085,10 @@ impl SimpleLoweringContext {
             ast::ExpressionKind::FieldAccess(object, field_name) => {
                 let lowered_object = Box::new(self.lower_expression(object)?);
 
-                // Get the type of the field access from type checker
-                let field_ty = self.typeck.check_expression(expr)?;
-
                 Ok(HirExpression::Field {
                     base: lowered_object,
                     field_name: field_name.name.clone(),
-                    ty: HirTy::from(field_ty),
+                    ty: self.expr_type(expr),
                     span: expr.span.clone(),
                 })
             }
@@ -685,10 +1098,14 @@ impl SimpleLoweringContext {
                 let lowered_scrutinee = Box::new(self.lower_expression(&scrutinee_expr)?);
 
                 // Lower match arms
+                let scrutinee_ty = lowered_scrutinee.ty().clone();
                 let mut hir_arms = Vec::new();
                 for arm in arms {
-                    // Lower pattern (simplified - just use the first pattern for now)
-                    let hir_pattern = self.lower_pattern(&arm.patterns[0], &arm.span)?;
+                    // An arm's alternatives become an or-pattern
+                    let hir_pattern = match arm.patterns.as_slice() {
+                        [pattern] => self.lower_pattern(pattern, &scrutinee_ty, &arm.span)?,
+                        patterns => self.lower_pattern(&ast::Pattern::Or(patterns.to_vec()), &scrutinee_ty, &arm.span)?,
+                    };
 
                     // Lower guard if present
                     let hir_guard = if let Some(guard_expr) = &arm.guard {
@@ -708,13 +1125,10 @@ impl SimpleLoweringContext {
                     });
                 }
 
-                // Get the type of the match expression from type checker
-                let match_ty = self.typeck.check_expression(expr)?;
-
                 Ok(HirExpression::Match {
                     scrutinee: lowered_scrutinee,
                     arms: hir_arms,
-                    ty: HirTy::from(match_ty),
+                    ty: self.expr_type(expr),
                     span: expr.span.clone(),
                 })
             }
@@ -765,7 +1179,7 @@ impl SimpleLoweringContext {
                 }
                 Ok(HirExpression::Tuple(
                     lowered_elements,
-                    HirTy::Tuple(vec![HirTy::I32; elements.len()]), // TODO: Infer actual types
+                    self.expr_type(expr),
                     expr.span.clone(),
                 ))
             }
@@ -778,14 +1192,36 @@ impl SimpleLoweringContext {
                 }
                 Ok(HirExpression::Array {
                     elements: lowered_elements,
-                    ty: HirTy::Array {
-                        inner: Box::new(HirTy::I32), // TODO: Infer actual type
-                        len: None,
-                    },
+                    ty: self.expr_type(expr),
                     span: expr.span.clone(),
                 })
             }
 
+            ast::ExpressionKind::Struct(literal) => {
+                // `Name { field: value, .. }`: fields stay in source order
+                if let Some(base) = &literal.base {
+                    return Err(LoweringError::UnsupportedFeature {
+                        feature: "struct update syntax".to_string(),
+                        span: base.span,
+                    });
+                }
+                let mut fields = Vec::new();
+                for field in &literal.fields {
+                    fields.push((field.name.name.clone(), self.lower_expression(&field.value)?));
+                }
+                let name = literal.path.last().map(|ident| ident.name.clone()).unwrap_or_default();
+                let ty = match self.typeck.expr_type(&expr.span) {
+                    Some(ty) => self.inferred_type(ty),
+                    None => self.lower_named_type(&name, Vec::new()),
+                };
+                Ok(HirExpression::Struct {
+                    name,
+                    fields,
+                    ty,
+                    span: expr.span,
+                })
+            }
+
             ast::ExpressionKind::Index(base, index) => {
                 // Array/tuple indexing: arr[index] or tuple.0
                 let lowered_base = Box::new(self.lower_expression(base)?);
@@ -793,7 +1229,7 @@ impl SimpleLoweringContext {
                 Ok(HirExpression::Index {
                     base: lowered_base,
                     index: lowered_index,
-                    ty: HirTy::I32, // TODO: Infer actual type
+                    ty: self.expr_type(expr),
                     span: expr.span.clone(),
                 })
             }
@@ -805,23 +1241,325 @@ impl SimpleLoweringContext {
         }
     }
 
-    /// Lower a pattern (simplified - literal patterns only)
-    fn lower_pattern(&mut self, pattern: &ast::Pattern, parent_span: &zulon_parser::Span) -> Result<HirPattern> {
+    /// Lower `receiver.method(args)` to a call with the receiver,
+    /// dereferenced and borrowed as the method requires, as the first
+    /// argument
+    fn lower_method_call(
+        &mut self,
+        receiver: &ast::Expression,
+        args: &[Box<ast::Expression>],
+        expr: &ast::Expression,
+    ) -> Result<HirExpression> {
+        let Some(resolution) = self.typeck.method_resolution(&expr.span).cloned() else {
+            return Err(LoweringError::UnsupportedFeature {
+                feature: "method call that was not type checked".to_string(),
+                span: expr.span,
+            });
+        };
+
+        let mut self_arg = self.lower_expression(receiver)?;
+        for _ in 0..resolution.autoderefs {
+            let ty = match self_arg.ty() {
+                HirTy::Ref { inner, .. } | HirTy::Ptr { inner, .. } => (**inner).clone(),
+                ty => ty.clone(),
+            };
+            self_arg = HirExpression::UnaryOp {
+                op: HirUnaryOp::Deref,
+                operand: Box::new(self_arg),
+                ty,
+                span: receiver.span,
+            };
+        }
+        if let Some(mutable) = resolution.autoref {
+            let ty = HirTy::Ref { inner: Box::new(self_arg.ty().clone()), mutable };
+            self_arg = HirExpression::UnaryOp {
+                op: if mutable { HirUnaryOp::RefMut } else { HirUnaryOp::Ref },
+                operand: Box::new(self_arg),
+                ty,
+                span: receiver.span,
+            };
+        }
+
+        let mut lowered_args = vec![self_arg];
+        for arg in args {
+            lowered_args.push(self.lower_expression(arg)?);
+        }
+        Ok(self.lower_resolved_call(resolution.callee, lowered_args, expr))
+    }
+
+    /// Lower the comparison `left op right` of values whose type implements
+    /// it with a trait to a call of `callee`, the trait's method, with both
+    /// operands borrowed
+    ///
+    /// `!=` negates what `eq` returns, and `>`, `<=` and `>=` match what
+    /// `partial_cmp` returns.
+    fn lower_comparison(
+        &mut self,
+        op: &ast::BinaryOp,
+        left: &ast::Expression,
+        right: &ast::Expression,
+        callee: MethodCallee,
+        expr: &ast::Expression,
+    ) -> Result<HirExpression> {
+        let span = expr.span;
+        let MethodCallee::Trait { trait_name, self_ty, method, .. } = callee else {
+            return Err(LoweringError::UnsupportedFeature {
+                feature: "comparison through an impl function".to_string(),
+                span,
+            });
+        };
+
+        let mut args = Vec::new();
+        for operand in [left, right] {
+            let value = self.lower_expression(operand)?;
+            let ty = HirTy::Ref { inner: Box::new(value.ty().clone()), mutable: false };
+            args.push(HirExpression::UnaryOp {
+                op: HirUnaryOp::Ref,
+                operand: Box::new(value),
+                ty,
+                span: operand.span,
+            });
+        }
+
+        let ordering_ty = HirTy::Enum { name: "Ordering".to_string(), generics: Vec::new() };
+        let option_ty = HirTy::Enum { name: "Option".to_string(), generics: vec![ordering_ty.clone()] };
+        let ty = if method == "partial_cmp" { option_ty.clone() } else { HirTy::Bool };
+        let call = HirExpression::MethodCall {
+            method_name: method,
+            trait_name,
+            self_ty: self.inferred_type(&self_ty),
+            args,
+            generic_args: Vec::new(),
+            ty,
+            span,
+        };
+        let orderings: &[&str] = match op {
+            ast::BinaryOp::Eq | ast::BinaryOp::Less => return Ok(call),
+            ast::BinaryOp::NotEq => {
+                return Ok(HirExpression::UnaryOp {
+                    op: HirUnaryOp::Not,
+                    operand: Box::new(call),
+                    ty: HirTy::Bool,
+                    span,
+                });
+            }
+            ast::BinaryOp::Greater => &["Greater"],
+            ast::BinaryOp::LessEq => &["Less", "Equal"],
+            _ => &["Greater", "Equal"],
+        };
+
+        // match call { Some(Ordering::Greater) => true, ..., _ => false }
+        let mut arm = |pattern, value| HirMatchArm {
+            pattern,
+            guard: None,
+            body: HirExpression::Literal(HirLiteral::Bool(value), self.alloc_id(), HirTy::Bool, span),
+            span,
+        };
+        let mut arms: Vec<_> = orderings
+            .iter()
+            .map(|ordering| {
+                let ordering = HirPattern::EnumVariant {
+                    enum_name: "Ordering".to_string(),
+                    variant_name: ordering.to_string(),
+                    inner: None,
+                    ty: ordering_ty.clone(),
+                    span,
+                };
+                let pattern = HirPattern::EnumVariant {
+                    enum_name: "Option".to_string(),
+                    variant_name: "Some".to_string(),
+                    inner: Some(Box::new(ordering)),
+                    ty: option_ty.clone(),
+                    span,
+                };
+                arm(pattern, true)
+            })
+            .collect();
+        arms.push(arm(HirPattern::Wildcard(span), false));
+
+        Ok(HirExpression::Match {
+            scrutinee: Box::new(call),
+            arms,
+            ty: HirTy::Bool,
+            span,
+        })
+    }
+
+    /// Lower a call of the method or associated function `callee`
+    fn lower_resolved_call(&mut self, callee: MethodCallee, args: Vec<HirExpression>, expr: &ast::Expression) -> HirExpression {
+        let ty = self.expr_type(expr);
+        match callee {
+            MethodCallee::Impl { function, generic_args } => {
+                let func_ty = HirTy::Function {
+                    params: args.iter().map(|arg| arg.ty().clone()).collect(),
+                    return_type: Box::new(ty.clone()),
+                };
+                HirExpression::Call {
+                    func: Box::new(HirExpression::Variable(function, self.alloc_id(), func_ty, expr.span)),
+                    args,
+                    generic_args: generic_args.iter().map(|ty| self.inferred_type(ty)).collect(),
+                    ty,
+                    span: expr.span,
+                }
+            }
+            MethodCallee::Trait { trait_name, self_ty, method, generic_args } => HirExpression::MethodCall {
+                method_name: method,
+                trait_name,
+                self_ty: self.inferred_type(&self_ty),
+                args,
+                generic_args: generic_args.iter().map(|ty| self.inferred_type(ty)).collect(),
+                ty,
+                span: expr.span,
+            },
+        }
+    }
+
+    /// Lower a pattern matching values of type `ty`
+    ///
+    /// Bindings take the types the type checker recorded for them. An
+    /// identifier that is not a binding names a unit variant, of `ty` if it
+    /// is an enum.
+    fn lower_pattern(&mut self, pattern: &ast::Pattern, ty: &HirTy, parent_span: &zulon_parser::Span) -> Result<HirPattern> {
+        // Patterns other than bindings match through references
+        let matched_ty = match ty {
+            HirTy::Ref { inner, .. } => inner,
+            _ => ty,
+        };
+
         match pattern {
             ast::Pattern::Wildcard => {
                 Ok(HirPattern::Wildcard(parent_span.clone()))
             }
             ast::Pattern::Literal(lit) => {
-                let hir_lit = self.lower_literal(lit)?;
+                let hir_lit = self.lower_literal(lit, parent_span)?;
                 Ok(HirPattern::Literal(hir_lit, parent_span.clone()))
             }
             ast::Pattern::Identifier(ident) => {
-                // Identifier pattern is a binding
-                // For now, use i32 as default type
-                Ok(HirPattern::Binding(ident.name.clone(), HirTy::I32, ident.span.clone()))
+                if let Some(binding_ty) = self.typeck.binding_type(&ident.span) {
+                    return Ok(HirPattern::Binding(ident.name.clone(), self.inferred_type(binding_ty), ident.span));
+                }
+                match self.unit_variant_enum(&ident.name, matched_ty) {
+                    Some(enum_name) => Ok(HirPattern::EnumVariant {
+                        enum_name,
+                        variant_name: ident.name.clone(),
+                        inner: None,
+                        ty: matched_ty.clone(),
+                        span: ident.span,
+                    }),
+                    // Without a type checker, bindings default to i32
+                    None => Ok(HirPattern::Binding(ident.name.clone(), HirTy::I32, ident.span)),
+                }
+            }
+            ast::Pattern::Tuple(patterns) => {
+                let lowered_patterns = patterns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        let element_ty = match matched_ty {
+                            HirTy::Tuple(tys) => tys.get(i).cloned().unwrap_or(HirTy::I32),
+                            _ => HirTy::I32,
+                        };
+                        self.lower_pattern(p, &element_ty, parent_span)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(HirPattern::Tuple(lowered_patterns, *parent_span))
+            }
+            ast::Pattern::TupleVariant(path, patterns) => {
+                // `Outcome::Ok(value)` / `Some(x)`: several fields become a tuple pattern
+                let enum_name = path[..path.len() - 1]
+                    .iter()
+                    .map(|ident| ident.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                let variant_name = path.last().map(|ident| ident.name.clone()).unwrap_or_default();
+                let enum_name = match (enum_name.is_empty(), matched_ty) {
+                    (true, HirTy::Enum { name, .. }) => name.clone(),
+                    _ => enum_name,
+                };
+
+                // Payload types are not tracked here; bindings in them take
+                // their types from the type checker
+                let inner = match patterns.as_slice() {
+                    [] => None,
+                    [pattern] => Some(Box::new(self.lower_pattern(pattern, &HirTy::I32, parent_span)?)),
+                    patterns => Some(Box::new(self.lower_pattern(&ast::Pattern::Tuple(patterns.to_vec()), &HirTy::I32, parent_span)?)),
+                };
+
+                Ok(HirPattern::EnumVariant {
+                    enum_name,
+                    variant_name,
+                    inner,
+                    ty: matched_ty.clone(),
+                    span: *parent_span,
+                })
+            }
+            ast::Pattern::Struct(path, fields) => {
+                let fields = fields.iter()
+                    .map(|field| match field {
+                        ast::StructPatternField::Field(name, pattern) => {
+                            Ok((name.name.clone(), self.lower_pattern(pattern, &HirTy::I32, parent_span)?))
+                        }
+                        ast::StructPatternField::Shorthand(name) => {
+                            let binding = self.lower_pattern(&ast::Pattern::Identifier(name.clone()), &HirTy::I32, parent_span)?;
+                            Ok((name.name.clone(), binding))
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let name = path.iter().map(|ident| ident.name.as_str()).collect::<Vec<_>>().join("::");
+
+                // `Color::Red` and `Shape::Circle { r }` name enum variants
+                let variant_name = path.last().map(|ident| ident.name.as_str()).unwrap_or_default();
+                let enum_name = match path.len() {
+                    1 => self.unit_variant_enum(variant_name, matched_ty),
+                    len => Some(path[len - 2].name.clone())
+                        .filter(|enum_name| self.enums.get(enum_name).is_some_and(|variants| variants.iter().any(|v| v == variant_name))),
+                };
+                if let Some(enum_name) = enum_name {
+                    let inner = (!fields.is_empty()).then(|| Box::new(HirPattern::Struct {
+                        name: name.clone(),
+                        fields,
+                        ty: HirTy::I32,
+                        span: *parent_span,
+                    }));
+                    return Ok(HirPattern::EnumVariant {
+                        enum_name,
+                        variant_name: variant_name.to_string(),
+                        inner,
+                        ty: matched_ty.clone(),
+                        span: *parent_span,
+                    });
+                }
+
+                Ok(HirPattern::Struct {
+                    name,
+                    fields,
+                    ty: matched_ty.clone(),
+                    span: *parent_span,
+                })
+            }
+            ast::Pattern::Range(start, kind, end) => {
+                let (ast::Pattern::Literal(start), ast::Pattern::Literal(end)) = (&**start, &**end) else {
+                    return Err(LoweringError::UnsupportedFeature {
+                        feature: format!("pattern: {:?}", pattern),
+                        span: *parent_span,
+                    });
+                };
+                Ok(HirPattern::Range {
+                    start: self.lower_literal(start, parent_span)?,
+                    end: self.lower_literal(end, parent_span)?,
+                    inclusive: matches!(kind, ast::RangeKind::Inclusive),
+                    ty: matched_ty.clone(),
+                    span: *parent_span,
+                })
+            }
+            ast::Pattern::Or(patterns) => {
+                let alternatives = patterns.iter()
+                    .map(|p| self.lower_pattern(p, ty, parent_span))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(HirPattern::Or(alternatives, *parent_span))
             }
             _ => {
-                // For now, only support simple patterns
+                // Array and slice patterns wait on arrays of unknown length
                 Err(LoweringError::UnsupportedFeature {
                     feature: format!("pattern: {:?}", pattern),
                     span: parent_span.clone(),
@@ -830,6 +1568,250 @@ impl SimpleLoweringContext {
         }
     }
 
+    /// The enum whose unit variant `name` is: `ty` if it is an enum with
+    /// that variant, else the first enum declaring one
+    fn unit_variant_enum(&self, name: &str, ty: &HirTy) -> Option<String> {
+        if let HirTy::Enum { name: enum_name, .. } = ty {
+            if self.enums.get(enum_name).is_some_and(|variants| variants.iter().any(|variant| variant == name)) {
+                return Some(enum_name.clone());
+            }
+        }
+        let mut enums: Vec<&String> = self.enums.iter()
+            .filter(|(_, variants)| variants.iter().any(|variant| variant == name))
+            .map(|(enum_name, _)| enum_name)
+            .collect();
+        enums.sort();
+        enums.first().map(|enum_name| (*enum_name).clone())
+    }
+
+    /// Lower `if let PAT = value { .. } else { .. }` to a two-armed match
+    fn lower_if_let(
+        &mut self,
+        condition: &ast::Expression,
+        then_block: &ast::Block,
+        else_block: &Option<ast::Block>,
+        span: &zulon_parser::Span,
+    ) -> Result<HirExpression> {
+        let ast::ExpressionKind::Let(pattern, value) = &condition.kind else {
+            unreachable!("lower_if_let called without a `let` condition");
+        };
+
+        let scrutinee = Box::new(self.lower_expression(value)?);
+        let hir_pattern = self.lower_pattern(pattern, scrutinee.ty(), &condition.span)?;
+        let then_lowered = self.lower_block(then_block)?;
+        let else_lowered = match else_block {
+            Some(block) => self.lower_block(block)?,
+            None => self.empty_block(span),
+        };
+
+        // Same rule as a plain `if`: without trailing expressions it is a statement
+        let then_is_stmt = then_block.trailing_expr.is_none();
+        let else_is_stmt = else_block.as_ref().is_none_or(|b| b.trailing_expr.is_none());
+        let ty = match self.typeck.expr_type(span) {
+            _ if then_is_stmt && else_is_stmt => HirTy::Unit,
+            Some(ty) => self.inferred_type(ty),
+            None => HirTy::I32,
+        };
+
+        Ok(HirExpression::Match {
+            scrutinee,
+            arms: vec![
+                HirMatchArm {
+                    pattern: hir_pattern,
+                    guard: None,
+                    body: HirExpression::Block(Box::new(then_lowered)),
+                    span: then_block.span,
+                },
+                HirMatchArm {
+                    pattern: HirPattern::Wildcard(*span),
+                    guard: None,
+                    body: HirExpression::Block(Box::new(else_lowered)),
+                    span: *span,
+                },
+            ],
+            ty,
+            span: *span,
+        })
+    }
+
+    /// Lower `while let PAT = value { body }` to
+    /// `loop { match value { PAT => body, _ => break } }`
+    fn lower_while_let(
+        &mut self,
+        condition: &ast::Expression,
+        body: &ast::Block,
+        span: &zulon_parser::Span,
+    ) -> Result<HirExpression> {
+        let ast::ExpressionKind::Let(pattern, value) = &condition.kind else {
+            unreachable!("lower_while_let called without a `let` condition");
+        };
+
+        let scrutinee = Box::new(self.lower_expression(value)?);
+        let hir_pattern = self.lower_pattern(pattern, scrutinee.ty(), &condition.span)?;
+        let body_lowered = self.lower_block(body)?;
+
+        let match_expr = HirExpression::Match {
+            scrutinee,
+            arms: vec![
+                HirMatchArm {
+                    pattern: hir_pattern,
+                    guard: None,
+                    body: HirExpression::Block(Box::new(body_lowered)),
+                    span: body.span,
+                },
+                HirMatchArm {
+                    pattern: HirPattern::Wildcard(*span),
+                    guard: None,
+                    body: HirExpression::Break(None, *span),
+                    span: *span,
+                },
+            ],
+            ty: HirTy::Unit,
+            span: *span,
+        };
+
+        Ok(HirExpression::Loop {
+            body: Box::new(HirBlock {
+                id: self.alloc_id(),
+                statements: vec![HirStatement::Semi(match_expr)],
+                trailing_expr: None,
+                ty: HirTy::Unit,
+                span: *span,
+            }),
+            ty: HirTy::Unit,
+            span: *span,
+        })
+    }
+
+    /// Lower `let PAT = value else { diverge };`
+    ///
+    /// A single name bound by the pattern becomes a local initialized by
+    /// `match value { PAT => name, _ => else_block }`. With several names,
+    /// the matching arm assigns each to a temporary that then initializes
+    /// its local, so the `else` block is still lowered only once.
+    fn lower_let_else(&mut self, let_else: &ast::LetElse, statements: &mut Vec<HirStatement>) -> Result<()> {
+        let span = let_else.init.span;
+        let scrutinee = self.lower_expression(&let_else.init)?;
+        let bindings: Vec<(String, HirTy, ast::Span)> = let_else.pattern.bindings().into_iter()
+            .map(|binding| (binding.name.clone(), self.binding_type(binding), binding.span))
+            .collect();
+
+        match bindings.as_slice() {
+            [] => {
+                let unit = HirExpression::Literal(HirLiteral::Unit, self.alloc_id(), HirTy::Unit, span);
+                let match_expr = self.let_else_match(let_else, scrutinee, unit)?;
+                statements.push(HirStatement::Semi(match_expr));
+            }
+            [(name, ty, binding_span)] => {
+                let value = HirExpression::Variable(name.clone(), self.alloc_id(), ty.clone(), *binding_span);
+                let match_expr = self.let_else_match(let_else, scrutinee, value)?;
+                statements.push(HirStatement::Local(HirLocal {
+                    id: self.alloc_id(),
+                    name: name.clone(),
+                    ty: ty.clone(),
+                    init: Some(match_expr),
+                    span: *binding_span,
+                }));
+            }
+            bindings => {
+                let prefix = format!("__let_else_{}", self.alloc_id());
+                let mut assignments = Vec::new();
+                for (name, ty, binding_span) in bindings {
+                    let temp = format!("{}_{}", prefix, name);
+                    statements.push(HirStatement::Local(HirLocal {
+                        id: self.alloc_id(),
+                        name: temp.clone(),
+                        ty: ty.clone(),
+                        init: None,
+                        span: *binding_span,
+                    }));
+                    assignments.push(HirStatement::Semi(HirExpression::BinaryOp {
+                        op: HirBinOp::Assign,
+                        left: Box::new(HirExpression::Variable(temp, self.alloc_id(), ty.clone(), *binding_span)),
+                        right: Box::new(HirExpression::Variable(name.clone(), self.alloc_id(), ty.clone(), *binding_span)),
+                        ty: ty.clone(),
+                        span: *binding_span,
+                    }));
+                }
+
+                let assign_all = HirExpression::Block(Box::new(HirBlock {
+                    id: self.alloc_id(),
+                    statements: assignments,
+                    trailing_expr: None,
+                    ty: HirTy::Unit,
+                    span,
+                }));
+                let match_expr = self.let_else_match(let_else, scrutinee, assign_all)?;
+                statements.push(HirStatement::Semi(match_expr));
+
+                for (name, ty, binding_span) in bindings {
+                    let temp = format!("{}_{}", prefix, name);
+                    statements.push(HirStatement::Local(HirLocal {
+                        id: self.alloc_id(),
+                        name: name.clone(),
+                        ty: ty.clone(),
+                        init: Some(HirExpression::Variable(temp, self.alloc_id(), ty.clone(), *binding_span)),
+                        span: *binding_span,
+                    }));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build `match scrutinee { PAT => on_match, _ => else_block }` for a `let...else`
+    fn let_else_match(
+        &mut self,
+        let_else: &ast::LetElse,
+        scrutinee: HirExpression,
+        on_match: HirExpression,
+    ) -> Result<HirExpression> {
+        let span = let_else.init.span;
+        let hir_pattern = self.lower_pattern(&let_else.pattern, scrutinee.ty(), &span)?;
+        let else_lowered = self.lower_block(&let_else.else_block)?;
+        let ty = on_match.ty().clone();
+
+        Ok(HirExpression::Match {
+            scrutinee: Box::new(scrutinee),
+            arms: vec![
+                HirMatchArm {
+                    pattern: hir_pattern,
+                    guard: None,
+                    body: on_match,
+                    span,
+                },
+                HirMatchArm {
+                    pattern: HirPattern::Wildcard(span),
+                    guard: None,
+                    body: HirExpression::Block(Box::new(else_lowered)),
+                    span: let_else.else_block.span,
+                },
+            ],
+            ty,
+            span,
+        })
+    }
+
+    /// The type the type checker gave the name bound at `ident`, or `i32`
+    /// when the context was created without one
+    fn binding_type(&self, ident: &ast::Identifier) -> HirTy {
+        self.typeck.binding_type(&ident.span)
+            .map(|ty| self.inferred_type(ty))
+            .unwrap_or(HirTy::I32)
+    }
+
+    /// An empty `{}` block
+    fn empty_block(&mut self, span: &zulon_parser::Span) -> HirBlock {
+        HirBlock {
+            id: self.alloc_id(),
+            statements: Vec::new(),
+            trailing_expr: None,
+            ty: HirTy::Unit,
+            span: *span,
+        }
+    }
+
     /// Lower a Local (for loop pattern)
     fn lower_pattern_local(&mut self, local: &ast::Local) -> Result<HirPattern> {
         // For now, just create a binding pattern from the local's name
@@ -841,14 +1823,76 @@ impl SimpleLoweringContext {
         ))
     }
 
+    /// The value a template string formats in place of `value`
+    ///
+    /// References format as the value they point to. Values the runtime
+    /// cannot format become a call of the `fmt` method of their spec's
+    /// trait, which monomorphization resolves to the impl's function; the
+    /// string it returns is then only padded, so `spec` becomes `Display`.
+    fn format_value(&self, mut value: HirExpression, spec: &mut FormatSpec, span: &zulon_parser::Span) -> HirExpression {
+        while let HirTy::Ref { inner, .. } = value.ty().clone() {
+            if value.ty().is_formatted_by_runtime() {
+                break;
+            }
+            value = HirExpression::UnaryOp {
+                op: HirUnaryOp::Deref,
+                operand: Box::new(value),
+                ty: *inner,
+                span: *span,
+            };
+        }
+        if value.ty().is_formatted_by_runtime() {
+            return value;
+        }
+
+        let trait_name = spec.kind.trait_name().to_string();
+        spec.kind = FormatKind::Display;
+        let self_ty = value.ty().clone();
+        let self_arg = HirExpression::UnaryOp {
+            op: HirUnaryOp::Ref,
+            operand: Box::new(value),
+            ty: HirTy::Ref { inner: Box::new(self_ty.clone()), mutable: false },
+            span: *span,
+        };
+        HirExpression::MethodCall {
+            method_name: "fmt".to_string(),
+            trait_name,
+            self_ty,
+            args: vec![self_arg],
+            generic_args: Vec::new(),
+            ty: HirTy::String,
+            span: *span,
+        }
+    }
+
+    /// Lower a width or precision taken from a variable, like `w` in `${x:w$}`
+    ///
+    /// Literal counts stay in the format spec.
+    fn lower_format_count(&mut self, count: &Option<Count>, span: &zulon_parser::Span) -> Option<Box<HirExpression>> {
+        match count {
+            Some(Count::Argument(Argument::Name(name))) => Some(Box::new(HirExpression::Variable(
+                name.clone(),
+                self.alloc_id(),
+                HirTy::I32,
+                *span,
+            ))),
+            _ => None,
+        }
+    }
+
     /// Lower a literal
-    fn lower_literal(&mut self, lit: &ast::Literal) -> Result<HirLiteral> {
+    fn lower_literal(&mut self, lit: &ast::Literal, span: &zulon_parser::Span) -> Result<HirLiteral> {
         match lit {
             ast::Literal::Bool(b) => Ok(HirLiteral::Bool(*b)),
-            ast::Literal::Int(n) => Ok(HirLiteral::Integer(*n as u64)),
-            ast::Literal::Float(f) => Ok(HirLiteral::Float(*f)),
+            ast::Literal::Int(n, _) => Ok(HirLiteral::Integer(*n)),
+            ast::Literal::Float(f, _) => Ok(HirLiteral::Float(*f)),
             ast::Literal::Char(c) => Ok(HirLiteral::Char(*c)),
             ast::Literal::String(s) => Ok(HirLiteral::String(s.clone())),
+            ast::Literal::Byte(b) => Ok(HirLiteral::Integer(*b as u128)),
+            ast::Literal::ByteString(_) => Err(LoweringError::UnsupportedFeature {
+                feature: "byte string literal pattern".to_string(),
+                span: *span,
+            }),
             ast::Literal::Null => Ok(HirLiteral::Unit),
         }
     }
@@ -861,10 +1905,38 @@ impl SimpleLoweringContext {
             HirLiteral::Float(_) => HirTy::F64,
             HirLiteral::Char(_) => HirTy::Char,
             HirLiteral::String(_) => HirTy::String,
+            HirLiteral::ByteString(bytes) => HirTy::Array {
+                inner: Box::new(HirTy::U8),
+                len: Some(bytes.len() as u64),
+            },
             HirLiteral::Unit => HirTy::Unit,
         }
     }
 
+    /// Get the type named by a literal's suffix (`42u8`, `1.5f32`), if any
+    fn literal_suffix_type(&self, lit: &ast::Literal) -> Option<HirTy> {
+        match lit {
+            ast::Literal::Int(_, Some(suffix)) => Some(match suffix {
+                ast::IntSuffix::I8 => HirTy::I8,
+                ast::IntSuffix::I16 => HirTy::I16,
+                ast::IntSuffix::I32 => HirTy::I32,
+                ast::IntSuffix::I64 => HirTy::I64,
+                ast::IntSuffix::I128 => HirTy::I128,
+                ast::IntSuffix::ISize => HirTy::ISize,
+                ast::IntSuffix::U8 => HirTy::U8,
+                ast::IntSuffix::U16 => HirTy::U16,
+                ast::IntSuffix::U32 => HirTy::U32,
+                ast::IntSuffix::U64 => HirTy::U64,
+                ast::IntSuffix::U128 => HirTy::U128,
+                ast::IntSuffix::USize => HirTy::USize,
+            }),
+            ast::Literal::Float(_, Some(ast::FloatSuffix::F32)) => Some(HirTy::F32),
+            ast::Literal::Float(_, Some(ast::FloatSuffix::F64)) => Some(HirTy::F64),
+            ast::Literal::Byte(_) => Some(HirTy::U8),
+            _ => None,
+        }
+    }
+
     /// Lower a binary operator
     fn lower_bin_op(&mut self, op: &ast::BinaryOp) -> Result<HirBinOp> {
         match op {
@@ -905,45 +1977,194 @@ impl SimpleLoweringContext {
         }
     }
 
-    /// Lower a type annotation (simplified version)
+    /// Lower a type annotation
     fn lower_type(&self, ty: Option<&ast::Type>) -> Result<HirTy> {
-        match ty {
-            None => Ok(HirTy::Unit),  // TODO: Use proper type variable
-            Some(ast_type) => {
-                // For now, just map common types
-                // TODO: Implement proper type lowering
-                match ast_type {
-                    ast::Type::Simple(ident) => match ident.name.as_str() {
-                        "i32" => Ok(HirTy::I32),
-                        "i64" => Ok(HirTy::I64),
-                        "f64" => Ok(HirTy::F64),
-                        "bool" => Ok(HirTy::Bool),
-                        "string" => Ok(HirTy::String),
-                        "char" => Ok(HirTy::Char),
-                        _ => Ok(HirTy::Unit),  // TODO: Unknown types
-                    },
-                    ast::Type::Path(path) if !path.is_empty() => {
-                        // For now, just use the last component
-                        let last = path.last().unwrap();
-                        match last.name.as_str() {
-                            "i32" => Ok(HirTy::I32),
-                            "i64" => Ok(HirTy::I64),
-                            "f64" => Ok(HirTy::F64),
-                            "bool" => Ok(HirTy::Bool),
-                            "string" => Ok(HirTy::String),
-                            "char" => Ok(HirTy::Char),
-                            _ => Ok(HirTy::Unit),  // TODO: Unknown types
-                        }
-                    }
-                    _ => Ok(HirTy::Unit),  // TODO: Handle other types
+        let Some(ast_type) = ty else {
+            return Ok(HirTy::Unit);  // TODO: Use proper type variable
+        };
+
+        // A type alias, as the type checker expanded it
+        let alias = match ast_type {
+            ast::Type::Simple(name) => Some(name),
+            ast::Type::PathGeneric(path, _) if path.len() == 1 => path.first(),
+            _ => None,
+        };
+        if let Some(ty) = alias.and_then(|name| self.typeck.alias_type(&name.span)) {
+            return Ok(self.inferred_type(ty));
+        }
+
+        Ok(match ast_type {
+            ast::Type::Simple(ident) => self.lower_named_type(&ident.name, Vec::new()),
+            // An associated type, `T::Item`, as the type checker resolved it
+            ast::Type::Path(path) | ast::Type::PathGeneric(path, None)
+                if path.len() == 2 && self.typeck.assoc_type_path(&path[1].span).is_some() =>
+            {
+                let ty = self.typeck.assoc_type_path(&path[1].span).unwrap();
+                self.inferred_type(ty)
+            }
+            ast::Type::Path(path) => match path.last() {
+                Some(last) => self.lower_named_type(&last.name, Vec::new()),
+                None => HirTy::Unit,
+            },
+            ast::Type::PathGeneric(path, args) => {
+                let generics = args.iter()
+                    .flatten()
+                    .map(|arg| self.lower_type(Some(arg)))
+                    .collect::<Result<Vec<_>>>()?;
+                match path.last() {
+                    Some(last) => self.lower_named_type(&last.name, generics),
+                    None => HirTy::Unit,
                 }
             }
+            ast::Type::Tuple(tys) => HirTy::Tuple(
+                tys.iter().map(|ty| self.lower_type(Some(ty))).collect::<Result<_>>()?,
+            ),
+            ast::Type::Array(inner, len) => HirTy::Array {
+                inner: Box::new(self.lower_type(Some(inner))?),
+                // Evaluated by the type checker, if the context has one
+                len: len.as_deref().and_then(|len| {
+                    self.typeck.array_len(&len.span).or(match &len.kind {
+                        ast::ExpressionKind::Literal(ast::Literal::Int(n, _)) => Some(*n as u64),
+                        _ => None,
+                    })
+                }),
+            },
+            ast::Type::Slice(inner) => HirTy::Slice(Box::new(self.lower_type(Some(inner))?)),
+            ast::Type::Ref(inner, mutable) => HirTy::Ref {
+                inner: Box::new(self.lower_type(Some(inner))?),
+                mutable: *mutable,
+            },
+            ast::Type::Pointer(inner, mutable) => HirTy::Ptr {
+                inner: Box::new(self.lower_type(Some(inner))?),
+                mutable: *mutable,
+            },
+            ast::Type::Function(params, return_type) => HirTy::Function {
+                params: params.iter().map(|ty| self.lower_type(Some(ty))).collect::<Result<_>>()?,
+                return_type: Box::new(self.lower_type(Some(return_type))?),
+            },
+            ast::Type::Optional(inner) => HirTy::Optional(Box::new(self.lower_type(Some(inner))?)),
+            // The error half of `T | E` is carried by `HirFunction::error_type`
+            ast::Type::Pipe(ok, _) => self.lower_type(Some(ok))?,
+            ast::Type::Never => HirTy::Never,
+            ast::Type::Unit => HirTy::Unit,
+            ast::Type::TraitObject(inner) => HirTy::TraitObject(type_name(inner).map(str::to_string).into_iter().collect()),
+            ast::Type::ImplTrait(_) => HirTy::Unit,  // TODO: impl Trait
+        })
+    }
+
+    /// Lower a type written by name: a primitive, a type parameter in
+    /// scope, or a struct or enum
+    fn lower_named_type(&self, name: &str, generics: Vec<HirTy>) -> HirTy {
+        match name {
+            "bool" => HirTy::Bool,
+            "i8" => HirTy::I8,
+            "i16" => HirTy::I16,
+            "i32" => HirTy::I32,
+            "i64" => HirTy::I64,
+            "i128" => HirTy::I128,
+            "isize" => HirTy::ISize,
+            "u8" => HirTy::U8,
+            "u16" => HirTy::U16,
+            "u32" => HirTy::U32,
+            "u64" => HirTy::U64,
+            "u128" => HirTy::U128,
+            "usize" => HirTy::USize,
+            "f32" => HirTy::F32,
+            "f64" => HirTy::F64,
+            "char" => HirTy::Char,
+            "str" | "string" | "String" => HirTy::String,
+            "Self" if self.self_ty.is_some() => self.self_ty.as_ref().map(HirTy::from_inferred).unwrap(),
+            _ if generics.is_empty() && self.generic_params.iter().any(|param| param == name) => {
+                HirTy::Param(name.to_string())
+            }
+            _ if self.enums.contains_key(name) => HirTy::Enum { name: name.to_string(), generics },
+            _ => HirTy::Struct { name: name.to_string(), generics },
         }
     }
 }
 
+/// The name of the trait or type a type refers to by name
+fn type_name(ty: &ast::Type) -> Option<&str> {
+    match ty {
+        ast::Type::Simple(name) => Some(&name.name),
+        ast::Type::Path(path) | ast::Type::PathGeneric(path, _) => path.last().map(|name| name.name.as_str()),
+        _ => None,
+    }
+}
+
 /// Convenience function to lower AST to HIR
 pub fn lower_ast_simple(ast: &ast::Ast) -> Result<HirCrate> {
     let mut ctx = SimpleLoweringContext::new();
     ctx.lower_ast(ast)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zulon_parser::Parser;
+
+    fn lower_main_body(source: &str) -> HirBlock {
+        let ast = Parser::from_source(source).parse().expect("failed to parse");
+        let hir = lower_ast_simple(&ast).expect("failed to lower");
+        match hir.items.into_iter().next() {
+            Some(HirItem::Function(func)) => func.body,
+            other => panic!("expected function, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_if_let_lowers_to_match() {
+        let body = lower_main_body(
+            "fn main() -> i32 { let pair = (1, 2); if let (a, b) = pair { a } else { 0 } }",
+        );
+        match body.trailing_expr {
+            Some(HirExpression::Match { arms, .. }) => {
+                assert_eq!(arms.len(), 2);
+                assert!(matches!(arms[0].pattern, HirPattern::Tuple(..)));
+                assert!(matches!(arms[1].pattern, HirPattern::Wildcard(_)));
+            }
+            other => panic!("expected match, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_while_let_lowers_to_loop_with_break() {
+        let body = lower_main_body("fn main() { while let Some(x) = next() { x; } }");
+        let loop_body = match body.trailing_expr {
+            Some(HirExpression::Loop { body, .. }) => body,
+            other => panic!("expected loop, found {:?}", other),
+        };
+        match &loop_body.statements[0] {
+            HirStatement::Semi(HirExpression::Match { arms, .. }) => {
+                assert!(matches!(&arms[0].pattern, HirPattern::EnumVariant { variant_name, .. } if variant_name == "Some"));
+                assert!(matches!(arms[1].body, HirExpression::Break(..)));
+            }
+            other => panic!("expected match statement, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_let_else_binds_each_name() {
+        let body = lower_main_body(
+            "fn main() -> i32 { let (a, b) = pair() else { return 0; }; a + b }",
+        );
+        let names: Vec<_> = body
+            .statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                HirStatement::Local(local) => Some(local.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        // The matching arm fills a temporary per name, so the else block
+        // is lowered once
+        assert_eq!(names.len(), 4);
+        assert!(names[0].starts_with("__let_else_") && names[0].ends_with("_a"));
+        assert!(names[1].starts_with("__let_else_") && names[1].ends_with("_b"));
+        assert_eq!(&names[2..], &["a", "b"]);
+        let matches = body.statements.iter()
+            .filter(|stmt| matches!(stmt, HirStatement::Semi(HirExpression::Match { .. })))
+            .count();
+        assert_eq!(matches, 1);
+    }
+}
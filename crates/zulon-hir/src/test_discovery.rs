@@ -122,7 +122,7 @@ mod tests {
         };
 
         let hir_crate = HirCrate {
-            items: vec![HirItem::Function(func)],
+            items: vec![HirItem::Function(Box::new(func))],
             span: Span::new(Position::new(0, 0), Position::new(0, 0)),
         };
 
@@ -166,7 +166,7 @@ mod tests {
         };
 
         let hir_crate = HirCrate {
-            items: vec![HirItem::Function(func)],
+            items: vec![HirItem::Function(Box::new(func))],
             span: Span::new(Position::new(0, 0), Position::new(0, 0)),
         };
 
@@ -213,7 +213,7 @@ mod tests {
         };
 
         let hir_crate = HirCrate {
-            items: vec![HirItem::Function(func)],
+            items: vec![HirItem::Function(Box::new(func))],
             span: Span::new(Position::new(0, 0), Position::new(0, 0)),
         };
 
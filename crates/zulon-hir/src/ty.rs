@@ -55,6 +55,17 @@ pub enum HirTy {
         generics: Vec<HirTy>,
     },
 
+    // Generic type parameter, replaced during monomorphization
+    Param(String),
+
+    // Associated type `name` of the impl of `trait_name` for `base`,
+    // normalized during monomorphization
+    Projection {
+        base: Box<HirTy>,
+        trait_name: String,
+        name: String,
+    },
+
     // Optional
     Optional(Box<HirTy>),
 
@@ -100,6 +111,17 @@ impl HirTy {
         matches!(self, HirTy::F32 | HirTy::F64)
     }
 
+    /// Whether template strings format values of this type with the
+    /// runtime's `zulon_format_*` functions rather than a `fmt` method:
+    /// the scalars up to 64 bits and strings
+    pub fn is_formatted_by_runtime(&self) -> bool {
+        match self {
+            HirTy::I128 | HirTy::U128 => false,
+            HirTy::Ref { inner, .. } | HirTy::Ptr { inner, .. } => matches!(**inner, HirTy::U8),
+            _ => self.is_numeric() || matches!(self, HirTy::Bool | HirTy::Char | HirTy::String),
+        }
+    }
+
     /// Check if type is copy
     pub fn is_copy(&self) -> bool {
         match self {
@@ -123,12 +145,16 @@ impl HirTy {
         match self {
             HirTy::Bool => "bool".to_string(),
             HirTy::I8 => "i8".to_string(),
+            HirTy::I16 => "i16".to_string(),
             HirTy::I32 => "i32".to_string(),
             HirTy::I64 => "i64".to_string(),
+            HirTy::I128 => "i128".to_string(),
             HirTy::ISize => "isize".to_string(),
             HirTy::U8 => "u8".to_string(),
+            HirTy::U16 => "u16".to_string(),
             HirTy::U32 => "u32".to_string(),
             HirTy::U64 => "u64".to_string(),
+            HirTy::U128 => "u128".to_string(),
             HirTy::USize => "usize".to_string(),
             HirTy::F32 => "f32".to_string(),
             HirTy::F64 => "f64".to_string(),
@@ -143,6 +169,16 @@ impl HirTy {
                     format!("&{}", inner.display_name())
                 }
             }
+            HirTy::Ptr { inner, mutable } => {
+                if *mutable {
+                    format!("*mut {}", inner.display_name())
+                } else {
+                    format!("*const {}", inner.display_name())
+                }
+            }
+            HirTy::Array { inner, len: Some(len) } => format!("[{}; {}]", inner.display_name(), len),
+            HirTy::Array { inner, len: None } | HirTy::Slice(inner) => format!("[{}]", inner.display_name()),
+            HirTy::Optional(inner) => format!("{}?", inner.display_name()),
             HirTy::Tuple(tys) => {
                 let inner = tys.iter()
                     .map(|t| t.display_name())
@@ -157,7 +193,7 @@ impl HirTy {
                     .join(", ");
                 format!("fn({}) -> {}", params, return_type.display_name())
             }
-            HirTy::Struct { name, generics } => {
+            HirTy::Struct { name, generics } | HirTy::Enum { name, generics } => {
                 if generics.is_empty() {
                     name.clone()
                 } else {
@@ -168,9 +204,59 @@ impl HirTy {
                     format!("{}<{}>", name, gens)
                 }
             }
+            HirTy::Param(name) => name.clone(),
+            HirTy::Projection { base, trait_name, name } => {
+                format!("<{} as {}>::{}", base.display_name(), trait_name, name)
+            }
+            HirTy::TraitObject(traits) => format!("dyn {}", traits.join(" + ")),
             _ => format!("{:?}", self),
         }
     }
+
+    /// Convert a type recorded by the type checker, defaulting type
+    /// variables that inference left unconstrained to `i32`
+    pub fn from_inferred(ty: &zulon_typeck::Ty) -> Self {
+        use zulon_typeck::Ty;
+
+        match ty {
+            Ty::TyVar(_) | Ty::IntVar(_) => HirTy::I32,
+            Ty::FloatVar(_) => HirTy::F64,
+            Ty::Effect(_) => HirTy::Unit,
+            Ty::Ref { inner, mutable } => HirTy::Ref {
+                inner: Box::new(Self::from_inferred(inner)),
+                mutable: *mutable,
+            },
+            Ty::Ptr { inner, mutable } => HirTy::Ptr {
+                inner: Box::new(Self::from_inferred(inner)),
+                mutable: *mutable,
+            },
+            Ty::Array { inner, len } => HirTy::Array {
+                inner: Box::new(Self::from_inferred(inner)),
+                len: *len,
+            },
+            Ty::Slice(inner) => HirTy::Slice(Box::new(Self::from_inferred(inner))),
+            Ty::Tuple(tys) => HirTy::Tuple(tys.iter().map(Self::from_inferred).collect()),
+            Ty::Function { params, return_type, .. } => HirTy::Function {
+                params: params.iter().map(Self::from_inferred).collect(),
+                return_type: Box::new(Self::from_inferred(return_type)),
+            },
+            Ty::Struct { name, generics } => HirTy::Struct {
+                name: name.name.clone(),
+                generics: generics.iter().map(Self::from_inferred).collect(),
+            },
+            Ty::Enum { name, generics } => HirTy::Enum {
+                name: name.name.clone(),
+                generics: generics.iter().map(Self::from_inferred).collect(),
+            },
+            Ty::Optional(inner) => HirTy::Optional(Box::new(Self::from_inferred(inner))),
+            Ty::Projection { base, trait_name, name } => HirTy::Projection {
+                base: Box::new(Self::from_inferred(base)),
+                trait_name: trait_name.clone(),
+                name: name.clone(),
+            },
+            _ => ty.clone().into(),
+        }
+    }
 }
 
 impl fmt::Display for HirTy {
@@ -257,17 +343,22 @@ impl From<zulon_typeck::Ty> for HirTy {
                 HirTy::Optional(Box::new((*inner).into()))
             }
 
-            zulon_typeck::Ty::TraitObject(inner) => {
-                // For now, convert to simplified trait object
-                HirTy::TraitObject(vec![format!("{:?}", *inner)])
-            }
+            zulon_typeck::Ty::Param(name) => HirTy::Param(name),
+
+            zulon_typeck::Ty::Projection { base, trait_name, name } => HirTy::Projection {
+                base: Box::new((*base).into()),
+                trait_name,
+                name,
+            },
+
+            zulon_typeck::Ty::TraitObject(trait_name) => HirTy::TraitObject(vec![trait_name]),
 
             zulon_typeck::Ty::ImplTrait(inner) => {
                 HirTy::ImplTrait(vec![format!("{:?}", *inner)])
             }
 
             // Type variables should be resolved by now
-            zulon_typeck::Ty::TyVar(id) => {
+            zulon_typeck::Ty::TyVar(id) | zulon_typeck::Ty::IntVar(id) | zulon_typeck::Ty::FloatVar(id) => {
                 panic!("Type variable ?{} not resolved during lowering", id)
             }
 
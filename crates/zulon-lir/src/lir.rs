@@ -73,7 +73,7 @@ impl LirFunction {
             // Add phi node for each parameter
             entry.phi_nodes.insert(reg, LirPhi {
                 def: reg,
-                sources: vec![(reg, entry_block)],
+                sources: vec![(Some(reg), entry_block)],
                 ty: func.param_types[i].clone(),
             });
         }
@@ -147,8 +147,9 @@ pub struct LirPhi {
     /// The virtual register being defined
     pub def: VReg,
 
-    /// Sources: (vreg, predecessor_block)
-    pub sources: Vec<(VReg, LirNodeId)>,
+    /// Sources: (vreg, predecessor_block); `None` is undef, for a
+    /// predecessor that produces no value
+    pub sources: Vec<(Option<VReg>, LirNodeId)>,
 
     /// Type of the phi node
     pub ty: LirTy,
@@ -213,11 +214,59 @@ pub enum LirInstruction {
         ty: LirTy,
     },
 
-    /// Function call
+    /// Insert `value` as field `index` of the struct value `aggregate`,
+    /// or of an undefined struct value when `aggregate` is `None`
+    InsertValue {
+        dest: VReg,
+        aggregate: Option<VReg>,
+        value: VReg,
+        value_ty: LirTy,
+        index: usize,
+        ty: LirTy,
+    },
+
+    /// Read field `index` of the struct value `aggregate` of type `ty`
+    ExtractValue {
+        dest: VReg,
+        aggregate: VReg,
+        index: usize,
+        ty: LirTy,
+        field_ty: LirTy,
+    },
+
+    /// Build a value of the enum `ty` holding `variant`, from its payload
+    /// fields in declaration order
+    EnumVariant {
+        dest: VReg,
+        variant: String,
+        fields: Vec<(VReg, LirTy)>,
+        ty: LirTy,
+    },
+
+    /// Read the `i32` discriminant of the enum value `value` of type `ty`
+    Discriminant {
+        dest: VReg,
+        value: VReg,
+        ty: LirTy,
+    },
+
+    /// Read payload field `index` of the enum value `value` of type `ty`,
+    /// which holds `variant`
+    VariantField {
+        dest: VReg,
+        value: VReg,
+        variant: String,
+        index: usize,
+        ty: LirTy,
+        field_ty: LirTy,
+    },
+
+    /// Indirect call through the function pointer `func`
     Call {
         dest: Option<VReg>,
         func: VReg,
         args: Vec<VReg>,
+        arg_types: Vec<LirTy>,
         return_type: LirTy,
     },
 
@@ -236,6 +285,8 @@ pub enum LirInstruction {
         op: LirCmpOp,
         left: VReg,
         right: VReg,
+        /// Type of the operands
+        ty: LirTy,
     },
 
     /// Cast
@@ -263,10 +314,17 @@ pub enum LirInstruction {
 #[derive(Debug, Clone)]
 pub enum LirConstant {
     Bool(bool),
-    Integer(u64),
+    /// The bit pattern, wide enough for every integer type
+    Integer(u128),
     Float(f64),
     String(String),
+    /// A byte string, as a `[u8; N]` value
+    ByteString(Vec<u8>),
     Unit,
+    /// Address of the vtable with the given symbol
+    VTable(String),
+    /// Address of the `static` item with the given name
+    Global(String),
 }
 
 /// Operand (can be register or immediate)
@@ -330,6 +388,8 @@ pub enum LirTerminator {
     /// Switch (for match expressions)
     Switch {
         scrutinee: VReg,
+        /// Type of the scrutinee
+        ty: LirTy,
         targets: Vec<(u64, LirNodeId)>,
         default: LirNodeId,
     },
@@ -11,6 +11,9 @@ use crate::ty::LirTy;
 use zulon_mir::{MirBody, MirFunction, MirInstruction, MirNodeId, MirPlace, MirTerminator};
 use std::collections::{HashMap, HashSet};
 
+/// Sources of a phi node not yet mapped to vregs: (MIR temp, predecessor)
+type PhiSources = Vec<(Option<zulon_mir::TempVar>, MirNodeId)>;
+
 /// Context for lowering MIR to LIR
 pub struct LirLoweringContext {
     /// MIR temp to LIR vreg mapping
@@ -25,8 +28,10 @@ pub struct LirLoweringContext {
     block_returns: HashMap<MirNodeId, VReg>,
     /// Block predecessors tracking (for Phi node detection)
     block_preds: HashMap<MirNodeId, Vec<MirNodeId>>,
-    /// Pending Phi nodes to add to blocks (block_id -> (vreg, phi))
-    pending_phis: HashMap<MirNodeId, Vec<(VReg, LirPhi)>>,
+    /// Pending Phi nodes to add to blocks (block_id -> (vreg, MIR temp,
+    /// sources)), built once every block is lowered since a predecessor may
+    /// come after the join block; a source with no temp is undef
+    pending_phis: HashMap<MirNodeId, Vec<(VReg, zulon_mir::TempVar, PhiSources)>>,
     /// Mutable local variables (need memory operations instead of SSA)
     mutable_locals: HashSet<String>,
     /// Stack slots for mutable locals (local_name -> vreg for alloca)
@@ -56,28 +61,6 @@ impl LirLoweringContext {
     pub fn lower_body(&mut self, mir_body: &MirBody) -> Result<LirBody> {
         let mut lir_body = LirBody::new();
 
-        // CRITICAL FIX: Auto-inject common C standard library externals
-        // When using the simplified build pipeline (yan build), these externals
-        // aren't extracted from source code, so we inject them here.
-        //
-        // Inject printf: extern fn printf(format: *u8, ...) -> i32
-        lir_body.externals.push(LirExternal {
-            name: "printf".to_string(),
-            param_types: vec![LirTy::Ptr(Box::new(LirTy::U8))],
-            return_type: LirTy::I32,
-            variadic: true,
-        });
-
-        // Inject scanf: extern fn scanf(format: *u8, ...) -> i32
-        lir_body.externals.push(LirExternal {
-            name: "scanf".to_string(),
-            param_types: vec![LirTy::Ptr(Box::new(LirTy::U8))],
-            return_type: LirTy::I32,
-            variadic: true,
-        });
-
-        eprintln!("DEBUG: Injected {} externals (printf, scanf)", lir_body.externals.len());
-
         for func in &mir_body.functions {
             let lir_func = self.lower_function(func)?;
             lir_body.push_function(lir_func);
@@ -162,10 +145,40 @@ impl LirLoweringContext {
             lir_func.blocks.insert(lir_block_id, lir_block);
         }
 
+        // A phi has the type of the values flowing into it, which may come
+        // from other phis: type those first
+        let mut typed = true;
+        while std::mem::take(&mut typed) {
+            for (_, dest, sources) in self.pending_phis.values().flatten() {
+                if self.temp_types.contains_key(dest) {
+                    continue;
+                }
+                if let Some(ty) = sources.iter().find_map(|(temp, _)| self.temp_types.get(temp.as_ref()?)) {
+                    self.temp_types.insert(*dest, ty.clone());
+                    typed = true;
+                }
+            }
+        }
+
         // Add pending Phi nodes to their respective blocks
-        for (block_id, phis) in self.pending_phis.drain() {
+        for (block_id, phis) in std::mem::take(&mut self.pending_phis) {
             if let Some(lir_block) = lir_func.blocks.get_mut(&block_id) {
-                for (vreg, phi) in phis {
+                for (vreg, dest, sources) in phis {
+                    // Map MIR temps to LIR vregs
+                    let ty = self.temp_types.get(&dest).cloned().unwrap_or(LirTy::I32);
+                    let sources = sources.into_iter()
+                        .map(|(temp, pred_block_id)| {
+                            let src_vreg = temp.map(|temp| {
+                                self.temp_map.get(&temp).copied().unwrap_or(temp as VReg)
+                            });
+                            (src_vreg, pred_block_id)
+                        })
+                        .collect();
+                    let phi = LirPhi {
+                        def: vreg,
+                        sources,
+                        ty,
+                    };
                     lir_block.add_phi(vreg, phi);
                 }
             }
@@ -256,8 +269,12 @@ impl LirLoweringContext {
                     MirInstruction::Load { dest, .. } => Some(*dest),
                     MirInstruction::BinaryOp { dest, .. } => Some(*dest),
                     MirInstruction::UnaryOp { dest, .. } => Some(*dest),
+                    MirInstruction::Cast { dest, .. } => Some(*dest),
                     MirInstruction::Const { dest, .. } => Some(*dest),
                     MirInstruction::FieldAccess { dest, .. } => Some(*dest),
+                    MirInstruction::Variant { dest, .. } => Some(*dest),
+                    MirInstruction::VariantField { dest, .. } => Some(*dest),
+                    MirInstruction::Move { dest, .. } => Some(*dest),
                     _ => None,
                 };
 
@@ -542,11 +559,13 @@ impl LirLoweringContext {
 
                 let lir_value = match value {
                     zulon_mir::MirConstant::Bool(b) => LirConstant::Bool(*b),
-                    zulon_mir::MirConstant::Integer(i) => LirConstant::Integer(*i as u64),
+                    zulon_mir::MirConstant::Integer(i) => LirConstant::Integer(*i as u128),
                     zulon_mir::MirConstant::Float(f) => LirConstant::Float(*f),
                     zulon_mir::MirConstant::String(s) => LirConstant::String(s.clone()),
-                    zulon_mir::MirConstant::Char(c) => LirConstant::Integer(*c as u64),
+                    zulon_mir::MirConstant::ByteString(bytes) => LirConstant::ByteString(bytes.clone()),
+                    zulon_mir::MirConstant::Char(c) => LirConstant::Integer(*c as u128),
                     zulon_mir::MirConstant::Unit => LirConstant::Unit,
+                    zulon_mir::MirConstant::VTable(name) => LirConstant::VTable(name.clone()),
                 };
 
                 Ok(vec![LirInstruction::Const {
@@ -617,11 +636,13 @@ impl LirLoweringContext {
                 if is_comparison {
                     // Generate comparison instruction
                     let lir_cmp_op = self.lower_cmp_op(*op);
+                    let operand_ty = self.temp_types.get(left).cloned().unwrap_or(LirTy::I32);
                     instructions.push(LirInstruction::Cmp {
                         dest: dest_vreg,
                         op: lir_cmp_op,
                         left: left_vreg,
                         right: right_vreg,
+                        ty: operand_ty,
                     });
                 } else {
                     // Regular binary operation (arithmetic, bitwise, or logical)
@@ -638,6 +659,20 @@ impl LirLoweringContext {
                 Ok(instructions)
             }
 
+            MirInstruction::Cast { dest, src, from, to } => {
+                let src_vreg = self.temp_map.get(src).copied().unwrap_or(*src as VReg);
+                let dest_vreg = func.alloc_vreg();
+                self.temp_map.insert(*dest, dest_vreg);
+                self.temp_types.insert(*dest, to.clone().into());
+
+                Ok(vec![LirInstruction::Cast {
+                    dest: dest_vreg,
+                    src: src_vreg,
+                    from: from.clone().into(),
+                    to: to.clone().into(),
+                }])
+            }
+
             MirInstruction::UnaryOp { dest, op, operand, ty } => {
                 let dest_vreg = func.alloc_vreg();
                 let mut instructions = Vec::new();
@@ -677,7 +712,34 @@ impl LirLoweringContext {
                 let lir_op = match op {
                     zulon_mir::MirUnaryOp::Neg => LirUnaryOp::Neg,
                     zulon_mir::MirUnaryOp::Not => LirUnaryOp::Not,
-                    _ => LirUnaryOp::Neg, // Default to Neg for other ops (Ref, Deref, etc.)
+                    // A reference is the address of a stack copy of the value
+                    zulon_mir::MirUnaryOp::Ref | zulon_mir::MirUnaryOp::RefMut => {
+                        let value_ty = match self.temp_types.get(operand).cloned() {
+                            Some(value_ty) => value_ty,
+                            None => match LirTy::from(ty.clone()) {
+                                LirTy::Ptr(inner) => *inner,
+                                other => other,
+                            },
+                        };
+                        instructions.push(LirInstruction::Alloca(crate::lir::LirAlloca {
+                            dest: dest_vreg,
+                            ty: value_ty.clone(),
+                        }));
+                        instructions.push(LirInstruction::Store {
+                            dest: LirOperand::Reg(dest_vreg),
+                            src: operand_vreg,
+                            ty: value_ty,
+                        });
+                        return Ok(instructions);
+                    }
+                    zulon_mir::MirUnaryOp::Deref => {
+                        instructions.push(LirInstruction::Load {
+                            dest: dest_vreg,
+                            src: LirOperand::Reg(operand_vreg),
+                            ty: ty.clone().into(),
+                        });
+                        return Ok(instructions);
+                    }
                 };
 
                 instructions.push(LirInstruction::UnaryOp {
@@ -696,8 +758,14 @@ impl LirLoweringContext {
 
                 self.temp_map.insert(*dest, dest_vreg);
 
-                // Infer type from context (placeholder)
-                let ty = LirTy::I32;
+                // The copy has the type of its source temp when known
+                let ty = match src {
+                    MirPlace::Temp(src_temp) => self.temp_types.get(src_temp).cloned().unwrap_or(LirTy::I32),
+                    _ => LirTy::I32,
+                };
+                if let MirPlace::Temp(_) = src {
+                    self.temp_types.insert(*dest, ty.clone());
+                }
 
                 Ok(vec![LirInstruction::Copy {
                     dest: dest_vreg,
@@ -720,36 +788,19 @@ impl LirLoweringContext {
 
                     if let Some(preds) = self.block_preds.get(&current_block) {
                         for &pred_block_id in preds {
-                            // Get the return value from this predecessor
-                            if let Some(&return_temp) = self.block_returns.get(&pred_block_id) {
-                                // Map MIR temp to LIR vreg - cast to usize
-                                let src_vreg = self.temp_map.get(&(return_temp as zulon_mir::TempVar))
-                                    .copied()
-                                    .unwrap_or(return_temp);
-
-
-                                phi_sources.push((src_vreg, pred_block_id));
-                            } else {
-                                // This predecessor doesn't produce a value
-                                // Use undef (represented by vreg 0, which is always undef)
-                                // In practice, this means the predecessor takes a different path
-                                phi_sources.push((0, pred_block_id)); // 0 = undef
-                            }
+                            // The return value of this predecessor; one that
+                            // doesn't produce a value takes a different path
+                            // and contributes undef
+                            let return_temp = self.block_returns.get(&pred_block_id)
+                                .map(|&temp| temp as zulon_mir::TempVar);
+                            phi_sources.push((return_temp, pred_block_id));
                         }
                     }
 
-                    // Create Phi node and store for later
-                    let phi = LirPhi {
-                        def: dest_vreg,
-                        sources: phi_sources,
-                        ty: LirTy::I32, // TODO: Infer proper type
-                    };
-
-
                     self.pending_phis
                         .entry(current_block)
                         .or_insert_with(Vec::new)
-                        .push((dest_vreg, phi));
+                        .push((dest_vreg, *dest, phi_sources));
 
                     // No instruction needed - Phi is separate
                     Ok(vec![])
@@ -762,10 +813,12 @@ impl LirLoweringContext {
                         self.get_or_alloc_vreg(src, func)
                     };
 
+                    let ty = self.get_place_type(src);
+                    self.temp_types.insert(*dest, ty.clone());
                     Ok(vec![LirInstruction::Copy {
                         dest: dest_vreg,
                         src: src_vreg,
-                        ty: LirTy::I32, // Placeholder
+                        ty,
                     }])
                 }
             }
@@ -813,7 +866,79 @@ impl LirLoweringContext {
                 }])
             }
 
+            MirInstruction::CallVirtual { dest, object, slot, args, return_type } => {
+                // Split the fat pointer, load the method from its vtable slot
+                // and call it with the data pointer as `self`
+                let object_vreg = self.temp_map.get(object).copied().unwrap_or(*object as VReg);
+                let ptr_ty = LirTy::Ptr(Box::new(LirTy::U8));
+                let data_vreg = func.alloc_vreg();
+                let vtable_vreg = func.alloc_vreg();
+                let slot_vreg = func.alloc_vreg();
+                let method_vreg = func.alloc_vreg();
+
+                let mut arg_vregs = vec![data_vreg];
+                let mut arg_types = vec![ptr_ty.clone()];
+                for arg in args {
+                    arg_vregs.push(self.get_or_alloc_vreg(arg, func));
+                    arg_types.push(self.get_place_type(arg));
+                }
+
+                let dest_vreg = dest.map(|_| func.alloc_vreg());
+                if let (Some(d), Some(vreg)) = (dest, dest_vreg) {
+                    self.temp_map.insert(*d, vreg);
+                    self.temp_types.insert(*d, return_type.clone().into());
+                }
+
+                Ok(vec![
+                    LirInstruction::ExtractValue {
+                        dest: data_vreg,
+                        aggregate: object_vreg,
+                        index: 0,
+                        ty: LirTy::trait_object(),
+                        field_ty: ptr_ty.clone(),
+                    },
+                    LirInstruction::ExtractValue {
+                        dest: vtable_vreg,
+                        aggregate: object_vreg,
+                        index: 1,
+                        ty: LirTy::trait_object(),
+                        field_ty: ptr_ty.clone(),
+                    },
+                    LirInstruction::Gep {
+                        dest: slot_vreg,
+                        base: vtable_vreg,
+                        indices: vec![LirOperand::Imm(*slot as u64)],
+                        ty: ptr_ty.clone(),
+                    },
+                    LirInstruction::Load {
+                        dest: method_vreg,
+                        src: LirOperand::Reg(slot_vreg),
+                        ty: ptr_ty,
+                    },
+                    LirInstruction::Call {
+                        dest: dest_vreg,
+                        func: method_vreg,
+                        args: arg_vregs,
+                        arg_types,
+                        return_type: return_type.clone().into(),
+                    },
+                ])
+            }
+
             MirInstruction::Load { dest, src, ty } => {
+                // A static is loaded through its address
+                if let MirPlace::Global(name) = src {
+                    let dest_vreg = func.alloc_vreg();
+                    self.temp_map.insert(*dest, dest_vreg);
+                    self.temp_types.insert(*dest, ty.clone().into());
+                    let (addr, addr_inst) = Self::global_address(name, ty, func);
+                    return Ok(vec![addr_inst, LirInstruction::Load {
+                        dest: dest_vreg,
+                        src: LirOperand::Reg(addr),
+                        ty: ty.clone().into(),
+                    }]);
+                }
+
                 // Check if this is a load from a field (struct/enum field access)
                 if let MirPlace::Field { base, field } = src {
                     // Generate GEP for field access
@@ -917,6 +1042,16 @@ impl LirLoweringContext {
             MirInstruction::Store { dest, src, ty } => {
                 let src_vreg = self.temp_map.get(src).copied().unwrap_or_else(|| *src as VReg);
 
+                // A static is stored through its address
+                if let MirPlace::Global(name) = dest {
+                    let (addr, addr_inst) = Self::global_address(name, ty, func);
+                    return Ok(vec![addr_inst, LirInstruction::Store {
+                        dest: LirOperand::Reg(addr),
+                        src: src_vreg,
+                        ty: ty.clone().into(),
+                    }]);
+                }
+
                 // Check if this is a store to a mutable local
                 if let MirPlace::Local(name) = dest {
                     if self.mutable_locals.contains(name) {
@@ -953,14 +1088,31 @@ impl LirLoweringContext {
             }
 
             MirInstruction::FieldAccess { dest, base, field_name: _, field_index, ty } => {
-                // Lower MIR FieldAccess to LIR GEP + Load
                 let base_vreg = self.temp_map.get(base).copied().unwrap_or_else(|| *base as VReg);
                 let dest_vreg = func.alloc_vreg();
-                let gep_vreg = func.alloc_vreg();
 
                 self.temp_map.insert(*dest, dest_vreg);
                 self.temp_types.insert(*dest, ty.clone().into());
 
+                // A struct value is read directly; anything else is a pointer
+                if let Some(struct_ty @ LirTy::Struct { .. }) = self.temp_types.get(base).cloned() {
+                    return Ok(vec![LirInstruction::ExtractValue {
+                        dest: dest_vreg,
+                        aggregate: base_vreg,
+                        index: *field_index,
+                        ty: struct_ty,
+                        field_ty: ty.clone().into(),
+                    }]);
+                }
+
+                // Lower MIR FieldAccess to LIR GEP + Load, indexing into the
+                // struct the base points to
+                let gep_vreg = func.alloc_vreg();
+                let gep_ty = match self.temp_types.get(base) {
+                    Some(LirTy::Ptr(pointee)) if matches!(**pointee, LirTy::Struct { .. }) => (**pointee).clone(),
+                    _ => ty.clone().into(),
+                };
+
                 // Generate GEP to get pointer to field, then Load the value
                 Ok(vec![
                     LirInstruction::Gep {
@@ -970,7 +1122,7 @@ impl LirLoweringContext {
                             LirOperand::Imm(0),  // struct pointer
                             LirOperand::Imm(*field_index as u64),  // field index
                         ],
-                        ty: ty.clone().into(),
+                        ty: gep_ty,
                     },
                     LirInstruction::Load {
                         dest: dest_vreg,
@@ -980,6 +1132,84 @@ impl LirLoweringContext {
                 ])
             }
 
+            MirInstruction::Aggregate { dest, fields, ty } => {
+                // Start from an undefined struct and insert each field in turn
+                let ty: LirTy = ty.clone().into();
+                let mut instructions = Vec::new();
+                let mut aggregate = None;
+                for (index, (field, field_ty)) in fields.iter().enumerate() {
+                    let value = self.temp_map.get(field).copied().unwrap_or(*field as VReg);
+                    let next = func.alloc_vreg();
+                    instructions.push(LirInstruction::InsertValue {
+                        dest: next,
+                        aggregate,
+                        value,
+                        value_ty: field_ty.clone().into(),
+                        index,
+                        ty: ty.clone(),
+                    });
+                    aggregate = Some(next);
+                }
+
+                let dest_vreg = match aggregate {
+                    Some(vreg) => vreg,
+                    None => {
+                        let vreg = func.alloc_vreg();
+                        instructions.push(LirInstruction::Const {
+                            dest: vreg,
+                            value: LirConstant::Unit,
+                            ty: ty.clone(),
+                        });
+                        vreg
+                    }
+                };
+                self.temp_map.insert(*dest, dest_vreg);
+                self.temp_types.insert(*dest, ty);
+                Ok(instructions)
+            }
+
+            MirInstruction::Variant { dest, variant, fields, ty } => {
+                let fields = fields.iter()
+                    .map(|(field, field_ty)| (self.temp_map.get(field).copied().unwrap_or(*field as VReg), field_ty.clone().into()))
+                    .collect();
+                let dest_vreg = func.alloc_vreg();
+                self.temp_map.insert(*dest, dest_vreg);
+                self.temp_types.insert(*dest, ty.clone().into());
+                Ok(vec![LirInstruction::EnumVariant {
+                    dest: dest_vreg,
+                    variant: variant.clone(),
+                    fields,
+                    ty: ty.clone().into(),
+                }])
+            }
+
+            MirInstruction::Discriminant { dest, value, enum_ty } => {
+                let value_vreg = self.temp_map.get(value).copied().unwrap_or(*value as VReg);
+                let dest_vreg = func.alloc_vreg();
+                self.temp_map.insert(*dest, dest_vreg);
+                self.temp_types.insert(*dest, LirTy::I32);
+                Ok(vec![LirInstruction::Discriminant {
+                    dest: dest_vreg,
+                    value: value_vreg,
+                    ty: enum_ty.clone().into(),
+                }])
+            }
+
+            MirInstruction::VariantField { dest, value, variant, field_index, enum_ty, ty } => {
+                let value_vreg = self.temp_map.get(value).copied().unwrap_or(*value as VReg);
+                let dest_vreg = func.alloc_vreg();
+                self.temp_map.insert(*dest, dest_vreg);
+                self.temp_types.insert(*dest, ty.clone().into());
+                Ok(vec![LirInstruction::VariantField {
+                    dest: dest_vreg,
+                    value: value_vreg,
+                    variant: variant.clone(),
+                    index: *field_index,
+                    ty: enum_ty.clone().into(),
+                    field_ty: ty.clone().into(),
+                }])
+            }
+
             MirInstruction::PerformEffect { dest, effect_name: _, operation_name: _, args: _, return_type } => {
                 // Effect operations are currently stubbed
                 // In a full implementation, this would:
@@ -1079,7 +1309,7 @@ impl LirLoweringContext {
                 })
             }
 
-            MirTerminator::Switch { scrutinee, targets, default } => {
+            MirTerminator::Switch { scrutinee, ty, targets, default } => {
                 let scrutinee_vreg = self.temp_map.get(scrutinee).copied().unwrap_or(*scrutinee as VReg);
 
                 // Convert MIR constants to u64 values for LIR
@@ -1105,6 +1335,7 @@ impl LirLoweringContext {
 
                 Ok(LirTerminator::Switch {
                     scrutinee: scrutinee_vreg,
+                    ty: ty.clone().into(),
                     targets: lir_targets,
                     default: *default,
                 })
@@ -1132,6 +1363,17 @@ impl LirLoweringContext {
         }
     }
 
+    /// Materialize the address of a static holding a value of type `ty`
+    fn global_address(name: &str, ty: &zulon_mir::MirTy, func: &mut LirFunction) -> (VReg, LirInstruction) {
+        let addr = func.alloc_vreg();
+        let inst = LirInstruction::Const {
+            dest: addr,
+            value: LirConstant::Global(name.to_string()),
+            ty: LirTy::Ptr(Box::new(ty.clone().into())),
+        };
+        (addr, inst)
+    }
+
     /// Get or allocate a virtual register for a place
     fn get_or_alloc_vreg(&mut self, place: &zulon_mir::MirPlace, func: &mut LirFunction) -> VReg {
         match place {
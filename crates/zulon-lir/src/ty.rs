@@ -38,9 +38,24 @@ pub enum LirTy {
         fields: Vec<LirTy>,
         size: u64,
     },
+
+    // Enums (laid out by codegen from their variants)
+    Enum {
+        name: String,
+    },
 }
 
 impl LirTy {
+    /// The type of a trait object reference: a data pointer and a pointer
+    /// to the vtable
+    pub fn trait_object() -> Self {
+        LirTy::Struct {
+            name: "dyn".to_string(),
+            fields: vec![LirTy::Ptr(Box::new(LirTy::U8)), LirTy::Ptr(Box::new(LirTy::U8))],
+            size: 16,
+        }
+    }
+
     /// Get size in bytes
     pub fn size(&self) -> u64 {
         match self {
@@ -54,6 +69,7 @@ impl LirTy {
             LirTy::Never => 0,
             LirTy::Array { inner, len } => inner.size() * len,
             LirTy::Struct { size, .. } => *size,
+            LirTy::Enum { .. } => 8, // Placeholder
         }
     }
 
@@ -70,6 +86,7 @@ impl LirTy {
             LirTy::Never => 1,
             LirTy::Array { inner, .. } => inner.align(),
             LirTy::Struct { .. } => 8, // Default struct alignment
+            LirTy::Enum { .. } => 8,
         }
     }
 
@@ -91,7 +108,7 @@ impl LirTy {
             LirTy::Array { inner, len } => {
                 format!("[{}; {}]", inner.display_name(), len)
             }
-            LirTy::Struct { name, .. } => name.clone(),
+            LirTy::Struct { name, .. } | LirTy::Enum { name } => name.clone(),
             _ => format!("{:?}", self),
         }
     }
@@ -135,6 +152,14 @@ impl From<zulon_mir::MirTy> for LirTy {
             zulon_mir::MirTy::Unit => LirTy::Unit,
             zulon_mir::MirTy::Never => LirTy::Never,
 
+            // Trait object references are fat pointers: the data pointer
+            // and the vtable pointer
+            zulon_mir::MirTy::Ref { inner, .. } | zulon_mir::MirTy::Ptr { inner, .. }
+                if matches!(*inner, zulon_mir::MirTy::TraitObject(_)) =>
+            {
+                LirTy::trait_object()
+            }
+
             // Pointers
             zulon_mir::MirTy::Ref { inner, .. } => {
                 LirTy::Ptr(Box::new((*inner).into()))
@@ -195,13 +220,8 @@ impl From<zulon_mir::MirTy> for LirTy {
             zulon_mir::MirTy::Function { .. } => {
                 LirTy::Ptr(Box::new(LirTy::Unit))
             }
-            zulon_mir::MirTy::Enum { name, .. } => {
-                LirTy::Struct {
-                    name,
-                    fields: Vec::new(), // TODO: Extract enum variants
-                    size: 8, // Placeholder
-                }
-            }
+            // Generic enums are named by their instance
+            zulon_mir::MirTy::Enum { name, .. } => LirTy::Enum { name },
             zulon_mir::MirTy::Optional(_) => {
                 LirTy::Struct {
                     name: "Option".to_string(),
@@ -209,6 +229,8 @@ impl From<zulon_mir::MirTy> for LirTy {
                     size: 16, // Size + discriminant
                 }
             }
+            // Only used behind a reference; the pointee is opaque
+            zulon_mir::MirTy::TraitObject(_) => LirTy::U8,
         }
     }
 }
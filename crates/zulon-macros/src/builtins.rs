@@ -0,0 +1,237 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Builtin macros that read the compilation environment
+//!
+//! `include_str!("schema.sql")` and `include_bytes!` embed a file, found
+//! relative to the file the invocation is in, and record it in
+//! [`Expansion::dependencies`](crate::Expansion::dependencies). `env!` and
+//! `option_env!` read environment variables while expanding, and `concat!`
+//! joins literals into one string:
+//!
+//! ```text
+//! const VERSION: &str = concat!("v", env!("APP_VERSION"));
+//! const SCHEMA: &str = include_str!(concat!(env!("SQL_DIR"), "/schema.sql"));
+//! ```
+//!
+//! These macros expand their arguments first, so they accept invocations
+//! of each other. `file!()`, `line!()`, `column!()` and `module_path!()`
+//! describe where they are invoked; inside a macro template, that is the
+//! outermost invocation in the source.
+
+use std::env::VarError;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use crate::{split_arguments, Invocation, MacroError, MacroResult};
+use zulon_parser::lexer::parse_int_literal;
+use zulon_parser::{Span, Token, TokenKind};
+
+/// `include_str!("path")`: the contents of a UTF-8 file as a string literal
+pub fn expand_include_str(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let (bytes, path, span) = include("include_str", input, invocation)?;
+    let text = String::from_utf8(bytes).map_err(|_| MacroError::Include {
+        path: path.display().to_string(),
+        message: "the file is not valid UTF-8; use `include_bytes!` for binary data".to_string(),
+        span,
+    })?;
+    Ok(vec![token(TokenKind::StringLiteral(text.into()), invocation.call_site)])
+}
+
+/// `include_bytes!("path")`: the contents of a file as a byte string literal
+pub fn expand_include_bytes(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let (bytes, _, _) = include("include_bytes", input, invocation)?;
+    Ok(vec![token(TokenKind::ByteStringLiteral(bytes.into()), invocation.call_site)])
+}
+
+/// `env!("NAME")` or `env!("NAME", "message")`: the variable's value
+///
+/// Fails with the message, if given, when the variable is not set.
+pub fn expand_env(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let arguments = string_arguments("env", input, 1..=2, invocation.call_site)?;
+    let (name, span) = &arguments[0];
+    let message = match std::env::var(name) {
+        Ok(value) => return Ok(vec![token(TokenKind::StringLiteral(value.into()), invocation.call_site)]),
+        Err(_) if arguments.len() == 2 => arguments[1].0.clone(),
+        Err(VarError::NotPresent) => format!("environment variable `{}` not defined at compile time", name),
+        Err(VarError::NotUnicode(_)) => format!("environment variable `{}` is not valid unicode", name),
+    };
+    Err(MacroError::Env { message, span: *span })
+}
+
+/// `option_env!("NAME")`: `Some("value")`, or `None` when the variable is not set
+pub fn expand_option_env(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let arguments = string_arguments("option_env", input, 1..=1, invocation.call_site)?;
+    let (name, span) = &arguments[0];
+    let call_site = invocation.call_site;
+    match std::env::var(name) {
+        Ok(value) => Ok(vec![
+            token(TokenKind::Ident("Some".into()), call_site),
+            token(TokenKind::LeftParen, call_site),
+            token(TokenKind::StringLiteral(value.into()), call_site),
+            token(TokenKind::RightParen, call_site),
+        ]),
+        Err(VarError::NotPresent) => Ok(vec![token(TokenKind::Ident("None".into()), call_site)]),
+        Err(VarError::NotUnicode(_)) => Err(MacroError::Env {
+            message: format!("environment variable `{}` is not valid unicode", name),
+            span: *span,
+        }),
+    }
+}
+
+/// `concat!(lit, ...)`: string, character, integer, float and boolean
+/// literals joined into a string literal
+pub fn expand_concat(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let mut text = String::new();
+    for argument in split_arguments(input) {
+        let (negative, value) = match argument {
+            [Token { kind: TokenKind::Minus, .. }, value] => (true, value),
+            [value] => (false, value),
+            _ => return Err(not_a_literal(argument, invocation.call_site)),
+        };
+        match &value.kind {
+            TokenKind::IntLiteral(digits) => {
+                if negative {
+                    text.push('-');
+                }
+                match parse_int_literal(digits) {
+                    Ok((value, _)) => text.push_str(&value.to_string()),
+                    Err(_) => text.push_str(digits),
+                }
+            }
+            TokenKind::FloatLiteral(digits) => {
+                if negative {
+                    text.push('-');
+                }
+                // Drop the `f32`/`f64` suffix
+                let end = digits
+                    .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+                    .unwrap_or(digits.len());
+                text.push_str(&digits[..end]);
+            }
+            TokenKind::StringLiteral(value) if !negative => text.push_str(value),
+            TokenKind::CharLiteral(value) if !negative => text.push(*value),
+            TokenKind::True if !negative => text.push_str("true"),
+            TokenKind::False if !negative => text.push_str("false"),
+            _ => return Err(not_a_literal(argument, invocation.call_site)),
+        }
+    }
+    Ok(vec![token(TokenKind::StringLiteral(text.into()), invocation.call_site)])
+}
+
+/// `file!()`: the path of the source file, as given to the compiler
+pub fn expand_file(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    no_arguments("file", input, invocation.call_site)?;
+    let path = invocation
+        .source_file
+        .map_or_else(|| "<input>".to_string(), |path| path.display().to_string());
+    Ok(vec![token(TokenKind::StringLiteral(path.into()), invocation.call_site)])
+}
+
+/// `line!()`: the line of the invocation, starting at 1
+pub fn expand_line(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    no_arguments("line", input, invocation.call_site)?;
+    let line = (invocation.call_site.start.line - invocation.line_offset).to_string();
+    Ok(vec![token(TokenKind::IntLiteral(line.into()), invocation.call_site)])
+}
+
+/// `column!()`: the column of the invocation, starting at 1
+pub fn expand_column(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    no_arguments("column", input, invocation.call_site)?;
+    let column = invocation.call_site.start.column.to_string();
+    Ok(vec![token(TokenKind::IntLiteral(column.into()), invocation.call_site)])
+}
+
+/// `module_path!()`: the crate and module the invocation is in, like `app::db::schema`
+///
+/// The crate is named after the source file, or `main` without one.
+pub fn expand_module_path(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    no_arguments("module_path", input, invocation.call_site)?;
+    let crate_name = invocation
+        .crate_root
+        .and_then(Path::file_stem)
+        .map_or_else(|| "main".to_string(), |stem| stem.to_string_lossy().into_owned());
+    let path = std::iter::once(crate_name)
+        .chain(invocation.module.iter().cloned())
+        .collect::<Vec<_>>()
+        .join("::");
+    Ok(vec![token(TokenKind::StringLiteral(path.into()), invocation.call_site)])
+}
+
+/// Read the file named by an include macro's argument, recording it as a
+/// dependency; returns the contents, the resolved path and the argument's span
+fn include(name: &str, input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<(Vec<u8>, PathBuf, Span)> {
+    let arguments = string_arguments(name, input, 1..=1, invocation.call_site)?;
+    let (path, span) = &arguments[0];
+
+    // Relative paths start at the directory of the including file
+    let path = match invocation.source_file.and_then(Path::parent) {
+        Some(directory) if Path::new(path).is_relative() => directory.join(path),
+        _ => PathBuf::from(path),
+    };
+    let bytes = std::fs::read(&path).map_err(|error| MacroError::Include {
+        path: path.display().to_string(),
+        message: error.to_string(),
+        span: *span,
+    })?;
+
+    if !invocation.dependencies.contains(&path) {
+        invocation.dependencies.push(path.clone());
+    }
+    Ok((bytes, path, *span))
+}
+
+/// Arguments that must each be a single string literal, with their spans
+fn string_arguments(
+    name: &str,
+    input: &[Token],
+    count: RangeInclusive<usize>,
+    call_site: Span,
+) -> MacroResult<Vec<(String, Span)>> {
+    let arguments = split_arguments(input);
+    let strings: Option<Vec<_>> = arguments
+        .iter()
+        .map(|argument| match argument {
+            [Token { kind: TokenKind::StringLiteral(text), span }] => Some((text.to_string(), *span)),
+            _ => None,
+        })
+        .collect();
+
+    match strings {
+        Some(strings) if count.contains(&strings.len()) => Ok(strings),
+        _ => {
+            let expected = match (count.start(), count.end()) {
+                (1, 1) => "a string literal".to_string(),
+                (min, max) => format!("{} to {} string literals", min, max),
+            };
+            Err(MacroError::InvalidArguments {
+                name: name.to_string(),
+                message: format!("takes {}", expected),
+                span: call_site,
+            })
+        }
+    }
+}
+
+fn no_arguments(name: &str, input: &[Token], call_site: Span) -> MacroResult<()> {
+    if input.is_empty() {
+        return Ok(());
+    }
+    Err(MacroError::InvalidArguments {
+        name: name.to_string(),
+        message: "takes no arguments".to_string(),
+        span: call_site,
+    })
+}
+
+fn not_a_literal(argument: &[Token], call_site: Span) -> MacroError {
+    MacroError::InvalidArguments {
+        name: "concat".to_string(),
+        message: "takes only literals".to_string(),
+        span: argument.first().map_or(call_site, |token| token.span),
+    }
+}
+
+fn token(kind: TokenKind, span: Span) -> Token {
+    Token { kind, span }
+}
@@ -0,0 +1,874 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `#[derive(...)]` for structs and enums
+//!
+//! Derive runs on the AST once `#[cfg]` attributes have been evaluated. Each
+//! trait named in a `#[derive(...)]` attribute becomes an `impl` item placed
+//! right after the struct or enum, as if it had been written by hand:
+//!
+//! ```text
+//! #[derive(Clone)]
+//! struct Pair<T> { first: T, second: T }
+//!
+//! impl<T> Clone for Pair<T> where T: Clone {
+//!     fn clone(self: &Self) -> Self {
+//!         Pair { first: self.first.clone(), second: self.second.clone() }
+//!     }
+//! }
+//! ```
+//!
+//! Every type parameter is bounded by the derived trait, in addition to the
+//! bounds it already has. The derivable traits are those of
+//! `zulon_std_core::traits` plus `Debug`:
+//!
+//! | Trait        | Generated methods                                 |
+//! |--------------|---------------------------------------------------|
+//! | `Clone`      | `clone`: field by field                           |
+//! | `Copy`       | none                                              |
+//! | `PartialEq`  | `eq`: same variant and all fields equal           |
+//! | `Eq`         | none                                              |
+//! | `PartialOrd` | `partial_cmp` and `lt`: lexicographic             |
+//! | `Ord`        | `cmp`: by variant order, then lexicographic       |
+//! | `Hash`       | `hash`: FNV-1a over the variant index and fields  |
+//! | `Debug`      | `fmt`: `Name { field: value }` or `Variant(value)` |
+//!
+//! ```rust
+//! use zulon_macros::derive::expand_derives;
+//! use zulon_parser::ast::ItemKind;
+//! use zulon_parser::Parser;
+//!
+//! let source = "#[derive(Clone, PartialEq)] struct Point { x: i32, y: i32 }";
+//! let mut ast = Parser::from_source(source).parse().unwrap();
+//! assert!(expand_derives(&mut ast).is_empty());
+//! assert_eq!(ast.items.len(), 3);
+//! assert!(matches!(ast.items[1].kind, ItemKind::Impl(_)));
+//! ```
+
+use std::borrow::BorrowMut;
+use std::cell::Cell;
+use zulon_parser::ast::visit_mut::{self, MutVisitor};
+use zulon_parser::ast::*;
+use zulon_parser::FormatSpec;
+
+/// The traits `#[derive]` can implement
+pub const DERIVABLE: &[&str] = &["Clone", "Copy", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "Debug"];
+
+/// FNV-1a offset basis, the hash of a value without fields
+const FNV_OFFSET: u128 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a prime
+const FNV_PRIME: u128 = 0x0100_0000_01b3;
+
+/// Invalid `#[derive]` attribute
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum DeriveError {
+    #[error("cannot find derive macro `{name}`")]
+    Unknown { name: String, span: Span },
+
+    #[error("`derive` may only be applied to structs and enums")]
+    InvalidTarget { span: Span },
+
+    #[error("malformed `derive` attribute: expected a list of trait names")]
+    Malformed { span: Span },
+}
+
+impl DeriveError {
+    /// The attribute or trait name the error was found at
+    pub fn span(&self) -> Span {
+        match self {
+            DeriveError::Unknown { span, .. }
+            | DeriveError::InvalidTarget { span }
+            | DeriveError::Malformed { span } => *span,
+        }
+    }
+}
+
+/// Add the impls requested by `#[derive(...)]` attributes
+///
+/// The attributes are removed from the items they expanded. Returns the
+/// invalid attributes found; the traits that could be derived still are.
+pub fn expand_derives(ast: &mut Ast) -> Vec<DeriveError> {
+    let mut expander = DeriveExpander { errors: Vec::new() };
+    expander.visit_ast(ast);
+    expander.errors
+}
+
+struct DeriveExpander {
+    errors: Vec<DeriveError>,
+}
+
+impl DeriveExpander {
+    /// Insert the derived impls of each item right after it
+    fn expand_items<T: BorrowMut<Item> + From<Item>>(&mut self, items: &mut Vec<T>) {
+        let mut i = 0;
+        while i < items.len() {
+            let derived = self.derive(items[i].borrow_mut());
+            i += 1;
+            let count = derived.len();
+            items.splice(i..i, derived.into_iter().map(T::from));
+            i += count;
+        }
+    }
+
+    /// The derived impls of an item, removing its `#[derive]` attributes
+    fn derive(&mut self, item: &mut Item) -> Vec<Item> {
+        let mut traits = Vec::new();
+        let mut attribute_span = None;
+        let errors = &mut self.errors;
+        item.attributes.retain(|attribute| {
+            if attribute.name.name != "derive" {
+                return true;
+            }
+            attribute_span.get_or_insert(attribute.name.span);
+            for arg in &attribute.args {
+                match arg {
+                    AttributeArg::Ident(name) if DERIVABLE.contains(&name.name.as_str()) => {
+                        traits.push(name.clone());
+                    }
+                    AttributeArg::Ident(name) => errors.push(DeriveError::Unknown {
+                        name: name.name.clone(),
+                        span: name.span,
+                    }),
+                    _ => errors.push(DeriveError::Malformed {
+                        span: attribute.name.span,
+                    }),
+                }
+            }
+            false
+        });
+
+        let (name, generics, shape) = match &item.kind {
+            ItemKind::Struct(struct_def) => (
+                &struct_def.name,
+                struct_def.generics.as_ref(),
+                Shape::Struct(Fields::of_struct(&struct_def.fields)),
+            ),
+            ItemKind::Enum(enum_def) => (
+                &enum_def.name,
+                enum_def.generics.as_ref(),
+                Shape::Enum(
+                    enum_def
+                        .variants
+                        .iter()
+                        .map(|variant| (&variant.name, Fields::of_variant(&variant.name, &variant.fields)))
+                        .collect(),
+                ),
+            ),
+            _ => {
+                if let Some(span) = attribute_span {
+                    self.errors.push(DeriveError::InvalidTarget { span });
+                }
+                return Vec::new();
+            }
+        };
+
+        // The impls share field spans, so they count their nodes together
+        let nodes = Cell::new(0);
+        traits
+            .iter()
+            .map(|trait_name| {
+                let deriving = Deriving {
+                    span: trait_name.span,
+                    nodes: &nodes,
+                    type_name: name,
+                    shape: &shape,
+                };
+                Item {
+                    span: trait_name.span,
+                    attributes: Vec::new(),
+                    visibility: Visibility::Private,
+                    kind: ItemKind::Impl(deriving.implement(trait_name, generics)),
+                }
+            })
+            .collect()
+    }
+}
+
+impl MutVisitor for DeriveExpander {
+    fn visit_ast(&mut self, ast: &mut Ast) {
+        self.expand_items(&mut ast.items);
+        visit_mut::walk_ast(self, ast);
+    }
+
+    fn visit_module(&mut self, module: &mut Module) {
+        if let Some(items) = &mut module.items {
+            self.expand_items(items);
+        }
+        visit_mut::walk_module(self, module);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        let mut i = 0;
+        while i < block.statements.len() {
+            let derived = match &mut block.statements[i].kind {
+                StatementKind::Item(item) => self.derive(item),
+                _ => Vec::new(),
+            };
+            i += 1;
+            let count = derived.len();
+            let statements = derived.into_iter().map(|item| Statement {
+                span: item.span,
+                kind: StatementKind::Item(Box::new(item)),
+            });
+            block.statements.splice(i..i, statements);
+            i += count;
+        }
+        visit_mut::walk_block(self, block);
+    }
+}
+
+/// The fields of a struct or of one enum variant
+enum Fields<'a> {
+    /// `{ a: A, b: B }`
+    Named(Vec<&'a Identifier>),
+    /// `(A, B)`, by count, and the name of their variant
+    Unnamed(usize, &'a Identifier),
+    /// A variant without fields
+    Unit,
+}
+
+impl<'a> Fields<'a> {
+    fn of_struct(fields: &'a [StructField]) -> Self {
+        Fields::Named(fields.iter().map(|field| &field.name).collect())
+    }
+
+    fn of_variant(variant: &'a Identifier, fields: &'a [VariantField]) -> Self {
+        match fields.first() {
+            None => Fields::Unit,
+            Some(VariantField::Named(..)) => Fields::Named(
+                fields
+                    .iter()
+                    .filter_map(|field| match field {
+                        VariantField::Named(name, _) => Some(name),
+                        VariantField::Unnamed(_) => None,
+                    })
+                    .collect(),
+            ),
+            Some(VariantField::Unnamed(_)) => Fields::Unnamed(fields.len(), variant),
+        }
+    }
+
+    /// Field names, or `None` for each unnamed field
+    fn labels(&self) -> Vec<Option<&'a Identifier>> {
+        match self {
+            Fields::Named(names) => names.iter().map(|name| Some(*name)).collect(),
+            Fields::Unnamed(count, _) => vec![None; *count],
+            Fields::Unit => Vec::new(),
+        }
+    }
+
+    /// Where each field is declared: its name, or that of its variant if
+    /// it has none
+    fn spans(&self) -> Vec<Span> {
+        match self {
+            Fields::Named(names) => names.iter().map(|name| name.span).collect(),
+            Fields::Unnamed(count, variant) => vec![variant.span; *count],
+            Fields::Unit => Vec::new(),
+        }
+    }
+}
+
+/// What is being derived for
+enum Shape<'a> {
+    Struct(Fields<'a>),
+    Enum(Vec<(&'a Identifier, Fields<'a>)>),
+}
+
+/// Where the field values of a derived method come from
+#[derive(Clone, Copy)]
+enum Source<'s> {
+    /// Fields of a struct reached through a parameter: `self.x`
+    Param(&'s str),
+    /// Bindings of a variant pattern: `__self_x`, `__self_0`
+    Binding(&'s str),
+}
+
+/// Builds one derived impl
+///
+/// The checker records types by span, so every node gets a span of its
+/// own: that of the field it works on, or else of the trait name, its end
+/// moved one column further per node of the item's impls. Method names
+/// called on a field keep the field's span as it is, so that errors about
+/// them point at the field.
+struct Deriving<'a> {
+    span: Span,
+    /// Nodes of the item's impls given a span so far
+    nodes: &'a Cell<usize>,
+    type_name: &'a Identifier,
+    shape: &'a Shape<'a>,
+}
+
+impl Deriving<'_> {
+    fn implement(&self, trait_name: &Identifier, generics: Option<&Generics>) -> Impl {
+        let items = match trait_name.name.as_str() {
+            "Clone" => vec![self.method("clone", false, self.ty("Self"), self.clone_body())],
+            "PartialEq" => vec![self.method("eq", true, self.ty("bool"), self.eq_body())],
+            "Copy" | "Eq" => Vec::new(),
+            "PartialOrd" => vec![
+                self.method("partial_cmp", true, self.option_ordering(), self.compare_body(true)),
+                self.method("lt", true, self.ty("bool"), self.lt_body()),
+            ],
+            "Ord" => vec![self.method("cmp", true, self.ty("Ordering"), self.compare_body(false))],
+            "Hash" => vec![self.method("hash", false, self.ty("u64"), self.hash_body())],
+            "Debug" => vec![self.method("fmt", false, self.ty("str"), self.debug_body())],
+            _ => unreachable!("`{}` is not derivable", trait_name.name),
+        };
+
+        Impl {
+            impl_span: self.span,
+            generics: generics.map(|generics| self.bounded(generics, trait_name)),
+            trait_name: Some(Type::Simple(trait_name.clone())),
+            self_type: self.self_type(generics),
+            assoc_types: Vec::new(),
+            items,
+        }
+    }
+
+    /// The item's generics with every type parameter bounded by the trait
+    fn bounded(&self, generics: &Generics, trait_name: &Identifier) -> Generics {
+        let mut generics = generics.clone();
+        for param in &generics.params {
+            if let GenericParam::Type(name) = param {
+                generics.where_clause.push(WhereClause {
+                    span: self.span(),
+                    type_param: name.clone(),
+                    bounds: vec![TraitBound::Trait(Type::Simple(trait_name.clone()))],
+                });
+            }
+        }
+        generics
+    }
+
+    /// `Name` or `Name<T, U>`
+    fn self_type(&self, generics: Option<&Generics>) -> Type {
+        let params: Vec<Type> = generics
+            .into_iter()
+            .flat_map(|generics| &generics.params)
+            .map(|param| match param {
+                GenericParam::Type(name) | GenericParam::Const(name, _) | GenericParam::Lifetime(name) => {
+                    Type::Simple(name.clone())
+                }
+            })
+            .collect();
+
+        if params.is_empty() {
+            Type::Simple(self.type_name.clone())
+        } else {
+            Type::PathGeneric(vec![self.type_name.clone()], Some(params))
+        }
+    }
+
+    fn method(&self, name: &str, with_other: bool, return_type: Type, body: Block) -> Box<Function> {
+        let self_ref = Type::Ref(Box::new(self.ty("Self")), false);
+        let mut params = vec![self.param("self", self_ref.clone())];
+        if with_other {
+            params.push(self.param("other", self_ref));
+        }
+
+        Box::new(Function {
+            name: self.ident(name),
+            generics: None,
+            params,
+            return_type: Some(return_type),
+            error_type: None,
+            effects: Vec::new(),
+            is_variadic: false,
+            body,
+            is_async: false,
+            is_unsafe: false,
+            is_const: false,
+            attributes: Vec::new(),
+        })
+    }
+
+    // ------------------------------------------------------------------------
+    // Method bodies
+    // ------------------------------------------------------------------------
+
+    /// `Name { x: self.x.clone() }`, or a match rebuilding each variant
+    fn clone_body(&self) -> Block {
+        let clone = |fields: &Fields, source: Source| -> Vec<Expression> {
+            self.values(fields, source)
+                .into_iter()
+                .zip(fields.spans())
+                .map(|(value, field)| self.call_method(field, value, "clone", Vec::new()))
+                .collect()
+        };
+
+        let body = match self.shape {
+            Shape::Struct(fields) => {
+                let values = clone(fields, Source::Param("self"));
+                self.construct(vec![self.type_name.clone()], fields, values)
+            }
+            Shape::Enum(_) => self.match_variants(self.path(&["self"]), |variant, fields| {
+                let values = clone(fields, Source::Binding("self"));
+                self.construct(self.variant_path(variant), fields, values)
+            }),
+        };
+        self.block(Vec::new(), body)
+    }
+
+    /// All fields equal, for the same variant
+    fn eq_body(&self) -> Block {
+        let all_equal = |fields: &Fields, left: Source, right: Source| {
+            self.values(fields, left)
+                .into_iter()
+                .zip(self.values(fields, right))
+                .zip(fields.spans())
+                .map(|((left, right), field)| self.call_method(field, left, "eq", vec![self.reference(right)]))
+                .reduce(|all, equal| self.binary(BinaryOp::And, all, equal))
+                .unwrap_or_else(|| self.bool(true))
+        };
+
+        let body = match self.shape {
+            Shape::Struct(fields) => all_equal(fields, Source::Param("self"), Source::Param("other")),
+            Shape::Enum(_) => self.match_pairs(|| self.bool(false), |fields| {
+                all_equal(fields, Source::Binding("self"), Source::Binding("other"))
+            }),
+        };
+        self.block(Vec::new(), body)
+    }
+
+    /// Lexicographic `cmp`, or `partial_cmp` if `partial`
+    ///
+    /// Different variants compare by declaration order.
+    fn compare_body(&self, partial: bool) -> Block {
+        let method = if partial { "partial_cmp" } else { "cmp" };
+        let equal = || {
+            let equal = self.path(&["Ordering", "Equal"]);
+            if partial { self.some(equal) } else { equal }
+        };
+
+        // match a.cmp(&b) { Ordering::Equal => rest, ordering => ordering }
+        let lexicographic = |fields: &Fields, left: Source, right: Source| {
+            let pairs: Vec<_> = self.values(fields, left)
+                .into_iter()
+                .zip(self.values(fields, right))
+                .zip(fields.spans())
+                .collect();
+            pairs.into_iter().rev().fold(equal(), |rest, ((left, right), field)| {
+                let comparison = self.call_method(field, left, method, vec![self.reference(right)]);
+                let equal_pattern = self.ordering_pattern("Equal", partial);
+                let ordering = self.ident("ordering");
+                self.match_expr(
+                    comparison,
+                    vec![
+                        (equal_pattern, rest),
+                        (Pattern::Identifier(ordering.clone()), self.expr(ExpressionKind::Path(vec![ordering]))),
+                    ],
+                )
+            })
+        };
+
+        let body = match self.shape {
+            Shape::Struct(fields) => lexicographic(fields, Source::Param("self"), Source::Param("other")),
+            Shape::Enum(_) => {
+                // { let left = match self { .. }; let right = match other { .. }; left.cmp(&right) }
+                let by_index = || {
+                    let left = self.ident("left");
+                    let right = self.ident("right");
+                    let block = self.block(
+                        vec![
+                            self.local(left.clone(), false, None, self.variant_index("self")),
+                            self.local(right.clone(), false, None, self.variant_index("other")),
+                        ],
+                        self.call_method(
+                            self.span,
+                            self.expr(ExpressionKind::Path(vec![left])),
+                            method,
+                            vec![self.reference(self.expr(ExpressionKind::Path(vec![right])))],
+                        ),
+                    );
+                    self.expr(ExpressionKind::Block(block))
+                };
+
+                self.match_pairs(by_index, |fields| {
+                    lexicographic(fields, Source::Binding("self"), Source::Binding("other"))
+                })
+            }
+        };
+        self.block(Vec::new(), body)
+    }
+
+    /// `match self.partial_cmp(other) { Some(Ordering::Less) => true, _ => false }`
+    fn lt_body(&self) -> Block {
+        let comparison = self.call_method(self.span, self.path(&["self"]), "partial_cmp", vec![self.path(&["other"])]);
+        let body = self.match_expr(
+            comparison,
+            vec![
+                (self.ordering_pattern("Less", true), self.bool(true)),
+                (Pattern::Wildcard, self.bool(false)),
+            ],
+        );
+        self.block(Vec::new(), body)
+    }
+
+    /// FNV-1a over the variant index and the fields' hashes
+    fn hash_body(&self) -> Block {
+        let hash = self.ident("hash");
+        // let mut hash: u64 = OFFSET; hash = (hash ^ value) * PRIME; ...; hash
+        let fold = |index: Option<usize>, fields: &Fields, source: Source| {
+            let mut statements = vec![self.local(
+                hash.clone(),
+                true,
+                Some(self.ty("u64")),
+                self.int(FNV_OFFSET),
+            )];
+            let index = index.map(|index| self.int(index as u128));
+            let hashes = self.values(fields, source)
+                .into_iter()
+                .zip(fields.spans())
+                .map(|(value, field)| self.call_method(field, value, "hash", Vec::new()));
+            for value in index.into_iter().chain(hashes) {
+                let hash_path = || self.expr(ExpressionKind::Path(vec![hash.clone()]));
+                let mixed = self.binary(BinaryOp::BitXor, hash_path(), value);
+                let multiplied = self.binary(BinaryOp::Mul, mixed, self.int(FNV_PRIME));
+                let assign = ExpressionKind::Assign(Box::new(hash_path()), Box::new(multiplied));
+                statements.push(self.statement(StatementKind::Expr(self.expr(assign))));
+            }
+            self.block(statements, self.expr(ExpressionKind::Path(vec![hash.clone()])))
+        };
+
+        match self.shape {
+            Shape::Struct(fields) => fold(None, fields, Source::Param("self")),
+            Shape::Enum(variants) => {
+                let mut index = 0;
+                let body = self.match_variants(self.path(&["self"]), |_, fields| {
+                    let block = fold(Some(index), fields, Source::Binding("self"));
+                    index += 1;
+                    self.expr(ExpressionKind::Block(block))
+                });
+                debug_assert_eq!(index, variants.len());
+                self.block(Vec::new(), body)
+            }
+        }
+    }
+
+    /// `Name { x: .. }` for structs, `Variant(..)` for enums, with fields formatted by `Debug`
+    fn debug_body(&self) -> Block {
+        let body = match self.shape {
+            Shape::Struct(fields) => self.debug_string(self.type_name, fields, Source::Param("self")),
+            Shape::Enum(_) => self.match_variants(self.path(&["self"]), |variant, fields| {
+                self.debug_string(variant, fields, Source::Binding("self"))
+            }),
+        };
+        self.block(Vec::new(), body)
+    }
+
+    fn debug_string(&self, name: &Identifier, fields: &Fields, source: Source) -> Expression {
+        let mut parts = vec![TemplateStringPart::Static(name.name.clone())];
+        let labels = fields.labels();
+        let (open, close) = match fields {
+            Fields::Named(_) => (" { ", " }"),
+            _ => ("(", ")"),
+        };
+
+        for (i, (label, value)) in labels.iter().zip(self.values(fields, source)).enumerate() {
+            let mut text = if i == 0 { open.to_string() } else { ", ".to_string() };
+            if let Some(label) = label {
+                text.push_str(&label.name);
+                text.push_str(": ");
+            }
+            parts.push(TemplateStringPart::Static(text));
+            parts.push(TemplateStringPart::Expr(Box::new(value), FormatSpec::debug()));
+        }
+
+        if !labels.is_empty() {
+            parts.push(TemplateStringPart::Static(close.to_string()));
+        }
+        self.expr(ExpressionKind::TemplateString(TemplateString { parts }))
+    }
+
+    // ------------------------------------------------------------------------
+    // Variants
+    // ------------------------------------------------------------------------
+
+    /// `match scrutinee { Variant(__self_0) => arm(..), ... }`
+    fn match_variants(
+        &self,
+        scrutinee: Expression,
+        mut arm: impl FnMut(&Identifier, &Fields) -> Expression,
+    ) -> Expression {
+        let Shape::Enum(variants) = self.shape else {
+            unreachable!("only enums have variants")
+        };
+        let arms = variants
+            .iter()
+            .map(|(variant, fields)| (self.variant_pattern(variant, fields, Some("self")), arm(variant, fields)))
+            .collect();
+        self.match_expr(scrutinee, arms)
+    }
+
+    /// `match self { V(__self_0) => match other { V(__other_0) => arm(..), _ => otherwise() }, ... }`
+    fn match_pairs(&self, otherwise: impl Fn() -> Expression, arm: impl Fn(&Fields) -> Expression) -> Expression {
+        let Shape::Enum(variants) = self.shape else {
+            unreachable!("only enums have variants")
+        };
+        self.match_variants(self.path(&["self"]), |variant, fields| {
+            let mut arms = vec![(self.variant_pattern(variant, fields, Some("other")), arm(fields))];
+            if variants.len() > 1 {
+                arms.push((Pattern::Wildcard, otherwise()));
+            }
+            self.match_expr(self.path(&["other"]), arms)
+        })
+    }
+
+    /// `match value { Variant(_) => 0u64, ... }`
+    fn variant_index(&self, value: &str) -> Expression {
+        let Shape::Enum(variants) = self.shape else {
+            unreachable!("only enums have variants")
+        };
+        let arms = variants
+            .iter()
+            .enumerate()
+            .map(|(index, (variant, fields))| (self.variant_pattern(variant, fields, None), self.int(index as u128)))
+            .collect();
+        self.match_expr(self.path(&[value]), arms)
+    }
+
+    /// A pattern for one variant, binding fields as `__{prefix}_{field}` or
+    /// ignoring them if `prefix` is `None`
+    fn variant_pattern(&self, variant: &Identifier, fields: &Fields, prefix: Option<&str>) -> Pattern {
+        let path = self.variant_path(variant);
+        let bind = |field: &str| match prefix {
+            Some(prefix) => Pattern::Identifier(self.ident(&binding(prefix, field))),
+            None => Pattern::Wildcard,
+        };
+
+        match fields {
+            Fields::Unit => Pattern::Struct(path, Vec::new()),
+            Fields::Unnamed(count, _) => {
+                Pattern::TupleVariant(path, (0..*count).map(|i| bind(&i.to_string())).collect())
+            }
+            Fields::Named(names) => Pattern::Struct(
+                path,
+                names
+                    .iter()
+                    .map(|name| StructPatternField::Field((*name).clone(), Box::new(bind(&name.name))))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn variant_path(&self, variant: &Identifier) -> Vec<Identifier> {
+        vec![self.type_name.clone(), variant.clone()]
+    }
+
+    /// The field values of a struct or of a variant's bindings, each
+    /// spanned by its field
+    fn values(&self, fields: &Fields, source: Source) -> Vec<Expression> {
+        let names: Vec<String> = match fields {
+            Fields::Named(names) => names.iter().map(|name| name.name.clone()).collect(),
+            Fields::Unnamed(count, _) => (0..*count).map(|i| i.to_string()).collect(),
+            Fields::Unit => Vec::new(),
+        };
+
+        names
+            .iter()
+            .zip(fields.spans())
+            .map(|(name, field)| {
+                let kind = match source {
+                    Source::Param(param) => ExpressionKind::FieldAccess(Box::new(self.path(&[param])), self.ident(name)),
+                    Source::Binding(prefix) => ExpressionKind::Path(vec![self.ident(&binding(prefix, name))]),
+                };
+                Expression { span: self.span_at(field), kind }
+            })
+            .collect()
+    }
+
+    /// `Path { a: .. }`, `Path(..)` or `Path`, depending on the fields
+    fn construct(&self, path: Vec<Identifier>, fields: &Fields, values: Vec<Expression>) -> Expression {
+        match fields {
+            Fields::Named(names) => self.expr(ExpressionKind::Struct(StructLiteral {
+                path,
+                fields: names
+                    .iter()
+                    .zip(values)
+                    .map(|(name, value)| StructExprField {
+                        span: self.span(),
+                        name: (*name).clone(),
+                        value,
+                    })
+                    .collect(),
+                base: None,
+            })),
+            Fields::Unnamed(..) => self.expr(ExpressionKind::Call(
+                Box::new(self.expr(ExpressionKind::Path(path))),
+                values.into_iter().map(Box::new).collect(),
+            )),
+            Fields::Unit => self.expr(ExpressionKind::Path(path)),
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Nodes
+    // ------------------------------------------------------------------------
+
+    /// A span no other node of the impl has, at the trait name
+    fn span(&self) -> Span {
+        self.span_at(self.span)
+    }
+
+    /// A span no other node of the impl has, starting where `anchor` does
+    fn span_at(&self, anchor: Span) -> Span {
+        let node = self.nodes.get() + 1;
+        self.nodes.set(node);
+        let mut span = anchor;
+        span.end.column += node;
+        span
+    }
+
+    fn ident(&self, name: &str) -> Identifier {
+        Identifier::new(self.span(), name)
+    }
+
+    fn ty(&self, name: &str) -> Type {
+        Type::Simple(self.ident(name))
+    }
+
+    /// `Option<Ordering>`
+    fn option_ordering(&self) -> Type {
+        Type::PathGeneric(vec![self.ident("Option")], Some(vec![self.ty("Ordering")]))
+    }
+
+    fn param(&self, name: &str, ty: Type) -> Param {
+        Param {
+            span: self.span(),
+            name: self.ident(name),
+            type_annotation: Some(ty),
+            default_value: None,
+        }
+    }
+
+    fn expr(&self, kind: ExpressionKind) -> Expression {
+        Expression { span: self.span(), kind }
+    }
+
+    fn path(&self, segments: &[&str]) -> Expression {
+        self.expr(ExpressionKind::Path(segments.iter().map(|segment| self.ident(segment)).collect()))
+    }
+
+    fn bool(&self, value: bool) -> Expression {
+        self.expr(ExpressionKind::Literal(Literal::Bool(value)))
+    }
+
+    fn int(&self, value: u128) -> Expression {
+        self.expr(ExpressionKind::Literal(Literal::Int(value, Some(IntSuffix::U64))))
+    }
+
+    fn some(&self, value: Expression) -> Expression {
+        self.expr(ExpressionKind::Call(Box::new(self.path(&["Some"])), vec![Box::new(value)]))
+    }
+
+    fn reference(&self, value: Expression) -> Expression {
+        self.expr(ExpressionKind::Unary(UnaryOp::Ref, Box::new(value)))
+    }
+
+    fn binary(&self, op: BinaryOp, left: Expression, right: Expression) -> Expression {
+        self.expr(ExpressionKind::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    /// `receiver.method(args)`, with the method name spanned by `anchor`
+    fn call_method(&self, anchor: Span, receiver: Expression, method: &str, args: Vec<Expression>) -> Expression {
+        let kind = ExpressionKind::MethodCall(
+            Box::new(receiver),
+            Identifier::new(anchor, method),
+            args.into_iter().map(Box::new).collect(),
+        );
+        Expression { span: self.span_at(anchor), kind }
+    }
+
+    /// `Ordering::Less`, or `Some(Ordering::Less)` if `partial`
+    fn ordering_pattern(&self, ordering: &str, partial: bool) -> Pattern {
+        let pattern = Pattern::Struct(vec![self.ident("Ordering"), self.ident(ordering)], Vec::new());
+        if partial {
+            Pattern::TupleVariant(vec![self.ident("Some")], vec![pattern])
+        } else {
+            pattern
+        }
+    }
+
+    fn match_expr(&self, scrutinee: Expression, arms: Vec<(Pattern, Expression)>) -> Expression {
+        let arms = arms
+            .into_iter()
+            .map(|(pattern, body)| MatchArm {
+                span: self.span(),
+                patterns: vec![pattern],
+                guard: None,
+                body: Box::new(body),
+            })
+            .collect();
+        self.expr(ExpressionKind::Match(Box::new(scrutinee), arms))
+    }
+
+    fn statement(&self, kind: StatementKind) -> Statement {
+        Statement { span: self.span(), kind }
+    }
+
+    fn local(&self, name: Identifier, is_mutable: bool, ty: Option<Type>, init: Expression) -> Statement {
+        self.statement(StatementKind::Local(Local {
+            name,
+            type_annotation: ty,
+            init: Some(Box::new(init)),
+            is_mutable,
+        }))
+    }
+
+    fn block(&self, statements: Vec<Statement>, trailing: Expression) -> Block {
+        Block {
+            span: self.span(),
+            statements,
+            trailing_expr: Some(Box::new(trailing)),
+        }
+    }
+}
+
+/// The name a derived pattern binds a field to, e.g. `__other_x`
+fn binding(prefix: &str, field: &str) -> String {
+    format!("__{}_{}", prefix, field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zulon_parser::Parser;
+
+    fn impls(source: &str) -> Vec<Impl> {
+        let mut ast = Parser::from_source(source).parse().unwrap();
+        let errors = expand_derives(&mut ast);
+        assert!(errors.is_empty(), "{:?}", errors);
+        ast.items
+            .into_iter()
+            .filter_map(|item| match item.kind {
+                ItemKind::Impl(impl_def) => Some(impl_def),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_binding_names() {
+        assert_eq!(binding("self", "0"), "__self_0");
+        assert_eq!(binding("other", "x"), "__other_x");
+    }
+
+    #[test]
+    fn test_unit_variants_compare_by_index() {
+        let impls = impls("#[derive(Ord)] enum Level { Low, High }");
+        let body = impls[0].items[0].body.trailing_expr.as_deref().unwrap();
+        let ExpressionKind::Match(_, arms) = &body.kind else {
+            panic!("expected a match, found {:?}", body.kind);
+        };
+        // One arm per variant, each matching `other` against the same
+        // variant before falling back to comparing indices
+        assert_eq!(arms.len(), 2);
+        for arm in arms {
+            let ExpressionKind::Match(_, inner) = &arm.body.kind else {
+                panic!("expected a nested match, found {:?}", arm.body.kind);
+            };
+            assert_eq!(inner.len(), 2);
+            assert!(matches!(inner[1].patterns[0], Pattern::Wildcard));
+            assert!(matches!(inner[1].body.kind, ExpressionKind::Block(_)));
+        }
+    }
+}
@@ -0,0 +1,341 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `format!` and the macros built on it
+//!
+//! `format!("{} is {value:>8.2}", name, value = price)` checks its format
+//! string with [`zulon_parser::format`] and expands to a template string,
+//! so formatting is lowered in one place:
+//!
+//! ```text
+//! { let __fmt_1 = (price); `${name} is ${__fmt_1: >8.2}` }
+//! ```
+//!
+//! Arguments that are plain identifiers are referenced directly and integer
+//! literals used as counts are inlined; everything else is evaluated once,
+//! in order, into a `__fmt_N` local. `{name}` without a matching named
+//! argument captures the variable `name`.
+//!
+//! `print!` and `println!` pass the result to `printf`, and `panic!` to the
+//! runtime's panic handler.
+
+use crate::{split_arguments, Invocation, MacroResult};
+use zulon_parser::format::{parse_format_string, Argument, Count, Piece};
+use zulon_parser::{FormatError, Position, Span, Token, TokenKind};
+
+/// `format!(fmt, args...)`: the formatted string
+pub fn expand_format(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    Format::parse(input, invocation.call_site)?.expand()
+}
+
+/// `print!(fmt, args...)`: print the formatted string
+pub fn expand_print(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    printf("%s", input, invocation.call_site)
+}
+
+/// `println!(fmt, args...)`: print the formatted string and a newline
+pub fn expand_println(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let call_site = invocation.call_site;
+    if input.is_empty() {
+        return Ok(call(&["printf"], vec![string_literal("\n", call_site)], call_site));
+    }
+    printf("%s\n", input, call_site)
+}
+
+/// `panic!(fmt, args...)`: panic with the formatted message
+pub fn expand_panic(input: &[Token], invocation: &mut Invocation<'_>) -> MacroResult<Vec<Token>> {
+    let call_site = invocation.call_site;
+    let message = if input.is_empty() {
+        vec![string_literal("explicit panic", call_site)]
+    } else {
+        Format::parse(input, call_site)?.expand()?
+    };
+    Ok(call(&["", "__zulon_builtin_panic"], message, call_site))
+}
+
+fn printf(format: &str, input: &[Token], call_site: Span) -> MacroResult<Vec<Token>> {
+    let mut args = vec![string_literal(format, call_site), token(TokenKind::Comma, call_site)];
+    args.extend(Format::parse(input, call_site)?.expand()?);
+    Ok(call(&["printf"], args, call_site))
+}
+
+/// A checked `format!` invocation
+struct Format<'a> {
+    pieces: Vec<Piece>,
+    positional: Vec<&'a [Token]>,
+    named: Vec<(String, &'a [Token])>,
+    call_site: Span,
+}
+
+/// What a placeholder or count refers to once resolved
+#[derive(Clone)]
+enum Source {
+    Positional(usize),
+    Named(usize),
+    /// `{name}` without a named argument: the variable `name`
+    Capture(String),
+}
+
+/// The value, width and precision sources of one placeholder
+type Resolved = (Source, Option<Source>, Option<Source>);
+
+impl<'a> Format<'a> {
+    fn parse(input: &'a [Token], call_site: Span) -> MacroResult<Self> {
+        let mut arguments = split_arguments(input).into_iter();
+        let Some(TokenKind::StringLiteral(text)) = arguments.next().and_then(|format| match format {
+            [token] => Some(&token.kind),
+            _ => None,
+        }) else {
+            let span = input.first().map_or(call_site, |token| token.span);
+            return Err(FormatError::new("format argument must be a string literal", span)
+                .with_note("use `\"{}\"` to format a single value")
+                .into());
+        };
+
+        // The text starts after the opening quote
+        let start = input[0].span.start;
+        let pieces = parse_format_string(text, Position::new(start.line, start.column + 1))?;
+
+        let mut positional = Vec::new();
+        let mut named: Vec<(String, &[Token])> = Vec::new();
+        for argument in arguments {
+            match argument {
+                [name, equals, value @ ..] if equals.kind == TokenKind::Equals => {
+                    let TokenKind::Ident(name_text) = &name.kind else {
+                        return Err(FormatError::new("expected an argument name before `=`", name.span).into());
+                    };
+                    if named.iter().any(|(existing, _)| **existing == **name_text) {
+                        return Err(FormatError::new(format!("duplicate argument named `{}`", name_text), name.span).into());
+                    }
+                    named.push((name_text.to_string(), value));
+                }
+                _ if !named.is_empty() => {
+                    return Err(FormatError::new("positional arguments cannot follow named arguments", argument[0].span)
+                        .into());
+                }
+                _ => positional.push(argument),
+            }
+        }
+
+        Ok(Format {
+            pieces,
+            positional,
+            named,
+            call_site,
+        })
+    }
+
+    /// Resolve every placeholder, check that all arguments are used, and
+    /// build the template string
+    fn expand(self) -> MacroResult<Vec<Token>> {
+        let mut resolved = Vec::new();
+        let mut used = vec![false; self.positional.len() + self.named.len()];
+        let mut next = 0;
+
+        for piece in &self.pieces {
+            let Piece::Placeholder(placeholder) = piece else {
+                continue;
+            };
+            let mut resolve = |argument: &Argument| -> MacroResult<Source> {
+                let source = match argument {
+                    Argument::Next => {
+                        next += 1;
+                        self.positional_source(next - 1, placeholder.span)?
+                    }
+                    Argument::Index(index) => self.positional_source(*index, placeholder.span)?,
+                    Argument::Name(name) => match self.named.iter().position(|(n, _)| n == name) {
+                        Some(index) => Source::Named(index),
+                        None => Source::Capture(name.clone()),
+                    },
+                };
+                match source {
+                    Source::Positional(index) => used[index] = true,
+                    Source::Named(index) => used[self.positional.len() + index] = true,
+                    Source::Capture(_) => {}
+                }
+                Ok(source)
+            };
+
+            // `.*` takes its argument before the value's
+            let precision = match &placeholder.spec.precision {
+                Some(Count::Argument(argument)) => Some(resolve(argument)?),
+                _ => None,
+            };
+            let value = resolve(&placeholder.argument)?;
+            let width = match &placeholder.spec.width {
+                Some(Count::Argument(argument)) => Some(resolve(argument)?),
+                _ => None,
+            };
+            resolved.push((value, width, precision));
+        }
+
+        if let Some(index) = used.iter().position(|used| !used) {
+            let (message, tokens) = match index.checked_sub(self.positional.len()) {
+                None => ("argument never used", self.positional[index]),
+                Some(index) => ("named argument never used", self.named[index].1),
+            };
+            let span = tokens.first().map_or(self.call_site, |token| token.span);
+            return Err(FormatError::new(message, span)
+                .with_note("formatting specifiers use arguments in order, like `\"{} {}\"`")
+                .into());
+        }
+
+        Ok(self.build(&resolved))
+    }
+
+    fn positional_source(&self, index: usize, span: Span) -> MacroResult<Source> {
+        if index < self.positional.len() {
+            return Ok(Source::Positional(index));
+        }
+        let count = match self.positional.len() {
+            1 => "there is 1 argument".to_string(),
+            n => format!("there are {} arguments", n),
+        };
+        Err(FormatError::new(
+            format!("invalid reference to positional argument {} ({})", index, count),
+            span,
+        )
+        .into())
+    }
+
+    fn arguments(&self) -> impl Iterator<Item = &'a [Token]> + '_ {
+        self.positional
+            .iter()
+            .copied()
+            .chain(self.named.iter().map(|(_, tokens)| *tokens))
+    }
+
+    fn argument_index(&self, source: &Source) -> Option<usize> {
+        match source {
+            Source::Positional(index) => Some(*index),
+            Source::Named(index) => Some(self.positional.len() + index),
+            Source::Capture(_) => None,
+        }
+    }
+
+    fn build(&self, resolved: &[Resolved]) -> Vec<Token> {
+        if resolved.is_empty() {
+            let text: String = self
+                .pieces
+                .iter()
+                .filter_map(|piece| match piece {
+                    Piece::Literal(text) => Some(text.as_str()),
+                    Piece::Placeholder(_) => None,
+                })
+                .collect();
+            return vec![string_literal(&text, self.call_site)];
+        }
+
+        // Identifiers are used as they are; other arguments get a local
+        let arguments: Vec<&[Token]> = self.arguments().collect();
+        let names: Vec<String> = arguments
+            .iter()
+            .enumerate()
+            .map(|(index, tokens)| match tokens {
+                [Token { kind: TokenKind::Ident(name), .. }] => name.to_string(),
+                _ => format!("__fmt_{}", index),
+            })
+            .collect();
+        let mut bound = vec![false; arguments.len()];
+        let mut reference = |source: &Source| match self.argument_index(source) {
+            Some(index) => {
+                bound[index] = !matches!(arguments[index], [Token { kind: TokenKind::Ident(_), .. }]);
+                names[index].clone()
+            }
+            None => match source {
+                Source::Capture(name) => name.clone(),
+                _ => unreachable!(),
+            },
+        };
+
+        let mut template = String::new();
+        let mut placeholders = resolved.iter();
+        for piece in &self.pieces {
+            match piece {
+                // `${` would start an interpolation, so its `$` is interpolated instead
+                Piece::Literal(text) => template.push_str(&text.replace("${", "${\"$\"}{")),
+                Piece::Placeholder(placeholder) => {
+                    let Some((value, width, precision)) = placeholders.next() else {
+                        continue;
+                    };
+                    let mut spec = placeholder.spec.clone();
+                    for (count, source) in [(&mut spec.width, width), (&mut spec.precision, precision)] {
+                        let Some(source) = source else {
+                            continue;
+                        };
+                        // Integer literal counts don't need a variable
+                        let literal = self
+                            .argument_index(source)
+                            .and_then(|index| integer_literal(arguments[index]));
+                        *count = Some(match literal {
+                            Some(value) => Count::Is(value),
+                            None => Count::Argument(Argument::Name(reference(source))),
+                        });
+                    }
+
+                    template.push_str("${");
+                    template.push_str(&reference(value));
+                    if !spec.is_default() {
+                        template.push(':');
+                        template.push_str(&spec.to_string());
+                    }
+                    template.push('}');
+                }
+            }
+        }
+
+        let span = self.call_site;
+        let template = token(TokenKind::TemplateString(template.into()), span);
+        if !bound.contains(&true) {
+            return vec![template];
+        }
+
+        // { let __fmt_0 = (expr); ... `template` }
+        let mut tokens = vec![token(TokenKind::LeftBrace, span)];
+        for (index, expr) in arguments.iter().enumerate().filter(|(index, _)| bound[*index]) {
+            tokens.push(token(TokenKind::Let, span));
+            tokens.push(token(TokenKind::Ident(names[index].as_str().into()), span));
+            tokens.push(token(TokenKind::Equals, span));
+            tokens.push(token(TokenKind::LeftParen, span));
+            tokens.extend_from_slice(expr);
+            tokens.push(token(TokenKind::RightParen, span));
+            tokens.push(token(TokenKind::Semicolon, span));
+        }
+        tokens.push(template);
+        tokens.push(token(TokenKind::RightBrace, span));
+        tokens
+    }
+}
+
+/// The value of an argument that is a single integer literal
+fn integer_literal(tokens: &[Token]) -> Option<usize> {
+    match tokens {
+        [Token { kind: TokenKind::IntLiteral(value), .. }] => value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn token(kind: TokenKind, span: Span) -> Token {
+    Token { kind, span }
+}
+
+fn string_literal(text: &str, span: Span) -> Token {
+    token(TokenKind::StringLiteral(text.into()), span)
+}
+
+/// `path(args)`, where an empty first segment makes the path global
+fn call(path: &[&str], args: Vec<Token>, span: Span) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for (i, segment) in path.iter().enumerate() {
+        if i > 0 {
+            tokens.push(token(TokenKind::PathSep, span));
+        }
+        if !segment.is_empty() {
+            tokens.push(token(TokenKind::Ident((*segment).into()), span));
+        }
+    }
+    tokens.push(token(TokenKind::LeftParen, span));
+    tokens.extend(args);
+    tokens.push(token(TokenKind::RightParen, span));
+    tokens
+}
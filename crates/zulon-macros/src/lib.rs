@@ -3,9 +3,152 @@
 
 //! Macro system for ZULON
 //!
-//! Provides compile-time macro expansion capabilities.
+//! Provides compile-time macro expansion on token streams from
+//! [`zulon_parser::Lexer`]. Expansion runs between lexing and parsing:
+//!
+//! ```rust
+//! use zulon_macros::MacroExpanderEngine;
+//! use zulon_parser::{Lexer, Parser};
+//!
+//! let (tokens, _) = Lexer::new("fn main() { assert!(1 < 2); }").lex_all();
+//! let expansion = MacroExpanderEngine::with_builtins().expand_tokens(tokens);
+//! assert!(expansion.errors.is_empty());
+//!
+//! let ast = Parser::new(expansion.tokens).parse().unwrap();
+//! assert_eq!(ast.items.len(), 1);
+//! ```
+//!
+//! Macros inside string literals are never expanded, since a string is a
+//! single token. Expanded tokens keep spans in the original source, and the
+//! [`SpanMap`] records where each expansion happened.
+//!
+//! Expansion is hygienic for locals: a variable bound by `let` or `for` in a
+//! template is renamed for each expansion, so it can neither capture nor be
+//! captured by identifiers from the invocation.
+//!
+//! ## Macro Items
+//!
+//! Source code defines macros with `macro` items, which are registered
+//! before anything is expanded:
+//!
+//! ```text
+//! pub macro max {
+//!     ($a:expr) => { $a };
+//!     ($a:expr, $($rest:expr),+) => { larger($a, max!($($rest),+)) };
+//! }
+//! ```
+//!
+//! A pattern variable either has a fragment specifier (`expr`, `ty`, `pat`,
+//! `block`, `item`, `path`, `ident`, `literal` or `tt`) or, without one,
+//! takes everything up to the next token of the pattern. `$( ... )` with
+//! `*`, `+` or `?` and an optional separator matches repeatedly. A macro is
+//! visible in its module and the module's descendants; other modules invoke
+//! a `pub macro` by path, as in `utils::max!(a, b)`.
+//!
+//! ## Formatting
+//!
+//! `format!`, `print!`, `println!` and `panic!` take a format string with
+//! `{}`, `{name:>8.2}`, `{:#x}` or `{:?}` placeholders, checked when the
+//! macro expands. They expand to template strings, so `format!("{x:.3}")`
+//! and `` `${x:.3}` `` are the same expression.
+//!
+//! ## Compile-Time Values
+//!
+//! `include_str!` and `include_bytes!` embed a file relative to the source
+//! file, which [`Expansion::dependencies`] lists so the output can be
+//! rebuilt when it changes. `env!`, `option_env!` and `concat!` produce
+//! literals while expanding, and `file!`, `line!`, `column!` and
+//! `module_path!` describe where they are invoked.
+//!
+//! ## Derive
+//!
+//! `#[derive(Clone, PartialEq, ...)]` works on the parsed AST instead of
+//! tokens; see [`derive`].
+
+pub mod derive;
+pub mod span_map;
+mod builtins;
+mod format;
+mod scope;
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use zulon_parser::ast::{ItemKind, MacroDef};
+use zulon_parser::{FormatError, FragmentKind, Lexer, Parser, SourceMap, Span, Token, TokenKind};
+
+use scope::{module_path, Definition, Definitions, ModuleTracker};
+pub use derive::{expand_derives, DeriveError};
+pub use span_map::{ExpansionId, ExpansionInfo, SpanMap};
+
+/// How deeply macro expansions may nest before expansion gives up
+pub const RECURSION_LIMIT: usize = 64;
+
+/// Macro expansion error
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MacroError {
+    #[error("cannot find macro `{name}!`")]
+    Undefined { name: String, span: Span },
+
+    #[error("macro `{name}!` is private")]
+    Private { name: String, span: Span },
+
+    #[error("macro `{name}!` is defined multiple times in the same module")]
+    Redefined { name: String, span: Span },
+
+    #[error("invalid macro rule: {message}")]
+    InvalidRule { message: String, span: Span },
+
+    #[error("no rules of macro `{name}!` matched this invocation")]
+    NoMatchingRule { name: String, span: Span },
+
+    #[error("unclosed delimiter in invocation of `{name}!`")]
+    UnclosedDelimiter { name: String, span: Span },
 
-use std::collections::HashMap;
+    #[error("recursion limit of {limit} reached while expanding `{name}!`")]
+    RecursionLimit { name: String, limit: usize, span: Span },
+
+    #[error("unknown macro variable `${name}` in `{macro_name}!`")]
+    UnknownVariable { macro_name: String, name: String, span: Span },
+
+    #[error("`${name}` does not repeat the way its use in `{macro_name}!` does")]
+    RepetitionMismatch { macro_name: String, name: String, span: Span },
+
+    #[error("`{name}!` {message}")]
+    InvalidArguments { name: String, message: String, span: Span },
+
+    #[error("couldn't read `{path}`: {message}")]
+    Include { path: String, message: String, span: Span },
+
+    #[error("{message}")]
+    Env { message: String, span: Span },
+
+    #[error(transparent)]
+    Format(#[from] FormatError),
+}
+
+impl MacroError {
+    /// The invocation or definition the error was found in
+    pub fn span(&self) -> Span {
+        match self {
+            MacroError::Undefined { span, .. }
+            | MacroError::Private { span, .. }
+            | MacroError::Redefined { span, .. }
+            | MacroError::InvalidRule { span, .. }
+            | MacroError::NoMatchingRule { span, .. }
+            | MacroError::UnclosedDelimiter { span, .. }
+            | MacroError::RecursionLimit { span, .. }
+            | MacroError::UnknownVariable { span, .. }
+            | MacroError::RepetitionMismatch { span, .. }
+            | MacroError::InvalidArguments { span, .. }
+            | MacroError::Include { span, .. }
+            | MacroError::Env { span, .. } => *span,
+            MacroError::Format(error) => error.span,
+        }
+    }
+}
+
+/// Macro expansion result type
+pub type MacroResult<T> = Result<T, MacroError>;
 
 /// Simple identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -28,6 +171,21 @@ pub struct Macro {
     pub rules: Vec<MacroRule>,
 }
 
+impl Macro {
+    /// Build a macro from a parsed `macro` item
+    pub fn from_def(def: &MacroDef) -> MacroResult<Self> {
+        let rules = def
+            .rules
+            .iter()
+            .map(|rule| MacroRule::from_tokens(&rule.pattern, &rule.template))
+            .collect::<MacroResult<_>>()?;
+        Ok(Macro {
+            name: Identifier::new(&def.name.name),
+            rules,
+        })
+    }
+}
+
 /// Macro expansion rule
 #[derive(Debug, Clone)]
 pub struct MacroRule {
@@ -35,152 +193,1119 @@ pub struct MacroRule {
     pub expander: MacroExpander,
 }
 
+impl MacroRule {
+    /// Build a rule from pattern and template source text
+    ///
+    /// Meant for rules written in Rust, such as the builtins.
+    ///
+    /// # Panics
+    ///
+    /// If the pattern or template is malformed, e.g. has an unclosed `$(`.
+    pub fn new(pattern: &str, template: &str) -> Self {
+        Self::from_tokens(&lex(pattern), &lex(template)).expect("invalid macro rule")
+    }
+
+    /// Build a rule from the tokens inside its pattern and template delimiters
+    pub fn from_tokens(pattern: &[Token], template: &[Token]) -> MacroResult<Self> {
+        Ok(MacroRule {
+            matcher: MacroMatcher::from_tokens(pattern)?,
+            expander: MacroExpander::from_tokens(template)?,
+        })
+    }
+}
+
 /// Pattern matcher for macros
 #[derive(Debug, Clone)]
 pub struct MacroMatcher {
     pub patterns: Vec<PatternFragment>,
 }
 
+impl MacroMatcher {
+    /// Build a matcher from pattern tokens
+    ///
+    /// `$name` and `$name:kind` are variables and `$( ... ) sep op` is a
+    /// repetition; everything else must match token by token.
+    pub fn from_tokens(tokens: &[Token]) -> MacroResult<Self> {
+        let patterns = pieces(tokens, true)?.into_iter().map(Piece::into_pattern).collect();
+        Ok(MacroMatcher { patterns })
+    }
+}
+
 /// Macro expansion template
 #[derive(Debug, Clone)]
 pub struct MacroExpander {
     pub template: Vec<TemplateFragment>,
 }
 
+impl MacroExpander {
+    /// Build a template from tokens, where `$name` is a variable and
+    /// `$( ... ) sep op` repeats once per match of the variables inside
+    pub fn from_tokens(tokens: &[Token]) -> MacroResult<Self> {
+        let template = pieces(tokens, false)?.into_iter().map(Piece::into_template).collect();
+        Ok(MacroExpander { template })
+    }
+}
+
 /// Fragment in pattern matching
 #[derive(Debug, Clone)]
 pub enum PatternFragment {
     /// Literal token
-    Literal(String),
-    /// Variable binding $name
+    Literal(TokenKind),
+    /// Variable binding $name, matching token trees up to the next literal
     Var(String),
-    /// Repetition $(...)* or $(...)+
+    /// Variable binding with a fragment specifier: $name:kind
+    Fragment { name: String, kind: FragmentKind },
+    /// Repetition $(...)*, $(...)+ or $(...)?
     Repetition {
         inner: Vec<PatternFragment>,
-        separator: Option<String>,
+        separator: Option<TokenKind>,
+        op: RepetitionOp,
     },
 }
 
+/// How often a repetition may match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepetitionOp {
+    /// `*`
+    ZeroOrMore,
+    /// `+`
+    OneOrMore,
+    /// `?`
+    ZeroOrOne,
+}
+
+impl RepetitionOp {
+    fn from_token(kind: &TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::Star => Some(RepetitionOp::ZeroOrMore),
+            TokenKind::Plus => Some(RepetitionOp::OneOrMore),
+            TokenKind::Question => Some(RepetitionOp::ZeroOrOne),
+            _ => None,
+        }
+    }
+}
+
 /// Fragment in expansion template
 #[derive(Debug, Clone)]
 pub enum TemplateFragment {
-    /// Literal text
-    Literal(String),
+    /// Literal token
+    Literal(TokenKind),
     /// Variable reference $name
     Var(String),
     /// Repetition expansion
     Repetition {
         inner: Vec<TemplateFragment>,
-        separator: Option<String>,
+        separator: Option<TokenKind>,
+        /// The first variable inside, used to name the repetition in errors
         var: String,
     },
 }
 
+/// A macro implemented in Rust rather than by rules
+///
+/// Receives the tokens between the invocation's delimiters and where the
+/// invocation is.
+pub type BuiltinMacro = fn(&[Token], &mut Invocation<'_>) -> MacroResult<Vec<Token>>;
+
+/// Where a builtin macro is invoked
+pub struct Invocation<'a> {
+    /// Span of the whole invocation
+    ///
+    /// An invocation written in a macro template has the span of the
+    /// outermost invocation in the source, since template tokens take it.
+    pub call_site: Span,
+    /// Path of the inline module the invocation is in, empty at the crate root
+    pub module: &'a [String],
+    /// The file the invocation is in, if the tokens came from one
+    pub source_file: Option<&'a Path>,
+    /// The crate's root file, which names the crate
+    pub crate_root: Option<&'a Path>,
+    /// Lines of the crate before the first line of `source_file`; see
+    /// [`SourceMap`]
+    pub line_offset: usize,
+    /// Files the expansion read, so the output can be rebuilt when they change
+    pub dependencies: &'a mut Vec<PathBuf>,
+}
+
+/// The result of expanding a token stream
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    /// Tokens with every known macro invocation expanded
+    pub tokens: Vec<Token>,
+    /// Where each expansion happened
+    pub span_map: SpanMap,
+    /// Invocations that failed to expand; they are left in `tokens` as written
+    pub errors: Vec<MacroError>,
+    /// Files read by macros such as `include_str!`, in the order they were read
+    pub dependencies: Vec<PathBuf>,
+}
+
+/// What a pattern variable matched
+#[derive(Debug, Clone)]
+enum Binding {
+    Single(Vec<Token>),
+    /// One binding per iteration of the enclosing repetition
+    Repeated(Vec<Binding>),
+}
+
+type Bindings = HashMap<String, Binding>;
+
+/// The macro an invocation refers to
+#[derive(Clone, Copy)]
+enum Resolved<'a> {
+    Builtin(BuiltinMacro),
+    /// A rule-based macro and the module it was defined in
+    Rules(&'a Macro, &'a [String]),
+}
+
+/// Where an invocation is resolved from
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    definitions: &'a Definitions,
+    /// The module the invocation is in
+    module: &'a [String],
+    /// For invocations produced by a macro, the module that macro was defined in
+    def_module: &'a [String],
+    source_file: Option<&'a Path>,
+    /// For a crate of several files, which file each line is in
+    source_map: Option<&'a SourceMap>,
+}
+
+/// What expanding a token stream records besides the tokens
+#[derive(Default)]
+struct Record {
+    span_map: SpanMap,
+    errors: Vec<MacroError>,
+    dependencies: Vec<PathBuf>,
+}
+
 /// Macro expansion engine
 pub struct MacroExpanderEngine {
     macros: HashMap<String, Macro>,
+    builtins: HashMap<String, BuiltinMacro>,
+    /// Builtins whose arguments are expanded before they run
+    eager: HashSet<String>,
 }
 
 impl MacroExpanderEngine {
     pub fn new() -> Self {
         MacroExpanderEngine {
             macros: HashMap::new(),
+            builtins: HashMap::new(),
+            eager: HashSet::new(),
         }
     }
 
-    /// Register a macro
+    /// Register a macro, visible from every module
     pub fn register_macro(&mut self, macro_def: Macro) {
         let name = macro_def.name.name.clone();
         self.macros.insert(name, macro_def);
     }
 
-    /// Expand a macro invocation
-    pub fn expand(&self, name: &str, input: &str) -> Result<String, String> {
-        let macro_def = self.macros.get(name)
-            .ok_or_else(|| format!("Macro '{}' not found", name))?;
+    /// Register a macro implemented in Rust
+    pub fn register_builtin(&mut self, name: &str, expander: BuiltinMacro) {
+        self.builtins.insert(name.to_string(), expander);
+        self.eager.remove(name);
+    }
+
+    /// Register a macro implemented in Rust whose arguments are expanded
+    /// first, so `concat!(env!("DIR"), "/schema.sql")` sees a literal
+    pub fn register_eager_builtin(&mut self, name: &str, expander: BuiltinMacro) {
+        self.builtins.insert(name.to_string(), expander);
+        self.eager.insert(name.to_string());
+    }
+
+    /// Whether a macro with this name is registered
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name) || self.builtins.contains_key(name)
+    }
+
+    /// Expand every invocation of a known macro in a token stream
+    ///
+    /// Takes tokens as the parser would see them, i.e. without whitespace or
+    /// comments. The stream's `macro` items are registered first and left in
+    /// place for the parser. Invocations of unknown macros are left alone.
+    /// Expanded output is expanded again, up to [`RECURSION_LIMIT`] levels.
+    ///
+    /// Paths given to `include_str!` and `include_bytes!` are relative to
+    /// the current directory; see [`Self::expand_file`].
+    pub fn expand_tokens(&self, tokens: Vec<Token>) -> Expansion {
+        self.expand_source(tokens, None, None)
+    }
+
+    /// Expand the tokens of a source file
+    ///
+    /// Like [`Self::expand_tokens`], but `include_str!` and `include_bytes!`
+    /// resolve paths relative to the file's directory, and `file!()` names it.
+    pub fn expand_file(&self, tokens: Vec<Token>, path: &Path) -> Expansion {
+        self.expand_source(tokens, Some(path), None)
+    }
+
+    /// Expand the tokens of a crate whose files were spliced together
+    ///
+    /// Like [`Self::expand_file`], with the file and line of each
+    /// invocation looked up in `source_map`, whose first file is the root.
+    pub fn expand_crate(&self, tokens: Vec<Token>, source_map: &SourceMap) -> Expansion {
+        let root = source_map.files().first().map(|file| file.path.as_path());
+        self.expand_source(tokens, root, Some(source_map))
+    }
+
+    fn expand_source(&self, tokens: Vec<Token>, source_file: Option<&Path>, source_map: Option<&SourceMap>) -> Expansion {
+        let mut record = Record::default();
+        let definitions = collect_definitions(&tokens, &mut record.errors);
+        let scope = Scope {
+            definitions: &definitions,
+            module: &[],
+            def_module: &[],
+            source_file,
+            source_map,
+        };
+        let tokens = self.expand_stream(&tokens, scope, None, 0, &mut record);
+        Expansion {
+            tokens,
+            span_map: record.span_map,
+            errors: record.errors,
+            dependencies: record.dependencies,
+        }
+    }
+
+    /// Expand a single invocation of a registered macro
+    ///
+    /// `input` holds the tokens between the invocation's delimiters and
+    /// `call_site` is the span of the whole invocation. Nested invocations in
+    /// the output are not expanded.
+    pub fn expand(
+        &self,
+        name: &str,
+        input: &[Token],
+        call_site: Span,
+        span_map: &mut SpanMap,
+    ) -> MacroResult<Vec<Token>> {
+        let resolved = self.global(name).ok_or_else(|| MacroError::Undefined {
+            name: name.to_string(),
+            span: call_site,
+        })?;
+        let mut invocation = Invocation {
+            call_site,
+            module: &[],
+            source_file: None,
+            crate_root: None,
+            line_offset: 0,
+            dependencies: &mut Vec::new(),
+        };
+        self.expand_invocation(name, resolved, input, &mut invocation, None, span_map)
+            .map(|(_, tokens)| tokens)
+    }
+
+    fn expand_invocation(
+        &self,
+        name: &str,
+        resolved: Resolved<'_>,
+        input: &[Token],
+        invocation: &mut Invocation<'_>,
+        parent: Option<ExpansionId>,
+        span_map: &mut SpanMap,
+    ) -> MacroResult<(ExpansionId, Vec<Token>)> {
+        let call_site = invocation.call_site;
+        let info = ExpansionInfo {
+            macro_name: name.to_string(),
+            call_site,
+            parent,
+        };
+
+        let macro_def = match resolved {
+            Resolved::Builtin(builtin) => {
+                let tokens = builtin(input, invocation)?;
+                return Ok((span_map.push(info), tokens));
+            }
+            Resolved::Rules(macro_def, _) => macro_def,
+        };
 
         // Try each rule until one matches
         for rule in &macro_def.rules {
-            if let Some(binding) = self.try_match(&rule.matcher, input) {
-                return Ok(self.expand_template(&rule.expander, &binding));
+            if let Some(bindings) = self.try_match(&rule.matcher, input) {
+                let id = span_map.push(info);
+                let hygiene = Hygiene::new(&rule.expander, id);
+                let tokens = self.expand_template(name, &rule.expander, &bindings, call_site, &hygiene)?;
+                return Ok((id, tokens));
             }
         }
 
-        Err(format!("No matching rule for macro '{}'", name))
+        Err(MacroError::NoMatchingRule {
+            name: name.to_string(),
+            span: call_site,
+        })
     }
 
-    /// Try to match input against a pattern
-    fn try_match(&self, matcher: &MacroMatcher, input: &str) -> Option<HashMap<String, String>> {
-        let mut binding = HashMap::new();
+    fn expand_stream(
+        &self,
+        tokens: &[Token],
+        scope: Scope<'_>,
+        parent: Option<ExpansionId>,
+        depth: usize,
+        record: &mut Record,
+    ) -> Vec<Token> {
+        let mut output = Vec::with_capacity(tokens.len());
+        let mut modules = ModuleTracker::new(scope.module);
         let mut pos = 0;
 
-        for (i, frag) in matcher.patterns.iter().enumerate() {
-            match frag {
-                PatternFragment::Literal(lit) => {
-                    if input.len() >= pos + lit.len() && &input[pos..pos + lit.len()] == lit {
-                        pos += lit.len();
-                    } else {
-                        return None;
-                    }
+        while pos < tokens.len() {
+            // Macro items stay as written for the parser
+            if let Some(close) = macro_item_at(tokens, pos) {
+                output.extend_from_slice(&tokens[pos..=close]);
+                pos = close + 1;
+                continue;
+            }
+
+            let Some((path, open)) = invocation_at(tokens, pos) else {
+                modules.step(tokens, pos);
+                output.push(tokens[pos].clone());
+                pos += 1;
+                continue;
+            };
+
+            let module = modules.path().to_vec();
+            let scope = Scope {
+                module: &module,
+                ..scope
+            };
+            let name = path.join("::");
+            let start = tokens[pos].span.start;
+            let path_span = Span::new(start, tokens[open - 2].span.end);
+            let close = matching_delimiter(tokens, open);
+
+            let resolved = match self.resolve(&path, scope, path_span) {
+                Ok(Some(resolved)) => resolved,
+                // Unknown macros are left for the parser
+                Ok(None) => {
+                    modules.step(tokens, pos);
+                    output.push(tokens[pos].clone());
+                    pos += 1;
+                    continue;
                 }
-                PatternFragment::Var(name) => {
-                    // Find the next literal to determine where this var ends
-                    let end_pos = if i + 1 < matcher.patterns.len() {
-                        if let PatternFragment::Literal(next_lit) = &matcher.patterns[i + 1] {
-                            if let Some(found_pos) = input[pos..].find(next_lit) {
-                                pos + found_pos
-                            } else {
-                                input.len()
-                            }
-                        } else {
-                            input.len()
-                        }
-                    } else {
-                        input.len()
+                Err(error) => {
+                    record.errors.push(error);
+                    let end = close.unwrap_or(tokens.len() - 1);
+                    output.extend_from_slice(&tokens[pos..=end]);
+                    pos = end + 1;
+                    continue;
+                }
+            };
+
+            let Some(close) = close else {
+                record.errors.push(MacroError::UnclosedDelimiter {
+                    name,
+                    span: Span::new(start, tokens[open - 1].span.end),
+                });
+                output.extend_from_slice(&tokens[pos..]);
+                break;
+            };
+
+            let call_site = Span::new(start, tokens[close].span.end);
+            let mut input = &tokens[open + 1..close];
+            let expanded_input;
+            if matches!(resolved, Resolved::Builtin(_)) && self.eager.contains(&name) {
+                expanded_input = self.expand_stream(input, scope, parent, depth + 1, record);
+                input = &expanded_input;
+            }
+
+            let expanded = if depth >= RECURSION_LIMIT {
+                Err(MacroError::RecursionLimit {
+                    name: name.clone(),
+                    limit: RECURSION_LIMIT,
+                    span: call_site,
+                })
+            } else {
+                let file = scope.source_map.and_then(|map| map.lookup(call_site.start.line));
+                let mut invocation = Invocation {
+                    call_site,
+                    module: scope.module,
+                    source_file: file.map(|file| file.path.as_path()).or(scope.source_file),
+                    crate_root: scope.source_file,
+                    line_offset: file.map_or(0, |file| file.line_offset),
+                    dependencies: &mut record.dependencies,
+                };
+                self.expand_invocation(&name, resolved, input, &mut invocation, parent, &mut record.span_map)
+            };
+
+            match expanded {
+                Ok((id, expanded)) => {
+                    // Invocations in the output also see the defining module's macros
+                    let def_module = match resolved {
+                        Resolved::Rules(_, def_module) => def_module,
+                        Resolved::Builtin(_) => scope.def_module,
                     };
+                    let inner = Scope { def_module, ..scope };
+                    output.extend(self.expand_stream(&expanded, inner, Some(id), depth + 1, record));
+                }
+                Err(error) => {
+                    record.errors.push(error);
+                    output.extend_from_slice(&tokens[pos..=close]);
+                }
+            }
+            pos = close + 1;
+        }
 
-                    binding.insert(name.clone(), input[pos..end_pos].to_string());
-                    pos = end_pos;
+        output
+    }
+
+    /// A macro registered from Rust
+    fn global(&self, name: &str) -> Option<Resolved<'_>> {
+        if let Some(builtin) = self.builtins.get(name) {
+            return Some(Resolved::Builtin(*builtin));
+        }
+        self.macros.get(name).map(|macro_def| Resolved::Rules(macro_def, &[]))
+    }
+
+    /// Find the macro an invocation path refers to
+    ///
+    /// A plain name is looked up in the invocation's module and its
+    /// ancestors, then among the registered macros; if it is not found, the
+    /// invocation is left for the parser. A longer path names the module the
+    /// macro is defined in, and must reach a `pub macro` unless the
+    /// invocation is inside that module.
+    fn resolve<'a>(&'a self, path: &[String], scope: Scope<'a>, span: Span) -> MacroResult<Option<Resolved<'a>>> {
+        let (name, prefix) = path.split_last().expect("macro paths are never empty");
+        if prefix.is_empty() {
+            for module in [scope.module, scope.def_module] {
+                for end in (0..=module.len()).rev() {
+                    if let Some(definition) = scope.definitions.find(&module[..end], name) {
+                        return Ok(Some(Resolved::Rules(&definition.macro_def, &definition.module)));
+                    }
+                }
+            }
+            return Ok(self.global(name));
+        }
+
+        let undefined = || MacroError::Undefined {
+            name: path.join("::"),
+            span,
+        };
+        let module = module_path(prefix, scope.module).ok_or_else(undefined)?;
+        let Some(definition) = scope.definitions.find(&module, name) else {
+            // Registered macros live at the crate root
+            return match self.global(name) {
+                Some(resolved) if module.is_empty() => Ok(Some(resolved)),
+                _ => Err(undefined()),
+            };
+        };
+
+        let inside = [scope.module, scope.def_module]
+            .iter()
+            .any(|module| module.starts_with(&definition.module));
+        if !definition.is_pub && !inside {
+            return Err(MacroError::Private {
+                name: path.join("::"),
+                span,
+            });
+        }
+        Ok(Some(Resolved::Rules(&definition.macro_def, &definition.module)))
+    }
+
+    /// Match the whole input against a pattern
+    fn try_match(&self, matcher: &MacroMatcher, input: &[Token]) -> Option<Bindings> {
+        match_fragments(&matcher.patterns, input, 0, None)
+            .and_then(|(bindings, end)| (end == input.len()).then_some(bindings))
+    }
+
+    /// Expand a template with variable bindings
+    fn expand_template(
+        &self,
+        name: &str,
+        expander: &MacroExpander,
+        bindings: &Bindings,
+        call_site: Span,
+        hygiene: &Hygiene,
+    ) -> MacroResult<Vec<Token>> {
+        let mut result = Vec::new();
+        let template = Template {
+            macro_name: name,
+            bindings,
+            call_site,
+            hygiene,
+        };
+        template.transcribe(&expander.template, &[], &mut result)?;
+        Ok(result)
+    }
+}
+
+impl Default for MacroExpanderEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Match `patterns` against the input from `pos`, without requiring all of it
+///
+/// `follow` is the token after this sequence in the enclosing pattern, which
+/// ends an untyped variable at the end of the sequence. Returns the bindings
+/// and the position after the match. Matching is greedy and never backtracks.
+fn match_fragments(
+    patterns: &[PatternFragment],
+    input: &[Token],
+    mut pos: usize,
+    follow: Option<&TokenKind>,
+) -> Option<(Bindings, usize)> {
+    let mut bindings = Bindings::new();
+
+    for (i, fragment) in patterns.iter().enumerate() {
+        let next = match patterns.get(i + 1) {
+            Some(PatternFragment::Literal(kind)) => Some(kind),
+            Some(_) => None,
+            None => follow,
+        };
+
+        match fragment {
+            PatternFragment::Literal(kind) => {
+                if input.get(pos)?.kind != *kind {
+                    return None;
+                }
+                pos += 1;
+            }
+            PatternFragment::Var(name) => {
+                let end = match patterns.get(i + 1) {
+                    Some(PatternFragment::Literal(kind)) => find_at_depth_zero(input, pos, kind)?,
+                    Some(_) => input.len(),
+                    None => next
+                        .and_then(|kind| find_at_depth_zero(input, pos, kind))
+                        .unwrap_or(input.len()),
+                };
+                bindings.insert(name.clone(), Binding::Single(input[pos..end].to_vec()));
+                pos = end;
+            }
+            PatternFragment::Fragment { name, kind } => {
+                let len = Parser::fragment_len(&input[pos..], *kind)?;
+                let mut tokens = input[pos..pos + len].to_vec();
+                // An expression stays one operand wherever it is substituted
+                if *kind == FragmentKind::Expr && len > 1 {
+                    let span = Span::new(tokens[0].span.start, tokens[len - 1].span.end);
+                    tokens.insert(0, Token { kind: TokenKind::LeftParen, span });
+                    tokens.push(Token { kind: TokenKind::RightParen, span });
+                }
+                bindings.insert(name.clone(), Binding::Single(tokens));
+                pos += len;
+            }
+            PatternFragment::Repetition { inner, separator, op } => {
+                let follow = separator.as_ref().or(next);
+                let mut iterations: Vec<Bindings> = Vec::new();
+
+                while *op != RepetitionOp::ZeroOrOne || iterations.is_empty() {
+                    let mut start = pos;
+                    if let (Some(separator), false) = (separator, iterations.is_empty()) {
+                        if input.get(pos).map(|token| &token.kind) != Some(separator) {
+                            break;
+                        }
+                        start += 1;
+                    }
+                    match match_fragments(inner, input, start, follow) {
+                        Some((iteration, end)) if end > start => {
+                            iterations.push(iteration);
+                            pos = end;
+                        }
+                        _ => break,
+                    }
                 }
-                PatternFragment::Repetition { .. } => {
+
+                if *op == RepetitionOp::OneOrMore && iterations.is_empty() {
                     return None;
                 }
+
+                let mut names = Vec::new();
+                pattern_variables(inner, &mut names);
+                for name in names {
+                    let items = iterations
+                        .iter_mut()
+                        .map(|iteration| iteration.remove(&name).unwrap_or(Binding::Repeated(Vec::new())))
+                        .collect();
+                    bindings.insert(name, Binding::Repeated(items));
+                }
             }
         }
+    }
 
-        Some(binding)
+    Some((bindings, pos))
+}
+
+fn pattern_variables(patterns: &[PatternFragment], names: &mut Vec<String>) {
+    for fragment in patterns {
+        match fragment {
+            PatternFragment::Var(name) | PatternFragment::Fragment { name, .. } => names.push(name.clone()),
+            PatternFragment::Repetition { inner, .. } => pattern_variables(inner, names),
+            PatternFragment::Literal(_) => {}
+        }
     }
+}
 
-    /// Expand a template with variable bindings
-    fn expand_template(&self, expander: &MacroExpander, binding: &HashMap<String, String>) -> String {
-        let mut result = String::new();
+fn template_variables<'t>(template: &'t [TemplateFragment], names: &mut Vec<&'t str>) {
+    for fragment in template {
+        match fragment {
+            TemplateFragment::Var(name) => names.push(name),
+            TemplateFragment::Repetition { inner, .. } => template_variables(inner, names),
+            TemplateFragment::Literal(_) => {}
+        }
+    }
+}
+
+/// The binding of a variable inside the repetitions at `indices`
+///
+/// Variables that repeat less deeply than the template position keep their
+/// outermost value.
+fn select<'b>(mut binding: &'b Binding, indices: &[usize]) -> &'b Binding {
+    for &index in indices {
+        match binding {
+            Binding::Repeated(items) if index < items.len() => binding = &items[index],
+            _ => break,
+        }
+    }
+    binding
+}
+
+/// Everything a template is expanded with
+struct Template<'a> {
+    macro_name: &'a str,
+    bindings: &'a Bindings,
+    call_site: Span,
+    hygiene: &'a Hygiene,
+}
 
-        for frag in &expander.template {
-            match frag {
-                TemplateFragment::Literal(lit) => {
-                    result.push_str(lit);
+impl Template<'_> {
+    /// Append the expansion of `template` inside the repetitions at `indices`
+    fn transcribe(&self, template: &[TemplateFragment], indices: &[usize], out: &mut Vec<Token>) -> MacroResult<()> {
+        for fragment in template {
+            match fragment {
+                TemplateFragment::Literal(kind) => {
+                    out.push(Token {
+                        kind: self.hygiene.apply(kind),
+                        span: self.call_site,
+                    });
                 }
-                TemplateFragment::Var(name) => {
-                    if let Some(value) = binding.get(name) {
-                        result.push_str(value);
+                TemplateFragment::Var(var) => match select(self.binding(var)?, indices) {
+                    Binding::Single(tokens) => out.extend_from_slice(tokens),
+                    Binding::Repeated(_) => return Err(self.mismatch(var)),
+                },
+                TemplateFragment::Repetition { inner, separator, var } => {
+                    for index in 0..self.repetitions(inner, var, indices)? {
+                        if let (Some(separator), true) = (separator, index > 0) {
+                            out.push(Token {
+                                kind: separator.clone(),
+                                span: self.call_site,
+                            });
+                        }
+                        let mut nested = indices.to_vec();
+                        nested.push(index);
+                        self.transcribe(inner, &nested, out)?;
                     }
                 }
-                TemplateFragment::Repetition { .. } => {
-                    // TODO
+            }
+        }
+        Ok(())
+    }
+
+    /// How often a template repetition repeats: once per match of the
+    /// repeating variables inside it, which must all agree
+    fn repetitions(&self, inner: &[TemplateFragment], var: &str, indices: &[usize]) -> MacroResult<usize> {
+        let mut names = Vec::new();
+        template_variables(inner, &mut names);
+
+        let mut count = None;
+        for name in names {
+            if let Binding::Repeated(items) = select(self.binding(name)?, indices) {
+                match count {
+                    None => count = Some(items.len()),
+                    Some(count) if count != items.len() => return Err(self.mismatch(name)),
+                    Some(_) => {}
                 }
             }
         }
+        count.ok_or_else(|| self.mismatch(var))
+    }
 
-        result
+    fn binding(&self, var: &str) -> MacroResult<&Binding> {
+        self.bindings.get(var).ok_or_else(|| MacroError::UnknownVariable {
+            macro_name: self.macro_name.to_string(),
+            name: var.to_string(),
+            span: self.call_site,
+        })
+    }
+
+    fn mismatch(&self, var: &str) -> MacroError {
+        MacroError::RepetitionMismatch {
+            macro_name: self.macro_name.to_string(),
+            name: var.to_string(),
+            span: self.call_site,
+        }
     }
 }
 
-impl Default for MacroExpanderEngine {
-    fn default() -> Self {
-        Self::new()
+/// Renames locals bound by a template, once per expansion
+///
+/// The new name contains `$`, which the lexer never puts in an identifier,
+/// so it cannot collide with anything the user wrote.
+struct Hygiene {
+    bound: HashSet<String>,
+    id: ExpansionId,
+}
+
+impl Hygiene {
+    fn new(expander: &MacroExpander, id: ExpansionId) -> Self {
+        let mut bound = HashSet::new();
+        collect_bindings(&expander.template, &mut bound);
+        Hygiene { bound, id }
+    }
+
+    fn apply(&self, kind: &TokenKind) -> TokenKind {
+        match kind {
+            TokenKind::Ident(name) if self.bound.contains(&**name) => {
+                TokenKind::Ident(format!("{}${}", name, self.id.index()).into())
+            }
+            _ => kind.clone(),
+        }
+    }
+}
+
+/// Names bound by `let`, `let mut` or `for` in template literals
+fn collect_bindings(template: &[TemplateFragment], bound: &mut HashSet<String>) {
+    let mut previous: [Option<&TokenKind>; 2] = [None, None];
+    for frag in template {
+        match frag {
+            TemplateFragment::Literal(kind) => {
+                if let TokenKind::Ident(name) = kind {
+                    let binds = matches!(
+                        previous,
+                        [_, Some(TokenKind::Let | TokenKind::For)] | [Some(TokenKind::Let), Some(TokenKind::Mut)]
+                    );
+                    if binds {
+                        bound.insert(name.to_string());
+                    }
+                }
+                previous = [previous[1], Some(kind)];
+            }
+            TemplateFragment::Repetition { inner, .. } => {
+                collect_bindings(inner, bound);
+                previous = [None, None];
+            }
+            TemplateFragment::Var(_) => previous = [None, None],
+        }
+    }
+}
+
+/// Collect the `macro` items of a token stream, noting the module of each
+///
+/// Items that fail to parse are skipped here; the parser reports them.
+fn collect_definitions(tokens: &[Token], errors: &mut Vec<MacroError>) -> Definitions {
+    let mut definitions = Definitions::default();
+    let mut modules = ModuleTracker::new(&[]);
+    let mut pos = 0;
+
+    while pos < tokens.len() {
+        let Some(close) = macro_item_at(tokens, pos) else {
+            modules.step(tokens, pos);
+            pos += 1;
+            continue;
+        };
+
+        let start = if pos > 0 && tokens[pos - 1].kind == TokenKind::Pub { pos - 1 } else { pos };
+        if let Some(def) = parse_macro_item(&tokens[start..=close]) {
+            match Macro::from_def(&def) {
+                Ok(macro_def) => {
+                    let definition = Definition {
+                        macro_def,
+                        module: modules.path().to_vec(),
+                        is_pub: def.is_pub,
+                    };
+                    if definitions.insert(definition).is_err() {
+                        errors.push(MacroError::Redefined {
+                            name: def.name.name.clone(),
+                            span: def.name.span,
+                        });
+                    }
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+        pos = close + 1;
+    }
+
+    definitions
+}
+
+/// The index of the closing brace of a `macro name { ... }` item at `pos`
+fn macro_item_at(tokens: &[Token], pos: usize) -> Option<usize> {
+    let kinds = (&tokens[pos].kind, tokens.get(pos + 1)?, tokens.get(pos + 2)?);
+    match kinds {
+        (TokenKind::Ident(keyword), name, open)
+            if &**keyword == "macro"
+                && matches!(name.kind, TokenKind::Ident(_))
+                && open.kind == TokenKind::LeftBrace =>
+        {
+            matching_delimiter(tokens, pos + 2)
+        }
+        _ => None,
+    }
+}
+
+fn parse_macro_item(tokens: &[Token]) -> Option<MacroDef> {
+    let ast = Parser::new(tokens.to_vec()).parse().ok()?;
+    match ast.items.into_iter().next()?.kind {
+        ItemKind::MacroDef(def) => Some(def),
+        _ => None,
+    }
+}
+
+/// An invocation `a::b::name!(` at `pos`: the path and the opening delimiter's index
+fn invocation_at(tokens: &[Token], pos: usize) -> Option<(Vec<String>, usize)> {
+    // Not the middle of a longer path
+    if pos > 0 && tokens[pos - 1].kind == TokenKind::PathSep {
+        return None;
+    }
+
+    let mut path = Vec::new();
+    let mut i = pos;
+    loop {
+        let TokenKind::Ident(segment) = &tokens.get(i)?.kind else {
+            return None;
+        };
+        path.push(segment.to_string());
+        match tokens.get(i + 1)?.kind {
+            TokenKind::PathSep => i += 2,
+            TokenKind::Bang => break,
+            _ => return None,
+        }
+    }
+
+    let open = i + 2;
+    closing_delimiter(&tokens.get(open)?.kind)?;
+    Some((path, open))
+}
+
+/// A pattern or template split into tokens, variables and repetitions
+enum Piece {
+    Token(TokenKind),
+    Var { name: String, kind: Option<FragmentKind> },
+    Repetition { inner: Vec<Piece>, separator: Option<TokenKind>, op: RepetitionOp },
+}
+
+impl Piece {
+    fn into_pattern(self) -> PatternFragment {
+        match self {
+            Piece::Token(kind) => PatternFragment::Literal(kind),
+            Piece::Var { name, kind: None } => PatternFragment::Var(name),
+            Piece::Var { name, kind: Some(kind) } => PatternFragment::Fragment { name, kind },
+            Piece::Repetition { inner, separator, op } => PatternFragment::Repetition {
+                inner: inner.into_iter().map(Piece::into_pattern).collect(),
+                separator,
+                op,
+            },
+        }
+    }
+
+    fn into_template(self) -> TemplateFragment {
+        match self {
+            Piece::Token(kind) => TemplateFragment::Literal(kind),
+            Piece::Var { name, .. } => TemplateFragment::Var(name),
+            Piece::Repetition { inner, separator, .. } => {
+                let var = first_variable(&inner).unwrap_or_default().to_string();
+                TemplateFragment::Repetition {
+                    inner: inner.into_iter().map(Piece::into_template).collect(),
+                    separator,
+                    var,
+                }
+            }
+        }
     }
 }
 
+fn first_variable(pieces: &[Piece]) -> Option<&str> {
+    pieces.iter().find_map(|piece| match piece {
+        Piece::Var { name, .. } => Some(name.as_str()),
+        Piece::Repetition { inner, .. } => first_variable(inner),
+        Piece::Token(_) => None,
+    })
+}
+
+/// Split rule tokens into pieces
+///
+/// Fragment specifiers are only read in patterns; in a template, `$x: T`
+/// is a variable followed by a colon.
+fn pieces(tokens: &[Token], in_pattern: bool) -> MacroResult<Vec<Piece>> {
+    let invalid = |message: String, span: Span| MacroError::InvalidRule { message, span };
+    let mut output = Vec::new();
+    let mut pos = 0;
+
+    while pos < tokens.len() {
+        let token = &tokens[pos];
+        let next = tokens.get(pos + 1).map(|next| &next.kind);
+        if token.kind != TokenKind::Dollar {
+            output.push(Piece::Token(token.kind.clone()));
+            pos += 1;
+            continue;
+        }
+
+        if next == Some(&TokenKind::LeftParen) {
+            let close = matching_delimiter(tokens, pos + 1)
+                .ok_or_else(|| invalid("unclosed `$(`".to_string(), token.span))?;
+            let inner = pieces(&tokens[pos + 2..close], in_pattern)?;
+            if first_variable(&inner).is_none() {
+                return Err(invalid("repetition without variables".to_string(), token.span));
+            }
+
+            let op_at = |i: usize| tokens.get(i).and_then(|token| RepetitionOp::from_token(&token.kind));
+            let (separator, op, end) = match (op_at(close + 1), op_at(close + 2)) {
+                (Some(op), _) => (None, op, close + 2),
+                (None, Some(op)) => (Some(tokens[close + 1].kind.clone()), op, close + 3),
+                (None, None) => {
+                    let message = "expected `*`, `+` or `?` after `$( ... )`".to_string();
+                    return Err(invalid(message, tokens[close].span));
+                }
+            };
+            output.push(Piece::Repetition { inner, separator, op });
+            pos = end;
+            continue;
+        }
+
+        let Some(name) = next.and_then(variable_name) else {
+            output.push(Piece::Token(TokenKind::Dollar));
+            pos += 1;
+            continue;
+        };
+        pos += 2;
+
+        let mut kind = None;
+        if in_pattern && tokens.get(pos).map(|token| &token.kind) == Some(&TokenKind::Colon) {
+            let specifier = tokens.get(pos + 1).and_then(|token| variable_name(&token.kind));
+            let fragment = specifier.as_deref().and_then(FragmentKind::from_name).ok_or_else(|| {
+                let message = format!("expected a fragment specifier after `${}:`", name);
+                invalid(message, tokens[pos].span)
+            })?;
+            kind = Some(fragment);
+            pos += 2;
+        }
+        output.push(Piece::Var { name, kind });
+    }
+
+    Ok(output)
+}
+
+/// The name of a macro variable: an identifier or keyword after `$`
+fn variable_name(kind: &TokenKind) -> Option<String> {
+    let text = kind.to_source();
+    let is_word = text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_alphanumeric() || c == '_');
+    is_word.then_some(text)
+}
+
+fn lex(source: &str) -> Vec<Token> {
+    Lexer::new(source)
+        .lex_all()
+        .0
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .collect()
+}
+
+fn closing_delimiter(kind: &TokenKind) -> Option<TokenKind> {
+    match kind {
+        TokenKind::LeftParen => Some(TokenKind::RightParen),
+        TokenKind::LeftBracket => Some(TokenKind::RightBracket),
+        TokenKind::LeftBrace => Some(TokenKind::RightBrace),
+        _ => None,
+    }
+}
+
+/// Index of the delimiter closing the one at `open`
+fn matching_delimiter(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut stack = Vec::new();
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if let Some(close) = closing_delimiter(&token.kind) {
+            stack.push(close);
+        } else if matches!(token.kind, TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace) {
+            if stack.pop() != Some(token.kind.clone()) {
+                return None;
+            }
+            if stack.is_empty() {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Index of the first `kind` at or after `start` outside any delimiters
+fn find_at_depth_zero(tokens: &[Token], start: usize, kind: &TokenKind) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if depth == 0 && &token.kind == kind {
+            return Some(i);
+        }
+        match token.kind {
+            TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split macro input at top-level commas, allowing a trailing comma
+fn split_arguments(input: &[Token]) -> Vec<&[Token]> {
+    let mut arguments = Vec::new();
+    let mut start = 0;
+    while start < input.len() {
+        let end = find_at_depth_zero(input, start, &TokenKind::Comma).unwrap_or(input.len());
+        arguments.push(&input[start..end]);
+        start = end + 1;
+    }
+    arguments
+}
+
+/// Render tokens as source text
+///
+/// Spacing is normalized: `f(a, b)` and `f( a , b )` both render as
+/// `f(a, b)`.
+pub fn tokens_to_string(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    let mut previous: Option<&TokenKind> = None;
+
+    for token in tokens {
+        let text = token.kind.to_source();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(previous) = previous {
+            if needs_space(previous, &token.kind) {
+                out.push(' ');
+            }
+        }
+        out.push_str(&text);
+        previous = Some(&token.kind);
+    }
+
+    out
+}
+
+fn needs_space(previous: &TokenKind, next: &TokenKind) -> bool {
+    let glued_after = matches!(
+        previous,
+        TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::Dot | TokenKind::PathSep | TokenKind::Bang
+    );
+    let glued_before = matches!(
+        next,
+        TokenKind::RightParen
+            | TokenKind::RightBracket
+            | TokenKind::Comma
+            | TokenKind::Semicolon
+            | TokenKind::Dot
+            | TokenKind::PathSep
+            | TokenKind::Question
+            | TokenKind::Colon
+    );
+    let call = matches!(next, TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::Bang)
+        && matches!(previous, TokenKind::Ident(_) | TokenKind::RightParen | TokenKind::RightBracket);
+    !(glued_after || glued_before || call)
+}
+
 /// Built-in macros
 impl MacroExpanderEngine {
     /// Create with all built-in macros registered
@@ -190,228 +1315,83 @@ impl MacroExpanderEngine {
         engine
     }
 
+    /// Register a macro made of rules written as source text
+    fn register_rules(&mut self, name: &str, rules: &[(&str, &str)]) {
+        self.register_macro(Macro {
+            name: Identifier::new(name),
+            rules: rules
+                .iter()
+                .map(|(pattern, template)| MacroRule::new(pattern, template))
+                .collect(),
+        });
+    }
+
     /// Register all built-in macros
     fn register_assert_macros(&mut self) {
-        // panic!($message)
-        self.macros.insert("panic".to_string(), Macro {
-            name: Identifier::new("panic"),
-            rules: vec![
-                // Simple form: panic!("message") - match just the message content
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("message".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "::__zulon_builtin_panic(".to_string()
-                            ),
-                            TemplateFragment::Var("message".to_string()),
-                            TemplateFragment::Literal(")".to_string()),
-                        ],
-                    },
-                },
-                // Formatted form: panic!("format: {}", arg1, arg2)
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("format_string".to_string()),
-                            PatternFragment::Literal(", ".to_string()),
-                            PatternFragment::Var("args".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "::__zulon_builtin_panic_formatted(".to_string()
-                            ),
-                            TemplateFragment::Var("format_string".to_string()),
-                            TemplateFragment::Literal(", ".to_string()),
-                            TemplateFragment::Var("args".to_string()),
-                            TemplateFragment::Literal(")".to_string()),
-                        ],
-                    },
-                },
-            ],
-        });
+        // panic!("message") or panic!("format: {}", arg1, arg2)
+        self.register_builtin("panic", format::expand_panic);
 
-        // stringify!($expr) - converts expression to string
-        self.macros.insert("stringify".to_string(), Macro {
-            name: Identifier::new("stringify"),
-            rules: vec![
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("expr".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal("\"".to_string()),
-                            TemplateFragment::Var("expr".to_string()),
-                            TemplateFragment::Literal("\"".to_string()),
-                        ],
-                    },
-                },
-            ],
+        // stringify!($expr) - converts tokens to a string literal
+        self.register_builtin("stringify", |input, invocation| {
+            Ok(vec![Token {
+                kind: TokenKind::StringLiteral(tokens_to_string(input).into()),
+                span: invocation.call_site,
+            }])
         });
 
         // assert!($condition)
-        self.macros.insert("assert".to_string(), Macro {
-            name: Identifier::new("assert"),
-            rules: vec![
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("condition".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "if (".to_string()
-                            ),
-                            TemplateFragment::Var("condition".to_string()),
-                            TemplateFragment::Literal(
-                                ") { } else { return 1; }".to_string()
-                            ),
-                        ],
-                    },
-                },
-            ],
-        });
+        self.register_rules("assert", &[("$condition", "if ($condition) { } else { return 1; }")]);
 
         // assert_eq!($left, $right)
         // Expands to an if statement that checks the condition
         // NOTE: Early return from if blocks is a known limitation in current MIR lowering
         // The macro compiles successfully but the return doesn't actually exit the function early
         // This will be fixed when proper early return support is implemented
-        self.macros.insert("assert_eq".to_string(), Macro {
-            name: Identifier::new("assert_eq"),
-            rules: vec![
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("left".to_string()),
-                            PatternFragment::Literal(", ".to_string()),
-                            PatternFragment::Var("right".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "if (".to_string()
-                            ),
-                            TemplateFragment::Var("left".to_string()),
-                            TemplateFragment::Literal(
-                                " != ".to_string()
-                            ),
-                            TemplateFragment::Var("right".to_string()),
-                            TemplateFragment::Literal(
-                                ") { return 1; }".to_string()
-                            ),
-                        ],
-                    },
-                },
-            ],
-        });
+        self.register_rules("assert_eq", &[("$left, $right", "if ($left != $right) { return 1; }")]);
 
         // assert_ne!($left, $right)
-        self.macros.insert("assert_ne".to_string(), Macro {
-            name: Identifier::new("assert_ne"),
-            rules: vec![
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("left".to_string()),
-                            PatternFragment::Literal(", ".to_string()),
-                            PatternFragment::Var("right".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "if (".to_string()
-                            ),
-                            TemplateFragment::Var("left".to_string()),
-                            TemplateFragment::Literal(
-                                " == ".to_string()
-                            ),
-                            TemplateFragment::Var("right".to_string()),
-                            TemplateFragment::Literal(
-                                ") {\n    \
-                                    ::__zulon_builtin_panic(\"assertion failed: \", stringify!(".to_string()
-                            ),
-                            TemplateFragment::Var("left".to_string()),
-                            TemplateFragment::Literal(
-                                "), \" == \", stringify!(".to_string()
-                            ),
-                            TemplateFragment::Var("right".to_string()),
-                            TemplateFragment::Literal(
-                                "));\n\
-                                }".to_string()
-                            ),
-                        ],
-                    },
-                },
-            ],
-        });
+        self.register_rules(
+            "assert_ne",
+            &[(
+                "$left, $right",
+                r#"if ($left == $right) {
+                    ::__zulon_builtin_panic("assertion failed: ", stringify!($left), " == ", stringify!($right));
+                }"#,
+            )],
+        );
 
-        // println!($format_string, $args...)
-        // Expands to a call to external printf function
-        // Note: Requires "extern fn printf(s: &u8, ...) -> i32;" at module level
-        // Simple form: println!("Hello")
-        self.macros.insert("println".to_string(), Macro {
-            name: Identifier::new("println"),
-            rules: vec![
-                // Simple form with just format string
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("format_string".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "printf(".to_string()
-                            ),
-                            TemplateFragment::Var("format_string".to_string()),
-                            TemplateFragment::Literal(");\n".to_string()),
-                        ],
-                    },
-                },
-                // Form with arguments: println!("Value: {}", x)
-                MacroRule {
-                    matcher: MacroMatcher {
-                        patterns: vec![
-                            PatternFragment::Var("format_string".to_string()),
-                            PatternFragment::Literal(", ".to_string()),
-                            PatternFragment::Var("args".to_string()),
-                        ],
-                    },
-                    expander: MacroExpander {
-                        template: vec![
-                            TemplateFragment::Literal(
-                                "printf(".to_string()
-                            ),
-                            TemplateFragment::Var("format_string".to_string()),
-                            TemplateFragment::Literal(", ".to_string()),
-                            TemplateFragment::Var("args".to_string()),
-                            TemplateFragment::Literal(");\n".to_string()),
-                        ],
-                    },
-                },
-            ],
-        });
+        // format!("{} {name:>8.2}", a, name = b), checked at compile time
+        // The result is a template string; see the `format` module
+        self.register_builtin("format", format::expand_format);
+
+        // print!(...) and println!(...) pass the formatted string to printf
+        self.register_builtin("print", format::expand_print);
+        self.register_builtin("println", format::expand_println);
+
+        // Compile-time values; see the `builtins` module
+        self.register_eager_builtin("include_str", builtins::expand_include_str);
+        self.register_eager_builtin("include_bytes", builtins::expand_include_bytes);
+        self.register_eager_builtin("env", builtins::expand_env);
+        self.register_eager_builtin("option_env", builtins::expand_option_env);
+        self.register_eager_builtin("concat", builtins::expand_concat);
+        self.register_builtin("file", builtins::expand_file);
+        self.register_builtin("line", builtins::expand_line);
+        self.register_builtin("column", builtins::expand_column);
+        self.register_builtin("module_path", builtins::expand_module_path);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zulon_parser::Position;
+
+    fn expand(engine: &MacroExpanderEngine, name: &str, input: &str) -> MacroResult<String> {
+        let call_site = Span::new(Position::new(1, 1), Position::new(1, 1));
+        engine
+            .expand(name, &lex(input), call_site, &mut SpanMap::new())
+            .map(|tokens| tokens_to_string(&tokens))
+    }
 
     #[test]
     fn test_macro_expander_creation() {
@@ -422,18 +1402,18 @@ mod tests {
     #[test]
     fn test_builtin_macros() {
         let engine = MacroExpanderEngine::with_builtins();
-        assert!(engine.macros.contains_key("assert"));
-        assert!(engine.macros.contains_key("assert_eq"));
-        assert!(engine.macros.contains_key("assert_ne"));
-        assert!(engine.macros.contains_key("panic"));
-        assert!(engine.macros.contains_key("stringify"));
-        assert!(engine.macros.contains_key("println"));
+        assert!(engine.is_defined("assert"));
+        assert!(engine.is_defined("assert_eq"));
+        assert!(engine.is_defined("assert_ne"));
+        assert!(engine.is_defined("panic"));
+        assert!(engine.is_defined("stringify"));
+        assert!(engine.is_defined("println"));
     }
 
     #[test]
     fn test_panic_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("panic", "\"test message\"");
+        let result = expand(&engine, "panic", "\"test message\"");
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("panic! expansion: {}", expanded);
@@ -443,7 +1423,7 @@ mod tests {
     #[test]
     fn test_stringify_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("stringify", "x + y");
+        let result = expand(&engine, "stringify", "x + y");
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("stringify! expansion: {}", expanded);
@@ -453,7 +1433,7 @@ mod tests {
     #[test]
     fn test_assert_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("assert", "x > 0");
+        let result = expand(&engine, "assert", "x > 0");
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("assert! expansion: {}", expanded);
@@ -466,7 +1446,7 @@ mod tests {
     #[test]
     fn test_assert_eq_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("assert_eq", "a, b");
+        let result = expand(&engine, "assert_eq", "a, b");
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("assert_eq! expansion: {}", expanded);
@@ -481,7 +1461,7 @@ mod tests {
     #[test]
     fn test_assert_ne_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("assert_ne", "a, b");
+        let result = expand(&engine, "assert_ne", "a, b");
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("assert_ne! expansion: {}", expanded);
@@ -504,7 +1484,8 @@ mod tests {
                     },
                     expander: MacroExpander {
                         template: vec![
-                            TemplateFragment::Literal("result: ".to_string()),
+                            TemplateFragment::Literal(TokenKind::Ident("result".into())),
+                            TemplateFragment::Literal(TokenKind::Colon),
                             TemplateFragment::Var("x".to_string()),
                         ],
                     },
@@ -512,8 +1493,7 @@ mod tests {
             ],
         });
 
-        // Test with proper input that matches pattern "!(...)"
-        let result = engine.expand("test", "foo)");
+        let result = expand(&engine, "test", "foo)");
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "result: foo)");
     }
@@ -521,7 +1501,7 @@ mod tests {
     #[test]
     fn test_println_macro() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("println", r#""Hello, World!""#);
+        let result = expand(&engine, "println", r#""Hello, World!""#);
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("println! expansion: {}", expanded);
@@ -532,12 +1512,31 @@ mod tests {
     #[test]
     fn test_println_with_args() {
         let engine = MacroExpanderEngine::with_builtins();
-        let result = engine.expand("println", r#""Value: {}", x"#);
+        let result = expand(&engine, "println", r#""Value: {}", x"#);
         assert!(result.is_ok());
         let expanded = result.unwrap();
         println!("println! with args expansion: {}", expanded);
-        assert!(expanded.contains("printf("));
-        assert!(expanded.contains("Value: {}"));
-        assert!(expanded.contains("x"));
+        assert_eq!(expanded, r#"printf("%s\n", `Value: ${x}`)"#);
+    }
+
+    #[test]
+    fn test_arguments_split_at_top_level_commas() {
+        let engine = MacroExpanderEngine::with_builtins();
+        let expanded = expand(&engine, "assert_eq", "f(a, b), [1, 2]").unwrap();
+        assert_eq!(expanded, "if (f(a, b) != [1, 2]) { return 1; }");
+    }
+
+    #[test]
+    fn test_no_matching_rule() {
+        let mut engine = MacroExpanderEngine::new();
+        engine.register_rules("pair", &[("$a, $b", "($a, $b)")]);
+        assert!(matches!(
+            expand(&engine, "pair", "1"),
+            Err(MacroError::NoMatchingRule { .. })
+        ));
+        assert!(matches!(
+            expand(&engine, "missing", "1"),
+            Err(MacroError::Undefined { .. })
+        ));
     }
 }
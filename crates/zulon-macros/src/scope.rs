@@ -0,0 +1,152 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Macros defined in source code and the modules they live in
+//!
+//! A `macro` item is visible in the module that defines it and in that
+//! module's descendants. Other modules reach it by path, `utils::square!(x)`,
+//! which requires `pub macro`.
+
+use std::collections::HashMap;
+use zulon_parser::{Token, TokenKind};
+
+use crate::Macro;
+
+/// A macro defined by a `macro` item
+#[derive(Debug, Clone)]
+pub(crate) struct Definition {
+    pub macro_def: Macro,
+    /// Path of the inline module the item is in, empty at the crate root
+    pub module: Vec<String>,
+    pub is_pub: bool,
+}
+
+/// Every `macro` item of a token stream, by name
+#[derive(Debug, Default)]
+pub(crate) struct Definitions {
+    by_name: HashMap<String, Vec<Definition>>,
+}
+
+impl Definitions {
+    /// Add a definition, returning it back if its module already has one of the same name
+    pub fn insert(&mut self, definition: Definition) -> Result<(), Definition> {
+        let name = definition.macro_def.name.name.clone();
+        let entries = self.by_name.entry(name).or_default();
+        if entries.iter().any(|existing| existing.module == definition.module) {
+            return Err(definition);
+        }
+        entries.push(definition);
+        Ok(())
+    }
+
+    /// The macro `name` defined directly in `module`
+    pub fn find(&self, module: &[String], name: &str) -> Option<&Definition> {
+        self.by_name.get(name)?.iter().find(|definition| definition.module == module)
+    }
+}
+
+/// The module a `path::` prefix names, relative to `current`
+///
+/// Paths start at the crate root unless they begin with `self` or `super`;
+/// a leading `crate` is allowed. Returns `None` for `super` at the root.
+pub(crate) fn module_path(prefix: &[String], current: &[String]) -> Option<Vec<String>> {
+    let mut segments = prefix.iter().peekable();
+    let mut path = match segments.peek().map(|segment| segment.as_str()) {
+        Some("crate") => {
+            segments.next();
+            Vec::new()
+        }
+        Some("self" | "super") => current.to_vec(),
+        _ => Vec::new(),
+    };
+
+    if segments.peek().map(|segment| segment.as_str()) == Some("self") {
+        segments.next();
+    }
+    while segments.peek().map(|segment| segment.as_str()) == Some("super") {
+        segments.next();
+        path.pop()?;
+    }
+    path.extend(segments.cloned());
+    Some(path)
+}
+
+/// Follows `mod name { ... }` blocks while scanning a token stream
+pub(crate) struct ModuleTracker {
+    path: Vec<String>,
+    /// Brace depth just inside each module on `path`
+    opened_at: Vec<usize>,
+    depth: usize,
+}
+
+impl ModuleTracker {
+    pub fn new(module: &[String]) -> Self {
+        ModuleTracker {
+            path: module.to_vec(),
+            opened_at: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// The module the scanned position is in
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// Account for the token at `pos`
+    pub fn step(&mut self, tokens: &[Token], pos: usize) {
+        match &tokens[pos].kind {
+            TokenKind::Mod => {
+                let kinds = (tokens.get(pos + 1).map(|t| &t.kind), tokens.get(pos + 2).map(|t| &t.kind));
+                if let (Some(TokenKind::Ident(name)), Some(TokenKind::LeftBrace)) = kinds {
+                    self.path.push(name.to_string());
+                    self.opened_at.push(self.depth + 1);
+                }
+            }
+            TokenKind::LeftBrace => self.depth += 1,
+            TokenKind::RightBrace => {
+                if self.opened_at.last() == Some(&self.depth) {
+                    self.opened_at.pop();
+                    self.path.pop();
+                }
+                self.depth = self.depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|segment| segment.to_string()).collect()
+    }
+
+    #[test]
+    fn test_module_path() {
+        let current = path(&["a", "b"]);
+        assert_eq!(module_path(&path(&["x"]), &current), Some(path(&["x"])));
+        assert_eq!(module_path(&path(&["crate", "x"]), &current), Some(path(&["x"])));
+        assert_eq!(module_path(&path(&["self", "c"]), &current), Some(path(&["a", "b", "c"])));
+        assert_eq!(module_path(&path(&["super", "c"]), &current), Some(path(&["a", "c"])));
+        assert_eq!(module_path(&path(&["super", "super", "super"]), &current), None);
+    }
+
+    #[test]
+    fn test_module_tracker() {
+        let (tokens, _) = zulon_parser::Lexer::new("mod a { fn f() { } mod b { x } y } z").lex_all();
+        let mut tracker = ModuleTracker::new(&[]);
+        let mut seen = Vec::new();
+        for pos in 0..tokens.len() {
+            tracker.step(&tokens, pos);
+            if let TokenKind::Ident(name) = &tokens[pos].kind {
+                if ["x", "y", "z"].contains(&&**name) {
+                    seen.push(tracker.path().join("::"));
+                }
+            }
+        }
+        assert_eq!(seen, ["a::b", "a", ""]);
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Expansion-origin span map
+//!
+//! Expanded tokens keep spans in the original source: tokens written in a
+//! macro template take the span of the invocation, and tokens passed as
+//! arguments keep their own. The [`SpanMap`] records every expansion so
+//! diagnostics can say which macro a span came from.
+
+use zulon_parser::{Position, Span};
+
+/// Identifies one macro expansion in a [`SpanMap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpansionId(usize);
+
+impl ExpansionId {
+    /// Index of the expansion, in the order expansions happened
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Where a macro expansion came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpansionInfo {
+    /// Name of the expanded macro, without the `!`
+    pub macro_name: String,
+    /// Span of the whole invocation in the original source
+    pub call_site: Span,
+    /// The expansion whose output contained this invocation
+    pub parent: Option<ExpansionId>,
+}
+
+/// Every expansion performed on a token stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpanMap {
+    expansions: Vec<ExpansionInfo>,
+}
+
+impl SpanMap {
+    /// Create an empty span map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an expansion
+    pub fn push(&mut self, info: ExpansionInfo) -> ExpansionId {
+        self.expansions.push(info);
+        ExpansionId(self.expansions.len() - 1)
+    }
+
+    /// Look up an expansion
+    pub fn get(&self, id: ExpansionId) -> Option<&ExpansionInfo> {
+        self.expansions.get(id.0)
+    }
+
+    /// Number of expansions
+    pub fn len(&self) -> usize {
+        self.expansions.len()
+    }
+
+    /// Whether no macro was expanded
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+
+    /// All expansions, in the order they happened
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (ExpansionId, &ExpansionInfo)> {
+        self.expansions.iter().enumerate().map(|(index, info)| (ExpansionId(index), info))
+    }
+
+    /// The innermost expansion whose invocation covers `span`
+    ///
+    /// Returns `None` for spans outside every macro invocation.
+    pub fn expansion_of(&self, span: Span) -> Option<ExpansionId> {
+        self.iter()
+            .rev()
+            .find(|(_, info)| contains(info.call_site, span))
+            .map(|(id, _)| id)
+    }
+
+    /// The chain of expansions from `id` out to the invocation in user code
+    pub fn backtrace(&self, id: ExpansionId) -> Vec<&ExpansionInfo> {
+        let mut chain = Vec::new();
+        let mut current = Some(id);
+        while let Some(info) = current.and_then(|id| self.get(id)) {
+            chain.push(info);
+            current = info.parent;
+        }
+        chain
+    }
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    let key = |position: Position| (position.line, position.column);
+    key(outer.start) <= key(inner.start) && key(inner.end) <= key(outer.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: usize, end: usize) -> Span {
+        Span::new(Position::new(1, start), Position::new(1, end))
+    }
+
+    #[test]
+    fn test_expansion_of_and_backtrace() {
+        let mut map = SpanMap::new();
+        let outer = map.push(ExpansionInfo {
+            macro_name: "assert_ne".to_string(),
+            call_site: span(5, 25),
+            parent: None,
+        });
+        let inner = map.push(ExpansionInfo {
+            macro_name: "stringify".to_string(),
+            call_site: span(5, 25),
+            parent: Some(outer),
+        });
+
+        assert_eq!(map.expansion_of(span(10, 11)), Some(inner));
+        assert_eq!(map.expansion_of(span(1, 4)), None);
+
+        let names: Vec<_> = map.backtrace(inner).iter().map(|info| info.macro_name.as_str()).collect();
+        assert_eq!(names, ["stringify", "assert_ne"]);
+    }
+}
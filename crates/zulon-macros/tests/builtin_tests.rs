@@ -0,0 +1,176 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for the compile-time builtins: includes, environment variables,
+//! `concat!` and source locations
+
+use std::path::{Path, PathBuf};
+use zulon_macros::{tokens_to_string, Expansion, MacroError, MacroExpanderEngine};
+use zulon_parser::{Lexer, SourceMap, Token, TokenKind};
+
+fn expand(source: &str) -> Expansion {
+    let (tokens, _) = Lexer::new(source).lex_all();
+    MacroExpanderEngine::with_builtins().expand_tokens(tokens)
+}
+
+fn expand_ok(source: &str) -> String {
+    let expansion = expand(source);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    tokens_to_string(&expansion.tokens)
+}
+
+/// A fresh directory holding `files`, and the path of a source file in it
+fn project(name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("zulon-builtins-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    dir.join("main.zl")
+}
+
+fn expand_file(source: &str, path: &Path) -> Expansion {
+    let (tokens, _) = Lexer::new(source).lex_all();
+    MacroExpanderEngine::with_builtins().expand_file(tokens, path)
+}
+
+// ============================================================================
+// Includes
+// ============================================================================
+
+#[test]
+fn test_include_str_is_relative_to_the_source_file() {
+    let path = project("str", &[("schema.sql", b"CREATE TABLE users;\n")]);
+    let expansion = expand_file(r#"let s = include_str!("schema.sql");"#, &path);
+
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    assert_eq!(expansion.tokens[3].kind, TokenKind::StringLiteral("CREATE TABLE users;\n".into()));
+    assert_eq!(expansion.dependencies, vec![path.with_file_name("schema.sql")]);
+}
+
+#[test]
+fn test_include_bytes() {
+    let path = project("bytes", &[("logo.bin", &[0, 159, 146, 150])]);
+    let expansion = expand_file(r#"include_bytes!("logo.bin")"#, &path);
+
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    assert_eq!(expansion.tokens[0].kind, TokenKind::ByteStringLiteral(vec![0, 159, 146, 150].into()));
+}
+
+#[test]
+fn test_include_errors() {
+    let path = project("errors", &[("logo.bin", &[0, 159, 146, 150])]);
+
+    let expansion = expand_file(r#"include_str!("missing.txt")"#, &path);
+    assert!(matches!(&expansion.errors[..], [MacroError::Include { path, .. }] if path.ends_with("missing.txt")));
+    assert!(expansion.dependencies.is_empty());
+
+    let expansion = expand_file(r#"include_str!("logo.bin")"#, &path);
+    let [error] = &expansion.errors[..] else {
+        panic!("expected one error, found {:?}", expansion.errors);
+    };
+    assert!(error.to_string().contains("not valid UTF-8"), "{}", error);
+}
+
+// ============================================================================
+// Environment and concat!
+// ============================================================================
+
+#[test]
+fn test_env() {
+    assert_eq!(expand_ok(r#"env!("CARGO_PKG_NAME")"#), r#""zulon-macros""#);
+    assert_eq!(expand_ok(r#"option_env!("CARGO_PKG_NAME")"#), r#"Some("zulon-macros")"#);
+    assert_eq!(expand_ok(r#"option_env!("ZULON_TEST_UNSET_VARIABLE")"#), "None");
+
+    let expansion = expand(r#"env!("ZULON_TEST_UNSET_VARIABLE", "set it first")"#);
+    assert_eq!(expansion.errors[0].to_string(), "set it first");
+    let expansion = expand(r#"env!("ZULON_TEST_UNSET_VARIABLE")"#);
+    assert!(expansion.errors[0].to_string().contains("not defined at compile time"));
+}
+
+#[test]
+fn test_concat() {
+    assert_eq!(
+        expand_ok(r#"concat!("v", 1, '.', 0x2, "-", -3, 2.5f64, true)"#),
+        r#""v1.2--32.5true""#
+    );
+    assert_eq!(expand_ok("concat!()"), r#""""#);
+
+    let expansion = expand("concat!(x)");
+    assert_eq!(expansion.errors[0].to_string(), "`concat!` takes only literals");
+}
+
+#[test]
+fn test_builtins_expand_their_arguments_first() {
+    assert_eq!(expand_ok(r#"concat!(env!("CARGO_PKG_NAME"), "/", line!())"#), r#""zulon-macros/1""#);
+
+    let path = project("eager", &[("schema.sql", b"schema")]);
+    let expansion = expand_file(r#"include_str!(concat!("sch", "ema.sql"))"#, &path);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    assert_eq!(expansion.tokens[0].kind, TokenKind::StringLiteral("schema".into()));
+}
+
+// ============================================================================
+// Source Locations
+// ============================================================================
+
+#[test]
+fn test_line_and_column() {
+    assert_eq!(expand_ok("fn f() {\n    let l = line!();\n  column!()\n}"), "fn f() { let l = 2; 3 }");
+}
+
+#[test]
+fn test_locations_inside_macros_are_the_outermost_call_site() {
+    let source = "macro here { () => { line!() } }\nfn f() {\n\n    here!()\n}";
+    assert!(expand_ok(source).ends_with("fn f() { 4 }"));
+}
+
+#[test]
+fn test_file_and_module_path() {
+    let path = project("location", &[]);
+    let source = "mod db { mod schema { fn f() { module_path!() } } } fn g() { file!() }";
+    let expansion = expand_file(source, &path);
+    let expanded = tokens_to_string(&expansion.tokens);
+
+    assert!(expanded.contains(r#"fn f() { "main::db::schema" }"#), "{}", expanded);
+    assert!(expanded.contains(&format!("{:?}", path.display().to_string())), "{}", expanded);
+    assert_eq!(expand_ok("module_path!()"), r#""main""#);
+}
+
+#[test]
+fn test_locations_in_a_crate_of_several_files() {
+    let root = project("crate", &[("data.txt", b"db data")]);
+    let db = root.with_file_name("db").join("mod.zl");
+    std::fs::create_dir_all(db.parent().unwrap()).unwrap();
+    std::fs::write(db.with_file_name("data.txt"), "nested data").unwrap();
+
+    // What the compiler makes of `mod db;` in main.zl and db/mod.zl
+    let mut map = SourceMap::new();
+    let root_source = "mod db;\nfn g() { file!() }";
+    let (mut tokens, _) = Lexer::new(root_source).lex_all();
+    map.add_file(root.clone(), root_source.to_string());
+    let db_source = "\nfn f() { (line!(), file!(), module_path!(), include_str!(\"data.txt\")) }";
+    let (mut db_tokens, _) = Lexer::new(db_source).lex_all();
+    map.add_file(db.clone(), db_source.to_string()).shift(&mut db_tokens);
+    let semicolon = tokens.remove(2);
+    tokens.insert(2, Token { kind: TokenKind::LeftBrace, span: semicolon.span });
+    let close = 3 + db_tokens.len();
+    tokens.splice(3..3, db_tokens);
+    tokens.insert(close, Token { kind: TokenKind::RightBrace, span: semicolon.span });
+
+    let expansion = MacroExpanderEngine::with_builtins().expand_crate(tokens, &map);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    let expanded = tokens_to_string(&expansion.tokens);
+    let inner = format!(
+        r#"(2, {:?}, "main::db", "nested data")"#,
+        db.display().to_string()
+    );
+    assert!(expanded.contains(&inner), "{}", expanded);
+    assert!(expanded.contains(&format!("fn g() {{ {:?} }}", root.display().to_string())), "{}", expanded);
+}
+
+#[test]
+fn test_location_macros_take_no_arguments() {
+    let expansion = expand("line!(1)");
+    assert_eq!(expansion.errors[0].to_string(), "`line!` takes no arguments");
+}
@@ -0,0 +1,410 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `#[derive(...)]` on structs and enums
+
+use zulon_macros::{expand_derives, DeriveError};
+use zulon_parser::ast::visit::{self, Visitor};
+use zulon_parser::ast::*;
+use zulon_parser::{FormatSpec, Parser, Span};
+
+fn derive(source: &str) -> (Ast, Vec<DeriveError>) {
+    let mut ast = Parser::from_source(source).parse().unwrap();
+    let errors = expand_derives(&mut ast);
+    (ast, errors)
+}
+
+/// The impls derived for the items of `source`, in order
+fn impls(source: &str) -> Vec<Impl> {
+    let (ast, errors) = derive(source);
+    assert!(errors.is_empty(), "{:?}", errors);
+    ast.items
+        .into_iter()
+        .filter_map(|item| match item.kind {
+            ItemKind::Impl(impl_def) => Some(impl_def),
+            _ => None,
+        })
+        .collect()
+}
+
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Simple(name) => name.name.clone(),
+        Type::Ref(inner, false) => format!("&{}", type_name(inner)),
+        Type::PathGeneric(path, Some(args)) => {
+            let args: Vec<String> = args.iter().map(type_name).collect();
+            format!("{}<{}>", path[0].name, args.join(", "))
+        }
+        other => panic!("unexpected type {:?}", other),
+    }
+}
+
+fn trait_name(impl_def: &Impl) -> String {
+    type_name(impl_def.trait_name.as_ref().unwrap())
+}
+
+fn method<'a>(impl_def: &'a Impl, name: &str) -> &'a Function {
+    impl_def
+        .items
+        .iter()
+        .find(|method| method.name.name == name)
+        .unwrap_or_else(|| panic!("no method `{}`", name))
+}
+
+fn body(function: &Function) -> &ExpressionKind {
+    &function.body.trailing_expr.as_ref().unwrap().kind
+}
+
+fn path(expr: &Expression) -> String {
+    match &expr.kind {
+        ExpressionKind::Path(path) => path.iter().map(|segment| segment.name.as_str()).collect::<Vec<_>>().join("::"),
+        other => panic!("expected a path, found {:?}", other),
+    }
+}
+
+// ============================================================================
+// Impl Items
+// ============================================================================
+
+#[test]
+fn test_impls_follow_the_item() {
+    let (ast, errors) = derive("#[derive(Clone, Eq)] #[derive(Copy)] struct A { x: i32 } fn f() {}");
+    assert!(errors.is_empty());
+    assert_eq!(ast.items.len(), 5);
+    assert!(matches!(ast.items[0].kind, ItemKind::Struct(_)));
+    assert!(ast.items[0].attributes.is_empty(), "the derive attribute is consumed");
+    assert!(matches!(&ast.items[1].kind, ItemKind::Impl(i) if trait_name(i) == "Clone"));
+    assert!(matches!(&ast.items[2].kind, ItemKind::Impl(i) if trait_name(i) == "Eq"));
+    assert!(matches!(&ast.items[3].kind, ItemKind::Impl(i) if trait_name(i) == "Copy" && i.items.is_empty()));
+    assert!(matches!(ast.items[4].kind, ItemKind::Function(_)));
+}
+
+#[test]
+fn test_method_signatures() {
+    let impls = impls("#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)] struct P { x: i32 }");
+    let signatures: Vec<(String, Vec<String>)> = impls
+        .iter()
+        .map(|impl_def| {
+            let methods = impl_def
+                .items
+                .iter()
+                .map(|method| {
+                    let params: Vec<String> = method
+                        .params
+                        .iter()
+                        .map(|param| format!("{}: {}", param.name.name, type_name(param.type_annotation.as_ref().unwrap())))
+                        .collect();
+                    let ret = type_name(method.return_type.as_ref().unwrap());
+                    format!("fn {}({}) -> {}", method.name.name, params.join(", "), ret)
+                })
+                .collect();
+            (trait_name(impl_def), methods)
+        })
+        .collect();
+
+    let expected = [
+        ("Clone", vec!["fn clone(self: &Self) -> Self"]),
+        ("PartialEq", vec!["fn eq(self: &Self, other: &Self) -> bool"]),
+        ("Eq", vec![]),
+        (
+            "PartialOrd",
+            vec![
+                "fn partial_cmp(self: &Self, other: &Self) -> Option<Ordering>",
+                "fn lt(self: &Self, other: &Self) -> bool",
+            ],
+        ),
+        ("Ord", vec!["fn cmp(self: &Self, other: &Self) -> Ordering"]),
+        ("Hash", vec!["fn hash(self: &Self) -> u64"]),
+        ("Debug", vec!["fn fmt(self: &Self) -> str"]),
+    ];
+    assert_eq!(signatures.len(), expected.len());
+    for ((name, methods), (expected_name, expected_methods)) in signatures.iter().zip(expected) {
+        assert_eq!(name, expected_name);
+        assert_eq!(methods, &expected_methods);
+    }
+}
+
+#[test]
+fn test_generic_parameters_get_bounds() {
+    let impls = impls("#[derive(Clone)] struct Pair<K: Hash + Eq, V> { key: K, value: V }");
+    let impl_def = &impls[0];
+    assert_eq!(type_name(&impl_def.self_type), "Pair<K, V>");
+
+    let generics = impl_def.generics.as_ref().unwrap();
+    assert_eq!(generics.params.len(), 2);
+    let clauses: Vec<String> = generics
+        .where_clause
+        .iter()
+        .map(|clause| {
+            let bounds: Vec<String> = clause
+                .bounds
+                .iter()
+                .map(|bound| match bound {
+                    TraitBound::Trait(ty) => type_name(ty),
+                    TraitBound::Lifetime(name) => name.name.clone(),
+                })
+                .collect();
+            format!("{}: {}", clause.type_param.name, bounds.join(" + "))
+        })
+        .collect();
+    assert_eq!(clauses, ["K: Hash + Eq", "K: Clone", "V: Clone"]);
+}
+
+#[test]
+fn test_non_generic_items_have_no_generics() {
+    let impls = impls("#[derive(Clone)] enum E { A }");
+    assert!(impls[0].generics.is_none());
+    assert_eq!(type_name(&impls[0].self_type), "E");
+}
+
+// ============================================================================
+// Method Bodies
+// ============================================================================
+
+#[test]
+fn test_clone_struct_field_by_field() {
+    let impls = impls("#[derive(Clone)] struct Point { x: f64, y: f64 }");
+    let ExpressionKind::Struct(literal) = body(method(&impls[0], "clone")) else {
+        panic!("expected a struct literal");
+    };
+    assert_eq!(literal.path[0].name, "Point");
+    let fields: Vec<&str> = literal.fields.iter().map(|field| field.name.name.as_str()).collect();
+    assert_eq!(fields, ["x", "y"]);
+    for field in &literal.fields {
+        let ExpressionKind::MethodCall(receiver, name, args) = &field.value.kind else {
+            panic!("expected a clone call");
+        };
+        assert_eq!(name.name, "clone");
+        assert!(args.is_empty());
+        assert!(matches!(&receiver.kind, ExpressionKind::FieldAccess(_, f) if f == &field.name));
+    }
+}
+
+#[test]
+fn test_clone_enum_rebuilds_each_variant() {
+    let impls = impls("#[derive(Clone)] enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }");
+    let ExpressionKind::Match(scrutinee, arms) = body(method(&impls[0], "clone")) else {
+        panic!("expected a match");
+    };
+    assert_eq!(path(scrutinee), "self");
+    assert_eq!(arms.len(), 3);
+
+    let Pattern::TupleVariant(variant, bindings) = &arms[0].patterns[0] else {
+        panic!("expected a tuple variant pattern");
+    };
+    assert_eq!(variant[1].name, "Circle");
+    assert!(matches!(&bindings[..], [Pattern::Identifier(name)] if name.name == "__self_0"));
+    assert!(matches!(&arms[0].body.kind, ExpressionKind::Call(callee, args) if path(callee) == "Shape::Circle" && args.len() == 1));
+
+    assert!(matches!(&arms[1].patterns[0], Pattern::Struct(path, fields) if path[1].name == "Rect" && fields.len() == 2));
+    assert!(matches!(&arms[1].body.kind, ExpressionKind::Struct(literal) if literal.fields.len() == 2));
+
+    assert!(matches!(&arms[2].patterns[0], Pattern::Struct(path, fields) if path[1].name == "Empty" && fields.is_empty()));
+    assert_eq!(path(&arms[2].body), "Shape::Empty");
+}
+
+#[test]
+fn test_partial_eq_struct() {
+    let impls = impls("#[derive(PartialEq)] struct P { a: i32, b: i32 } #[derive(PartialEq)] struct Unit {}");
+    let ExpressionKind::Binary(BinaryOp::And, left, right) = body(method(&impls[0], "eq")) else {
+        panic!("expected a conjunction");
+    };
+    for equal in [left, right] {
+        assert!(matches!(&equal.kind, ExpressionKind::MethodCall(_, name, args) if name.name == "eq" && args.len() == 1));
+    }
+
+    assert!(matches!(body(method(&impls[1], "eq")), ExpressionKind::Literal(Literal::Bool(true))));
+}
+
+#[test]
+fn test_partial_eq_enum_requires_the_same_variant() {
+    let impls = impls("#[derive(PartialEq)] enum E { A(i32), B } #[derive(PartialEq)] enum Single { Only(i32) }");
+    let ExpressionKind::Match(scrutinee, arms) = body(method(&impls[0], "eq")) else {
+        panic!("expected a match");
+    };
+    assert_eq!(path(scrutinee), "self");
+    assert_eq!(arms.len(), 2);
+    assert!(matches!(&arms[0].patterns[0], Pattern::TupleVariant(_, b) if matches!(&b[0], Pattern::Identifier(n) if n.name == "__self_0")));
+
+    // Each variant of `self` matches `other` against the same variant
+    let ExpressionKind::Match(scrutinee, inner) = &arms[0].body.kind else {
+        panic!("expected a nested match");
+    };
+    assert_eq!(path(scrutinee), "other");
+    assert_eq!(inner.len(), 2);
+    assert!(matches!(&inner[0].patterns[0], Pattern::TupleVariant(_, b) if matches!(&b[0], Pattern::Identifier(n) if n.name == "__other_0")));
+    assert!(matches!(inner[1].patterns[0], Pattern::Wildcard));
+    assert!(matches!(inner[1].body.kind, ExpressionKind::Literal(Literal::Bool(false))));
+
+    // No fallback arm when there is nothing to fall back to
+    let ExpressionKind::Match(_, arms) = body(method(&impls[1], "eq")) else {
+        panic!("expected a match");
+    };
+    let ExpressionKind::Match(_, inner) = &arms[0].body.kind else {
+        panic!("expected a nested match");
+    };
+    assert_eq!(inner.len(), 1);
+}
+
+#[test]
+fn test_ord_is_lexicographic() {
+    let impls = impls("#[derive(Ord)] struct Version { major: u32, minor: u32 }");
+    let ExpressionKind::Match(comparison, arms) = body(method(&impls[0], "cmp")) else {
+        panic!("expected a match");
+    };
+    assert!(matches!(&comparison.kind, ExpressionKind::MethodCall(_, name, args) if name.name == "cmp" && args.len() == 1));
+    assert!(matches!(&arms[0].patterns[0], Pattern::Struct(path, _) if path[1].name == "Equal"));
+    assert!(matches!(&arms[1].patterns[0], Pattern::Identifier(name) if name.name == "ordering"));
+
+    // Equal majors fall through to the minors, then to `Ordering::Equal`
+    let ExpressionKind::Match(_, inner) = &arms[0].body.kind else {
+        panic!("expected a nested match");
+    };
+    assert_eq!(path(&inner[0].body), "Ordering::Equal");
+}
+
+#[test]
+fn test_partial_ord_wraps_in_some() {
+    let impls = impls("#[derive(PartialOrd)] struct S {} ");
+    let ExpressionKind::Call(callee, args) = body(method(&impls[0], "partial_cmp")) else {
+        panic!("expected `Some(Ordering::Equal)`");
+    };
+    assert_eq!(path(callee), "Some");
+    assert_eq!(path(&args[0]), "Ordering::Equal");
+
+    let ExpressionKind::Match(_, arms) = body(method(&impls[0], "lt")) else {
+        panic!("expected a match");
+    };
+    assert!(matches!(&arms[0].patterns[0], Pattern::TupleVariant(some, inner)
+        if some[0].name == "Some" && matches!(&inner[0], Pattern::Struct(path, _) if path[1].name == "Less")));
+}
+
+#[test]
+fn test_hash_mixes_variant_index() {
+    let impls = impls("#[derive(Hash)] struct S { a: u64 } #[derive(Hash)] enum E { A, B(u64) }");
+
+    let block = &method(&impls[0], "hash").body;
+    assert_eq!(block.statements.len(), 2, "initial value and one field");
+    assert!(matches!(&block.statements[0].kind, StatementKind::Local(local) if local.is_mutable));
+
+    let ExpressionKind::Match(_, arms) = body(method(&impls[1], "hash")) else {
+        panic!("expected a match");
+    };
+    let ExpressionKind::Block(second) = &arms[1].body.kind else {
+        panic!("expected a block");
+    };
+    // Initial value, variant index, one field
+    assert_eq!(second.statements.len(), 3);
+    let StatementKind::Expr(Expression { kind: ExpressionKind::Assign(_, value), .. }) = &second.statements[1].kind else {
+        panic!("expected an assignment");
+    };
+    let ExpressionKind::Binary(BinaryOp::Mul, mixed, _) = &value.kind else {
+        panic!("expected a multiplication");
+    };
+    assert!(matches!(&mixed.kind, ExpressionKind::Binary(BinaryOp::BitXor, _, index)
+        if matches!(index.kind, ExpressionKind::Literal(Literal::Int(1, _)))));
+}
+
+#[test]
+fn test_debug_strings() {
+    let impls = impls("#[derive(Debug)] struct Point { x: i32, y: i32 } #[derive(Debug)] enum E { Unit, Pair(i32, i32) }");
+
+    let ExpressionKind::TemplateString(template) = body(method(&impls[0], "fmt")) else {
+        panic!("expected a template string");
+    };
+    let statics: Vec<&str> = template
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            TemplateStringPart::Static(text) => Some(text.as_str()),
+            TemplateStringPart::Expr(_, spec) => {
+                assert_eq!(*spec, FormatSpec::debug());
+                None
+            }
+        })
+        .collect();
+    assert_eq!(statics, ["Point", " { x: ", ", y: ", " }"]);
+
+    let ExpressionKind::Match(_, arms) = body(method(&impls[1], "fmt")) else {
+        panic!("expected a match");
+    };
+    assert!(matches!(&arms[0].body.kind, ExpressionKind::TemplateString(template)
+        if matches!(&template.parts[..], [TemplateStringPart::Static(name)] if name == "Unit")));
+    let ExpressionKind::TemplateString(template) = &arms[1].body.kind else {
+        panic!("expected a template string");
+    };
+    assert_eq!(template.parts.len(), 6);
+}
+
+#[test]
+fn test_expressions_have_their_own_spans() {
+    struct Spans(Vec<Span>);
+    impl Visitor<'_> for Spans {
+        fn visit_expression(&mut self, expr: &Expression) {
+            self.0.push(expr.span);
+            visit::walk_expression(self, expr);
+        }
+    }
+
+    let impls = impls("#[derive(PartialOrd)] enum E { A(i32), B { x: i32, y: i32 } }");
+    let mut spans = Spans(Vec::new());
+    spans.visit_impl(&impls[0]);
+    let count = spans.0.len();
+    spans.0.sort_by_key(|span| (span.end.line, span.end.column));
+    spans.0.dedup();
+    assert_eq!(spans.0.len(), count);
+    // All start at the trait name, or at the field they are about: its
+    // name, or its variant's if it has none
+    let starts = [impls[0].impl_span.start.column, 32, 44, 52];
+    assert!(spans.0.iter().all(|span| starts.contains(&span.start.column)));
+    assert!(starts.iter().all(|start| spans.0.iter().any(|span| span.start.column == *start)));
+}
+
+// ============================================================================
+// Placement and Errors
+// ============================================================================
+
+#[test]
+fn test_items_in_modules_and_blocks() {
+    let (ast, errors) = derive(
+        r#"
+        mod shapes { #[derive(Clone)] struct Circle { r: f64 } }
+        fn main() { #[derive(Eq)] struct Local {} }
+        "#,
+    );
+    assert!(errors.is_empty());
+
+    let ItemKind::Module(module) = &ast.items[0].kind else {
+        panic!("expected a module");
+    };
+    let items = module.items.as_ref().unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(matches!(items[1].kind, ItemKind::Impl(_)));
+
+    let ItemKind::Function(main) = &ast.items[1].kind else {
+        panic!("expected a function");
+    };
+    assert_eq!(main.body.statements.len(), 2);
+    assert!(matches!(&main.body.statements[1].kind, StatementKind::Item(item) if matches!(item.kind, ItemKind::Impl(_))));
+}
+
+#[test]
+fn test_unknown_and_malformed_derives() {
+    let (ast, errors) = derive(r#"#[derive(Clone, Serialize, "Eq")] struct S {}"#);
+    assert!(matches!(
+        errors.as_slice(),
+        [DeriveError::Unknown { name, .. }, DeriveError::Malformed { .. }] if name == "Serialize"
+    ));
+    assert_eq!(errors[0].to_string(), "cannot find derive macro `Serialize`");
+
+    // Known traits are still derived
+    assert_eq!(ast.items.len(), 2);
+}
+
+#[test]
+fn test_derive_on_a_function() {
+    let (ast, errors) = derive("#[derive(Clone)] fn f() {}");
+    assert!(matches!(errors.as_slice(), [DeriveError::InvalidTarget { .. }]));
+    assert_eq!(errors[0].span().start.line, 1);
+    assert_eq!(ast.items.len(), 1);
+}
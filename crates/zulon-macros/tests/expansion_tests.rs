@@ -0,0 +1,198 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for token-based macro expansion
+
+use zulon_macros::{
+    tokens_to_string, Expansion, Identifier, Macro, MacroError, MacroExpanderEngine, MacroRule,
+    RECURSION_LIMIT,
+};
+use zulon_parser::ast::{ExpressionKind, ItemKind, StatementKind};
+use zulon_parser::{Lexer, Parser, Position, Span};
+
+fn lex(source: &str) -> Vec<zulon_parser::Token> {
+    Lexer::new(source).lex_all().0
+}
+
+fn expand_with(engine: &MacroExpanderEngine, source: &str) -> Expansion {
+    engine.expand_tokens(lex(source))
+}
+
+fn register(engine: &mut MacroExpanderEngine, name: &str, rules: &[(&str, &str)]) {
+    engine.register_macro(Macro {
+        name: Identifier::new(name),
+        rules: rules
+            .iter()
+            .map(|(pattern, template)| MacroRule::new(pattern, template))
+            .collect(),
+    });
+}
+
+// ============================================================================
+// Token Streams
+// ============================================================================
+
+#[test]
+fn test_macros_inside_strings_are_not_expanded() {
+    let engine = MacroExpanderEngine::with_builtins();
+    let expansion = expand_with(&engine, r#"fn main() { let s = "assert!(false)"; panic!("x") }"#);
+
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    let text = tokens_to_string(&expansion.tokens);
+    assert!(text.contains(r#""assert!(false)""#), "{}", text);
+    assert!(text.contains("::__zulon_builtin_panic(\"x\")"), "{}", text);
+    assert_eq!(expansion.span_map.len(), 1);
+}
+
+#[test]
+fn test_unknown_macros_are_left_for_the_parser() {
+    let engine = MacroExpanderEngine::with_builtins();
+    let source = "fn main() { vec![1, 2] }";
+    let expansion = expand_with(&engine, source);
+
+    assert!(expansion.errors.is_empty());
+    assert!(expansion.span_map.is_empty());
+    assert_eq!(expansion.tokens, lex(source));
+}
+
+#[test]
+fn test_nested_invocations_are_expanded() {
+    let engine = MacroExpanderEngine::with_builtins();
+    let expansion = expand_with(&engine, "fn main() { assert_ne!(a + 1, b); }");
+
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    let text = tokens_to_string(&expansion.tokens);
+    assert!(text.contains(r#""a + 1""#), "{}", text);
+    assert!(!text.contains("stringify"), "{}", text);
+
+    // assert_ne! and the two stringify! calls in its output
+    assert_eq!(expansion.span_map.len(), 3);
+    let (inner, info) = expansion.span_map.iter().last().unwrap();
+    assert_eq!(info.macro_name, "stringify");
+    let chain: Vec<_> = expansion
+        .span_map
+        .backtrace(inner)
+        .iter()
+        .map(|info| info.macro_name.as_str())
+        .collect();
+    assert_eq!(chain, ["stringify", "assert_ne"]);
+}
+
+#[test]
+fn test_unclosed_invocation_is_an_error() {
+    let engine = MacroExpanderEngine::with_builtins();
+    let expansion = expand_with(&engine, "fn main() { assert!(x > 0; }");
+
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::UnclosedDelimiter { name, .. }] if name == "assert"
+    ));
+}
+
+#[test]
+fn test_recursion_limit() {
+    let mut engine = MacroExpanderEngine::new();
+    register(&mut engine, "forever", &[("$x", "forever!($x)")]);
+    let expansion = expand_with(&engine, "fn main() { forever!(1) }");
+
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::RecursionLimit { limit: RECURSION_LIMIT, .. }]
+    ));
+}
+
+// ============================================================================
+// Spans
+// ============================================================================
+
+#[test]
+fn test_spans_point_at_original_source() {
+    let engine = MacroExpanderEngine::with_builtins();
+    let source = "fn main() {\n    assert_eq!(value, 42);\n}";
+    let expansion = expand_with(&engine, source);
+    let call_site = Span::new(Position::new(2, 5), Position::new(2, 26));
+
+    // Template tokens take the invocation's span
+    let if_token = expansion
+        .tokens
+        .iter()
+        .find(|token| token.kind == zulon_parser::TokenKind::If)
+        .unwrap();
+    assert_eq!(if_token.span, call_site);
+
+    // Argument tokens keep their own
+    let value = expansion
+        .tokens
+        .iter()
+        .find(|token| token.kind == zulon_parser::TokenKind::Ident("value".into()))
+        .unwrap();
+    assert_eq!(value.span.start, Position::new(2, 16));
+
+    let id = expansion.span_map.expansion_of(value.span).unwrap();
+    assert_eq!(expansion.span_map.get(id).unwrap().call_site, call_site);
+}
+
+#[test]
+fn test_parse_errors_in_expansions_point_at_the_call_site() {
+    let mut engine = MacroExpanderEngine::new();
+    register(&mut engine, "broken", &[("", "let = 1;")]);
+    let source = "fn main() {\n    broken!();\n}";
+    let expansion = expand_with(&engine, source);
+
+    let error = Parser::new(expansion.tokens).parse().unwrap_err();
+    let span = match error {
+        zulon_parser::ParseError::UnexpectedToken { span, .. }
+        | zulon_parser::ParseError::InvalidSyntax { span, .. } => span,
+        other => panic!("unexpected error: {:?}", other),
+    };
+    assert_eq!(span.start, Position::new(2, 5));
+    assert!(expansion.span_map.expansion_of(span).is_some());
+}
+
+// ============================================================================
+// Hygiene
+// ============================================================================
+
+#[test]
+fn test_macro_locals_do_not_capture_arguments() {
+    let mut engine = MacroExpanderEngine::new();
+    register(&mut engine, "double", &[("$e", "{ let tmp = $e; tmp + tmp }")]);
+    let expansion = expand_with(&engine, "fn f(tmp: i32) -> i32 { double!(tmp * 2) }");
+
+    let ast = Parser::new(expansion.tokens).parse().unwrap();
+    let ItemKind::Function(function) = &ast.items[0].kind else {
+        panic!("expected a function");
+    };
+    let ExpressionKind::Block(block) = &function.body.trailing_expr.as_ref().unwrap().kind else {
+        panic!("expected a block");
+    };
+
+    // The macro's `tmp` is renamed, the argument's `tmp` still refers to the parameter
+    let StatementKind::Local(local) = &block.statements[0].kind else {
+        panic!("expected a let statement");
+    };
+    assert_ne!(local.name.name, "tmp");
+    let text = format!("{:?}", local.init);
+    assert!(text.contains("\"tmp\""), "{}", text);
+}
+
+#[test]
+fn test_each_expansion_gets_fresh_names() {
+    let mut engine = MacroExpanderEngine::new();
+    register(&mut engine, "bind", &[("$e", "let x = $e;")]);
+    let expansion = expand_with(&engine, "fn f() { bind!(1) bind!(x) }");
+    let text = tokens_to_string(&expansion.tokens);
+
+    let first = text.find("let x$0 = 1;").unwrap_or_else(|| panic!("{}", text));
+    let second = text.find("let x$1 = x;").unwrap_or_else(|| panic!("{}", text));
+    assert!(first < second);
+}
+
+#[test]
+fn test_free_identifiers_are_not_renamed() {
+    let mut engine = MacroExpanderEngine::new();
+    register(&mut engine, "call", &[("$e", "helper($e)")]);
+    let expansion = expand_with(&engine, "fn f() { call!(1) }");
+
+    assert!(tokens_to_string(&expansion.tokens).contains("helper(1)"));
+}
@@ -0,0 +1,137 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `format!` and the printing macros
+
+use zulon_macros::{tokens_to_string, Expansion, MacroError, MacroExpanderEngine};
+use zulon_parser::ast::{ExpressionKind, ItemKind, StatementKind, TemplateStringPart};
+use zulon_parser::format::{Align, Count, FormatKind};
+use zulon_parser::{Lexer, Parser};
+
+fn expand(source: &str) -> Expansion {
+    let (tokens, _) = Lexer::new(source).lex_all();
+    MacroExpanderEngine::with_builtins().expand_tokens(tokens)
+}
+
+fn expand_ok(source: &str) -> String {
+    let expansion = expand(source);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    tokens_to_string(&expansion.tokens)
+}
+
+/// The message of the single format error in `source`
+fn format_error(source: &str) -> String {
+    match expand(source).errors.as_slice() {
+        [MacroError::Format(error)] => error.message.clone(),
+        errors => panic!("expected one format error, found {:?}", errors),
+    }
+}
+
+// ============================================================================
+// Expansion
+// ============================================================================
+
+#[test]
+fn test_format_without_placeholders_is_a_string() {
+    assert_eq!(expand_ok(r#"format!("a {{b}} c")"#), r#""a {b} c""#);
+}
+
+#[test]
+fn test_identifiers_are_interpolated_directly() {
+    assert_eq!(expand_ok(r#"format!("{} and {}", a, b)"#), "`${a} and ${b}`");
+    assert_eq!(expand_ok(r#"format!("{1} {0} {1}", a, b)"#), "`${b} ${a} ${b}`");
+}
+
+#[test]
+fn test_expressions_are_evaluated_once() {
+    let expanded = expand_ok(r#"format!("{0}{0:?}", f(x, 1))"#);
+    assert_eq!(expanded, "{ let __fmt_0 = (f(x, 1)); `${__fmt_0}${__fmt_0:?}` }");
+}
+
+#[test]
+fn test_named_arguments_and_implicit_capture() {
+    let expanded = expand_ok(r#"format!("{total:>8.2} {name}", total = price * 2)"#);
+    assert_eq!(expanded, "{ let __fmt_0 = (price * 2); `${__fmt_0: >8.2} ${name}` }");
+}
+
+#[test]
+fn test_counts_from_arguments() {
+    // `.*` takes the precision before the value
+    assert_eq!(expand_ok(r#"format!("{:.*}", 3, pi)"#), "`${pi:.3}`");
+    assert_eq!(expand_ok(r#"format!("{:w$}", x, w = width)"#), "`${x:width$}`");
+    assert_eq!(expand_ok(r#"format!("{:1$}", x, n + 1)"#), "{ let __fmt_1 = (n + 1); `${x:__fmt_1$}` }");
+}
+
+#[test]
+fn test_dollar_signs_stay_literal() {
+    assert_eq!(expand_ok(r#"format!("${}", price)"#), r#"`$${price}`"#);
+    assert_eq!(expand_ok(r#"format!("${{{}}}", x)"#), r#"`${"$"}{${x}}`"#);
+}
+
+#[test]
+fn test_print_macros() {
+    assert_eq!(expand_ok(r#"print!("{}", x)"#), r#"printf("%s", `${x}`)"#);
+    assert_eq!(expand_ok(r#"println!("hi")"#), r#"printf("%s\n", "hi")"#);
+    assert_eq!(expand_ok("println!()"), r#"printf("\n")"#);
+    assert_eq!(expand_ok(r#"panic!("bad {:x}", n)"#), "::__zulon_builtin_panic(`bad ${n:x}`)");
+}
+
+#[test]
+fn test_expansion_parses_to_formatted_interpolations() {
+    let expanded = expand("fn main() { let s = format!(\"{:>8} {:#x}\", name, 255); }");
+    assert!(expanded.errors.is_empty(), "{:?}", expanded.errors);
+
+    let ast = Parser::new(expanded.tokens).parse().unwrap();
+    let ItemKind::Function(func) = &ast.items[0].kind else {
+        panic!("expected a function");
+    };
+    let StatementKind::Local(local) = &func.body.statements[0].kind else {
+        panic!("expected a let statement");
+    };
+    let ExpressionKind::Block(block) = &local.init.as_ref().unwrap().kind else {
+        panic!("expected a block binding the literal");
+    };
+    let ExpressionKind::TemplateString(template) = &block.trailing_expr.as_ref().unwrap().kind else {
+        panic!("expected a template string");
+    };
+    let [TemplateStringPart::Expr(_, width), TemplateStringPart::Static(_), TemplateStringPart::Expr(_, hex)] =
+        template.parts.as_slice()
+    else {
+        panic!("unexpected parts {:?}", template.parts);
+    };
+    assert_eq!(width.align, Some(Align::Right));
+    assert_eq!(width.width, Some(Count::Is(8)));
+    assert!(hex.alternate);
+    assert_eq!(hex.kind, FormatKind::LowerHex);
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[test]
+fn test_bad_specifiers() {
+    assert_eq!(format_error(r#"format!("{:y}", x)"#), "unknown format trait `y`");
+    assert!(format_error(r#"format!("{", x)"#).contains("expected `}`"));
+    assert!(format_error(r#"format!("}")"#).contains("unmatched `}`"));
+}
+
+#[test]
+fn test_argument_errors() {
+    assert_eq!(
+        format_error(r#"format!("{} {}", a)"#),
+        "invalid reference to positional argument 1 (there is 1 argument)"
+    );
+    assert_eq!(format_error(r#"format!("{}", a, b)"#), "argument never used");
+    assert_eq!(format_error(r#"format!("{}", a, n = 1)"#), "named argument never used");
+    assert_eq!(format_error(r#"format!(message)"#), "format argument must be a string literal");
+    assert!(format_error(r#"format!("{n}", n = 1, 2)"#).contains("cannot follow named"));
+}
+
+#[test]
+fn test_error_spans_point_into_the_format_string() {
+    let expansion = expand("fn f() {\n    format!(\"ab {:q}\", x);\n}");
+    let error = &expansion.errors[0];
+    assert_eq!(error.span().start.line, 2);
+    assert_eq!(error.span().start.column, 19);
+}
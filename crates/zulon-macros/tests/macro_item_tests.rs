@@ -0,0 +1,283 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for macros defined with `macro` items
+
+use zulon_macros::{tokens_to_string, Expansion, MacroError, MacroExpanderEngine};
+use zulon_parser::ast::ItemKind;
+use zulon_parser::{Lexer, Parser};
+
+fn expand(source: &str) -> Expansion {
+    let (tokens, _) = Lexer::new(source).lex_all();
+    MacroExpanderEngine::with_builtins().expand_tokens(tokens)
+}
+
+/// The expanded body of the last function in `source`
+fn expand_ok(source: &str) -> String {
+    let expansion = expand(source);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    let text = tokens_to_string(&expansion.tokens);
+    let body = text.rfind("fn ").map(|start| &text[start..]).unwrap_or(&text);
+    body.to_string()
+}
+
+// ============================================================================
+// Definitions
+// ============================================================================
+
+#[test]
+fn test_macro_item_expands_and_parses() {
+    let source = r#"
+        macro square {
+            ($x:expr) => { $x * $x };
+        }
+
+        fn main() -> i32 { square!(1 + 2) }
+    "#;
+    let expansion = expand(source);
+    assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+    assert!(tokens_to_string(&expansion.tokens).contains("{ (1 + 2) * (1 + 2) }"));
+
+    // The definition stays in the token stream as an item
+    let ast = Parser::new(expansion.tokens).parse().unwrap();
+    let ItemKind::MacroDef(def) = &ast.items[0].kind else {
+        panic!("expected a macro definition");
+    };
+    assert_eq!(def.name.name, "square");
+    assert_eq!(def.rules.len(), 1);
+}
+
+#[test]
+fn test_macros_can_be_used_before_their_definition() {
+    let body = expand_ok("fn main() -> i32 { one!() } macro one { () => { 1 } }");
+    assert!(body.starts_with("fn main() -> i32 { 1 }"), "{}", body);
+}
+
+#[test]
+fn test_rules_are_tried_in_order() {
+    let source = r#"
+        macro pick {
+            (zero) => { 0 };
+            ($x:literal) => { $x };
+            ($x:ident) => { $x + 1 };
+        }
+        fn main() { let a = pick!(zero); let b = pick!(7); let c = pick!(n); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("let a = 0;"), "{}", body);
+    assert!(body.contains("let b = 7;"), "{}", body);
+    assert!(body.contains("let c = n + 1;"), "{}", body);
+}
+
+#[test]
+fn test_user_macros_shadow_builtins() {
+    let body = expand_ok("macro assert { ($e:expr) => { check($e) } } fn main() { assert!(x); }");
+    assert!(body.contains("check(x)"), "{}", body);
+}
+
+#[test]
+fn test_invalid_fragment_specifier() {
+    let expansion = expand("macro bad { ($x:number) => { $x } } fn main() {}");
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::InvalidRule { message, .. }] if message.contains("`$x:`")
+    ));
+}
+
+#[test]
+fn test_redefinition_in_the_same_module() {
+    let source = r#"
+        macro m { () => { 1 } }
+        macro m { () => { 2 } }
+        mod inner { macro m { () => { 3 } } }
+    "#;
+    let expansion = expand(source);
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::Redefined { name, .. }] if name == "m"
+    ));
+}
+
+// ============================================================================
+// Fragment Specifiers
+// ============================================================================
+
+#[test]
+fn test_fragment_specifiers() {
+    let source = r#"
+        macro declare {
+            ($name:ident : $t:ty = $value:expr) => { let $name: $t = $value; };
+        }
+        fn main() { declare!(total: Vec<i32> = make(1, 2)); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("let total: Vec < i32 > = (make(1, 2));"), "{}", body);
+}
+
+#[test]
+fn test_expr_fragment_stops_at_a_comma() {
+    let source = r#"
+        macro second { ($a:expr, $b:expr) => { $b } }
+        fn main() -> i32 { second!(f(1, 2), 3 + 4) }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("{ (3 + 4) }"), "{}", body);
+}
+
+#[test]
+fn test_fragment_mismatch_tries_the_next_rule() {
+    let source = r#"
+        macro kind {
+            ($b:block) => { "block" };
+            ($p:pat) => { "pattern" };
+        }
+        fn main() { let a = kind!({ 1 }); let b = kind!(Some(_)); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains(r#"let a = "block";"#), "{}", body);
+    assert!(body.contains(r#"let b = "pattern";"#), "{}", body);
+
+    let expansion = expand("macro only_ident { ($i:ident) => { $i } } fn main() { only_ident!(1); }");
+    assert!(matches!(expansion.errors.as_slice(), [MacroError::NoMatchingRule { .. }]));
+}
+
+// ============================================================================
+// Repetitions
+// ============================================================================
+
+#[test]
+fn test_repetition_with_separator() {
+    let source = r#"
+        macro sum {
+            ($($x:expr),*) => { 0 $(+ $x)* };
+        }
+        fn main() { let a = sum!(1, f(2, 3), 4); let b = sum!(); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("let a = 0 + 1 + (f(2, 3)) + 4;"), "{}", body);
+    assert!(body.contains("let b = 0;"), "{}", body);
+}
+
+#[test]
+fn test_recursive_macro_with_repetitions() {
+    let source = r#"
+        macro max {
+            ($a:expr) => { $a };
+            ($a:expr, $($rest:expr),+) => { larger($a, max!($($rest),+)) };
+        }
+        fn main() -> i32 { max!(1, 2, 3) }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("larger(1, larger(2, 3))"), "{}", body);
+}
+
+#[test]
+fn test_one_or_more_and_optional() {
+    let source = r#"
+        macro call {
+            ($f:ident $(, $arg:expr)? ) => { $f($($arg)?) };
+        }
+        fn main() { call!(a); call!(b, 2); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("a();"), "{}", body);
+    assert!(body.contains("b(2);"), "{}", body);
+
+    let expansion = expand("macro nonempty { ($($x:tt)+) => { 1 } } fn main() { nonempty!(); }");
+    assert!(matches!(expansion.errors.as_slice(), [MacroError::NoMatchingRule { .. }]));
+}
+
+#[test]
+fn test_nested_repetitions() {
+    let source = r#"
+        macro table {
+            ($($row:ident: [$($cell:expr),*]);*) => { $(let $row = [$($cell * 2),*];)* };
+        }
+        fn main() { table!(a: [1, 2]; b: []; c: [3]); }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("let a = [1 * 2, 2 * 2]; let b = []; let c = [3 * 2];"), "{}", body);
+}
+
+#[test]
+fn test_repetition_counts_must_agree() {
+    let source = r#"
+        macro zip { ($($a:ident),* ; $($b:ident),*) => { $(($a, $b))* } }
+        fn main() { zip!(x, y; z); }
+    "#;
+    let expansion = expand(source);
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::RepetitionMismatch { name, .. }] if name == "b"
+    ));
+}
+
+// ============================================================================
+// Modules
+// ============================================================================
+
+#[test]
+fn test_macros_are_visible_in_child_modules() {
+    let source = r#"
+        macro one { () => { 1 } }
+        mod outer {
+            macro two { () => { 2 } }
+            mod inner { fn f() -> i32 { one!() + two!() } }
+        }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("{ 1 + 2 }"), "{}", body);
+}
+
+#[test]
+fn test_macros_are_not_visible_in_sibling_modules() {
+    let source = r#"
+        mod a { macro hidden { () => { 1 } } }
+        mod b { fn f() -> i32 { hidden!() } }
+    "#;
+    let expansion = expand(source);
+    assert!(expansion.errors.is_empty());
+    assert!(tokens_to_string(&expansion.tokens).contains("hidden!()"));
+}
+
+#[test]
+fn test_exported_macros_are_invoked_by_path() {
+    let source = r#"
+        mod utils {
+            pub macro double { ($x:expr) => { $x * 2 } }
+            mod nested { pub macro triple { ($x:expr) => { $x * 3 } } }
+        }
+        mod app {
+            fn f() -> i32 { utils::double!(1) + crate::utils::nested::triple!(2) + super::utils::double!(3) }
+        }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("{ 1 * 2 + 2 * 3 + 3 * 2 }"), "{}", body);
+}
+
+#[test]
+fn test_private_macros_cannot_be_invoked_from_outside() {
+    let source = r#"
+        mod utils { macro secret { () => { 0 } } }
+        fn f() -> i32 { utils::secret!() + utils::missing!() }
+    "#;
+    let expansion = expand(source);
+    assert!(matches!(
+        expansion.errors.as_slice(),
+        [MacroError::Private { name, .. }, MacroError::Undefined { name: missing, .. }]
+            if name == "utils::secret" && missing == "utils::missing"
+    ));
+}
+
+#[test]
+fn test_exported_macros_can_use_private_helpers() {
+    let source = r#"
+        mod utils {
+            macro helper { ($x:expr) => { $x + 1 } }
+            pub macro api { ($x:expr) => { helper!($x) } }
+        }
+        fn f() -> i32 { utils::api!(5) }
+    "#;
+    let body = expand_ok(source);
+    assert!(body.contains("{ 5 + 1 }"), "{}", body);
+}
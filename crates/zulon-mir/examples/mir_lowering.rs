@@ -128,6 +128,9 @@ fn format_instruction(inst: &zulon_mir::MirInstruction, _func: &zulon_mir::MirFu
             format!("_{} = {} _{} ({})",
                 dest, format_unary_op(*op), operand, ty.display_name())
         }
+        zulon_mir::MirInstruction::Cast { dest, src, from, to } => {
+            format!("_{} = _{} as {} ({})", dest, src, to.display_name(), from.display_name())
+        }
         zulon_mir::MirInstruction::Call { dest, func, args, return_type } => {
             let dest_str = if let Some(d) = dest {
                 format!("_{} = ", d)
@@ -140,6 +143,19 @@ fn format_instruction(inst: &zulon_mir::MirInstruction, _func: &zulon_mir::MirFu
                 args.iter().map(|a| format_place(a)).collect::<Vec<_>>().join(", "),
                 return_type.display_name())
         }
+        zulon_mir::MirInstruction::CallVirtual { dest, object, slot, args, return_type } => {
+            let dest_str = if let Some(d) = dest {
+                format!("_{} = ", d)
+            } else {
+                String::new()
+            };
+            format!("{}call virtual _{}[{}]({}) -> {}",
+                dest_str,
+                object,
+                slot,
+                args.iter().map(format_place).collect::<Vec<_>>().join(", "),
+                return_type.display_name())
+        }
         zulon_mir::MirInstruction::Load { dest, src, ty } => {
             format!("_{} = load {} {}", dest, ty.display_name(), format_place(src))
         }
@@ -157,6 +173,23 @@ fn format_instruction(inst: &zulon_mir::MirInstruction, _func: &zulon_mir::MirFu
         zulon_mir::MirInstruction::FieldAccess { .. } => {
             format!("<field access>")
         }
+        zulon_mir::MirInstruction::Variant { dest, variant, fields, ty } => {
+            format!("_{} = {}::{}({})",
+                dest,
+                ty.display_name(),
+                variant,
+                fields.iter().map(|(field, _)| format!("_{}", field)).collect::<Vec<_>>().join(", "))
+        }
+        zulon_mir::MirInstruction::Discriminant { dest, value, .. } => {
+            format!("_{} = discriminant _{}", dest, value)
+        }
+        zulon_mir::MirInstruction::VariantField { dest, value, variant, field_index, ty, .. } => {
+            format!("_{} = (_{} as {}).{} ({})", dest, value, variant, field_index, ty.display_name())
+        }
+        zulon_mir::MirInstruction::Aggregate { dest, fields, ty } => {
+            let fields = fields.iter().map(|(field, _)| format!("_{}", field)).collect::<Vec<_>>();
+            format!("_{} = {} {{ {} }}", dest, ty.display_name(), fields.join(", "))
+        }
         zulon_mir::MirInstruction::PerformEffect { .. } => {
             format!("<perform effect>")
         }
@@ -182,7 +215,7 @@ fn format_terminator(terminator: &zulon_mir::MirTerminator) -> String {
             format!("if _{} -> block{} else block{}",
                 condition, then_block, else_block)
         }
-        zulon_mir::MirTerminator::Switch { scrutinee, targets, default } => {
+        zulon_mir::MirTerminator::Switch { scrutinee, targets, default, .. } => {
             let targets_str = targets.iter()
                 .map(|(val, blk)| format!("{:?} -> block{}", val, blk))
                 .collect::<Vec<_>>()
@@ -204,6 +237,7 @@ fn format_place(place: &zulon_mir::MirPlace) -> String {
         zulon_mir::MirPlace::Local(name) => name.clone(),
         zulon_mir::MirPlace::Temp(temp) => format!("_{}", temp),
         zulon_mir::MirPlace::Param(name) => format!("'{}", name),
+        zulon_mir::MirPlace::Global(name) => format!("@{}", name),
         zulon_mir::MirPlace::Field { base, field } => {
             format!("{}.{}", format_place(base), field)
         }
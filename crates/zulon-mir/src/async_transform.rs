@@ -248,6 +248,10 @@ impl AsyncTransformContext {
                 live_vars.insert(*dest);
                 live_vars.insert(*operand);
             }
+            MirInstruction::Cast { dest, src, .. } => {
+                live_vars.insert(*dest);
+                live_vars.insert(*src);
+            }
             MirInstruction::Call { dest, args, .. } => {
                 if let &Some(d) = dest {
                     live_vars.insert(d);
@@ -270,6 +274,23 @@ impl AsyncTransformContext {
                 live_vars.insert(*dest);
                 live_vars.insert(*base);
             }
+            MirInstruction::Aggregate { dest, fields, .. } | MirInstruction::Variant { dest, fields, .. } => {
+                live_vars.insert(*dest);
+                live_vars.extend(fields.iter().map(|(field, _)| *field));
+            }
+            MirInstruction::Discriminant { dest, value, .. } | MirInstruction::VariantField { dest, value, .. } => {
+                live_vars.insert(*dest);
+                live_vars.insert(*value);
+            }
+            MirInstruction::CallVirtual { dest, object, args, .. } => {
+                if let &Some(d) = dest {
+                    live_vars.insert(d);
+                }
+                live_vars.insert(*object);
+                for arg in args {
+                    self.collect_temporaries_from_place(arg, live_vars);
+                }
+            }
             MirInstruction::Drop { .. } => {
                 // Drops don't produce values
             }
@@ -331,8 +352,8 @@ impl AsyncTransformContext {
             MirPlace::Ref { place, .. } => {
                 self.collect_temporaries_from_place(place, live_vars);
             }
-            MirPlace::Local(_) | MirPlace::Param(_) => {
-                // Locals and params are not temporaries
+            MirPlace::Local(_) | MirPlace::Param(_) | MirPlace::Global(_) => {
+                // Locals, params and statics are not temporaries
             }
         }
     }
@@ -605,6 +626,7 @@ impl AsyncTransformContext {
 
         entry_block.set_terminator(MirTerminator::Switch {
             scrutinee: self.state_var,
+            ty: MirTy::I32,
             targets,
             default: error_block,
         });
@@ -321,6 +321,10 @@ impl BorrowChecker {
                 self.can_read(&MirPlace::Temp(*operand), self.current_block)?;
             }
 
+            MirInstruction::Cast { src, .. } => {
+                self.can_read(&MirPlace::Temp(*src), self.current_block)?;
+            }
+
             MirInstruction::Call {
                 dest: _,
                 func: _,
@@ -368,7 +372,7 @@ impl BorrowChecker {
                 self.can_read(&MirPlace::Temp(*condition), self.current_block)?;
             }
 
-            MirTerminator::Switch { scrutinee, targets: _, default: _ } => {
+            MirTerminator::Switch { scrutinee, .. } => {
                 self.can_read(&MirPlace::Temp(*scrutinee), self.current_block)?;
             }
 
@@ -208,7 +208,7 @@ impl EffectChecker {
                 // If is pure (effects are in the blocks)
             }
 
-            MirTerminator::Switch { scrutinee: _, targets: _, default: _ } => {
+            MirTerminator::Switch { .. } => {
                 // Switch is pure (effects are in the blocks)
             }
 
@@ -11,7 +11,8 @@
 use crate::error::{MirError, Result};
 use crate::mir::*;
 use crate::ty::MirTy;
-use zulon_hir::{HirCrate, HirItem, HirFunction, HirExpression, HirBlock, HirStatement, HirTy};
+use zulon_hir::{HirCrate, HirItem, HirFunction, HirExpression, HirBlock, HirStatement, HirTy, HirVTable, HirPattern, HirVariant};
+use zulon_parser::format::{Align, Count, FormatKind, FormatSpec, Sign};
 
 /// Loop context for tracking break/continue targets
 struct LoopContext {
@@ -33,10 +34,20 @@ struct DeferContext {
 pub struct MirLoweringContext {
     /// Struct definitions: name -> (field_names, field_indices)
     struct_defs: std::collections::HashMap<String, Vec<String>>,
+    /// Field types of each struct, in declaration order
+    struct_field_tys: std::collections::HashMap<String, Vec<HirTy>>,
+    /// Variants of each enum, and of each instance of a generic enum
+    enum_defs: std::collections::HashMap<String, Vec<HirVariant>>,
     /// Loop context stack (for nested loops)
     loop_stack: Vec<LoopContext>,
     /// Defer statement stack (for cleanup blocks)
     defer_stack: Vec<DeferContext>,
+    /// Vtable layout of each trait: trait name -> (declaring trait, method)
+    vtable_slots: std::collections::HashMap<String, Vec<(String, String)>>,
+    /// Locals in scope, innermost scope last: source name -> MIR local
+    scopes: Vec<std::collections::HashMap<String, String>>,
+    /// Number of locals of the current function declared with each name
+    local_counts: std::collections::HashMap<String, usize>,
 }
 
 impl MirLoweringContext {
@@ -44,8 +55,13 @@ impl MirLoweringContext {
     pub fn new() -> Self {
         MirLoweringContext {
             struct_defs: std::collections::HashMap::new(),
+            struct_field_tys: std::collections::HashMap::new(),
+            enum_defs: std::collections::HashMap::new(),
             loop_stack: Vec::new(),
             defer_stack: Vec::new(),
+            vtable_slots: std::collections::HashMap::new(),
+            scopes: Vec::new(),
+            local_counts: std::collections::HashMap::new(),
         }
     }
 
@@ -60,6 +76,14 @@ impl MirLoweringContext {
                     .map(|field| field.name.clone())
                     .collect();
                 self.struct_defs.insert(struct_def.name.clone(), field_names);
+                let field_tys = struct_def.fields.iter().map(|field| field.ty.clone()).collect();
+                self.struct_field_tys.insert(struct_def.name.clone(), field_tys);
+            }
+            if let HirItem::Enum(enum_def) = item {
+                self.enum_defs.insert(enum_def.name.clone(), enum_def.variants.clone());
+            }
+            if let HirItem::Trait(trait_def) = item {
+                self.vtable_slots.insert(trait_def.name.clone(), hir_crate.vtable_slots(&trait_def.name));
             }
         }
 
@@ -118,6 +142,13 @@ impl MirLoweringContext {
         // Store effect names in MIR function for use during lowering
         mir_func.effects = effect_names.clone();
 
+        // Parameters keep their names; locals shadowing them get new ones
+        self.scopes = vec![std::collections::HashMap::new()];
+        self.local_counts.clear();
+        for param in &func.params {
+            self.declare_local(&param.name);
+        }
+
         // Lower function body
         let entry_block = mir_func.entry_block;
         let (return_block, return_temp) = self.lower_block(&mut mir_func, &func.body, entry_block, true)?;
@@ -146,6 +177,7 @@ impl MirLoweringContext {
         is_func_body: bool,
     ) -> Result<(MirNodeId, Option<TempVar>)> {
         let mut current_block = entry_block;
+        self.scopes.push(std::collections::HashMap::new());
 
         // Process statements
         for stmt in &block.statements {
@@ -185,9 +217,33 @@ impl MirLoweringContext {
             drop(self.lower_statement(func, &mut current_block, &defer_stmt));
         }
 
+        self.scopes.pop();
         Ok((current_block, last_temp))
     }
 
+    /// Declare a local in the innermost scope, returning its MIR name
+    ///
+    /// Each declaration gets a stack slot of its own: a name declared again,
+    /// in the same scope or a nested one, gets a numbered MIR name, so that
+    /// locals of different types never share a slot.
+    fn declare_local(&mut self, name: &str) -> String {
+        let count = self.local_counts.entry(name.to_string()).or_insert(0);
+        let local = if *count == 0 { name.to_string() } else { format!("{}.{}", name, count) };
+        *count += 1;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), local.clone());
+        }
+        local
+    }
+
+    /// The MIR name of the local a source name refers to
+    fn local(&self, name: &str) -> String {
+        self.scopes.iter().rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
     /// Lower a HIR statement to MIR instructions
     fn lower_statement(
         &mut self,
@@ -197,15 +253,20 @@ impl MirLoweringContext {
     ) -> Result<()> {
         match stmt {
             HirStatement::Local(local) => {
-                // Handle local variable declaration
-                if let Some(init) = &local.init {
-                    let temp = self.lower_expression(func, current_block, init)?;
+                // Handle local variable declaration; the initializer still
+                // sees any local the new one shadows
+                let init = match &local.init {
+                    Some(init) => Some((self.lower_expression(func, current_block, init)?, init.ty())),
+                    None => None,
+                };
+                let name = self.declare_local(&local.name);
+                if let Some((temp, ty)) = init {
                     // Store to local
                     let block_obj = func.blocks.get_mut(current_block).unwrap();
                     block_obj.push_instruction(MirInstruction::Store {
-                        dest: MirPlace::Local(local.name.clone()),
+                        dest: MirPlace::Local(name),
                         src: temp,
-                        ty: init.ty().clone().into(),
+                        ty: ty.clone().into(),
                     });
                 }
             }
@@ -260,9 +321,11 @@ impl MirLoweringContext {
     ) -> Result<TempVar> {
         match expr {
             // Literals
-            HirExpression::Literal(lit, _id, _ty, _span) => {
+            HirExpression::Literal(lit, _id, lit_ty, _span) => {
                 let temp = func.alloc_temp();
                 let (value, ty) = self.lower_literal(lit)?;
+                // Numbers have the type inferred for them
+                let ty = if lit_ty.is_numeric() { lit_ty.clone().into() } else { ty };
                 let block_obj = func.blocks.get_mut(current_block).unwrap();
                 block_obj.push_instruction(MirInstruction::Const {
                     dest: temp,
@@ -274,31 +337,18 @@ impl MirLoweringContext {
 
             // Variables
             HirExpression::Variable(name, _id, _ty, _span) => {
-                // Check if this is an enum variant (e.g., "DivideError::Zero")
+                // A unit variant (e.g., "DivideError::Zero")
                 if name.contains("::") {
-                    // For MVP: Treat enum variants as constant discriminant values
-                    // Extract the variant name and use a simple heuristic
-                    let variant_name = name.split("::").last().unwrap_or("");
-
-                    // Simple heuristic: "Zero" or similar -> 0, can be improved later
-                    let discriminant = if variant_name == "Zero" || variant_name == "None" {
-                        0
-                    } else if variant_name == "One" || variant_name == "Some" {
-                        1
-                    } else {
-                        // For other variants, use a hash-based or sequential approach
-                        // For now, default to 0
-                        0
-                    };
+                    let variant_name = name.rsplit("::").next().unwrap_or(name);
+                    self.variant(expr.ty(), variant_name)?;
 
-                    // Generate a constant instruction
                     let temp = func.alloc_temp();
-                    let mir_ty = expr.ty().clone().into();
                     let block_obj = func.blocks.get_mut(current_block).unwrap();
-                    block_obj.push_instruction(MirInstruction::Const {
+                    block_obj.push_instruction(MirInstruction::Variant {
                         dest: temp,
-                        value: MirConstant::Integer(discriminant as i128),
-                        ty: mir_ty,
+                        variant: variant_name.to_string(),
+                        fields: Vec::new(),
+                        ty: expr.ty().clone().into(),
                     });
                     Ok(temp)
                 } else {
@@ -310,13 +360,25 @@ impl MirLoweringContext {
                     // Load from local
                     block_obj.push_instruction(MirInstruction::Load {
                         dest: temp,
-                        src: MirPlace::Local(name.clone()),
+                        src: MirPlace::Local(self.local(name)),
                         ty,
                     });
                     Ok(temp)
                 }
             }
 
+            // Statics are read in place
+            HirExpression::Static { name, ty, .. } => {
+                let temp = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::Load {
+                    dest: temp,
+                    src: MirPlace::Global(name.clone()),
+                    ty: ty.clone().into(),
+                });
+                Ok(temp)
+            }
+
             // Binary operations
             HirExpression::BinaryOp { op, left, right, ty, span: _ } => {
                 // Special handling for assignment: x = expr
@@ -325,13 +387,19 @@ impl MirLoweringContext {
                     let value_temp = self.lower_expression(func, current_block, right)?;
 
                     // For the left-hand side, we need to extract the variable name
-                    // Currently, this expects left to be a simple variable reference
-                    if let HirExpression::Variable(name, ..) = &**left {
+                    // Currently, this expects left to be a simple variable
+                    // reference or a static
+                    let dest = match &**left {
+                        HirExpression::Variable(name, ..) => Some(MirPlace::Local(self.local(name))),
+                        HirExpression::Static { name, .. } => Some(MirPlace::Global(name.clone())),
+                        _ => None,
+                    };
+                    if let Some(dest) = dest {
                         // Store the value to the variable
                         let mir_ty = ty.clone().into();
                         let block_obj = func.blocks.get_mut(current_block).unwrap();
                         block_obj.push_instruction(MirInstruction::Store {
-                            dest: MirPlace::Local(name.clone()),
+                            dest,
                             src: value_temp,
                             ty: mir_ty,
                         });
@@ -382,8 +450,23 @@ impl MirLoweringContext {
                 Ok(result_temp)
             }
 
+            // Casts between primitive types
+            HirExpression::Cast { value, ty, span: _ } => {
+                let src = self.lower_expression(func, current_block, value)?;
+
+                let dest = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::Cast {
+                    dest,
+                    src,
+                    from: value.ty().clone().into(),
+                    to: ty.clone().into(),
+                });
+                Ok(dest)
+            }
+
             // Function calls
-            HirExpression::Call { func: func_expr, args, ty, span: _ } => {
+            HirExpression::Call { func: func_expr, args, ty, .. } => {
                 // Lower function name
                 let func_name = match func_expr.as_ref() {
                     HirExpression::Variable(name, _id, _ty, _span) => name.clone(),
@@ -399,6 +482,20 @@ impl MirLoweringContext {
                     arg_temps.push(arg_temp);
                 }
 
+                // A tuple variant (e.g., "Shape::Rect(w, h)") builds its value
+                let variant_name = func_name.rsplit("::").next().unwrap_or(&func_name);
+                if self.is_variant(ty, variant_name) {
+                    let temp = func.alloc_temp();
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    block_obj.push_instruction(MirInstruction::Variant {
+                        dest: temp,
+                        variant: variant_name.to_string(),
+                        fields: arg_temps.into_iter().zip(args).map(|(temp, arg)| (temp, arg.ty().clone().into())).collect(),
+                        ty: ty.clone().into(),
+                    });
+                    return Ok(temp);
+                }
+
                 // Allocate result temp (or None if unit)
                 let return_ty = ty.clone().into();
                 let dest_temp = if return_ty != MirTy::Unit {
@@ -472,6 +569,80 @@ impl MirLoweringContext {
                 }
             }
 
+            // Trait method calls on trait objects, dispatched through the
+            // object's vtable
+            HirExpression::MethodCall { method_name, trait_name, self_ty: HirTy::TraitObject(traits), args, ty, .. } => {
+                let object_trait = traits.join(" + ");
+                let slot = self.vtable_slots.get(&object_trait)
+                    .and_then(|slots| slots.iter().position(|(declaring, method)| declaring == trait_name && method == method_name))
+                    .ok_or_else(|| MirError::LoweringError(
+                        format!("`{}::{}` is not a method of `dyn {}`", trait_name, method_name, object_trait)
+                    ))?;
+
+                let mut arg_temps = Vec::new();
+                for arg in args {
+                    arg_temps.push(self.lower_expression(func, current_block, arg)?);
+                }
+                if arg_temps.is_empty() {
+                    return Err(MirError::LoweringError(
+                        format!("call of `{}` on a trait object has no receiver", method_name)
+                    ));
+                }
+                let object = arg_temps.remove(0);
+
+                let return_ty: MirTy = ty.clone().into();
+                let dest_temp = if return_ty != MirTy::Unit {
+                    Some(func.alloc_temp())
+                } else {
+                    None
+                };
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::CallVirtual {
+                    dest: dest_temp,
+                    object,
+                    slot,
+                    args: arg_temps.into_iter().map(MirPlace::Temp).collect(),
+                    return_type: return_ty,
+                });
+
+                Ok(dest_temp.unwrap_or_else(|| func.alloc_temp()))
+            }
+
+            // Conversion of a reference to a trait object reference: the
+            // reference paired with the vtable of the referenced type
+            HirExpression::TraitObject { value, ty, span: _ } => {
+                let (HirTy::Ref { inner: self_ty, .. } | HirTy::Ptr { inner: self_ty, .. }) = value.ty() else {
+                    return Err(MirError::LoweringError(
+                        format!("cannot convert a value of type `{}` to a trait object", value.ty())
+                    ));
+                };
+                let trait_name = match ty {
+                    HirTy::Ref { inner, .. } | HirTy::Ptr { inner, .. } => match inner.as_ref() {
+                        HirTy::TraitObject(traits) => traits.join(" + "),
+                        _ => String::new(),
+                    },
+                    _ => String::new(),
+                };
+                let data_temp = self.lower_expression(func, current_block, value)?;
+
+                let vtable_ty = MirTy::Ptr { inner: Box::new(MirTy::U8), mutable: false };
+                let vtable_temp = func.alloc_temp();
+                let result_temp = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::Const {
+                    dest: vtable_temp,
+                    value: MirConstant::VTable(HirVTable::symbol(self_ty, &trait_name)),
+                    ty: vtable_ty.clone(),
+                });
+                block_obj.push_instruction(MirInstruction::Aggregate {
+                    dest: result_temp,
+                    fields: vec![(data_temp, value.ty().clone().into()), (vtable_temp, vtable_ty)],
+                    ty: ty.clone().into(),
+                });
+
+                Ok(result_temp)
+            }
+
             // Field access (e.g., object.field_name)
             HirExpression::Field { base, field_name, ty, span: _ } => {
                 // Lower the base expression
@@ -509,8 +680,9 @@ impl MirLoweringContext {
                 let block_obj = func.blocks.get_mut(&old_block).unwrap();
                 block_obj.set_terminator(MirTerminator::Goto { target: new_block });
 
-                *current_block = new_block;
-                let (_, temp) = self.lower_block(func, block, new_block, false)?;
+                // The block's value is reached at the block it ends in
+                let (final_block, temp) = self.lower_block(func, block, new_block, false)?;
+                *current_block = final_block;
 
                 Ok(temp.unwrap_or_else(|| {
                     // Empty block, return unit
@@ -596,16 +768,12 @@ impl MirLoweringContext {
                 *current_block = then_block_id;
                 let (then_final_block, then_temp) = self.lower_block(func, then_block, then_block_id, false)?;
 
-                // For Unit-type if expressions (statements), check the final block for terminators
-                // For value-producing if expressions, check the initial block
+                // A branch with control flow of its own, like a nested if or
+                // a match, reaches the join from its final block
                 let mir_ty: MirTy = ty.clone().into();
-                let check_final_block = matches!(mir_ty, MirTy::Unit);
+                let is_unit = matches!(mir_ty, MirTy::Unit);
 
-                let then_block_obj = if check_final_block {
-                    func.blocks.get_mut(&then_final_block).unwrap()
-                } else {
-                    func.blocks.get_mut(&then_block_id).unwrap()
-                };
+                let then_block_obj = func.blocks.get_mut(&then_final_block).unwrap();
                 let then_has_term = then_block_obj.terminator.is_some();
                 // Only set terminator if block doesn't already have one (e.g., from break/continue)
                 if !then_has_term {
@@ -618,11 +786,7 @@ impl MirLoweringContext {
                     let (final_block, et) = self.lower_block(func, else_blk, else_block_id, false)?;
                     let et = et.unwrap_or_else(|| func.alloc_temp());
 
-                    let else_block_obj = if check_final_block {
-                        func.blocks.get_mut(&final_block).unwrap()
-                    } else {
-                        func.blocks.get_mut(&else_block_id).unwrap()
-                    };
+                    let else_block_obj = func.blocks.get_mut(&final_block).unwrap();
                     let has_term = else_block_obj.terminator.is_some();
                     // Only set terminator if block doesn't already have one (e.g., from break/continue)
                     if !has_term {
@@ -648,7 +812,7 @@ impl MirLoweringContext {
                     // Both branches have terminators
                     // For Unit-type ifs (statements), the join block is always the continuation
                     // For value-producing ifs (expressions), we need to check the terminators
-                    if check_final_block {
+                    if is_unit {
                         // Unit type: join block is always the continuation point
                         // Ensure final blocks actually branch to the join block
                         let then_final_block_obj = func.blocks.get_mut(&then_final_block).unwrap();
@@ -671,8 +835,8 @@ impl MirLoweringContext {
                         Ok(dummy_temp)
                     } else {
                         // Value-producing type: check if both branches end with Return
-                        let then_block_obj = func.blocks.get(&then_block_id).unwrap();
-                        let else_block_obj = func.blocks.get(&else_block_id).unwrap();
+                        let then_block_obj = func.blocks.get(&then_final_block).unwrap();
+                        let else_block_obj = func.blocks.get(&_else_final_block).unwrap();
 
                         let then_has_return = matches!(then_block_obj.terminator, Some(MirTerminator::Return(_)));
                         let else_has_return = matches!(else_block_obj.terminator, Some(MirTerminator::Return(_)));
@@ -766,89 +930,109 @@ impl MirLoweringContext {
                 Ok(dummy_temp)
             }
 
-            // Match expression (simplified - literal patterns only)
-            HirExpression::Match { scrutinee, arms, ty: _, span: _ } => {
-                // Lower the scrutinee expression
+            // Match expression: arms of literals and wildcards without
+            // guards switch on the scrutinee, others test each arm's pattern
+            // in turn
+            HirExpression::Match { scrutinee, arms, ty, span: _ } => {
                 let scrutinee_temp = self.lower_expression(func, current_block, scrutinee)?;
-
-                // Allocate blocks for each arm
-                let mut arm_blocks = Vec::new();
-                for _arm in arms {
-                    arm_blocks.push(func.alloc_block());
-                }
-                let default_block = func.alloc_block();
                 let join_block = func.alloc_block();
 
-                // Collect targets for switch (literal patterns only)
-                let mut switch_targets = Vec::new();
-                for (i, arm) in arms.iter().enumerate() {
-                    match &arm.pattern {
-                        zulon_hir::HirPattern::Literal(lit, _span) => {
-                            // Convert literal to MIR constant
-                            let mir_const = self.lower_literal(lit)?.0;
-                            switch_targets.push((mir_const, arm_blocks[i]));
-                        }
-                        zulon_hir::HirPattern::Wildcard(_span) => {
-                            // Wildcard goes to default
-                            // (handled by not adding to switch_targets)
+                // The value of each arm whose body reaches the join block
+                let mut arm_temps = Vec::new();
+                let mut lower_arm_body = |this: &mut Self, func: &mut MirFunction, current_block: &mut MirNodeId, arm: &zulon_hir::HirMatchArm| -> Result<()> {
+                    let body_temp = this.lower_expression(func, current_block, &arm.body)?;
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    if block_obj.terminator.is_none() {
+                        block_obj.set_terminator(MirTerminator::Goto { target: join_block });
+                        arm_temps.push(body_temp);
+                    }
+                    Ok(())
+                };
+
+                let is_switch = arms.iter().all(|arm| {
+                    arm.guard.is_none() && matches!(arm.pattern, HirPattern::Literal(..) | HirPattern::Wildcard(_))
+                });
+                if is_switch {
+                    // Arms after a wildcard, or repeating a literal, are
+                    // unreachable and left out
+                    let mut switch_targets: Vec<(MirConstant, MirNodeId)> = Vec::new();
+                    let mut default_block = None;
+                    let mut arm_blocks = Vec::new();
+                    for arm in arms {
+                        if default_block.is_some() {
+                            break;
                         }
-                        _ => {
-                            // For now, ignore complex patterns
-                            // They'll match the default case
+                        let arm_block = func.alloc_block();
+                        match &arm.pattern {
+                            HirPattern::Literal(lit, _span) => {
+                                let value = self.lower_literal(lit)?.0;
+                                if switch_targets.iter().any(|(target, _)| *target == value) {
+                                    continue;
+                                }
+                                switch_targets.push((value, arm_block));
+                            }
+                            _ => default_block = Some(arm_block),
                         }
+                        arm_blocks.push((arm_block, arm));
                     }
-                }
+                    let default_block = default_block.unwrap_or_else(|| {
+                        let block = func.alloc_block();
+                        func.blocks.get_mut(&block).unwrap().set_terminator(MirTerminator::Unreachable);
+                        block
+                    });
 
-                // Set switch terminator in current block
-                let block_obj = func.blocks.get_mut(current_block).unwrap();
-                block_obj.set_terminator(MirTerminator::Switch {
-                    scrutinee: scrutinee_temp,
-                    targets: switch_targets,
-                    default: default_block,
-                });
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    block_obj.set_terminator(MirTerminator::Switch {
+                        scrutinee: scrutinee_temp,
+                        ty: scrutinee.ty().clone().into(),
+                        targets: switch_targets,
+                        default: default_block,
+                    });
 
-                // Lower each arm
-                let mut arm_temps = Vec::new();
-                for (i, arm) in arms.iter().enumerate() {
-                    *current_block = arm_blocks[i];
-
-                    // TODO: Handle pattern bindings (ignore for now)
-                    // Lower the arm body
-                    let body_temp = self.lower_expression(func, current_block, &arm.body)?;
-                    arm_temps.push(body_temp);
-
-                    // Add goto to join block
-                    let arm_block_obj = func.blocks.get_mut(&arm_blocks[i]).unwrap();
-                    if arm_block_obj.terminator.is_none() {
-                        arm_block_obj.set_terminator(MirTerminator::Goto { target: join_block });
+                    for (arm_block, arm) in arm_blocks {
+                        *current_block = arm_block;
+                        lower_arm_body(self, func, current_block, arm)?;
                     }
-                }
-
-                // Handle default block (for wildcard or non-literal patterns)
-                *current_block = default_block;
-                // For now, just use the last arm as default
-                let default_temp = if let Some(last_arm) = arms.last() {
-                    self.lower_expression(func, current_block, &last_arm.body)?
                 } else {
-                    func.alloc_temp()
-                };
+                    for arm in arms {
+                        let next_block = func.alloc_block();
+                        self.scopes.push(std::collections::HashMap::new());
+                        self.lower_pattern_test(func, current_block, scrutinee_temp, &arm.pattern, scrutinee.ty(), next_block)?;
+
+                        if let Some(guard) = &arm.guard {
+                            let guard_temp = self.lower_expression(func, current_block, guard)?;
+                            let body_block = func.alloc_block();
+                            let block_obj = func.blocks.get_mut(current_block).unwrap();
+                            block_obj.set_terminator(MirTerminator::If {
+                                condition: guard_temp,
+                                then_block: body_block,
+                                else_block: next_block,
+                            });
+                            *current_block = body_block;
+                        }
 
-                let default_block_obj = func.blocks.get_mut(&default_block).unwrap();
-                if default_block_obj.terminator.is_none() {
-                    default_block_obj.set_terminator(MirTerminator::Goto { target: join_block });
+                        lower_arm_body(self, func, current_block, arm)?;
+                        self.scopes.pop();
+                        *current_block = next_block;
+                    }
+
+                    // The type checker saw that the arms cover every value
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    block_obj.set_terminator(MirTerminator::Unreachable);
                 }
 
-                // Join block: create Phi-like value (simplified - use first arm temp)
+                // Join block: LIR turns the move into a phi of the value
+                // each arm reaches it with
                 *current_block = join_block;
                 let result_temp = func.alloc_temp();
-                let join_block_obj = func.blocks.get_mut(&join_block).unwrap();
-
-                // TODO: Proper Phi nodes - for now, just move first arm value
-                let first_arm_temp = arm_temps.first().copied().unwrap_or(default_temp);
-                join_block_obj.push_instruction(MirInstruction::Move {
-                    dest: result_temp,
-                    src: MirPlace::Temp(first_arm_temp),
-                });
+                let mir_ty: MirTy = ty.clone().into();
+                if let (false, Some(&first_arm_temp)) = (matches!(mir_ty, MirTy::Unit), arm_temps.first()) {
+                    let join_block_obj = func.blocks.get_mut(&join_block).unwrap();
+                    join_block_obj.push_instruction(MirInstruction::Move {
+                        dest: result_temp,
+                        src: MirPlace::Temp(first_arm_temp),
+                    });
+                }
 
                 Ok(result_temp)
             }
@@ -1299,6 +1483,7 @@ impl MirLoweringContext {
             HirExpression::TemplateString { parts, ty, span: _ } => {
                 // Desugar template strings to runtime string_concat calls
                 // `Hello ${name}!` desugars to: string_concat("Hello ", name, "!")
+                // Interpolations that aren't plain strings are formatted first
                 // For efficiency, we chain binary calls: string_concat(string_concat("Hello ", name), "!")
 
                 if parts.is_empty() {
@@ -1328,9 +1513,16 @@ impl MirLoweringContext {
                             });
                             part_temps.push(temp);
                         }
-                        zulon_hir::HirTemplateStringPart::Expr(expr) => {
-                            // Lower the interpolated expression
-                            let expr_temp = self.lower_expression(func, current_block, expr)?;
+                        zulon_hir::HirTemplateStringPart::Expr { expr, spec, width, precision } => {
+                            // Format the interpolated expression into a string
+                            let expr_temp = self.lower_format_argument(
+                                func,
+                                current_block,
+                                expr,
+                                spec,
+                                width.as_deref(),
+                                precision.as_deref(),
+                            )?;
                             part_temps.push(expr_temp);
                         }
                     }
@@ -1399,6 +1591,50 @@ impl MirLoweringContext {
                 Ok(result_temp)
             }
 
+            // Struct literal: fields are evaluated in source order, then
+            // assembled in declaration order
+            HirExpression::Struct { name, fields, ty, span: _ } => {
+                // A struct-like variant (e.g., "Shape::Circle { r }")
+                let variant_fields = match ty {
+                    HirTy::Enum { .. } => Some(self.variant(ty, name)?.fields.clone()),
+                    _ => None,
+                };
+
+                let mut field_temps = Vec::new();
+                for (field_name, value) in fields {
+                    let index = match &variant_fields {
+                        Some(variant_fields) => variant_fields.iter().position(|field| field.name == *field_name)
+                            .ok_or_else(|| MirError::InvalidFieldAccess {
+                                field: field_name.clone(),
+                                reason: format!("variant `{}` has no such field", name),
+                            })?,
+                        None => self.get_field_index_in_struct(name, field_name)?,
+                    };
+                    let temp = self.lower_expression(func, current_block, value)?;
+                    field_temps.push((index, temp, value.ty().clone().into()));
+                }
+                field_temps.sort_by_key(|(index, ..)| *index);
+                let field_temps = field_temps.into_iter().map(|(_, temp, ty)| (temp, ty)).collect();
+
+                let result_temp = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(match variant_fields {
+                    Some(_) => MirInstruction::Variant {
+                        dest: result_temp,
+                        variant: name.clone(),
+                        fields: field_temps,
+                        ty: ty.clone().into(),
+                    },
+                    None => MirInstruction::Aggregate {
+                        dest: result_temp,
+                        fields: field_temps,
+                        ty: ty.clone().into(),
+                    },
+                });
+
+                Ok(result_temp)
+            }
+
             _ => {
                 return Err(MirError::LoweringError(
                     format!("Unsupported expression: {:?}", expr)
@@ -1407,6 +1643,124 @@ impl MirLoweringContext {
         }
     }
 
+    /// Lower an interpolated template string expression to a string
+    ///
+    /// Primitives are formatted by the runtime's `zulon_format_*` helpers,
+    /// which apply the whole spec. Other values call the `fmt` method of the
+    /// spec's trait (`Display::fmt`, `Debug::fmt`, ...) and the result is
+    /// padded by `zulon_format_str` if the spec asks for it.
+    fn lower_format_argument(
+        &mut self,
+        func: &mut MirFunction,
+        current_block: &mut MirNodeId,
+        expr: &HirExpression,
+        spec: &FormatSpec,
+        width: Option<&HirExpression>,
+        precision: Option<&HirExpression>,
+    ) -> Result<TempVar> {
+        let value_ty: MirTy = expr.ty().clone().into();
+        let mut value = self.lower_expression(func, current_block, expr)?;
+
+        let is_str = match &value_ty {
+            MirTy::String => true,
+            MirTy::Ref { inner, .. } | MirTy::Ptr { inner, .. } => **inner == MirTy::U8,
+            _ => false,
+        };
+        // Plain strings are already formatted
+        if spec.is_default() && is_str {
+            return Ok(value);
+        }
+
+        // Narrower scalars are widened to the helper's argument type
+        let (helper, widened) = match value_ty {
+            MirTy::I8 | MirTy::I16 => ("zulon_format_i32", Some(MirTy::I32)),
+            MirTy::I32 => ("zulon_format_i32", None),
+            MirTy::I64 | MirTy::ISize => ("zulon_format_i64", None),
+            MirTy::U8 | MirTy::U16 => ("zulon_format_u32", Some(MirTy::U32)),
+            MirTy::U32 => ("zulon_format_u32", None),
+            MirTy::U64 | MirTy::USize => ("zulon_format_u64", None),
+            MirTy::F32 => ("zulon_format_f64", Some(MirTy::F64)),
+            MirTy::F64 => ("zulon_format_f64", None),
+            MirTy::Bool => ("zulon_format_bool", None),
+            MirTy::Char => ("zulon_format_char", None),
+            _ if is_str => ("zulon_format_str", None),
+            // Other values were formatted by their `fmt` method in HIR
+            _ => {
+                return Err(MirError::TypeError(format!(
+                    "cannot format a `{}` with `{}`",
+                    value_ty.display_name(),
+                    spec.kind.trait_name()
+                )));
+            }
+        };
+        if let Some(to) = widened {
+            let dest = func.alloc_temp();
+            let block_obj = func.blocks.get_mut(current_block).unwrap();
+            block_obj.push_instruction(MirInstruction::Cast {
+                dest,
+                src: value,
+                from: value_ty.clone(),
+                to,
+            });
+            value = dest;
+        }
+
+        let align = match spec.align {
+            None => 0,
+            Some(Align::Left) => 1,
+            Some(Align::Center) => 2,
+            Some(Align::Right) => 3,
+        };
+        let mut flags = match spec.sign {
+            Some(Sign::Plus) => 1,
+            Some(Sign::Minus) => 2,
+            None => 0,
+        };
+        if spec.alternate {
+            flags |= 4;
+        }
+        if spec.zero_pad {
+            flags |= 8;
+        }
+        let kind = FormatKind::ALL.iter().position(|kind| *kind == spec.kind).unwrap_or(0);
+
+        let mut args = vec![MirPlace::Temp(value)];
+        for constant in [spec.fill as i128, align, flags] {
+            args.push(MirPlace::Temp(Self::lower_i32_constant(func, current_block, constant)));
+        }
+        for (count, expr) in [(&spec.width, width), (&spec.precision, precision)] {
+            let temp = match (count, expr) {
+                (_, Some(expr)) => self.lower_expression(func, current_block, expr)?,
+                (Some(Count::Is(count)), None) => Self::lower_i32_constant(func, current_block, *count as i128),
+                _ => Self::lower_i32_constant(func, current_block, -1),
+            };
+            args.push(MirPlace::Temp(temp));
+        }
+        args.push(MirPlace::Temp(Self::lower_i32_constant(func, current_block, kind as i128)));
+
+        let result = func.alloc_temp();
+        let block_obj = func.blocks.get_mut(current_block).unwrap();
+        block_obj.push_instruction(MirInstruction::Call {
+            dest: Some(result),
+            func: MirPlace::Local(helper.to_string()),
+            args,
+            return_type: MirTy::String,
+        });
+        Ok(result)
+    }
+
+    /// Load an `i32` constant into a new temporary
+    fn lower_i32_constant(func: &mut MirFunction, current_block: &MirNodeId, value: i128) -> TempVar {
+        let temp = func.alloc_temp();
+        let block_obj = func.blocks.get_mut(current_block).unwrap();
+        block_obj.push_instruction(MirInstruction::Const {
+            dest: temp,
+            value: MirConstant::Integer(value),
+            ty: MirTy::I32,
+        });
+        temp
+    }
+
     /// Lower a literal to MIR constant
     fn lower_literal(&self, lit: &zulon_hir::HirLiteral) -> Result<(MirConstant, MirTy)> {
         match lit {
@@ -1414,11 +1768,286 @@ impl MirLoweringContext {
             zulon_hir::HirLiteral::Integer(i) => Ok((MirConstant::Integer(*i as i128), MirTy::I32)),
             zulon_hir::HirLiteral::Float(f) => Ok((MirConstant::Float(*f), MirTy::F64)),
             zulon_hir::HirLiteral::String(s) => Ok((MirConstant::String(s.clone()), MirTy::String)),
+            zulon_hir::HirLiteral::ByteString(bytes) => Ok((
+                MirConstant::ByteString(bytes.clone()),
+                MirTy::Array { inner: Box::new(MirTy::U8), len: bytes.len() as u64 },
+            )),
             zulon_hir::HirLiteral::Char(c) => Ok((MirConstant::Char(*c), MirTy::Char)),
             zulon_hir::HirLiteral::Unit => Ok((MirConstant::Unit, MirTy::Unit)),
         }
     }
 
+    /// Branch to `otherwise` unless `value` matches `pattern`, storing the
+    /// names it binds; `current_block` is left at the block reached on a
+    /// match
+    fn lower_pattern_test(
+        &mut self,
+        func: &mut MirFunction,
+        current_block: &mut MirNodeId,
+        value: TempVar,
+        pattern: &HirPattern,
+        ty: &HirTy,
+        otherwise: MirNodeId,
+    ) -> Result<()> {
+        // Patterns other than bindings and strings match the value a
+        // reference points to; or-patterns leave that to each alternative
+        let matches_ref = matches!(
+            pattern,
+            HirPattern::Wildcard(_)
+                | HirPattern::Binding(..)
+                | HirPattern::Or(..)
+                | HirPattern::Literal(zulon_hir::HirLiteral::String(_), _)
+        );
+        let (value, ty) = match ty {
+            HirTy::Ref { inner, .. } if !matches_ref => {
+                let pointee = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::UnaryOp {
+                    dest: pointee,
+                    op: MirUnaryOp::Deref,
+                    operand: value,
+                    ty: (**inner).clone().into(),
+                });
+                (pointee, &**inner)
+            }
+            _ => (value, ty),
+        };
+
+        match pattern {
+            HirPattern::Wildcard(_) => {}
+            HirPattern::Binding(name, binding_ty, _span) => {
+                // Each alternative of an or-pattern binds the same locals
+                let local = match self.scopes.last().and_then(|scope| scope.get(name)) {
+                    Some(local) => local.clone(),
+                    None => self.declare_local(name),
+                };
+
+                // The local gets a copy so later tests still read the
+                // matched value itself
+                let bound = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::Copy {
+                    dest: bound,
+                    src: MirPlace::Temp(value),
+                });
+                block_obj.push_instruction(MirInstruction::Store {
+                    dest: MirPlace::Local(local),
+                    src: bound,
+                    ty: binding_ty.clone().into(),
+                });
+            }
+            HirPattern::Literal(lit, _span) => {
+                let operand = self.pattern_constant(func, current_block, lit, ty)?;
+                self.branch_unless(func, current_block, MirBinOp::Eq, value, operand, otherwise);
+            }
+            HirPattern::Range { start, end, inclusive, ty: range_ty, .. } => {
+                let lo = self.pattern_constant(func, current_block, start, range_ty)?;
+                self.branch_unless(func, current_block, MirBinOp::GreaterEq, value, lo, otherwise);
+                let hi = self.pattern_constant(func, current_block, end, range_ty)?;
+                let op = if *inclusive { MirBinOp::LessEq } else { MirBinOp::Less };
+                self.branch_unless(func, current_block, op, value, hi, otherwise);
+            }
+            HirPattern::EnumVariant { variant_name, inner, ty: pattern_ty, .. } => {
+                // The matched type names the enum, or the instance of a
+                // generic enum, whose payload types the fields have
+                let enum_ty = match ty {
+                    HirTy::Enum { .. } => ty,
+                    _ => pattern_ty,
+                };
+                let variant = self.variant(enum_ty, variant_name)?.clone();
+
+                let discriminant = func.alloc_temp();
+                let operand = func.alloc_temp();
+                let block_obj = func.blocks.get_mut(current_block).unwrap();
+                block_obj.push_instruction(MirInstruction::Discriminant {
+                    dest: discriminant,
+                    value,
+                    enum_ty: enum_ty.clone().into(),
+                });
+                block_obj.push_instruction(MirInstruction::Const {
+                    dest: operand,
+                    value: MirConstant::Integer(variant.discriminant),
+                    ty: MirTy::I32,
+                });
+                self.branch_unless(func, current_block, MirBinOp::Eq, discriminant, operand, otherwise);
+
+                // Each sub-pattern matches a field of the payload: a tuple
+                // pattern one per field when there are several, a struct
+                // pattern by name when the fields have names
+                let Some(inner) = inner else {
+                    return Ok(());
+                };
+                let named = variant.fields.first().is_some_and(|field| field.name.parse::<usize>().is_err());
+                let field_patterns: Vec<(usize, &HirPattern)> = match inner.as_ref() {
+                    HirPattern::Tuple(patterns, _) if variant.fields.len() != 1 => patterns.iter().enumerate().collect(),
+                    HirPattern::Struct { fields, .. } if named => fields.iter()
+                        .map(|(name, pattern)| {
+                            let index = variant.fields.iter().position(|field| field.name == *name)
+                                .ok_or_else(|| MirError::InvalidFieldAccess {
+                                    field: name.clone(),
+                                    reason: format!("variant `{}` has no such field", variant.name),
+                                })?;
+                            Ok((index, pattern))
+                        })
+                        .collect::<Result<_>>()?,
+                    pattern => vec![(0, pattern)],
+                };
+                for (index, pattern) in field_patterns {
+                    let Some(field) = variant.fields.get(index) else {
+                        return Err(MirError::InvalidFieldAccess {
+                            field: index.to_string(),
+                            reason: format!("variant `{}` has {} fields", variant.name, variant.fields.len()),
+                        });
+                    };
+                    let field_value = func.alloc_temp();
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    block_obj.push_instruction(MirInstruction::VariantField {
+                        dest: field_value,
+                        value,
+                        variant: variant.name.clone(),
+                        field_index: index,
+                        enum_ty: enum_ty.clone().into(),
+                        ty: field.ty.clone().into(),
+                    });
+                    self.lower_pattern_test(func, current_block, field_value, pattern, &field.ty, otherwise)?;
+                }
+            }
+            HirPattern::Tuple(patterns, _span) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    let field_ty = match ty {
+                        HirTy::Tuple(tys) => tys.get(i).cloned().unwrap_or(HirTy::I32),
+                        _ => HirTy::I32,
+                    };
+                    let field = self.pattern_field(func, current_block, value, &i.to_string(), i, &field_ty);
+                    self.lower_pattern_test(func, current_block, field, pattern, &field_ty, otherwise)?;
+                }
+            }
+            HirPattern::Struct { fields, ty: struct_ty, .. } => {
+                // Payload patterns only learn their struct from the matched type
+                let struct_ty = match ty {
+                    HirTy::Struct { .. } => ty,
+                    _ => struct_ty,
+                };
+                for (name, pattern) in fields {
+                    let field_index = self.get_field_index(struct_ty, name)?;
+                    let field_ty = match struct_ty {
+                        HirTy::Struct { name: struct_name, .. } => self.struct_field_tys.get(struct_name)
+                            .and_then(|tys| tys.get(field_index).cloned()),
+                        _ => None,
+                    }
+                    .unwrap_or(HirTy::I32);
+                    let field = self.pattern_field(func, current_block, value, name, field_index, &field_ty);
+                    self.lower_pattern_test(func, current_block, field, pattern, &field_ty, otherwise)?;
+                }
+            }
+            HirPattern::Or(alternatives, _span) => {
+                // Each alternative that fails tries the next one
+                let matched_block = func.alloc_block();
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    let next_block = if i + 1 == alternatives.len() { otherwise } else { func.alloc_block() };
+                    self.lower_pattern_test(func, current_block, value, alternative, ty, next_block)?;
+                    let block_obj = func.blocks.get_mut(current_block).unwrap();
+                    block_obj.set_terminator(MirTerminator::Goto { target: matched_block });
+                    *current_block = next_block;
+                }
+                *current_block = matched_block;
+            }
+        }
+        Ok(())
+    }
+
+    /// A constant a pattern compares against, of the matched type when it
+    /// is a scalar
+    fn pattern_constant(
+        &self,
+        func: &mut MirFunction,
+        current_block: &MirNodeId,
+        lit: &zulon_hir::HirLiteral,
+        ty: &HirTy,
+    ) -> Result<TempVar> {
+        let (value, literal_ty) = self.lower_literal(lit)?;
+        let ty = if ty.is_numeric() || matches!(ty, HirTy::Bool | HirTy::Char) { ty.clone().into() } else { literal_ty };
+        let temp = func.alloc_temp();
+        let block_obj = func.blocks.get_mut(current_block).unwrap();
+        block_obj.push_instruction(MirInstruction::Const { dest: temp, value, ty });
+        Ok(temp)
+    }
+
+    /// Compare `value` with `operand`, continuing in a new block when `op`
+    /// holds and branching to `otherwise` when it does not
+    fn branch_unless(
+        &self,
+        func: &mut MirFunction,
+        current_block: &mut MirNodeId,
+        op: MirBinOp,
+        value: TempVar,
+        operand: TempVar,
+        otherwise: MirNodeId,
+    ) {
+        let condition = func.alloc_temp();
+        let matched_block = func.alloc_block();
+        let block_obj = func.blocks.get_mut(current_block).unwrap();
+        block_obj.push_instruction(MirInstruction::BinaryOp {
+            dest: condition,
+            op,
+            left: value,
+            right: operand,
+            ty: MirTy::Bool,
+        });
+        block_obj.set_terminator(MirTerminator::If {
+            condition,
+            then_block: matched_block,
+            else_block: otherwise,
+        });
+        *current_block = matched_block;
+    }
+
+    /// Read a field of a tuple or struct a pattern matches
+    fn pattern_field(
+        &self,
+        func: &mut MirFunction,
+        current_block: &MirNodeId,
+        base: TempVar,
+        field_name: &str,
+        field_index: usize,
+        ty: &HirTy,
+    ) -> TempVar {
+        let dest = func.alloc_temp();
+        let block_obj = func.blocks.get_mut(current_block).unwrap();
+        block_obj.push_instruction(MirInstruction::FieldAccess {
+            dest,
+            base,
+            field_name: field_name.to_string(),
+            field_index,
+            ty: ty.clone().into(),
+        });
+        dest
+    }
+
+    /// The variant `variant_name` of the enum type `ty`, found in the
+    /// instance for its type arguments when it has any
+    fn variant(&self, ty: &HirTy, variant_name: &str) -> Result<&HirVariant> {
+        let HirTy::Enum { name, generics } = ty else {
+            return Err(MirError::TypeError(
+                format!("`{}` is not an enum, so it has no variant `{}`", ty, variant_name)
+            ));
+        };
+        let name = match generics.as_slice() {
+            [] => name.clone(),
+            args => zulon_hir::mangle(name, args),
+        };
+        self.enum_defs.get(&name)
+            .and_then(|variants| variants.iter().find(|variant| variant.name == variant_name))
+            .ok_or_else(|| MirError::TypeError(
+                format!("enum `{}` has no variant `{}`", name, variant_name)
+            ))
+    }
+
+    /// Whether `variant_name` names a variant of the enum type `ty`
+    fn is_variant(&self, ty: &HirTy, variant_name: &str) -> bool {
+        self.variant(ty, variant_name).is_ok()
+    }
+
     /// Lower a binary operator
     fn lower_bin_op(&self, op: zulon_hir::HirBinOp) -> MirBinOp {
         match op {
@@ -1464,6 +2093,9 @@ impl MirLoweringContext {
     fn get_field_index(&self, ty: &HirTy, field_name: &str) -> Result<usize> {
         // First, try to extract struct name from the type
         let struct_name = match ty {
+            HirTy::Ref { inner, .. } | HirTy::Ptr { inner, .. } => {
+                return self.get_field_index(inner, field_name);
+            }
             HirTy::Struct { name, .. } => {
                 // Type info is available - use it
                 return self.get_field_index_in_struct(name, field_name);
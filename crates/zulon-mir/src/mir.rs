@@ -170,6 +170,14 @@ pub enum MirInstruction {
         ty: MirTy,
     },
 
+    /// Conversion between primitive types
+    Cast {
+        dest: TempVar,
+        src: TempVar,
+        from: MirTy,
+        to: MirTy,
+    },
+
     /// Function call
     Call {
         dest: Option<TempVar>,  // None if function returns unit
@@ -209,12 +217,56 @@ pub enum MirInstruction {
         ty: MirTy,
     },
 
+    /// Build a struct value from its field values, in declaration order
+    Aggregate {
+        dest: TempVar,
+        fields: Vec<(TempVar, MirTy)>,
+        ty: MirTy,
+    },
+
+    /// Build a value of the enum `ty` holding `variant`, from its payload
+    /// field values in declaration order
+    Variant {
+        dest: TempVar,
+        variant: String,
+        fields: Vec<(TempVar, MirTy)>,
+        ty: MirTy,
+    },
+
+    /// Read the discriminant of the enum value `value`, as an `i32`
+    Discriminant {
+        dest: TempVar,
+        value: TempVar,
+        enum_ty: MirTy,
+    },
+
+    /// Read payload field `field_index` of the enum value `value`, which
+    /// holds `variant`
+    VariantField {
+        dest: TempVar,
+        value: TempVar,
+        variant: String,
+        field_index: usize,
+        enum_ty: MirTy,
+        ty: MirTy,
+    },
+
     /// Drop a value (run destructor if needed)
     Drop {
         place: MirPlace,
         ty: MirTy,
     },
 
+    /// Call the method in `slot` of the vtable of the trait object
+    /// reference `object`, passing its data pointer as the first argument
+    CallVirtual {
+        dest: Option<TempVar>,  // None if the method returns unit
+        object: TempVar,
+        slot: usize,
+        args: Vec<MirPlace>,
+        return_type: MirTy,
+    },
+
     /// Perform an effect operation (to be handled by try...with blocks)
     PerformEffect {
         dest: Option<TempVar>,  // None if effect operation returns unit
@@ -226,14 +278,18 @@ pub enum MirInstruction {
 }
 
 /// Constant value
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum MirConstant {
     Bool(bool),
     Integer(i128),
     Float(f64),
     Char(char),
     String(String),
+    /// A byte string, as a `[u8; N]` value
+    ByteString(Vec<u8>),
     Unit,
+    /// Address of the vtable with the given symbol
+    VTable(String),
 }
 
 /// Place in memory (variable, temporary, field, etc.)
@@ -248,6 +304,9 @@ pub enum MirPlace {
     /// Parameter
     Param(String),
 
+    /// A `static` item
+    Global(String),
+
     /// Field access: base.field
     Field {
         base: Box<MirPlace>,
@@ -311,6 +370,8 @@ pub enum MirTerminator {
     /// Switch (for match expressions)
     Switch {
         scrutinee: TempVar,
+        /// Type of the scrutinee
+        ty: MirTy,
         targets: Vec<(MirConstant, MirNodeId)>,
         default: MirNodeId,
     },
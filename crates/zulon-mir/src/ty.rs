@@ -63,6 +63,10 @@ pub enum MirTy {
 
     // Optional
     Optional(Box<MirTy>),
+
+    // Trait object, `dyn Trait`; only used behind a reference, which is a
+    // pair of a data pointer and a vtable pointer
+    TraitObject(String),
 }
 
 impl MirTy {
@@ -89,8 +93,8 @@ impl MirTy {
             // Structs and functions are not copy by default
             MirTy::Struct { .. } | MirTy::Enum { .. } | MirTy::Function { .. } => false,
 
-            // Slices are not copy (dynamically sized)
-            MirTy::Slice(_) => false,
+            // Slices and trait objects are not copy (dynamically sized)
+            MirTy::Slice(_) | MirTy::TraitObject(_) => false,
 
             // String is not copy (owned data)
             MirTy::String => false,
@@ -117,6 +121,7 @@ impl MirTy {
             MirTy::Slice(_) => true,  // Fat pointer needs drop
             MirTy::Struct { .. } | MirTy::Enum { .. } => true, // Assume needs drop
             MirTy::Function { .. } => false, // Function pointers don't need drop
+            MirTy::TraitObject(_) => false,  // Only borrowed
         }
     }
 
@@ -134,6 +139,7 @@ impl MirTy {
             MirTy::String => 24,  // Boxed str + metadata
             MirTy::Unit => 0,
             MirTy::Never => 0,
+            MirTy::Ref { inner, .. } | MirTy::Ptr { inner, .. } if matches!(**inner, MirTy::TraitObject(_)) => 16,  // Fat pointer
             MirTy::Ref { .. } | MirTy::Ptr { .. } => 8,
             MirTy::Array { inner, len } => inner.size() * (*len as usize),
             MirTy::Slice(_) => 16,  // Fat pointer
@@ -142,6 +148,7 @@ impl MirTy {
             MirTy::Struct { .. } => 8,  // Placeholder
             MirTy::Enum { .. } => 8,  // Placeholder
             MirTy::Optional(inner) => inner.size() + 1,  // Size + discriminant
+            MirTy::TraitObject(_) => 0,  // Unsized
         }
     }
 
@@ -208,6 +215,7 @@ impl MirTy {
                 }
             }
             MirTy::Optional(inner) => format!("Option<{}>", inner.display_name()),
+            MirTy::TraitObject(trait_name) => format!("dyn {}", trait_name),
             _ => format!("{:?}", self),
         }
     }
@@ -297,7 +305,17 @@ impl From<zulon_hir::HirTy> for MirTy {
                 MirTy::Optional(Box::new((*inner).into()))
             }
 
-            zulon_hir::HirTy::TraitObject(_) | zulon_hir::HirTy::ImplTrait(_) => {
+            // Generic parameters are substituted by monomorphization
+            zulon_hir::HirTy::Param(name) => {
+                panic!("Generic parameter '{}' not substituted before MIR lowering", name)
+            }
+            zulon_hir::HirTy::Projection { base, name, .. } => {
+                panic!("Associated type '{}' of '{}' not normalized before MIR lowering", name, base)
+            }
+
+            zulon_hir::HirTy::TraitObject(traits) => MirTy::TraitObject(traits.join(" + ")),
+
+            zulon_hir::HirTy::ImplTrait(_) => {
                 // Simplified: treat as opaque
                 MirTy::Struct { name: "TraitObject".to_string(), generics: Vec::new() }
             }
@@ -49,6 +49,9 @@ fn test_panic() {
                                         zulon_parser::ast::AttributeArg::String(s) => {
                                             print!("\"{}\"", s)
                                         }
+                                        zulon_parser::ast::AttributeArg::List(name, args) => {
+                                            print!("{}(..{} args)", name.name, args.len())
+                                        }
                                     }
                                 }
                                 print!(")");
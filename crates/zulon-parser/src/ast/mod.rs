@@ -5,12 +5,22 @@
 //!
 //! The AST represents the syntactic structure of ZULON code after parsing.
 
+pub mod visit;
+pub mod visit_mut;
+
+pub use visit::Visitor;
+pub use visit_mut::MutVisitor;
+
+use crate::format::FormatSpec;
+
 /// A span in source code
 pub type Span = crate::lexer::Span;
 
 /// The root of a ZULON program (compilation unit)
 #[derive(Debug, Clone)]
 pub struct Ast {
+    /// Inner attributes of the compilation unit (e.g., `//!` docs)
+    pub attributes: Vec<Attribute>,
     /// Items in the compilation unit
     pub items: Vec<Item>,
     /// Source file path (if available)
@@ -21,6 +31,7 @@ impl Ast {
     /// Create a new AST
     pub fn new(items: Vec<Item>) -> Self {
         Ast {
+            attributes: Vec::new(),
             items,
             source_file: None,
         }
@@ -29,6 +40,7 @@ impl Ast {
     /// Create a new AST with source file
     pub fn with_source(items: Vec<Item>, source_file: String) -> Self {
         Ast {
+            attributes: Vec::new(),
             items,
             source_file: Some(source_file),
         }
@@ -39,6 +51,10 @@ impl Ast {
 #[derive(Debug, Clone)]
 pub struct Item {
     pub span: Span,
+    /// Outer attributes, including `///` docs as `#[doc]`
+    pub attributes: Vec<Attribute>,
+    /// `pub`, `pub(crate)` and so on; [`Visibility::Private`] if not given
+    pub visibility: Visibility,
     pub kind: ItemKind,
 }
 
@@ -71,6 +87,10 @@ pub enum ItemKind {
     ExternCrate(ExternCrate),
     /// Effect declaration: `effect Name { operations }`
     Effect(Effect),
+    /// Declarative macro: `macro name { (pattern) => { template } }`
+    MacroDef(MacroDef),
+    /// Placeholder for an item that failed to parse (error recovery)
+    Error,
 }
 
 /// Function definition
@@ -86,6 +106,7 @@ pub struct Function {
     pub body: Block,
     pub is_async: bool,
     pub is_unsafe: bool,
+    pub is_const: bool,              // `const fn`, callable in constant expressions
     pub attributes: Vec<Attribute>,  // Function attributes (e.g., #[test])
 }
 
@@ -96,6 +117,42 @@ pub struct Attribute {
     pub args: Vec<AttributeArg>,
 }
 
+impl Attribute {
+    /// Create a `#[doc = "text"]` attribute, as produced by doc comments
+    pub fn doc(span: Span, text: impl Into<String>) -> Self {
+        Attribute {
+            name: Identifier::new(span, "doc"),
+            args: vec![AttributeArg::String(text.into())],
+        }
+    }
+
+    /// The text of a `#[doc]` attribute
+    pub fn doc_text(&self) -> Option<&str> {
+        match (self.name.name.as_str(), self.args.as_slice()) {
+            ("doc", [AttributeArg::String(text)]) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Collect the documentation in a list of attributes
+///
+/// Doc lines are joined with newlines, and the single space that usually
+/// follows `///` is removed. Returns `None` if there are no doc attributes.
+pub fn doc_string(attributes: &[Attribute]) -> Option<String> {
+    let lines: Vec<&str> = attributes
+        .iter()
+        .filter_map(Attribute::doc_text)
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
 /// Attribute argument
 #[derive(Debug, Clone)]
 pub enum AttributeArg {
@@ -105,6 +162,8 @@ pub enum AttributeArg {
     KeyValue { key: Identifier, value: String },
     /// String literal: #[attribute("value")]
     String(String),
+    /// Nested list: #[cfg(not(test))]
+    List(Identifier, Vec<AttributeArg>),
 }
 
 /// Function parameter
@@ -128,6 +187,7 @@ pub struct Struct {
 #[derive(Debug, Clone)]
 pub struct StructField {
     pub span: Span,
+    pub attributes: Vec<Attribute>,
     pub name: Identifier,
     pub type_annotation: Type,
     pub default_value: Option<Box<Expression>>, // Box to break recursion
@@ -145,8 +205,11 @@ pub struct Enum {
 #[derive(Debug, Clone)]
 pub struct EnumVariant {
     pub span: Span,
+    pub attributes: Vec<Attribute>,
     pub name: Identifier,
     pub fields: Vec<VariantField>,
+    /// Explicit discriminant: `Variant = expr`
+    pub discriminant: Option<Expression>,
 }
 
 /// Variant field (either named or tuple-style)
@@ -169,9 +232,22 @@ pub struct Trait {
 
 /// Trait item (method or associated type)
 #[derive(Debug, Clone)]
-pub enum TraitItem {
-    /// Method signature (optional body provided in impl)
+pub struct TraitItem {
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+    pub kind: TraitItemKind,
+}
+
+/// Kinds of trait items
+#[derive(Debug, Clone)]
+pub enum TraitItemKind {
+    /// Method with a default body, which impls may override
     Method(Box<Function>),
+    /// Method signature, `fn name(...) -> T;`, which every impl must
+    /// provide
+    ///
+    /// The function's body is empty.
+    RequiredMethod(Box<Function>),
     /// Associated type: `type Name;`
     AssociatedType(Identifier, Vec<Type>),
     /// Constant: `const NAME: Type = value;`
@@ -185,6 +261,8 @@ pub struct Impl {
     pub generics: Option<Generics>,
     pub trait_name: Option<Type>,
     pub self_type: Type,
+    /// Associated type definitions: type Item = i32;
+    pub assoc_types: Vec<(Identifier, Type)>,
     pub items: Vec<Box<Function>>,
 }
 
@@ -218,6 +296,8 @@ pub struct Static {
 #[derive(Debug, Clone)]
 pub struct Module {
     pub name: Identifier,
+    /// Inner attributes of an inline module (e.g., `//!` docs)
+    pub attributes: Vec<Attribute>,
     pub items: Option<Vec<Box<Item>>>, // None for `mod name;`
 }
 
@@ -247,6 +327,27 @@ pub struct ExternCrate {
     pub rename: Option<Identifier>,
 }
 
+/// Declarative macro definition: `macro name { (pattern) => { template } ... }`
+///
+/// Rules are kept as tokens; they are interpreted by `zulon-macros`, which
+/// expands invocations before parsing.
+#[derive(Debug, Clone)]
+pub struct MacroDef {
+    pub name: Identifier,
+    pub is_pub: bool,
+    pub rules: Vec<MacroRuleDef>,
+}
+
+/// One `(pattern) => { template }` rule of a macro
+#[derive(Debug, Clone)]
+pub struct MacroRuleDef {
+    pub span: Span,
+    /// Tokens between the pattern's delimiters
+    pub pattern: Vec<crate::lexer::Token>,
+    /// Tokens between the template's delimiters
+    pub template: Vec<crate::lexer::Token>,
+}
+
 /// Effect declaration: `effect Name { operations }`
 #[derive(Debug, Clone)]
 pub struct Effect {
@@ -258,6 +359,7 @@ pub struct Effect {
 /// Effect operation
 #[derive(Debug, Clone)]
 pub struct EffectOperation {
+    pub attributes: Vec<Attribute>,
     pub name: Identifier,
     pub params: Vec<Param>,
     pub return_type: Option<Type>,
@@ -311,6 +413,8 @@ pub struct Statement {
 pub enum StatementKind {
     /// Local variable: `let x: Type = value;`
     Local(Local),
+    /// Refutable binding: `let PAT = value else { diverge };`
+    LetElse(LetElse),
     /// Item declaration (function, struct, etc.)
     Item(Box<Item>),
     /// Expression statement: `expr;`
@@ -319,6 +423,8 @@ pub enum StatementKind {
     Defer(Box<Statement>),
     /// Semi-colon (empty statement)
     Empty,
+    /// Placeholder for a statement that failed to parse (error recovery)
+    Error,
 }
 
 /// Local variable declaration
@@ -330,6 +436,20 @@ pub struct Local {
     pub is_mutable: bool,
 }
 
+/// `let PAT: Type = value else { diverge };`
+///
+/// The bindings of `pattern` are in scope for the rest of the block; the
+/// `else` block runs when the pattern does not match and must not complete
+/// normally.
+#[derive(Debug, Clone)]
+pub struct LetElse {
+    pub pattern: Pattern,
+    pub type_annotation: Option<Type>,
+    pub init: Box<Expression>,
+    pub else_block: Block,
+    pub is_mutable: bool,
+}
+
 /// Expressions
 #[derive(Debug, Clone)]
 pub struct Expression {
@@ -380,6 +500,8 @@ pub enum ExpressionKind {
     Struct(StructLiteral),
 
     /// If expression: `if cond { then } else { else }`
+    ///
+    /// `cond` may be a [`ExpressionKind::Let`] for `if let`.
     If(Box<Expression>, Block, Option<Block>),
 
     /// Match expression: `match value { patterns }`
@@ -389,8 +511,16 @@ pub enum ExpressionKind {
     Loop(Block, Option<Identifier>),
 
     /// While loop: `while cond { body }`
+    ///
+    /// `cond` may be a [`ExpressionKind::Let`] for `while let`.
     While(Box<Expression>, Block, Option<Identifier>),
 
+    /// Pattern test in an `if`/`while` condition: `let PAT = value`
+    ///
+    /// Evaluates to whether `value` matches `PAT`; the bindings are in scope
+    /// in the guarded block.
+    Let(Box<Pattern>, Box<Expression>),
+
     /// For loop: `for item in iter { body }`
     For(Local, Box<Expression>, Block, Option<Identifier>),
 
@@ -458,6 +588,9 @@ pub enum ExpressionKind {
 
     /// Await expression: `future.await`
     Await(Box<Expression>),
+
+    /// Placeholder for an expression that failed to parse (error recovery)
+    Error,
 }
 
 /// Binary operators
@@ -509,20 +642,133 @@ pub enum MacroDelimiter {
 /// Literal values
 #[derive(Debug, Clone)]
 pub enum Literal {
-    /// Integer: `42`
-    Int(i64),
-    /// Float: `3.14`
-    Float(f64),
-    /// String: `"hello"`
+    /// Integer: `42`, `0xFF_u8`, `1_000i64`
+    Int(u128, Option<IntSuffix>),
+    /// Float: `3.14`, `1e-3`, `2.5f32`
+    Float(f64, Option<FloatSuffix>),
+    /// String: `"hello"`, `r#"raw"#` or an indented `"""` block
     String(String),
     /// Character: `'a'`
     Char(char),
+    /// Byte string: `b"GET"`, `br"\d+"`
+    ByteString(Vec<u8>),
+    /// Byte: `b'x'`
+    Byte(u8),
     /// Boolean: `true`, `false`
     Bool(bool),
     /// Null: `null`
     Null,
 }
 
+/// Type suffix of an integer literal: `42u8`, `7i64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntSuffix {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    ISize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    USize,
+}
+
+impl IntSuffix {
+    /// Parse a suffix name such as `"u8"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "i8" => IntSuffix::I8,
+            "i16" => IntSuffix::I16,
+            "i32" => IntSuffix::I32,
+            "i64" => IntSuffix::I64,
+            "i128" => IntSuffix::I128,
+            "isize" => IntSuffix::ISize,
+            "u8" => IntSuffix::U8,
+            "u16" => IntSuffix::U16,
+            "u32" => IntSuffix::U32,
+            "u64" => IntSuffix::U64,
+            "u128" => IntSuffix::U128,
+            "usize" => IntSuffix::USize,
+            _ => return None,
+        })
+    }
+
+    /// The suffix as written in source
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntSuffix::I8 => "i8",
+            IntSuffix::I16 => "i16",
+            IntSuffix::I32 => "i32",
+            IntSuffix::I64 => "i64",
+            IntSuffix::I128 => "i128",
+            IntSuffix::ISize => "isize",
+            IntSuffix::U8 => "u8",
+            IntSuffix::U16 => "u16",
+            IntSuffix::U32 => "u32",
+            IntSuffix::U64 => "u64",
+            IntSuffix::U128 => "u128",
+            IntSuffix::USize => "usize",
+        }
+    }
+
+    /// Whether the suffix names a signed type
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            IntSuffix::I8 | IntSuffix::I16 | IntSuffix::I32 | IntSuffix::I64 | IntSuffix::I128 | IntSuffix::ISize
+        )
+    }
+
+    /// Largest literal magnitude allowed, taking a leading `-` into account
+    ///
+    /// `isize`/`usize` are treated as 64-bit.
+    pub fn max_magnitude(&self, negated: bool) -> u128 {
+        let bits = match self {
+            IntSuffix::I8 | IntSuffix::U8 => 8,
+            IntSuffix::I16 | IntSuffix::U16 => 16,
+            IntSuffix::I32 | IntSuffix::U32 => 32,
+            IntSuffix::I64 | IntSuffix::U64 | IntSuffix::ISize | IntSuffix::USize => 64,
+            IntSuffix::I128 | IntSuffix::U128 => 128,
+        };
+        match (self.is_signed(), negated) {
+            (true, true) => 1u128 << (bits - 1),
+            (true, false) => (1u128 << (bits - 1)) - 1,
+            (false, true) => 0,
+            (false, false) => u128::MAX >> (128 - bits),
+        }
+    }
+}
+
+/// Type suffix of a float literal: `1.5f32`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatSuffix {
+    F32,
+    F64,
+}
+
+impl FloatSuffix {
+    /// Parse a suffix name such as `"f32"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "f32" => Some(FloatSuffix::F32),
+            "f64" => Some(FloatSuffix::F64),
+            _ => None,
+        }
+    }
+
+    /// The suffix as written in source
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FloatSuffix::F32 => "f32",
+            FloatSuffix::F64 => "f64",
+        }
+    }
+}
+
 /// Block of statements
 #[derive(Debug, Clone)]
 pub struct Block {
@@ -581,6 +827,34 @@ pub enum Pattern {
     Or(Vec<Pattern>),
 }
 
+impl Pattern {
+    /// The names bound by this pattern, in source order
+    pub fn bindings(&self) -> Vec<&Identifier> {
+        struct Bindings<'ast>(Vec<&'ast Identifier>);
+
+        impl<'ast> Visitor<'ast> for Bindings<'ast> {
+            fn visit_pattern(&mut self, pattern: &'ast Pattern) {
+                match pattern {
+                    Pattern::Identifier(ident) => self.0.push(ident),
+                    Pattern::Struct(_, fields) => {
+                        for field in fields {
+                            if let StructPatternField::Shorthand(name) = field {
+                                self.0.push(name);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                visit::walk_pattern(self, pattern);
+            }
+        }
+
+        let mut bindings = Bindings(Vec::new());
+        bindings.visit_pattern(self);
+        bindings.0
+    }
+}
+
 /// Struct pattern field
 #[derive(Debug, Clone)]
 pub enum StructPatternField {
@@ -625,8 +899,8 @@ pub struct TemplateString {
 pub enum TemplateStringPart {
     /// Static string
     Static(String),
-    /// Interpolated expression
-    Expr(Expression),
+    /// Interpolated expression, with the spec after its `:`
+    Expr(Box<Expression>, FormatSpec),
 }
 
 /// Types
@@ -0,0 +1,822 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Read-only AST traversal
+//!
+//! [`Visitor`] has one `visit_*` method per kind of node. Every method
+//! defaults to the matching `walk_*` function, which visits the children of
+//! the node. An analysis overrides only the methods it cares about and calls
+//! the `walk_*` function to keep descending:
+//!
+//! ```rust
+//! use zulon_parser::ast::visit::{self, Visitor};
+//! use zulon_parser::ast::{Expression, ExpressionKind};
+//! use zulon_parser::Parser;
+//!
+//! #[derive(Default)]
+//! struct CallCounter {
+//!     calls: usize,
+//! }
+//!
+//! impl<'ast> Visitor<'ast> for CallCounter {
+//!     fn visit_expression(&mut self, expr: &'ast Expression) {
+//!         if let ExpressionKind::Call(..) = expr.kind {
+//!             self.calls += 1;
+//!         }
+//!         visit::walk_expression(self, expr);
+//!     }
+//! }
+//!
+//! let ast = Parser::from_source("fn main() { f(g(1)); }").parse().unwrap();
+//! let mut counter = CallCounter::default();
+//! counter.visit_ast(&ast);
+//! assert_eq!(counter.calls, 2);
+//! ```
+//!
+//! The `walk_*` functions match exhaustively, so new AST variants are handled
+//! here once instead of in every pass.
+
+use super::*;
+
+/// A read-only AST visitor
+///
+/// The `'ast` lifetime lets implementations keep references into the tree.
+pub trait Visitor<'ast> {
+    fn visit_ast(&mut self, ast: &'ast Ast) {
+        walk_ast(self, ast);
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        walk_item(self, item);
+    }
+
+    fn visit_attribute(&mut self, attribute: &'ast Attribute) {
+        walk_attribute(self, attribute);
+    }
+
+    fn visit_function(&mut self, func: &'ast Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_param(&mut self, param: &'ast Param) {
+        walk_param(self, param);
+    }
+
+    fn visit_generics(&mut self, generics: &'ast Generics) {
+        walk_generics(self, generics);
+    }
+
+    fn visit_struct(&mut self, struct_def: &'ast Struct) {
+        walk_struct(self, struct_def);
+    }
+
+    fn visit_struct_field(&mut self, field: &'ast StructField) {
+        walk_struct_field(self, field);
+    }
+
+    fn visit_enum(&mut self, enum_def: &'ast Enum) {
+        walk_enum(self, enum_def);
+    }
+
+    fn visit_enum_variant(&mut self, variant: &'ast EnumVariant) {
+        walk_enum_variant(self, variant);
+    }
+
+    fn visit_trait(&mut self, trait_def: &'ast Trait) {
+        walk_trait(self, trait_def);
+    }
+
+    fn visit_trait_item(&mut self, item: &'ast TraitItem) {
+        walk_trait_item(self, item);
+    }
+
+    fn visit_impl(&mut self, impl_def: &'ast Impl) {
+        walk_impl(self, impl_def);
+    }
+
+    fn visit_type_alias(&mut self, alias: &'ast TypeAlias) {
+        walk_type_alias(self, alias);
+    }
+
+    fn visit_const(&mut self, const_def: &'ast Const) {
+        walk_const(self, const_def);
+    }
+
+    fn visit_static(&mut self, static_def: &'ast Static) {
+        walk_static(self, static_def);
+    }
+
+    fn visit_module(&mut self, module: &'ast Module) {
+        walk_module(self, module);
+    }
+
+    fn visit_use(&mut self, use_item: &'ast Use) {
+        walk_use(self, use_item);
+    }
+
+    fn visit_extern_crate(&mut self, extern_crate: &'ast ExternCrate) {
+        walk_extern_crate(self, extern_crate);
+    }
+
+    fn visit_effect(&mut self, effect: &'ast Effect) {
+        walk_effect(self, effect);
+    }
+
+    fn visit_macro_def(&mut self, macro_def: &'ast MacroDef) {
+        walk_macro_def(self, macro_def);
+    }
+
+    fn visit_effect_operation(&mut self, operation: &'ast EffectOperation) {
+        walk_effect_operation(self, operation);
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, stmt: &'ast Statement) {
+        walk_statement(self, stmt);
+    }
+
+    fn visit_local(&mut self, local: &'ast Local) {
+        walk_local(self, local);
+    }
+
+    fn visit_expression(&mut self, expr: &'ast Expression) {
+        walk_expression(self, expr);
+    }
+
+    fn visit_match_arm(&mut self, arm: &'ast MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
+    fn visit_effect_handler(&mut self, handler: &'ast EffectHandler) {
+        walk_effect_handler(self, handler);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'ast Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_type(&mut self, ty: &'ast Type) {
+        walk_type(self, ty);
+    }
+
+    fn visit_path(&mut self, path: &'ast [Identifier]) {
+        walk_path(self, path);
+    }
+
+    fn visit_literal(&mut self, _literal: &'ast Literal) {}
+
+    fn visit_identifier(&mut self, _ident: &'ast Identifier) {}
+}
+
+pub fn walk_ast<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, ast: &'ast Ast) {
+    for attribute in &ast.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    for item in &ast.items {
+        visitor.visit_item(item);
+    }
+}
+
+pub fn walk_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast Item) {
+    for attribute in &item.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    match &item.kind {
+        ItemKind::Function(func) | ItemKind::ExternFunction(func) => visitor.visit_function(func),
+        ItemKind::Struct(struct_def) => visitor.visit_struct(struct_def),
+        ItemKind::Enum(enum_def) => visitor.visit_enum(enum_def),
+        ItemKind::Trait(trait_def) => visitor.visit_trait(trait_def),
+        ItemKind::Impl(impl_def) => visitor.visit_impl(impl_def),
+        ItemKind::TypeAlias(alias) => visitor.visit_type_alias(alias),
+        ItemKind::Const(const_def) => visitor.visit_const(const_def),
+        ItemKind::Static(static_def) => visitor.visit_static(static_def),
+        ItemKind::Module(module) => visitor.visit_module(module),
+        ItemKind::Use(use_item) => visitor.visit_use(use_item),
+        ItemKind::ExternCrate(extern_crate) => visitor.visit_extern_crate(extern_crate),
+        ItemKind::Effect(effect) => visitor.visit_effect(effect),
+        ItemKind::MacroDef(macro_def) => visitor.visit_macro_def(macro_def),
+        ItemKind::Error => {}
+    }
+}
+
+pub fn walk_attribute<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, attribute: &'ast Attribute) {
+    visitor.visit_identifier(&attribute.name);
+    for arg in &attribute.args {
+        walk_attribute_arg(visitor, arg);
+    }
+}
+
+fn walk_attribute_arg<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, arg: &'ast AttributeArg) {
+    match arg {
+        AttributeArg::Ident(ident) => visitor.visit_identifier(ident),
+        AttributeArg::KeyValue { key, .. } => visitor.visit_identifier(key),
+        AttributeArg::String(_) => {}
+        AttributeArg::List(name, args) => {
+            visitor.visit_identifier(name);
+            for arg in args {
+                walk_attribute_arg(visitor, arg);
+            }
+        }
+    }
+}
+
+/// Walk a function
+///
+/// The function's own `attributes` are not visited here: for items they are
+/// the same attributes as [`Item::attributes`], which [`walk_item`] visits.
+pub fn walk_function<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, func: &'ast Function) {
+    visitor.visit_identifier(&func.name);
+    if let Some(generics) = &func.generics {
+        visitor.visit_generics(generics);
+    }
+    for param in &func.params {
+        visitor.visit_param(param);
+    }
+    for ty in func.return_type.iter().chain(&func.error_type).chain(&func.effects) {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_block(&func.body);
+}
+
+pub fn walk_param<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, param: &'ast Param) {
+    visitor.visit_identifier(&param.name);
+    if let Some(ty) = &param.type_annotation {
+        visitor.visit_type(ty);
+    }
+    if let Some(default_value) = &param.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_generics<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, generics: &'ast Generics) {
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(ident) | GenericParam::Lifetime(ident) => visitor.visit_identifier(ident),
+            GenericParam::Const(ident, ty) => {
+                visitor.visit_identifier(ident);
+                visitor.visit_type(ty);
+            }
+        }
+    }
+    for clause in &generics.where_clause {
+        visitor.visit_identifier(&clause.type_param);
+        for bound in &clause.bounds {
+            match bound {
+                TraitBound::Trait(ty) => visitor.visit_type(ty),
+                TraitBound::Lifetime(ident) => visitor.visit_identifier(ident),
+            }
+        }
+    }
+}
+
+pub fn walk_struct<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, struct_def: &'ast Struct) {
+    visitor.visit_identifier(&struct_def.name);
+    if let Some(generics) = &struct_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for field in &struct_def.fields {
+        visitor.visit_struct_field(field);
+    }
+}
+
+pub fn walk_struct_field<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, field: &'ast StructField) {
+    for attribute in &field.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&field.name);
+    visitor.visit_type(&field.type_annotation);
+    if let Some(default_value) = &field.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_enum<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, enum_def: &'ast Enum) {
+    visitor.visit_identifier(&enum_def.name);
+    if let Some(generics) = &enum_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for variant in &enum_def.variants {
+        visitor.visit_enum_variant(variant);
+    }
+}
+
+pub fn walk_enum_variant<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, variant: &'ast EnumVariant) {
+    for attribute in &variant.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&variant.name);
+    for field in &variant.fields {
+        match field {
+            VariantField::Named(name, ty) => {
+                visitor.visit_identifier(name);
+                visitor.visit_type(ty);
+            }
+            VariantField::Unnamed(ty) => visitor.visit_type(ty),
+        }
+    }
+    if let Some(discriminant) = &variant.discriminant {
+        visitor.visit_expression(discriminant);
+    }
+}
+
+pub fn walk_trait<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, trait_def: &'ast Trait) {
+    visitor.visit_identifier(&trait_def.name);
+    if let Some(generics) = &trait_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for super_trait in &trait_def.super_traits {
+        visitor.visit_type(super_trait);
+    }
+    for item in &trait_def.items {
+        visitor.visit_trait_item(item);
+    }
+}
+
+pub fn walk_trait_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast TraitItem) {
+    for attribute in &item.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    match &item.kind {
+        TraitItemKind::Method(func) | TraitItemKind::RequiredMethod(func) => visitor.visit_function(func),
+        TraitItemKind::AssociatedType(name, bounds) => {
+            visitor.visit_identifier(name);
+            for bound in bounds {
+                visitor.visit_type(bound);
+            }
+        }
+        TraitItemKind::Const(const_def) => visitor.visit_const(const_def),
+    }
+}
+
+/// Walk an impl block
+///
+/// Methods carry their attributes in [`Function::attributes`], so they are
+/// visited here (unlike for top-level functions).
+pub fn walk_impl<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, impl_def: &'ast Impl) {
+    if let Some(generics) = &impl_def.generics {
+        visitor.visit_generics(generics);
+    }
+    if let Some(trait_name) = &impl_def.trait_name {
+        visitor.visit_type(trait_name);
+    }
+    visitor.visit_type(&impl_def.self_type);
+    for (_, ty) in &impl_def.assoc_types {
+        visitor.visit_type(ty);
+    }
+    for method in &impl_def.items {
+        for attribute in &method.attributes {
+            visitor.visit_attribute(attribute);
+        }
+        visitor.visit_function(method);
+    }
+}
+
+pub fn walk_type_alias<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, alias: &'ast TypeAlias) {
+    visitor.visit_identifier(&alias.name);
+    if let Some(generics) = &alias.generics {
+        visitor.visit_generics(generics);
+    }
+    visitor.visit_type(&alias.type_annotation);
+}
+
+pub fn walk_const<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, const_def: &'ast Const) {
+    visitor.visit_identifier(&const_def.name);
+    visitor.visit_type(&const_def.type_annotation);
+    visitor.visit_expression(&const_def.value);
+}
+
+pub fn walk_static<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, static_def: &'ast Static) {
+    visitor.visit_identifier(&static_def.name);
+    visitor.visit_type(&static_def.type_annotation);
+    visitor.visit_expression(&static_def.value);
+}
+
+pub fn walk_module<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, module: &'ast Module) {
+    visitor.visit_identifier(&module.name);
+    for attribute in &module.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    for item in module.items.iter().flatten() {
+        visitor.visit_item(item);
+    }
+}
+
+pub fn walk_use<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, use_item: &'ast Use) {
+    match &use_item.path {
+        UsePath::Simple(path) | UsePath::Glob(path) => visitor.visit_path(path),
+        UsePath::List(path, names) => {
+            visitor.visit_path(path);
+            for name in names {
+                visitor.visit_identifier(name);
+            }
+        }
+    }
+    if let Some(alias) = &use_item.alias {
+        visitor.visit_identifier(alias);
+    }
+}
+
+pub fn walk_extern_crate<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, extern_crate: &'ast ExternCrate) {
+    visitor.visit_identifier(&extern_crate.name);
+    if let Some(rename) = &extern_crate.rename {
+        visitor.visit_identifier(rename);
+    }
+}
+
+pub fn walk_effect<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, effect: &'ast Effect) {
+    visitor.visit_identifier(&effect.name);
+    if let Some(generics) = &effect.generics {
+        visitor.visit_generics(generics);
+    }
+    for operation in &effect.operations {
+        visitor.visit_effect_operation(operation);
+    }
+}
+
+pub fn walk_macro_def<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, macro_def: &'ast MacroDef) {
+    visitor.visit_identifier(&macro_def.name);
+}
+
+pub fn walk_effect_operation<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    operation: &'ast EffectOperation,
+) {
+    for attribute in &operation.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&operation.name);
+    for param in &operation.params {
+        visitor.visit_param(param);
+    }
+    if let Some(ty) = &operation.return_type {
+        visitor.visit_type(ty);
+    }
+}
+
+pub fn walk_block<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, block: &'ast Block) {
+    for stmt in &block.statements {
+        visitor.visit_statement(stmt);
+    }
+    if let Some(expr) = &block.trailing_expr {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, stmt: &'ast Statement) {
+    match &stmt.kind {
+        StatementKind::Local(local) => visitor.visit_local(local),
+        StatementKind::LetElse(let_else) => {
+            visitor.visit_pattern(&let_else.pattern);
+            if let Some(ty) = &let_else.type_annotation {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expression(&let_else.init);
+            visitor.visit_block(&let_else.else_block);
+        }
+        StatementKind::Item(item) => visitor.visit_item(item),
+        StatementKind::Expr(expr) => visitor.visit_expression(expr),
+        StatementKind::Defer(stmt) => visitor.visit_statement(stmt),
+        StatementKind::Empty | StatementKind::Error => {}
+    }
+}
+
+pub fn walk_local<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, local: &'ast Local) {
+    visitor.visit_identifier(&local.name);
+    if let Some(ty) = &local.type_annotation {
+        visitor.visit_type(ty);
+    }
+    if let Some(init) = &local.init {
+        visitor.visit_expression(init);
+    }
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expression) {
+    match &expr.kind {
+        ExpressionKind::Literal(literal) => visitor.visit_literal(literal),
+        ExpressionKind::Path(path) => visitor.visit_path(path),
+        ExpressionKind::Block(block) => visitor.visit_block(block),
+        ExpressionKind::Binary(_, left, right)
+        | ExpressionKind::Index(left, right)
+        | ExpressionKind::Assign(left, right)
+        | ExpressionKind::AssignOp(_, left, right)
+        | ExpressionKind::Range(left, _, right) => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        ExpressionKind::Unary(_, operand)
+        | ExpressionKind::TupleIndex(operand, _)
+        | ExpressionKind::Throw(operand)
+        | ExpressionKind::QuestionMark(operand)
+        | ExpressionKind::Grouped(operand)
+        | ExpressionKind::Await(operand) => visitor.visit_expression(operand),
+        ExpressionKind::Call(callee, args) => {
+            visitor.visit_expression(callee);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::MethodCall(receiver, method, args) => {
+            visitor.visit_expression(receiver);
+            visitor.visit_identifier(method);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::FieldAccess(object, field) => {
+            visitor.visit_expression(object);
+            visitor.visit_identifier(field);
+        }
+        ExpressionKind::Array(elements) | ExpressionKind::Tuple(elements) => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        ExpressionKind::Struct(literal) => {
+            visitor.visit_path(&literal.path);
+            for field in &literal.fields {
+                visitor.visit_identifier(&field.name);
+                visitor.visit_expression(&field.value);
+            }
+            if let Some(base) = &literal.base {
+                visitor.visit_expression(base);
+            }
+        }
+        ExpressionKind::If(condition, then_block, else_block) => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(then_block);
+            if let Some(else_block) = else_block {
+                visitor.visit_block(else_block);
+            }
+        }
+        ExpressionKind::Match(scrutinee, arms) => {
+            visitor.visit_expression(scrutinee);
+            for arm in arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        ExpressionKind::Loop(body, label) => {
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::While(condition, body, label) => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::For(binding, iter, body, label) => {
+            visitor.visit_local(binding);
+            visitor.visit_expression(iter);
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::Break(label) | ExpressionKind::Continue(label) => {
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        ExpressionKind::Closure { params, return_type, body } => {
+            for param in params {
+                visitor.visit_local(param);
+            }
+            if let Some(ty) = return_type {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expression(body);
+        }
+        ExpressionKind::Let(pattern, value) => {
+            visitor.visit_pattern(pattern);
+            visitor.visit_expression(value);
+        }
+        ExpressionKind::Defer(stmt) => visitor.visit_statement(stmt),
+        ExpressionKind::Try(block, handlers) => {
+            visitor.visit_block(block);
+            for handler in handlers {
+                visitor.visit_effect_handler(handler);
+            }
+        }
+        ExpressionKind::Perform(operation, args) => {
+            visitor.visit_identifier(operation);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::Cast(value, ty) => {
+            visitor.visit_expression(value);
+            visitor.visit_type(ty);
+        }
+        ExpressionKind::TemplateString(template) => {
+            for part in &template.parts {
+                if let TemplateStringPart::Expr(expr, _) = part {
+                    visitor.visit_expression(expr);
+                }
+            }
+        }
+        ExpressionKind::MacroInvocation { macro_name, args, .. } => {
+            visitor.visit_identifier(macro_name);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::Error => {}
+    }
+}
+
+pub fn walk_match_arm<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, arm: &'ast MatchArm) {
+    for pattern in &arm.patterns {
+        visitor.visit_pattern(pattern);
+    }
+    if let Some(guard) = &arm.guard {
+        visitor.visit_expression(guard);
+    }
+    visitor.visit_expression(&arm.body);
+}
+
+pub fn walk_effect_handler<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, handler: &'ast EffectHandler) {
+    visitor.visit_identifier(&handler.effect_name);
+    for method in &handler.methods {
+        visitor.visit_identifier(&method.name);
+        for param in &method.params {
+            visitor.visit_param(param);
+        }
+        visitor.visit_block(&method.body);
+    }
+}
+
+pub fn walk_pattern<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, pattern: &'ast Pattern) {
+    match pattern {
+        Pattern::Wildcard => {}
+        Pattern::Literal(literal) => visitor.visit_literal(literal),
+        Pattern::Identifier(ident) => visitor.visit_identifier(ident),
+        Pattern::Struct(path, fields) => {
+            visitor.visit_path(path);
+            for field in fields {
+                match field {
+                    StructPatternField::Field(name, pattern) => {
+                        visitor.visit_identifier(name);
+                        visitor.visit_pattern(pattern);
+                    }
+                    StructPatternField::Shorthand(name) => visitor.visit_identifier(name),
+                }
+            }
+        }
+        Pattern::TupleVariant(path, patterns) => {
+            visitor.visit_path(path);
+            for pattern in patterns {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Tuple(patterns) | Pattern::Array(patterns) | Pattern::Or(patterns) => {
+            for pattern in patterns {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Slice(before, middle, after) => {
+            for pattern in before.iter().chain(middle).chain(after) {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Range(start, _, end) => {
+            visitor.visit_pattern(start);
+            visitor.visit_pattern(end);
+        }
+    }
+}
+
+pub fn walk_type<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, ty: &'ast Type) {
+    match ty {
+        Type::Simple(ident) => visitor.visit_identifier(ident),
+        Type::Tuple(types) => {
+            for ty in types {
+                visitor.visit_type(ty);
+            }
+        }
+        Type::Array(element, len) => {
+            visitor.visit_type(element);
+            if let Some(len) = len {
+                visitor.visit_expression(len);
+            }
+        }
+        Type::Slice(inner)
+        | Type::Ref(inner, _)
+        | Type::Pointer(inner, _)
+        | Type::TraitObject(inner)
+        | Type::ImplTrait(inner)
+        | Type::Optional(inner) => visitor.visit_type(inner),
+        Type::Function(params, ret) => {
+            for param in params {
+                visitor.visit_type(param);
+            }
+            visitor.visit_type(ret);
+        }
+        Type::Pipe(ok, err) => {
+            visitor.visit_type(ok);
+            visitor.visit_type(err);
+        }
+        Type::Path(path) => visitor.visit_path(path),
+        Type::PathGeneric(path, args) => {
+            visitor.visit_path(path);
+            for arg in args.iter().flatten() {
+                visitor.visit_type(arg);
+            }
+        }
+        Type::Never | Type::Unit => {}
+    }
+}
+
+pub fn walk_path<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, path: &'ast [Identifier]) {
+    for segment in path {
+        visitor.visit_identifier(segment);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    #[derive(Default)]
+    struct Collector<'ast> {
+        identifiers: Vec<&'ast str>,
+        literals: usize,
+        patterns: usize,
+        types: usize,
+    }
+
+    impl<'ast> Visitor<'ast> for Collector<'ast> {
+        fn visit_identifier(&mut self, ident: &'ast Identifier) {
+            self.identifiers.push(&ident.name);
+        }
+
+        fn visit_literal(&mut self, _literal: &'ast Literal) {
+            self.literals += 1;
+        }
+
+        fn visit_pattern(&mut self, pattern: &'ast Pattern) {
+            self.patterns += 1;
+            walk_pattern(self, pattern);
+        }
+
+        fn visit_type(&mut self, ty: &'ast Type) {
+            self.types += 1;
+            walk_type(self, ty);
+        }
+    }
+
+    #[test]
+    fn test_visitor_reaches_nested_nodes() {
+        let source = r#"
+            struct Point { x: i32, y: i32 }
+
+            fn classify(n: i32) -> i32 {
+                let limit: i32 = 10;
+                match n {
+                    0 => 1,
+                    m if m > limit => helper(m),
+                    _ => { let p = n * 2; p }
+                }
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let mut collector = Collector::default();
+        collector.visit_ast(&ast);
+
+        for name in ["Point", "x", "y", "classify", "n", "limit", "m", "helper", "p"] {
+            assert!(collector.identifiers.contains(&name), "missing identifier {}", name);
+        }
+        assert_eq!(collector.literals, 4);
+        assert_eq!(collector.patterns, 3);
+        // x, y, param, return type and `limit` annotation
+        assert_eq!(collector.types, 5);
+    }
+
+    #[test]
+    fn test_overriding_without_walking_prunes_subtree() {
+        struct TopLevelOnly(usize);
+
+        impl<'ast> Visitor<'ast> for TopLevelOnly {
+            fn visit_expression(&mut self, _expr: &'ast Expression) {
+                self.0 += 1;
+            }
+        }
+
+        let ast = Parser::from_source("fn main() { f(1 + 2); g(3); }").parse().unwrap();
+        let mut visitor = TopLevelOnly(0);
+        visitor.visit_ast(&ast);
+        assert_eq!(visitor.0, 2);
+    }
+}
@@ -0,0 +1,787 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Mutable AST traversal
+//!
+//! [`MutVisitor`] mirrors [`Visitor`](super::visit::Visitor) but receives
+//! `&mut` references, so passes can rewrite the tree in place (desugaring,
+//! renaming, stripping nodes). Each `visit_*` method defaults to the matching
+//! `walk_*` function in this module:
+//!
+//! ```rust
+//! use zulon_parser::ast::visit_mut::{self, MutVisitor};
+//! use zulon_parser::ast::{Expression, ExpressionKind};
+//! use zulon_parser::Parser;
+//!
+//! /// Replace `(expr)` with `expr`
+//! struct Ungroup;
+//!
+//! impl MutVisitor for Ungroup {
+//!     fn visit_expression(&mut self, expr: &mut Expression) {
+//!         visit_mut::walk_expression(self, expr);
+//!         if let ExpressionKind::Grouped(inner) = &mut expr.kind {
+//!             *expr = std::mem::replace(&mut **inner, Expression {
+//!                 span: expr.span,
+//!                 kind: ExpressionKind::Error,
+//!             });
+//!         }
+//!     }
+//! }
+//!
+//! let mut ast = Parser::from_source("fn main() -> i32 { ((1)) }").parse().unwrap();
+//! Ungroup.visit_ast(&mut ast);
+//! ```
+
+use super::*;
+
+/// A mutating AST visitor
+pub trait MutVisitor {
+    fn visit_ast(&mut self, ast: &mut Ast) {
+        walk_ast(self, ast);
+    }
+
+    fn visit_item(&mut self, item: &mut Item) {
+        walk_item(self, item);
+    }
+
+    fn visit_attribute(&mut self, attribute: &mut Attribute) {
+        walk_attribute(self, attribute);
+    }
+
+    fn visit_function(&mut self, func: &mut Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_param(&mut self, param: &mut Param) {
+        walk_param(self, param);
+    }
+
+    fn visit_generics(&mut self, generics: &mut Generics) {
+        walk_generics(self, generics);
+    }
+
+    fn visit_struct(&mut self, struct_def: &mut Struct) {
+        walk_struct(self, struct_def);
+    }
+
+    fn visit_struct_field(&mut self, field: &mut StructField) {
+        walk_struct_field(self, field);
+    }
+
+    fn visit_enum(&mut self, enum_def: &mut Enum) {
+        walk_enum(self, enum_def);
+    }
+
+    fn visit_enum_variant(&mut self, variant: &mut EnumVariant) {
+        walk_enum_variant(self, variant);
+    }
+
+    fn visit_trait(&mut self, trait_def: &mut Trait) {
+        walk_trait(self, trait_def);
+    }
+
+    fn visit_trait_item(&mut self, item: &mut TraitItem) {
+        walk_trait_item(self, item);
+    }
+
+    fn visit_impl(&mut self, impl_def: &mut Impl) {
+        walk_impl(self, impl_def);
+    }
+
+    fn visit_type_alias(&mut self, alias: &mut TypeAlias) {
+        walk_type_alias(self, alias);
+    }
+
+    fn visit_const(&mut self, const_def: &mut Const) {
+        walk_const(self, const_def);
+    }
+
+    fn visit_static(&mut self, static_def: &mut Static) {
+        walk_static(self, static_def);
+    }
+
+    fn visit_module(&mut self, module: &mut Module) {
+        walk_module(self, module);
+    }
+
+    fn visit_use(&mut self, use_item: &mut Use) {
+        walk_use(self, use_item);
+    }
+
+    fn visit_extern_crate(&mut self, extern_crate: &mut ExternCrate) {
+        walk_extern_crate(self, extern_crate);
+    }
+
+    fn visit_effect(&mut self, effect: &mut Effect) {
+        walk_effect(self, effect);
+    }
+
+    fn visit_macro_def(&mut self, macro_def: &mut MacroDef) {
+        walk_macro_def(self, macro_def);
+    }
+
+    fn visit_effect_operation(&mut self, operation: &mut EffectOperation) {
+        walk_effect_operation(self, operation);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement) {
+        walk_statement(self, stmt);
+    }
+
+    fn visit_local(&mut self, local: &mut Local) {
+        walk_local(self, local);
+    }
+
+    fn visit_expression(&mut self, expr: &mut Expression) {
+        walk_expression(self, expr);
+    }
+
+    fn visit_match_arm(&mut self, arm: &mut MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
+    fn visit_effect_handler(&mut self, handler: &mut EffectHandler) {
+        walk_effect_handler(self, handler);
+    }
+
+    fn visit_pattern(&mut self, pattern: &mut Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_type(&mut self, ty: &mut Type) {
+        walk_type(self, ty);
+    }
+
+    fn visit_path(&mut self, path: &mut Vec<Identifier>) {
+        walk_path(self, path);
+    }
+
+    fn visit_literal(&mut self, _literal: &mut Literal) {}
+
+    fn visit_identifier(&mut self, _ident: &mut Identifier) {}
+}
+
+pub fn walk_ast<V: MutVisitor + ?Sized>(visitor: &mut V, ast: &mut Ast) {
+    for attribute in &mut ast.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    for item in &mut ast.items {
+        visitor.visit_item(item);
+    }
+}
+
+pub fn walk_item<V: MutVisitor + ?Sized>(visitor: &mut V, item: &mut Item) {
+    for attribute in &mut item.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    match &mut item.kind {
+        ItemKind::Function(func) | ItemKind::ExternFunction(func) => visitor.visit_function(func),
+        ItemKind::Struct(struct_def) => visitor.visit_struct(struct_def),
+        ItemKind::Enum(enum_def) => visitor.visit_enum(enum_def),
+        ItemKind::Trait(trait_def) => visitor.visit_trait(trait_def),
+        ItemKind::Impl(impl_def) => visitor.visit_impl(impl_def),
+        ItemKind::TypeAlias(alias) => visitor.visit_type_alias(alias),
+        ItemKind::Const(const_def) => visitor.visit_const(const_def),
+        ItemKind::Static(static_def) => visitor.visit_static(static_def),
+        ItemKind::Module(module) => visitor.visit_module(module),
+        ItemKind::Use(use_item) => visitor.visit_use(use_item),
+        ItemKind::ExternCrate(extern_crate) => visitor.visit_extern_crate(extern_crate),
+        ItemKind::Effect(effect) => visitor.visit_effect(effect),
+        ItemKind::MacroDef(macro_def) => visitor.visit_macro_def(macro_def),
+        ItemKind::Error => {}
+    }
+}
+
+pub fn walk_attribute<V: MutVisitor + ?Sized>(visitor: &mut V, attribute: &mut Attribute) {
+    visitor.visit_identifier(&mut attribute.name);
+    for arg in &mut attribute.args {
+        walk_attribute_arg(visitor, arg);
+    }
+}
+
+fn walk_attribute_arg<V: MutVisitor + ?Sized>(visitor: &mut V, arg: &mut AttributeArg) {
+    match arg {
+        AttributeArg::Ident(ident) => visitor.visit_identifier(ident),
+        AttributeArg::KeyValue { key, .. } => visitor.visit_identifier(key),
+        AttributeArg::String(_) => {}
+        AttributeArg::List(name, args) => {
+            visitor.visit_identifier(name);
+            for arg in args {
+                walk_attribute_arg(visitor, arg);
+            }
+        }
+    }
+}
+
+/// Walk a function
+///
+/// The function's own `attributes` are not visited here: for items they are
+/// the same attributes as [`Item::attributes`], which [`walk_item`] visits.
+pub fn walk_function<V: MutVisitor + ?Sized>(visitor: &mut V, func: &mut Function) {
+    visitor.visit_identifier(&mut func.name);
+    if let Some(generics) = &mut func.generics {
+        visitor.visit_generics(generics);
+    }
+    for param in &mut func.params {
+        visitor.visit_param(param);
+    }
+    for ty in func.return_type.iter_mut().chain(&mut func.error_type).chain(&mut func.effects) {
+        visitor.visit_type(ty);
+    }
+    visitor.visit_block(&mut func.body);
+}
+
+pub fn walk_param<V: MutVisitor + ?Sized>(visitor: &mut V, param: &mut Param) {
+    visitor.visit_identifier(&mut param.name);
+    if let Some(ty) = &mut param.type_annotation {
+        visitor.visit_type(ty);
+    }
+    if let Some(default_value) = &mut param.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_generics<V: MutVisitor + ?Sized>(visitor: &mut V, generics: &mut Generics) {
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type(ident) | GenericParam::Lifetime(ident) => visitor.visit_identifier(ident),
+            GenericParam::Const(ident, ty) => {
+                visitor.visit_identifier(ident);
+                visitor.visit_type(ty);
+            }
+        }
+    }
+    for clause in &mut generics.where_clause {
+        visitor.visit_identifier(&mut clause.type_param);
+        for bound in &mut clause.bounds {
+            match bound {
+                TraitBound::Trait(ty) => visitor.visit_type(ty),
+                TraitBound::Lifetime(ident) => visitor.visit_identifier(ident),
+            }
+        }
+    }
+}
+
+pub fn walk_struct<V: MutVisitor + ?Sized>(visitor: &mut V, struct_def: &mut Struct) {
+    visitor.visit_identifier(&mut struct_def.name);
+    if let Some(generics) = &mut struct_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for field in &mut struct_def.fields {
+        visitor.visit_struct_field(field);
+    }
+}
+
+pub fn walk_struct_field<V: MutVisitor + ?Sized>(visitor: &mut V, field: &mut StructField) {
+    for attribute in &mut field.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&mut field.name);
+    visitor.visit_type(&mut field.type_annotation);
+    if let Some(default_value) = &mut field.default_value {
+        visitor.visit_expression(default_value);
+    }
+}
+
+pub fn walk_enum<V: MutVisitor + ?Sized>(visitor: &mut V, enum_def: &mut Enum) {
+    visitor.visit_identifier(&mut enum_def.name);
+    if let Some(generics) = &mut enum_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for variant in &mut enum_def.variants {
+        visitor.visit_enum_variant(variant);
+    }
+}
+
+pub fn walk_enum_variant<V: MutVisitor + ?Sized>(visitor: &mut V, variant: &mut EnumVariant) {
+    for attribute in &mut variant.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&mut variant.name);
+    for field in &mut variant.fields {
+        match field {
+            VariantField::Named(name, ty) => {
+                visitor.visit_identifier(name);
+                visitor.visit_type(ty);
+            }
+            VariantField::Unnamed(ty) => visitor.visit_type(ty),
+        }
+    }
+}
+
+pub fn walk_trait<V: MutVisitor + ?Sized>(visitor: &mut V, trait_def: &mut Trait) {
+    visitor.visit_identifier(&mut trait_def.name);
+    if let Some(generics) = &mut trait_def.generics {
+        visitor.visit_generics(generics);
+    }
+    for super_trait in &mut trait_def.super_traits {
+        visitor.visit_type(super_trait);
+    }
+    for item in &mut trait_def.items {
+        visitor.visit_trait_item(item);
+    }
+}
+
+pub fn walk_trait_item<V: MutVisitor + ?Sized>(visitor: &mut V, item: &mut TraitItem) {
+    for attribute in &mut item.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    match &mut item.kind {
+        TraitItemKind::Method(func) | TraitItemKind::RequiredMethod(func) => visitor.visit_function(func),
+        TraitItemKind::AssociatedType(name, bounds) => {
+            visitor.visit_identifier(name);
+            for bound in bounds {
+                visitor.visit_type(bound);
+            }
+        }
+        TraitItemKind::Const(const_def) => visitor.visit_const(const_def),
+    }
+}
+
+/// Walk an impl block
+///
+/// Methods carry their attributes in [`Function::attributes`], so they are
+/// visited here (unlike for top-level functions).
+pub fn walk_impl<V: MutVisitor + ?Sized>(visitor: &mut V, impl_def: &mut Impl) {
+    if let Some(generics) = &mut impl_def.generics {
+        visitor.visit_generics(generics);
+    }
+    if let Some(trait_name) = &mut impl_def.trait_name {
+        visitor.visit_type(trait_name);
+    }
+    visitor.visit_type(&mut impl_def.self_type);
+    for method in &mut impl_def.items {
+        for attribute in &mut method.attributes {
+            visitor.visit_attribute(attribute);
+        }
+        visitor.visit_function(method);
+    }
+}
+
+pub fn walk_type_alias<V: MutVisitor + ?Sized>(visitor: &mut V, alias: &mut TypeAlias) {
+    visitor.visit_identifier(&mut alias.name);
+    if let Some(generics) = &mut alias.generics {
+        visitor.visit_generics(generics);
+    }
+    visitor.visit_type(&mut alias.type_annotation);
+}
+
+pub fn walk_const<V: MutVisitor + ?Sized>(visitor: &mut V, const_def: &mut Const) {
+    visitor.visit_identifier(&mut const_def.name);
+    visitor.visit_type(&mut const_def.type_annotation);
+    visitor.visit_expression(&mut const_def.value);
+}
+
+pub fn walk_static<V: MutVisitor + ?Sized>(visitor: &mut V, static_def: &mut Static) {
+    visitor.visit_identifier(&mut static_def.name);
+    visitor.visit_type(&mut static_def.type_annotation);
+    visitor.visit_expression(&mut static_def.value);
+}
+
+pub fn walk_module<V: MutVisitor + ?Sized>(visitor: &mut V, module: &mut Module) {
+    visitor.visit_identifier(&mut module.name);
+    for attribute in &mut module.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    for item in module.items.iter_mut().flatten() {
+        visitor.visit_item(item);
+    }
+}
+
+pub fn walk_use<V: MutVisitor + ?Sized>(visitor: &mut V, use_item: &mut Use) {
+    match &mut use_item.path {
+        UsePath::Simple(path) | UsePath::Glob(path) => visitor.visit_path(path),
+        UsePath::List(path, names) => {
+            visitor.visit_path(path);
+            for name in names {
+                visitor.visit_identifier(name);
+            }
+        }
+    }
+    if let Some(alias) = &mut use_item.alias {
+        visitor.visit_identifier(alias);
+    }
+}
+
+pub fn walk_extern_crate<V: MutVisitor + ?Sized>(visitor: &mut V, extern_crate: &mut ExternCrate) {
+    visitor.visit_identifier(&mut extern_crate.name);
+    if let Some(rename) = &mut extern_crate.rename {
+        visitor.visit_identifier(rename);
+    }
+}
+
+pub fn walk_effect<V: MutVisitor + ?Sized>(visitor: &mut V, effect: &mut Effect) {
+    visitor.visit_identifier(&mut effect.name);
+    if let Some(generics) = &mut effect.generics {
+        visitor.visit_generics(generics);
+    }
+    for operation in &mut effect.operations {
+        visitor.visit_effect_operation(operation);
+    }
+}
+
+pub fn walk_macro_def<V: MutVisitor + ?Sized>(visitor: &mut V, macro_def: &mut MacroDef) {
+    visitor.visit_identifier(&mut macro_def.name);
+}
+
+pub fn walk_effect_operation<V: MutVisitor + ?Sized>(
+    visitor: &mut V,
+    operation: &mut EffectOperation,
+) {
+    for attribute in &mut operation.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    visitor.visit_identifier(&mut operation.name);
+    for param in &mut operation.params {
+        visitor.visit_param(param);
+    }
+    if let Some(ty) = &mut operation.return_type {
+        visitor.visit_type(ty);
+    }
+}
+
+pub fn walk_block<V: MutVisitor + ?Sized>(visitor: &mut V, block: &mut Block) {
+    for stmt in &mut block.statements {
+        visitor.visit_statement(stmt);
+    }
+    if let Some(expr) = &mut block.trailing_expr {
+        visitor.visit_expression(expr);
+    }
+}
+
+pub fn walk_statement<V: MutVisitor + ?Sized>(visitor: &mut V, stmt: &mut Statement) {
+    match &mut stmt.kind {
+        StatementKind::Local(local) => visitor.visit_local(local),
+        StatementKind::LetElse(let_else) => {
+            visitor.visit_pattern(&mut let_else.pattern);
+            if let Some(ty) = &mut let_else.type_annotation {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expression(&mut let_else.init);
+            visitor.visit_block(&mut let_else.else_block);
+        }
+        StatementKind::Item(item) => visitor.visit_item(item),
+        StatementKind::Expr(expr) => visitor.visit_expression(expr),
+        StatementKind::Defer(stmt) => visitor.visit_statement(stmt),
+        StatementKind::Empty | StatementKind::Error => {}
+    }
+}
+
+pub fn walk_local<V: MutVisitor + ?Sized>(visitor: &mut V, local: &mut Local) {
+    visitor.visit_identifier(&mut local.name);
+    if let Some(ty) = &mut local.type_annotation {
+        visitor.visit_type(ty);
+    }
+    if let Some(init) = &mut local.init {
+        visitor.visit_expression(init);
+    }
+}
+
+pub fn walk_expression<V: MutVisitor + ?Sized>(visitor: &mut V, expr: &mut Expression) {
+    match &mut expr.kind {
+        ExpressionKind::Literal(literal) => visitor.visit_literal(literal),
+        ExpressionKind::Path(path) => visitor.visit_path(path),
+        ExpressionKind::Block(block) => visitor.visit_block(block),
+        ExpressionKind::Binary(_, left, right)
+        | ExpressionKind::Index(left, right)
+        | ExpressionKind::Assign(left, right)
+        | ExpressionKind::AssignOp(_, left, right)
+        | ExpressionKind::Range(left, _, right) => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        ExpressionKind::Unary(_, operand)
+        | ExpressionKind::TupleIndex(operand, _)
+        | ExpressionKind::Throw(operand)
+        | ExpressionKind::QuestionMark(operand)
+        | ExpressionKind::Grouped(operand)
+        | ExpressionKind::Await(operand) => visitor.visit_expression(operand),
+        ExpressionKind::Call(callee, args) => {
+            visitor.visit_expression(callee);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::MethodCall(receiver, method, args) => {
+            visitor.visit_expression(receiver);
+            visitor.visit_identifier(method);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::FieldAccess(object, field) => {
+            visitor.visit_expression(object);
+            visitor.visit_identifier(field);
+        }
+        ExpressionKind::Array(elements) | ExpressionKind::Tuple(elements) => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        ExpressionKind::Struct(literal) => {
+            visitor.visit_path(&mut literal.path);
+            for field in &mut literal.fields {
+                visitor.visit_identifier(&mut field.name);
+                visitor.visit_expression(&mut field.value);
+            }
+            if let Some(base) = &mut literal.base {
+                visitor.visit_expression(base);
+            }
+        }
+        ExpressionKind::If(condition, then_block, else_block) => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(then_block);
+            if let Some(else_block) = else_block {
+                visitor.visit_block(else_block);
+            }
+        }
+        ExpressionKind::Match(scrutinee, arms) => {
+            visitor.visit_expression(scrutinee);
+            for arm in arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        ExpressionKind::Loop(body, label) => {
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::While(condition, body, label) => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::For(binding, iter, body, label) => {
+            visitor.visit_local(binding);
+            visitor.visit_expression(iter);
+            visitor.visit_block(body);
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::Break(label) | ExpressionKind::Continue(label) => {
+            if let Some(label) = label {
+                visitor.visit_identifier(label);
+            }
+        }
+        ExpressionKind::Return(value) => {
+            if let Some(value) = value {
+                visitor.visit_expression(value);
+            }
+        }
+        ExpressionKind::Closure { params, return_type, body } => {
+            for param in params {
+                visitor.visit_local(param);
+            }
+            if let Some(ty) = return_type {
+                visitor.visit_type(ty);
+            }
+            visitor.visit_expression(body);
+        }
+        ExpressionKind::Let(pattern, value) => {
+            visitor.visit_pattern(pattern);
+            visitor.visit_expression(value);
+        }
+        ExpressionKind::Defer(stmt) => visitor.visit_statement(stmt),
+        ExpressionKind::Try(block, handlers) => {
+            visitor.visit_block(block);
+            for handler in handlers {
+                visitor.visit_effect_handler(handler);
+            }
+        }
+        ExpressionKind::Perform(operation, args) => {
+            visitor.visit_identifier(operation);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::Cast(value, ty) => {
+            visitor.visit_expression(value);
+            visitor.visit_type(ty);
+        }
+        ExpressionKind::TemplateString(template) => {
+            for part in &mut template.parts {
+                if let TemplateStringPart::Expr(expr, _) = part {
+                    visitor.visit_expression(expr);
+                }
+            }
+        }
+        ExpressionKind::MacroInvocation { macro_name, args, .. } => {
+            visitor.visit_identifier(macro_name);
+            for arg in args {
+                visitor.visit_expression(arg);
+            }
+        }
+        ExpressionKind::Error => {}
+    }
+}
+
+pub fn walk_match_arm<V: MutVisitor + ?Sized>(visitor: &mut V, arm: &mut MatchArm) {
+    for pattern in &mut arm.patterns {
+        visitor.visit_pattern(pattern);
+    }
+    if let Some(guard) = &mut arm.guard {
+        visitor.visit_expression(guard);
+    }
+    visitor.visit_expression(&mut arm.body);
+}
+
+pub fn walk_effect_handler<V: MutVisitor + ?Sized>(visitor: &mut V, handler: &mut EffectHandler) {
+    visitor.visit_identifier(&mut handler.effect_name);
+    for method in &mut handler.methods {
+        visitor.visit_identifier(&mut method.name);
+        for param in &mut method.params {
+            visitor.visit_param(param);
+        }
+        visitor.visit_block(&mut method.body);
+    }
+}
+
+pub fn walk_pattern<V: MutVisitor + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match pattern {
+        Pattern::Wildcard => {}
+        Pattern::Literal(literal) => visitor.visit_literal(literal),
+        Pattern::Identifier(ident) => visitor.visit_identifier(ident),
+        Pattern::Struct(path, fields) => {
+            visitor.visit_path(path);
+            for field in fields {
+                match field {
+                    StructPatternField::Field(name, pattern) => {
+                        visitor.visit_identifier(name);
+                        visitor.visit_pattern(pattern);
+                    }
+                    StructPatternField::Shorthand(name) => visitor.visit_identifier(name),
+                }
+            }
+        }
+        Pattern::TupleVariant(path, patterns) => {
+            visitor.visit_path(path);
+            for pattern in patterns {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Tuple(patterns) | Pattern::Array(patterns) | Pattern::Or(patterns) => {
+            for pattern in patterns {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Slice(before, middle, after) => {
+            for pattern in before.iter_mut().chain(middle).chain(after) {
+                visitor.visit_pattern(pattern);
+            }
+        }
+        Pattern::Range(start, _, end) => {
+            visitor.visit_pattern(start);
+            visitor.visit_pattern(end);
+        }
+    }
+}
+
+pub fn walk_type<V: MutVisitor + ?Sized>(visitor: &mut V, ty: &mut Type) {
+    match ty {
+        Type::Simple(ident) => visitor.visit_identifier(ident),
+        Type::Tuple(types) => {
+            for ty in types {
+                visitor.visit_type(ty);
+            }
+        }
+        Type::Array(element, len) => {
+            visitor.visit_type(element);
+            if let Some(len) = len {
+                visitor.visit_expression(len);
+            }
+        }
+        Type::Slice(inner)
+        | Type::Ref(inner, _)
+        | Type::Pointer(inner, _)
+        | Type::TraitObject(inner)
+        | Type::ImplTrait(inner)
+        | Type::Optional(inner) => visitor.visit_type(inner),
+        Type::Function(params, ret) => {
+            for param in params {
+                visitor.visit_type(param);
+            }
+            visitor.visit_type(ret);
+        }
+        Type::Pipe(ok, err) => {
+            visitor.visit_type(ok);
+            visitor.visit_type(err);
+        }
+        Type::Path(path) => visitor.visit_path(path),
+        Type::PathGeneric(path, args) => {
+            visitor.visit_path(path);
+            for arg in args.iter_mut().flatten() {
+                visitor.visit_type(arg);
+            }
+        }
+        Type::Never | Type::Unit => {}
+    }
+}
+
+pub fn walk_path<V: MutVisitor + ?Sized>(visitor: &mut V, path: &mut Vec<Identifier>) {
+    for segment in path {
+        visitor.visit_identifier(segment);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    /// Prefix every identifier named `old` with `new_`
+    struct Rename;
+
+    impl MutVisitor for Rename {
+        fn visit_identifier(&mut self, ident: &mut Identifier) {
+            if ident.name == "old" {
+                ident.name = "new_old".to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn test_mut_visitor_rewrites_in_place() {
+        let source = r#"
+            fn old(a: i32) -> i32 {
+                let x = old(a);
+                match x {
+                    old => old + 1,
+                }
+            }
+        "#;
+        let mut ast = Parser::from_source(source).parse().unwrap();
+        Rename.visit_ast(&mut ast);
+
+        let func = match &ast.items[0].kind {
+            ItemKind::Function(func) => func,
+            other => panic!("expected function, found {:?}", other),
+        };
+        assert_eq!(func.name.name, "new_old");
+
+        let init = match &func.body.statements[0].kind {
+            StatementKind::Local(local) => local.init.as_ref().unwrap(),
+            other => panic!("expected local, found {:?}", other),
+        };
+        match &init.kind {
+            ExpressionKind::Call(callee, _) => {
+                assert!(matches!(&callee.kind, ExpressionKind::Path(path) if path[0].name == "new_old"));
+            }
+            other => panic!("expected call, found {:?}", other),
+        }
+
+        match &func.body.trailing_expr.as_ref().unwrap().kind {
+            ExpressionKind::Match(_, arms) => {
+                assert!(matches!(&arms[0].patterns[0], Pattern::Identifier(id) if id.name == "new_old"));
+            }
+            other => panic!("expected match, found {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,321 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conditional compilation (条件编译)
+//!
+//! Items, fields, variants and methods annotated with `#[cfg(predicate)]` are
+//! removed from the AST when the predicate does not hold, before type checking
+//! ever sees them. A crate-level `#![cfg(predicate)]` (or one inside an inline
+//! module) removes the whole file or module.
+//!
+//! Predicates are built from:
+//! - names: `test`, `unix`, `windows`
+//! - key-value pairs: `target_os = "linux"`, `target_arch = "x86_64"`,
+//!   `feature = "x"`
+//! - combinators: `not(p)`, `all(p, ...)`, `any(p, ...)`
+//!
+//! ```rust
+//! use zulon_parser::cfg::{strip_unconfigured, CfgOptions};
+//! use zulon_parser::Parser;
+//!
+//! let source = r#"
+//!     #[cfg(test)]
+//!     fn helper() {}
+//!
+//!     #[cfg(not(test))]
+//!     fn main() {}
+//! "#;
+//!
+//! let mut ast = Parser::from_source(source).parse().unwrap();
+//! let errors = strip_unconfigured(&mut ast, &CfgOptions::new());
+//! assert!(errors.is_empty());
+//! assert_eq!(ast.items.len(), 1);
+//! ```
+
+use crate::ast::visit_mut::{self, MutVisitor};
+use crate::ast::*;
+use crate::lexer::Span;
+use std::collections::BTreeSet;
+
+/// Malformed `#[cfg]` predicate
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("invalid cfg predicate: {message}")]
+pub struct CfgError {
+    pub message: String,
+    pub span: Span,
+}
+
+/// The configuration `#[cfg]` predicates are evaluated against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgOptions {
+    /// Set names, e.g. `test` or `unix`
+    names: BTreeSet<String>,
+    /// Set key-value pairs, e.g. `("feature", "x")`
+    values: BTreeSet<(String, String)>,
+}
+
+impl CfgOptions {
+    /// An empty configuration: only `not(...)` predicates hold
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The configuration of the machine the compiler runs on
+    pub fn host() -> Self {
+        Self::new()
+            .with_value("target_os", std::env::consts::OS)
+            .with_value("target_arch", std::env::consts::ARCH)
+            .with_name(std::env::consts::FAMILY)
+    }
+
+    /// The configuration of a target triple such as `x86_64-unknown-linux-gnu`
+    ///
+    /// Unknown operating systems are recorded as `target_os = "unknown"`.
+    pub fn for_target(triple: &str) -> Self {
+        let arch = triple.split('-').next().unwrap_or(triple);
+        let os = ["linux", "macos", "windows", "freebsd", "android", "ios"]
+            .into_iter()
+            .find(|os| triple.split('-').any(|part| part == *os))
+            .or_else(|| triple.contains("darwin").then_some("macos"))
+            .unwrap_or("unknown");
+        let family = match os {
+            "windows" => "windows",
+            "unknown" => "",
+            _ => "unix",
+        };
+
+        let options = Self::new()
+            .with_value("target_os", os)
+            .with_value("target_arch", arch);
+        if family.is_empty() {
+            options
+        } else {
+            options.with_name(family)
+        }
+    }
+
+    /// Set or clear the `test` name
+    pub fn with_test(mut self, test: bool) -> Self {
+        if test {
+            self.names.insert("test".to_string());
+        } else {
+            self.names.remove("test");
+        }
+        self
+    }
+
+    /// Enable `feature = "name"`
+    pub fn with_feature(self, feature: impl Into<String>) -> Self {
+        self.with_value("feature", feature)
+    }
+
+    /// Set a name, e.g. `unix`
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.names.insert(name.into());
+        self
+    }
+
+    /// Set a key-value pair, e.g. `target_os = "linux"`
+    pub fn with_value(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.insert((key.into(), value.into()));
+        self
+    }
+
+    /// Whether a name is set
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Whether a key-value pair is set
+    pub fn has_value(&self, key: &str, value: &str) -> bool {
+        self.values.contains(&(key.to_string(), value.to_string()))
+    }
+
+    /// Evaluate a single predicate of the `cfg` attribute at `span`
+    fn eval(&self, predicate: &AttributeArg, span: Span) -> Result<bool, CfgError> {
+        match predicate {
+            AttributeArg::Ident(name) => Ok(self.has_name(&name.name)),
+            AttributeArg::KeyValue { key, value } => Ok(self.has_value(&key.name, value)),
+            AttributeArg::List(op, args) => match op.name.as_str() {
+                "all" => args.iter().try_fold(true, |acc, arg| Ok(self.eval(arg, span)? && acc)),
+                "any" => args.iter().try_fold(false, |acc, arg| Ok(self.eval(arg, span)? || acc)),
+                "not" => match args.as_slice() {
+                    [arg] => Ok(!self.eval(arg, span)?),
+                    _ => Err(CfgError {
+                        message: format!("`not` takes exactly one predicate, found {}", args.len()),
+                        span: op.span,
+                    }),
+                },
+                other => Err(CfgError {
+                    message: format!("unknown predicate `{}`, expected `all`, `any` or `not`", other),
+                    span: op.span,
+                }),
+            },
+            AttributeArg::String(value) => Err(CfgError {
+                message: format!("unexpected string literal \"{}\"", value),
+                span,
+            }),
+        }
+    }
+
+    /// Whether every `#[cfg]` attribute in the list holds
+    ///
+    /// Attributes other than `cfg` are ignored.
+    pub fn is_enabled(&self, attributes: &[Attribute]) -> Result<bool, CfgError> {
+        let mut enabled = true;
+        for attribute in attributes.iter().filter(|attr| attr.name.name == "cfg") {
+            match attribute.args.as_slice() {
+                [predicate] => enabled &= self.eval(predicate, attribute.name.span)?,
+                args => {
+                    return Err(CfgError {
+                        message: format!("`cfg` takes exactly one predicate, found {}", args.len()),
+                        span: attribute.name.span,
+                    })
+                }
+            }
+        }
+        Ok(enabled)
+    }
+}
+
+/// Remove everything whose `#[cfg]` predicate does not hold
+///
+/// Returns the malformed predicates found; the annotated nodes are kept in
+/// that case so later passes can still report errors inside them.
+pub fn strip_unconfigured(ast: &mut Ast, options: &CfgOptions) -> Vec<CfgError> {
+    let mut stripper = CfgStripper {
+        options,
+        errors: Vec::new(),
+    };
+
+    if stripper.keep(&ast.attributes) {
+        stripper.visit_ast(ast);
+    } else {
+        ast.items.clear();
+    }
+
+    stripper.errors
+}
+
+struct CfgStripper<'a> {
+    options: &'a CfgOptions,
+    errors: Vec<CfgError>,
+}
+
+impl CfgStripper<'_> {
+    fn keep(&mut self, attributes: &[Attribute]) -> bool {
+        match self.options.is_enabled(attributes) {
+            Ok(enabled) => enabled,
+            Err(error) => {
+                self.errors.push(error);
+                true
+            }
+        }
+    }
+
+    fn keep_item(&mut self, item: &Item) -> bool {
+        match &item.kind {
+            ItemKind::Module(module) => self.keep(&item.attributes) && self.keep(&module.attributes),
+            _ => self.keep(&item.attributes),
+        }
+    }
+}
+
+impl MutVisitor for CfgStripper<'_> {
+    fn visit_ast(&mut self, ast: &mut Ast) {
+        ast.items.retain(|item| self.keep_item(item));
+        visit_mut::walk_ast(self, ast);
+    }
+
+    fn visit_module(&mut self, module: &mut Module) {
+        if let Some(items) = &mut module.items {
+            items.retain(|item| self.keep_item(item));
+        }
+        visit_mut::walk_module(self, module);
+    }
+
+    fn visit_struct(&mut self, struct_def: &mut Struct) {
+        struct_def.fields.retain(|field| self.keep(&field.attributes));
+        visit_mut::walk_struct(self, struct_def);
+    }
+
+    fn visit_enum(&mut self, enum_def: &mut Enum) {
+        enum_def.variants.retain(|variant| self.keep(&variant.attributes));
+        visit_mut::walk_enum(self, enum_def);
+    }
+
+    fn visit_trait(&mut self, trait_def: &mut Trait) {
+        trait_def.items.retain(|item| self.keep(&item.attributes));
+        visit_mut::walk_trait(self, trait_def);
+    }
+
+    fn visit_impl(&mut self, impl_def: &mut Impl) {
+        impl_def.items.retain(|method| self.keep(&method.attributes));
+        visit_mut::walk_impl(self, impl_def);
+    }
+
+    fn visit_effect(&mut self, effect: &mut Effect) {
+        effect.operations.retain(|operation| self.keep(&operation.attributes));
+        visit_mut::walk_effect(self, effect);
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        block.statements.retain(|stmt| match &stmt.kind {
+            StatementKind::Item(item) => self.keep_item(item),
+            _ => true,
+        });
+        visit_mut::walk_block(self, block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    fn cfg(source: &str) -> Vec<Attribute> {
+        let ast = Parser::from_source(&format!("#[cfg({})] fn f() {{}}", source))
+            .parse()
+            .unwrap();
+        ast.items[0].attributes.clone()
+    }
+
+    #[test]
+    fn test_eval_predicates() {
+        let options = CfgOptions::for_target("x86_64-unknown-linux-gnu")
+            .with_test(true)
+            .with_feature("fast");
+
+        assert!(options.is_enabled(&cfg("test")).unwrap());
+        assert!(options.is_enabled(&cfg("unix")).unwrap());
+        assert!(!options.is_enabled(&cfg("windows")).unwrap());
+        assert!(options.is_enabled(&cfg("target_os = \"linux\"")).unwrap());
+        assert!(options.is_enabled(&cfg("target_arch = \"x86_64\"")).unwrap());
+        assert!(options.is_enabled(&cfg("feature = \"fast\"")).unwrap());
+        assert!(!options.is_enabled(&cfg("feature = \"slow\"")).unwrap());
+        assert!(options.is_enabled(&cfg("all(test, not(windows))")).unwrap());
+        assert!(options.is_enabled(&cfg("any(windows, feature = \"fast\")")).unwrap());
+        assert!(options.is_enabled(&cfg("all()")).unwrap());
+        assert!(!options.is_enabled(&cfg("any()")).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_predicates() {
+        let options = CfgOptions::new();
+        assert!(options.is_enabled(&cfg("not(test, unix)")).is_err());
+        assert!(options.is_enabled(&cfg("maybe(test)")).is_err());
+        assert!(options.is_enabled(&cfg("\"test\"")).is_err());
+    }
+
+    #[test]
+    fn test_for_target() {
+        let mac = CfgOptions::for_target("aarch64-apple-darwin");
+        assert!(mac.has_value("target_os", "macos"));
+        assert!(mac.has_value("target_arch", "aarch64"));
+        assert!(mac.has_name("unix"));
+
+        let windows = CfgOptions::for_target("x86_64-pc-windows-msvc");
+        assert!(windows.has_name("windows"));
+        assert!(!windows.has_name("unix"));
+    }
+}
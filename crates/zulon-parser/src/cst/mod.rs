@@ -0,0 +1,366 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Lossless concrete syntax tree (具体语法树)
+//!
+//! The [`SyntaxTree`] keeps every character of the source: each significant
+//! token carries the whitespace and comments around it as *trivia*, together
+//! with its exact text and span. Printing the tree reproduces the input
+//! byte-for-byte, which is what the formatter, refactorings and the language
+//! server build on.
+//!
+//! Trivia is attached following the usual convention:
+//! - trailing trivia is everything after a token up to (not including) the
+//!   next newline
+//! - leading trivia is everything else before a token
+//!
+//! The typed [`Ast`] is derived from the significant tokens with
+//! [`SyntaxTree::to_ast`].
+
+use crate::ast::Ast;
+use crate::lexer::{LexError, Lexer, Position, Span, Token, TokenKind};
+use crate::parser::{ParseError, Parser};
+use std::fmt;
+
+/// Kinds of trivia
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// Spaces, tabs and newlines
+    Whitespace,
+    /// Line (`//`) or block (`/* */`) comment
+    Comment,
+}
+
+/// A piece of trivia (whitespace or comment) attached to a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub span: Span,
+}
+
+/// A significant token together with its source text and trivia
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Span,
+    /// Trivia before the token (previous lines and indentation)
+    pub leading_trivia: Vec<Trivia>,
+    /// Trivia after the token on the same line
+    pub trailing_trivia: Vec<Trivia>,
+}
+
+impl SyntaxToken {
+    /// Convert to a plain token for the parser
+    pub fn to_token(&self) -> Token {
+        Token {
+            kind: self.kind.clone(),
+            span: self.span,
+        }
+    }
+
+    /// Span covering the token and all of its trivia
+    pub fn full_span(&self) -> Span {
+        let start = self
+            .leading_trivia
+            .first()
+            .map(|t| t.span.start)
+            .unwrap_or(self.span.start);
+        let end = self
+            .trailing_trivia
+            .last()
+            .map(|t| t.span.end)
+            .unwrap_or(self.span.end);
+        Span::new(start, end)
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading_trivia {
+            f.write_str(&trivia.text)?;
+        }
+        f.write_str(&self.text)?;
+        for trivia in &self.trailing_trivia {
+            f.write_str(&trivia.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lossless token+trivia tree of a source file
+#[derive(Debug, Clone)]
+pub struct SyntaxTree {
+    /// Significant tokens in source order
+    pub tokens: Vec<SyntaxToken>,
+    /// Trivia after the last token (up to end of file)
+    pub eof_trivia: Vec<Trivia>,
+    /// Errors reported by the lexer
+    pub lex_errors: Vec<LexError>,
+}
+
+impl SyntaxTree {
+    /// Build the syntax tree for the given source code
+    pub fn parse(source: &str) -> Self {
+        let (raw_tokens, lex_errors) = Lexer::new(source).lex_all_with_trivia();
+        let index = LineIndex::new(source);
+
+        let mut tokens: Vec<SyntaxToken> = Vec::new();
+        let mut pending: Vec<Trivia> = Vec::new();
+
+        for token in raw_tokens {
+            let text = index.slice(source, token.span).to_string();
+
+            let trivia_kind = match token.kind {
+                TokenKind::Whitespace => Some(TriviaKind::Whitespace),
+                TokenKind::Comment => Some(TriviaKind::Comment),
+                _ => None,
+            };
+
+            match trivia_kind {
+                Some(kind) => pending.push(Trivia {
+                    kind,
+                    text,
+                    span: token.span,
+                }),
+                None => {
+                    let leading_trivia = match tokens.last_mut() {
+                        Some(prev) => {
+                            let (trailing, leading) = split_trailing(std::mem::take(&mut pending));
+                            prev.trailing_trivia = trailing;
+                            leading
+                        }
+                        None => std::mem::take(&mut pending),
+                    };
+
+                    tokens.push(SyntaxToken {
+                        kind: token.kind,
+                        text,
+                        span: token.span,
+                        leading_trivia,
+                        trailing_trivia: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        let eof_trivia = match tokens.last_mut() {
+            Some(prev) => {
+                let (trailing, leading) = split_trailing(pending);
+                prev.trailing_trivia = trailing;
+                leading
+            }
+            None => pending,
+        };
+
+        SyntaxTree {
+            tokens,
+            eof_trivia,
+            lex_errors,
+        }
+    }
+
+    /// Significant tokens, ready to be fed to the [`Parser`]
+    pub fn significant_tokens(&self) -> Vec<Token> {
+        self.tokens.iter().map(SyntaxToken::to_token).collect()
+    }
+
+    /// Derive the typed AST, recovering from syntax errors
+    pub fn to_ast(&self) -> (Ast, Vec<ParseError>) {
+        Parser::new(self.significant_tokens()).parse_with_recovery()
+    }
+
+    /// Find the significant token whose span contains the given position
+    pub fn token_at(&self, position: Position) -> Option<&SyntaxToken> {
+        let key = (position.line, position.column);
+        self.tokens.iter().find(|token| {
+            let start = (token.span.start.line, token.span.start.column);
+            let end = (token.span.end.line, token.span.end.column);
+            start <= key && key < end
+        })
+    }
+
+    /// All comments in the file, in source order
+    pub fn comments(&self) -> impl Iterator<Item = &Trivia> {
+        self.tokens
+            .iter()
+            .flat_map(|t| t.leading_trivia.iter().chain(t.trailing_trivia.iter()))
+            .chain(self.eof_trivia.iter())
+            .filter(|t| t.kind == TriviaKind::Comment)
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in &self.tokens {
+            write!(f, "{}", token)?;
+        }
+        for trivia in &self.eof_trivia {
+            f.write_str(&trivia.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split trivia following a token into its trailing part (same line) and the
+/// leading part of the next token
+///
+/// A whitespace piece containing a newline is split right before the newline;
+/// a block comment spanning several lines starts the leading part.
+fn split_trailing(trivia: Vec<Trivia>) -> (Vec<Trivia>, Vec<Trivia>) {
+    let mut trailing = Vec::new();
+    let mut rest = trivia.into_iter();
+
+    for piece in rest.by_ref() {
+        let newline = match (piece.kind, piece.text.find('\n')) {
+            (TriviaKind::Comment, Some(_)) => Some(0),
+            (_, found) => found,
+        };
+        match newline {
+            None => trailing.push(piece),
+            Some(0) => {
+                let mut leading = vec![piece];
+                leading.extend(rest);
+                return (trailing, leading);
+            }
+            Some(newline) => {
+                let (same_line, next) = piece.text.split_at(newline);
+                let split_at = Position::new(
+                    piece.span.start.line,
+                    piece.span.start.column + same_line.chars().count(),
+                );
+                trailing.push(Trivia {
+                    kind: piece.kind,
+                    text: same_line.to_string(),
+                    span: Span::new(piece.span.start, split_at),
+                });
+                let mut leading = vec![Trivia {
+                    kind: piece.kind,
+                    text: next.to_string(),
+                    span: Span::new(split_at, piece.span.end),
+                }];
+                leading.extend(rest);
+                return (trailing, leading);
+            }
+        }
+    }
+
+    (trailing, Vec::new())
+}
+
+/// Maps 1-indexed line/column positions to byte offsets
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { line_starts }
+    }
+
+    fn offset(&self, source: &str, position: Position) -> usize {
+        let line_start = match self.line_starts.get(position.line.saturating_sub(1)) {
+            Some(&start) => start,
+            None => return source.len(),
+        };
+        source[line_start..]
+            .char_indices()
+            .nth(position.column.saturating_sub(1))
+            .map(|(i, _)| line_start + i)
+            .unwrap_or(source.len())
+    }
+
+    fn slice<'s>(&self, source: &'s str, span: Span) -> &'s str {
+        let start = self.offset(source, span.start);
+        let end = self.offset(source, span.end);
+        &source[start..end.max(start)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::ItemKind;
+
+    const SOURCE: &str = "// leading comment\nfn main() -> i32 { // trailing\n    /* block */ let x = 1;\n\tx   \n}\n";
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        let tree = SyntaxTree::parse(SOURCE);
+        assert_eq!(tree.to_string(), SOURCE);
+    }
+
+    #[test]
+    fn test_round_trip_unicode_and_no_trailing_newline() {
+        let source = "fn 名字() {\r\n  let s = \"héllo\"; /* ünïcode */ }";
+        let tree = SyntaxTree::parse(source);
+        assert_eq!(tree.to_string(), source);
+    }
+
+    #[test]
+    fn test_multi_line_block_comment_is_not_split() {
+        let source = "let a = 1; /* one\ntwo */ let b = 2;";
+        let tree = SyntaxTree::parse(source);
+        assert_eq!(tree.to_string(), source);
+
+        let b = tree.tokens.iter().filter(|t| t.kind == TokenKind::Let).nth(1).unwrap();
+        assert_eq!(b.leading_trivia[0].text, "/* one\ntwo */");
+    }
+
+    #[test]
+    fn test_round_trip_empty_and_trivia_only() {
+        assert_eq!(SyntaxTree::parse("").to_string(), "");
+        let source = "  // only a comment\n\n";
+        let tree = SyntaxTree::parse(source);
+        assert!(tree.tokens.is_empty());
+        assert_eq!(tree.to_string(), source);
+    }
+
+    #[test]
+    fn test_trivia_attachment() {
+        let tree = SyntaxTree::parse(SOURCE);
+
+        let fn_token = &tree.tokens[0];
+        assert_eq!(fn_token.kind, TokenKind::Fn);
+        assert_eq!(fn_token.leading_trivia.len(), 2);
+        assert_eq!(fn_token.leading_trivia[0].text, "// leading comment");
+
+        let brace = tree.tokens.iter().find(|t| t.kind == TokenKind::LeftBrace).unwrap();
+        let trailing: Vec<_> = brace.trailing_trivia.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(trailing, vec![" ", "// trailing"]);
+
+        let let_token = tree.tokens.iter().find(|t| t.kind == TokenKind::Let).unwrap();
+        let leading: Vec<_> = let_token.leading_trivia.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(leading, vec!["\n    ", "/* block */", " "]);
+
+        assert_eq!(tree.comments().count(), 3);
+    }
+
+    #[test]
+    fn test_exact_spans() {
+        let tree = SyntaxTree::parse(SOURCE);
+        let x = tree
+            .tokens
+            .iter()
+            .find(|t| matches!(&t.kind, TokenKind::Ident(name) if &**name == "x"))
+            .unwrap();
+        assert_eq!(x.span, Span::new(Position::new(3, 21), Position::new(3, 22)));
+        assert_eq!(x.text, "x");
+
+        let found = tree.token_at(Position::new(3, 21)).unwrap();
+        assert_eq!(found.text, "x");
+        assert!(tree.token_at(Position::new(1, 3)).is_none());
+    }
+
+    #[test]
+    fn test_ast_derived_from_tree() {
+        let tree = SyntaxTree::parse(SOURCE);
+        let (ast, errors) = tree.to_ast();
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(ast.items.len(), 1);
+        assert!(matches!(ast.items[0].kind, ItemKind::Function(_)));
+    }
+}
@@ -3,7 +3,7 @@
 
 //! Integration with zulon-diagnostic
 
-use crate::{LexError, LexErrorKind};
+use crate::{FormatError, LexError, LexErrorKind};
 use zulon_diagnostic::{Diagnostic, Span, Suggestion, Loc};
 use std::path::PathBuf;
 
@@ -71,6 +71,18 @@ impl LexError {
             LexErrorKind::UnterminatedInterpolation => {
                 ("unterminated string interpolation '${{...}}'".to_string(), Some("E0010"))
             }
+            LexErrorKind::InvalidDigit { digit, radix } => {
+                (format!("invalid digit '{}' for a base {} literal", digit, radix), Some("E0011"))
+            }
+            LexErrorKind::InvalidNumberSuffix(suffix) => {
+                (format!("invalid suffix '{}' for number literal", suffix), Some("E0012"))
+            }
+            LexErrorKind::IntegerTooLarge => {
+                ("integer literal is too large".to_string(), Some("E0013"))
+            }
+            LexErrorKind::NonAsciiByte(c) => {
+                (format!("non-ASCII character '{}' in byte literal", c), Some("E0014"))
+            }
         };
 
         let mut diagnostic = Diagnostic::error()
@@ -90,6 +102,14 @@ impl LexError {
                     "",
                 ));
             }
+            LexErrorKind::InvalidNumberSuffix(_) => {
+                diagnostic = diagnostic.note(
+                    "valid suffixes are i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32 and f64",
+                );
+            }
+            LexErrorKind::NonAsciiByte(_) => {
+                diagnostic = diagnostic.note("use a \\xNN escape to write a byte above 0x7F");
+            }
             LexErrorKind::UnterminatedString => {
                 diagnostic = diagnostic.suggestion(Suggestion::new(
                     "close the string with a quote (\")",
@@ -116,6 +136,27 @@ impl LexError {
     }
 }
 
+impl FormatError {
+    /// Convert to a Diagnostic
+    pub fn to_diagnostic(&self, source_code: &str) -> Diagnostic {
+        let file = Some(PathBuf::from("input.zl"));
+        let (start, end) = (self.span.start, self.span.end);
+        let lo = Loc::new(file.clone(), start.line, start.column, estimate_byte_offset(source_code, start.line, start.column));
+        let hi = Loc::new(file, end.line, end.column, estimate_byte_offset(source_code, end.line, end.column));
+        let span = Span::new(lo, hi);
+
+        let mut diagnostic = Diagnostic::error()
+            .message(self.message.clone())
+            .span(span.clone())
+            .code("E0015")
+            .label(span, "in this format string");
+        if let Some(note) = &self.note {
+            diagnostic = diagnostic.note(note.clone());
+        }
+        diagnostic.build()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +189,16 @@ mod tests {
         assert_eq!(diagnostic.code, Some("E0002".to_string()));
         assert!(diagnostic.suggestions.len() > 0);
     }
+
+    #[test]
+    fn test_format_error_diagnostic() {
+        let source = "let s = format!(\"{:q}\", x);";
+        let error = crate::format::parse_format_string("{:q}", Position::new(1, 18)).unwrap_err();
+
+        let diagnostic = error.to_diagnostic(source);
+        assert_eq!(diagnostic.message, "unknown format trait `q`");
+        assert_eq!(diagnostic.code, Some("E0015".to_string()));
+        assert_eq!(diagnostic.span.unwrap().lo.offset, 19);
+        assert_eq!(diagnostic.notes.len(), 1);
+    }
 }
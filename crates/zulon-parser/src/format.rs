@@ -0,0 +1,619 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Format strings
+//!
+//! `format!`-style strings and the `${expr:spec}` holes of template strings
+//! share one specifier mini-language, modelled on Rust's `std::fmt`:
+//!
+//! ```text
+//! format    := (text | '{{' | '}}' | '{' [argument] [':' spec] '}')*
+//! argument  := integer | identifier
+//! spec      := [[fill] align] [sign] ['#'] ['0'] [width] ['.' precision] [kind]
+//! align     := '<' | '^' | '>'
+//! sign      := '+' | '-'
+//! width     := count
+//! precision := count | '*'
+//! count     := integer | argument '$'
+//! kind      := '' | '?' | 'x' | 'X' | 'b' | 'o' | 'e' | 'E'
+//! ```
+//!
+//! Specifiers are checked here, at compile time, so lowering only has to
+//! pick the `Display`/`Debug` call and apply the padding.
+//!
+//! ```rust
+//! use zulon_parser::format::{parse_format_string, Argument, Count, Piece};
+//! use zulon_parser::Position;
+//!
+//! let pieces = parse_format_string("x = {x:>8.3}", Position::new(1, 1)).unwrap();
+//! let Piece::Placeholder(placeholder) = &pieces[1] else { unreachable!() };
+//! assert_eq!(placeholder.argument, Argument::Name("x".to_string()));
+//! assert_eq!(placeholder.spec.width, Some(Count::Is(8)));
+//! assert_eq!(placeholder.spec.to_string(), " >8.3");
+//! ```
+
+use crate::lexer::{Position, Span};
+use std::fmt;
+
+/// A bad format string or specifier
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("{message}")]
+pub struct FormatError {
+    pub message: String,
+    pub span: Span,
+    /// Extra help shown below the error
+    pub note: Option<String>,
+}
+
+impl FormatError {
+    /// Create an error without a note
+    pub fn new(message: impl Into<String>, span: Span) -> Self {
+        FormatError {
+            message: message.into(),
+            span,
+            note: None,
+        }
+    }
+
+    /// Attach a note explaining how to fix the error
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// A piece of a parsed format string
+#[derive(Debug, Clone, PartialEq)]
+pub enum Piece {
+    /// Text copied to the output, with `{{` and `}}` already unescaped
+    Literal(String),
+    /// A `{...}` placeholder
+    Placeholder(Placeholder),
+}
+
+/// A `{argument:spec}` placeholder
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder {
+    pub argument: Argument,
+    pub spec: FormatSpec,
+    pub span: Span,
+}
+
+/// Which argument a placeholder or count refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argument {
+    /// `{}` or `.*`: the argument after the previous one
+    Next,
+    /// `{0}` or `0$`
+    Index(usize),
+    /// `{name}` or `name$`
+    Name(String),
+}
+
+/// A width or precision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Count {
+    /// A literal count, like the `8` in `{:8}`
+    Is(usize),
+    /// A count taken from an argument, like `{:w$}` or `{:.*}`
+    Argument(Argument),
+}
+
+/// Alignment inside the padded width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// An explicit sign flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sign {
+    Plus,
+    Minus,
+}
+
+/// The formatting trait a placeholder uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatKind {
+    #[default]
+    Display,
+    Debug,
+    LowerHex,
+    UpperHex,
+    Binary,
+    Octal,
+    LowerExp,
+    UpperExp,
+}
+
+impl FormatKind {
+    /// Every kind, in the order of their runtime encoding
+    pub const ALL: [FormatKind; 8] = [
+        FormatKind::Display,
+        FormatKind::Debug,
+        FormatKind::LowerHex,
+        FormatKind::UpperHex,
+        FormatKind::Binary,
+        FormatKind::Octal,
+        FormatKind::LowerExp,
+        FormatKind::UpperExp,
+    ];
+
+    /// The kind written at the end of a spec, like `x` in `{:08x}`
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.suffix() == suffix)
+    }
+
+    /// The text that selects this kind at the end of a spec
+    pub fn suffix(self) -> &'static str {
+        match self {
+            FormatKind::Display => "",
+            FormatKind::Debug => "?",
+            FormatKind::LowerHex => "x",
+            FormatKind::UpperHex => "X",
+            FormatKind::Binary => "b",
+            FormatKind::Octal => "o",
+            FormatKind::LowerExp => "e",
+            FormatKind::UpperExp => "E",
+        }
+    }
+
+    /// The trait whose `fmt` method formats a value with this kind
+    pub fn trait_name(self) -> &'static str {
+        match self {
+            FormatKind::Display => "Display",
+            FormatKind::Debug => "Debug",
+            FormatKind::LowerHex => "LowerHex",
+            FormatKind::UpperHex => "UpperHex",
+            FormatKind::Binary => "Binary",
+            FormatKind::Octal => "Octal",
+            FormatKind::LowerExp => "LowerExp",
+            FormatKind::UpperExp => "UpperExp",
+        }
+    }
+}
+
+/// A parsed format specifier, everything after the `:` of a placeholder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    pub align: Option<Align>,
+    pub sign: Option<Sign>,
+    /// The `#` flag
+    pub alternate: bool,
+    /// The `0` flag: pad numbers with zeros after the sign
+    pub zero_pad: bool,
+    pub width: Option<Count>,
+    pub precision: Option<Count>,
+    pub kind: FormatKind,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        FormatSpec {
+            fill: ' ',
+            align: None,
+            sign: None,
+            alternate: false,
+            zero_pad: false,
+            width: None,
+            precision: None,
+            kind: FormatKind::Display,
+        }
+    }
+}
+
+impl FormatSpec {
+    /// The `{:?}` spec
+    pub fn debug() -> Self {
+        FormatSpec {
+            kind: FormatKind::Debug,
+            ..Self::default()
+        }
+    }
+
+    /// Whether this is the plain `{}` spec
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Parse a spec on its own, as found after the `:` in `${value:spec}`
+    ///
+    /// `start` is the position of the first character of `text`.
+    pub fn parse(text: &str, start: Position) -> Result<Self, FormatError> {
+        Cursor::new(text, start).spec()
+    }
+
+    /// Iterate over the counts that come from arguments, width first
+    pub fn count_arguments(&self) -> impl Iterator<Item = &Argument> {
+        [&self.width, &self.precision]
+            .into_iter()
+            .filter_map(|count| match count {
+                Some(Count::Argument(argument)) => Some(argument),
+                _ => None,
+            })
+    }
+}
+
+/// Prints the spec back in its canonical form, without the leading `:`
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(align) = self.align {
+            let align = match align {
+                Align::Left => '<',
+                Align::Center => '^',
+                Align::Right => '>',
+            };
+            write!(f, "{}{}", self.fill, align)?;
+        }
+        match self.sign {
+            Some(Sign::Plus) => write!(f, "+")?,
+            Some(Sign::Minus) => write!(f, "-")?,
+            None => {}
+        }
+        if self.alternate {
+            write!(f, "#")?;
+        }
+        if self.zero_pad {
+            write!(f, "0")?;
+        }
+        if let Some(width) = &self.width {
+            write!(f, "{}", width)?;
+        }
+        if let Some(precision) = &self.precision {
+            write!(f, ".{}", precision)?;
+        }
+        write!(f, "{}", self.kind.suffix())
+    }
+}
+
+impl fmt::Display for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Count::Is(count) => write!(f, "{}", count),
+            Count::Argument(Argument::Next) => write!(f, "*"),
+            Count::Argument(Argument::Index(index)) => write!(f, "{}$", index),
+            Count::Argument(Argument::Name(name)) => write!(f, "{}$", name),
+        }
+    }
+}
+
+/// Parse a `format!`-style string into literal text and placeholders
+///
+/// `start` is the position of the first character of `text`, used for the
+/// spans of placeholders and errors.
+pub fn parse_format_string(text: &str, start: Position) -> Result<Vec<Piece>, FormatError> {
+    let mut cursor = Cursor::new(text, start);
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+
+    while let Some(c) = cursor.peek() {
+        let open = cursor.offset;
+        cursor.bump();
+        match c {
+            '{' if cursor.eat('{') => literal.push('{'),
+            '}' if cursor.eat('}') => literal.push('}'),
+            '{' => {
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Placeholder(cursor.placeholder(open)?));
+            }
+            '}' => {
+                return Err(FormatError::new("invalid format string: unmatched `}` found", cursor.span(open))
+                    .with_note("if you intended to print `}`, you can escape it using `}}`"));
+            }
+            _ => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+/// The position reached after `text`, starting from `start`
+pub(crate) fn advance_position(start: Position, text: &str) -> Position {
+    text.chars().fold(start, |position, c| {
+        if c == '\n' {
+            Position::new(position.line + 1, 1)
+        } else {
+            Position::new(position.line, position.column + 1)
+        }
+    })
+}
+
+/// Walks a format string, tracking byte offsets for spans
+struct Cursor<'a> {
+    text: &'a str,
+    offset: usize,
+    start: Position,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str, start: Position) -> Self {
+        Cursor { text, offset: 0, start }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.offset;
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+        &self.text[start..self.offset]
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        advance_position(self.start, &self.text[..offset])
+    }
+
+    /// The span from `from` up to the cursor, or of one character if empty
+    fn span(&self, from: usize) -> Span {
+        let to = if self.offset > from {
+            self.offset
+        } else {
+            from + self.text[from..].chars().next().map_or(0, char::len_utf8)
+        };
+        Span::new(self.position(from), self.position(to))
+    }
+
+    /// Parse the rest of a placeholder whose `{` started at `open`
+    fn placeholder(&mut self, open: usize) -> Result<Placeholder, FormatError> {
+        let Some(len) = self.rest().find(['{', '}']).filter(|&len| self.rest()[len..].starts_with('}')) else {
+            return Err(FormatError::new(
+                "invalid format string: expected `}` but string was terminated",
+                self.span(open),
+            )
+            .with_note("if you intended to print `{`, you can escape it using `{{`"));
+        };
+
+        let mut inner = Cursor {
+            text: &self.text[..self.offset + len],
+            offset: self.offset,
+            start: self.start,
+        };
+        let argument_start = inner.offset;
+        let argument_text = inner.eat_while(|c| c != ':');
+        let argument = parse_argument(argument_text).ok_or_else(|| {
+            FormatError::new(
+                format!("invalid argument name `{}`", argument_text),
+                inner.span(argument_start),
+            )
+        })?;
+        let spec = if inner.eat(':') { inner.spec()? } else { FormatSpec::default() };
+
+        self.offset = inner.offset;
+        self.bump(); // '}'
+        Ok(Placeholder {
+            argument,
+            spec,
+            span: self.span(open),
+        })
+    }
+
+    /// Parse a spec that runs to the end of the cursor's text
+    fn spec(&mut self) -> Result<FormatSpec, FormatError> {
+        let mut spec = FormatSpec::default();
+
+        if let Some(align) = self.peek_second().and_then(align) {
+            spec.fill = self.bump().unwrap_or(' ');
+            self.bump();
+            spec.align = Some(align);
+        } else if let Some(align) = self.peek().and_then(align) {
+            self.bump();
+            spec.align = Some(align);
+        }
+
+        if self.eat('+') {
+            spec.sign = Some(Sign::Plus);
+        } else if self.eat('-') {
+            spec.sign = Some(Sign::Minus);
+        }
+        spec.alternate = self.eat('#');
+        if self.peek() == Some('0') && self.peek_second() != Some('$') {
+            self.bump();
+            spec.zero_pad = true;
+        }
+
+        spec.width = self.count()?;
+        if self.eat('.') {
+            let precision_start = self.offset;
+            spec.precision = if self.eat('*') {
+                Some(Count::Argument(Argument::Next))
+            } else {
+                match self.count()? {
+                    Some(count) => Some(count),
+                    None => {
+                        return Err(FormatError::new("expected a precision after `.`", self.span(precision_start)));
+                    }
+                }
+            };
+        }
+
+        let kind_start = self.offset;
+        let kind = self.eat_while(|_| true);
+        spec.kind = FormatKind::from_suffix(kind).ok_or_else(|| {
+            FormatError::new(format!("unknown format trait `{}`", kind), self.span(kind_start)).with_note(
+                "the only appropriate formatting traits are:\n\
+                 - ``, which uses the `Display` trait\n\
+                 - `?`, which uses the `Debug` trait\n\
+                 - `x`, `X`, `b` and `o`, which print integers in hexadecimal, binary and octal\n\
+                 - `e` and `E`, which use scientific notation",
+            )
+        })?;
+        Ok(spec)
+    }
+
+    /// Parse an optional width or precision
+    fn count(&mut self) -> Result<Option<Count>, FormatError> {
+        let start = self.offset;
+        if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            let digits = self.eat_while(|c| c.is_ascii_digit());
+            let value = digits
+                .parse()
+                .map_err(|_| FormatError::new(format!("count `{}` is too large", digits), self.span(start)))?;
+            return Ok(Some(if self.eat('$') {
+                Count::Argument(Argument::Index(value))
+            } else {
+                Count::Is(value)
+            }));
+        }
+
+        if self.peek().is_some_and(is_identifier_start) {
+            let name = self.eat_while(is_identifier_continue);
+            if self.eat('$') {
+                return Ok(Some(Count::Argument(Argument::Name(name.to_string()))));
+            }
+            // Not a count: the identifier is the format kind
+            self.offset = start;
+        }
+        Ok(None)
+    }
+}
+
+fn align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '^' => Some(Align::Center),
+        '>' => Some(Align::Right),
+        _ => None,
+    }
+}
+
+fn parse_argument(text: &str) -> Option<Argument> {
+    if text.is_empty() {
+        Some(Argument::Next)
+    } else if text.chars().all(|c| c.is_ascii_digit()) {
+        text.parse().ok().map(Argument::Index)
+    } else if text.starts_with(is_identifier_start) && text.chars().all(is_identifier_continue) {
+        Some(Argument::Name(text.to_string()))
+    } else {
+        None
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c == '_' || unicode_xid::UnicodeXID::is_xid_start(c)
+}
+
+fn is_identifier_continue(c: char) -> bool {
+    unicode_xid::UnicodeXID::is_xid_continue(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(text: &str) -> FormatSpec {
+        FormatSpec::parse(text, Position::new(1, 1)).unwrap()
+    }
+
+    fn placeholders(text: &str) -> Vec<Placeholder> {
+        parse_format_string(text, Position::new(1, 1))
+            .unwrap()
+            .into_iter()
+            .filter_map(|piece| match piece {
+                Piece::Placeholder(placeholder) => Some(placeholder),
+                Piece::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_literals_and_escapes() {
+        let pieces = parse_format_string("{{a}} {} b", Position::new(1, 1)).unwrap();
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[0], Piece::Literal("{a} ".to_string()));
+        assert_eq!(pieces[2], Piece::Literal(" b".to_string()));
+    }
+
+    #[test]
+    fn test_arguments() {
+        let arguments: Vec<_> = placeholders("{} {1} {name:?}").into_iter().map(|p| p.argument).collect();
+        assert_eq!(
+            arguments,
+            vec![Argument::Next, Argument::Index(1), Argument::Name("name".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fill_align_sign_and_flags() {
+        let parsed = spec("*^+#010x");
+        assert_eq!(parsed.fill, '*');
+        assert_eq!(parsed.align, Some(Align::Center));
+        assert_eq!(parsed.sign, Some(Sign::Plus));
+        assert!(parsed.alternate && parsed.zero_pad);
+        assert_eq!(parsed.width, Some(Count::Is(10)));
+        assert_eq!(parsed.kind, FormatKind::LowerHex);
+
+        assert_eq!(spec(">8").align, Some(Align::Right));
+        assert_eq!(spec("<").fill, ' ');
+    }
+
+    #[test]
+    fn test_counts_from_arguments() {
+        let parsed = spec("w$.*");
+        assert_eq!(parsed.width, Some(Count::Argument(Argument::Name("w".to_string()))));
+        assert_eq!(parsed.precision, Some(Count::Argument(Argument::Next)));
+
+        let parsed = spec("0$.1$e");
+        assert!(!parsed.zero_pad);
+        assert_eq!(parsed.width, Some(Count::Argument(Argument::Index(0))));
+        assert_eq!(parsed.precision, Some(Count::Argument(Argument::Index(1))));
+        assert_eq!(parsed.kind, FormatKind::LowerExp);
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for text in ["", "?", "*^+#010x", " <w$.*", ".3", "0$.1$e"] {
+            assert_eq!(spec(text).to_string(), text);
+        }
+        assert_eq!(spec(">8").to_string(), " >8");
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = parse_format_string("a } b", Position::new(1, 1)).unwrap_err();
+        assert!(error.message.contains("unmatched `}`"));
+        assert_eq!(error.span.start, Position::new(1, 3));
+
+        let error = parse_format_string("{:q}", Position::new(2, 5)).unwrap_err();
+        assert_eq!(error.message, "unknown format trait `q`");
+        assert_eq!(error.span.start, Position::new(2, 7));
+        assert!(error.note.is_some());
+
+        assert!(parse_format_string("{", Position::new(1, 1)).is_err());
+        assert!(parse_format_string("{a b}", Position::new(1, 1)).is_err());
+        assert!(FormatSpec::parse(".", Position::new(1, 1)).is_err());
+    }
+}
@@ -45,6 +45,14 @@ pub enum LexErrorKind {
     UnexpectedEof,
     /// Unterminated string interpolation (${...})
     UnterminatedInterpolation,
+    /// Digit not valid for the literal's base (e.g., `0b102`)
+    InvalidDigit { digit: char, radix: u32 },
+    /// Unknown numeric literal suffix (e.g., `42u7`)
+    InvalidNumberSuffix(String),
+    /// Integer literal does not fit in 128 bits
+    IntegerTooLarge,
+    /// Non-ASCII character in a byte or byte string literal
+    NonAsciiByte(char),
 }
 
 impl fmt::Display for LexErrorKind {
@@ -80,6 +88,18 @@ impl fmt::Display for LexErrorKind {
             LexErrorKind::UnterminatedInterpolation => {
                 write!(f, "unterminated string interpolation '${{...}}'")
             }
+            LexErrorKind::InvalidDigit { digit, radix } => {
+                write!(f, "invalid digit '{}' for a base {} literal", digit, radix)
+            }
+            LexErrorKind::InvalidNumberSuffix(suffix) => {
+                write!(f, "invalid suffix '{}' for number literal", suffix)
+            }
+            LexErrorKind::IntegerTooLarge => {
+                write!(f, "integer literal is too large")
+            }
+            LexErrorKind::NonAsciiByte(c) => {
+                write!(f, "non-ASCII character '{}' in byte literal", c)
+            }
         }
     }
 }
@@ -14,6 +14,8 @@
 mod token;
 mod error;
 
+use crate::ast::{FloatSuffix, IntSuffix};
+
 pub use token::*;
 pub use error::*;
 
@@ -33,6 +35,8 @@ pub struct Lexer<'a> {
     token_start: Position,
     /// Collected errors
     errors: Vec<LexError>,
+    /// Emit whitespace as `Whitespace` tokens instead of skipping it
+    keep_trivia: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -45,6 +49,7 @@ impl<'a> Lexer<'a> {
             position: Position::new(1, 1),
             token_start: Position::new(1, 1),
             errors: Vec::new(),
+            keep_trivia: false,
         }
     }
 
@@ -62,10 +67,37 @@ impl<'a> Lexer<'a> {
         (tokens, self.errors)
     }
 
+    /// Lex all tokens in the source, keeping whitespace and comments
+    ///
+    /// Every character of the source belongs to exactly one token, and each
+    /// token's span starts where the previous one ended, so concatenating the
+    /// token texts reproduces the source exactly.
+    pub fn lex_all_with_trivia(mut self) -> (Vec<Token>, Vec<LexError>) {
+        self.keep_trivia = true;
+        let mut tokens = Vec::new();
+
+        while let Some(token) = self.next_token() {
+            tokens.push(token);
+        }
+
+        (tokens, self.errors)
+    }
+
     /// Get the next token
     pub fn next_token(&mut self) -> Option<Token> {
         self.token_start = self.position;
 
+        // Emit a run of whitespace as a single token when keeping trivia
+        if self.keep_trivia && self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+                self.advance();
+            }
+            return Some(Token {
+                kind: TokenKind::Whitespace,
+                span: Span::new(self.token_start, self.position),
+            });
+        }
+
         // Skip whitespace
         while let Some(&c) = self.chars.peek() {
             if c.is_whitespace() {
@@ -74,11 +106,24 @@ impl<'a> Lexer<'a> {
                 break;
             }
         }
+        self.token_start = self.position;
 
         let c = self.advance()?;
 
         let kind = match c {
             // Identifiers and keywords
+            // Raw strings, byte strings and byte literals
+            'r' if self.at_raw_string_start(0) => {
+                TokenKind::StringLiteral(self.lex_raw_string().into())
+            }
+            'b' if self.chars.peek() == Some(&'\'') => self.lex_byte_char(),
+            'b' if self.chars.peek() == Some(&'"') => self.lex_byte_string(),
+            'b' if self.chars.peek() == Some(&'r') && self.at_raw_string_start(1) => {
+                self.advance(); // consume 'r'
+                let content = self.lex_raw_string();
+                TokenKind::ByteStringLiteral(self.raw_string_bytes(&content).into())
+            }
+
             'a'..='z' | 'A'..='Z' => self.lex_identifier_or_keyword(c),
             '_' => {
                 // Check if underscore is followed by more identifier characters
@@ -90,6 +135,7 @@ impl<'a> Lexer<'a> {
                     _ => TokenKind::Underscore,
                 }
             }
+            c if is_identifier_start(c) => self.lex_identifier_or_keyword(c),
 
             // Numbers
             '0'..='9' => self.lex_number(c),
@@ -219,92 +265,152 @@ impl<'a> Lexer<'a> {
     }
 
     /// Lex a number literal (integer or float)
+    ///
+    /// Supports `0x`/`0o`/`0b` prefixes, `_` digit separators, fractions,
+    /// exponents and type suffixes (`42u8`, `1.5f32`). The token keeps the
+    /// literal as written; invalid digits and suffixes are reported here and
+    /// the value is decoded with [`parse_int_literal`]/[`parse_float_literal`].
     fn lex_number(&mut self, first: char) -> TokenKind {
         let mut num_str = String::from(first);
 
-        // Parse remaining digits
-        while let Some(&c) = self.chars.peek() {
-            if c.is_ascii_digit() {
+        // Radix prefix
+        if first == '0' {
+            if let Some(&('x' | 'o' | 'b')) = self.chars.peek() {
                 num_str.push(self.advance().unwrap());
-            } else {
-                break;
-            }
-        }
-
-        // Check for float (with decimal point)
-        if let Some(&'.') = self.chars.peek() {
-            // Look ahead to ensure it's not ".." (range)
-            self.chars.peek(); // Just peek, don't consume yet
-
-            // If it's a decimal followed by a digit, it's a float
-            if let Some('.') = self.chars.next() {
-                num_str.push('.');
                 while let Some(&c) = self.chars.peek() {
-                    if c.is_ascii_digit() {
+                    if is_identifier_continue(c) {
                         num_str.push(self.advance().unwrap());
                     } else {
                         break;
                     }
                 }
-                return TokenKind::FloatLiteral(num_str.into());
+                if let Err(kind) = parse_int_literal(&num_str) {
+                    self.report_number_error(kind);
+                }
+                return TokenKind::IntLiteral(num_str.into());
             }
         }
 
-        // Check for exponent (e.g., 1e10)
-        if let Some(&'e' | &'E') = self.chars.peek() {
-            self.advance(); // consume 'e' or 'E'
-            num_str.push('e');
+        self.lex_digits(&mut num_str);
+        let mut is_float = false;
 
-            // Optional sign
-            if let Some(&'+' | &'-') = self.chars.peek() {
-                num_str.push(self.advance().unwrap());
-            }
+        // Fraction: a '.' followed by a digit (so `1..2` and `1.foo()` still work)
+        if self.chars.peek() == Some(&'.') && self.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            num_str.push(self.advance().unwrap());
+            self.lex_digits(&mut num_str);
+            is_float = true;
+        }
 
-            // Required exponent digits
-            while let Some(&c) = self.chars.peek() {
-                if c.is_ascii_digit() {
+        // Exponent (e.g., 1e10, 2.5E-3)
+        if let Some(&('e' | 'E')) = self.chars.peek() {
+            let has_exponent = match self.peek_nth(1) {
+                Some('+' | '-') => self.peek_nth(2).is_some_and(|c| c.is_ascii_digit()),
+                Some(c) => c.is_ascii_digit(),
+                None => false,
+            };
+            if has_exponent {
+                num_str.push(self.advance().unwrap());
+                if let Some(&('+' | '-')) = self.chars.peek() {
                     num_str.push(self.advance().unwrap());
-                } else {
-                    break;
                 }
+                self.lex_digits(&mut num_str);
+                is_float = true;
             }
+        }
 
-            return TokenKind::FloatLiteral(num_str.into());
+        // Type suffix (e.g., 42i32, 3.14f64)
+        let mut suffix = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if is_identifier_continue(c) {
+                suffix.push(self.advance().unwrap());
+            } else {
+                break;
+            }
+        }
+        if FloatSuffix::from_name(&suffix).is_some() {
+            is_float = true;
         }
+        num_str.push_str(&suffix);
 
-        // Check for type suffix (e.g., 42i32, 3.14f64)
-        if let Some(&c) = self.chars.peek() {
-            if c == 'i' || c == 'u' || c == 'f' {
-                let mut type_suffix = String::new();
-                while let Some(&c) = self.chars.peek() {
-                    if c.is_ascii_alphabetic() {
-                        type_suffix.push(self.advance().unwrap());
-                    } else {
-                        break;
-                    }
-                }
-                // TODO: Validate type suffix
-                return TokenKind::IntLiteral(num_str.into());
+        if is_float {
+            if let Err(kind) = parse_float_literal(&num_str) {
+                self.report_number_error(kind);
+            }
+            TokenKind::FloatLiteral(num_str.into())
+        } else {
+            if let Err(kind) = parse_int_literal(&num_str) {
+                self.report_number_error(kind);
+            }
+            TokenKind::IntLiteral(num_str.into())
+        }
+    }
+
+    /// Consume decimal digits and `_` separators
+    fn lex_digits(&mut self, num_str: &mut String) {
+        while let Some(&c) = self.chars.peek() {
+            if c.is_ascii_digit() || c == '_' {
+                num_str.push(self.advance().unwrap());
+            } else {
+                break;
             }
         }
+    }
 
-        TokenKind::IntLiteral(num_str.into())
+    /// Peek `n` characters past the next one without consuming anything
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.clone().nth(n)
+    }
+
+    /// Record an error for the number literal starting at the current token
+    fn report_number_error(&mut self, kind: LexErrorKind) {
+        self.errors.push(LexError {
+            kind,
+            position: self.token_start,
+        });
     }
 
-    /// Lex a regular string literal
+    /// Lex a string literal (opening quote already consumed)
+    ///
+    /// A `\` at the end of a line continues the string on the next line,
+    /// skipping the line break and leading whitespace. `"""` starts an
+    /// indented multi-line string.
     fn lex_string(&mut self) -> TokenKind {
+        if self.chars.peek() == Some(&'"') && self.peek_nth(1) == Some('"') {
+            return self.lex_multiline_string();
+        }
+        TokenKind::StringLiteral(self.lex_string_body(false).into())
+    }
+
+    /// Lex the contents of a `"..."` or `b"..."` literal up to the closing quote
+    ///
+    /// In byte mode, non-ASCII characters are reported and escapes may produce
+    /// any value up to `\xFF`; the result then holds one char per byte.
+    fn lex_string_body(&mut self, byte_mode: bool) -> String {
         let mut s = String::new();
 
         while let Some(&c) = self.chars.peek() {
             match c {
                 '"' => {
                     self.advance(); // consume closing quote
-                    return TokenKind::StringLiteral(s.into());
+                    return s;
                 }
                 '\\' => {
                     self.advance(); // consume backslash
-                    if let Some(escaped) = self.advance() {
-                        s.push(self.parse_escape_sequence(escaped));
+                    match self.chars.peek() {
+                        Some('\n' | '\r') => self.skip_line_continuation(),
+                        Some(_) => {
+                            let escaped = self.advance().unwrap();
+                            let decoded = self.parse_escape_sequence(escaped);
+                            if byte_mode && (decoded as u32) > 0xFF {
+                                self.errors.push(LexError {
+                                    kind: LexErrorKind::NonAsciiByte(decoded),
+                                    position: self.token_start,
+                                });
+                            } else {
+                                s.push(decoded);
+                            }
+                        }
+                        None => {}
                     }
                 }
                 c if c == '\n' || c == '\r' => {
@@ -313,7 +419,14 @@ impl<'a> Lexer<'a> {
                         kind: LexErrorKind::UnterminatedString,
                         position: self.token_start,
                     });
-                    return TokenKind::StringLiteral(s.into());
+                    return s;
+                }
+                c if byte_mode && !c.is_ascii() => {
+                    self.advance();
+                    self.errors.push(LexError {
+                        kind: LexErrorKind::NonAsciiByte(c),
+                        position: self.token_start,
+                    });
                 }
                 _ => {
                     s.push(self.advance().unwrap());
@@ -326,9 +439,164 @@ impl<'a> Lexer<'a> {
             kind: LexErrorKind::UnterminatedString,
             position: self.token_start,
         });
+        s
+    }
+
+    /// Skip a line break and the indentation that follows it
+    fn skip_line_continuation(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    /// Lex an indented multi-line string (`"""` with the first quote consumed)
+    ///
+    /// The line break after the opening `"""` and the line holding the closing
+    /// `"""` are dropped, and the closing delimiter's indentation is removed
+    /// from every line:
+    ///
+    /// ```text
+    /// let query = """
+    ///     SELECT *
+    ///     FROM users
+    ///     """;   // => "SELECT *\nFROM users"
+    /// ```
+    fn lex_multiline_string(&mut self) -> TokenKind {
+        self.advance();
+        self.advance();
+
+        let mut raw = String::new();
+        let mut terminated = false;
+        while let Some(&c) = self.chars.peek() {
+            if c == '"' && self.peek_nth(1) == Some('"') && self.peek_nth(2) == Some('"') {
+                self.advance();
+                self.advance();
+                self.advance();
+                terminated = true;
+                break;
+            }
+            raw.push(self.advance().unwrap());
+            // Keep escaped characters raw so that `\"` cannot close the string
+            if c == '\\' {
+                if let Some(next) = self.advance() {
+                    raw.push(next);
+                }
+            }
+        }
+
+        if !terminated {
+            self.errors.push(LexError {
+                kind: LexErrorKind::UnterminatedString,
+                position: self.token_start,
+            });
+        }
+
+        let mut s = String::new();
+        let dedented = dedent(&raw);
+        let mut chars = dedented.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                s.push(c);
+                continue;
+            }
+            match chars.as_str().chars().next() {
+                Some('\n' | '\r') => {
+                    while chars.as_str().starts_with(char::is_whitespace) {
+                        chars.next();
+                    }
+                }
+                Some(_) => {
+                    let escaped = chars.next().unwrap();
+                    match decode_escape(escaped, || chars.next()) {
+                        Ok(c) => s.push(c),
+                        Err(kind) => self.errors.push(LexError {
+                            kind,
+                            position: self.token_start,
+                        }),
+                    }
+                }
+                None => {}
+            }
+        }
+
         TokenKind::StringLiteral(s.into())
     }
 
+    /// Check if a raw string (`r"` or `r#..#"`) starts `offset` characters ahead
+    fn at_raw_string_start(&self, offset: usize) -> bool {
+        let mut ahead = self.chars.clone().skip(offset).skip_while(|&c| c == '#');
+        ahead.next() == Some('"')
+    }
+
+    /// Lex a raw string after the `r`: no escapes, and line breaks are kept
+    fn lex_raw_string(&mut self) -> String {
+        let mut hashes = 0;
+        while self.chars.peek() == Some(&'#') {
+            self.advance();
+            hashes += 1;
+        }
+        self.advance(); // consume opening quote
+
+        let mut s = String::new();
+        while let Some(c) = self.advance() {
+            if c == '"' {
+                let closing = (0..hashes).all(|i| self.peek_nth(i) == Some('#'));
+                if closing {
+                    for _ in 0..hashes {
+                        self.advance();
+                    }
+                    return s;
+                }
+            }
+            s.push(c);
+        }
+
+        self.errors.push(LexError {
+            kind: LexErrorKind::UnterminatedString,
+            position: self.token_start,
+        });
+        s
+    }
+
+    /// Lex a byte string after the `b`
+    fn lex_byte_string(&mut self) -> TokenKind {
+        self.advance(); // consume opening quote
+        let content = self.lex_string_body(true);
+        TokenKind::ByteStringLiteral(content.chars().map(|c| c as u8).collect())
+    }
+
+    /// Lex a byte literal after the `b`
+    fn lex_byte_char(&mut self) -> TokenKind {
+        self.advance(); // consume opening quote
+        let escaped = self.chars.peek() == Some(&'\\');
+
+        match self.lex_char() {
+            TokenKind::CharLiteral(c) if escaped && (c as u32) <= 0xFF => {
+                TokenKind::ByteLiteral(c as u8)
+            }
+            TokenKind::CharLiteral(c) if c.is_ascii() => TokenKind::ByteLiteral(c as u8),
+            TokenKind::CharLiteral(c) => {
+                self.errors.push(LexError {
+                    kind: LexErrorKind::NonAsciiByte(c),
+                    position: self.token_start,
+                });
+                TokenKind::ByteLiteral(0)
+            }
+            other => other,
+        }
+    }
+
+    /// Convert raw byte string content to bytes, reporting non-ASCII characters
+    fn raw_string_bytes(&mut self, content: &str) -> Vec<u8> {
+        if let Some(c) = content.chars().find(|c| !c.is_ascii()) {
+            self.errors.push(LexError {
+                kind: LexErrorKind::NonAsciiByte(c),
+                position: self.token_start,
+            });
+        }
+        content.chars().filter(char::is_ascii).map(|c| c as u8).collect()
+    }
+
     /// Lex a template string (backtick delimited)
     fn lex_template_string(&mut self) -> TokenKind {
         let mut s = String::new();
@@ -434,21 +702,17 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    /// Parse escape sequences like \n, \t, \u{...}
-    fn parse_escape_sequence(&self, c: char) -> char {
-        match c {
-            'n' => '\n',
-            'r' => '\r',
-            't' => '\t',
-            '\\' => '\\',
-            '"' => '"',
-            '\'' => '\'',
-            '0' => '\0',
-            'x' | 'u' => {
-                // TODO: Implement hex and unicode escape sequences
-                c // placeholder
+    /// Parse escape sequences like \n, \t, \x7F, \u{...}
+    fn parse_escape_sequence(&mut self, c: char) -> char {
+        match decode_escape(c, || self.advance()) {
+            Ok(decoded) => decoded,
+            Err(kind) => {
+                self.errors.push(LexError {
+                    kind,
+                    position: self.token_start,
+                });
+                c
             }
-            _ => c, // Unknown escape, just return the character
         }
     }
 
@@ -492,15 +756,32 @@ impl<'a> Lexer<'a> {
         if let Some(&'/') = self.chars.peek() {
             self.advance(); // consume second '/'
 
+            // `///` (but not `////`) and `//!` are doc comments
+            let doc = match (self.chars.peek().copied(), self.peek_nth(1)) {
+                (Some('/'), second) => (second != Some('/')).then_some(false),
+                (Some('!'), _) => Some(true),
+                _ => None,
+            };
+            if doc.is_some() {
+                self.advance();
+            }
+
             // Line comment
+            let mut text = String::new();
             while let Some(&c) = self.chars.peek() {
                 if c == '\n' {
                     break;
                 }
+                text.push(c);
                 self.advance();
             }
+            let text = text.strip_suffix('\r').unwrap_or(&text);
 
-            TokenKind::Comment
+            match doc {
+                Some(false) => TokenKind::DocComment(text.into()),
+                Some(true) => TokenKind::InnerDocComment(text.into()),
+                None => TokenKind::Comment,
+            }
         } else if let Some(&'*') = self.chars.peek() {
             self.advance(); // consume '*'
 
@@ -699,8 +980,143 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// Decode an integer literal as written in source (e.g. `0xFF_u8`)
+///
+/// Returns the value and the optional type suffix.
+pub fn parse_int_literal(text: &str) -> Result<(u128, Option<IntSuffix>), LexErrorKind> {
+    let (radix, body) = match text.get(..2) {
+        Some("0x") => (16, &text[2..]),
+        Some("0o") => (8, &text[2..]),
+        Some("0b") => (2, &text[2..]),
+        _ => (10, text),
+    };
+
+    // Digits run until the first character that cannot be part of the number;
+    // for base 2/8 all decimal digits are taken so `0b102` reports the bad digit
+    let digits_end = body
+        .find(|c: char| {
+            let is_digit = if radix == 16 { c.is_ascii_hexdigit() } else { c.is_ascii_digit() };
+            !is_digit && c != '_'
+        })
+        .unwrap_or(body.len());
+    let (digits, suffix) = body.split_at(digits_end);
+
+    let digits: String = digits.chars().filter(|&c| c != '_').collect();
+    if digits.is_empty() {
+        return Err(LexErrorKind::InvalidNumber(text.to_string()));
+    }
+    if let Some(digit) = digits.chars().find(|c| !c.is_digit(radix)) {
+        return Err(LexErrorKind::InvalidDigit { digit, radix });
+    }
+
+    let suffix = match suffix {
+        "" => None,
+        name => Some(
+            IntSuffix::from_name(name)
+                .ok_or_else(|| LexErrorKind::InvalidNumberSuffix(name.to_string()))?,
+        ),
+    };
+
+    let value = u128::from_str_radix(&digits, radix).map_err(|_| LexErrorKind::IntegerTooLarge)?;
+    Ok((value, suffix))
+}
+
+/// Decode a float literal as written in source (e.g. `1_000.5e-3f32`)
+///
+/// Returns the value and the optional type suffix.
+pub fn parse_float_literal(text: &str) -> Result<(f64, Option<FloatSuffix>), LexErrorKind> {
+    // The suffix starts at the first letter that is not an exponent marker
+    let suffix_start = text
+        .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+        .unwrap_or(text.len());
+    let (body, suffix) = text.split_at(suffix_start);
+
+    let suffix = match suffix {
+        "" => None,
+        name => Some(
+            FloatSuffix::from_name(name)
+                .ok_or_else(|| LexErrorKind::InvalidNumberSuffix(name.to_string()))?,
+        ),
+    };
+
+    let body: String = body.chars().filter(|&c| c != '_').collect();
+    let value = body
+        .parse::<f64>()
+        .map_err(|_| LexErrorKind::InvalidNumber(text.to_string()))?;
+    Ok((value, suffix))
+}
+
+/// Decode the escape sequence introduced by `\c`
+///
+/// `next` yields the characters following `c`, for the `\xNN` and `\u{...}`
+/// forms. `\xNN` accepts values up to `\xFF` (byte strings need them).
+/// Unknown escapes decode to the character itself.
+fn decode_escape(c: char, mut next: impl FnMut() -> Option<char>) -> Result<char, LexErrorKind> {
+    let decoded = match c {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '\\' => '\\',
+        '"' => '"',
+        '\'' => '\'',
+        '0' => '\0',
+        'x' => {
+            let digits: String = [next(), next()].into_iter().flatten().collect();
+            let value = match u8::from_str_radix(&digits, 16) {
+                Ok(value) if digits.len() == 2 => value,
+                _ => return Err(LexErrorKind::InvalidEscapeSequence('x')),
+            };
+            char::from(value)
+        }
+        'u' => {
+            if next() != Some('{') {
+                return Err(LexErrorKind::InvalidEscapeSequence('u'));
+            }
+            let mut digits = String::new();
+            loop {
+                match next() {
+                    Some('}') => break,
+                    Some(d) if d.is_ascii_hexdigit() && digits.len() < 6 => digits.push(d),
+                    _ => return Err(LexErrorKind::InvalidEscapeSequence('u')),
+                }
+            }
+            u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(LexErrorKind::InvalidEscapeSequence('u'))?
+        }
+        _ => c, // Unknown escape, just return the character
+    };
+    Ok(decoded)
+}
+
+/// Remove the indentation of a multi-line string body
+///
+/// Drops a blank first line, and treats a whitespace-only last line as the
+/// indentation of the closing delimiter, which is stripped from every line.
+fn dedent(raw: &str) -> String {
+    let mut lines: Vec<&str> = raw.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).collect();
+
+    if lines.len() > 1 && lines[0].trim().is_empty() {
+        lines.remove(0);
+    }
+    let indent = match lines.last() {
+        Some(last) if lines.len() > 1 && last.trim().is_empty() => lines.pop().unwrap(),
+        _ => "",
+    };
+
+    let dedented: Vec<&str> = lines
+        .iter()
+        .map(|line| match line.strip_prefix(indent) {
+            Some(rest) => rest,
+            None => line.trim_start(),
+        })
+        .collect();
+
+    dedented.join("\n")
+}
+
 /// Check if character can start an identifier
-#[allow(dead_code)]
 fn is_identifier_start(c: char) -> bool {
     unicode_xid::UnicodeXID::is_xid_start(c) || c == '_'
 }
@@ -741,6 +1157,55 @@ mod tests {
         assert_eq!(tokens[1].kind, TokenKind::FloatLiteral("3.14".into()));
     }
 
+    #[test]
+    fn test_number_prefixes_separators_and_suffixes() {
+        let source = "0xFF_u8 0o17 0b1010_1010 1_000_000i64 2.5f32 7f64 1..10";
+        let lexer = Lexer::new(source);
+        let (tokens, errors) = lexer.lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::IntLiteral("0xFF_u8".into()));
+        assert_eq!(tokens[1].kind, TokenKind::IntLiteral("0o17".into()));
+        assert_eq!(tokens[2].kind, TokenKind::IntLiteral("0b1010_1010".into()));
+        assert_eq!(tokens[3].kind, TokenKind::IntLiteral("1_000_000i64".into()));
+        assert_eq!(tokens[4].kind, TokenKind::FloatLiteral("2.5f32".into()));
+        assert_eq!(tokens[5].kind, TokenKind::FloatLiteral("7f64".into()));
+        assert_eq!(tokens[6].kind, TokenKind::IntLiteral("1".into()));
+        assert_eq!(tokens[7].kind, TokenKind::DotDot);
+        assert_eq!(tokens[8].kind, TokenKind::IntLiteral("10".into()));
+    }
+
+    #[test]
+    fn test_parse_number_literals() {
+        assert_eq!(parse_int_literal("0xFF_u8"), Ok((255, Some(IntSuffix::U8))));
+        assert_eq!(parse_int_literal("0o17"), Ok((15, None)));
+        assert_eq!(parse_int_literal("0b1010_1010"), Ok((170, None)));
+        assert_eq!(
+            parse_int_literal("340282366920938463463374607431768211455u128"),
+            Ok((u128::MAX, Some(IntSuffix::U128)))
+        );
+        assert_eq!(parse_float_literal("1_000.5e-1f32"), Ok((100.05, Some(FloatSuffix::F32))));
+        assert_eq!(parse_float_literal("1e3"), Ok((1000.0, None)));
+    }
+
+    #[test]
+    fn test_invalid_number_literals() {
+        let cases = [
+            ("0b102", LexErrorKind::InvalidDigit { digit: '2', radix: 2 }),
+            ("0o8", LexErrorKind::InvalidDigit { digit: '8', radix: 8 }),
+            ("42u7", LexErrorKind::InvalidNumberSuffix("u7".to_string())),
+            ("1.5i32", LexErrorKind::InvalidNumberSuffix("i32".to_string())),
+            ("0x", LexErrorKind::InvalidNumber("0x".to_string())),
+            ("340282366920938463463374607431768211456", LexErrorKind::IntegerTooLarge),
+        ];
+
+        for (source, expected) in cases {
+            let (_tokens, errors) = Lexer::new(source).lex_all();
+            assert_eq!(errors.len(), 1, "{}: {:?}", source, errors);
+            assert_eq!(errors[0].kind, expected, "{}", source);
+        }
+    }
+
     #[test]
     fn test_strings() {
         let source = r#""hello" `multiline`"#;
@@ -751,6 +1216,103 @@ mod tests {
         assert_eq!(tokens[1].kind, TokenKind::TemplateString("multiline".into()));
     }
 
+    #[test]
+    fn test_raw_strings() {
+        let source = r####"r"C:\path\n" r#"say "hi""# r##"a "# b"## r"line1
+line2""####;
+        let (tokens, errors) = Lexer::new(source).lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::StringLiteral(r"C:\path\n".into()));
+        assert_eq!(tokens[1].kind, TokenKind::StringLiteral(r#"say "hi""#.into()));
+        assert_eq!(tokens[2].kind, TokenKind::StringLiteral(r##"a "# b"##.into()));
+        assert_eq!(tokens[3].kind, TokenKind::StringLiteral("line1\nline2".into()));
+    }
+
+    #[test]
+    fn test_raw_identifier_prefix_is_not_a_string() {
+        let (tokens, _errors) = Lexer::new("r b rb").lex_all();
+        assert_eq!(tokens[0].kind, TokenKind::Ident("r".into()));
+        assert_eq!(tokens[1].kind, TokenKind::Ident("b".into()));
+        assert_eq!(tokens[2].kind, TokenKind::Ident("rb".into()));
+    }
+
+    #[test]
+    fn test_byte_strings_and_bytes() {
+        let source = r#"b"GET\r\n" b"\x00\xFF" br"\d+" b'x' b'\n' b'\xFF'"#;
+        let (tokens, errors) = Lexer::new(source).lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::ByteStringLiteral(b"GET\r\n".to_vec().into()));
+        assert_eq!(tokens[1].kind, TokenKind::ByteStringLiteral(vec![0x00, 0xFF].into()));
+        assert_eq!(tokens[2].kind, TokenKind::ByteStringLiteral(br"\d+".to_vec().into()));
+        assert_eq!(tokens[3].kind, TokenKind::ByteLiteral(b'x'));
+        assert_eq!(tokens[4].kind, TokenKind::ByteLiteral(b'\n'));
+        assert_eq!(tokens[5].kind, TokenKind::ByteLiteral(0xFF));
+    }
+
+    #[test]
+    fn test_non_ascii_byte_literals() {
+        for source in ["b\"caf\u{e9}\"", "b'\u{e9}'", r#"b"\u{1F600}""#] {
+            let (_tokens, errors) = Lexer::new(source).lex_all();
+            assert_eq!(errors.len(), 1, "{}: {:?}", source, errors);
+            assert!(matches!(errors[0].kind, LexErrorKind::NonAsciiByte(_)));
+        }
+    }
+
+    #[test]
+    fn test_hex_and_unicode_escapes() {
+        let (tokens, errors) = Lexer::new(r#""\x41\u{1F600}\u{e9}""#).lex_all();
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::StringLiteral("A\u{1F600}\u{e9}".into()));
+
+        let (_tokens, errors) = Lexer::new(r#""\xZZ" "\u{110000}""#).lex_all();
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_string_line_continuation() {
+        let source = "\"hello \\\n        world\"";
+        let (tokens, errors) = Lexer::new(source).lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::StringLiteral("hello world".into()));
+    }
+
+    #[test]
+    fn test_indented_multiline_string() {
+        let source = "let q = \"\"\"\n        SELECT *\n          FROM t\n\n        WHERE a = \"x\" \\\n          AND b\n        \"\"\";";
+        let (tokens, errors) = Lexer::new(source).lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(
+            tokens[3].kind,
+            TokenKind::StringLiteral("SELECT *\n  FROM t\n\nWHERE a = \"x\" AND b".into())
+        );
+        assert_eq!(tokens[4].kind, TokenKind::Semicolon);
+    }
+
+    #[test]
+    fn test_unterminated_multiline_string() {
+        let (_tokens, errors) = Lexer::new("\"\"\"\n  never closed").lex_all();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, LexErrorKind::UnterminatedString);
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        let source = "//! Crate docs\n/// Item docs\n//// not docs\n// plain\n///\nfn";
+        let (tokens, errors) = Lexer::new(source).lex_all();
+
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(tokens[0].kind, TokenKind::InnerDocComment(" Crate docs".into()));
+        assert_eq!(tokens[1].kind, TokenKind::DocComment(" Item docs".into()));
+        assert_eq!(tokens[2].kind, TokenKind::Comment);
+        assert_eq!(tokens[3].kind, TokenKind::Comment);
+        assert_eq!(tokens[4].kind, TokenKind::DocComment("".into()));
+        assert_eq!(tokens[5].kind, TokenKind::Fn);
+    }
+
     #[test]
     fn test_fat_arrow() {
         let source = "=>";
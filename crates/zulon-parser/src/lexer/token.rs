@@ -80,10 +80,14 @@ pub enum TokenKind {
     IntLiteral(Box<str>),
     /// Float literal (e.g., 3.14, 1e10, 1.5f64)
     FloatLiteral(Box<str>),
-    /// String literal (e.g., "hello")
+    /// String literal (e.g., "hello", r#"raw"#, multi-line """...""")
     StringLiteral(Box<str>),
     /// Character literal (e.g., 'a', '\n')
     CharLiteral(char),
+    /// Byte string literal (e.g., b"GET", br"\d+")
+    ByteStringLiteral(Box<[u8]>),
+    /// Byte literal (e.g., b'x', b'\xFF')
+    ByteLiteral(u8),
     /// Template string (backtick delimited, with interpolation)
     TemplateString(Box<str>),
 
@@ -163,6 +167,10 @@ pub enum TokenKind {
     Whitespace,
     /// Comment (line or block)
     Comment,
+    /// Outer doc comment: `/// text` (holds the text after `///`)
+    DocComment(Box<str>),
+    /// Inner doc comment: `//! text` (holds the text after `//!`)
+    InnerDocComment(Box<str>),
 
     /// Unknown/invalid token
     Unknown,
@@ -176,14 +184,155 @@ impl fmt::Display for TokenKind {
             TokenKind::FloatLiteral(n) => write!(f, "float({})", n),
             TokenKind::StringLiteral(s) => write!(f, "string(\"{}\")", s),
             TokenKind::CharLiteral(c) => write!(f, "char('{}')", c),
+            TokenKind::ByteStringLiteral(b) => {
+                write!(f, "bytes(b\"{}\")", b.escape_ascii())
+            }
+            TokenKind::ByteLiteral(b) => write!(f, "byte(b'{}')", b.escape_ascii()),
             TokenKind::TemplateString(s) => write!(f, "template({})", s),
             _ => write!(f, "{:?}", self),
         }
     }
 }
 
+impl TokenKind {
+    /// Source text that lexes back to this token
+    ///
+    /// Literals are re-escaped. Whitespace, comments and unknown tokens have
+    /// no source form and produce an empty string.
+    pub fn to_source(&self) -> String {
+        let text = match self {
+            TokenKind::Ident(s) | TokenKind::IntLiteral(s) | TokenKind::FloatLiteral(s) => return s.to_string(),
+            TokenKind::StringLiteral(s) => return quote(s.chars(), '"'),
+            TokenKind::CharLiteral(c) => return quote(std::iter::once(*c), '\''),
+            TokenKind::ByteStringLiteral(bytes) => return format!("b\"{}\"", bytes.escape_ascii()),
+            TokenKind::ByteLiteral(b) => return format!("b'{}'", b.escape_ascii()),
+            TokenKind::TemplateString(s) => return format!("`{}`", s),
+            TokenKind::DocComment(s) => return format!("///{}", s),
+            TokenKind::InnerDocComment(s) => return format!("//!{}", s),
+
+            TokenKind::If => "if",
+            TokenKind::Else => "else",
+            TokenKind::Match => "match",
+            TokenKind::Loop => "loop",
+            TokenKind::While => "while",
+            TokenKind::For => "for",
+            TokenKind::In => "in",
+            TokenKind::Break => "break",
+            TokenKind::Continue => "continue",
+            TokenKind::Return => "return",
+            TokenKind::Defer => "defer",
+            TokenKind::Extern => "extern",
+            TokenKind::Async => "async",
+            TokenKind::Fn => "fn",
+            TokenKind::Struct => "struct",
+            TokenKind::Enum => "enum",
+            TokenKind::Trait => "trait",
+            TokenKind::Impl => "impl",
+            TokenKind::Type => "type",
+            TokenKind::Let => "let",
+            TokenKind::Mut => "mut",
+            TokenKind::Const => "const",
+            TokenKind::Static => "static",
+            TokenKind::Pub => "pub",
+            TokenKind::Use => "use",
+            TokenKind::Mod => "mod",
+            TokenKind::Where => "where",
+            TokenKind::Error => "error",
+            TokenKind::Effect => "effect",
+            TokenKind::Throw => "throw",
+            TokenKind::Perform => "perform",
+            TokenKind::Try => "try",
+            TokenKind::With => "with",
+            TokenKind::True => "true",
+            TokenKind::False => "false",
+            TokenKind::Null => "null",
+            TokenKind::Bool => "bool",
+            TokenKind::Char => "char",
+            TokenKind::Str => "str",
+
+            TokenKind::Plus => "+",
+            TokenKind::Minus => "-",
+            TokenKind::Star => "*",
+            TokenKind::Slash => "/",
+            TokenKind::Percent => "%",
+            TokenKind::Caret => "^",
+            TokenKind::PlusEq => "+=",
+            TokenKind::MinusEq => "-=",
+            TokenKind::StarEq => "*=",
+            TokenKind::SlashEq => "/=",
+            TokenKind::PercentEq => "%=",
+            TokenKind::CaretEq => "^=",
+            TokenKind::AmpersandEq => "&=",
+            TokenKind::PipeEq => "|=",
+            TokenKind::LeftShiftEq => "<<=",
+            TokenKind::RightShiftEq => ">>=",
+            TokenKind::Equals => "=",
+            TokenKind::EqEq => "==",
+            TokenKind::BangEq => "!=",
+            TokenKind::Less => "<",
+            TokenKind::LessEq => "<=",
+            TokenKind::Greater => ">",
+            TokenKind::GreaterEq => ">=",
+            TokenKind::And => "&&",
+            TokenKind::Or => "||",
+            TokenKind::Bang => "!",
+            TokenKind::Ampersand => "&",
+            TokenKind::Pipe => "|",
+            TokenKind::LeftShift => "<<",
+            TokenKind::RightShift => ">>",
+            TokenKind::Arrow => "->",
+            TokenKind::FatArrow => "=>",
+            TokenKind::Dot => ".",
+            TokenKind::DotDot => "..",
+            TokenKind::DotDotDot => "...",
+            TokenKind::DotDotEq => "..=",
+            TokenKind::PathSep => "::",
+            TokenKind::Question => "?",
+            TokenKind::Underscore => "_",
+
+            TokenKind::LeftParen => "(",
+            TokenKind::RightParen => ")",
+            TokenKind::LeftBrace => "{",
+            TokenKind::RightBrace => "}",
+            TokenKind::LeftBracket => "[",
+            TokenKind::RightBracket => "]",
+            TokenKind::Colon => ":",
+            TokenKind::Semicolon => ";",
+            TokenKind::Comma => ",",
+            TokenKind::At => "@",
+            TokenKind::Hash => "#",
+            TokenKind::Dollar => "$",
+
+            TokenKind::Whitespace | TokenKind::Comment | TokenKind::Unknown => "",
+        };
+        text.to_string()
+    }
+}
+
+/// Quote and escape string or char literal contents
+fn quote(chars: impl Iterator<Item = char>, quote: char) -> String {
+    let mut out = String::new();
+    out.push(quote);
+    for c in chars {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\0' => out.push_str("\\0"),
+            '\\' => out.push_str("\\\\"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
 /// A position in source code (1-indexed)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
@@ -202,7 +351,7 @@ impl fmt::Display for Position {
 }
 
 /// A span in source code
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Span {
     pub start: Position,
     pub end: Position,
@@ -21,6 +21,19 @@
 //! }
 //! ```
 //!
+//! ## Concrete Syntax Tree
+//!
+//! For tooling (formatter, LSP), [`cst::SyntaxTree`] keeps whitespace and
+//! comments as trivia and prints back the exact source:
+//!
+//! ```rust
+//! use zulon_parser::cst::SyntaxTree;
+//!
+//! let source = "fn main() {} // entry point\n";
+//! let tree = SyntaxTree::parse(source);
+//! assert_eq!(tree.to_string(), source);
+//! ```
+//!
 //! ## Features
 //!
 //! - Unicode identifier support
@@ -33,7 +46,14 @@ pub mod lexer;
 pub mod ast;
 pub mod parser;
 pub mod diagnostic;
+pub mod cst;
+pub mod cfg;
+pub mod format;
+pub mod source_map;
 
 pub use lexer::{Lexer, Token, TokenKind, Span, Position, LexError, LexErrorKind};
 pub use ast::*;
-pub use parser::{Parser, ParseError, ParseResult};
+pub use parser::{FragmentKind, Parser, ParseError, ParseResult, MAX_NESTING_DEPTH};
+pub use cst::{SyntaxTree, SyntaxToken, Trivia, TriviaKind};
+pub use format::{FormatError, FormatSpec};
+pub use source_map::{SourceFile, SourceMap};
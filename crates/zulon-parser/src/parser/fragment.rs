@@ -0,0 +1,185 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Macro fragment parsing
+//!
+//! Macro patterns can bind a piece of syntax with a fragment specifier, as in
+//! `$e:expr`. The macro matcher asks the parser how many tokens of its input
+//! form such a fragment.
+
+use super::{matching_close, Parser};
+use crate::lexer::{Token, TokenKind};
+
+/// The kind of syntax a macro variable matches: `$name:kind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FragmentKind {
+    /// `expr`: an expression
+    Expr,
+    /// `ty`: a type
+    Ty,
+    /// `pat`: a pattern
+    Pat,
+    /// `block`: a block expression
+    Block,
+    /// `item`: an item
+    Item,
+    /// `path`: a path such as `a::b`
+    Path,
+    /// `ident`: an identifier (not a keyword)
+    Ident,
+    /// `literal`: a literal, optionally negated
+    Literal,
+    /// `tt`: a single token or a delimited group
+    Tt,
+}
+
+impl FragmentKind {
+    /// Look up a fragment specifier by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "expr" => FragmentKind::Expr,
+            "ty" => FragmentKind::Ty,
+            "pat" => FragmentKind::Pat,
+            "block" => FragmentKind::Block,
+            "item" => FragmentKind::Item,
+            "path" => FragmentKind::Path,
+            "ident" => FragmentKind::Ident,
+            "literal" => FragmentKind::Literal,
+            "tt" => FragmentKind::Tt,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    /// The specifier as written after the colon
+    pub fn name(self) -> &'static str {
+        match self {
+            FragmentKind::Expr => "expr",
+            FragmentKind::Ty => "ty",
+            FragmentKind::Pat => "pat",
+            FragmentKind::Block => "block",
+            FragmentKind::Item => "item",
+            FragmentKind::Path => "path",
+            FragmentKind::Ident => "ident",
+            FragmentKind::Literal => "literal",
+            FragmentKind::Tt => "tt",
+        }
+    }
+}
+
+impl Parser {
+    /// Number of leading tokens that form a fragment of the given kind
+    ///
+    /// Returns `None` if `tokens` does not start with such a fragment. The
+    /// fragment is the longest one the parser accepts, so `1 + 2, 3` has an
+    /// `expr` of three tokens.
+    pub fn fragment_len(tokens: &[Token], kind: FragmentKind) -> Option<usize> {
+        let first = &tokens.first()?.kind;
+        match kind {
+            FragmentKind::Ident => return matches!(first, TokenKind::Ident(_)).then_some(1),
+            FragmentKind::Tt => return token_tree_len(tokens),
+            FragmentKind::Literal => {
+                let start = usize::from(*first == TokenKind::Minus);
+                return tokens.get(start).filter(|token| is_literal(&token.kind)).map(|_| start + 1);
+            }
+            _ => {}
+        }
+
+        let mut parser = Parser::new(tokens.to_vec());
+        let parsed = match kind {
+            FragmentKind::Expr => parser.parse_expression().is_ok(),
+            FragmentKind::Ty => parser.parse_type().is_ok(),
+            FragmentKind::Pat => parser.parse_pattern().is_ok(),
+            FragmentKind::Block => *first == TokenKind::LeftBrace && parser.parse_block().is_ok(),
+            FragmentKind::Item => matches!(parser.parse_item(), Ok(Some(_))),
+            FragmentKind::Path => matches!(first, TokenKind::Ident(_)) && parser.parse_path().is_ok(),
+            FragmentKind::Ident | FragmentKind::Literal | FragmentKind::Tt => unreachable!(),
+        };
+
+        let remaining = parser.tokens.len() + usize::from(parser.current.is_some());
+        let consumed = tokens.len() - remaining;
+        (parsed && consumed > 0).then_some(consumed)
+    }
+}
+
+fn is_literal(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::IntLiteral(_)
+            | TokenKind::FloatLiteral(_)
+            | TokenKind::StringLiteral(_)
+            | TokenKind::CharLiteral(_)
+            | TokenKind::ByteStringLiteral(_)
+            | TokenKind::ByteLiteral(_)
+            | TokenKind::True
+            | TokenKind::False
+    )
+}
+
+/// Length of the token tree at the start of `tokens`
+fn token_tree_len(tokens: &[Token]) -> Option<usize> {
+    let Some(close) = matching_close(&tokens.first()?.kind) else {
+        let is_close = matches!(
+            tokens[0].kind,
+            TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace
+        );
+        return (!is_close).then_some(1);
+    };
+
+    let mut expected = vec![close];
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        if let Some(close) = matching_close(&token.kind) {
+            expected.push(close);
+        } else if matches!(token.kind, TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace) {
+            if expected.pop() != Some(token.kind.clone()) {
+                return None;
+            }
+            if expected.is_empty() {
+                return Some(i + 1);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+
+    fn len(source: &str, kind: FragmentKind) -> Option<usize> {
+        let (tokens, _) = Lexer::new(source).lex_all();
+        Parser::fragment_len(&tokens, kind)
+    }
+
+    #[test]
+    fn test_fragment_lengths() {
+        assert_eq!(len("1 + f(2, 3), x", FragmentKind::Expr), Some(8));
+        assert_eq!(len("Vec<i32> = x", FragmentKind::Ty), Some(4));
+        assert_eq!(len("Some(x) => 1", FragmentKind::Pat), Some(4));
+        assert_eq!(len("{ 1 } + 2", FragmentKind::Block), Some(3));
+        assert_eq!(len("a::b::c + 1", FragmentKind::Path), Some(5));
+        assert_eq!(len("name, x", FragmentKind::Ident), Some(1));
+        assert_eq!(len("-1.5 x", FragmentKind::Literal), Some(2));
+        assert_eq!(len("(a [b]) c", FragmentKind::Tt), Some(6));
+        assert_eq!(len("struct S {} fn f() {}", FragmentKind::Item), Some(4));
+    }
+
+    #[test]
+    fn test_fragment_mismatches() {
+        assert_eq!(len("let", FragmentKind::Ident), None);
+        assert_eq!(len("x", FragmentKind::Literal), None);
+        assert_eq!(len("x", FragmentKind::Block), None);
+        assert_eq!(len(")", FragmentKind::Tt), None);
+        assert_eq!(len(", 1", FragmentKind::Expr), None);
+        assert_eq!(len("", FragmentKind::Expr), None);
+    }
+
+    #[test]
+    fn test_from_name_round_trips() {
+        for name in ["expr", "ty", "pat", "block", "item", "path", "ident", "literal", "tt"] {
+            assert_eq!(FragmentKind::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(FragmentKind::from_name("stmt"), None);
+    }
+}
@@ -5,11 +5,16 @@
 //!
 //! The parser converts tokens into an Abstract Syntax Tree (AST).
 
-use crate::lexer::{Lexer, Token, TokenKind, Span};
+use crate::lexer::{parse_float_literal, parse_int_literal, Lexer, Token, TokenKind, Span, Position};
 use crate::ast::*;
+use crate::format::{advance_position, Argument, FormatError, FormatSpec};
 use std::iter::Peekable;
 use std::vec::IntoIter;
 
+mod fragment;
+
+pub use fragment::FragmentKind;
+
 /// Parser error
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseError {
@@ -36,17 +41,35 @@ pub enum ParseError {
         #[source]
         source: Box<ParseError>,
     },
+
+    #[error(transparent)]
+    Format(#[from] FormatError),
 }
 
 /// Parser result type
 pub type ParseResult<T> = Result<T, ParseError>;
 
+/// Maximum nesting of expressions, blocks, types, patterns and modules
+///
+/// The parser is recursive descent, so unbounded nesting would overflow the
+/// stack instead of producing an error.
+pub const MAX_NESTING_DEPTH: usize = 128;
+
 /// The ZULON parser
 pub struct Parser {
     /// Tokens from the lexer
     tokens: Peekable<IntoIter<Token>>,
     /// Current token
     current: Option<Token>,
+    /// Whether to recover from syntax errors instead of stopping at the first one
+    recovering: bool,
+    /// Errors collected while recovering
+    errors: Vec<ParseError>,
+    /// Current nesting depth, bounded by [`MAX_NESTING_DEPTH`]
+    depth: usize,
+    /// Set while parsing a condition or scrutinee, where `Path {` opens the
+    /// body block rather than a struct literal
+    no_struct_literals: bool,
 }
 
 impl Parser {
@@ -58,6 +81,10 @@ impl Parser {
         Parser {
             tokens,
             current,
+            recovering: false,
+            errors: Vec::new(),
+            depth: 0,
+            no_struct_literals: false,
         }
     }
 
@@ -77,6 +104,7 @@ impl Parser {
 
     /// Parse an entire compilation unit
     pub fn parse(&mut self) -> ParseResult<Ast> {
+        let attributes = self.parse_inner_attributes()?;
         let mut items = Vec::new();
 
         while !self.is_at_end() {
@@ -85,7 +113,234 @@ impl Parser {
             }
         }
 
-        Ok(Ast::new(items))
+        let mut ast = Ast::new(items);
+        ast.attributes = attributes;
+        Ok(ast)
+    }
+
+    /// Parse an entire compilation unit, recovering from syntax errors
+    ///
+    /// Unlike [`Parser::parse`], this does not stop at the first error. The parser
+    /// resynchronizes at item, statement and delimiter boundaries, replacing the
+    /// broken pieces with `Error` nodes, and returns the partial AST together with
+    /// every error it encountered (in source order).
+    pub fn parse_with_recovery(&mut self) -> (Ast, Vec<ParseError>) {
+        self.recovering = true;
+        let attributes = match self.parse_inner_attributes() {
+            Ok(attributes) => attributes,
+            Err(error) => {
+                self.errors.push(error);
+                Vec::new()
+            }
+        };
+        let mut items = Vec::new();
+
+        while !self.is_at_end() {
+            let span = self.current_span();
+            match self.parse_item() {
+                Ok(Some(item)) => items.push(item),
+                Ok(None) => {}
+                Err(error) => {
+                    self.errors.push(error);
+                    self.synchronize_item(span);
+                    items.push(Item {
+                        span,
+                        attributes: Vec::new(),
+                        visibility: Visibility::Private,
+                        kind: ItemKind::Error,
+                    });
+                }
+            }
+        }
+
+        self.recovering = false;
+        let mut ast = Ast::new(items);
+        ast.attributes = attributes;
+        (ast, std::mem::take(&mut self.errors))
+    }
+
+    /// Record an error and continue if recovering, otherwise propagate it
+    ///
+    /// Errors at the end of input are always propagated, since there is nothing
+    /// left to resynchronize on.
+    fn recover(&mut self, error: ParseError) -> ParseResult<()> {
+        if !self.recovering || self.is_at_end() {
+            return Err(error);
+        }
+        self.errors.push(error);
+        Ok(())
+    }
+
+    /// Run a parse function one nesting level deeper
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("nesting exceeds the limit of {} levels", MAX_NESTING_DEPTH),
+                span: self.current_span(),
+            });
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Count one more link of an operator chain such as `a + b + c` or
+    /// `a.b().c`
+    ///
+    /// The chain is parsed in a loop, but each link nests the expression so
+    /// far one level deeper, so long chains count against the nesting limit.
+    fn chain_link(&self, links: &mut usize) -> ParseResult<()> {
+        *links += 1;
+        if self.depth + *links > MAX_NESTING_DEPTH {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("nesting exceeds the limit of {} levels", MAX_NESTING_DEPTH),
+                span: self.current_span(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Run `parse` with struct literals allowed or not, restoring the
+    /// previous setting afterwards
+    fn with_struct_literals<T>(
+        &mut self,
+        allowed: bool,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let previous = std::mem::replace(&mut self.no_struct_literals, !allowed);
+        let result = parse(self);
+        self.no_struct_literals = previous;
+        result
+    }
+
+    /// Check if the current token can start an item
+    fn at_item_start(&self) -> bool {
+        matches!(
+            self.current_kind(),
+            Some(
+                TokenKind::Fn
+                    | TokenKind::Async
+                    | TokenKind::Extern
+                    | TokenKind::Struct
+                    | TokenKind::Enum
+                    | TokenKind::Trait
+                    | TokenKind::Impl
+                    | TokenKind::Type
+                    | TokenKind::Const
+                    | TokenKind::Static
+                    | TokenKind::Mod
+                    | TokenKind::Use
+                    | TokenKind::Effect
+                    | TokenKind::Pub
+                    | TokenKind::Hash
+                    | TokenKind::DocComment(_)
+            )
+        )
+    }
+
+    /// Skip tokens until the start of the next top-level item
+    ///
+    /// `start` is the span of the token the failed item began at; if the parser
+    /// has not moved past it, the offending token is skipped so that recovery
+    /// always makes progress.
+    fn synchronize_item(&mut self, start: Span) {
+        let mut depth = 0usize;
+        if self.current_span() == start {
+            if let Some(token) = self.advance() {
+                if token.kind == TokenKind::LeftBrace {
+                    depth += 1;
+                }
+            }
+        }
+
+        while !self.is_at_end() {
+            if depth == 0 && self.at_item_start() {
+                return;
+            }
+            match self.current_kind() {
+                Some(TokenKind::LeftBrace) => depth += 1,
+                Some(TokenKind::RightBrace) => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    /// Skip tokens until the end of the current statement
+    ///
+    /// Stops after a `;`, before the `}` that closes the enclosing block, or
+    /// before a keyword that starts a new statement.
+    fn synchronize_statement(&mut self, start: Span) {
+        let mut depth = 0usize;
+
+        while !self.is_at_end() {
+            let progressed = self.current_span() != start;
+            match self.current_kind() {
+                Some(TokenKind::Semicolon) if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                Some(TokenKind::RightBrace) if depth == 0 => return,
+                Some(TokenKind::Let | TokenKind::Defer | TokenKind::Return)
+                    if depth == 0 && progressed => return,
+                Some(TokenKind::LeftBrace) => depth += 1,
+                Some(TokenKind::RightBrace) => depth -= 1,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    /// Skip tokens until the given closing delimiter (or a separating comma)
+    ///
+    /// Nested `()`, `[]` and `{}` groups are skipped as a whole. The closing
+    /// delimiter itself is not consumed.
+    fn synchronize_delimited(&mut self, close: &TokenKind) {
+        let mut depth = 0usize;
+
+        while !self.is_at_end() {
+            match self.current_kind() {
+                Some(kind) if depth == 0 && (kind == close || *kind == TokenKind::Comma) => return,
+                // Never run past the end of the enclosing block
+                Some(TokenKind::RightBrace | TokenKind::Semicolon) if depth == 0 => return,
+                Some(TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace) => {
+                    depth += 1
+                }
+                Some(TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace) => {
+                    depth = depth.saturating_sub(1)
+                }
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    /// Decode an integer or float literal token
+    fn number_literal(token: &Token) -> ParseResult<Literal> {
+        let result = match &token.kind {
+            TokenKind::IntLiteral(s) => {
+                parse_int_literal(s).map(|(value, suffix)| Literal::Int(value, suffix))
+            }
+            TokenKind::FloatLiteral(s) => {
+                parse_float_literal(s).map(|(value, suffix)| Literal::Float(value, suffix))
+            }
+            _ => unreachable!("number_literal called on a non-number token"),
+        };
+
+        result.map_err(|kind| ParseError::InvalidSyntax {
+            message: kind.to_string(),
+            span: token.span,
+        })
+    }
+
+    /// Create a placeholder expression for a recovered error
+    fn error_expression(span: Span) -> Expression {
+        Expression {
+            span,
+            kind: ExpressionKind::Error,
+        }
     }
 
     /// Check if we're at the end of input
@@ -158,21 +413,29 @@ impl Parser {
             return Ok(None);
         }
 
-        // Parse attributes before the item (e.g., #[test])
-        let mut attributes = Vec::new();
-        while self.check(&TokenKind::Hash) {
-            attributes.push(self.parse_attribute()?);
-        }
+        // Parse attributes before the item (e.g., #[test], `///` docs)
+        let attributes = self.parse_outer_attributes()?;
+        self.parse_item_with_attributes(span, attributes).map(Some)
+    }
 
-        // Check for visibility modifier
-        let _visibility = if self.check(&TokenKind::Pub) {
-            self.advance();
-            Some(Visibility::Public)
-        } else {
-            Some(Visibility::Private)
-        };
+    /// Parse the rest of an item whose outer attributes were already parsed
+    fn parse_item_with_attributes(
+        &mut self,
+        span: Span,
+        attributes: Vec<Attribute>,
+    ) -> ParseResult<Item> {
+        let visibility = self.parse_visibility()?;
+
+        // `macro` is only a keyword in item position, before the macro's name
+        let is_macro_def = matches!(self.current_kind(), Some(TokenKind::Ident(name)) if &**name == "macro")
+            && matches!(self.peek_kind(), Some(TokenKind::Ident(_)));
+        let is_const_fn = self.check(&TokenKind::Const) && matches!(self.peek_kind(), Some(TokenKind::Fn));
 
         let kind = match self.current_kind() {
+            _ if is_macro_def => {
+                let macro_def = self.parse_macro_def(visibility == Visibility::Public)?;
+                ItemKind::MacroDef(macro_def)
+            }
             Some(TokenKind::Extern) => {
                 self.advance();
                 if self.check(&TokenKind::Fn) {
@@ -255,7 +518,8 @@ impl Parser {
                         },
                         is_async: false,
                         is_unsafe: false,
-                        attributes,
+                        is_const: false,
+                        attributes: Vec::new(),
                     };
 
                     ItemKind::ExternFunction(func)
@@ -267,16 +531,12 @@ impl Parser {
                 }
             }
             Some(TokenKind::Fn) => {
-                let mut func = self.parse_function()?;
-                // Add attributes parsed before the item
-                func.attributes.extend(attributes);
+                let func = self.parse_function()?;
                 ItemKind::Function(func)
             }
             Some(TokenKind::Async) => {
                 // async fn should be handled by parse_function
-                let mut func = self.parse_function()?;
-                // Add attributes parsed before the item
-                func.attributes.extend(attributes);
+                let func = self.parse_function()?;
                 ItemKind::Function(func)
             }
             Some(TokenKind::Struct) => {
@@ -299,6 +559,10 @@ impl Parser {
                 let type_alias = self.parse_type_alias()?;
                 ItemKind::TypeAlias(type_alias)
             }
+            Some(TokenKind::Const) if is_const_fn => {
+                let func = self.parse_function()?;
+                ItemKind::Function(func)
+            }
             Some(TokenKind::Const) => {
                 let const_def = self.parse_const()?;
                 ItemKind::Const(const_def)
@@ -312,7 +576,7 @@ impl Parser {
                 ItemKind::Module(module)
             }
             Some(TokenKind::Use) => {
-                let use_stmt = self.parse_use()?;
+                let use_stmt = self.parse_use(visibility != Visibility::Private)?;
                 ItemKind::Use(use_stmt)
             }
             Some(TokenKind::Effect) => {
@@ -327,11 +591,155 @@ impl Parser {
             }
         };
 
-        Ok(Some(Item { span, kind }))
+        Ok(Item {
+            span,
+            attributes,
+            visibility,
+            kind,
+        })
+    }
+
+    /// Parse an optional visibility: `pub`, `pub(crate)`, `pub(self)`,
+    /// `pub(super)` or `pub(in path)`
+    fn parse_visibility(&mut self) -> ParseResult<Visibility> {
+        if !self.check(&TokenKind::Pub) {
+            return Ok(Visibility::Private);
+        }
+        self.advance();
+        if !self.check(&TokenKind::LeftParen) {
+            return Ok(Visibility::Public);
+        }
+        self.advance();
+
+        let visibility = match self.current_kind() {
+            Some(TokenKind::Ident(name)) if &**name == "crate" => {
+                self.advance();
+                Visibility::Crate
+            }
+            Some(TokenKind::Ident(name)) if &**name == "self" || &**name == "super" => {
+                Visibility::Restricted(vec![self.parse_identifier()?])
+            }
+            Some(TokenKind::In) => {
+                self.advance();
+                let mut path = vec![self.parse_identifier()?];
+                while self.check(&TokenKind::PathSep) {
+                    self.advance();
+                    path.push(self.parse_identifier()?);
+                }
+                Visibility::Restricted(path)
+            }
+            _ => {
+                return Err(ParseError::InvalidSyntax {
+                    message: "expected `crate`, `self`, `super` or `in path` after `pub(`".to_string(),
+                    span: self.current_span(),
+                });
+            }
+        };
+
+        self.consume(TokenKind::RightParen)?;
+        Ok(visibility)
+    }
+
+    /// Parse a macro definition: `macro name { (pattern) => { template } ... }`
+    ///
+    /// Rules may be separated by `;` or `,`, and each pattern and template may
+    /// use any of `()`, `[]` or `{}`.
+    fn parse_macro_def(&mut self, is_pub: bool) -> ParseResult<MacroDef> {
+        // Consume `macro`
+        self.advance();
+        let name = self.parse_identifier()?;
+        self.consume(TokenKind::LeftBrace)?;
+
+        let mut rules = Vec::new();
+        while !self.check(&TokenKind::RightBrace) {
+            let span = self.current_span();
+            let pattern = self.parse_delimited_tokens()?;
+            self.consume(TokenKind::FatArrow)?;
+            let template = self.parse_delimited_tokens()?;
+            rules.push(MacroRuleDef {
+                span,
+                pattern,
+                template,
+            });
+
+            if self.check(&TokenKind::Semicolon) || self.check(&TokenKind::Comma) {
+                self.advance();
+            }
+        }
+        self.consume(TokenKind::RightBrace)?;
+
+        if rules.is_empty() {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("macro `{}` has no rules", name.name),
+                span: name.span,
+            });
+        }
+
+        Ok(MacroDef { name, is_pub, rules })
+    }
+
+    /// Parse a delimited token tree and return the tokens inside it
+    fn parse_delimited_tokens(&mut self) -> ParseResult<Vec<Token>> {
+        let mut expected = match self.current_kind().and_then(matching_close) {
+            Some(close) => vec![close],
+            None => {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "delimiter (, {, or [".to_string(),
+                    found: self.current_kind().cloned().unwrap_or(TokenKind::Unknown),
+                    span: self.current_span(),
+                })
+            }
+        };
+        self.advance();
+
+        let mut tokens = Vec::new();
+        loop {
+            let Some(token) = self.current.clone() else {
+                return Err(ParseError::UnexpectedEof {
+                    span: self.current_span(),
+                });
+            };
+
+            if let Some(close) = matching_close(&token.kind) {
+                expected.push(close);
+            } else if matches!(token.kind, TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace) {
+                if expected.pop() != Some(token.kind.clone()) {
+                    return Err(ParseError::UnexpectedToken {
+                        expected: "matching closing delimiter".to_string(),
+                        found: token.kind,
+                        span: token.span,
+                    });
+                }
+                if expected.is_empty() {
+                    self.advance();
+                    return Ok(tokens);
+                }
+            }
+
+            tokens.push(token);
+            self.advance();
+        }
     }
 
     /// Parse a function definition
     fn parse_function(&mut self) -> ParseResult<Function> {
+        self.parse_function_or_signature(false).map(|(func, _)| func)
+    }
+
+    /// Parse a function definition, or if `allow_signature` is set, a
+    /// signature ending in `;` as in a trait
+    ///
+    /// Also returns whether the function has a body. A signature gets an
+    /// empty one.
+    fn parse_function_or_signature(&mut self, allow_signature: bool) -> ParseResult<(Function, bool)> {
+        // Check for const modifier
+        let is_const = if self.check(&TokenKind::Const) {
+            self.advance();
+            true
+        } else {
+            false
+        };
+
         // Check for async modifier
         let is_async = if self.check(&TokenKind::Async) {
             self.advance();
@@ -345,7 +753,7 @@ impl Parser {
         let name = self.parse_identifier()?;
 
         // Parse generics
-        let generics = if self.check(&TokenKind::Less) {
+        let mut generics = if self.check(&TokenKind::Less) {
             Some(self.parse_generics()?)
         } else {
             None
@@ -420,10 +828,23 @@ impl Parser {
             }
         }
 
+        self.parse_where_clause(&mut generics)?;
+
         // Parse body
-        let body = self.parse_block()?;
+        let has_body = !(allow_signature && self.check(&TokenKind::Semicolon));
+        let body = if has_body {
+            self.parse_block()?
+        } else {
+            let span = self.current_span();
+            self.advance();
+            Block {
+                statements: Vec::new(),
+                trailing_expr: None,
+                span,
+            }
+        };
 
-        Ok(Function {
+        let func = Function {
             name,
             generics,
             params,
@@ -434,18 +855,46 @@ impl Parser {
             body,
             is_async,
             is_unsafe: false, // TODO: Parse unsafe modifier
+            is_const,
             attributes: Vec::new(), // Will be populated by parse_item
-        })
+        };
+        Ok((func, has_body))
     }
 
     /// Parse a function parameter
+    ///
+    /// The receiver shorthands `self`, `&self` and `&mut self` are given
+    /// the types `Self`, `&Self` and `&mut Self`.
     fn parse_param(&mut self) -> ParseResult<Param> {
         let span = self.current_span();
+
+        let self_ref = if self.check(&TokenKind::Ampersand) {
+            self.advance();
+            let is_mutable = self.check(&TokenKind::Mut);
+            if is_mutable {
+                self.advance();
+            }
+            Some(is_mutable)
+        } else {
+            None
+        };
         let name = self.parse_identifier()?;
+        if self_ref.is_some() && name.name != "self" {
+            return Err(ParseError::InvalidSyntax {
+                message: "only `self` can be taken by reference in a parameter list".to_string(),
+                span,
+            });
+        }
 
-        let type_annotation = if self.check(&TokenKind::Colon) {
+        let type_annotation = if self.check(&TokenKind::Colon) && self_ref.is_none() {
             self.advance();
             Some(self.parse_type()?)
+        } else if name.name == "self" {
+            let self_ty = Type::Simple(Identifier::new(name.span, "Self".to_string()));
+            Some(match self_ref {
+                Some(is_mutable) => Type::Ref(Box::new(self_ty), is_mutable),
+                None => self_ty,
+            })
         } else {
             None
         };
@@ -467,6 +916,10 @@ impl Parser {
 
     /// Parse a block statement
     fn parse_block(&mut self) -> ParseResult<Block> {
+        self.nested(Self::parse_block_inner)
+    }
+
+    fn parse_block_inner(&mut self) -> ParseResult<Block> {
         let span = self.current_span();
         self.consume(TokenKind::LeftBrace)?;
 
@@ -474,7 +927,19 @@ impl Parser {
         let mut trailing_expr = None;
 
         while !self.check(&TokenKind::RightBrace) {
-            let stmt = self.parse_statement()?;
+            let stmt_span = self.current_span();
+            let stmt = match self.parse_statement() {
+                Ok(stmt) => stmt,
+                Err(error) => {
+                    self.recover(error)?;
+                    self.synchronize_statement(stmt_span);
+                    statements.push(Statement {
+                        span: stmt_span,
+                        kind: StatementKind::Error,
+                    });
+                    continue;
+                }
+            };
 
             // Check if this is a trailing expression (no semicolon)
             match stmt.kind {
@@ -512,10 +977,7 @@ impl Parser {
         let span = self.current_span();
 
         let kind = match self.current_kind() {
-            Some(TokenKind::Let) => {
-                let local = self.parse_local()?;
-                StatementKind::Local(local)
-            }
+            Some(TokenKind::Let) => self.parse_let()?,
             Some(TokenKind::Defer) => {
                 // Defer statement: defer expr_or_statement
                 self.advance();
@@ -544,6 +1006,26 @@ impl Parser {
                 let item = Box::new(self.parse_item()?.unwrap());
                 StatementKind::Item(item)
             }
+            Some(TokenKind::DocComment(_) | TokenKind::Hash) => {
+                // Attributes belong to a local item; stray doc comments are ignored
+                let attributes = self.parse_outer_attributes()?;
+                match self.current_kind() {
+                    Some(TokenKind::Fn | TokenKind::Struct | TokenKind::Enum | TokenKind::Trait |
+                         TokenKind::Impl | TokenKind::Type | TokenKind::Const | TokenKind::Static |
+                         TokenKind::Mod | TokenKind::Use) => {
+                        let item = self.parse_item_with_attributes(span, attributes)?;
+                        StatementKind::Item(Box::new(item))
+                    }
+                    _ if attributes.iter().any(|attr| attr.doc_text().is_none()) => {
+                        return Err(ParseError::InvalidSyntax {
+                            message: "expected an item after attributes".to_string(),
+                            span: self.current_span(),
+                        });
+                    }
+                    Some(TokenKind::RightBrace) => StatementKind::Empty,
+                    _ => return self.parse_statement(),
+                }
+            }
             _ => {
                 let expr = self.parse_expression()?;
                 StatementKind::Expr(expr)  // Don't consume semicolon here - let parse_block handle it
@@ -553,8 +1035,9 @@ impl Parser {
         Ok(Statement { span, kind })
     }
 
-    /// Parse a local variable declaration
-    fn parse_local(&mut self) -> ParseResult<Local> {
+    /// Parse a `let` statement: a local variable declaration, or
+    /// `let PAT = value else { ... };`
+    fn parse_let(&mut self) -> ParseResult<StatementKind> {
         self.consume(TokenKind::Let)?;
 
         let is_mutable = if self.check(&TokenKind::Mut) {
@@ -564,7 +1047,18 @@ impl Parser {
             false
         };
 
-        let name = self.parse_identifier()?;
+        // A lone identifier is a plain binding; anything else is a pattern
+        let is_name = matches!(self.current_kind(), Some(TokenKind::Ident(_)))
+            && matches!(
+                self.peek_kind(),
+                Some(TokenKind::Colon | TokenKind::Equals | TokenKind::Semicolon)
+            );
+        let pattern_span = self.current_span();
+        let pattern = if is_name {
+            Pattern::Identifier(self.parse_identifier()?)
+        } else {
+            self.parse_pattern()?
+        };
 
         let type_annotation = if self.check(&TokenKind::Colon) {
             self.advance();
@@ -580,19 +1074,60 @@ impl Parser {
             None
         };
 
-        self.consume(TokenKind::Semicolon)?;
+        match (pattern, init) {
+            (pattern, Some(init)) if self.check(&TokenKind::Else) => {
+                self.advance();
+                let else_block = self.parse_block()?;
+                self.consume(TokenKind::Semicolon)?;
 
-        Ok(Local {
-            name,
-            type_annotation,
-            init,
-            is_mutable,
+                Ok(StatementKind::LetElse(LetElse {
+                    pattern,
+                    type_annotation,
+                    init,
+                    else_block,
+                    is_mutable,
+                }))
+            }
+            (Pattern::Identifier(name), init) => {
+                self.consume(TokenKind::Semicolon)?;
+
+                Ok(StatementKind::Local(Local {
+                    name,
+                    type_annotation,
+                    init,
+                    is_mutable,
+                }))
+            }
+            // Only `let...else` destructures; irrefutable patterns such as
+            // `let (a, b) = pair;` are not supported yet
+            _ => Err(ParseError::InvalidSyntax {
+                message: "destructuring `let` is not supported; add an `else` block or use `match`".to_string(),
+                span: pattern_span,
+            }),
+        }
+    }
+
+    /// Parse the condition of an `if` or `while`: an expression or `let PAT = value`
+    fn parse_condition(&mut self) -> ParseResult<Expression> {
+        if !self.check(&TokenKind::Let) {
+            return self.with_struct_literals(false, Self::parse_expression);
+        }
+
+        let span = self.current_span();
+        self.advance();
+        let pattern = Box::new(self.parse_pattern()?);
+        self.consume(TokenKind::Equals)?;
+        let value = Box::new(self.with_struct_literals(false, Self::parse_expression)?);
+
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Let(pattern, value),
         })
     }
 
     /// Parse an expression (using precedence climbing)
     fn parse_expression(&mut self) -> ParseResult<Expression> {
-        self.parse_assignment()
+        self.nested(Self::parse_assignment)
     }
 
     /// Parse assignment expressions
@@ -645,8 +1180,10 @@ impl Parser {
     /// Parse logical OR
     fn parse_or(&mut self) -> ParseResult<Expression> {
         let mut left = self.parse_and()?;
+        let mut links = 0;
 
         while self.check(&TokenKind::Or) {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
             let right = Box::new(self.parse_and()?);
@@ -663,8 +1200,10 @@ impl Parser {
     /// Parse logical AND
     fn parse_and(&mut self) -> ParseResult<Expression> {
         let mut left = self.parse_equality()?;
+        let mut links = 0;
 
         while self.check(&TokenKind::And) {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
             let right = Box::new(self.parse_equality()?);
@@ -681,8 +1220,10 @@ impl Parser {
     /// Parse equality comparisons
     fn parse_equality(&mut self) -> ParseResult<Expression> {
         let mut left = self.parse_comparison()?;
+        let mut links = 0;
 
         while let Some(op) = self.match_equality_op() {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
             let right = Box::new(self.parse_comparison()?);
@@ -708,8 +1249,10 @@ impl Parser {
     /// Parse comparison operators
     fn parse_comparison(&mut self) -> ParseResult<Expression> {
         let mut left = self.parse_term()?;
+        let mut links = 0;
 
         while let Some(op) = self.match_comparison_op() {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
             let right = Box::new(self.parse_term()?);
@@ -737,8 +1280,10 @@ impl Parser {
     /// Parse term (addition/subtraction)
     fn parse_term(&mut self) -> ParseResult<Expression> {
         let mut left = self.parse_factor()?;
+        let mut links = 0;
 
         while let Some(op) = self.match_additive_op() {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
             let right = Box::new(self.parse_factor()?);
@@ -763,12 +1308,14 @@ impl Parser {
 
     /// Parse factor (multiplication/division)
     fn parse_factor(&mut self) -> ParseResult<Expression> {
-        let mut left = self.parse_unary()?;
+        let mut left = self.parse_cast()?;
+        let mut links = 0;
 
         while let Some(op) = self.match_multiplicative_op() {
+            self.chain_link(&mut links)?;
             let span = self.current_span();
             self.advance();
-            let right = Box::new(self.parse_unary()?);
+            let right = Box::new(self.parse_cast()?);
 
             left = Expression {
                 span,
@@ -789,12 +1336,35 @@ impl Parser {
         }
     }
 
+    /// Parse casts, `value as Type`, which bind tighter than binary
+    /// operators but looser than prefix ones
+    ///
+    /// `as` is not a keyword, so it is recognized by name.
+    fn parse_cast(&mut self) -> ParseResult<Expression> {
+        let mut expr = self.parse_unary()?;
+        let mut links = 0;
+
+        while matches!(self.current_kind(), Some(TokenKind::Ident(name)) if &**name == "as") {
+            self.chain_link(&mut links)?;
+            let span = self.current_span();
+            self.advance();
+            let ty = self.parse_type()?;
+
+            expr = Expression {
+                span,
+                kind: ExpressionKind::Cast(Box::new(expr), ty),
+            };
+        }
+
+        Ok(expr)
+    }
+
     /// Parse unary expressions
     fn parse_unary(&mut self) -> ParseResult<Expression> {
         if let Some(op) = self.match_unary_op() {
             let span = self.current_span();
             self.advance();
-            let operand = Box::new(self.parse_unary()?);
+            let operand = Box::new(self.nested(Self::parse_unary)?);
 
             return Ok(Expression {
                 span,
@@ -819,34 +1389,70 @@ impl Parser {
 
     /// Parse postfix operators (calls, field access, indexing)
     fn parse_postfix(&mut self, mut expr: Expression) -> ParseResult<Expression> {
+        let mut links = 0;
         loop {
             let span = self.current_span();
 
             match self.current_kind() {
                 // Function call: func(args)
                 Some(TokenKind::LeftParen) => {
+                    self.chain_link(&mut links)?;
                     self.advance();
 
                     let mut args = Vec::new();
 
+                    // Set when recovery could not find the closing `)`
+                    let mut unclosed = false;
+
                     while !self.check(&TokenKind::RightParen) {
-                        args.push(Box::new(self.parse_expression()?));
+                        let arg_span = self.current_span();
+                        let arg_failed = match self.with_struct_literals(true, Self::parse_expression) {
+                            Ok(arg) => {
+                                args.push(Box::new(arg));
+                                false
+                            }
+                            Err(error) => {
+                                self.recover(error)?;
+                                self.synchronize_delimited(&TokenKind::RightParen);
+                                args.push(Box::new(Self::error_expression(arg_span)));
+                                true
+                            }
+                        };
 
-                        if !self.check(&TokenKind::RightParen) {
-                            self.consume(TokenKind::Comma)?;
+                        if self.check(&TokenKind::RightParen) {
+                            break;
+                        }
+                        if !arg_failed || self.check(&TokenKind::Comma) {
+                            if let Err(error) = self.consume(TokenKind::Comma) {
+                                self.recover(error)?;
+                                self.synchronize_delimited(&TokenKind::RightParen);
+                                if self.check(&TokenKind::Comma) {
+                                    self.advance();
+                                    continue;
+                                }
+                            } else {
+                                continue;
+                            }
                         }
+                        unclosed = !self.check(&TokenKind::RightParen);
+                        break;
                     }
 
-                    self.consume(TokenKind::RightParen)?;
+                    if !unclosed {
+                        self.consume(TokenKind::RightParen)?;
+                    }
 
-                    expr = Expression {
-                        span,
-                        kind: ExpressionKind::Call(Box::new(expr), args),
+                    // `receiver.name(args)` is a method call
+                    let kind = match expr.kind {
+                        ExpressionKind::FieldAccess(receiver, name) => ExpressionKind::MethodCall(receiver, name, args),
+                        kind => ExpressionKind::Call(Box::new(Expression { kind, ..expr }), args),
                     };
+                    expr = Expression { span, kind };
                 }
 
                 // Field access: obj.field OR tuple.0 (numeric field access)
                 Some(TokenKind::Dot) => {
+                    self.chain_link(&mut links)?;
                     self.advance();
 
                     // Check if it's a numeric field access (tuple.0, tuple.1, etc.)
@@ -854,7 +1460,7 @@ impl Parser {
                         // Numeric field access for tuples: tuple.0, tuple.1
                         let index_token = self.advance().unwrap();
                         let index_value = if let TokenKind::IntLiteral(s) = &index_token.kind {
-                            s.parse().unwrap_or(0)
+                            parse_int_literal(s).map(|(value, _)| value).unwrap_or(0)
                         } else {
                             0
                         };
@@ -865,7 +1471,7 @@ impl Parser {
                                 Box::new(expr),
                                 Box::new(Expression {
                                     span: index_token.span.clone(),
-                                    kind: ExpressionKind::Literal(Literal::Int(index_value)),
+                                    kind: ExpressionKind::Literal(Literal::Int(index_value, None)),
                                 }),
                             ),
                         };
@@ -890,6 +1496,7 @@ impl Parser {
 
                 // Array indexing: arr[index]
                 Some(TokenKind::LeftBracket) => {
+                    self.chain_link(&mut links)?;
                     self.advance();
 
                     let index = Box::new(self.parse_expression()?);
@@ -904,6 +1511,7 @@ impl Parser {
 
                 // Question mark operator: expr?
                 Some(TokenKind::Question) => {
+                    self.chain_link(&mut links)?;
                     self.advance();
 
                     expr = Expression {
@@ -934,36 +1542,7 @@ impl Parser {
 
         match self.current_kind() {
             // Control flow: if expression
-            Some(TokenKind::If) => {
-                self.advance();
-                let condition = Box::new(self.parse_expression()?);
-                let then_block = self.parse_block()?;
-
-                let else_block = if self.check(&TokenKind::Else) {
-                    self.advance();
-
-                    if self.check(&TokenKind::If) {
-                        // else if - parse the if expression and extract block
-                        let if_expr = self.parse_primary_base()?;
-                        match if_expr.kind {
-                            ExpressionKind::If(_, else_then_block, _) => {
-                                Some(else_then_block)
-                            }
-                            _ => None,
-                        }
-                    } else {
-                        // else { block }
-                        Some(self.parse_block()?)
-                    }
-                } else {
-                    None
-                };
-
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::If(condition, then_block, else_block),
-                })
-            }
+            Some(TokenKind::If) => self.parse_if_expression(),
 
             // Control flow: loop expression
             Some(TokenKind::Loop) => {
@@ -978,7 +1557,7 @@ impl Parser {
             // Control flow: while loop
             Some(TokenKind::While) => {
                 self.advance();
-                let condition = Box::new(self.parse_expression()?);
+                let condition = Box::new(self.parse_condition()?);
                 let body = self.parse_block()?;
                 Ok(Expression {
                     span,
@@ -987,293 +1566,407 @@ impl Parser {
             }
 
             // Control flow: for loop
-            Some(TokenKind::For) => {
-                self.advance();
-                let span = self.current_span();
-
-                // Parse pattern (currently only identifier)
-                let name = self.parse_identifier()?;
+            Some(TokenKind::For) => self.parse_for_expression(),
 
-                self.consume(TokenKind::In)?;
-
-                // Parse iterator expression
-                let iter = self.parse_expression()?;
-
-                // Parse body
-                let body = self.parse_block()?;
+            // Control flow: match expression
+            Some(TokenKind::Match) => self.parse_match_expression(),
 
+            // Control flow: break
+            Some(TokenKind::Break) => {
+                self.advance();
                 Ok(Expression {
                     span,
-                    kind: ExpressionKind::For(
-                        Local {
-                            name,
-                            type_annotation: None,
-                            init: None,
-                            is_mutable: false,
-                        },
-                        Box::new(iter),
-                        body,
-                        None, // TODO: Parse label
-                    ),
+                    kind: ExpressionKind::Break(None), // TODO: Parse label
                 })
             }
 
-            // Control flow: match expression
-            Some(TokenKind::Match) => {
+            // Control flow: continue
+            Some(TokenKind::Continue) => {
                 self.advance();
-                let scrutinee = Box::new(self.parse_expression()?);
-                self.consume(TokenKind::LeftBrace)?;
+                Ok(Expression {
+                    span,
+                    kind: ExpressionKind::Continue(None), // TODO: Parse label
+                })
+            }
 
-                let mut arms = Vec::new();
+            // Control flow: return
+            Some(TokenKind::Return) => self.parse_return_expression(),
 
-                while !self.check(&TokenKind::RightBrace) {
-                    let arm_span = self.current_span();
+            // Error handling: throw statement
+            Some(TokenKind::Throw) => {
+                self.advance();
 
-                    // Parse patterns
-                    let mut patterns = Vec::new();
-                    patterns.push(self.parse_pattern()?);
+                let error = Box::new(self.parse_expression()?);
 
-                    while self.check(&TokenKind::Pipe) {
-                        self.advance();
-                        patterns.push(self.parse_pattern()?);
-                    }
+                Ok(Expression {
+                    span,
+                    kind: ExpressionKind::Throw(error),
+                })
+            }
 
-                    // Parse guard (if condition)
-                    let guard = if self.check(&TokenKind::If) {
-                        self.advance();
-                        Some(Box::new(self.parse_expression()?))
-                    } else {
-                        None
-                    };
+            // Effect handling: try block with handlers
+            Some(TokenKind::Try) => self.parse_try_expression(),
 
-                    self.consume(TokenKind::FatArrow)?;
+            // Closure: |params| body or |params: Type| -> Type { body }
+            Some(TokenKind::Pipe) => self.parse_closure(),
 
-                    // Parse arm body (expression or block)
-                    let body = if self.check(&TokenKind::LeftBrace) {
-                        let block = self.parse_block()?;
-                        Box::new(Expression {
-                            span: arm_span,
-                            kind: ExpressionKind::Block(block),
-                        })
-                    } else {
-                        Box::new(self.parse_expression()?)
-                    };
+            // Literals
+            Some(TokenKind::IntLiteral(_))
+            | Some(TokenKind::FloatLiteral(_))
+            | Some(TokenKind::StringLiteral(_))
+            | Some(TokenKind::TemplateString(_))
+            | Some(TokenKind::CharLiteral(_))
+            | Some(TokenKind::ByteStringLiteral(_))
+            | Some(TokenKind::ByteLiteral(_))
+            | Some(TokenKind::True)
+            | Some(TokenKind::False)
+            | Some(TokenKind::Null) => self.parse_literal_expression(),
 
-                    arms.push(MatchArm {
-                        span: arm_span,
-                        patterns,
-                        guard,
-                        body,
-                    });
+            // Parenthesized expression or tuple
+            Some(TokenKind::LeftParen) => self.with_struct_literals(true, Self::parse_paren_or_tuple),
 
-                    if !self.check(&TokenKind::RightBrace) {
-                        self.consume(TokenKind::Comma)?;
-                    }
+            // Identifier or path or macro invocation
+            Some(TokenKind::Ident(_)) => {
+                // Check if this is a macro invocation (identifier followed by !)
+                if let Some(TokenKind::Bang) = self.peek_kind() {
+                    // Parse as macro invocation
+                    let macro_name = self.parse_identifier()?;
+                    return self.parse_macro_invocation(macro_name, span);
                 }
 
-                self.consume(TokenKind::RightBrace)?;
+                // Otherwise parse as path
+                let path = self.parse_path()?;
+
+                // Struct literal: `Path { field: value, .. }`
+                if self.check(&TokenKind::LeftBrace) && !self.no_struct_literals {
+                    return self.parse_struct_literal(path, span);
+                }
 
                 Ok(Expression {
                     span,
-                    kind: ExpressionKind::Match(scrutinee, arms),
+                    kind: ExpressionKind::Path(path),
                 })
             }
 
-            // Control flow: break
-            Some(TokenKind::Break) => {
-                self.advance();
+            // Path starting with :: (e.g., ::__builtin_function)
+            Some(TokenKind::PathSep) => self.parse_global_path(),
+
+            // Array literal
+            Some(TokenKind::LeftBracket) => self.with_struct_literals(true, Self::parse_array_literal),
+
+            // Block expression: `{ statements }`
+            Some(TokenKind::LeftBrace) => {
+                let block = self.with_struct_literals(true, Self::parse_block)?;
                 Ok(Expression {
                     span,
-                    kind: ExpressionKind::Break(None), // TODO: Parse label
+                    kind: ExpressionKind::Block(block),
                 })
             }
 
-            // Control flow: continue
-            Some(TokenKind::Continue) => {
-                self.advance();
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Continue(None), // TODO: Parse label
-                })
+            _ => Err(ParseError::InvalidSyntax {
+                message: format!("unexpected token in expression: {:?}", self.current_kind()),
+                span,
+            }),
+        }
+    }
+
+    /// Parse an `if` expression
+    fn parse_if_expression(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
+
+        self.advance();
+        let condition = Box::new(self.parse_condition()?);
+        let then_block = self.parse_block()?;
+
+        let else_block = if self.check(&TokenKind::Else) {
+            self.advance();
+
+            if self.check(&TokenKind::If) {
+                // else if - parse the if expression and extract block
+                let if_expr = self.parse_primary_base()?;
+                match if_expr.kind {
+                    ExpressionKind::If(_, else_then_block, _) => {
+                        Some(else_then_block)
+                    }
+                    _ => None,
+                }
+            } else {
+                // else { block }
+                Some(self.parse_block()?)
             }
+        } else {
+            None
+        };
 
-            // Control flow: return
-            Some(TokenKind::Return) => {
-                self.advance();
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::If(condition, then_block, else_block),
+        })
+    }
 
-                let value = if !self.check(&TokenKind::Semicolon) && !self.check(&TokenKind::RightBrace) {
-                    Some(Box::new(self.parse_expression()?))
-                } else {
-                    None
-                };
+    /// Parse a `for` loop
+    fn parse_for_expression(&mut self) -> ParseResult<Expression> {
+        self.advance();
+        let span = self.current_span();
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Return(value),
-                })
+        // Parse pattern (currently only identifier)
+        let name = self.parse_identifier()?;
+
+        self.consume(TokenKind::In)?;
+
+        // Parse iterator expression
+        let iter = self.with_struct_literals(false, Self::parse_expression)?;
+
+        // Parse body
+        let body = self.parse_block()?;
+
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::For(
+                Local {
+                    name,
+                    type_annotation: None,
+                    init: None,
+                    is_mutable: false,
+                },
+                Box::new(iter),
+                body,
+                None, // TODO: Parse label
+            ),
+        })
+    }
+
+    /// Parse a `match` expression
+    fn parse_match_expression(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
+
+        self.advance();
+        let scrutinee = Box::new(self.with_struct_literals(false, Self::parse_expression)?);
+        self.consume(TokenKind::LeftBrace)?;
+
+        let mut arms = Vec::new();
+
+        while !self.check(&TokenKind::RightBrace) {
+            let arm_span = self.current_span();
+
+            // Match arms take no attributes, so there is nothing to document
+            if let Some(TokenKind::DocComment(_)) = self.current_kind() {
+                return Err(Self::doc_comment_documents_nothing(arm_span));
             }
 
-            // Error handling: throw statement
-            Some(TokenKind::Throw) => {
+            // Parse patterns
+            let mut patterns = Vec::new();
+            patterns.push(self.parse_pattern()?);
+
+            while self.check(&TokenKind::Pipe) {
                 self.advance();
+                patterns.push(self.parse_pattern()?);
+            }
 
-                let error = Box::new(self.parse_expression()?);
+            // Parse guard (if condition)
+            let guard = if self.check(&TokenKind::If) {
+                self.advance();
+                Some(Box::new(self.parse_expression()?))
+            } else {
+                None
+            };
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Throw(error),
+            self.consume(TokenKind::FatArrow)?;
+
+            // Parse arm body (expression or block)
+            let body = if self.check(&TokenKind::LeftBrace) {
+                let block = self.parse_block()?;
+                Box::new(Expression {
+                    span: arm_span,
+                    kind: ExpressionKind::Block(block),
                 })
+            } else {
+                Box::new(self.parse_expression()?)
+            };
+
+            arms.push(MatchArm {
+                span: arm_span,
+                patterns,
+                guard,
+                body,
+            });
+
+            if !self.check(&TokenKind::RightBrace) {
+                self.consume(TokenKind::Comma)?;
             }
+        }
 
-            // Effect handling: try block with handlers
-            Some(TokenKind::Try) => {
-                self.advance();
+        self.consume(TokenKind::RightBrace)?;
 
-                // Parse the try block body
-                let try_block = Box::new(self.parse_block()?);
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Match(scrutinee, arms),
+        })
+    }
 
-                // Parse handlers: with Effect1 { ... } with Effect2 { ... }
-                let mut handlers = Vec::new();
+    /// Parse a `return` expression
+    fn parse_return_expression(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
 
-                while self.check(&TokenKind::With) {
-                    self.advance();
+        self.advance();
 
-                    // Parse effect name
-                    let effect_name = self.parse_identifier()?;
+        let value = if !self.check(&TokenKind::Semicolon) && !self.check(&TokenKind::RightBrace) {
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
 
-                    self.consume(TokenKind::LeftBrace)?;
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Return(value),
+        })
+    }
 
-                    // Parse handler methods
-                    let mut methods = Vec::new();
+    /// Parse a `try` block with effect handlers
+    fn parse_try_expression(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
 
-                    while !self.check(&TokenKind::RightBrace) {
-                        // Parse: fn name(params) { body }
-                        let name = self.parse_identifier()?;
+        self.advance();
 
-                        self.consume(TokenKind::LeftParen)?;
+        // Parse the try block body
+        let try_block = Box::new(self.parse_block()?);
 
-                        // Parse parameters
-                        let mut params = Vec::new();
-                        while !self.check(&TokenKind::RightParen) {
-                            params.push(self.parse_param()?);
+        // Parse handlers: with Effect1 { ... } with Effect2 { ... }
+        let mut handlers = Vec::new();
 
-                            if !self.check(&TokenKind::RightParen) {
-                                self.consume(TokenKind::Comma)?;
-                            }
-                        }
+        while self.check(&TokenKind::With) {
+            self.advance();
 
-                        self.consume(TokenKind::RightParen)?;
+            // Parse effect name
+            let effect_name = self.parse_identifier()?;
 
-                        // Note: Effect methods in handlers don't have explicit return types
-                        // The return type is inferred from the effect signature
+            self.consume(TokenKind::LeftBrace)?;
 
-                        // Parse method body
-                        let body = self.parse_block()?;
+            // Parse handler methods
+            let mut methods = Vec::new();
 
-                        methods.push(EffectMethod {
-                            name,
-                            params,
-                            body,
-                        });
+            while !self.check(&TokenKind::RightBrace) {
+                // Parse: fn name(params) { body }
+                let name = self.parse_identifier()?;
 
-                        // Methods can be separated by commas or semicolons
-                        if !self.check(&TokenKind::RightBrace) {
-                            self.consume_one_of(&[TokenKind::Comma, TokenKind::Semicolon])?;
-                        }
-                    }
+                self.consume(TokenKind::LeftParen)?;
 
-                    self.consume(TokenKind::RightBrace)?;
+                // Parse parameters
+                let mut params = Vec::new();
+                while !self.check(&TokenKind::RightParen) {
+                    params.push(self.parse_param()?);
 
-                    handlers.push(EffectHandler {
-                        effect_name,
-                        methods,
-                    });
+                    if !self.check(&TokenKind::RightParen) {
+                        self.consume(TokenKind::Comma)?;
+                    }
                 }
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Try(try_block, handlers),
-                })
-            }
+                self.consume(TokenKind::RightParen)?;
 
-            // Closure: |params| body or |params: Type| -> Type { body }
-            Some(TokenKind::Pipe) => {
-                self.advance(); // consume first pipe
+                // Note: Effect methods in handlers don't have explicit return types
+                // The return type is inferred from the effect signature
 
-                // Parse parameters
-                let mut params = Vec::new();
+                // Parse method body
+                let body = self.parse_block()?;
 
-                // Check for empty parameter list: ||
-                if !self.check(&TokenKind::Pipe) {
-                    // Parse first parameter
-                    params.push(self.parse_closure_param()?);
+                methods.push(EffectMethod {
+                    name,
+                    params,
+                    body,
+                });
 
-                    // Parse additional parameters separated by commas
-                    while self.check(&TokenKind::Comma) {
-                        self.advance();
-                        params.push(self.parse_closure_param()?);
-                    }
+                // Methods can be separated by commas or semicolons
+                if !self.check(&TokenKind::RightBrace) {
+                    self.consume_one_of(&[TokenKind::Comma, TokenKind::Semicolon])?;
                 }
+            }
 
-                self.consume(TokenKind::Pipe)?; // consume closing pipe
+            self.consume(TokenKind::RightBrace)?;
 
-                // Parse optional return type: -> Type
-                let return_type = if self.check(&TokenKind::Arrow) {
-                    self.advance(); // consume ->
-                    Some(self.parse_type()?)
-                } else {
-                    None
-                };
+            handlers.push(EffectHandler {
+                effect_name,
+                methods,
+            });
+        }
 
-                // Parse closure body
-                // Body can be a block expression or a simple expression
-                let body = if self.check(&TokenKind::LeftBrace) {
-                    // Block body: |...| { statements }
-                    let block = self.parse_block()?;
-                    Box::new(Expression {
-                        span: block.span,
-                        kind: ExpressionKind::Block(block),
-                    })
-                } else {
-                    // Expression body: |...| expression
-                    Box::new(self.parse_expression()?)
-                };
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Try(try_block, handlers),
+        })
+    }
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Closure {
-                        params,
-                        return_type,
-                        body,
-                    },
-                })
+    /// Parse a closure: `|params| body`
+    fn parse_closure(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
+
+        self.advance(); // consume first pipe
+
+        // Parse parameters
+        let mut params = Vec::new();
+
+        // Check for empty parameter list: ||
+        if !self.check(&TokenKind::Pipe) {
+            // Parse first parameter
+            params.push(self.parse_closure_param()?);
+
+            // Parse additional parameters separated by commas
+            while self.check(&TokenKind::Comma) {
+                self.advance();
+                params.push(self.parse_closure_param()?);
             }
+        }
 
-            // Literals
+        self.consume(TokenKind::Pipe)?; // consume closing pipe
+
+        // Parse optional return type: -> Type
+        let return_type = if self.check(&TokenKind::Arrow) {
+            self.advance(); // consume ->
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        // Parse closure body
+        // Body can be a block expression or a simple expression
+        let body = if self.check(&TokenKind::LeftBrace) {
+            // Block body: |...| { statements }
+            let block = self.parse_block()?;
+            Box::new(Expression {
+                span: block.span,
+                kind: ExpressionKind::Block(block),
+            })
+        } else {
+            // Expression body: |...| expression
+            Box::new(self.parse_expression()?)
+        };
+
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Closure {
+                params,
+                return_type,
+                body,
+            },
+        })
+    }
+
+    /// Parse a literal expression
+    fn parse_literal_expression(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
+
+        match self.current_kind() {
             Some(TokenKind::IntLiteral(_)) => {
                 let token = self.advance().unwrap();
-                // Extract the integer value from IntLiteral
-                let value = if let TokenKind::IntLiteral(s) = &token.kind {
-                    s.parse().unwrap_or(0)
-                } else {
-                    0
-                };
                 Ok(Expression {
                     span,
-                    kind: ExpressionKind::Literal(Literal::Int(value)),
+                    kind: ExpressionKind::Literal(Self::number_literal(&token)?),
                 })
             }
             Some(TokenKind::FloatLiteral(_)) => {
                 let token = self.advance().unwrap();
-                // Extract the float value from FloatLiteral
-                let value = if let TokenKind::FloatLiteral(s) = &token.kind {
-                    s.parse().unwrap_or(0.0)
-                } else {
-                    0.0
-                };
                 Ok(Expression {
                     span,
-                    kind: ExpressionKind::Literal(Literal::Float(value)),
+                    kind: ExpressionKind::Literal(Self::number_literal(&token)?),
                 })
             }
             Some(TokenKind::StringLiteral(_)) => {
@@ -1291,7 +1984,7 @@ impl Parser {
                 let token = self.advance().unwrap();
                 if let TokenKind::TemplateString(template) = &token.kind {
                     // Parse template string with interpolation
-                    let parts = self.parse_template_string_parts(template, &token.span)?;
+                    let parts = Self::parse_template_string_parts(template, &token.span)?;
 
                     Ok(Expression {
                         span,
@@ -1314,6 +2007,25 @@ impl Parser {
                     unreachable!()
                 }
             }
+            Some(TokenKind::ByteStringLiteral(_)) => {
+                let token = self.advance().unwrap();
+                if let TokenKind::ByteStringLiteral(bytes) = token.kind {
+                    Ok(Expression {
+                        span,
+                        kind: ExpressionKind::Literal(Literal::ByteString(bytes.into_vec())),
+                    })
+                } else {
+                    unreachable!()
+                }
+            }
+            Some(TokenKind::ByteLiteral(b)) => {
+                let b = *b;
+                self.advance();
+                Ok(Expression {
+                    span,
+                    kind: ExpressionKind::Literal(Literal::Byte(b)),
+                })
+            }
             Some(TokenKind::True) => {
                 self.advance();
                 Ok(Expression {
@@ -1335,111 +2047,137 @@ impl Parser {
                     kind: ExpressionKind::Literal(Literal::Null),
                 })
             }
+            _ => unreachable!("parse_literal_expression called on a non-literal token"),
+        }
+    }
 
-            // Parenthesized expression or tuple
-            Some(TokenKind::LeftParen) => {
-                self.advance();
-
-                // Try to parse as tuple (multiple expressions)
-                let mut elements = Vec::new();
-                let first_expr = self.parse_expression()?;
+    /// Parse a parenthesized expression or a tuple
+    fn parse_paren_or_tuple(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
 
-                if self.check(&TokenKind::Comma) {
-                    // It's a tuple
-                    elements.push(Box::new(first_expr));
+        self.advance();
 
-                    while self.check(&TokenKind::Comma) {
-                        self.advance();
-                        if self.check(&TokenKind::RightParen) {
-                            break; // Trailing comma
-                        }
-                        elements.push(Box::new(self.parse_expression()?));
-                    }
+        // Try to parse as tuple (multiple expressions)
+        let mut elements = Vec::new();
+        let first_expr = self.parse_expression()?;
 
-                    self.consume(TokenKind::RightParen)?;
+        if self.check(&TokenKind::Comma) {
+            // It's a tuple
+            elements.push(Box::new(first_expr));
 
-                    Ok(Expression {
-                        span,
-                        kind: ExpressionKind::Tuple(elements),
-                    })
-                } else {
-                    // It's a parenthesized expression
-                    self.consume(TokenKind::RightParen)?;
-                    Ok(first_expr)
+            while self.check(&TokenKind::Comma) {
+                self.advance();
+                if self.check(&TokenKind::RightParen) {
+                    break; // Trailing comma
                 }
+                elements.push(Box::new(self.parse_expression()?));
             }
 
-            // Identifier or path or macro invocation
-            Some(TokenKind::Ident(_)) => {
-                // Check if this is a macro invocation (identifier followed by !)
-                if let Some(TokenKind::Bang) = self.peek_kind() {
-                    // Parse as macro invocation
-                    let macro_name = self.parse_identifier()?;
-                    return self.parse_macro_invocation(macro_name, span);
-                }
+            self.consume(TokenKind::RightParen)?;
 
-                // Otherwise parse as path
-                let path = self.parse_path()?;
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Path(path),
-                })
+            Ok(Expression {
+                span,
+                kind: ExpressionKind::Tuple(elements),
+            })
+        } else {
+            // It's a parenthesized expression
+            self.consume(TokenKind::RightParen)?;
+            Ok(first_expr)
+        }
+    }
+
+    /// Parse a path starting with `::`
+    fn parse_global_path(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
+
+        let mut path = Vec::new();
+        // Add empty identifier for the leading ::
+        path.push(Identifier::new(span, String::new()));
+        self.advance();
+
+        // Parse the rest of the path
+        while let Some(TokenKind::Ident(_)) = self.current_kind() {
+            path.push(self.parse_identifier()?);
+
+            if !self.check(&TokenKind::PathSep) {
+                break;
             }
+            self.advance();
+        }
 
-            // Path starting with :: (e.g., ::__builtin_function)
-            Some(TokenKind::PathSep) => {
-                let mut path = Vec::new();
-                // Add empty identifier for the leading ::
-                path.push(Identifier::new(span, String::new()));
-                self.advance();
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Path(path),
+        })
+    }
 
-                // Parse the rest of the path
-                loop {
-                    match self.current_kind() {
-                        Some(TokenKind::Ident(_)) => {
-                            path.push(self.parse_identifier()?);
-                        }
-                        _ => break,
-                    }
+    /// Parse the braces of a struct literal after its path:
+    /// `{ name: value, shorthand, ..base }`
+    fn parse_struct_literal(&mut self, path: Vec<Identifier>, span: Span) -> ParseResult<Expression> {
+        self.consume(TokenKind::LeftBrace)?;
 
-                    if !self.check(&TokenKind::PathSep) {
-                        break;
-                    }
-                    self.advance();
-                }
+        let mut fields = Vec::new();
+        let mut base = None;
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Path(path),
-                })
+        while !self.check(&TokenKind::RightBrace) {
+            if self.check(&TokenKind::DotDot) {
+                self.advance();
+                base = Some(Box::new(self.with_struct_literals(true, Self::parse_expression)?));
+                break;
             }
 
-            // Array literal
-            Some(TokenKind::LeftBracket) => {
+            let field_span = self.current_span();
+            let name = self.parse_identifier()?;
+            let value = if self.check(&TokenKind::Colon) {
                 self.advance();
-                let mut elements = Vec::new();
+                self.with_struct_literals(true, Self::parse_expression)?
+            } else {
+                // `name` is short for `name: name`
+                Expression {
+                    span: name.span,
+                    kind: ExpressionKind::Path(vec![name.clone()]),
+                }
+            };
+            fields.push(StructExprField {
+                span: field_span,
+                name,
+                value,
+            });
 
-                while !self.check(&TokenKind::RightBracket) {
-                    elements.push(Box::new(self.parse_expression()?));
+            if !self.check(&TokenKind::RightBrace) {
+                self.consume(TokenKind::Comma)?;
+            }
+        }
 
-                    if !self.check(&TokenKind::RightBracket) {
-                        self.consume(TokenKind::Comma)?;
-                    }
-                }
+        self.consume(TokenKind::RightBrace)?;
 
-                self.consume(TokenKind::RightBracket)?;
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Struct(StructLiteral { path, fields, base }),
+        })
+    }
 
-                Ok(Expression {
-                    span,
-                    kind: ExpressionKind::Array(elements),
-                })
-            }
+    /// Parse an array literal
+    fn parse_array_literal(&mut self) -> ParseResult<Expression> {
+        let span = self.current_span();
 
-            _ => Err(ParseError::InvalidSyntax {
-                message: format!("unexpected token in expression: {:?}", self.current_kind()),
-                span,
-            }),
+        self.advance();
+        let mut elements = Vec::new();
+
+        while !self.check(&TokenKind::RightBracket) {
+            elements.push(Box::new(self.parse_expression()?));
+
+            if !self.check(&TokenKind::RightBracket) {
+                self.consume(TokenKind::Comma)?;
+            }
         }
+
+        self.consume(TokenKind::RightBracket)?;
+
+        Ok(Expression {
+            span,
+            kind: ExpressionKind::Array(elements),
+        })
     }
 
     /// Parse a path (identifier or qualified path)
@@ -1804,8 +2542,25 @@ impl Parser {
 
     /// Parse a type
     fn parse_type(&mut self) -> ParseResult<Type> {
+        self.nested(Self::parse_type_inner)
+    }
+
+    fn parse_type_inner(&mut self) -> ParseResult<Type> {
         let span = self.current_span();
 
+        // `&&T` is lexed as a single `&&` token
+        if self.check(&TokenKind::And) {
+            self.advance();
+            let is_mutable = if self.check(&TokenKind::Mut) {
+                self.advance();
+                true
+            } else {
+                false
+            };
+            let inner = Box::new(self.parse_type()?);
+            return Ok(Type::Ref(Box::new(Type::Ref(inner, is_mutable)), false));
+        }
+
         // Reference type: &T or &mut T
         if self.check(&TokenKind::Ampersand) {
             self.advance();
@@ -1827,6 +2582,32 @@ impl Parser {
             return Ok(Type::Ref(inner, false));
         }
 
+        // Never type: `!`, for functions that do not return
+        if self.check(&TokenKind::Bang) {
+            self.advance();
+            return Ok(Type::Never);
+        }
+
+        // Trait object type: `dyn Trait`; `dyn` is only a keyword here
+        if matches!(self.current_kind(), Some(TokenKind::Ident(name)) if &**name == "dyn")
+            && matches!(self.peek_kind(), Some(TokenKind::Ident(_)))
+        {
+            self.advance();
+            return Ok(Type::TraitObject(Box::new(self.parse_type()?)));
+        }
+
+        // Primitive types that are lexed as keywords
+        let keyword_type = match self.current_kind() {
+            Some(TokenKind::Bool) => Some("bool"),
+            Some(TokenKind::Char) => Some("char"),
+            Some(TokenKind::Str) => Some("str"),
+            _ => None,
+        };
+        if let Some(name) = keyword_type {
+            self.advance();
+            return Ok(Type::Simple(Identifier::new(span, name)));
+        }
+
         // Simple type or path (with optional generic arguments)
         if let Some(TokenKind::Ident(_)) = self.current_kind() {
             let path = self.parse_path()?;
@@ -1899,14 +2680,34 @@ impl Parser {
     }
 
     /// Parse generics
+    ///
+    /// Inline bounds, `<T: Clone + Hash>`, are recorded in the where clause;
+    /// see [`parse_where_clause`](Self::parse_where_clause) for the bounds
+    /// written after the signature.
     fn parse_generics(&mut self) -> ParseResult<Generics> {
         let span = self.current_span();
         self.consume(TokenKind::Less)?;
 
         let mut params = Vec::new();
+        let mut where_clause = Vec::new();
 
         while !self.check(&TokenKind::Greater) {
             let name = self.parse_identifier()?;
+
+            if self.check(&TokenKind::Colon) {
+                self.advance();
+                let mut bounds = vec![TraitBound::Trait(self.parse_type()?)];
+                while self.check(&TokenKind::Plus) {
+                    self.advance();
+                    bounds.push(TraitBound::Trait(self.parse_type()?));
+                }
+                where_clause.push(WhereClause {
+                    span: name.span,
+                    type_param: name.clone(),
+                    bounds,
+                });
+            }
+
             params.push(GenericParam::Type(name));
 
             if !self.check(&TokenKind::Greater) {
@@ -1919,10 +2720,48 @@ impl Parser {
         Ok(Generics {
             span,
             params,
-            where_clause: Vec::new(), // TODO: Parse where clause
+            where_clause,
         })
     }
 
+    /// Parse an optional `where T: Trait + Other, U: Trait` clause after a
+    /// signature, adding its bounds to `generics`
+    ///
+    /// The clause ends at the `{` or `;` that follows it.
+    fn parse_where_clause(&mut self, generics: &mut Option<Generics>) -> ParseResult<()> {
+        if !self.check(&TokenKind::Where) {
+            return Ok(());
+        }
+        let span = self.current_span();
+        self.advance();
+
+        let generics = generics.get_or_insert_with(|| Generics {
+            span,
+            params: Vec::new(),
+            where_clause: Vec::new(),
+        });
+        while !self.check(&TokenKind::LeftBrace) && !self.check(&TokenKind::Semicolon) {
+            let type_param = self.parse_identifier()?;
+            self.consume(TokenKind::Colon)?;
+
+            let mut bounds = vec![TraitBound::Trait(self.parse_type()?)];
+            while self.check(&TokenKind::Plus) {
+                self.advance();
+                bounds.push(TraitBound::Trait(self.parse_type()?));
+            }
+            generics.where_clause.push(WhereClause {
+                span: type_param.span,
+                type_param,
+                bounds,
+            });
+
+            if !self.check(&TokenKind::LeftBrace) && !self.check(&TokenKind::Semicolon) {
+                self.consume(TokenKind::Comma)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Parse a struct definition
     fn parse_struct(&mut self) -> ParseResult<Struct> {
         self.consume(TokenKind::Struct)?;
@@ -1930,11 +2769,12 @@ impl Parser {
         let name = self.parse_identifier()?;
 
         // Parse generics
-        let generics = if self.check(&TokenKind::Less) {
+        let mut generics = if self.check(&TokenKind::Less) {
             Some(self.parse_generics()?)
         } else {
             None
         };
+        self.parse_where_clause(&mut generics)?;
 
         self.consume(TokenKind::LeftBrace)?;
 
@@ -1942,6 +2782,7 @@ impl Parser {
 
         while !self.check(&TokenKind::RightBrace) {
             let span = self.current_span();
+            let attributes = self.parse_member_attributes()?;
             let field_name = self.parse_identifier()?;
 
             self.consume(TokenKind::Colon)?;
@@ -1957,6 +2798,7 @@ impl Parser {
 
             fields.push(StructField {
                 span,
+                attributes,
                 name: field_name,
                 type_annotation: field_type,
                 default_value,
@@ -1983,11 +2825,12 @@ impl Parser {
         let name = self.parse_identifier()?;
 
         // Parse generics
-        let generics = if self.check(&TokenKind::Less) {
+        let mut generics = if self.check(&TokenKind::Less) {
             Some(self.parse_generics()?)
         } else {
             None
         };
+        self.parse_where_clause(&mut generics)?;
 
         self.consume(TokenKind::LeftBrace)?;
 
@@ -1995,6 +2838,7 @@ impl Parser {
 
         while !self.check(&TokenKind::RightBrace) {
             let span = self.current_span();
+            let attributes = self.parse_member_attributes()?;
             let variant_name = self.parse_identifier()?;
 
             let mut fields = Vec::new();
@@ -2036,10 +2880,20 @@ impl Parser {
             }
             // else: unit variant (no data)
 
+            // Explicit discriminant: Variant = expr
+            let discriminant = if self.check(&TokenKind::Equals) {
+                self.advance();
+                Some(self.parse_expression()?)
+            } else {
+                None
+            };
+
             variants.push(EnumVariant {
                 span,
+                attributes,
                 name: variant_name,
                 fields,
+                discriminant,
             });
 
             if !self.check(&TokenKind::RightBrace) {
@@ -2063,7 +2917,7 @@ impl Parser {
         let name = self.parse_identifier()?;
 
         // Parse generics
-        let generics = if self.check(&TokenKind::Less) {
+        let mut generics = if self.check(&TokenKind::Less) {
             Some(self.parse_generics()?)
         } else {
             None
@@ -2085,6 +2939,7 @@ impl Parser {
                 self.advance();
             }
         }
+        self.parse_where_clause(&mut generics)?;
 
         self.consume(TokenKind::LeftBrace)?;
 
@@ -2092,11 +2947,17 @@ impl Parser {
 
         while !self.check(&TokenKind::RightBrace) {
             let span = self.current_span();
+            let attributes = self.parse_member_attributes()?;
 
             // Check for method or associated type or const
-            if self.check(&TokenKind::Fn) {
-                let func = Box::new(self.parse_function()?);
-                items.push(TraitItem::Method(func));
+            let kind = if self.check(&TokenKind::Fn) {
+                let (func, has_body) = self.parse_function_or_signature(true)?;
+                let func = Box::new(func);
+                if has_body {
+                    TraitItemKind::Method(func)
+                } else {
+                    TraitItemKind::RequiredMethod(func)
+                }
             } else if self.check(&TokenKind::Type) {
                 let _token = self.advance();
 
@@ -2121,16 +2982,22 @@ impl Parser {
 
                 self.consume(TokenKind::Semicolon)?;
 
-                items.push(TraitItem::AssociatedType(type_name, bounds));
+                TraitItemKind::AssociatedType(type_name, bounds)
             } else if self.check(&TokenKind::Const) {
                 let const_def = Box::new(self.parse_const()?);
-                items.push(TraitItem::Const(const_def));
+                TraitItemKind::Const(const_def)
             } else {
                 return Err(ParseError::InvalidSyntax {
                     message: format!("expected trait item, found {:?}", self.current_kind()),
                     span,
                 });
-            }
+            };
+
+            items.push(TraitItem {
+                span,
+                attributes,
+                kind,
+            });
         }
 
         self.consume(TokenKind::RightBrace)?;
@@ -2149,32 +3016,42 @@ impl Parser {
         self.consume(TokenKind::Impl)?;
 
         // Parse generics
-        let generics = if self.check(&TokenKind::Less) {
+        let mut generics = if self.check(&TokenKind::Less) {
             Some(self.parse_generics()?)
         } else {
             None
         };
 
-        // Parse trait name: impl Trait for Type
-        let trait_name = if self.check(&TokenKind::For) {
-            // We have: impl Trait for Type
-            let trait_type = self.parse_type()?;
-            self.consume(TokenKind::For)?;
-            Some(trait_type)
+        // `impl Trait for Type`, or `impl Type` for an inherent impl
+        let first_type = self.parse_type()?;
+        let (trait_name, self_type) = if self.check(&TokenKind::For) {
+            self.advance();
+            (Some(first_type), self.parse_type()?)
         } else {
-            // We have: impl Type (inherent impl)
-            None
+            (None, first_type)
         };
-
-        // Parse self type
-        let self_type = self.parse_type()?;
+        self.parse_where_clause(&mut generics)?;
 
         self.consume(TokenKind::LeftBrace)?;
 
         let mut items = Vec::new();
+        let mut assoc_types = Vec::new();
 
         while !self.check(&TokenKind::RightBrace) {
-            let func = Box::new(self.parse_function()?);
+            // Associated type definition: type Item = i32;
+            if self.check(&TokenKind::Type) {
+                self.advance();
+                let type_name = self.parse_identifier()?;
+                self.consume(TokenKind::Equals)?;
+                let ty = self.parse_type()?;
+                self.consume(TokenKind::Semicolon)?;
+                assoc_types.push((type_name, ty));
+                continue;
+            }
+
+            let attributes = self.parse_member_attributes()?;
+            let mut func = Box::new(self.parse_function()?);
+            func.attributes.extend(attributes);
             items.push(func);
         }
 
@@ -2185,6 +3062,7 @@ impl Parser {
             generics,
             trait_name,
             self_type,
+            assoc_types,
             items,
         })
     }
@@ -2268,13 +3146,19 @@ impl Parser {
 
     /// Parse a module declaration
     fn parse_module(&mut self) -> ParseResult<Module> {
+        self.nested(Self::parse_module_inner)
+    }
+
+    fn parse_module_inner(&mut self) -> ParseResult<Module> {
         self.consume(TokenKind::Mod)?;
 
         let name = self.parse_identifier()?;
+        let mut attributes = Vec::new();
 
         let items = if self.check(&TokenKind::LeftBrace) {
             // Inline module: mod name { ... }
             let _token = self.advance();
+            attributes = self.parse_inner_attributes()?;
 
             let mut module_items = Vec::new();
 
@@ -2295,29 +3179,27 @@ impl Parser {
 
         Ok(Module {
             name,
+            attributes,
             items,
         })
     }
 
-    /// Parse a use statement
-    fn parse_use(&mut self) -> ParseResult<Use> {
+    /// Parse a use statement: `use a::b;`, `use a::b as c;`, `use a::*;` or
+    /// `use a::{b, c};`
+    fn parse_use(&mut self, is_pub: bool) -> ParseResult<Use> {
         self.consume(TokenKind::Use)?;
 
-        let is_pub = false; // TODO: Parse pub use
-
-        // Parse the path
-        let mut path_segments = Vec::new();
-
-        loop {
-            path_segments.push(self.parse_identifier()?);
-
-            if !self.check(&TokenKind::PathSep) {
+        // Parse the path, stopping before a trailing `*` or `{`
+        let mut path_segments = vec![self.parse_identifier()?];
+        while self.check(&TokenKind::PathSep) {
+            let _token = self.advance();
+            if self.check(&TokenKind::LeftBrace) || self.check(&TokenKind::Star) {
                 break;
             }
-
-            let _token = self.advance();
+            path_segments.push(self.parse_identifier()?);
         }
 
+        let mut alias = None;
         let path = if self.check(&TokenKind::LeftBrace) {
             // Use list: use path::{a, b, c}
             let _token2 = self.advance();
@@ -2340,21 +3222,59 @@ impl Parser {
             let _token3 = self.advance();
             UsePath::Glob(path_segments)
         } else {
-            // Simple use: use path::to::item
+            // Simple use: use path::to::item, optionally `as name`
+            if matches!(self.current_kind(), Some(TokenKind::Ident(name)) if &**name == "as") {
+                self.advance();
+                alias = Some(self.parse_identifier()?);
+            }
             UsePath::Simple(path_segments)
         };
 
         self.consume(TokenKind::Semicolon)?;
 
-        Ok(Use {
-            path,
-            is_pub,
-            alias: None, // TODO: Parse `as` alias
-        })
+        Ok(Use { path, is_pub, alias })
     }
 
     /// Parse a pattern (for match expressions, let bindings, etc.)
     fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        self.nested(Self::parse_pattern_inner)
+    }
+
+    /// Parse a pattern with alternatives, `A | B`, where a `|` cannot end
+    /// it: inside parentheses, brackets and braces
+    fn parse_pattern_alternatives(&mut self) -> ParseResult<Pattern> {
+        let mut patterns = vec![self.parse_pattern()?];
+        while self.check(&TokenKind::Pipe) {
+            self.advance();
+            patterns.push(self.parse_pattern()?);
+        }
+
+        if patterns.len() == 1 {
+            Ok(patterns.pop().unwrap())
+        } else {
+            Ok(Pattern::Or(patterns))
+        }
+    }
+
+    /// Parse the literal of a literal or range pattern
+    fn parse_literal_pattern(&mut self) -> ParseResult<Literal> {
+        let span = self.current_span();
+        let token = self.advance().ok_or(ParseError::UnexpectedEof { span })?;
+        match token.kind {
+            TokenKind::IntLiteral(_) | TokenKind::FloatLiteral(_) => Self::number_literal(&token),
+            TokenKind::StringLiteral(s) => Ok(Literal::String(s.to_string())),
+            TokenKind::CharLiteral(c) => Ok(Literal::Char(c)),
+            TokenKind::ByteLiteral(b) => Ok(Literal::Byte(b)),
+            TokenKind::True => Ok(Literal::Bool(true)),
+            TokenKind::False => Ok(Literal::Bool(false)),
+            _ => Err(ParseError::InvalidSyntax {
+                message: "expected a literal".to_string(),
+                span: token.span,
+            }),
+        }
+    }
+
+    fn parse_pattern_inner(&mut self) -> ParseResult<Pattern> {
         let _span = self.current_span();
 
         match self.current_kind() {
@@ -2364,31 +3284,23 @@ impl Parser {
                 Ok(Pattern::Wildcard)
             }
 
-            // Literal pattern
-            Some(TokenKind::IntLiteral(_) | TokenKind::FloatLiteral(_) |
-                 TokenKind::StringLiteral(_) | TokenKind::CharLiteral(_) |
-                 TokenKind::True | TokenKind::False) => {
-                // We'll create a literal pattern directly
-                match self.current_kind() {
-                    Some(TokenKind::IntLiteral(_)) => {
-                        let token = self.advance().unwrap();
-                        if let TokenKind::IntLiteral(s) = &token.kind {
-                            let value = s.to_string().parse().unwrap_or(0);
-                            Ok(Pattern::Literal(Literal::Int(value)))
-                        } else {
-                            unreachable!()
-                        }
-                    }
-                    Some(TokenKind::True) => {
-                        self.advance();
-                        Ok(Pattern::Literal(Literal::Bool(true)))
-                    }
-                    Some(TokenKind::False) => {
-                        self.advance();
-                        Ok(Pattern::Literal(Literal::Bool(false)))
-                    }
-                    _ => Ok(Pattern::Wildcard), // TODO: Handle other literals
-                }
+            // Literal pattern, or a range pattern: `1..=5`, `'a'..'z'`
+            Some(TokenKind::IntLiteral(_) | TokenKind::FloatLiteral(_) |
+                 TokenKind::StringLiteral(_) | TokenKind::CharLiteral(_) |
+                 TokenKind::ByteLiteral(_) | TokenKind::True | TokenKind::False) => {
+                let start = self.parse_literal_pattern()?;
+                let kind = match self.current_kind() {
+                    Some(TokenKind::DotDotEq) => RangeKind::Inclusive,
+                    Some(TokenKind::DotDot) => RangeKind::Exclusive,
+                    _ => return Ok(Pattern::Literal(start)),
+                };
+                self.advance();
+                let end = self.parse_literal_pattern()?;
+                Ok(Pattern::Range(
+                    Box::new(Pattern::Literal(start)),
+                    kind,
+                    Box::new(Pattern::Literal(end)),
+                ))
             }
 
             // Identifier pattern or Struct pattern (including path patterns like Outcome::Ok)
@@ -2414,7 +3326,7 @@ impl Parser {
 
                         let field = if self.check(&TokenKind::Colon) {
                             self.advance();
-                            let pattern = Box::new(self.parse_pattern()?);
+                            let pattern = Box::new(self.parse_pattern_alternatives()?);
                             StructPatternField::Field(field_name, pattern)
                         } else {
                             StructPatternField::Shorthand(field_name)
@@ -2437,7 +3349,7 @@ impl Parser {
                     let mut patterns = Vec::new();
 
                     while !self.check(&TokenKind::RightParen) {
-                        patterns.push(self.parse_pattern()?);
+                        patterns.push(self.parse_pattern_alternatives()?);
 
                         if !self.check(&TokenKind::RightParen) {
                             self.consume(TokenKind::Comma)?;
@@ -2464,7 +3376,7 @@ impl Parser {
                 let mut patterns = Vec::new();
 
                 while !self.check(&TokenKind::RightParen) {
-                    patterns.push(self.parse_pattern()?);
+                    patterns.push(self.parse_pattern_alternatives()?);
 
                     if !self.check(&TokenKind::RightParen) {
                         self.consume(TokenKind::Comma)?;
@@ -2476,14 +3388,21 @@ impl Parser {
                 Ok(Pattern::Tuple(patterns))
             }
 
-            // Array pattern: [a, b, c]
+            // Array pattern: [a, b, c], or slice pattern: [first, .., last]
             Some(TokenKind::LeftBracket) => {
                 self.advance();
 
                 let mut patterns = Vec::new();
+                // The patterns before the `..`, once it is seen
+                let mut before = None;
 
                 while !self.check(&TokenKind::RightBracket) {
-                    patterns.push(self.parse_pattern()?);
+                    if self.check(&TokenKind::DotDot) && before.is_none() {
+                        self.advance();
+                        before = Some(std::mem::take(&mut patterns));
+                    } else {
+                        patterns.push(self.parse_pattern_alternatives()?);
+                    }
 
                     if !self.check(&TokenKind::RightBracket) {
                         self.consume(TokenKind::Comma)?;
@@ -2492,7 +3411,10 @@ impl Parser {
 
                 self.consume(TokenKind::RightBracket)?;
 
-                Ok(Pattern::Array(patterns))
+                match before {
+                    Some(before) => Ok(Pattern::Slice(before, Vec::new(), patterns)),
+                    None => Ok(Pattern::Array(patterns)),
+                }
             }
 
             _ => {
@@ -2504,11 +3426,106 @@ impl Parser {
         }
     }
 
+    /// Parse outer attributes: `#[...]` and `///` doc comments
+    ///
+    /// Each doc comment line becomes a `#[doc = "..."]` attribute holding the
+    /// text after `///`. A doc comment at the end of the file documents
+    /// nothing and is an error.
+    fn parse_outer_attributes(&mut self) -> ParseResult<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        let mut last_doc = None;
+
+        loop {
+            match self.current_kind() {
+                Some(TokenKind::Hash) => {
+                    attributes.push(self.parse_attribute()?);
+                    last_doc = None;
+                }
+                Some(TokenKind::DocComment(text)) => {
+                    let text = text.to_string();
+                    let span = self.current_span();
+                    self.advance();
+                    attributes.push(Attribute::doc(span, text));
+                    last_doc = Some(span);
+                }
+                None => {
+                    return match last_doc {
+                        Some(span) => Err(Self::doc_comment_documents_nothing(span)),
+                        None => Ok(attributes),
+                    };
+                }
+                _ => return Ok(attributes),
+            }
+        }
+    }
+
+    /// Parse the outer attributes of a member of a `{ ... }` body, such as a
+    /// struct field or an impl method
+    ///
+    /// A doc comment right before the closing `}` documents nothing and is an
+    /// error.
+    fn parse_member_attributes(&mut self) -> ParseResult<Vec<Attribute>> {
+        let attributes = self.parse_outer_attributes()?;
+        if self.check(&TokenKind::RightBrace) {
+            if let Some(doc) = attributes.last().filter(|attribute| attribute.doc_text().is_some()) {
+                return Err(Self::doc_comment_documents_nothing(doc.name.span));
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// The error for a doc comment with nothing after it to document
+    fn doc_comment_documents_nothing(span: Span) -> ParseError {
+        ParseError::InvalidSyntax {
+            message: "doc comment documents nothing".to_string(),
+            span,
+        }
+    }
+
+    /// Parse inner attributes at the start of a file or inline module:
+    /// `#![...]` and `//!` doc comments
+    fn parse_inner_attributes(&mut self) -> ParseResult<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+
+        loop {
+            let is_inner_attribute = self.check(&TokenKind::Hash)
+                && matches!(self.peek_kind(), Some(TokenKind::Bang));
+            if is_inner_attribute {
+                self.advance();
+                self.advance();
+                attributes.push(self.parse_attribute_body()?);
+                continue;
+            }
+
+            match self.current_kind() {
+                Some(TokenKind::InnerDocComment(text)) => {
+                    let text = text.to_string();
+                    let span = self.current_span();
+                    self.advance();
+                    attributes.push(Attribute::doc(span, text));
+                }
+                _ => return Ok(attributes),
+            }
+        }
+    }
+
     /// Parse an attribute: #[attribute] or #[attribute(arg)] or #[attribute(key = value)]
     fn parse_attribute(&mut self) -> ParseResult<Attribute> {
         // Consume #
         self.consume(TokenKind::Hash)?;
 
+        if self.check(&TokenKind::Bang) {
+            return Err(ParseError::InvalidSyntax {
+                message: "inner attributes `#![...]` are only allowed at the start of a file or module".to_string(),
+                span: self.current_span(),
+            });
+        }
+
+        self.parse_attribute_body()
+    }
+
+    /// Parse the bracketed part of an attribute, after `#` or `#!`
+    fn parse_attribute_body(&mut self) -> ParseResult<Attribute> {
         // Consume [
         self.consume(TokenKind::LeftBracket)?;
 
@@ -2518,84 +3535,16 @@ impl Parser {
         // Parse attribute arguments (optional)
         let mut args = Vec::new();
 
+        // Name-value form: #[doc = "text"]
+        if self.check(&TokenKind::Equals) {
+            self.advance();
+            args.push(AttributeArg::String(self.parse_attribute_string()?));
+        }
+
         // Check for ( ... ) - note: this is NOT part of standard ZULON attributes
         // but we allow it for compatibility with testing framework syntax like #[test()]
         if self.check(&TokenKind::LeftParen) {
-            self.advance();
-
-            // Parse arguments inside parentheses
-            while !self.check(&TokenKind::RightParen) {
-                match &self.current {
-                    Some(token) => match &token.kind {
-                        TokenKind::StringLiteral(s) => {
-                            // String literal argument
-                            args.push(AttributeArg::String(s.to_string()));
-                            self.advance();
-                        }
-                        TokenKind::Ident(ident_str) => {
-                            // Identifier or key-value pair
-                            let key = Identifier::new(token.span, ident_str.as_ref());
-                            self.advance();
-
-                            if self.check(&TokenKind::Equals) {
-                                // Key-value pair: key = "value"
-                                self.advance();
-
-                                let value = match &self.current {
-                                    Some(t) if matches!(t.kind, TokenKind::StringLiteral(_)) => {
-                                        if let TokenKind::StringLiteral(s) = &t.kind {
-                                            s.to_string()
-                                        } else {
-                                            unreachable!()
-                                        }
-                                    }
-                                    Some(t) => {
-                                        return Err(ParseError::UnexpectedToken {
-                                            expected: "string literal".to_string(),
-                                            found: t.kind.clone(),
-                                            span: self.current_span(),
-                                        });
-                                    }
-                                    None => {
-                                        return Err(ParseError::UnexpectedEof {
-                                            span: self.current_span(),
-                                        });
-                                    }
-                                };
-
-                                args.push(AttributeArg::KeyValue { key, value });
-                                self.advance();
-                            } else {
-                                // Just an identifier
-                                args.push(AttributeArg::Ident(key));
-                            }
-                        }
-                        _ => {
-                            return Err(ParseError::UnexpectedToken {
-                                expected: "identifier or string literal".to_string(),
-                                found: self.current_kind().cloned().unwrap_or(TokenKind::Unknown),
-                                span: self.current_span(),
-                            });
-                        }
-                    },
-                    None => {
-                        return Err(ParseError::UnexpectedEof {
-                            span: self.current_span(),
-                        });
-                    }
-                }
-
-                // Check for comma separator
-                if self.check(&TokenKind::Comma) {
-                    self.advance();
-                    // Continue parsing next argument
-                } else {
-                    break;
-                }
-            }
-
-            // Consume )
-            self.consume(TokenKind::RightParen)?;
+            args = self.parse_attribute_args()?;
         }
 
         // Consume ]
@@ -2607,6 +3556,76 @@ impl Parser {
         })
     }
 
+    /// Parse a parenthesized, comma separated attribute argument list
+    fn parse_attribute_args(&mut self) -> ParseResult<Vec<AttributeArg>> {
+        self.consume(TokenKind::LeftParen)?;
+
+        let mut args = Vec::new();
+        while !self.check(&TokenKind::RightParen) {
+            args.push(self.parse_attribute_arg()?);
+
+            // Check for comma separator
+            if self.check(&TokenKind::Comma) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        self.consume(TokenKind::RightParen)?;
+        Ok(args)
+    }
+
+    /// Parse one attribute argument: `"value"`, `name`, `key = "value"` or `name(args)`
+    fn parse_attribute_arg(&mut self) -> ParseResult<AttributeArg> {
+        match self.current_kind() {
+            Some(TokenKind::StringLiteral(_)) => Ok(AttributeArg::String(self.parse_attribute_string()?)),
+            Some(TokenKind::Ident(_)) => {
+                let key = self.parse_identifier()?;
+
+                if self.check(&TokenKind::Equals) {
+                    // Key-value pair: key = "value"
+                    self.advance();
+                    let value = self.parse_attribute_string()?;
+                    Ok(AttributeArg::KeyValue { key, value })
+                } else if self.check(&TokenKind::LeftParen) {
+                    // Nested list: name(args)
+                    Ok(AttributeArg::List(key, self.parse_attribute_args()?))
+                } else {
+                    // Just an identifier
+                    Ok(AttributeArg::Ident(key))
+                }
+            }
+            Some(kind) => Err(ParseError::UnexpectedToken {
+                expected: "identifier or string literal".to_string(),
+                found: kind.clone(),
+                span: self.current_span(),
+            }),
+            None => Err(ParseError::UnexpectedEof {
+                span: self.current_span(),
+            }),
+        }
+    }
+
+    /// Parse the string literal value of an attribute argument
+    fn parse_attribute_string(&mut self) -> ParseResult<String> {
+        match self.current_kind() {
+            Some(TokenKind::StringLiteral(s)) => {
+                let value = s.to_string();
+                self.advance();
+                Ok(value)
+            }
+            Some(kind) => Err(ParseError::UnexpectedToken {
+                expected: "string literal".to_string(),
+                found: kind.clone(),
+                span: self.current_span(),
+            }),
+            None => Err(ParseError::UnexpectedEof {
+                span: self.current_span(),
+            }),
+        }
+    }
+
     /// Parse an effect declaration: `effect Name { operations }`
     fn parse_effect(&mut self) -> ParseResult<Effect> {
         self.consume(TokenKind::Effect)?;
@@ -2644,6 +3663,7 @@ impl Parser {
 
     /// Parse an effect operation: `fn name(params) -> ReturnType`
     fn parse_effect_operation(&mut self) -> ParseResult<EffectOperation> {
+        let attributes = self.parse_member_attributes()?;
         let name = self.parse_identifier()?;
 
         self.consume(TokenKind::LeftParen)?;
@@ -2669,6 +3689,7 @@ impl Parser {
         };
 
         Ok(EffectOperation {
+            attributes,
             name,
             params,
             return_type,
@@ -2691,99 +3712,201 @@ impl Parser {
         })
     }
 
-    /// Parse template string parts, splitting static text from interpolated expressions
-    fn parse_template_string_parts(&mut self, template: &str, span: &Span) -> ParseResult<Vec<TemplateStringPart>> {
-        use crate::ast::TemplateStringPart;
-
+    /// Parse template string parts, splitting static text from `${expr}` and
+    /// `${expr:spec}` interpolations
+    fn parse_template_string_parts(template: &str, span: &Span) -> ParseResult<Vec<TemplateStringPart>> {
+        // The text starts after the opening backtick
+        let start = Position::new(span.start.line, span.start.column + 1);
         let mut parts = Vec::new();
-        let mut current = String::new();
-        let mut chars = template.chars().peekable();
-        let mut in_interpolation = false;
-        let mut brace_depth = 0;
-
-        while let Some(c) = chars.next() {
-            if in_interpolation {
-                if c == '{' {
-                    brace_depth += 1;
-                    current.push(c);
-                } else if c == '}' {
-                    brace_depth -= 1;
-                    if brace_depth == 0 {
-                        // End of interpolation
-                        let expr_str = current[1..].to_string(); // Skip the opening '$'
-                        current.clear();
-
-                        // Parse the interpolated expression
-                        // We need to create a temporary lexer to tokenize just this expression
-                        let lexer = Lexer::new(&expr_str);
-                        let (tokens, errors) = lexer.lex_all();
-
-                        if !errors.is_empty() {
-                            return Err(ParseError::InvalidSyntax {
-                                message: format!("Failed to lex interpolated expression: {}", expr_str),
-                                span: span.clone(),
-                            });
-                        }
-
-                        // Parse the expression using the tokens
-                        let old_tokens = std::mem::replace(&mut self.tokens, tokens.into_iter().peekable());
-                        let _old_current = std::mem::replace(&mut self.current, self.tokens.next());
-
-                        let expr_result = self.parse_expression();
+        let mut text = String::new();
+        let mut offset = 0;
+
+        while let Some(c) = template[offset..].chars().next() {
+            if !template[offset..].starts_with("${") {
+                text.push(c);
+                offset += c.len_utf8();
+                continue;
+            }
 
-                        // Restore the original token stream BEFORE checking expr_result
-                        self.tokens = old_tokens;
-                        // Don't restore old_current - we've moved past it
-                        // Instead, get the next token from the restored stream
-                        self.current = self.tokens.next();
+            let open = offset + 2;
+            let Some(close) = interpolation_end(template, open) else {
+                return Err(ParseError::InvalidSyntax {
+                    message: "Unterminated interpolation in template string".to_string(),
+                    span: *span,
+                });
+            };
+            if !text.is_empty() {
+                parts.push(TemplateStringPart::Static(std::mem::take(&mut text)));
+            }
 
-                        parts.push(TemplateStringPart::Expr(expr_result?));
-                        in_interpolation = false;
-                    } else {
-                        current.push(c);
+            let hole = &template[open..close];
+            let colon = format_spec_colon(hole);
+            let expr_start = advance_position(start, &template[..open]);
+            let expr = Self::parse_interpolated_expression(&hole[..colon.unwrap_or(hole.len())], expr_start)?;
+
+            let spec = match colon {
+                Some(colon) => {
+                    let spec_start = advance_position(expr_start, &hole[..colon + 1]);
+                    let spec = FormatSpec::parse(&hole[colon + 1..], spec_start)?;
+                    if spec.count_arguments().any(|argument| !matches!(argument, Argument::Name(_))) {
+                        let spec_end = advance_position(expr_start, hole);
+                        return Err(FormatError::new(
+                            "template strings take widths and precisions from names",
+                            Span::new(spec_start, spec_end),
+                        )
+                        .with_note("write the count as a variable, like `${value:>width$}`")
+                        .into());
                     }
-                } else {
-                    current.push(c);
+                    spec
                 }
+                None => FormatSpec::default(),
+            };
+
+            parts.push(TemplateStringPart::Expr(Box::new(expr), spec));
+            offset = close + 1;
+        }
+
+        if !text.is_empty() {
+            parts.push(TemplateStringPart::Static(text));
+        }
+        Ok(parts)
+    }
+
+    /// Parse the expression of a template string interpolation
+    ///
+    /// `start` is the position of the expression in the source, so its spans
+    /// point into the template string.
+    fn parse_interpolated_expression(text: &str, start: Position) -> ParseResult<Expression> {
+        let end = advance_position(start, text);
+        let (tokens, errors) = Lexer::new(text).lex_all();
+        if !errors.is_empty() {
+            return Err(ParseError::InvalidSyntax {
+                message: format!("Failed to lex interpolated expression: {}", text),
+                span: Span::new(start, end),
+            });
+        }
+
+        // Tokens are lexed from the interpolation alone, so move them into place
+        let relocate = |position: Position| {
+            if position.line == 1 {
+                Position::new(start.line, start.column + position.column - 1)
             } else {
-                if c == '$' {
-                    // Check if next char is '{'
-                    if let Some(&'{') = chars.peek() {
-                        chars.next(); // consume '{'
-
-                        // Save the static part before interpolation
-                        if !current.is_empty() {
-                            parts.push(TemplateStringPart::Static(std::mem::take(&mut current)));
-                        }
+                Position::new(start.line + position.line - 1, position.column)
+            }
+        };
+        let tokens: Vec<Token> = tokens
+            .into_iter()
+            .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment))
+            .map(|token| Token {
+                span: Span::new(relocate(token.span.start), relocate(token.span.end)),
+                ..token
+            })
+            .collect();
 
-                        // Start interpolation
-                        current.push('$');
-                        current.push('{');
-                        in_interpolation = true;
-                        brace_depth = 1;
-                    } else {
-                        current.push(c);
+        if tokens.is_empty() {
+            return Err(ParseError::InvalidSyntax {
+                message: "Empty interpolation in template string".to_string(),
+                span: Span::new(start, end),
+            });
+        }
+
+        let mut parser = Parser::new(tokens);
+        let expr = parser.parse_expression()?;
+        if let Some(token) = parser.current.take() {
+            return Err(ParseError::UnexpectedToken {
+                expected: "`}` or `:` after the interpolated expression".to_string(),
+                found: token.kind,
+                span: token.span,
+            });
+        }
+        Ok(expr)
+    }
+}
+
+/// The byte offset of the `}` closing an interpolation whose body starts at `from`
+///
+/// Braces inside string and character literals are skipped.
+fn interpolation_end(template: &str, from: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut chars = template[from..].char_indices().map(|(i, c)| (from + i, c)).peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
                     }
+                }
+            }
+            '\'' => {
+                // Only skip a complete character literal, not a lifetime
+                let rest = &template[index + 1..];
+                let len = if rest.starts_with('\\') {
+                    rest.find('\'').map(|end| end + 1)
                 } else {
-                    current.push(c);
+                    rest.chars().next().map(char::len_utf8).filter(|&len| rest[len..].starts_with('\''))
+                };
+                if let Some(len) = len {
+                    let end = index + 1 + len;
+                    while chars.next_if(|&(i, _)| i <= end).is_some() {}
                 }
             }
+            _ => {}
         }
+    }
+    None
+}
 
-        // Add remaining static text
-        if !current.is_empty() && !in_interpolation {
-            parts.push(TemplateStringPart::Static(current));
-        }
-
-        // If we're still in interpolation at EOF, that's an error
-        if in_interpolation {
-            return Err(ParseError::InvalidSyntax {
-                message: "Unterminated interpolation in template string".to_string(),
-                span: span.clone(),
-            });
+/// The offset of the `:` separating an interpolated expression from its spec
+///
+/// Only a single `:` outside brackets and literals counts, so paths like
+/// `a::b` and struct literals stay part of the expression.
+fn format_spec_colon(hole: &str) -> Option<usize> {
+    let bytes = hole.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' if in_string => index += 1,
+            b'"' => in_string = !in_string,
+            _ if in_string => {}
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b':' if depth == 0 => {
+                if bytes.get(index + 1) == Some(&b':') {
+                    index += 1;
+                } else if index == 0 || bytes[index - 1] != b':' {
+                    return Some(index);
+                }
+            }
+            _ => {}
         }
+        index += 1;
+    }
+    None
+}
 
-        Ok(parts)
+/// The delimiter closing an opening `(`, `[` or `{`
+fn matching_close(kind: &TokenKind) -> Option<TokenKind> {
+    match kind {
+        TokenKind::LeftParen => Some(TokenKind::RightParen),
+        TokenKind::LeftBracket => Some(TokenKind::RightBracket),
+        TokenKind::LeftBrace => Some(TokenKind::RightBrace),
+        _ => None,
     }
 }
 
@@ -2822,6 +3945,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trait_required_and_provided_methods() {
+        let source = r#"
+            trait Shape {
+                fn area(self: &Self) -> f64;
+                fn describe(self: &Self) -> i32 { 0 }
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Trait(trait_def) = &ast.items[0].kind else { panic!("expected trait") };
+        assert!(matches!(&trait_def.items[0].kind, TraitItemKind::RequiredMethod(func) if func.name.name == "area"));
+        assert!(matches!(&trait_def.items[1].kind, TraitItemKind::Method(func) if func.name.name == "describe"));
+
+        // Only trait methods can leave out their bodies
+        assert!(Parser::from_source("fn area() -> f64;").parse().is_err());
+    }
+
+    #[test]
+    fn test_impl_blocks_and_method_calls() {
+        let source = r#"
+            impl Shape for Circle {
+                fn area(&self) -> f64 { self.radius() }
+            }
+            impl Circle {
+                fn grow(&mut self, by: f64) {}
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Impl(trait_impl) = &ast.items[0].kind else { panic!("expected impl") };
+        assert!(matches!(&trait_impl.trait_name, Some(Type::Simple(name)) if name.name == "Shape"));
+        assert!(matches!(&trait_impl.self_type, Type::Simple(name) if name.name == "Circle"));
+        let area = &trait_impl.items[0];
+        assert!(matches!(
+            &area.params[0].type_annotation,
+            Some(Type::Ref(inner, false)) if matches!(&**inner, Type::Simple(name) if name.name == "Self")
+        ));
+        let body = area.body.trailing_expr.as_ref().unwrap();
+        assert!(matches!(&body.kind, ExpressionKind::MethodCall(_, name, args) if name.name == "radius" && args.is_empty()));
+
+        let ItemKind::Impl(inherent_impl) = &ast.items[1].kind else { panic!("expected impl") };
+        assert!(inherent_impl.trait_name.is_none());
+        assert!(matches!(&inherent_impl.items[0].params[0].type_annotation, Some(Type::Ref(_, true))));
+    }
+
+    #[test]
+    fn test_trait_object_type() {
+        let ast = Parser::from_source("fn draw(shape: &dyn Shape, dyn: i32) {}").parse().unwrap();
+        let ItemKind::Function(func) = &ast.items[0].kind else { panic!("expected function") };
+        assert!(matches!(
+            &func.params[0].type_annotation,
+            Some(Type::Ref(inner, false))
+                if matches!(&**inner, Type::TraitObject(name) if matches!(&**name, Type::Simple(name) if name.name == "Shape"))
+        ));
+        assert_eq!(func.params[1].name.name, "dyn");
+    }
+
+    #[test]
+    fn test_never_return_type() {
+        let ast = Parser::from_source("fn fail() -> ! { loop {} }").parse().unwrap();
+        let ItemKind::Function(func) = &ast.items[0].kind else { panic!("expected function") };
+        assert!(matches!(func.return_type, Some(Type::Never)));
+    }
+
     #[test]
     fn test_arithmetic_expression() {
         let source = "fn test() { let x = 1 + 2 * 3; }";
@@ -2831,6 +4019,24 @@ mod tests {
         assert_eq!(ast.items.len(), 1);
     }
 
+    #[test]
+    fn test_cast_precedence() {
+        let ast = Parser::from_source("fn test() -> i64 { -a as i64 * 2 + b as i64 as i64 }").parse().unwrap();
+        let ItemKind::Function(func) = &ast.items[0].kind else { panic!("expected function") };
+        let Some(ExpressionKind::Binary(BinaryOp::Add, left, right)) = func.body.trailing_expr.as_ref().map(|expr| &expr.kind) else {
+            panic!("expected an addition");
+        };
+
+        // `as` binds looser than `-` and tighter than `*`
+        let ExpressionKind::Binary(BinaryOp::Mul, product, _) = &left.kind else { panic!("expected a product") };
+        assert!(matches!(&product.kind, ExpressionKind::Cast(negated, Type::Simple(ty))
+            if ty.name == "i64" && matches!(negated.kind, ExpressionKind::Unary(UnaryOp::Neg, _))));
+
+        // Casts chain to the left
+        let ExpressionKind::Cast(inner, _) = &right.kind else { panic!("expected a cast") };
+        assert!(matches!(&inner.kind, ExpressionKind::Cast(value, _) if matches!(value.kind, ExpressionKind::Path(_))));
+    }
+
     #[test]
     fn test_function_call() {
         let source = r#"
@@ -3018,6 +4224,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_macro_definition() {
+        let source = r#"
+            pub macro pair {
+                ($a:expr, $b:expr) => { ($a, $b) };
+                [$a:expr] => { ($a, $a) }
+            }
+        "#;
+        let mut parser = Parser::from_source(source);
+        let ast = parser.parse().unwrap();
+
+        assert_eq!(ast.items.len(), 1);
+        match &ast.items[0].kind {
+            ItemKind::MacroDef(def) => {
+                assert_eq!(def.name.name, "pair");
+                assert!(def.is_pub);
+                assert_eq!(def.rules.len(), 2);
+                // Delimiters are not part of the rule
+                assert_eq!(def.rules[0].pattern.len(), 9);
+                assert_eq!(def.rules[0].template.len(), 7);
+                assert_eq!(def.rules[1].pattern.len(), 4);
+            }
+            _ => panic!("expected macro definition"),
+        }
+
+        // `macro` is still an ordinary identifier elsewhere
+        let mut parser = Parser::from_source("fn f(macro: i32) -> i32 { macro }");
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn test_macro_definition_errors() {
+        for source in ["macro empty {}", "macro m { ($x) => { $x ] }", "macro m { ($x) { $x } }"] {
+            let mut parser = Parser::from_source(source);
+            assert!(parser.parse().is_err(), "{}", source);
+        }
+    }
+
     // TODO: Implement struct instantiation syntax
     // Deferred due to ambiguity with block expressions in control flow
     // #[test]
@@ -3071,6 +4315,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generic_bounds() {
+        let source = "struct Map<K: Hash + Eq, V> { keys: Vec<K>, values: Vec<V> }";
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Struct(s) = &ast.items[0].kind else {
+            panic!("expected struct");
+        };
+        let generics = s.generics.as_ref().unwrap();
+        assert_eq!(generics.params.len(), 2);
+        assert_eq!(generics.where_clause.len(), 1);
+
+        let clause = &generics.where_clause[0];
+        assert_eq!(clause.type_param.name, "K");
+        let bounds: Vec<&str> = clause
+            .bounds
+            .iter()
+            .map(|bound| match bound {
+                TraitBound::Trait(Type::Simple(name)) => name.name.as_str(),
+                other => panic!("unexpected bound {:?}", other),
+            })
+            .collect();
+        assert_eq!(bounds, ["Hash", "Eq"]);
+    }
+
+    #[test]
+    fn test_where_clause_and_associated_types() {
+        let source = r#"
+            fn first<I>(iter: I) -> Option<I::Item>
+            where
+                I: Iterator + Clone,
+            {
+                iter.next()
+            }
+
+            impl Iterator for Counter {
+                type Item = i64;
+                fn next(&mut self) -> Option<i64> { None }
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Function(func) = &ast.items[0].kind else {
+            panic!("expected function");
+        };
+        let generics = func.generics.as_ref().unwrap();
+        assert_eq!(generics.where_clause.len(), 1);
+        assert_eq!(generics.where_clause[0].type_param.name, "I");
+        assert_eq!(generics.where_clause[0].bounds.len(), 2);
+
+        let ItemKind::Impl(imp) = &ast.items[1].kind else {
+            panic!("expected impl");
+        };
+        assert_eq!(imp.assoc_types.len(), 1);
+        assert_eq!(imp.assoc_types[0].0.name, "Item");
+        assert_eq!(imp.items.len(), 1);
+    }
+
+    #[test]
+    fn test_const_fn_and_enum_discriminants() {
+        let source = r#"
+            const fn double(x: i32) -> i32 { x * 2 }
+            const LIMIT: i32 = double(4);
+
+            enum Level {
+                Low = 1,
+                High = LIMIT + 1,
+                Max,
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Function(func) = &ast.items[0].kind else {
+            panic!("expected function");
+        };
+        assert!(func.is_const);
+        assert!(matches!(ast.items[1].kind, ItemKind::Const(_)));
+
+        let ItemKind::Enum(enum_def) = &ast.items[2].kind else {
+            panic!("expected enum");
+        };
+        assert!(matches!(
+            enum_def.variants[0].discriminant.as_ref().map(|expr| &expr.kind),
+            Some(ExpressionKind::Literal(Literal::Int(1, None)))
+        ));
+        assert!(matches!(
+            enum_def.variants[1].discriminant.as_ref().map(|expr| &expr.kind),
+            Some(ExpressionKind::Binary(BinaryOp::Add, ..))
+        ));
+        assert!(enum_def.variants[2].discriminant.is_none());
+    }
+
     #[test]
     fn test_path_expressions() {
         let source = r#"
@@ -3090,4 +4426,35 @@ mod tests {
 
         assert_eq!(ast.items.len(), 2); // mod a, fn test
     }
+
+    #[test]
+    fn test_struct_literal() {
+        let source = r#"
+            fn test() {
+                let p = Pair { first: 1, second };
+                if ready { p }
+            }
+        "#;
+        let ast = Parser::from_source(source).parse().unwrap();
+
+        let ItemKind::Function(f) = &ast.items[0].kind else {
+            panic!("expected function");
+        };
+        let StatementKind::Local(local) = &f.body.statements[0].kind else {
+            panic!("expected let");
+        };
+        let Some(ExpressionKind::Struct(literal)) = local.init.as_ref().map(|e| &e.kind) else {
+            panic!("expected struct literal");
+        };
+        assert_eq!(literal.path[0].name, "Pair");
+        let names: Vec<&str> = literal.fields.iter().map(|f| f.name.name.as_str()).collect();
+        assert_eq!(names, ["first", "second"]);
+        assert!(matches!(&literal.fields[1].value.kind, ExpressionKind::Path(path) if path[0].name == "second"));
+
+        // `ready {` in the condition opens the `if` body, not a struct literal
+        let Some(ExpressionKind::If(_, then_block, None)) = f.body.trailing_expr.as_ref().map(|e| &e.kind) else {
+            panic!("expected if");
+        };
+        assert!(then_block.trailing_expr.is_some());
+    }
 }
@@ -0,0 +1,203 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The files of a crate spliced into one token stream
+//!
+//! Each file is lexed on its own, and the tokens of a file declared with
+//! `mod name;` take the place of the `;`, inside braces. So that spans stay
+//! unique across the crate, every file gets its own range of lines: the
+//! lines of a file added after a 10-line file start at 11. Diagnostics are
+//! made against [`SourceMap::text`], which has every file at those lines,
+//! and [`SourceMap::localize`] maps them back to the file they are in.
+//!
+//! ```rust
+//! use zulon_parser::source_map::SourceMap;
+//!
+//! let mut map = SourceMap::new();
+//! map.add_file("main.zl".into(), "mod util;\nfn main() {}\n".to_string());
+//! let util = map.add_file("util.zl".into(), "fn helper() {}\n".to_string()).line_offset;
+//!
+//! assert_eq!(util, 3);
+//! assert_eq!(map.lookup(4).unwrap().path.to_str(), Some("util.zl"));
+//! ```
+
+use crate::lexer::{Span, Token};
+use std::path::PathBuf;
+use zulon_diagnostic::{Diagnostic, FileId, Loc};
+
+/// One file of a crate
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// The path the file was read from
+    pub path: PathBuf,
+    /// The file's contents
+    pub source: String,
+    /// How many lines of the crate come before the file's first line
+    pub line_offset: usize,
+    /// Where the file starts in [`SourceMap::text`]
+    text_offset: usize,
+    /// Lines the file takes up, counting the one after a final newline
+    line_count: usize,
+}
+
+impl SourceFile {
+    /// Move tokens lexed from this file to its lines in the crate
+    pub fn shift(&self, tokens: &mut [Token]) {
+        for token in tokens {
+            token.span.start.line += self.line_offset;
+            token.span.end.line += self.line_offset;
+        }
+    }
+
+    /// Whether crate line `line` is in this file
+    pub fn contains(&self, line: usize) -> bool {
+        line > self.line_offset && line <= self.line_offset + self.line_count
+    }
+}
+
+/// The files of a crate, in the order they were added
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    text: String,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, after the lines of the files already added
+    pub fn add_file(&mut self, path: PathBuf, source: String) -> &SourceFile {
+        let line_offset = self
+            .files
+            .last()
+            .map_or(0, |file| file.line_offset + file.line_count);
+        let text_offset = self.text.len();
+        self.text.push_str(&source);
+        self.text.push('\n');
+        self.files.push(SourceFile {
+            path,
+            line_count: source.split('\n').count(),
+            source,
+            line_offset,
+            text_offset,
+        });
+        self.files.last().expect("a file was just added")
+    }
+
+    /// Every file, the first being the crate root
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Every file, one after the other, each at its lines in the crate
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The file crate line `line` is in
+    pub fn lookup(&self, line: usize) -> Option<&SourceFile> {
+        self.files.iter().find(|file| file.contains(line))
+    }
+
+    /// The source of `file`, which a localized span is in; empty if it is
+    /// not a file of the crate
+    pub fn source(&self, file: Option<&FileId>) -> &str {
+        file.and_then(|file| self.files.iter().find(|source| source.path == file.path()))
+            .map_or("", |file| file.source.as_str())
+    }
+
+    /// A span as `path:line:column to line:column`, with lines in its file
+    pub fn location(&self, span: &Span) -> String {
+        let (path, offset) = match self.lookup(span.start.line) {
+            Some(file) => (file.path.display().to_string(), file.line_offset),
+            None => ("<unknown>".to_string(), 0),
+        };
+        format!(
+            "{}:{}:{} to {}:{}",
+            path,
+            span.start.line - offset,
+            span.start.column,
+            span.end.line.saturating_sub(offset),
+            span.end.column
+        )
+    }
+
+    /// Move a diagnostic made against [`Self::text`] to the files its spans
+    /// are in
+    ///
+    /// Returns the file of the primary span, whose source the diagnostic
+    /// should be displayed with.
+    pub fn localize(&self, mut diagnostic: Diagnostic) -> (Diagnostic, Option<&SourceFile>) {
+        let file = diagnostic.span.as_ref().and_then(|span| self.lookup(span.lo.line));
+        self.localize_spans(&mut diagnostic);
+        (diagnostic, file)
+    }
+
+    fn localize_spans(&self, diagnostic: &mut Diagnostic) {
+        let spans = diagnostic
+            .span
+            .iter_mut()
+            .chain(diagnostic.labels.iter_mut().map(|label| &mut label.span))
+            .chain(diagnostic.suggestions.iter_mut().map(|suggestion| &mut suggestion.span));
+        for span in spans {
+            self.localize_loc(&mut span.lo);
+            self.localize_loc(&mut span.hi);
+        }
+        for related in &mut diagnostic.related {
+            self.localize_spans(related);
+        }
+    }
+
+    fn localize_loc(&self, loc: &mut Loc) {
+        if let Some(file) = self.lookup(loc.line) {
+            loc.line -= file.line_offset;
+            loc.offset = loc.offset.saturating_sub(file.text_offset);
+            loc.file = Some(FileId::new(file.path.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::{Lexer, Position};
+
+    #[test]
+    fn test_files_get_their_own_lines() {
+        let mut map = SourceMap::new();
+        map.add_file("main.zl".into(), "fn main() {}".to_string());
+        let mut tokens = Lexer::new("\nfn helper() {}\n").lex_all().0;
+        map.add_file("helper.zl".into(), "\nfn helper() {}\n".to_string()).shift(&mut tokens);
+
+        assert_eq!(tokens[0].span.start.line, 3);
+        assert_eq!(map.text().lines().nth(2), Some("fn helper() {}"));
+        assert_eq!(map.lookup(1).map(|file| file.line_offset), Some(0));
+        assert_eq!(map.lookup(3).map(|file| file.line_offset), Some(1));
+        assert!(map.lookup(5).is_none());
+
+        let span = Span::new(Position::new(3, 4), Position::new(3, 10));
+        assert_eq!(map.location(&span), "helper.zl:2:4 to 2:10");
+    }
+
+    #[test]
+    fn test_localize_moves_spans_to_their_file() {
+        let mut map = SourceMap::new();
+        map.add_file("main.zl".into(), "mod m;\n".to_string());
+        map.add_file("m.zl".into(), "fn f() { x }\n".to_string());
+
+        let lo = Loc::new(None, 3, 10, 17);
+        let hi = Loc::new(None, 3, 11, 18);
+        let diagnostic = Diagnostic::error()
+            .message("cannot find value `x`")
+            .span(zulon_diagnostic::Span { lo, hi })
+            .build();
+
+        let (diagnostic, file) = map.localize(diagnostic);
+        let span = diagnostic.span.unwrap();
+        assert_eq!(file.map(|file| file.path.to_str()), Some(Some("m.zl")));
+        assert_eq!((span.lo.line, span.lo.offset), (1, 9));
+        assert_eq!(&map.files()[1].source[span.lo.offset..span.hi.offset], "x");
+    }
+}
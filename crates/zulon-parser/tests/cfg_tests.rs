@@ -0,0 +1,199 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `#[cfg(...)]` stripping and inner `#![...]` attributes
+
+use zulon_parser::ast::{Ast, AttributeArg, ItemKind, StatementKind};
+use zulon_parser::cfg::{strip_unconfigured, CfgOptions};
+use zulon_parser::Parser;
+
+/// Test helper to parse source code and strip it for the given configuration
+fn configure(source: &str, options: &CfgOptions) -> Ast {
+    let mut ast = Parser::from_source(source).parse().expect("Parsing failed");
+    let errors = strip_unconfigured(&mut ast, options);
+    assert!(errors.is_empty(), "unexpected cfg errors: {:?}", errors);
+    ast
+}
+
+fn item_names(ast: &Ast) -> Vec<&str> {
+    ast.items
+        .iter()
+        .map(|item| match &item.kind {
+            ItemKind::Function(func) => func.name.name.as_str(),
+            ItemKind::Struct(s) => s.name.name.as_str(),
+            ItemKind::Module(m) => m.name.name.as_str(),
+            other => panic!("unexpected item {:?}", other),
+        })
+        .collect()
+}
+
+const SOURCE: &str = r#"
+    #[cfg(test)]
+    fn test_helper() -> i32 { 1 }
+
+    #[cfg(target_os = "linux")]
+    fn platform() -> i32 { 1 }
+
+    #[cfg(not(target_os = "linux"))]
+    fn platform() -> i32 { 2 }
+
+    #[cfg(feature = "extra")]
+    fn extra() {}
+
+    fn main() -> i32 { platform() }
+"#;
+
+#[test]
+fn test_strip_items() {
+    let linux = CfgOptions::for_target("x86_64-unknown-linux-gnu");
+    let ast = configure(SOURCE, &linux);
+    assert_eq!(item_names(&ast), vec!["platform", "main"]);
+
+    let ast = configure(SOURCE, &linux.clone().with_test(true).with_feature("extra"));
+    assert_eq!(item_names(&ast), vec!["test_helper", "platform", "extra", "main"]);
+
+    // Exactly one `platform` survives on every target
+    let windows = CfgOptions::for_target("x86_64-pc-windows-msvc");
+    assert_eq!(item_names(&configure(SOURCE, &windows)), vec!["platform", "main"]);
+}
+
+#[test]
+fn test_strip_members_and_local_items() {
+    let source = r#"
+        struct Config {
+            name: i32,
+            #[cfg(feature = "debug")]
+            trace: i32,
+        }
+
+        enum Backend {
+            Cpu,
+            #[cfg(feature = "gpu")]
+            Gpu,
+        }
+
+        impl Config {
+            #[cfg(test)]
+            fn fixture() -> i32 { 0 }
+            fn default_name() -> i32 { 1 }
+        }
+
+        fn main() {
+            #[cfg(test)]
+            fn local() {}
+            let x = 1;
+        }
+    "#;
+
+    let ast = configure(source, &CfgOptions::new());
+    match &ast.items[0].kind {
+        ItemKind::Struct(s) => assert_eq!(s.fields.len(), 1),
+        other => panic!("expected struct, found {:?}", other),
+    }
+    match &ast.items[1].kind {
+        ItemKind::Enum(e) => assert_eq!(e.variants.len(), 1),
+        other => panic!("expected enum, found {:?}", other),
+    }
+    match &ast.items[2].kind {
+        ItemKind::Impl(i) => {
+            assert_eq!(i.items.len(), 1);
+            assert_eq!(i.items[0].name.name, "default_name");
+        }
+        other => panic!("expected impl, found {:?}", other),
+    }
+    match &ast.items[3].kind {
+        ItemKind::Function(func) => {
+            assert_eq!(func.body.statements.len(), 1);
+            assert!(matches!(func.body.statements[0].kind, StatementKind::Local(_)));
+        }
+        other => panic!("expected function, found {:?}", other),
+    }
+
+    let ast = configure(source, &CfgOptions::new().with_test(true));
+    match &ast.items[3].kind {
+        ItemKind::Function(func) => assert_eq!(func.body.statements.len(), 2),
+        other => panic!("expected function, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_inner_attributes() {
+    let source = r#"
+        #![cfg(test)]
+        //! Test support code.
+
+        fn helper() {}
+    "#;
+
+    let ast = Parser::from_source(source).parse().expect("Parsing failed");
+    assert_eq!(ast.attributes.len(), 2);
+    assert_eq!(ast.attributes[0].name.name, "cfg");
+    assert!(matches!(&ast.attributes[0].args[0], AttributeArg::Ident(id) if id.name == "test"));
+
+    assert!(configure(source, &CfgOptions::new()).items.is_empty());
+    assert_eq!(configure(source, &CfgOptions::new().with_test(true)).items.len(), 1);
+}
+
+#[test]
+fn test_module_inner_cfg() {
+    let source = r#"
+        mod tests {
+            #![cfg(test)]
+            fn check() {}
+        }
+
+        #[cfg(unix)]
+        mod sys {
+            fn open() {}
+        }
+
+        fn main() {}
+    "#;
+
+    let ast = configure(source, &CfgOptions::for_target("x86_64-unknown-linux-gnu"));
+    assert_eq!(item_names(&ast), vec!["sys", "main"]);
+}
+
+#[test]
+fn test_nested_predicate_arguments() {
+    let source = r#"
+        #[cfg(all(unix, any(feature = "a", feature = "b"), not(test)))]
+        fn f() {}
+    "#;
+
+    let ast = Parser::from_source(source).parse().expect("Parsing failed");
+    match &ast.items[0].attributes[0].args[..] {
+        [AttributeArg::List(all, args)] => {
+            assert_eq!(all.name, "all");
+            assert_eq!(args.len(), 3);
+            assert!(matches!(&args[1], AttributeArg::List(any, inner) if any.name == "any" && inner.len() == 2));
+        }
+        other => panic!("expected nested predicate, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_malformed_cfg_is_reported() {
+    let source = r#"
+        #[cfg(test, unix)]
+        fn a() {}
+
+        #[cfg(maybe(test))]
+        fn b() {}
+    "#;
+
+    let mut ast = Parser::from_source(source).parse().expect("Parsing failed");
+    let errors = strip_unconfigured(&mut ast, &CfgOptions::new());
+    assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+    assert_eq!(ast.items.len(), 2);
+}
+
+#[test]
+fn test_inner_attribute_after_items_is_an_error() {
+    let source = r#"
+        fn main() {}
+        #![cfg(test)]
+    "#;
+
+    assert!(Parser::from_source(source).parse().is_err());
+}
@@ -0,0 +1,208 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for doc comments and `#[doc]` attributes
+
+use zulon_parser::ast::{doc_string, Ast, ItemKind, StatementKind, TraitItemKind};
+use zulon_parser::Parser;
+
+/// Test helper to parse source code
+fn parse(source: &str) -> Ast {
+    let mut parser = Parser::from_source(source);
+    parser.parse().expect("failed to parse")
+}
+
+#[test]
+fn test_outer_doc_comments_attach_to_item() {
+    let source = r#"
+        /// Adds two numbers.
+        ///
+        /// Returns the sum.
+        fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        // A plain comment is not documentation
+        fn main() {}
+    "#;
+
+    let ast = parse(source);
+    assert_eq!(
+        doc_string(&ast.items[0].attributes).as_deref(),
+        Some("Adds two numbers.\n\nReturns the sum.")
+    );
+    assert_eq!(doc_string(&ast.items[1].attributes), None);
+
+    // The docs are kept on the item only, not repeated on the function
+    assert_eq!(ast.items[0].attributes.len(), 3);
+    match &ast.items[0].kind {
+        ItemKind::Function(func) => assert!(func.attributes.is_empty()),
+        other => panic!("expected function, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_doc_comments_mixed_with_attributes() {
+    let source = r#"
+        /// First line
+        #[test]
+        #[doc = "Second line"]
+        fn check() {}
+    "#;
+
+    let ast = parse(source);
+    let attributes = &ast.items[0].attributes;
+    assert_eq!(attributes.len(), 3);
+    assert_eq!(attributes[1].name.name, "test");
+    assert_eq!(doc_string(attributes).as_deref(), Some("First line\nSecond line"));
+}
+
+#[test]
+fn test_inner_doc_comments() {
+    let source = r#"
+        //! Crate documentation.
+        //! More details.
+
+        mod inner {
+            //! Module documentation.
+            fn f() {}
+        }
+    "#;
+
+    let ast = parse(source);
+    assert_eq!(
+        doc_string(&ast.attributes).as_deref(),
+        Some("Crate documentation.\nMore details.")
+    );
+
+    match &ast.items[0].kind {
+        ItemKind::Module(module) => {
+            assert_eq!(doc_string(&module.attributes).as_deref(), Some("Module documentation."));
+            assert_eq!(module.items.as_ref().unwrap().len(), 1);
+        }
+        other => panic!("expected module, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_doc_comments_on_members() {
+    let source = r#"
+        struct Point {
+            /// Horizontal
+            x: i32,
+            /// Vertical
+            y: i32,
+        }
+
+        enum Shape {
+            /// A circle
+            Circle(f64),
+            Square,
+        }
+
+        trait Area {
+            /// Compute the area
+            fn area(scale: f64) -> f64 { scale }
+            /// Unit of measure
+            type Unit;
+        }
+
+        impl Point {
+            /// X coordinate of the origin
+            fn origin_x() -> i32 {
+                0
+            }
+        }
+    "#;
+
+    let ast = parse(source);
+
+    match &ast.items[0].kind {
+        ItemKind::Struct(s) => {
+            assert_eq!(doc_string(&s.fields[0].attributes).as_deref(), Some("Horizontal"));
+            assert_eq!(doc_string(&s.fields[1].attributes).as_deref(), Some("Vertical"));
+        }
+        other => panic!("expected struct, found {:?}", other),
+    }
+
+    match &ast.items[1].kind {
+        ItemKind::Enum(e) => {
+            assert_eq!(doc_string(&e.variants[0].attributes).as_deref(), Some("A circle"));
+            assert_eq!(doc_string(&e.variants[1].attributes), None);
+        }
+        other => panic!("expected enum, found {:?}", other),
+    }
+
+    match &ast.items[2].kind {
+        ItemKind::Trait(t) => {
+            assert!(matches!(t.items[0].kind, TraitItemKind::Method(_)));
+            assert_eq!(doc_string(&t.items[0].attributes).as_deref(), Some("Compute the area"));
+            assert!(matches!(t.items[1].kind, TraitItemKind::AssociatedType(..)));
+            assert_eq!(doc_string(&t.items[1].attributes).as_deref(), Some("Unit of measure"));
+        }
+        other => panic!("expected trait, found {:?}", other),
+    }
+
+    match &ast.items[3].kind {
+        ItemKind::Impl(i) => {
+            assert_eq!(doc_string(&i.items[0].attributes).as_deref(), Some("X coordinate of the origin"));
+        }
+        other => panic!("expected impl, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_doc_comments_inside_function_bodies() {
+    let source = r#"
+        fn main() -> i32 {
+            /// Local helper
+            fn helper() -> i32 { 1 }
+            /// Not attached to anything useful
+            let x = helper();
+            x
+            /// Trailing
+        }
+    "#;
+
+    let ast = parse(source);
+    let body = match &ast.items[0].kind {
+        ItemKind::Function(func) => &func.body,
+        other => panic!("expected function, found {:?}", other),
+    };
+
+    match &body.statements[0].kind {
+        StatementKind::Item(item) => {
+            assert_eq!(doc_string(&item.attributes).as_deref(), Some("Local helper"));
+        }
+        other => panic!("expected item statement, found {:?}", other),
+    }
+    assert!(matches!(body.statements[1].kind, StatementKind::Local(_)));
+}
+
+/// Assert that `source` fails with a dangling doc comment on `line`
+fn assert_documents_nothing(source: &str, line: usize) {
+    let mut parser = Parser::from_source(source);
+    match parser.parse() {
+        Err(zulon_parser::ParseError::InvalidSyntax { message, span }) => {
+            assert_eq!(message, "doc comment documents nothing");
+            assert_eq!(span.start.line, line);
+        }
+        other => panic!("expected a dangling doc comment error, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_doc_comment_at_end_of_file() {
+    assert_documents_nothing("fn main() {}\n\n/// Dangling\n", 3);
+}
+
+#[test]
+fn test_doc_comment_at_end_of_struct_body() {
+    assert_documents_nothing("struct Point {\n    x: i32,\n    /// Dangling\n}\n", 3);
+}
+
+#[test]
+fn test_doc_comment_before_match_arm() {
+    let source = "fn main() {\n    match 1 {\n        /// One\n        1 => {},\n        _ => {},\n    }\n}\n";
+    assert_documents_nothing(source, 3);
+}
@@ -0,0 +1,254 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for the error-recovering parse mode
+
+use zulon_parser::ast::{Ast, ExpressionKind, ItemKind, StatementKind};
+use zulon_parser::{ParseError, Parser, MAX_NESTING_DEPTH};
+
+/// Test helper to parse source code with recovery enabled
+fn parse_recovering(source: &str) -> (Ast, Vec<ParseError>) {
+    let mut parser = Parser::from_source(source);
+    parser.parse_with_recovery()
+}
+
+fn function_body(ast: &Ast, index: usize) -> &zulon_parser::ast::Block {
+    match &ast.items[index].kind {
+        ItemKind::Function(func) => &func.body,
+        other => panic!("expected function, found {:?}", other),
+    }
+}
+
+// ============================================================================
+// Valid Input
+// ============================================================================
+
+#[test]
+fn test_recovery_valid_source_has_no_errors() {
+    let source = r#"
+        fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        fn main() -> i32 {
+            let x = add(1, 2);
+            x
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    assert_eq!(ast.items.len(), 2);
+}
+
+#[test]
+fn test_strict_parse_still_stops_at_first_error() {
+    let source = r#"
+        fn main() {
+            let = 1;
+            let = 2;
+        }
+    "#;
+
+    let mut parser = Parser::from_source(source);
+    assert!(parser.parse().is_err());
+}
+
+// ============================================================================
+// Item-Level Recovery
+// ============================================================================
+
+#[test]
+fn test_recovery_reports_errors_in_multiple_items() {
+    let source = r#"
+        fn first( {
+            0
+        }
+
+        struct Point { x: i32, y: i32 }
+
+        fn second() -> {
+            1
+        }
+
+        fn third() -> i32 {
+            3
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+    let kinds: Vec<_> = ast.items.iter().map(|item| &item.kind).collect();
+    assert!(matches!(kinds[0], ItemKind::Error));
+    assert!(matches!(kinds[1], ItemKind::Struct(_)));
+    assert!(matches!(kinds[2], ItemKind::Error));
+    assert!(matches!(kinds[3], ItemKind::Function(f) if f.name.name == "third"));
+}
+
+#[test]
+fn test_recovery_skips_garbage_between_items() {
+    let source = r#"
+        42 + garbage;
+
+        fn main() -> i32 {
+            0
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+    assert!(matches!(ast.items[0].kind, ItemKind::Error));
+    assert!(matches!(ast.items.last().unwrap().kind, ItemKind::Function(_)));
+}
+
+// ============================================================================
+// Statement-Level Recovery
+// ============================================================================
+
+#[test]
+fn test_recovery_reports_every_bad_statement() {
+    let source = r#"
+        fn main() -> i32 {
+            let = 1;
+            let y = 2;
+            let z = ;
+            y
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+    let body = function_body(&ast, 0);
+    assert_eq!(body.statements.len(), 3);
+    assert!(matches!(body.statements[0].kind, StatementKind::Error));
+    assert!(matches!(body.statements[1].kind, StatementKind::Local(_)));
+    assert!(matches!(body.statements[2].kind, StatementKind::Error));
+    assert!(body.trailing_expr.is_some());
+}
+
+#[test]
+fn test_recovery_missing_semicolon_keeps_next_statement() {
+    let source = r#"
+        fn main() {
+            let x = 1
+            let y = 2;
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+
+    let body = function_body(&ast, 0);
+    assert!(matches!(
+        &body.statements.last().unwrap().kind,
+        StatementKind::Local(local) if local.name.name == "y"
+    ));
+}
+
+#[test]
+fn test_recovery_in_nested_block() {
+    let source = r#"
+        fn main() {
+            if true {
+                let = 1;
+            }
+            let ok = 2;
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+    assert_eq!(function_body(&ast, 0).statements.len(), 2);
+}
+
+// ============================================================================
+// Delimiter-Level Recovery
+// ============================================================================
+
+#[test]
+fn test_recovery_inside_call_arguments() {
+    let source = r#"
+        fn main() {
+            foo(1, +, 3);
+            bar(;
+        }
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+
+    let body = function_body(&ast, 0);
+    match &body.statements[0].kind {
+        StatementKind::Expr(expr) => match &expr.kind {
+            ExpressionKind::Call(_, args) => {
+                assert_eq!(args.len(), 3);
+                assert!(matches!(args[1].kind, ExpressionKind::Error));
+            }
+            other => panic!("expected call, found {:?}", other),
+        },
+        other => panic!("expected expression statement, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_recovery_unclosed_function_at_eof() {
+    let source = r#"
+        fn ok() {}
+
+        fn broken() {
+            let x = 1;
+    "#;
+
+    let (ast, errors) = parse_recovering(source);
+    assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+    assert!(matches!(ast.items[0].kind, ItemKind::Function(_)));
+    assert!(matches!(ast.items[1].kind, ItemKind::Error));
+}
+
+// ============================================================================
+// Nesting Limit
+// ============================================================================
+
+/// Parse on a thread with a main-thread-sized stack
+///
+/// Unoptimized parser frames are large, so even nesting within the limit
+/// needs more than the 2 MiB a test thread gets by default.
+fn parse_on_large_stack(source: String) -> (Result<Ast, ParseError>, (Ast, Vec<ParseError>)) {
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(move || (Parser::from_source(&source).parse(), parse_recovering(&source)))
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+#[test]
+fn test_deep_nesting_is_an_error() {
+    let depth = MAX_NESTING_DEPTH * 10;
+    let sources = [
+        format!("fn f() {{ {}1{} }}", "(".repeat(depth), ")".repeat(depth)),
+        format!("fn f() {}{}", "{".repeat(depth), "}".repeat(depth)),
+        format!("fn f() {{ {}1 }}", "-".repeat(depth)),
+        format!("fn f(x: {}i32) {{}}", "&".repeat(depth)),
+        format!("{}{}", "mod m { ".repeat(depth), "}".repeat(depth)),
+        format!("fn f() {{ {}", "[".repeat(depth)),
+    ];
+
+    for source in sources {
+        let (strict, (_, errors)) = parse_on_large_stack(source);
+        let error = strict.expect_err("deep nesting should not parse");
+        assert!(error.to_string().contains("nesting"), "unexpected error: {}", error);
+        assert!(!errors.is_empty());
+    }
+}
+
+#[test]
+fn test_nesting_within_limit_parses() {
+    let depth = MAX_NESTING_DEPTH / 4;
+    let source = format!("fn f() {{ {}1{} }}", "(".repeat(depth), ")".repeat(depth));
+
+    let (strict, _) = parse_on_large_stack(source);
+    assert!(strict.is_ok(), "error: {:?}", strict.err());
+}
@@ -0,0 +1,162 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for `if let`, `while let` and `let...else`
+
+use zulon_parser::ast::{Ast, Block, ExpressionKind, ItemKind, Pattern, StatementKind};
+use zulon_parser::Parser;
+
+/// Test helper to parse source code
+fn parse(source: &str) -> Ast {
+    let mut parser = Parser::from_source(source);
+    parser.parse().expect("Parsing failed")
+}
+
+fn main_body(ast: &Ast) -> &Block {
+    match &ast.items[0].kind {
+        ItemKind::Function(func) => &func.body,
+        other => panic!("expected function, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_if_let() {
+    let source = r#"
+        fn main() -> i32 {
+            if let Some(x) = lookup(1) {
+                x
+            } else {
+                0
+            }
+        }
+    "#;
+
+    let ast = parse(source);
+    let body = main_body(&ast);
+    match &body.trailing_expr.as_ref().unwrap().kind {
+        ExpressionKind::If(condition, _, else_block) => {
+            match &condition.kind {
+                ExpressionKind::Let(pattern, value) => {
+                    assert!(matches!(&**pattern, Pattern::TupleVariant(path, _) if path[0].name == "Some"));
+                    assert!(matches!(value.kind, ExpressionKind::Call(..)));
+                }
+                other => panic!("expected let condition, found {:?}", other),
+            }
+            assert!(else_block.is_some());
+        }
+        other => panic!("expected if, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_plain_if_is_unchanged() {
+    let source = r#"
+        fn main() -> i32 {
+            if x > 1 { 1 } else { 0 }
+        }
+    "#;
+
+    let ast = parse(source);
+    match &main_body(&ast).trailing_expr.as_ref().unwrap().kind {
+        ExpressionKind::If(condition, _, _) => {
+            assert!(matches!(condition.kind, ExpressionKind::Binary(..)));
+        }
+        other => panic!("expected if, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_while_let() {
+    let source = r#"
+        fn main() {
+            while let (a, b) = next_pair() {
+                consume(a, b);
+            }
+        }
+    "#;
+
+    let ast = parse(source);
+    match &main_body(&ast).trailing_expr.as_ref().unwrap().kind {
+        ExpressionKind::While(condition, _, _) => match &condition.kind {
+            ExpressionKind::Let(pattern, _) => {
+                let names: Vec<_> = pattern.bindings().iter().map(|id| id.name.as_str()).collect();
+                assert_eq!(names, vec!["a", "b"]);
+            }
+            other => panic!("expected let condition, found {:?}", other),
+        },
+        other => panic!("expected while, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_let_else() {
+    let source = r#"
+        fn main() -> i32 {
+            let Some(value) = lookup(1) else {
+                return 0;
+            };
+            let count = 1;
+            value + count
+        }
+    "#;
+
+    let ast = parse(source);
+    let body = main_body(&ast);
+    assert_eq!(body.statements.len(), 2);
+
+    match &body.statements[0].kind {
+        StatementKind::LetElse(let_else) => {
+            assert!(matches!(&let_else.pattern, Pattern::TupleVariant(..)));
+            assert!(let_else.type_annotation.is_none());
+            assert_eq!(let_else.else_block.statements.len(), 1);
+            assert!(!let_else.is_mutable);
+        }
+        other => panic!("expected let-else, found {:?}", other),
+    }
+    assert!(matches!(&body.statements[1].kind, StatementKind::Local(local) if local.name.name == "count"));
+}
+
+#[test]
+fn test_parse_let_else_with_identifier_pattern() {
+    let source = r#"
+        fn main() {
+            let mut x: i32 = compute() else { return; };
+        }
+    "#;
+
+    let ast = parse(source);
+    match &main_body(&ast).statements[0].kind {
+        StatementKind::LetElse(let_else) => {
+            assert!(matches!(&let_else.pattern, Pattern::Identifier(id) if id.name == "x"));
+            assert!(let_else.type_annotation.is_some());
+            assert!(let_else.is_mutable);
+        }
+        other => panic!("expected let-else, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_let_pattern_without_else_is_an_error() {
+    let source = r#"
+        fn main() {
+            let Some(x) = lookup(1);
+        }
+    "#;
+
+    let mut parser = Parser::from_source(source);
+    assert!(parser.parse().is_err());
+}
+
+#[test]
+fn test_irrefutable_let_pattern_is_unsupported() {
+    let source = "fn main() {\n    let (a, b) = (1, 2);\n}\n";
+
+    let mut parser = Parser::from_source(source);
+    match parser.parse() {
+        Err(zulon_parser::ParseError::InvalidSyntax { message, span }) => {
+            assert_eq!(message, "destructuring `let` is not supported; add an `else` block or use `match`");
+            assert_eq!(span.start.line, 2);
+        }
+        other => panic!("expected an unsupported pattern error, found {:?}", other),
+    }
+}
@@ -545,3 +545,82 @@ fn test_parse_match_with_complex_blocks() {
     let ast = parse(source);
     assert_eq!(ast.items.len(), 3);  // println, print_int, main
 }
+
+// ============================================================================
+// Pattern Tests
+// ============================================================================
+
+/// The patterns of each arm of the match that is `main`'s trailing expression
+fn arm_patterns(ast: &zulon_parser::ast::Ast) -> Vec<Vec<zulon_parser::ast::Pattern>> {
+    use zulon_parser::ast::{ExpressionKind, ItemKind};
+
+    let ItemKind::Function(func) = &ast.items[0].kind else { panic!("expected function") };
+    let Some(trailing) = &func.body.trailing_expr else { panic!("expected trailing expression") };
+    let ExpressionKind::Match(_, arms) = &trailing.kind else { panic!("expected match") };
+    arms.iter().map(|arm| arm.patterns.clone()).collect()
+}
+
+#[test]
+fn test_parse_range_and_literal_patterns() {
+    use zulon_parser::ast::{Literal, Pattern, RangeKind};
+
+    let source = r#"
+        fn main(c: char) -> i32 {
+            match c {
+                'a'..='z' => 1,
+                '0'..'9' | '_' => 2,
+                _ => 0
+            }
+        }
+    "#;
+
+    let arms = arm_patterns(&parse(source));
+    assert!(matches!(
+        &arms[0][0],
+        Pattern::Range(start, RangeKind::Inclusive, end)
+            if matches!(**start, Pattern::Literal(Literal::Char('a')))
+                && matches!(**end, Pattern::Literal(Literal::Char('z')))
+    ));
+    assert!(matches!(&arms[1][0], Pattern::Range(_, RangeKind::Exclusive, _)));
+    assert!(matches!(&arms[1][1], Pattern::Literal(Literal::Char('_'))));
+}
+
+#[test]
+fn test_parse_string_literal_pattern() {
+    use zulon_parser::ast::{Literal, Pattern};
+
+    let source = r#"
+        fn main(s: string) -> i32 {
+            match s {
+                "yes" => 1,
+                _ => 0
+            }
+        }
+    "#;
+
+    let arms = arm_patterns(&parse(source));
+    assert!(matches!(&arms[0][0], Pattern::Literal(Literal::String(s)) if s == "yes"));
+}
+
+#[test]
+fn test_parse_slice_and_nested_or_patterns() {
+    use zulon_parser::ast::Pattern;
+
+    let source = r#"
+        fn main(x: i32) -> i32 {
+            match x {
+                [first, .., last] => 1,
+                [..] => 2,
+                Some(1 | 2) => 3,
+                (true | false, _) => 4,
+                _ => 0
+            }
+        }
+    "#;
+
+    let arms = arm_patterns(&parse(source));
+    assert!(matches!(&arms[0][0], Pattern::Slice(before, _, after) if before.len() == 1 && after.len() == 1));
+    assert!(matches!(&arms[1][0], Pattern::Slice(before, _, after) if before.is_empty() && after.is_empty()));
+    assert!(matches!(&arms[2][0], Pattern::TupleVariant(_, fields) if matches!(&fields[0], Pattern::Or(alts) if alts.len() == 2)));
+    assert!(matches!(&arms[3][0], Pattern::Tuple(fields) if matches!(&fields[0], Pattern::Or(_))));
+}
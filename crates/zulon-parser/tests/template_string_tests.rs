@@ -3,7 +3,9 @@
 
 //! Comprehensive tests for template string parsing
 
-use zulon_parser::Parser;
+use zulon_parser::ast::{ExpressionKind, ItemKind, StatementKind, TemplateStringPart};
+use zulon_parser::format::{Align, Argument, Count, FormatKind};
+use zulon_parser::{ParseError, Parser};
 
 /// Test helper to parse source code
 fn parse(source: &str) -> zulon_parser::ast::Ast {
@@ -275,13 +277,8 @@ fn test_parse_template_string_in_block() {
         }
     "#;
 
-    // This currently fails due to parser limitations with blocks
-    let result = std::panic::catch_unwind(|| {
-        parse(source)
-    });
-
-    // Document that this is a known limitation
-    assert!(result.is_err(), "Template strings in block expressions are currently limited");
+    let ast = parse(source);
+    assert_eq!(ast.items.len(), 2);
 }
 
 #[test]
@@ -436,3 +433,73 @@ fn test_parse_very_long_template_string() {
     let ast = parse(source);
     assert_eq!(ast.items.len(), 2);
 }
+
+// ============================================================================
+// Interpolation
+// ============================================================================
+
+/// The parts of the template string initializing the first `let`
+fn template_parts(source: &str) -> Vec<TemplateStringPart> {
+    let ast = parse(source);
+    let ItemKind::Function(func) = &ast.items[0].kind else {
+        panic!("expected a function");
+    };
+    let StatementKind::Local(local) = &func.body.statements[0].kind else {
+        panic!("expected a let statement");
+    };
+    match &local.init.as_ref().unwrap().kind {
+        ExpressionKind::TemplateString(template) => template.parts.clone(),
+        other => panic!("expected a template string, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_interpolation() {
+    let parts = template_parts("fn main() { let s = `Hi ${user.name}, you have ${count(1, 2)} messages`; }");
+    assert_eq!(parts.len(), 5);
+    assert!(matches!(&parts[0], TemplateStringPart::Static(s) if s == "Hi "));
+    assert!(matches!(&parts[1], TemplateStringPart::Expr(e, spec)
+        if matches!(e.kind, ExpressionKind::FieldAccess(..)) && spec.is_default()));
+    assert!(matches!(&parts[3], TemplateStringPart::Expr(e, _) if matches!(e.kind, ExpressionKind::Call(..))));
+    assert!(matches!(&parts[4], TemplateStringPart::Static(s) if s == " messages"));
+}
+
+#[test]
+fn test_parse_interpolation_with_format_spec() {
+    let parts = template_parts("fn main() { let s = `${price:>8.2} ${{ let p: i32 = 1; p }:?} ${a::b:w$}`; }");
+    let specs: Vec<_> = parts
+        .iter()
+        .filter_map(|part| match part {
+            TemplateStringPart::Expr(_, spec) => Some(spec),
+            TemplateStringPart::Static(_) => None,
+        })
+        .collect();
+    assert_eq!(specs.len(), 3);
+    assert_eq!(specs[0].align, Some(Align::Right));
+    assert_eq!(specs[0].width, Some(Count::Is(8)));
+    assert_eq!(specs[0].precision, Some(Count::Is(2)));
+    assert_eq!(specs[1].kind, FormatKind::Debug);
+    assert_eq!(specs[2].width, Some(Count::Argument(Argument::Name("w".to_string()))));
+}
+
+#[test]
+fn test_interpolation_spans_point_into_the_template() {
+    let parts = template_parts("fn main() {\n    let s = `a ${value}`;\n}");
+    let TemplateStringPart::Expr(expr, _) = &parts[1] else {
+        panic!("expected an interpolation");
+    };
+    assert_eq!(expr.span.start.line, 2);
+    assert_eq!(expr.span.start.column, 18);
+}
+
+#[test]
+fn test_interpolation_errors() {
+    let error = Parser::from_source("fn main() { let s = `${x:q}`; }").parse().unwrap_err();
+    assert!(matches!(&error, ParseError::Format(e) if e.message == "unknown format trait `q`"));
+
+    let error = Parser::from_source("fn main() { let s = `${x:.*}`; }").parse().unwrap_err();
+    assert!(matches!(&error, ParseError::Format(e) if e.message.contains("from names")));
+
+    assert!(Parser::from_source("fn main() { let s = `${}`; }").parse().is_err());
+    assert!(Parser::from_source("fn main() { let s = `${a b}`; }").parse().is_err());
+}
@@ -0,0 +1,74 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for visibility qualifiers and the forms of `use`
+
+use zulon_parser::ast::{ItemKind, UsePath, Visibility};
+use zulon_parser::Parser;
+
+fn names(path: &[zulon_parser::ast::Identifier]) -> Vec<&str> {
+    path.iter().map(|ident| ident.name.as_str()).collect()
+}
+
+#[test]
+fn test_visibility_qualifiers() {
+    let source = r#"
+        fn private() {}
+        pub fn public() {}
+        pub(crate) fn in_crate() {}
+        pub(super) fn in_parent() {}
+        pub(self) fn in_self() {}
+        pub(in crate::outer) fn in_outer() {}
+    "#;
+    let ast = Parser::from_source(source).parse().expect("Parsing failed");
+
+    let visibilities: Vec<_> = ast.items.iter().map(|item| item.visibility.clone()).collect();
+    assert_eq!(visibilities[0], Visibility::Private);
+    assert_eq!(visibilities[1], Visibility::Public);
+    assert_eq!(visibilities[2], Visibility::Crate);
+    for (visibility, expected) in visibilities[3..].iter().zip([vec!["super"], vec!["self"], vec!["crate", "outer"]]) {
+        match visibility {
+            Visibility::Restricted(path) => assert_eq!(names(path), expected),
+            other => panic!("expected a restricted visibility, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_bad_visibility_is_an_error() {
+    assert!(Parser::from_source("pub(everyone) fn f() {}").parse().is_err());
+}
+
+#[test]
+fn test_use_forms() {
+    let source = r#"
+        use a::b::c;
+        use a::b::c as d;
+        use a::b::*;
+        pub use a::{b, c};
+    "#;
+    let ast = Parser::from_source(source).parse().expect("Parsing failed");
+
+    let uses: Vec<_> = ast
+        .items
+        .iter()
+        .map(|item| match &item.kind {
+            ItemKind::Use(use_item) => use_item,
+            other => panic!("unexpected item {:?}", other),
+        })
+        .collect();
+
+    assert!(matches!(&uses[0].path, UsePath::Simple(path) if names(path) == ["a", "b", "c"]));
+    assert!(uses[0].alias.is_none());
+    assert_eq!(uses[1].alias.as_ref().map(|alias| alias.name.as_str()), Some("d"));
+    assert!(matches!(&uses[2].path, UsePath::Glob(path) if names(path) == ["a", "b"]));
+    match &uses[3].path {
+        UsePath::List(prefix, items) => {
+            assert_eq!(names(prefix), ["a"]);
+            assert_eq!(names(items), ["b", "c"]);
+        }
+        other => panic!("expected a use list, got {:?}", other),
+    }
+    assert!(uses[3].is_pub);
+    assert_eq!(ast.items[3].visibility, Visibility::Public);
+}
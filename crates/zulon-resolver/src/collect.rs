@@ -0,0 +1,144 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Defining items before any name is resolved
+//!
+//! Items can be used before they are defined, so every item of a module,
+//! and every variant, trait item and effect operation inside one, gets its
+//! definition first. Items in blocks are collected when resolution reaches
+//! the block.
+
+use crate::imports::Import;
+use crate::{DefId, DefKind, Namespace, Resolver, Vis};
+use zulon_parser::ast::{Identifier, Item, ItemKind, TraitItemKind, VariantField};
+use zulon_parser::Span;
+
+impl<'ast> Resolver<'ast> {
+    /// Define the items of a module and queue its imports
+    pub(crate) fn collect_module(&mut self, items: impl IntoIterator<Item = &'ast Item>, module: DefId) {
+        let saved = std::mem::replace(&mut self.module, module);
+        for item in items {
+            let vis = self.vis(&item.visibility, module);
+            if let ItemKind::Use(use_item) = &item.kind {
+                self.imports.extend(Import::from_use(use_item, module, vis));
+                continue;
+            }
+            for (ns, id) in self.collect_item(item, module) {
+                let definition = self.resolutions.definition(id);
+                let (name, span) = (definition.name.clone(), definition.span);
+                self.declare(module, ns, &name, id, span, vis);
+            }
+        }
+        self.module = saved;
+    }
+
+    /// Define an item and everything declared inside it
+    ///
+    /// Returns the definitions the item adds to its scope, with the
+    /// namespaces they are named in. Imports are left to the caller.
+    pub(crate) fn collect_item(&mut self, item: &'ast Item, parent: DefId) -> Vec<(Namespace, DefId)> {
+        match &item.kind {
+            ItemKind::Function(func) => {
+                let id = self.define_item(&func.name, DefKind::Function, parent);
+                vec![(Namespace::Value, id)]
+            }
+            ItemKind::ExternFunction(func) => {
+                let id = self.define_item(&func.name, DefKind::ExternFunction, parent);
+                vec![(Namespace::Value, id)]
+            }
+            ItemKind::Struct(struct_def) => {
+                let id = self.define_item(&struct_def.name, DefKind::Struct, parent);
+                // A struct without fields is also a value: `let s = Marker;`
+                if struct_def.fields.is_empty() {
+                    vec![(Namespace::Type, id), (Namespace::Value, id)]
+                } else {
+                    vec![(Namespace::Type, id)]
+                }
+            }
+            ItemKind::Enum(enum_def) => {
+                let id = self.define_item(&enum_def.name, DefKind::Enum, parent);
+                for variant in &enum_def.variants {
+                    let variant_id = self.define_item(&variant.name, DefKind::Variant, id);
+                    self.declare(id, Namespace::Type, &variant.name.name, variant_id, variant.name.span, Vis::Public);
+                    // Unit and tuple variants are values: `Color::Red`, `Some(x)`
+                    if !variant.fields.iter().any(|field| matches!(field, VariantField::Named(..))) {
+                        self.declare(id, Namespace::Value, &variant.name.name, variant_id, variant.name.span, Vis::Public);
+                    }
+                }
+                vec![(Namespace::Type, id)]
+            }
+            ItemKind::Trait(trait_def) => {
+                let id = self.define_item(&trait_def.name, DefKind::Trait, parent);
+                for trait_item in &trait_def.items {
+                    let (name, kind, ns) = match &trait_item.kind {
+                        TraitItemKind::Method(func) | TraitItemKind::RequiredMethod(func) => (&func.name, DefKind::AssocFn, Namespace::Value),
+                        TraitItemKind::AssociatedType(name, _) => (name, DefKind::AssocType, Namespace::Type),
+                        TraitItemKind::Const(const_def) => (&const_def.name, DefKind::AssocConst, Namespace::Value),
+                    };
+                    let item_id = self.define_item(name, kind, id);
+                    self.declare(id, ns, &name.name, item_id, name.span, Vis::Public);
+                }
+                vec![(Namespace::Type, id)]
+            }
+            ItemKind::Impl(impl_def) => {
+                let id = self.define_anonymous("impl", impl_def.impl_span, DefKind::Impl, parent);
+                self.item_defs.insert(impl_def as *const _ as *const (), id);
+                for (name, _) in &impl_def.assoc_types {
+                    let type_id = self.define_item(name, DefKind::AssocType, id);
+                    self.declare(id, Namespace::Type, &name.name, type_id, name.span, Vis::Public);
+                }
+                for method in &impl_def.items {
+                    let method_id = self.define_item(&method.name, DefKind::AssocFn, id);
+                    self.declare(id, Namespace::Value, &method.name.name, method_id, method.name.span, Vis::Public);
+                }
+                self.pending_impls.push((id, impl_def, self.module));
+                Vec::new()
+            }
+            ItemKind::TypeAlias(alias) => {
+                let id = self.define_item(&alias.name, DefKind::TypeAlias, parent);
+                vec![(Namespace::Type, id)]
+            }
+            ItemKind::Const(const_def) => {
+                let id = self.define_item(&const_def.name, DefKind::Const, parent);
+                vec![(Namespace::Value, id)]
+            }
+            ItemKind::Static(static_def) => {
+                let id = self.define_item(&static_def.name, DefKind::Static, parent);
+                vec![(Namespace::Value, id)]
+            }
+            ItemKind::Module(module) => {
+                let id = self.define_item(&module.name, DefKind::Module, parent);
+                if let Some(items) = &module.items {
+                    self.collect_module(items.iter().map(|item| &**item), id);
+                }
+                vec![(Namespace::Type, id)]
+            }
+            ItemKind::ExternCrate(extern_crate) => {
+                let name = extern_crate.rename.as_ref().unwrap_or(&extern_crate.name);
+                let id = self.define_item(name, DefKind::ExternCrate, parent);
+                vec![(Namespace::Type, id)]
+            }
+            ItemKind::Effect(effect) => {
+                let id = self.define_item(&effect.name, DefKind::Effect, parent);
+                for operation in &effect.operations {
+                    let operation_id = self.define_item(&operation.name, DefKind::EffectOperation, id);
+                    self.declare(id, Namespace::Value, &operation.name.name, operation_id, operation.name.span, Vis::Public);
+                }
+                vec![(Namespace::Type, id)]
+            }
+            // Macros are expanded before parsing; imports are queued by the caller
+            ItemKind::MacroDef(_) | ItemKind::Use(_) | ItemKind::Error => Vec::new(),
+        }
+    }
+
+    /// Define a named item, remembering the definition for its node
+    fn define_item(&mut self, name: &'ast Identifier, kind: DefKind, parent: DefId) -> DefId {
+        let id = self.define(name, kind, parent);
+        self.item_defs.insert(name as *const Identifier as *const (), id);
+        id
+    }
+
+    fn define_anonymous(&mut self, name: &str, span: Span, kind: DefKind, parent: DefId) -> DefId {
+        self.define(&Identifier::new(span, name), kind, parent)
+    }
+}
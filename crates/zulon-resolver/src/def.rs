@@ -0,0 +1,327 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Definitions and what names resolve to
+
+use std::fmt;
+use zulon_parser::Span;
+
+/// Identifies one definition in [`crate::Resolutions`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DefId(pub(crate) u32);
+
+impl DefId {
+    /// The crate root module
+    pub const ROOT: DefId = DefId(0);
+
+    /// Index of the definition, in the order definitions were made
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The two namespaces names live in
+///
+/// A type and a value may share a name: `struct Point` and `fn Point()` can
+/// coexist in one module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    /// Modules, types, traits, effects and type parameters
+    Type,
+    /// Functions, constants, statics, locals and const parameters
+    Value,
+}
+
+impl Namespace {
+    pub(crate) fn other(self) -> Namespace {
+        match self {
+            Namespace::Type => Namespace::Value,
+            Namespace::Value => Namespace::Type,
+        }
+    }
+}
+
+/// What kind of thing a definition is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DefKind {
+    /// `mod name { ... }`, `mod name;` or the crate root
+    Module,
+    /// `struct Name { ... }`
+    Struct,
+    /// `enum Name { ... }`
+    Enum,
+    /// A variant of an enum
+    Variant,
+    /// `trait Name { ... }`
+    Trait,
+    /// `type Name = Type;`
+    TypeAlias,
+    /// `effect Name { ... }`
+    Effect,
+    /// An operation of an effect
+    EffectOperation,
+    /// `fn name() { ... }`
+    Function,
+    /// `extern fn name();`
+    ExternFunction,
+    /// A function in an `impl` or `trait`
+    AssocFn,
+    /// `type Name;` in a trait
+    AssocType,
+    /// `const NAME: T;` in a trait
+    AssocConst,
+    /// `const NAME: T = value;`
+    Const,
+    /// `static NAME: T = value;`
+    Static,
+    /// `impl Type { ... }` or `impl Trait for Type { ... }`
+    Impl,
+    /// `extern crate name;`
+    ExternCrate,
+    /// A generic type parameter: `T`
+    TypeParam,
+    /// A generic const parameter: `const N: usize`
+    ConstParam,
+    /// A generic lifetime parameter: `'a`
+    LifetimeParam,
+    /// A local variable, parameter or pattern binding
+    Local,
+}
+
+impl DefKind {
+    /// The namespaces a definition of this kind is named in
+    ///
+    /// Structs without fields and variants without named fields are values
+    /// as well as types; see [`crate::Resolutions`].
+    pub fn namespace(self) -> Option<Namespace> {
+        match self {
+            DefKind::Module
+            | DefKind::Struct
+            | DefKind::Enum
+            | DefKind::Variant
+            | DefKind::Trait
+            | DefKind::TypeAlias
+            | DefKind::Effect
+            | DefKind::AssocType
+            | DefKind::ExternCrate
+            | DefKind::TypeParam => Some(Namespace::Type),
+            DefKind::EffectOperation
+            | DefKind::Function
+            | DefKind::ExternFunction
+            | DefKind::AssocFn
+            | DefKind::AssocConst
+            | DefKind::Const
+            | DefKind::Static
+            | DefKind::ConstParam
+            | DefKind::Local => Some(Namespace::Value),
+            DefKind::Impl | DefKind::LifetimeParam => None,
+        }
+    }
+
+    /// How diagnostics describe a definition of this kind
+    pub fn descr(self) -> &'static str {
+        match self {
+            DefKind::Module => "module",
+            DefKind::Struct => "struct",
+            DefKind::Enum => "enum",
+            DefKind::Variant => "variant",
+            DefKind::Trait => "trait",
+            DefKind::TypeAlias => "type alias",
+            DefKind::Effect => "effect",
+            DefKind::EffectOperation => "effect operation",
+            DefKind::Function | DefKind::ExternFunction => "function",
+            DefKind::AssocFn => "associated function",
+            DefKind::AssocType => "associated type",
+            DefKind::AssocConst => "associated constant",
+            DefKind::Const => "constant",
+            DefKind::Static => "static",
+            DefKind::Impl => "impl",
+            DefKind::ExternCrate => "extern crate",
+            DefKind::TypeParam => "type parameter",
+            DefKind::ConstParam => "const parameter",
+            DefKind::LifetimeParam => "lifetime parameter",
+            DefKind::Local => "local variable",
+        }
+    }
+
+    /// Whether paths can continue after a name of this kind, as in `m::f` or `Enum::Variant`
+    pub(crate) fn has_children(self) -> bool {
+        matches!(self, DefKind::Module | DefKind::Enum | DefKind::Trait | DefKind::Effect)
+    }
+
+    /// Definitions that belong to one function body and are not visible in
+    /// items nested inside it
+    pub(crate) fn is_local(self) -> bool {
+        matches!(
+            self,
+            DefKind::Local | DefKind::TypeParam | DefKind::ConstParam | DefKind::LifetimeParam
+        )
+    }
+}
+
+/// One definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    /// The defined name; `impl` for impls
+    pub name: String,
+    /// What is defined
+    pub kind: DefKind,
+    /// Span of the defining identifier, or of the `impl` keyword for impls
+    pub span: Span,
+    /// The module, item or function the definition is in; `None` for the root
+    pub parent: Option<DefId>,
+}
+
+/// A primitive type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimTy {
+    /// `bool`
+    Bool,
+    /// `char`
+    Char,
+    /// `str`
+    Str,
+    /// `string`, an owned string
+    String,
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `i128`
+    I128,
+    /// `isize`
+    ISize,
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `u128`
+    U128,
+    /// `usize`
+    USize,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+}
+
+impl PrimTy {
+    /// Every primitive type
+    pub const ALL: [PrimTy; 18] = [
+        PrimTy::Bool,
+        PrimTy::Char,
+        PrimTy::Str,
+        PrimTy::String,
+        PrimTy::I8,
+        PrimTy::I16,
+        PrimTy::I32,
+        PrimTy::I64,
+        PrimTy::I128,
+        PrimTy::ISize,
+        PrimTy::U8,
+        PrimTy::U16,
+        PrimTy::U32,
+        PrimTy::U64,
+        PrimTy::U128,
+        PrimTy::USize,
+        PrimTy::F32,
+        PrimTy::F64,
+    ];
+
+    /// The name the type is written with
+    pub fn name(self) -> &'static str {
+        match self {
+            PrimTy::Bool => "bool",
+            PrimTy::Char => "char",
+            PrimTy::Str => "str",
+            PrimTy::String => "string",
+            PrimTy::I8 => "i8",
+            PrimTy::I16 => "i16",
+            PrimTy::I32 => "i32",
+            PrimTy::I64 => "i64",
+            PrimTy::I128 => "i128",
+            PrimTy::ISize => "isize",
+            PrimTy::U8 => "u8",
+            PrimTy::U16 => "u16",
+            PrimTy::U32 => "u32",
+            PrimTy::U64 => "u64",
+            PrimTy::U128 => "u128",
+            PrimTy::USize => "usize",
+            PrimTy::F32 => "f32",
+            PrimTy::F64 => "f64",
+        }
+    }
+
+    /// The primitive type called `name`
+    pub fn from_name(name: &str) -> Option<PrimTy> {
+        PrimTy::ALL.into_iter().find(|prim| prim.name() == name)
+    }
+}
+
+impl fmt::Display for PrimTy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a name resolved to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Res {
+    /// A definition in the crate
+    Def(DefId),
+    /// A primitive type such as `i32`
+    Prim(PrimTy),
+    /// `Self` in the impl or trait with this id
+    SelfTy(DefId),
+    /// Something the compiler provides: a global path like
+    /// `::__zulon_builtin_panic` from the expansion of `panic!`, a
+    /// builtin effect such as `IO`, or an extern the compiler declares
+    Intrinsic(String),
+    /// The name could not be resolved; an error was reported
+    Err,
+}
+
+impl Res {
+    /// The definition, if the name resolved to one
+    pub fn def_id(&self) -> Option<DefId> {
+        match self {
+            Res::Def(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// The resolution of a path, some of whose trailing segments may need types
+/// to resolve
+///
+/// `T::default` with `T` a type parameter resolves `T` and leaves one
+/// segment for type checking, which knows `T`'s bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathResolution {
+    /// What the resolved segments name
+    pub res: Res,
+    /// How many segments at the end of the path are not resolved yet
+    pub unresolved_segments: usize,
+}
+
+impl PathResolution {
+    pub(crate) fn full(res: Res) -> Self {
+        PathResolution {
+            res,
+            unresolved_segments: 0,
+        }
+    }
+
+    /// The resolution, if the whole path was resolved
+    pub fn full_res(&self) -> Option<&Res> {
+        (self.unresolved_segments == 0).then_some(&self.res)
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Integration with zulon-diagnostic
+
+use crate::error::Similar;
+use crate::imports::with_article;
+use crate::{ResolveError, Warning};
+use std::path::PathBuf;
+use zulon_diagnostic::{Diagnostic, Loc, Span, Suggestion};
+use zulon_parser::Span as ParserSpan;
+
+impl ResolveError {
+    /// Convert to a Diagnostic
+    pub fn to_diagnostic(&self, source_code: &str) -> Diagnostic {
+        let span = parser_span_to_diagnostic_span(&self.span(), source_code);
+        let diagnostic = Diagnostic::error().message(self.to_string()).span(span.clone());
+
+        let diagnostic = match self {
+            ResolveError::NotFound { context, scope, .. } => {
+                let label = if scope == "this scope" {
+                    "not found in this scope".to_string()
+                } else {
+                    format!("not found in {}", scope)
+                };
+                diagnostic.code(context.not_found_code()).label(span, label)
+            }
+
+            ResolveError::WrongKind {
+                name, context, found, ..
+            } => diagnostic
+                .code(context.wrong_kind_code())
+                .label(span, format!("not a {}", context.expected()))
+                .note(format!("`{}` is {}", name, with_article(found))),
+
+            ResolveError::FailedToResolve { message, .. } => diagnostic.code("E0433").label(span, message),
+
+            ResolveError::NoAssociatedItem { owner, .. } => diagnostic
+                .code("E0599")
+                .label(span, format!("not found in {}", owner)),
+
+            ResolveError::UnresolvedImport { message, .. } => diagnostic.code("E0432").label(span, message),
+
+            ResolveError::Duplicate { name, previous, .. } => {
+                let previous = parser_span_to_diagnostic_span(previous, source_code);
+                diagnostic
+                    .code("E0428")
+                    .label(previous, format!("previous definition of `{}` here", name))
+                    .label(span, format!("`{}` redefined here", name))
+                    .note(format!("`{}` must be defined only once in its namespace", name))
+            }
+
+            ResolveError::DuplicateBinding { in_params, .. } => {
+                let (code, label) = if *in_params {
+                    ("E0415", "used as parameter more than once")
+                } else {
+                    ("E0416", "used in a pattern more than once")
+                };
+                diagnostic.code(code).label(span, label)
+            }
+
+            ResolveError::NotBoundInAllPatterns { name, .. } => diagnostic
+                .code("E0408")
+                .label(span, format!("variable `{}` is not bound in every alternative", name)),
+
+            ResolveError::CapturedLocal { .. } => diagnostic
+                .code("E0434")
+                .label(span, "local variable of an enclosing function")
+                .note("use a closure instead of a nested `fn` to capture variables"),
+
+            ResolveError::OuterGeneric { descr, .. } => diagnostic
+                .code("E0401")
+                .label(span, format!("use of {} from outer item", descr))
+                .note("nested items are independent of the item they are declared in"),
+
+            ResolveError::Private {
+                name, descr, defined, ..
+            } => {
+                let defined = parser_span_to_diagnostic_span(defined, source_code);
+                diagnostic
+                    .code("E0603")
+                    .label(span, format!("private {}", descr))
+                    .label(defined, format!("the {} `{}` is defined here", descr, name))
+                    .note(format!("mark the {} `pub` to use it outside its module", descr))
+            }
+
+            ResolveError::BadVisibility { .. } => diagnostic
+                .code("E0742")
+                .label(span, "not an ancestor of this item's module"),
+        };
+
+        let diagnostic = diagnostic.build();
+        match self.similar() {
+            Some(similar) => with_similar(diagnostic, similar, &self.span(), source_code),
+            None => diagnostic,
+        }
+    }
+}
+
+impl Warning {
+    /// Convert to a warning Diagnostic
+    pub fn to_diagnostic(&self, source_code: &str) -> Diagnostic {
+        let span = parser_span_to_diagnostic_span(&self.span(), source_code);
+        let diagnostic = Diagnostic::warning().message(self.to_string()).span(span.clone());
+        let diagnostic = match self {
+            Warning::UnusedVariable { name, .. } => diagnostic
+                .label(span.clone(), "never used")
+                .suggestion(Suggestion::new(
+                    "if this is intentional, prefix it with an underscore",
+                    span,
+                    format!("_{}", name),
+                )),
+            Warning::UnusedImport { .. } => diagnostic.label(span, "never used"),
+            Warning::UnusedFunction { .. } => diagnostic.label(span, "never called"),
+            Warning::Unreachable { what, cause, .. } => {
+                let cause = parser_span_to_diagnostic_span(cause, source_code);
+                diagnostic
+                    .label(span, format!("unreachable {}", what))
+                    .label(cause, "any code following this is unreachable")
+            }
+        };
+        diagnostic
+            .note(format!("`#[warn({})]` on by default", self.lint().name()))
+            .build()
+    }
+}
+
+/// Point at a similar name and suggest it in place of the one written
+fn with_similar(diagnostic: Diagnostic, similar: &Similar, span: &ParserSpan, source_code: &str) -> Diagnostic {
+    let span = parser_span_to_diagnostic_span(span, source_code);
+    let mut diagnostic = diagnostic.with_suggestion(Suggestion::new(
+        format!("{} with a similar name exists: `{}`", with_article(similar.descr), similar.name),
+        span,
+        similar.name.clone(),
+    ));
+    if let Some(similar_span) = &similar.span {
+        let similar_span = parser_span_to_diagnostic_span(similar_span, source_code);
+        diagnostic = diagnostic.with_label(similar_span, format!("similarly named {} `{}` defined here", similar.descr, similar.name));
+    }
+    diagnostic
+}
+
+/// Convert Parser Span to Diagnostic Span
+fn parser_span_to_diagnostic_span(span: &ParserSpan, source_code: &str) -> Span {
+    let file = Some(PathBuf::from("input.zl"));
+
+    let start_offset = byte_offset(source_code, span.start.line, span.start.column);
+    let end_offset = byte_offset(source_code, span.end.line, span.end.column);
+
+    let lo = Loc::new(file.clone(), span.start.line, span.start.column, start_offset);
+    let hi = Loc::new(file, span.end.line, span.end.column, end_offset);
+
+    Span { lo, hi }
+}
+
+/// Byte offset of a 1-indexed line and column, clamped to the source
+fn byte_offset(source_code: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = source_code
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(source_code.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zulon_parser::Position;
+
+    fn span(line: usize, start: usize, end: usize) -> ParserSpan {
+        ParserSpan::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    #[test]
+    fn test_not_found_suggests_similar_name() {
+        let source = "fn main() {\n    let counter = 1;\n    countr\n}\n";
+        let error = ResolveError::NotFound {
+            name: "countr".to_string(),
+            context: crate::PathSource::Expr,
+            scope: "this scope".to_string(),
+            span: span(3, 5, 11),
+            similar: Some(Box::new(Similar {
+                name: "counter".to_string(),
+                descr: "local variable",
+                span: Some(span(2, 9, 16)),
+            })),
+        };
+
+        let diagnostic = error.to_diagnostic(source);
+        assert_eq!(diagnostic.message, "cannot find value `countr` in this scope");
+        assert_eq!(diagnostic.code.as_deref(), Some("E0425"));
+        assert_eq!(diagnostic.labels.len(), 2);
+        assert_eq!(diagnostic.suggestions[0].replacement, "counter");
+        assert_eq!(
+            diagnostic.suggestions[0].apply(source),
+            "fn main() {\n    let counter = 1;\n    counter\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_points_at_both_definitions() {
+        let source = "fn f() {}\nfn f() {}\n";
+        let error = ResolveError::Duplicate {
+            name: "f".to_string(),
+            span: span(2, 4, 5),
+            previous: span(1, 4, 5),
+        };
+
+        let diagnostic = error.to_diagnostic(source);
+        assert_eq!(diagnostic.code.as_deref(), Some("E0428"));
+        assert_eq!(diagnostic.labels[0].span.lo.offset, 3);
+        assert_eq!(diagnostic.labels[1].span.lo.offset, 13);
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Name resolution errors
+
+use thiserror::Error;
+use zulon_parser::Span;
+
+/// Where a path was written, which decides what it may resolve to and how
+/// errors describe it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSource {
+    /// A path expression: `x`, `Color::Red`
+    Expr,
+    /// The callee of a call: `f(x)`
+    Call,
+    /// A type: `Vec<T>`
+    Type,
+    /// An impl's trait, a bound or a supertrait
+    Trait,
+    /// An effect in a function's effect list or a handler
+    Effect,
+    /// The path of a struct literal or struct pattern: `Point { x, y }`
+    Struct,
+    /// The path of a tuple variant pattern: `Some(x)`
+    TupleVariant,
+    /// A path in a `use` item
+    Import,
+}
+
+impl PathSource {
+    /// How errors name what was expected
+    pub fn expected(self) -> &'static str {
+        match self {
+            PathSource::Expr => "value",
+            PathSource::Call => "function",
+            PathSource::Type => "type",
+            PathSource::Trait => "trait",
+            PathSource::Effect => "effect",
+            PathSource::Struct => "struct or variant",
+            PathSource::TupleVariant => "tuple variant",
+            PathSource::Import => "item",
+        }
+    }
+
+    /// The error code for a name that is not found
+    pub(crate) fn not_found_code(self) -> &'static str {
+        match self {
+            PathSource::Expr | PathSource::Call => "E0425",
+            PathSource::Type | PathSource::Effect => "E0412",
+            PathSource::Trait => "E0405",
+            PathSource::Struct => "E0422",
+            PathSource::TupleVariant => "E0531",
+            PathSource::Import => "E0432",
+        }
+    }
+
+    /// The error code for a name that resolves to the wrong kind of thing
+    pub(crate) fn wrong_kind_code(self) -> &'static str {
+        match self {
+            PathSource::Expr | PathSource::Call => "E0423",
+            PathSource::Type | PathSource::Effect | PathSource::Import => "E0573",
+            PathSource::Trait => "E0404",
+            PathSource::Struct => "E0574",
+            PathSource::TupleVariant => "E0532",
+        }
+    }
+}
+
+/// A name that is similar to one that could not be found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Similar {
+    /// The similar name
+    pub name: String,
+    /// What the similar name is, like "function" or "local variable"
+    pub descr: &'static str,
+    /// Where the similar name is defined, if in source
+    pub span: Option<Span>,
+}
+
+/// Name resolution error
+///
+/// `span` is always the name the error is about.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[allow(missing_docs)]
+pub enum ResolveError {
+    /// A name that is not in scope, or not in the module a path names
+    #[error("cannot find {} `{name}` in {scope}", context.expected())]
+    NotFound {
+        name: String,
+        context: PathSource,
+        /// "this scope", or the module searched, like "module `geometry`"
+        scope: String,
+        span: Span,
+        similar: Option<Box<Similar>>,
+    },
+
+    /// A name that resolves to something that cannot be used where it is,
+    /// like a struct with fields used as a value
+    #[error("expected {}, found {found} `{name}`", context.expected())]
+    WrongKind {
+        name: String,
+        context: PathSource,
+        /// What the name is, like "module"
+        found: &'static str,
+        span: Span,
+    },
+
+    /// A path whose leading segments cannot be resolved
+    #[error("failed to resolve: {message}")]
+    FailedToResolve {
+        message: String,
+        span: Span,
+        similar: Option<Box<Similar>>,
+    },
+
+    /// A missing associated function, variant, trait item or effect operation
+    #[error("no function or associated item named `{name}` found for {owner}")]
+    NoAssociatedItem {
+        name: String,
+        /// The type, trait or effect searched, like "struct `Point`"
+        owner: String,
+        span: Span,
+        similar: Option<Box<Similar>>,
+    },
+
+    /// A `use` item that names nothing
+    #[error("unresolved import `{path}`")]
+    UnresolvedImport {
+        path: String,
+        /// What is missing, like "no `sqrt` in `math`"
+        message: String,
+        span: Span,
+        similar: Option<Box<Similar>>,
+    },
+
+    /// Two items with the same name in the same namespace of a scope
+    #[error("the name `{name}` is defined multiple times")]
+    Duplicate {
+        name: String,
+        span: Span,
+        /// The first definition
+        previous: Span,
+    },
+
+    /// A name bound twice by one pattern or parameter list
+    #[error("identifier `{name}` is bound more than once in {}", if *in_params { "this parameter list" } else { "the same pattern" })]
+    DuplicateBinding {
+        name: String,
+        in_params: bool,
+        span: Span,
+    },
+
+    /// A name bound by some alternatives of an or-pattern but not others
+    #[error("variable `{name}` is not bound in all patterns")]
+    NotBoundInAllPatterns { name: String, span: Span },
+
+    /// A local of an enclosing function used in a nested function
+    #[error("can't capture dynamic environment in a fn item")]
+    CapturedLocal { name: String, span: Span },
+
+    /// A generic parameter or `Self` of an enclosing item used in a nested item
+    #[error("can't use {descr} `{name}` from outer item")]
+    OuterGeneric {
+        name: String,
+        /// What the name is, like "type parameter"
+        descr: &'static str,
+        span: Span,
+    },
+
+    /// An item used through a path from outside where its visibility allows
+    #[error("{descr} `{name}` is private")]
+    Private {
+        name: String,
+        /// What the item is, like "function"
+        descr: &'static str,
+        span: Span,
+        /// Where the item is defined
+        defined: Span,
+    },
+
+    /// A `pub(in path)` whose path is not a module containing the item
+    #[error("visibilities can only be restricted to ancestor modules")]
+    BadVisibility { span: Span },
+}
+
+impl ResolveError {
+    /// The name the error is about
+    pub fn span(&self) -> Span {
+        match self {
+            ResolveError::NotFound { span, .. }
+            | ResolveError::WrongKind { span, .. }
+            | ResolveError::FailedToResolve { span, .. }
+            | ResolveError::NoAssociatedItem { span, .. }
+            | ResolveError::UnresolvedImport { span, .. }
+            | ResolveError::Duplicate { span, .. }
+            | ResolveError::DuplicateBinding { span, .. }
+            | ResolveError::NotBoundInAllPatterns { span, .. }
+            | ResolveError::CapturedLocal { span, .. }
+            | ResolveError::OuterGeneric { span, .. }
+            | ResolveError::Private { span, .. }
+            | ResolveError::BadVisibility { span } => *span,
+        }
+    }
+
+    /// A similar name that was probably meant
+    pub fn similar(&self) -> Option<&Similar> {
+        match self {
+            ResolveError::NotFound { similar, .. }
+            | ResolveError::FailedToResolve { similar, .. }
+            | ResolveError::NoAssociatedItem { similar, .. }
+            | ResolveError::UnresolvedImport { similar, .. } => similar.as_deref(),
+            _ => None,
+        }
+    }
+}
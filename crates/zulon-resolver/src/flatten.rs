@@ -0,0 +1,139 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Moving the items of modules to the crate root
+//!
+//! Type checking and lowering see one flat list of items. Once names are
+//! resolved, [`flatten_modules`] renames the items of nested modules after
+//! their path, so `geometry::area` becomes `geometry__area`, rewrites every
+//! path that names a module-level item to the item's new name, and moves
+//! the items out of their modules. `use` items have done their work by
+//! then and are dropped.
+//!
+//! The items of a prelude keep their names, unless the crate root has an
+//! item of the same name, in which case `Option` becomes `prelude__Option`.
+
+use crate::{DefId, DefKind, Namespace, Res, Resolutions};
+use zulon_parser::ast::visit_mut::{self, MutVisitor};
+use zulon_parser::ast::{Ast, Attribute, Identifier, Item, ItemKind, Type, Use};
+
+/// Move every item of an inline or loaded module to the crate root
+///
+/// `resolutions` must come from resolving `ast` without errors. To flatten
+/// a prelude resolved along with the crate, append its items to `ast`
+/// first. Extern functions keep their names, since they name symbols of
+/// other objects. Modules declared with `mod name;` whose file was never
+/// loaded are left in place.
+pub fn flatten_modules(ast: &mut Ast, resolutions: &Resolutions) {
+    Renamer { resolutions }.visit_ast(ast);
+    let mut items = Vec::with_capacity(ast.items.len());
+    hoist(std::mem::take(&mut ast.items), &mut items);
+    ast.items = items;
+}
+
+/// Add `items` to `flat`, replacing each module with its items
+fn hoist(items: Vec<Item>, flat: &mut Vec<Item>) {
+    for item in items {
+        match item.kind {
+            ItemKind::Module(module) => match module.items {
+                Some(items) => hoist(items.into_iter().map(|item| *item).collect(), flat),
+                None => flat.push(Item {
+                    kind: ItemKind::Module(module),
+                    ..item
+                }),
+            },
+            ItemKind::Use(_) => {}
+            _ => flat.push(item),
+        }
+    }
+}
+
+/// Renames module-level items and the paths to them
+struct Renamer<'a> {
+    resolutions: &'a Resolutions,
+}
+
+impl Renamer<'_> {
+    /// The name a module-level item has at the crate root
+    ///
+    /// `None` for modules themselves and for everything not directly in a
+    /// module, like variants, locals and items in blocks.
+    fn flat_name(&self, id: DefId) -> Option<String> {
+        let definition = self.resolutions.definition(id);
+        let parent = definition.parent?;
+        if definition.kind == DefKind::Module || self.resolutions.definition(parent).kind != DefKind::Module {
+            return None;
+        }
+        if parent == DefId::ROOT
+            || definition.kind == DefKind::ExternFunction
+            || (Some(parent) == self.resolutions.prelude() && !self.shadowed_at_root(id))
+        {
+            return Some(definition.name.clone());
+        }
+        Some(self.resolutions.path_str(id).replace("::", "__"))
+    }
+
+    /// Whether the crate root has another item with the same name as `id`
+    fn shadowed_at_root(&self, id: DefId) -> bool {
+        let name = &self.resolutions.definition(id).name;
+        [Namespace::Type, Namespace::Value].into_iter().any(|ns| {
+            self.resolutions
+                .child(DefId::ROOT, ns, name)
+                .is_some_and(|root_item| root_item != id)
+        })
+    }
+}
+
+impl MutVisitor for Renamer<'_> {
+    fn visit_attribute(&mut self, _attribute: &mut Attribute) {}
+
+    fn visit_use(&mut self, _use_item: &mut Use) {}
+
+    fn visit_identifier(&mut self, ident: &mut Identifier) {
+        // Defining identifiers, and single-name types like `Point`
+        let id = self
+            .resolutions
+            .def_of(ident)
+            .or_else(|| self.resolutions.res(ident).and_then(Res::def_id));
+        if let Some(name) = id.and_then(|id| self.flat_name(id)) {
+            ident.name = name;
+        }
+    }
+
+    fn visit_type(&mut self, ty: &mut Type) {
+        visit_mut::walk_type(self, ty);
+        // `geometry::Point` renamed to `geometry__Point` is now a simple type
+        if let Type::Path(path) | Type::PathGeneric(path, None) = ty {
+            if let [segment] = path.as_slice() {
+                *ty = Type::Simple(segment.clone());
+            }
+        }
+    }
+
+    fn visit_path(&mut self, path: &mut Vec<Identifier>) {
+        // A variant named on its own, as imports allow, gets its enum:
+        // `None` becomes `Option::None`
+        if let [segment] = path.as_slice() {
+            let variant = self.resolutions.res(segment).and_then(Res::def_id);
+            let definition = variant.map(|id| self.resolutions.definition(id));
+            if let Some(enum_id) = definition.filter(|d| d.kind == DefKind::Variant).and_then(|d| d.parent) {
+                let name = self
+                    .flat_name(enum_id)
+                    .unwrap_or_else(|| self.resolutions.definition(enum_id).name.clone());
+                path.insert(0, Identifier::new(segment.span, name));
+                return;
+            }
+        }
+
+        // The last segment naming a module-level item replaces the segments
+        // up to it: `crate::shapes::Shape::Circle` becomes `shapes__Shape::Circle`
+        let renamed = path.iter().enumerate().rev().find_map(|(index, segment)| {
+            let id = self.resolutions.res(segment)?.def_id()?;
+            Some((index, self.flat_name(id)?))
+        });
+        if let Some((index, name)) = renamed {
+            let segment = Identifier::new(path[index].span, name);
+            path.splice(..=index, [segment]);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `use` items
+//!
+//! An import can name something another import brings in, like an item of
+//! a glob-imported module, so the imports of all modules are resolved
+//! together, in rounds, until a round makes no progress. Whatever is left
+//! then is an error.
+
+use crate::error::Similar;
+use crate::{Binding, DefId, DefKind, Namespace, PathResolution, Res, ResolveError, Resolver, Vis};
+use zulon_parser::ast::{Identifier, Use, UsePath};
+use zulon_parser::Span;
+
+/// One name or glob of a `use` item
+pub(crate) struct Import<'ast> {
+    /// The module the `use` item is in
+    pub module: DefId,
+    /// The path to the module the import is from
+    pub prefix: &'ast [Identifier],
+    pub kind: ImportKind<'ast>,
+    /// Where the names it brings in can be used from, through paths
+    pub vis: Vis,
+}
+
+pub(crate) enum ImportKind<'ast> {
+    /// `use prefix::name;`, `use prefix::name as alias;` or one name of `use prefix::{...};`
+    Single {
+        name: &'ast Identifier,
+        alias: Option<&'ast Identifier>,
+    },
+    /// `use prefix::*;`
+    Glob,
+}
+
+/// Why an import could not be resolved
+pub(crate) struct ImportFailure {
+    span: Span,
+    message: String,
+    similar: Option<Box<Similar>>,
+    /// The error to report instead, for a name that exists but is private
+    private: Option<Box<ResolveError>>,
+}
+
+impl ImportFailure {
+    fn new(span: Span, message: String, similar: Option<Box<Similar>>) -> Self {
+        ImportFailure {
+            span,
+            message,
+            similar,
+            private: None,
+        }
+    }
+
+    fn private(error: ResolveError) -> Self {
+        ImportFailure {
+            span: error.span(),
+            message: String::new(),
+            similar: None,
+            private: Some(Box::new(error)),
+        }
+    }
+}
+
+impl<'ast> Import<'ast> {
+    /// The imports of a `use` item
+    pub(crate) fn from_use(use_item: &'ast Use, module: DefId, vis: Vis) -> Vec<Self> {
+        let single = |prefix, name, alias| Import {
+            module,
+            prefix,
+            kind: ImportKind::Single { name, alias },
+            vis,
+        };
+        match &use_item.path {
+            UsePath::Simple(path) => match path.split_last() {
+                Some((name, prefix)) => vec![single(prefix, name, use_item.alias.as_ref())],
+                None => Vec::new(),
+            },
+            UsePath::Glob(prefix) => vec![Import {
+                module,
+                prefix,
+                kind: ImportKind::Glob,
+                vis,
+            }],
+            UsePath::List(prefix, names) => names.iter().map(|name| single(prefix, name, None)).collect(),
+        }
+    }
+
+    fn path_str(&self) -> String {
+        let last = match &self.kind {
+            ImportKind::Single { name, .. } => name.name.as_str(),
+            ImportKind::Glob => "*",
+        };
+        let segments: Vec<&str> = self
+            .prefix
+            .iter()
+            .map(|segment| segment.name.as_str())
+            .chain(std::iter::once(last))
+            .collect();
+        segments.join("::")
+    }
+}
+
+impl<'ast> Resolver<'ast> {
+    /// Resolve the queued module-level imports
+    pub(crate) fn resolve_imports(&mut self) {
+        let mut pending = std::mem::take(&mut self.imports);
+        loop {
+            let count = pending.len();
+            pending.retain(|import| match self.import_bindings(import) {
+                Ok(bindings) => {
+                    for (ns, name, id) in bindings {
+                        let binding = Binding {
+                            id,
+                            span: name.span,
+                            vis: import.vis,
+                            import: Some(name.span),
+                        };
+                        self.declare_binding(import.module, ns, &name.name, binding);
+                    }
+                    false
+                }
+                Err(_) => true,
+            });
+            if pending.len() == count {
+                break;
+            }
+        }
+
+        for import in &pending {
+            if let Err(failure) = self.import_bindings(import) {
+                let error = self.import_error(import, failure);
+                self.errors.push(error);
+            }
+        }
+    }
+
+    pub(crate) fn import_error(&self, import: &Import<'ast>, failure: ImportFailure) -> ResolveError {
+        if let Some(error) = failure.private {
+            return *error;
+        }
+        ResolveError::UnresolvedImport {
+            path: import.path_str(),
+            message: failure.message,
+            span: failure.span,
+            similar: failure.similar,
+        }
+    }
+
+    /// The names an import brings into scope, with the namespace each is in
+    /// and what it refers to
+    pub(crate) fn import_bindings(
+        &mut self,
+        import: &Import<'ast>,
+    ) -> Result<Vec<(Namespace, &'ast Identifier, DefId)>, ImportFailure> {
+        let target = self.resolve_import_prefix(import)?;
+        if let Some(target) = target {
+            let kind = self.kind(target);
+            if !kind.has_children() {
+                let last = import.prefix.last().expect("a target comes from a non-empty prefix");
+                return Err(ImportFailure::new(
+                    last.span,
+                    format!("`{}` is {}, not a module", last.name, with_article(kind.descr())),
+                    None,
+                ));
+            }
+        }
+
+        match &import.kind {
+            ImportKind::Glob => {
+                let (Some(target), Some(last)) = (target, import.prefix.last()) else {
+                    return Ok(Vec::new());
+                };
+                let globs = self.resolutions.globs.entry(import.module).or_default();
+                if target != import.module && !globs.iter().any(|(glob, _, _)| *glob == target) {
+                    globs.push((target, import.vis, last.span));
+                }
+                Ok(Vec::new())
+            }
+            // `use prefix::{self}` imports the module itself
+            ImportKind::Single { name, alias } if name.name == "self" => {
+                let (Some(target), Some(last)) = (target, import.prefix.last()) else {
+                    return Err(ImportFailure::new(
+                        name.span,
+                        "`self` imports are only allowed within a { } list".to_string(),
+                        None,
+                    ));
+                };
+                self.record(name, PathResolution::full(Res::Def(target)));
+                Ok(vec![(Namespace::Type, alias.unwrap_or(last), target)])
+            }
+            ImportKind::Single { name, alias } => {
+                let owner = target.unwrap_or(import.module);
+                let bindings: Vec<_> = [Namespace::Type, Namespace::Value]
+                    .into_iter()
+                    .filter_map(|ns| {
+                        let id = self.resolutions.child(owner, ns, &name.name)?;
+                        Some((ns, alias.unwrap_or(name), id))
+                    })
+                    .collect();
+                let Some((_, _, id)) = bindings.first() else {
+                    let mut candidates = self.resolutions.child_names(owner, Namespace::Type);
+                    candidates.extend(self.resolutions.child_names(owner, Namespace::Value));
+                    return Err(ImportFailure::new(
+                        name.span,
+                        format!("no `{}` in {}", name.name, self.describe(owner)),
+                        self.similar_def(&name.name, candidates),
+                    ));
+                };
+                for (ns, _, _) in &bindings {
+                    self.note_use(owner, *ns, &name.name);
+                }
+                if self.kind(owner) == DefKind::Module {
+                    let (ns, _, _) = bindings[0];
+                    self.check_accessible(owner, ns, name, import.module)
+                        .map_err(ImportFailure::private)?;
+                }
+                self.record(name, PathResolution::full(Res::Def(*id)));
+                Ok(bindings)
+            }
+        }
+    }
+
+    /// The module an import's prefix names; `None` for an empty prefix
+    fn resolve_import_prefix(&mut self, import: &Import<'ast>) -> Result<Option<DefId>, ImportFailure> {
+        let prefix = import.prefix;
+        let Some(first) = prefix.first() else {
+            return Ok(None);
+        };
+        let (mut current, start) = match first.name.as_str() {
+            "" | "crate" => (DefId::ROOT, 1),
+            "self" => (import.module, 1),
+            "super" => {
+                let mut module = import.module;
+                let mut count = 0;
+                while prefix.get(count).is_some_and(|segment| segment.name == "super") {
+                    module = self.parent_module(module).ok_or_else(|| {
+                        ImportFailure::new(
+                            prefix[count].span,
+                            "there are too many leading `super` keywords".to_string(),
+                            None,
+                        )
+                    })?;
+                    count += 1;
+                }
+                (module, count)
+            }
+            _ => (import.module, 0),
+        };
+
+        for (index, segment) in prefix.iter().enumerate().skip(start) {
+            let kind = self.kind(current);
+            if !kind.has_children() {
+                let previous = &prefix[index - 1];
+                return Err(ImportFailure::new(
+                    previous.span,
+                    format!("`{}` is {}, not a module", previous.name, with_article(kind.descr())),
+                    None,
+                ));
+            }
+            // The first segment can also name an item of the prelude, as in
+            // `use Option::Some;`
+            let found = self.resolutions.child(current, Namespace::Type, &segment.name).or_else(|| {
+                (index == 0)
+                    .then(|| self.prelude_child(import.module, Namespace::Type, &segment.name))
+                    .flatten()
+            });
+            let Some(id) = found else {
+                let message = if index == 0 {
+                    format!("use of undeclared crate or module `{}`", segment.name)
+                } else {
+                    format!("could not find `{}` in {}", segment.name, self.describe(current))
+                };
+                let candidates = self.resolutions.child_names(current, Namespace::Type);
+                return Err(ImportFailure::new(
+                    segment.span,
+                    message,
+                    self.similar_def(&segment.name, candidates),
+                ));
+            };
+            if self.kind(current) == DefKind::Module {
+                self.check_accessible(current, Namespace::Type, segment, import.module)
+                    .map_err(ImportFailure::private)?;
+            }
+            self.note_use(current, Namespace::Type, &segment.name);
+            self.record(segment, PathResolution::full(Res::Def(id)));
+            current = id;
+        }
+        Ok(Some(current))
+    }
+}
+
+/// "a function", "an enum"
+pub(crate) fn with_article(descr: &str) -> String {
+    let article = if descr.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" };
+    format!("{} {}", article, descr)
+}
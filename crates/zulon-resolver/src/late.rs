@@ -0,0 +1,1220 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Resolving the names in item signatures and bodies
+//!
+//! Local scopes are a stack of ribs. Each rib holds the names one construct
+//! introduces: generic parameters, `Self`, function parameters, the items of
+//! a block, a `let` binding or the bindings of a match arm. A lookup walks
+//! the ribs from the innermost out, then tries the current module and the
+//! primitive types. An item rib marks where a nested item starts; locals
+//! and generic parameters beyond it belong to another function and cannot
+//! be used.
+
+use std::collections::HashMap;
+
+use crate::error::Similar;
+use crate::imports::{with_article, Import};
+use crate::{DefId, DefKind, Namespace, PathResolution, PathSource, PerNs, PrimTy, ResolveError, Res, Resolver, Vis};
+use zulon_parser::ast::{
+    BinaryOp, Block, EffectHandler, Expression, ExpressionKind, Function, GenericParam, Generics, Identifier, Item,
+    ItemKind, Local, Param, Pattern, Statement, StatementKind, StructPatternField, TemplateStringPart, TraitBound,
+    TraitItemKind, Type, VariantField,
+};
+use zulon_parser::format::{Argument, Count};
+use zulon_parser::Span;
+
+/// Effects the type checker provides without a declaration
+const BUILTIN_EFFECTS: [&str; 3] = ["IO", "Alloc", "Async"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RibKind {
+    /// A scope inside one function or item
+    Normal,
+    /// The start of an item: outer locals and generic parameters are not
+    /// visible past it
+    Item,
+}
+
+/// The names one scope introduces
+#[derive(Debug, Clone)]
+pub(crate) struct Rib {
+    kind: RibKind,
+    bindings: PerNs<HashMap<String, Res>>,
+}
+
+/// The names a pattern binds so far, with their definitions and spans
+type Bindings = HashMap<String, (DefId, Span)>;
+
+impl<'ast> Resolver<'ast> {
+    // ========================================================================
+    // Scopes
+    // ========================================================================
+
+    fn with_rib<T>(&mut self, kind: RibKind, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.ribs.push(Rib {
+            kind,
+            bindings: PerNs::default(),
+        });
+        let result = f(self);
+        self.ribs.pop();
+        result
+    }
+
+    /// Resolve an item's contents with `owner` as the owner of new locals
+    fn with_owner<T>(&mut self, owner: DefId, f: impl FnOnce(&mut Self) -> T) -> T {
+        let saved = std::mem::replace(&mut self.owner, owner);
+        let result = f(self);
+        self.owner = saved;
+        result
+    }
+
+    /// Add a name to the innermost rib
+    fn bind(&mut self, ns: Namespace, name: &str, res: Res) {
+        let rib = self.ribs.last_mut().expect("bindings are made inside a rib");
+        rib.bindings.get_mut(ns).insert(name.to_string(), res);
+    }
+
+    /// Look a single name up in the ribs, the current module, the prelude,
+    /// the primitive types and the compiler's externs
+    ///
+    /// Also returns whether the name was found beyond an item rib.
+    fn lookup(&self, name: &str, ns: Namespace) -> Option<(Res, bool)> {
+        let mut crossed_item = false;
+        for rib in self.ribs.iter().rev() {
+            if let Some(res) = rib.bindings.get(ns).get(name) {
+                return Some((res.clone(), crossed_item));
+            }
+            crossed_item |= rib.kind == RibKind::Item;
+        }
+        let module_item = self
+            .resolutions
+            .child(self.module, ns, name)
+            .or_else(|| self.prelude_child(self.module, ns, name));
+        if let Some(id) = module_item {
+            return Some((Res::Def(id), false));
+        }
+        match ns {
+            Namespace::Type => PrimTy::from_name(name).map(|prim| (Res::Prim(prim), false)),
+            Namespace::Value => self.externs.contains(name).then(|| (Res::Intrinsic(name.to_string()), false)),
+        }
+    }
+
+    /// Note a use of a name [`Self::lookup`] found, if it is a name of the
+    /// current module rather than a local
+    fn note_lookup(&mut self, name: &str, ns: Namespace) {
+        if !self.ribs.iter().any(|rib| rib.bindings.get(ns).contains_key(name)) {
+            self.note_use(self.module, ns, name);
+        }
+    }
+
+    /// The name in scope most similar to `name`
+    fn similar_in_scope(&self, name: &str, ns: Namespace) -> Option<Box<Similar>> {
+        let mut candidates: Vec<(&str, Res)> = Vec::new();
+        let mut crossed_item = false;
+        for rib in self.ribs.iter().rev() {
+            for (candidate, res) in rib.bindings.get(ns) {
+                if !(crossed_item && self.is_local_res(res)) {
+                    candidates.push((candidate, res.clone()));
+                }
+            }
+            crossed_item |= rib.kind == RibKind::Item;
+        }
+        for (candidate, id) in self.resolutions.child_names(self.module, ns) {
+            candidates.push((candidate, Res::Def(id)));
+        }
+        if let Some(prelude) = self.resolutions.prelude() {
+            for (candidate, id) in self.resolutions.child_names(prelude, ns) {
+                if self.prelude_child(self.module, ns, candidate) == Some(id) {
+                    candidates.push((candidate, Res::Def(id)));
+                }
+            }
+        }
+        if ns == Namespace::Type {
+            candidates.extend(PrimTy::ALL.iter().map(|prim| (prim.name(), Res::Prim(*prim))));
+        }
+
+        let (similar, res) = crate::suggest::best_match(name, candidates)?;
+        let (descr, span) = match res {
+            Res::Def(id) => {
+                let definition = self.resolutions.definition(id);
+                (definition.kind.descr(), Some(definition.span))
+            }
+            Res::Prim(_) => ("builtin type", None),
+            Res::SelfTy(_) => ("self type", None),
+            Res::Intrinsic(_) | Res::Err => ("builtin", None),
+        };
+        Some(Box::new(Similar {
+            name: similar.to_string(),
+            descr,
+            span,
+        }))
+    }
+
+    fn is_local_res(&self, res: &Res) -> bool {
+        match res {
+            Res::Def(id) => self.kind(*id).is_local(),
+            Res::SelfTy(_) => true,
+            _ => false,
+        }
+    }
+
+    // ========================================================================
+    // Paths
+    // ========================================================================
+
+    /// Resolve a path, reporting an error if it cannot be resolved
+    fn resolve_path(&mut self, path: &[Identifier], ns: Namespace, source: PathSource) -> PathResolution {
+        let resolution = match self.try_resolve_path(path, ns, source) {
+            Ok(resolution) => resolution,
+            Err(error) => {
+                self.errors.push(error);
+                PathResolution::full(Res::Err)
+            }
+        };
+        if let Some(last) = path.last() {
+            self.record(last, resolution.clone());
+        }
+        resolution
+    }
+
+
+    /// Resolve a path without reporting errors
+    pub(crate) fn try_resolve_path(
+        &mut self,
+        path: &[Identifier],
+        ns: Namespace,
+        source: PathSource,
+    ) -> Result<PathResolution, ResolveError> {
+        if path.is_empty() {
+            return Ok(PathResolution::full(Res::Err));
+        }
+        let (mut res, start) = self.resolve_first_segment(path, ns, source)?;
+        if start < path.len() {
+            self.record(&path[start - 1], PathResolution::full(res.clone()));
+        }
+
+        for index in start..path.len() {
+            let segment = &path[index];
+            let is_last = index + 1 == path.len();
+            let owner = match &res {
+                // `Self::new` in `impl Point` looks in `Point`
+                Res::SelfTy(id) => match self.resolutions.impl_self_type(*id) {
+                    Some(ty) => ty,
+                    None => return Ok(partial(res, path.len() - index)),
+                },
+                Res::Def(id) => *id,
+                // Associated items of primitive types need types to resolve
+                Res::Prim(_) => return Ok(partial(res, path.len() - index)),
+                Res::Intrinsic(_) | Res::Err => return Ok(PathResolution::full(Res::Err)),
+            };
+            let segment_ns = if is_last { ns } else { Namespace::Type };
+            match self.resolve_child(owner, segment, segment_ns, &path[index - 1], source, is_last)? {
+                Some(id) => res = Res::Def(id),
+                None => return Ok(partial(res, path.len() - index)),
+            }
+            if !is_last {
+                self.record(segment, PathResolution::full(res.clone()));
+            }
+        }
+
+        self.check_kind(&path[path.len() - 1], &res, source)?;
+        Ok(PathResolution::full(res))
+    }
+
+    /// Resolve the first segment, or the `crate`/`super` prefix, of a path
+    ///
+    /// Returns what it names and the index of the next segment.
+    fn resolve_first_segment(
+        &mut self,
+        path: &[Identifier],
+        ns: Namespace,
+        source: PathSource,
+    ) -> Result<(Res, usize), ResolveError> {
+        let first = &path[0];
+        let single = path.len() == 1;
+        match first.name.as_str() {
+            "" if !single => {
+                // `::__zulon_builtin_panic(...)` from the expansion of `panic!`
+                if path.len() == 2 && path[1].name.starts_with("__zulon_builtin_") {
+                    return Ok((Res::Intrinsic(path[1].name.clone()), 2));
+                }
+                Ok((Res::Def(DefId::ROOT), 1))
+            }
+            "crate" if !single => Ok((Res::Def(DefId::ROOT), 1)),
+            "self" if !single => Ok((Res::Def(self.module), 1)),
+            "super" if !single => {
+                let mut module = self.module;
+                let mut count = 0;
+                while count + 1 < path.len() && path[count].name == "super" {
+                    module = self.parent_module(module).ok_or_else(|| ResolveError::FailedToResolve {
+                        message: "there are too many leading `super` keywords".to_string(),
+                        span: path[count].span,
+                        similar: None,
+                    })?;
+                    count += 1;
+                }
+                Ok((Res::Def(module), count))
+            }
+            name => {
+                let lookup_ns = if single { ns } else { Namespace::Type };
+                match self.lookup(name, lookup_ns) {
+                    Some((res, true)) if self.is_local_res(&res) => Err(self.outer_item_error(first, &res)),
+                    Some((res, _)) => {
+                        self.note_lookup(name, lookup_ns);
+                        Ok((res, 1))
+                    }
+                    None if single && source == PathSource::Effect && BUILTIN_EFFECTS.contains(&name) => {
+                        Ok((Res::Intrinsic(name.to_string()), 1))
+                    }
+                    None => Err(self.not_found_in_scope(first, lookup_ns, source, single)),
+                }
+            }
+        }
+    }
+
+    /// Resolve a segment after the first, naming an item of `owner`
+    ///
+    /// Returns `None` for an associated item that needs types to resolve.
+    fn resolve_child(
+        &mut self,
+        owner: DefId,
+        segment: &Identifier,
+        ns: Namespace,
+        previous: &Identifier,
+        source: PathSource,
+        is_last: bool,
+    ) -> Result<Option<DefId>, ResolveError> {
+        let name = segment.name.as_str();
+        let kind = self.kind(owner);
+        match kind {
+            DefKind::Module => {
+                if let Some(id) = self.resolutions.child(owner, ns, name) {
+                    self.check_accessible(owner, ns, segment, self.module)?;
+                    self.note_use(owner, ns, name);
+                    return Ok(Some(id));
+                }
+                let similar = self.similar_def(name, self.resolutions.child_names(owner, ns));
+                if is_last {
+                    if let Some(id) = self.resolutions.child(owner, ns.other(), name) {
+                        return Err(ResolveError::WrongKind {
+                            name: name.to_string(),
+                            context: source,
+                            found: self.kind(id).descr(),
+                            span: segment.span,
+                        });
+                    }
+                    return Err(ResolveError::NotFound {
+                        name: name.to_string(),
+                        context: source,
+                        scope: self.describe(owner),
+                        span: segment.span,
+                        similar,
+                    });
+                }
+                Err(ResolveError::FailedToResolve {
+                    message: format!("could not find `{}` in {}", name, self.describe(owner)),
+                    span: segment.span,
+                    similar,
+                })
+            }
+            DefKind::Struct | DefKind::Enum | DefKind::Trait | DefKind::Effect => {
+                if let Some(id) = self.associated_item(owner, ns, name) {
+                    return Ok(Some(id));
+                }
+                // Associated types can come from trait impls, which don't list them
+                if ns == Namespace::Type && matches!(kind, DefKind::Struct | DefKind::Enum) {
+                    return Ok(None);
+                }
+                let candidates = self.associated_names(owner, ns);
+                let candidates = candidates.iter().map(|(name, id)| (name.as_str(), *id)).collect();
+                Err(ResolveError::NoAssociatedItem {
+                    name: name.to_string(),
+                    owner: self.describe(owner),
+                    span: segment.span,
+                    similar: self.similar_def(name, candidates),
+                })
+            }
+            // These need types or another crate to resolve further
+            DefKind::TypeAlias | DefKind::TypeParam | DefKind::AssocType | DefKind::ExternCrate => Ok(None),
+            _ => Err(ResolveError::FailedToResolve {
+                message: format!("`{}` is {}, not a module or type", previous.name, with_article(kind.descr())),
+                span: previous.span,
+                similar: None,
+            }),
+        }
+    }
+
+    /// A variant, trait item or effect operation of `owner`, or a function
+    /// of one of its impls or of the traits those implement
+    fn associated_item(&self, owner: DefId, ns: Namespace, name: &str) -> Option<DefId> {
+        if let Some(id) = self.resolutions.child(owner, ns, name) {
+            return Some(id);
+        }
+        let impls = self.resolutions.impls_of(owner);
+        impls
+            .iter()
+            .find_map(|impl_id| self.resolutions.child(*impl_id, ns, name))
+            .or_else(|| {
+                impls
+                    .iter()
+                    .filter_map(|impl_id| self.resolutions.impl_trait(*impl_id))
+                    .find_map(|trait_id| self.resolutions.child(trait_id, ns, name))
+            })
+    }
+
+    fn associated_names(&self, owner: DefId, ns: Namespace) -> Vec<(String, DefId)> {
+        let impls = self.resolutions.impls_of(owner);
+        let traits = impls.iter().filter_map(|impl_id| self.resolutions.impl_trait(*impl_id));
+        std::iter::once(owner)
+            .chain(impls.iter().copied())
+            .chain(traits)
+            .flat_map(|scope| self.resolutions.child_names(scope, ns))
+            .map(|(name, id)| (name.to_string(), id))
+            .collect()
+    }
+
+    /// Check that a resolved path names the kind of thing its position needs
+    fn check_kind(&self, last: &Identifier, res: &Res, source: PathSource) -> Result<(), ResolveError> {
+        let Res::Def(id) = res else {
+            return Ok(());
+        };
+        let kind = self.kind(*id);
+        let fits = match source {
+            PathSource::Trait => kind == DefKind::Trait,
+            PathSource::Effect => kind == DefKind::Effect,
+            PathSource::Struct => matches!(
+                kind,
+                DefKind::Struct | DefKind::Variant | DefKind::TypeAlias | DefKind::AssocType | DefKind::TypeParam
+            ),
+            PathSource::TupleVariant => matches!(kind, DefKind::Variant | DefKind::Struct),
+            PathSource::Type => !matches!(kind, DefKind::Module | DefKind::ExternCrate),
+            PathSource::Expr | PathSource::Call | PathSource::Import => true,
+        };
+        if fits {
+            return Ok(());
+        }
+        Err(ResolveError::WrongKind {
+            name: last.name.clone(),
+            context: source,
+            found: kind.descr(),
+            span: last.span,
+        })
+    }
+
+    fn not_found_in_scope(&self, ident: &Identifier, ns: Namespace, source: PathSource, single: bool) -> ResolveError {
+        if !single {
+            return ResolveError::FailedToResolve {
+                message: format!("use of undeclared type or module `{}`", ident.name),
+                span: ident.span,
+                similar: self.similar_in_scope(&ident.name, ns),
+            };
+        }
+        // `let p = Point;` for a struct with fields
+        if let Some((Res::Def(id), _)) = self.lookup(&ident.name, ns.other()) {
+            return ResolveError::WrongKind {
+                name: ident.name.clone(),
+                context: source,
+                found: self.kind(id).descr(),
+                span: ident.span,
+            };
+        }
+        ResolveError::NotFound {
+            name: ident.name.clone(),
+            context: source,
+            scope: "this scope".to_string(),
+            span: ident.span,
+            similar: self.similar_in_scope(&ident.name, ns),
+        }
+    }
+
+    fn outer_item_error(&self, ident: &Identifier, res: &Res) -> ResolveError {
+        let descr = match res {
+            Res::Def(id) if self.kind(*id) == DefKind::Local => {
+                return ResolveError::CapturedLocal {
+                    name: ident.name.clone(),
+                    span: ident.span,
+                }
+            }
+            Res::Def(id) => self.kind(*id).descr(),
+            _ => "self type",
+        };
+        ResolveError::OuterGeneric {
+            name: ident.name.clone(),
+            descr,
+            span: ident.span,
+        }
+    }
+
+    /// Resolve the self types and traits of the impls collected so far,
+    /// so paths like `Point::new` can find their functions
+    pub(crate) fn resolve_impl_headers(&mut self) {
+        for (impl_id, impl_def, module) in std::mem::take(&mut self.pending_impls) {
+            let saved = std::mem::replace(&mut self.module, module);
+            let generics: Vec<&str> = impl_def
+                .generics
+                .iter()
+                .flat_map(|generics| &generics.params)
+                .filter_map(|param| match param {
+                    GenericParam::Type(ident) => Some(ident.name.as_str()),
+                    _ => None,
+                })
+                .collect();
+
+            if let Some(ty) = self.quiet_type_def(&impl_def.self_type, &generics) {
+                if matches!(self.kind(ty), DefKind::Struct | DefKind::Enum) {
+                    self.resolutions.impls.entry(ty).or_default().push(impl_id);
+                    self.resolutions.impl_self.insert(impl_id, ty);
+                }
+            }
+            if let Some(trait_ty) = &impl_def.trait_name {
+                if let Some(trait_id) = self.quiet_type_def(trait_ty, &generics) {
+                    if self.kind(trait_id) == DefKind::Trait {
+                        self.resolutions.impl_traits.insert(impl_id, trait_id);
+                    }
+                }
+            }
+            self.module = saved;
+        }
+    }
+
+    /// The definition a path type names, without reporting errors
+    fn quiet_type_def(&mut self, ty: &Type, generics: &[&str]) -> Option<DefId> {
+        let path = match ty {
+            Type::Simple(ident) => std::slice::from_ref(ident),
+            Type::Path(path) | Type::PathGeneric(path, _) => path.as_slice(),
+            _ => return None,
+        };
+        if generics.contains(&path.first()?.name.as_str()) {
+            return None;
+        }
+        let resolution = self.try_resolve_path(path, Namespace::Type, PathSource::Type).ok()?;
+        resolution.full_res()?.def_id()
+    }
+
+    // ========================================================================
+    // Items
+    // ========================================================================
+
+    /// Resolve the names in the signatures and bodies of items
+    pub(crate) fn resolve_items(&mut self, items: impl IntoIterator<Item = &'ast Item>) {
+        for item in items {
+            self.resolve_item(item);
+        }
+    }
+
+    fn item_def(&self, node: *const ()) -> DefId {
+        self.item_defs[&node]
+    }
+
+    fn name_def(&self, name: &Identifier) -> DefId {
+        self.item_def(name as *const Identifier as *const ())
+    }
+
+    fn resolve_item(&mut self, item: &'ast Item) {
+        match &item.kind {
+            ItemKind::Function(func) | ItemKind::ExternFunction(func) => {
+                let id = self.name_def(&func.name);
+                self.with_item_rib(id, |this| this.resolve_function(func));
+            }
+            ItemKind::Struct(struct_def) => {
+                let id = self.name_def(&struct_def.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_generics(struct_def.generics.as_ref());
+                    for field in &struct_def.fields {
+                        this.resolve_type(&field.type_annotation, PathSource::Type);
+                        if let Some(default_value) = &field.default_value {
+                            this.resolve_expr(default_value);
+                        }
+                    }
+                });
+            }
+            ItemKind::Enum(enum_def) => {
+                let id = self.name_def(&enum_def.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_generics(enum_def.generics.as_ref());
+                    for variant in &enum_def.variants {
+                        for field in &variant.fields {
+                            let (VariantField::Named(_, ty) | VariantField::Unnamed(ty)) = field;
+                            this.resolve_type(ty, PathSource::Type);
+                        }
+                    }
+                });
+            }
+            ItemKind::Trait(trait_def) => {
+                let id = self.name_def(&trait_def.name);
+                self.with_item_rib(id, |this| {
+                    this.bind(Namespace::Type, "Self", Res::SelfTy(id));
+                    this.resolve_generics(trait_def.generics.as_ref());
+                    for super_trait in &trait_def.super_traits {
+                        this.resolve_type(super_trait, PathSource::Trait);
+                    }
+                    for trait_item in &trait_def.items {
+                        match &trait_item.kind {
+                            TraitItemKind::Method(func) | TraitItemKind::RequiredMethod(func) => {
+                                let method_id = this.name_def(&func.name);
+                                this.with_rib(RibKind::Normal, |this| {
+                                    this.with_owner(method_id, |this| this.resolve_function(func))
+                                });
+                            }
+                            TraitItemKind::AssociatedType(_, bounds) => {
+                                for bound in bounds {
+                                    this.resolve_type(bound, PathSource::Trait);
+                                }
+                            }
+                            TraitItemKind::Const(const_def) => {
+                                this.resolve_type(&const_def.type_annotation, PathSource::Type);
+                                this.resolve_expr(&const_def.value);
+                            }
+                        }
+                    }
+                });
+            }
+            ItemKind::Impl(impl_def) => {
+                let id = self.item_def(impl_def as *const _ as *const ());
+                self.with_item_rib(id, |this| {
+                    this.resolve_generics(impl_def.generics.as_ref());
+                    if let Some(trait_name) = &impl_def.trait_name {
+                        this.resolve_type(trait_name, PathSource::Trait);
+                    }
+                    this.resolve_type(&impl_def.self_type, PathSource::Type);
+                    this.bind(Namespace::Type, "Self", Res::SelfTy(id));
+                    for (_, ty) in &impl_def.assoc_types {
+                        this.resolve_type(ty, PathSource::Type);
+                    }
+                    for method in &impl_def.items {
+                        let method_id = this.name_def(&method.name);
+                        this.with_rib(RibKind::Normal, |this| {
+                            this.with_owner(method_id, |this| this.resolve_function(method))
+                        });
+                    }
+                });
+            }
+            ItemKind::TypeAlias(alias) => {
+                let id = self.name_def(&alias.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_generics(alias.generics.as_ref());
+                    this.resolve_type(&alias.type_annotation, PathSource::Type);
+                });
+            }
+            ItemKind::Const(const_def) => {
+                let id = self.name_def(&const_def.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_type(&const_def.type_annotation, PathSource::Type);
+                    this.resolve_expr(&const_def.value);
+                });
+            }
+            ItemKind::Static(static_def) => {
+                let id = self.name_def(&static_def.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_type(&static_def.type_annotation, PathSource::Type);
+                    this.resolve_expr(&static_def.value);
+                });
+            }
+            ItemKind::Module(module) => {
+                let Some(items) = &module.items else {
+                    return;
+                };
+                // A module starts a fresh scope, even inside a function
+                let module_id = self.name_def(&module.name);
+                let saved_module = std::mem::replace(&mut self.module, module_id);
+                let saved_ribs = std::mem::take(&mut self.ribs);
+                self.resolve_items(items.iter().map(|item| &**item));
+                self.ribs = saved_ribs;
+                self.module = saved_module;
+            }
+            ItemKind::Effect(effect) => {
+                let id = self.name_def(&effect.name);
+                self.with_item_rib(id, |this| {
+                    this.resolve_generics(effect.generics.as_ref());
+                    for operation in &effect.operations {
+                        let operation_id = this.name_def(&operation.name);
+                        this.with_rib(RibKind::Normal, |this| {
+                            this.with_owner(operation_id, |this| this.resolve_params(&operation.params));
+                            if let Some(ty) = &operation.return_type {
+                                this.resolve_type(ty, PathSource::Type);
+                            }
+                        });
+                    }
+                });
+            }
+            ItemKind::Use(_) | ItemKind::ExternCrate(_) | ItemKind::MacroDef(_) | ItemKind::Error => {}
+        }
+    }
+
+    fn with_item_rib(&mut self, id: DefId, f: impl FnOnce(&mut Self)) {
+        self.with_rib(RibKind::Item, |this| this.with_owner(id, f));
+    }
+
+    /// Bind generic parameters in the innermost rib and resolve the where clause
+    fn resolve_generics(&mut self, generics: Option<&'ast Generics>) {
+        let Some(generics) = generics else {
+            return;
+        };
+        for param in &generics.params {
+            match param {
+                GenericParam::Type(ident) => {
+                    let id = self.define(ident, DefKind::TypeParam, self.owner);
+                    self.bind(Namespace::Type, &ident.name, Res::Def(id));
+                }
+                GenericParam::Const(ident, ty) => {
+                    self.resolve_type(ty, PathSource::Type);
+                    let id = self.define(ident, DefKind::ConstParam, self.owner);
+                    self.bind(Namespace::Value, &ident.name, Res::Def(id));
+                }
+                GenericParam::Lifetime(ident) => {
+                    self.define(ident, DefKind::LifetimeParam, self.owner);
+                }
+            }
+        }
+        for clause in &generics.where_clause {
+            self.resolve_path(std::slice::from_ref(&clause.type_param), Namespace::Type, PathSource::Type);
+            for bound in &clause.bounds {
+                if let TraitBound::Trait(ty) = bound {
+                    self.resolve_type(ty, PathSource::Trait);
+                }
+            }
+        }
+    }
+
+    fn resolve_function(&mut self, func: &'ast Function) {
+        self.resolve_generics(func.generics.as_ref());
+        self.with_rib(RibKind::Normal, |this| {
+            // The operations of the function's effects can be called directly
+            let mut operations = Vec::new();
+            for effect in &func.effects {
+                let resolution = this.resolve_type(effect, PathSource::Effect);
+                if let Some(Res::Def(id)) = resolution.as_ref().and_then(PathResolution::full_res) {
+                    let names = this.resolutions.child_names(*id, Namespace::Value);
+                    operations.extend(names.into_iter().map(|(name, id)| (name.to_string(), id)));
+                }
+            }
+            for (name, id) in operations {
+                this.bind(Namespace::Value, &name, Res::Def(id));
+            }
+
+            this.resolve_params(&func.params);
+            for ty in func.return_type.iter().chain(&func.error_type) {
+                this.resolve_type(ty, PathSource::Type);
+            }
+            this.resolve_block(&func.body);
+        });
+    }
+
+    /// Bind function parameters in the innermost rib
+    fn resolve_params(&mut self, params: &'ast [Param]) {
+        let mut bindings = Bindings::new();
+        for param in params {
+            if let Some(ty) = &param.type_annotation {
+                self.resolve_type(ty, PathSource::Type);
+            }
+            if let Some(default_value) = &param.default_value {
+                self.resolve_expr(default_value);
+            }
+            self.bind_pattern_name(&param.name, &mut bindings, None, true);
+        }
+        self.bind_all(bindings);
+    }
+
+    /// Resolve a type; returns the resolution of its path, if it is one
+    fn resolve_type(&mut self, ty: &'ast Type, source: PathSource) -> Option<PathResolution> {
+        match ty {
+            // `_` asks for inference
+            Type::Simple(ident) if ident.name == "_" => None,
+            Type::Simple(ident) => Some(self.resolve_path(std::slice::from_ref(ident), Namespace::Type, source)),
+            Type::Path(path) => Some(self.resolve_path(path, Namespace::Type, source)),
+            Type::PathGeneric(path, args) => {
+                let resolution = self.resolve_path(path, Namespace::Type, source);
+                for arg in args.iter().flatten() {
+                    self.resolve_type(arg, PathSource::Type);
+                }
+                Some(resolution)
+            }
+            Type::Tuple(types) => {
+                for ty in types {
+                    self.resolve_type(ty, PathSource::Type);
+                }
+                None
+            }
+            Type::Array(element, len) => {
+                self.resolve_type(element, PathSource::Type);
+                if let Some(len) = len {
+                    self.resolve_expr(len);
+                }
+                None
+            }
+            Type::Slice(inner) | Type::Ref(inner, _) | Type::Pointer(inner, _) | Type::Optional(inner) => {
+                self.resolve_type(inner, PathSource::Type);
+                None
+            }
+            Type::TraitObject(inner) | Type::ImplTrait(inner) => {
+                self.resolve_type(inner, PathSource::Trait);
+                None
+            }
+            Type::Function(params, ret) => {
+                for ty in params.iter().chain(std::iter::once(&**ret)) {
+                    self.resolve_type(ty, PathSource::Type);
+                }
+                None
+            }
+            Type::Pipe(ok, err) => {
+                self.resolve_type(ok, PathSource::Type);
+                self.resolve_type(err, PathSource::Type);
+                None
+            }
+            Type::Never | Type::Unit => None,
+        }
+    }
+
+    // ========================================================================
+    // Blocks and Statements
+    // ========================================================================
+
+    fn resolve_block(&mut self, block: &'ast Block) {
+        self.with_rib(RibKind::Normal, |this| {
+            this.collect_block_items(block);
+            for stmt in &block.statements {
+                this.resolve_statement(stmt);
+            }
+            if let Some(expr) = &block.trailing_expr {
+                this.resolve_expr(expr);
+            }
+        });
+    }
+
+    /// Define the items of a block, which are visible in all of it
+    fn collect_block_items(&mut self, block: &'ast Block) {
+        let mut imports = Vec::new();
+        for stmt in &block.statements {
+            let StatementKind::Item(item) = &stmt.kind else {
+                continue;
+            };
+            if let ItemKind::Use(use_item) = &item.kind {
+                imports.extend(Import::from_use(use_item, self.module, Vis::Restricted(self.module)));
+                continue;
+            }
+            for (ns, id) in self.collect_item(item, self.owner) {
+                let name = self.resolutions.definition(id).name.clone();
+                self.bind(ns, &name, Res::Def(id));
+            }
+        }
+
+        // Imports of modules declared in the block
+        self.resolve_imports();
+        for import in imports {
+            match self.import_bindings(&import) {
+                Ok(bindings) => {
+                    for (ns, name, id) in bindings {
+                        self.bind(ns, &name.name, Res::Def(id));
+                    }
+                }
+                Err(failure) => {
+                    let error = self.import_error(&import, failure);
+                    self.errors.push(error);
+                }
+            }
+        }
+        self.resolve_impl_headers();
+    }
+
+    fn resolve_statement(&mut self, stmt: &'ast Statement) {
+        match &stmt.kind {
+            StatementKind::Local(local) => self.resolve_local(local),
+            StatementKind::LetElse(let_else) => {
+                if let Some(ty) = &let_else.type_annotation {
+                    self.resolve_type(ty, PathSource::Type);
+                }
+                self.resolve_expr(&let_else.init);
+                // The else block cannot see the bindings
+                self.resolve_block(&let_else.else_block);
+                self.resolve_pattern_top(std::slice::from_ref(&let_else.pattern));
+            }
+            StatementKind::Item(item) => self.resolve_item(item),
+            StatementKind::Expr(expr) => self.resolve_expr(expr),
+            StatementKind::Defer(stmt) => self.with_rib(RibKind::Normal, |this| this.resolve_statement(stmt)),
+            StatementKind::Empty | StatementKind::Error => {}
+        }
+    }
+
+    /// Resolve a `let`, binding its name for what follows
+    ///
+    /// The initializer is resolved first, so `let x = x + 1;` uses the
+    /// previous `x`.
+    fn resolve_local(&mut self, local: &'ast Local) {
+        if let Some(ty) = &local.type_annotation {
+            self.resolve_type(ty, PathSource::Type);
+        }
+        if let Some(init) = &local.init {
+            self.resolve_expr(init);
+        }
+        self.bind_local(&local.name);
+    }
+
+    fn bind_local(&mut self, ident: &Identifier) {
+        if ident.name == "_" {
+            return;
+        }
+        let id = self.define(ident, DefKind::Local, self.owner);
+        self.bind(Namespace::Value, &ident.name, Res::Def(id));
+    }
+
+    // ========================================================================
+    // Expressions
+    // ========================================================================
+
+    fn resolve_expr(&mut self, expr: &'ast Expression) {
+        match &expr.kind {
+            ExpressionKind::Path(path) => {
+                self.resolve_path(path, Namespace::Value, PathSource::Expr);
+            }
+            ExpressionKind::Call(callee, args) => {
+                match &callee.kind {
+                    ExpressionKind::Path(path) => {
+                        self.resolve_path(path, Namespace::Value, PathSource::Call);
+                    }
+                    _ => self.resolve_expr(callee),
+                }
+                for arg in args {
+                    self.resolve_expr(arg);
+                }
+            }
+            ExpressionKind::MethodCall(receiver, _, args) => {
+                self.resolve_expr(receiver);
+                for arg in args {
+                    self.resolve_expr(arg);
+                }
+            }
+            ExpressionKind::Unary(_, operand)
+            | ExpressionKind::FieldAccess(operand, _)
+            | ExpressionKind::TupleIndex(operand, _)
+            | ExpressionKind::Throw(operand)
+            | ExpressionKind::QuestionMark(operand)
+            | ExpressionKind::Grouped(operand)
+            | ExpressionKind::Await(operand) => self.resolve_expr(operand),
+            ExpressionKind::Binary(_, left, right)
+            | ExpressionKind::Index(left, right)
+            | ExpressionKind::Assign(left, right)
+            | ExpressionKind::AssignOp(_, left, right)
+            | ExpressionKind::Range(left, _, right) => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            ExpressionKind::Array(elements) | ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
+            ExpressionKind::Struct(literal) => {
+                self.resolve_path(&literal.path, Namespace::Type, PathSource::Struct);
+                for field in &literal.fields {
+                    self.resolve_expr(&field.value);
+                }
+                if let Some(base) = &literal.base {
+                    self.resolve_expr(base);
+                }
+            }
+            ExpressionKind::If(condition, then_block, else_block) => {
+                // `if let` bindings are only visible in the then block
+                self.with_rib(RibKind::Normal, |this| {
+                    this.resolve_condition(condition);
+                    this.resolve_block(then_block);
+                });
+                if let Some(else_block) = else_block {
+                    self.resolve_block(else_block);
+                }
+            }
+            ExpressionKind::While(condition, body, _) => {
+                self.with_rib(RibKind::Normal, |this| {
+                    this.resolve_condition(condition);
+                    this.resolve_block(body);
+                });
+            }
+            ExpressionKind::Let(pattern, value) => {
+                self.resolve_expr(value);
+                self.resolve_pattern_top(std::slice::from_ref(&**pattern));
+            }
+            ExpressionKind::Match(scrutinee, arms) => {
+                self.resolve_expr(scrutinee);
+                for arm in arms {
+                    self.with_rib(RibKind::Normal, |this| {
+                        this.resolve_pattern_top(&arm.patterns);
+                        if let Some(guard) = &arm.guard {
+                            this.resolve_expr(guard);
+                        }
+                        this.resolve_expr(&arm.body);
+                    });
+                }
+            }
+            ExpressionKind::Loop(body, _) => self.resolve_block(body),
+            ExpressionKind::For(binding, iter, body, _) => {
+                self.resolve_expr(iter);
+                self.with_rib(RibKind::Normal, |this| {
+                    if let Some(ty) = &binding.type_annotation {
+                        this.resolve_type(ty, PathSource::Type);
+                    }
+                    this.bind_local(&binding.name);
+                    this.resolve_block(body);
+                });
+            }
+            ExpressionKind::Closure {
+                params,
+                return_type,
+                body,
+            } => {
+                self.with_rib(RibKind::Normal, |this| {
+                    let mut bindings = Bindings::new();
+                    for param in params {
+                        if let Some(ty) = &param.type_annotation {
+                            this.resolve_type(ty, PathSource::Type);
+                        }
+                        this.bind_pattern_name(&param.name, &mut bindings, None, true);
+                    }
+                    this.bind_all(bindings);
+                    if let Some(ty) = return_type {
+                        this.resolve_type(ty, PathSource::Type);
+                    }
+                    this.resolve_expr(body);
+                });
+            }
+            ExpressionKind::Block(block) => self.resolve_block(block),
+            ExpressionKind::Try(block, handlers) => {
+                self.resolve_block(block);
+                for handler in handlers {
+                    self.resolve_handler(handler);
+                }
+            }
+            ExpressionKind::Perform(_, args) => {
+                for arg in args {
+                    self.resolve_expr(arg);
+                }
+            }
+            ExpressionKind::Cast(value, ty) => {
+                self.resolve_expr(value);
+                self.resolve_type(ty, PathSource::Type);
+            }
+            ExpressionKind::TemplateString(template) => {
+                for part in &template.parts {
+                    let TemplateStringPart::Expr(expr, spec) = part else {
+                        continue;
+                    };
+                    self.resolve_expr(expr);
+                    // `{:width$}` names a variable too
+                    for count in [&spec.width, &spec.precision] {
+                        if let Some(Count::Argument(Argument::Name(name))) = count {
+                            let ident = Identifier::new(expr.span, name.clone());
+                            self.resolve_path(std::slice::from_ref(&ident), Namespace::Value, PathSource::Expr);
+                        }
+                    }
+                }
+            }
+            ExpressionKind::Return(value) => {
+                if let Some(value) = value {
+                    self.resolve_expr(value);
+                }
+            }
+            ExpressionKind::Defer(stmt) => self.with_rib(RibKind::Normal, |this| this.resolve_statement(stmt)),
+            ExpressionKind::MacroInvocation { args, .. } => {
+                for arg in args {
+                    self.resolve_expr(arg);
+                }
+            }
+            ExpressionKind::Literal(_)
+            | ExpressionKind::Break(_)
+            | ExpressionKind::Continue(_)
+            | ExpressionKind::Error => {}
+        }
+    }
+
+    /// Resolve an `if` or `while` condition, binding the names of its
+    /// `let` patterns in the innermost rib
+    fn resolve_condition(&mut self, condition: &'ast Expression) {
+        match &condition.kind {
+            ExpressionKind::Let(pattern, value) => {
+                self.resolve_expr(value);
+                self.resolve_pattern_top(std::slice::from_ref(&**pattern));
+            }
+            ExpressionKind::Binary(BinaryOp::And, left, right) => {
+                self.resolve_condition(left);
+                self.resolve_condition(right);
+            }
+            _ => self.resolve_expr(condition),
+        }
+    }
+
+    fn resolve_handler(&mut self, handler: &'ast EffectHandler) {
+        let resolution = self.resolve_path(
+            std::slice::from_ref(&handler.effect_name),
+            Namespace::Type,
+            PathSource::Effect,
+        );
+        let effect = match resolution.full_res() {
+            Some(Res::Def(id)) if self.kind(*id) == DefKind::Effect => Some(*id),
+            _ => None,
+        };
+
+        for method in &handler.methods {
+            // Each handler method implements an operation of the effect
+            if let Some(effect) = effect {
+                match self.resolutions.child(effect, Namespace::Value, &method.name.name) {
+                    Some(operation) => self.record(&method.name, PathResolution::full(Res::Def(operation))),
+                    None => {
+                        let similar = self.similar_def(
+                            &method.name.name,
+                            self.resolutions.child_names(effect, Namespace::Value),
+                        );
+                        self.errors.push(ResolveError::NoAssociatedItem {
+                            name: method.name.name.clone(),
+                            owner: self.describe(effect),
+                            span: method.name.span,
+                            similar,
+                        });
+                    }
+                }
+            }
+            self.with_rib(RibKind::Normal, |this| {
+                this.resolve_params(&method.params);
+                this.resolve_block(&method.body);
+            });
+        }
+    }
+
+    // ========================================================================
+    // Patterns
+    // ========================================================================
+
+    /// Resolve the alternatives of a pattern and bind their names in the
+    /// innermost rib
+    fn resolve_pattern_top(&mut self, alternatives: &[Pattern]) {
+        let mut bindings = Bindings::new();
+        self.resolve_alternatives(alternatives, &mut bindings);
+        self.bind_all(bindings);
+    }
+
+    /// Resolve `A | B | ...`, where each alternative must bind the same
+    /// names, which share their definitions
+    fn resolve_alternatives(&mut self, alternatives: &[Pattern], bindings: &mut Bindings) {
+        if let [pattern] = alternatives {
+            self.resolve_pattern(pattern, bindings, None);
+            return;
+        }
+
+        let outer = bindings.clone();
+        let mut first: Option<Bindings> = None;
+        for alternative in alternatives {
+            let mut alternative_bindings = outer.clone();
+            self.resolve_pattern(alternative, &mut alternative_bindings, first.as_ref());
+            let Some(first) = &first else {
+                first = Some(alternative_bindings);
+                continue;
+            };
+            let missing = alternative_bindings
+                .iter()
+                .filter(|(name, _)| !first.contains_key(*name))
+                .chain(first.iter().filter(|(name, _)| !alternative_bindings.contains_key(*name)));
+            let mut errors: Vec<_> = missing
+                .map(|(name, (_, span))| ResolveError::NotBoundInAllPatterns {
+                    name: name.clone(),
+                    span: *span,
+                })
+                .collect();
+            errors.sort_by_key(|error| (error.span().start.line, error.span().start.column));
+            self.errors.extend(errors);
+        }
+        *bindings = first.unwrap_or(outer);
+    }
+
+    fn resolve_pattern(&mut self, pattern: &Pattern, bindings: &mut Bindings, shared: Option<&Bindings>) {
+        match pattern {
+            Pattern::Wildcard | Pattern::Literal(_) => {}
+            Pattern::Identifier(ident) => match self.pattern_constant(ident) {
+                // `None`, `Color::Red` or a constant, not a new binding
+                Some(res) => {
+                    self.note_lookup(&ident.name, Namespace::Value);
+                    self.record(ident, PathResolution::full(res));
+                }
+                None => self.bind_pattern_name(ident, bindings, shared, false),
+            },
+            Pattern::Struct(path, fields) => {
+                self.resolve_path(path, Namespace::Type, PathSource::Struct);
+                for field in fields {
+                    match field {
+                        StructPatternField::Field(_, pattern) => self.resolve_pattern(pattern, bindings, shared),
+                        StructPatternField::Shorthand(name) => self.bind_pattern_name(name, bindings, shared, false),
+                    }
+                }
+            }
+            Pattern::TupleVariant(path, patterns) => {
+                self.resolve_path(path, Namespace::Value, PathSource::TupleVariant);
+                for pattern in patterns {
+                    self.resolve_pattern(pattern, bindings, shared);
+                }
+            }
+            Pattern::Tuple(patterns) | Pattern::Array(patterns) => {
+                for pattern in patterns {
+                    self.resolve_pattern(pattern, bindings, shared);
+                }
+            }
+            Pattern::Slice(before, middle, after) => {
+                for pattern in before.iter().chain(middle).chain(after) {
+                    self.resolve_pattern(pattern, bindings, shared);
+                }
+            }
+            Pattern::Range(start, _, end) => {
+                self.resolve_pattern(start, bindings, shared);
+                self.resolve_pattern(end, bindings, shared);
+            }
+            Pattern::Or(alternatives) => self.resolve_alternatives(alternatives, bindings),
+        }
+    }
+
+    /// What an identifier pattern names, if it is a unit variant, unit
+    /// struct or constant in scope rather than a new binding
+    fn pattern_constant(&self, ident: &Identifier) -> Option<Res> {
+        let (res, _) = self.lookup(&ident.name, Namespace::Value)?;
+        let id = res.def_id()?;
+        matches!(
+            self.kind(id),
+            DefKind::Variant | DefKind::Struct | DefKind::Const | DefKind::AssocConst
+        )
+        .then_some(res)
+    }
+
+    /// Define a name bound by a pattern or parameter list
+    ///
+    /// In the second and later alternatives of an or-pattern, `shared`
+    /// holds the first alternative's bindings, whose definitions are reused.
+    fn bind_pattern_name(&mut self, ident: &Identifier, bindings: &mut Bindings, shared: Option<&Bindings>, in_params: bool) {
+        if ident.name == "_" {
+            return;
+        }
+        if bindings.contains_key(&ident.name) {
+            self.errors.push(ResolveError::DuplicateBinding {
+                name: ident.name.clone(),
+                in_params,
+                span: ident.span,
+            });
+            return;
+        }
+        let id = match shared.and_then(|shared| shared.get(&ident.name)) {
+            Some(&(id, _)) => {
+                self.resolutions.defs.insert((ident.span, ident.name.clone()), id);
+                id
+            }
+            None => self.define(ident, DefKind::Local, self.owner),
+        };
+        bindings.insert(ident.name.clone(), (id, ident.span));
+    }
+
+    fn bind_all(&mut self, bindings: Bindings) {
+        for (name, (id, _)) in bindings {
+            self.bind(Namespace::Value, &name, Res::Def(id));
+        }
+    }
+}
+
+fn partial(res: Res, unresolved_segments: usize) -> PathResolution {
+    PathResolution {
+        res,
+        unresolved_segments,
+    }
+}
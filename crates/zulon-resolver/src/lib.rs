@@ -3,8 +3,590 @@
 
 //! # zulon-resolver
 //!
-//! TODO: Add documentation
+//! Name resolution for ZULON. [`resolve`] gives every item, local variable
+//! and generic parameter a [`DefId`], and resolves every path in
+//! expressions, patterns and types to what it names:
+//!
+//! ```rust
+//! use zulon_parser::ast::{ExpressionKind, ItemKind};
+//! use zulon_parser::Parser;
+//! use zulon_resolver::{resolve, DefKind, Res};
+//!
+//! let ast = Parser::from_source("fn double(x: i32) -> i32 { x * 2 }").parse().unwrap();
+//! let (resolutions, errors) = resolve(&ast);
+//! assert!(errors.is_empty());
+//!
+//! let ItemKind::Function(func) = &ast.items[0].kind else { unreachable!() };
+//! let ExpressionKind::Binary(_, x, _) = &func.body.trailing_expr.as_ref().unwrap().kind else { unreachable!() };
+//! let ExpressionKind::Path(path) = &x.kind else { unreachable!() };
+//!
+//! let id = resolutions.res(&path[0]).and_then(Res::def_id).unwrap();
+//! assert_eq!(resolutions.definition(id).kind, DefKind::Local);
+//! assert_eq!(Some(id), resolutions.def_of(&func.params[0].name));
+//! ```
+//!
+//! ## Scopes
+//!
+//! Items in a module are visible throughout it, in any order, along with
+//! what its `use` items import. Inside a block, the block's items are
+//! visible everywhere in it and each `let` starts a new scope, so a
+//! binding shadows earlier ones from its next statement on. Items nested in
+//! a function cannot see the function's locals or generic parameters.
+//!
+//! Names not found in a module come from the prelude, if there is one: see
+//! [`resolve_with_prelude`].
+//!
+//! Paths start from the current scope, or from `crate`, `self`, `super` or
+//! `Self`. A path through a type parameter or type alias, like
+//! `T::default`, resolves only its first segments; the rest need types
+//! and are left to type checking.
+//!
+//! ## Errors
+//!
+//! Resolution continues past errors, resolving what it can. Names that are
+//! not found come with the most similar name in scope, if one is close
+//! enough to be a typo, which [`ResolveError::to_diagnostic`] turns into a
+//! suggestion.
 
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 
+mod collect;
+pub mod def;
+pub mod diagnostic;
+pub mod error;
+mod flatten;
+mod imports;
+mod late;
+pub mod lint;
+pub mod suggest;
+
+pub use def::{DefId, DefKind, Definition, Namespace, PathResolution, PrimTy, Res};
+pub use error::{PathSource, ResolveError, Similar};
+pub use flatten::flatten_modules;
+pub use lint::{lint, Lint, Warning};
+
+use std::collections::{HashMap, HashSet};
+use zulon_parser::ast::{Ast, Identifier, Visibility};
+use zulon_parser::{Position, Span};
+
+use imports::Import;
+use late::Rib;
+
+/// Resolve the names in a program
+///
+/// Returns every resolution that succeeded, along with the errors for
+/// those that did not.
+pub fn resolve(ast: &Ast) -> (Resolutions, Vec<ResolveError>) {
+    resolve_with_externs(ast, std::iter::empty::<String>())
+}
+
+/// Resolve the names in a program that can also call functions the
+/// compiler declares for it, like `printf`
+///
+/// The names resolve to [`Res::Intrinsic`] where no definition in scope
+/// shadows them.
+pub fn resolve_with_externs(
+    ast: &Ast,
+    externs: impl IntoIterator<Item = impl Into<String>>,
+) -> (Resolutions, Vec<ResolveError>) {
+    let mut resolver = Resolver::new();
+    resolver.externs = externs.into_iter().map(Into::into).collect();
+    resolver.resolve_crate(ast, None);
+    (resolver.resolutions, resolver.errors)
+}
+
+/// Resolve the names in a program along with a prelude, whose public items
+/// every module of the program can use without importing them
+///
+/// The prelude is resolved as a module named `prelude` outside the crate:
+/// its items cannot be named by path, and a name defined or imported in a
+/// module shadows the prelude's. The definitions of both are in the
+/// returned [`Resolutions`], with [`Resolutions::prelude`] as the parent of
+/// the prelude's items.
+pub fn resolve_with_prelude(ast: &Ast, prelude: &Ast) -> (Resolutions, Vec<ResolveError>) {
+    let mut resolver = Resolver::new();
+    resolver.resolve_crate(ast, Some(prelude));
+    (resolver.resolutions, resolver.errors)
+}
+
+/// The result of name resolution
+///
+/// Identifiers are looked up by span and name, since the AST has no node
+/// ids.
+#[derive(Debug, Clone)]
+pub struct Resolutions {
+    definitions: Vec<Definition>,
+    /// Names declared in modules, enums, traits, effects and impls,
+    /// including imported ones
+    children: HashMap<DefId, PerNs<HashMap<String, Binding>>>,
+    /// Modules whose names are glob-imported into a module, with the
+    /// glob's visibility and the last segment of its prefix
+    globs: HashMap<DefId, Vec<(DefId, Vis, Span)>>,
+    /// The impls of each struct or enum
+    impls: HashMap<DefId, Vec<DefId>>,
+    /// The struct or enum each impl is for
+    impl_self: HashMap<DefId, DefId>,
+    /// The trait each trait impl implements
+    impl_traits: HashMap<DefId, DefId>,
+    defs: HashMap<(Span, String), DefId>,
+    paths: HashMap<(Span, String), PathResolution>,
+    /// The imports some path resolved through, by the span of their binding
+    used_imports: HashSet<Span>,
+    prelude: Option<DefId>,
+}
+
+impl Resolutions {
+    fn new() -> Self {
+        let root = Definition {
+            name: "crate".to_string(),
+            kind: DefKind::Module,
+            span: Span::new(Position::new(1, 1), Position::new(1, 1)),
+            parent: None,
+        };
+        Resolutions {
+            definitions: vec![root],
+            children: HashMap::new(),
+            globs: HashMap::new(),
+            impls: HashMap::new(),
+            impl_self: HashMap::new(),
+            impl_traits: HashMap::new(),
+            defs: HashMap::new(),
+            paths: HashMap::new(),
+            used_imports: HashSet::new(),
+            prelude: None,
+        }
+    }
+
+    /// The crate root module
+    pub fn root(&self) -> DefId {
+        DefId::ROOT
+    }
+
+    /// The prelude module, if resolution had one
+    ///
+    /// Unlike every other definition, it has no parent.
+    pub fn prelude(&self) -> Option<DefId> {
+        self.prelude
+    }
+
+    /// The definition with id `id`
+    pub fn definition(&self, id: DefId) -> &Definition {
+        &self.definitions[id.index()]
+    }
+
+    /// Every definition, in the order they were made
+    pub fn definitions(&self) -> impl Iterator<Item = (DefId, &Definition)> {
+        self.definitions
+            .iter()
+            .enumerate()
+            .map(|(index, definition)| (DefId(index as u32), definition))
+    }
+
+    /// The definition made by a defining identifier, like a function's name
+    /// or a `let` binding
+    pub fn def_of(&self, ident: &Identifier) -> Option<DefId> {
+        self.defs.get(&(ident.span, ident.name.clone())).copied()
+    }
+
+    /// The resolution of a path, looked up by one of its segments
+    ///
+    /// For the last segment this is the resolution of the whole path; for
+    /// earlier segments, that of the path up to the segment.
+    pub fn resolution(&self, segment: &Identifier) -> Option<&PathResolution> {
+        self.paths.get(&(segment.span, segment.name.clone()))
+    }
+
+    /// What a path segment names, if the path up to it fully resolved
+    pub fn res(&self, segment: &Identifier) -> Option<&Res> {
+        self.resolution(segment).and_then(PathResolution::full_res)
+    }
+
+    /// The item named `name` in a module, enum, trait, effect or impl,
+    /// including imported names
+    pub fn child(&self, owner: DefId, ns: Namespace, name: &str) -> Option<DefId> {
+        self.child_binding(owner, ns, name, &mut Vec::new()).map(|binding| binding.id)
+    }
+
+    /// Whether a path resolved through the import of `ident`
+    ///
+    /// `ident` is the name a `use` item brings into scope: its last
+    /// segment, its alias, or for a glob the segment before the `*`.
+    pub fn is_import_used(&self, ident: &Identifier) -> bool {
+        self.used_imports.contains(&ident.span)
+    }
+
+    /// The impls whose self type is `ty`
+    pub fn impls_of(&self, ty: DefId) -> &[DefId] {
+        self.impls.get(&ty).map_or(&[], Vec::as_slice)
+    }
+
+    /// The struct or enum an impl is for, if its self type names one
+    pub fn impl_self_type(&self, impl_id: DefId) -> Option<DefId> {
+        self.impl_self.get(&impl_id).copied()
+    }
+
+    /// The trait an impl implements, if it is a trait impl
+    pub fn impl_trait(&self, impl_id: DefId) -> Option<DefId> {
+        self.impl_traits.get(&impl_id).copied()
+    }
+
+    /// The path of a definition from the crate root, like `geometry::Point`
+    ///
+    /// Paths of the prelude's items start with `prelude`.
+    pub fn path_str(&self, id: DefId) -> String {
+        let mut segments = Vec::new();
+        let mut current = Some(id);
+        while let Some(id) = current.filter(|id| *id != DefId::ROOT) {
+            let definition = self.definition(id);
+            segments.push(definition.name.as_str());
+            current = definition.parent;
+        }
+        segments.reverse();
+        segments.join("::")
+    }
+
+    /// The binding of `name` in `owner`
+    ///
+    /// A name a glob brings in has the glob's visibility, and the glob as its
+    /// import. Globs only bring in names `owner` can see.
+    fn child_binding(&self, owner: DefId, ns: Namespace, name: &str, visited: &mut Vec<DefId>) -> Option<Binding> {
+        if visited.contains(&owner) {
+            return None;
+        }
+        visited.push(owner);
+        if let Some(binding) = self.children.get(&owner).and_then(|names| names.get(ns).get(name)) {
+            return Some(*binding);
+        }
+        let globs = self.globs.get(&owner)?;
+        globs.iter().find_map(|(glob, vis, span)| {
+            let binding = self.child_binding(*glob, ns, name, visited)?;
+            self.is_accessible(binding.vis, owner).then_some(Binding {
+                vis: *vis,
+                import: Some(*span),
+                ..binding
+            })
+        })
+    }
+
+    /// Whether a name with visibility `vis` can be used from `module`
+    fn is_accessible(&self, vis: Vis, module: DefId) -> bool {
+        match vis {
+            Vis::Public => true,
+            Vis::Restricted(restriction) => {
+                let mut current = Some(module);
+                while let Some(id) = current {
+                    if id == restriction {
+                        return true;
+                    }
+                    current = self.definition(id).parent;
+                }
+                false
+            }
+        }
+    }
+
+    /// Every name visible in `owner`, for suggestions
+    fn child_names(&self, owner: DefId, ns: Namespace) -> Vec<(&str, DefId)> {
+        let mut names = Vec::new();
+        let mut visited = Vec::new();
+        let mut pending = vec![owner];
+        while let Some(owner) = pending.pop() {
+            if visited.contains(&owner) {
+                continue;
+            }
+            visited.push(owner);
+            if let Some(children) = self.children.get(&owner) {
+                names.extend(children.get(ns).iter().map(|(name, binding)| (name.as_str(), binding.id)));
+            }
+            pending.extend(self.globs.get(&owner).into_iter().flatten().map(|(glob, _, _)| *glob));
+        }
+        names
+    }
+}
+
+/// A name declared in a module-like scope
+#[derive(Debug, Clone, Copy)]
+struct Binding {
+    id: DefId,
+    /// The defining identifier, or the name in the `use` item
+    span: Span,
+    vis: Vis,
+    /// The span of the import that brought the name in, if one did
+    import: Option<Span>,
+}
+
+/// Where a name can be used from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Vis {
+    /// Anywhere: `pub` and `pub(crate)`
+    Public,
+    /// Inside this module and the modules nested in it
+    Restricted(DefId),
+}
+
+/// One value per namespace
+#[derive(Debug, Clone, Default)]
+struct PerNs<T> {
+    types: T,
+    values: T,
+}
+
+impl<T> PerNs<T> {
+    fn get(&self, ns: Namespace) -> &T {
+        match ns {
+            Namespace::Type => &self.types,
+            Namespace::Value => &self.values,
+        }
+    }
+
+    fn get_mut(&mut self, ns: Namespace) -> &mut T {
+        match ns {
+            Namespace::Type => &mut self.types,
+            Namespace::Value => &mut self.values,
+        }
+    }
+}
+
+/// State shared by the resolution phases
+struct Resolver<'ast> {
+    resolutions: Resolutions,
+    errors: Vec<ResolveError>,
+    /// Module-level imports not resolved yet
+    imports: Vec<Import<'ast>>,
+    /// Impls whose self type and trait are not resolved yet, with the
+    /// module they are in
+    pending_impls: Vec<(DefId, &'ast zulon_parser::ast::Impl, DefId)>,
+    /// The definitions of items and impls, by the address of their AST node
+    ///
+    /// Spans are not unique in expanded code, so this is how later phases
+    /// find the definition collection made for a node.
+    item_defs: HashMap<*const (), DefId>,
+    /// The module being resolved
+    module: DefId,
+    /// The item or function that new locals belong to
+    owner: DefId,
+    ribs: Vec<Rib>,
+    /// Functions the compiler declares for the program
+    externs: HashSet<String>,
+}
+
+impl<'ast> Resolver<'ast> {
+    fn new() -> Self {
+        Resolver {
+            resolutions: Resolutions::new(),
+            errors: Vec::new(),
+            imports: Vec::new(),
+            pending_impls: Vec::new(),
+            item_defs: HashMap::new(),
+            module: DefId::ROOT,
+            owner: DefId::ROOT,
+            ribs: Vec::new(),
+            externs: HashSet::new(),
+        }
+    }
+
+    /// Run every phase over a crate and its prelude
+    fn resolve_crate(&mut self, ast: &'ast Ast, prelude: Option<&'ast Ast>) {
+        if let Some(prelude) = prelude {
+            let id = DefId(self.resolutions.definitions.len() as u32);
+            self.resolutions.definitions.push(Definition {
+                name: "prelude".to_string(),
+                kind: DefKind::Module,
+                span: Span::new(Position::new(1, 1), Position::new(1, 1)),
+                parent: None,
+            });
+            self.resolutions.prelude = Some(id);
+            self.collect_module(prelude.items.iter(), id);
+        }
+        self.collect_module(ast.items.iter(), DefId::ROOT);
+        self.resolve_imports();
+        self.resolve_impl_headers();
+        if let (Some(prelude), Some(id)) = (prelude, self.resolutions.prelude) {
+            let saved = std::mem::replace(&mut self.module, id);
+            self.resolve_items(prelude.items.iter());
+            self.module = saved;
+        }
+        self.resolve_items(ast.items.iter());
+    }
+
+    /// The item of the prelude named `name` that `module` can see, for
+    /// names the module does not define
+    fn prelude_child(&self, module: DefId, ns: Namespace, name: &str) -> Option<DefId> {
+        let prelude = self.resolutions.prelude?;
+        let binding = self.resolutions.child_binding(prelude, ns, name, &mut Vec::new())?;
+        self.resolutions
+            .is_accessible(binding.vis, module)
+            .then_some(binding.id)
+    }
+
+    fn kind(&self, id: DefId) -> DefKind {
+        self.resolutions.definition(id).kind
+    }
+
+    /// Make a definition for `ident`
+    fn define(&mut self, ident: &Identifier, kind: DefKind, parent: DefId) -> DefId {
+        let id = DefId(self.resolutions.definitions.len() as u32);
+        self.resolutions.definitions.push(Definition {
+            name: ident.name.clone(),
+            kind,
+            span: ident.span,
+            parent: Some(parent),
+        });
+        self.resolutions.defs.insert((ident.span, ident.name.clone()), id);
+        id
+    }
+
+    /// Declare `id` as `name` in a module-like scope, reporting a clash
+    /// with an earlier name
+    fn declare(&mut self, owner: DefId, ns: Namespace, name: &str, id: DefId, span: Span, vis: Vis) {
+        let binding = Binding {
+            id,
+            span,
+            vis,
+            import: None,
+        };
+        self.declare_binding(owner, ns, name, binding);
+    }
+
+    /// Declare a name, defined or imported, in a module-like scope
+    fn declare_binding(&mut self, owner: DefId, ns: Namespace, name: &str, binding: Binding) {
+        let Binding { id, span, .. } = binding;
+        let names = self.resolutions.children.entry(owner).or_default().get_mut(ns);
+        match names.get(name) {
+            // The same item imported twice
+            Some(previous) if previous.id == id => {}
+            Some(previous) => {
+                let previous = previous.span;
+                self.errors.push(ResolveError::Duplicate {
+                    name: name.to_string(),
+                    span,
+                    previous,
+                });
+            }
+            None => {
+                names.insert(name.to_string(), binding);
+            }
+        }
+    }
+
+    /// Note a use of `name` in `owner`, marking the import it came through
+    fn note_use(&mut self, owner: DefId, ns: Namespace, name: &str) {
+        let import = self
+            .resolutions
+            .child_binding(owner, ns, name, &mut Vec::new())
+            .and_then(|binding| binding.import);
+        if let Some(span) = import {
+            self.resolutions.used_imports.insert(span);
+        }
+    }
+
+    fn record(&mut self, segment: &Identifier, resolution: PathResolution) {
+        self.resolutions
+            .paths
+            .insert((segment.span, segment.name.clone()), resolution);
+    }
+
+    /// The closest module containing `id`, or `id` itself if it is a module
+    fn module_of(&self, id: DefId) -> DefId {
+        let mut current = id;
+        while self.kind(current) != DefKind::Module {
+            match self.resolutions.definition(current).parent {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// The module `super` refers to from `module`
+    fn parent_module(&self, module: DefId) -> Option<DefId> {
+        let parent = self.resolutions.definition(module).parent?;
+        Some(self.module_of(parent))
+    }
+
+    /// Where an item with `visibility` in `module` can be used from
+    fn vis(&mut self, visibility: &Visibility, module: DefId) -> Vis {
+        match visibility {
+            Visibility::Public | Visibility::Crate => Vis::Public,
+            Visibility::Private => Vis::Restricted(module),
+            Visibility::Restricted(path) => {
+                // The path names an ancestor: `self`, `super`, or `crate` or
+                // `super` followed by the names of the modules down to it
+                let mut ancestors = vec![module];
+                let mut current = module;
+                while let Some(parent) = self.parent_module(current) {
+                    ancestors.push(parent);
+                    current = parent;
+                }
+                let target = match path.first().map(|segment| segment.name.as_str()) {
+                    Some("self") if path.len() == 1 => Some(module),
+                    Some("crate") => self.descend(DefId::ROOT, &path[1..], &ancestors),
+                    Some("super") => {
+                        let supers = path.iter().take_while(|segment| segment.name == "super").count();
+                        let start = ancestors.get(supers).copied();
+                        start.and_then(|start| self.descend(start, &path[supers..], &ancestors))
+                    }
+                    _ => None,
+                };
+                match target {
+                    Some(target) => Vis::Restricted(target),
+                    None => {
+                        let span = path.first().map_or(self.resolutions.definition(module).span, |segment| segment.span);
+                        self.errors.push(ResolveError::BadVisibility { span });
+                        Vis::Restricted(module)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Follow `path` from `start` through the modules in `ancestors`
+    fn descend(&self, start: DefId, path: &[Identifier], ancestors: &[DefId]) -> Option<DefId> {
+        path.iter().try_fold(start, |current, segment| {
+            ancestors.iter().copied().find(|ancestor| {
+                let definition = self.resolutions.definition(*ancestor);
+                definition.parent == Some(current) && definition.name == segment.name
+            })
+        })
+        .filter(|target| ancestors.contains(target))
+    }
+
+    /// The error for using a private binding of `owner` from `module`
+    fn check_accessible(&self, owner: DefId, ns: Namespace, segment: &Identifier, module: DefId) -> Result<(), ResolveError> {
+        let Some(binding) = self.resolutions.child_binding(owner, ns, &segment.name, &mut Vec::new()) else {
+            return Ok(());
+        };
+        if self.resolutions.is_accessible(binding.vis, module) {
+            return Ok(());
+        }
+        let definition = self.resolutions.definition(binding.id);
+        Err(ResolveError::Private {
+            name: segment.name.clone(),
+            descr: definition.kind.descr(),
+            span: segment.span,
+            defined: binding.span,
+        })
+    }
+
+    /// How errors name a scope, like "module `geometry`" or "the crate root"
+    fn describe(&self, id: DefId) -> String {
+        if id == DefId::ROOT {
+            return "the crate root".to_string();
+        }
+        format!("{} `{}`", self.kind(id).descr(), self.resolutions.path_str(id))
+    }
+
+    fn similar_def(&self, name: &str, mut candidates: Vec<(&str, DefId)>) -> Option<Box<Similar>> {
+        // Names come from hash maps; sort them so ties break the same way every run
+        candidates.sort();
+        suggest::best_match(name, candidates).map(|(similar, id)| {
+            let definition = self.resolutions.definition(id);
+            Box::new(Similar {
+                name: similar.to_string(),
+                descr: definition.kind.descr(),
+                span: Some(definition.span),
+            })
+        })
+    }
+}
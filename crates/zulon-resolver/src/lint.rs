@@ -0,0 +1,509 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Lints for unused and unreachable code
+//!
+//! [`lint`] runs after resolution and warns about:
+//!
+//! - `let` bindings that are never used (`unused_variables`)
+//! - private `use` items no path resolves through (`unused_imports`)
+//! - private functions that are never used outside their own body
+//!   (`dead_code`)
+//! - statements and expressions after one that never finishes, like
+//!   `return`, `throw`, `panic!` or a call of a function returning `!`
+//!   (`unreachable_code`)
+//!
+//! `#![allow(lint)]` on the crate or `#[allow(lint)]` on an item turns a
+//! lint off inside it; `#[allow(unused)]` turns off all four. Bindings
+//! whose names start with `_` are not reported.
+
+use crate::{DefId, Res, Resolutions};
+use std::collections::HashSet;
+use thiserror::Error;
+use zulon_parser::ast::visit::{self, Visitor};
+use zulon_parser::ast::{
+    Ast, Attribute, AttributeArg, BinaryOp, Block, Expression, ExpressionKind, Function, Identifier, Item, ItemKind,
+    Module, Statement, StatementKind, Type, Use, UsePath, Visibility,
+};
+use zulon_parser::Span;
+
+/// A lint, which `#[allow(...)]` can turn off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// `unused_variables`
+    UnusedVariables,
+    /// `unused_imports`
+    UnusedImports,
+    /// `dead_code`
+    DeadCode,
+    /// `unreachable_code`
+    UnreachableCode,
+}
+
+impl Lint {
+    /// Every lint
+    pub const ALL: [Lint; 4] = [Lint::UnusedVariables, Lint::UnusedImports, Lint::DeadCode, Lint::UnreachableCode];
+
+    /// The name `#[allow(...)]` takes
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedImports => "unused_imports",
+            Lint::DeadCode => "dead_code",
+            Lint::UnreachableCode => "unreachable_code",
+        }
+    }
+
+    /// The lints a name in `#[allow(...)]` stands for; `unused` is all of them
+    fn from_name(name: &str) -> Vec<Lint> {
+        if name == "unused" {
+            return Lint::ALL.to_vec();
+        }
+        Lint::ALL.into_iter().filter(|lint| lint.name() == name).collect()
+    }
+}
+
+/// A warning about unused or unreachable code
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Warning {
+    /// A `let` binding that is never used
+    #[error("unused variable: `{name}`")]
+    UnusedVariable {
+        /// The variable's name
+        name: String,
+        /// Where the variable is bound
+        span: Span,
+    },
+
+    /// An import no path resolves through
+    #[error("unused import: `{path}`")]
+    UnusedImport {
+        /// The imported path, as written
+        path: String,
+        /// Where the import is
+        span: Span,
+    },
+
+    /// A private function that is never used
+    #[error("function `{name}` is never used")]
+    UnusedFunction {
+        /// The function's name
+        name: String,
+        /// Where the function's name is
+        span: Span,
+    },
+
+    /// Code after code that never finishes
+    #[error("unreachable {what}")]
+    Unreachable {
+        /// "statement" or "expression"
+        what: &'static str,
+        /// The unreachable code
+        span: Span,
+        /// The code that never finishes
+        cause: Span,
+    },
+}
+
+impl Warning {
+    /// The lint that reported this warning
+    pub fn lint(&self) -> Lint {
+        match self {
+            Warning::UnusedVariable { .. } => Lint::UnusedVariables,
+            Warning::UnusedImport { .. } => Lint::UnusedImports,
+            Warning::UnusedFunction { .. } => Lint::DeadCode,
+            Warning::Unreachable { .. } => Lint::UnreachableCode,
+        }
+    }
+
+    /// Where the warning points
+    pub fn span(&self) -> Span {
+        match self {
+            Warning::UnusedVariable { span, .. }
+            | Warning::UnusedImport { span, .. }
+            | Warning::UnusedFunction { span, .. }
+            | Warning::Unreachable { span, .. } => *span,
+        }
+    }
+}
+
+/// Check a resolved crate for unused and unreachable code
+///
+/// `resolutions` must come from resolving `ast`. Warnings are in the order
+/// of the code they point at.
+pub fn lint(ast: &Ast, resolutions: &Resolutions) -> Vec<Warning> {
+    let mut uses = Uses {
+        resolutions,
+        used: HashSet::new(),
+        functions: Vec::new(),
+    };
+    uses.visit_ast(ast);
+
+    let mut linter = Linter {
+        resolutions,
+        used: uses.used,
+        never: HashSet::new(),
+        allowed: Vec::new(),
+        module_depth: 0,
+        function_depth: 0,
+        warnings: Vec::new(),
+    };
+    let mut never = NeverFunctions(&mut linter.never, resolutions);
+    never.visit_ast(ast);
+    linter.visit_ast(ast);
+
+    let mut warnings = linter.warnings;
+    warnings.sort_by_key(|warning| {
+        let start = warning.span().start;
+        (start.line, start.column)
+    });
+    warnings
+}
+
+/// Collects the definitions paths name, except in `use` items and in a
+/// function's own body
+struct Uses<'r> {
+    resolutions: &'r Resolutions,
+    used: HashSet<DefId>,
+    /// The functions being visited, innermost last
+    functions: Vec<Option<DefId>>,
+}
+
+impl<'ast> Visitor<'ast> for Uses<'_> {
+    fn visit_use(&mut self, _use_item: &'ast Use) {}
+
+    fn visit_function(&mut self, func: &'ast Function) {
+        self.functions.push(self.resolutions.def_of(&func.name));
+        visit::walk_function(self, func);
+        self.functions.pop();
+    }
+
+    fn visit_identifier(&mut self, ident: &'ast Identifier) {
+        if let Some(Res::Def(id)) = self.resolutions.res(ident) {
+            if !self.functions.contains(&Some(*id)) {
+                self.used.insert(*id);
+            }
+        }
+    }
+}
+
+/// Collects the functions declared to return `!`
+struct NeverFunctions<'a>(&'a mut HashSet<DefId>, &'a Resolutions);
+
+impl<'ast> Visitor<'ast> for NeverFunctions<'_> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        let func = match &item.kind {
+            ItemKind::Function(func) | ItemKind::ExternFunction(func) => Some(func),
+            _ => None,
+        };
+        if let Some(func) = func.filter(|func| matches!(func.return_type, Some(Type::Never))) {
+            self.0.extend(self.1.def_of(&func.name));
+        }
+        visit::walk_item(self, item);
+    }
+}
+
+struct Linter<'r> {
+    resolutions: &'r Resolutions,
+    used: HashSet<DefId>,
+    never: HashSet<DefId>,
+    /// The lints turned off where the visitor is
+    allowed: Vec<Lint>,
+    module_depth: usize,
+    function_depth: usize,
+    warnings: Vec<Warning>,
+}
+
+impl Linter<'_> {
+    fn is_allowed(&self, lint: Lint) -> bool {
+        self.allowed.contains(&lint)
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if !self.is_allowed(warning.lint()) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Run `f` with the lints `attributes` allow turned off
+    fn with_attributes(&mut self, attributes: &[Attribute], f: impl FnOnce(&mut Self)) {
+        let saved = self.allowed.len();
+        self.allowed.extend(allowed_lints(attributes));
+        f(self);
+        self.allowed.truncate(saved);
+    }
+
+    fn is_used(&self, ident: &Identifier) -> bool {
+        self.resolutions
+            .def_of(ident)
+            .is_none_or(|id| self.used.contains(&id))
+    }
+
+    /// Warn about a binding that is never used
+    fn check_binding(&mut self, ident: &Identifier) {
+        if !ident.name.starts_with('_') && !self.is_used(ident) {
+            self.warn(Warning::UnusedVariable {
+                name: ident.name.clone(),
+                span: ident.span,
+            });
+        }
+    }
+
+    /// Warn about the imports of a private `use` item that no path
+    /// resolved through
+    fn check_use(&mut self, use_item: &Use) {
+        let path_str = |segments: &[Identifier], last: &str| {
+            let mut names: Vec<&str> = segments.iter().map(|segment| segment.name.as_str()).collect();
+            names.push(last);
+            names.join("::")
+        };
+        // The identifier each import binds, the one its resolution is
+        // recorded on, and how the warning names it
+        let imports: Vec<(&Identifier, &Identifier, String)> = match &use_item.path {
+            UsePath::Simple(path) => match path.split_last() {
+                Some((last, prefix)) => {
+                    let bound = use_item.alias.as_ref().unwrap_or(last);
+                    vec![(bound, last, path_str(prefix, &last.name))]
+                }
+                None => Vec::new(),
+            },
+            UsePath::Glob(prefix) => prefix
+                .last()
+                .map(|last| (last, last, path_str(prefix, "*")))
+                .into_iter()
+                .collect(),
+            UsePath::List(prefix, names) => names
+                .iter()
+                .filter_map(|name| match name.name.as_str() {
+                    // `use a::{self}` binds `a`
+                    "self" => prefix.last().map(|last| (last, name, last.name.clone())),
+                    _ => Some((name, name, name.name.clone())),
+                })
+                .collect(),
+        };
+        for (bound, resolved, path) in imports {
+            // Imports that failed to resolve were reported as errors
+            if self.resolutions.resolution(resolved).is_some() && !self.resolutions.is_import_used(bound) {
+                self.warn(Warning::UnusedImport { path, span: bound.span });
+            }
+        }
+    }
+
+    /// Warn about a private function nothing calls
+    fn check_function(&mut self, item: &Item, func: &Function) {
+        let is_entry = func.name.name == "main" && self.module_depth == 0 && self.function_depth == 0;
+        let is_exported = item
+            .attributes
+            .iter()
+            .any(|attribute| matches!(attribute.name.name.as_str(), "test" | "bench" | "no_mangle"));
+        if !is_entry && !is_exported && !self.is_used(&func.name) {
+            self.warn(Warning::UnusedFunction {
+                name: func.name.name.clone(),
+                span: func.name.span,
+            });
+        }
+    }
+
+    /// Warn about the first statement or expression of a block after one
+    /// that never finishes
+    fn check_reachable(&mut self, block: &Block) {
+        let mut cause = None;
+        for stmt in &block.statements {
+            if matches!(stmt.kind, StatementKind::Empty | StatementKind::Item(_)) {
+                continue;
+            }
+            if let Some(cause) = cause {
+                self.warn(Warning::Unreachable {
+                    what: "statement",
+                    span: stmt.span,
+                    cause,
+                });
+                return;
+            }
+            if self.statement_diverges(stmt) {
+                cause = Some(stmt.span);
+            }
+        }
+        if let (Some(cause), Some(expr)) = (cause, &block.trailing_expr) {
+            self.warn(Warning::Unreachable {
+                what: "expression",
+                span: expr.span,
+                cause,
+            });
+        }
+    }
+
+    fn statement_diverges(&self, stmt: &Statement) -> bool {
+        match &stmt.kind {
+            StatementKind::Local(local) => local.init.as_ref().is_some_and(|init| self.diverges(init)),
+            StatementKind::LetElse(let_else) => self.diverges(&let_else.init),
+            StatementKind::Expr(expr) => self.diverges(expr),
+            StatementKind::Item(_) | StatementKind::Defer(_) | StatementKind::Empty | StatementKind::Error => false,
+        }
+    }
+
+    fn block_diverges(&self, block: &Block) -> bool {
+        block.statements.iter().any(|stmt| self.statement_diverges(stmt))
+            || block.trailing_expr.as_ref().is_some_and(|expr| self.diverges(expr))
+    }
+
+    /// Whether evaluating `expr` never finishes, judging by its syntax and
+    /// the functions paths name
+    fn diverges(&self, expr: &Expression) -> bool {
+        match &expr.kind {
+            ExpressionKind::Return(_)
+            | ExpressionKind::Throw(_)
+            | ExpressionKind::Break(_)
+            | ExpressionKind::Continue(_) => true,
+            ExpressionKind::Call(callee, args) => {
+                self.is_never_callee(callee) || self.diverges(callee) || args.iter().any(|arg| self.diverges(arg))
+            }
+            ExpressionKind::MethodCall(receiver, _, args) => {
+                self.diverges(receiver) || args.iter().any(|arg| self.diverges(arg))
+            }
+            ExpressionKind::Block(block) => self.block_diverges(block),
+            ExpressionKind::If(condition, then_block, else_block) => {
+                self.diverges(condition)
+                    || else_block
+                        .as_ref()
+                        .is_some_and(|else_block| self.block_diverges(then_block) && self.block_diverges(else_block))
+            }
+            ExpressionKind::Match(scrutinee, arms) => {
+                self.diverges(scrutinee) || (!arms.is_empty() && arms.iter().all(|arm| self.diverges(&arm.body)))
+            }
+            ExpressionKind::Loop(body, label) => !breaks_out(body, label.as_ref()),
+            // The right side of `&&` and `||` may not run
+            ExpressionKind::Binary(BinaryOp::And | BinaryOp::Or, left, _) => self.diverges(left),
+            ExpressionKind::Binary(_, left, right)
+            | ExpressionKind::Index(left, right)
+            | ExpressionKind::Assign(left, right)
+            | ExpressionKind::AssignOp(_, left, right) => self.diverges(left) || self.diverges(right),
+            ExpressionKind::Unary(_, inner)
+            | ExpressionKind::FieldAccess(inner, _)
+            | ExpressionKind::TupleIndex(inner, _)
+            | ExpressionKind::Cast(inner, _)
+            | ExpressionKind::Grouped(inner) => self.diverges(inner),
+            ExpressionKind::Array(elements) | ExpressionKind::Tuple(elements) => {
+                elements.iter().any(|element| self.diverges(element))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a callee names `panic!`'s builtin or a function returning `!`
+    fn is_never_callee(&self, callee: &Expression) -> bool {
+        let ExpressionKind::Path(path) = &callee.kind else {
+            return false;
+        };
+        match path.last().and_then(|segment| self.resolutions.res(segment)) {
+            Some(Res::Intrinsic(name)) => name == "__zulon_builtin_panic",
+            Some(Res::Def(id)) => self.never.contains(id),
+            _ => false,
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for Linter<'_> {
+    fn visit_ast(&mut self, ast: &'ast Ast) {
+        self.with_attributes(&ast.attributes, |linter| visit::walk_ast(linter, ast));
+    }
+
+    fn visit_item(&mut self, item: &'ast Item) {
+        self.with_attributes(&item.attributes, |linter| {
+            if item.visibility == Visibility::Private {
+                match &item.kind {
+                    ItemKind::Use(use_item) if !use_item.is_pub => linter.check_use(use_item),
+                    ItemKind::Function(func) => linter.check_function(item, func),
+                    _ => {}
+                }
+            }
+            visit::walk_item(linter, item);
+        });
+    }
+
+    fn visit_module(&mut self, module: &'ast Module) {
+        self.module_depth += 1;
+        visit::walk_module(self, module);
+        self.module_depth -= 1;
+    }
+
+    fn visit_function(&mut self, func: &'ast Function) {
+        self.with_attributes(&func.attributes, |linter| {
+            linter.function_depth += 1;
+            visit::walk_function(linter, func);
+            linter.function_depth -= 1;
+        });
+    }
+
+    fn visit_block(&mut self, block: &'ast Block) {
+        self.check_reachable(block);
+        visit::walk_block(self, block);
+    }
+
+    fn visit_statement(&mut self, stmt: &'ast Statement) {
+        match &stmt.kind {
+            StatementKind::Local(local) => self.check_binding(&local.name),
+            StatementKind::LetElse(let_else) => {
+                for ident in let_else.pattern.bindings() {
+                    self.check_binding(ident);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_statement(self, stmt);
+    }
+}
+
+/// The lints `#[allow(...)]` attributes turn off
+fn allowed_lints(attributes: &[Attribute]) -> Vec<Lint> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.name.name == "allow")
+        .flat_map(|attribute| &attribute.args)
+        .filter_map(|arg| match arg {
+            AttributeArg::Ident(name) => Some(Lint::from_name(&name.name)),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Whether a `break` in `body` leaves the loop labeled `label` that
+/// `body` belongs to
+fn breaks_out(body: &Block, label: Option<&Identifier>) -> bool {
+    struct Breaks<'a> {
+        label: Option<&'a Identifier>,
+        /// Loops nested inside the one checked
+        depth: usize,
+        found: bool,
+    }
+
+    impl<'ast> Visitor<'ast> for Breaks<'_> {
+        fn visit_expression(&mut self, expr: &'ast Expression) {
+            match &expr.kind {
+                ExpressionKind::Break(None) if self.depth == 0 => self.found = true,
+                ExpressionKind::Break(Some(name)) if self.label.is_some_and(|label| label.name == name.name) => {
+                    self.found = true
+                }
+                ExpressionKind::Loop(..) | ExpressionKind::While(..) | ExpressionKind::For(..) => {
+                    self.depth += 1;
+                    visit::walk_expression(self, expr);
+                    self.depth -= 1;
+                }
+                // A `break` in a closure cannot leave the loop
+                ExpressionKind::Closure { .. } => {}
+                _ => visit::walk_expression(self, expr),
+            }
+        }
+
+        fn visit_item(&mut self, _item: &'ast Item) {}
+    }
+
+    let mut breaks = Breaks {
+        label,
+        depth: 0,
+        found: false,
+    };
+    breaks.visit_block(body);
+    breaks.found
+}
@@ -0,0 +1,82 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Finding the name that was probably meant
+
+/// Edit distance between two names, in characters
+///
+/// This is the optimal string alignment distance: insertions, deletions,
+/// substitutions and swapping two adjacent characters each cost one edit.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 0..a.len() {
+        current[0] = i + 1;
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            let mut distance = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                distance = distance.min(before_previous[j - 1] + 1);
+            }
+            current[j + 1] = distance;
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The candidate closest to `name`, if any is close enough to be a typo
+///
+/// A candidate that differs only in case always matches. Otherwise up to
+/// one edit per three characters is allowed; ties go to the first
+/// candidate.
+pub fn best_match<'a, T>(name: &str, candidates: impl IntoIterator<Item = (&'a str, T)>) -> Option<(&'a str, T)> {
+    let max_distance = name.chars().count().max(3) / 3;
+    let mut best: Option<(usize, &'a str, T)> = None;
+
+    for (candidate, value) in candidates {
+        if candidate == name || candidate.starts_with("__") {
+            continue;
+        }
+        let distance = if candidate.eq_ignore_ascii_case(name) {
+            0
+        } else {
+            edit_distance(name, candidate)
+        };
+        if distance <= max_distance && best.as_ref().is_none_or(|(best, ..)| distance < *best) {
+            best = Some((distance, candidate, value));
+        }
+    }
+    best.map(|(_, candidate, value)| (candidate, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("conut", "count"), 1);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn test_best_match() {
+        let candidates = [("counter", 1), ("total", 2), ("Counter", 3)];
+        assert_eq!(best_match("countr", candidates), Some(("counter", 1)));
+        assert_eq!(best_match("COUNTER", candidates), Some(("counter", 1)));
+        assert_eq!(best_match("xyz", candidates), None);
+        // A swapped pair of letters is a single edit
+        assert_eq!(best_match("valeu", [("value", ())]), Some(("value", ())));
+        // Short names allow a single edit
+        assert_eq!(best_match("ab", [("ac", ()), ("xy", ())]), Some(("ac", ())));
+    }
+}
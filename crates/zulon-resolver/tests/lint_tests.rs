@@ -0,0 +1,211 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Integration tests for the unused and unreachable code lints
+
+use zulon_parser::Parser;
+use zulon_resolver::{lint, resolve, Lint, Warning};
+
+/// Parse, resolve and lint a program that resolves without errors
+fn warnings(source: &str) -> Vec<Warning> {
+    let ast = Parser::from_source(source).parse().expect("test source should parse");
+    let (resolutions, errors) = resolve(&ast);
+    assert_eq!(errors, Vec::new());
+    lint(&ast, &resolutions)
+}
+
+fn messages(source: &str) -> Vec<String> {
+    warnings(source).iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_used_code_has_no_warnings() {
+    let source = r#"
+        mod shapes {
+            pub fn area(w: i32, h: i32) -> i32 { w * h }
+        }
+        use shapes::area;
+
+        fn main() -> i32 {
+            let w = 2;
+            let _ignored = 3;
+            area(w, 4)
+        }
+    "#;
+    assert_eq!(messages(source), Vec::<String>::new());
+}
+
+#[test]
+fn test_unused_variables() {
+    let source = r#"
+        enum Opt { Some(i32), Empty }
+
+        fn main() -> i32 {
+            let unused = 1;
+            let used = 2;
+            let Opt::Some(value) = Opt::Some(used) else { return Opt::Empty; };
+            0
+        }
+    "#;
+    assert_eq!(messages(source), vec!["unused variable: `unused`", "unused variable: `value`"]);
+}
+
+#[test]
+fn test_unused_imports() {
+    let source = r#"
+        mod math {
+            pub fn add(a: i32, b: i32) -> i32 { a + b }
+            pub fn sub(a: i32, b: i32) -> i32 { a - b }
+            pub const ZERO: i32 = 0;
+        }
+        mod other {
+            pub fn unused() {}
+        }
+        use math::{add, sub};
+        use math::ZERO as NOTHING;
+        use other::*;
+        pub use math::sub as minus;
+
+        fn main() -> i32 { add(1, 2) }
+    "#;
+    assert_eq!(
+        messages(source),
+        vec!["unused import: `sub`", "unused import: `math::ZERO`", "unused import: `other::*`"]
+    );
+}
+
+#[test]
+fn test_glob_and_nested_imports_are_used_through_paths() {
+    let source = r#"
+        mod outer {
+            pub mod inner {
+                pub fn f() -> i32 { 1 }
+            }
+        }
+        use outer::inner;
+        use outer::*;
+
+        fn main() -> i32 { inner::f() }
+    "#;
+    // `inner` comes from the first import, so the glob is unused
+    assert_eq!(messages(source), vec!["unused import: `outer::*`"]);
+}
+
+#[test]
+fn test_unused_private_functions() {
+    let source = r#"
+        fn main() {}
+        fn helper() {}
+        pub fn exported() {}
+        fn recursive(n: i32) -> i32 { recursive(n - 1) }
+        #[test]
+        fn test_something() {}
+        mod nested {
+            fn main() {}
+        }
+    "#;
+    let warnings = warnings(source);
+    let names: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        names,
+        vec![
+            "function `helper` is never used",
+            "function `recursive` is never used",
+            "function `main` is never used",
+        ]
+    );
+    assert!(warnings.iter().all(|warning| warning.lint() == Lint::DeadCode));
+}
+
+#[test]
+fn test_unreachable_code() {
+    let source = r#"
+        fn fail() -> ! { loop {} }
+
+        fn after_return() -> i32 {
+            return 1;
+            let x = 2;
+            x
+        }
+
+        fn after_if() -> i32 {
+            if true { return 1; } else { return 2; }
+            3
+        }
+
+        fn after_panic() {
+            let _message = ::__zulon_builtin_panic("no");
+            fail();
+        }
+
+        fn after_call() -> i32 {
+            fail();
+            4
+        }
+
+        fn reachable(flag: bool) -> i32 {
+            if flag { return 1; }
+            loop { break; }
+            5
+        }
+
+        fn main() -> i32 { after_return() + after_if() + after_call() + reachable(true) }
+    "#;
+    let warnings = warnings(source);
+    let messages: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "unreachable statement",
+            "unreachable expression",
+            "function `after_panic` is never used",
+            "unreachable statement",
+            "unreachable expression",
+        ]
+    );
+    let Warning::Unreachable { span, cause, .. } = &warnings[0] else {
+        panic!("expected an unreachable statement");
+    };
+    assert_eq!((cause.start.line, span.start.line), (5, 6));
+}
+
+#[test]
+fn test_allow_attributes() {
+    let source = r#"
+        mod util {
+            pub fn f() {}
+        }
+        #[allow(unused_imports)]
+        use util::f;
+
+        #[allow(dead_code)]
+        fn helper() {}
+
+        #[allow(unused_variables, unreachable_code)]
+        fn main() {
+            let x = 1;
+            return;
+            let y = 2;
+        }
+
+        #[allow(unused)]
+        fn everything() {
+            let z = 3;
+        }
+    "#;
+    assert_eq!(messages(source), Vec::<String>::new());
+
+    let source = "#![allow(unused)]\nfn helper() { let x = 1; }\nfn main() {}";
+    assert_eq!(messages(source), Vec::<String>::new());
+}
+
+#[test]
+fn test_warning_diagnostic() {
+    let source = "fn main() {\n    let count = 1;\n}\n";
+    let warnings = warnings(source);
+    let diagnostic = warnings[0].to_diagnostic(source);
+    assert_eq!(diagnostic.severity, zulon_diagnostic::Severity::Warning);
+    assert_eq!(diagnostic.message, "unused variable: `count`");
+    assert_eq!(diagnostic.suggestions[0].apply(source), "fn main() {\n    let _count = 1;\n}\n");
+    assert_eq!(diagnostic.notes, vec!["`#[warn(unused_variables)]` on by default"]);
+}
@@ -0,0 +1,623 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Integration tests for name resolution
+//!
+//! These tests parse whole programs and check what their names resolve to,
+//! and which errors come out for names that cannot be resolved.
+
+use zulon_parser::ast::{ExpressionKind, ItemKind, StatementKind};
+use zulon_parser::Parser;
+use zulon_resolver::{
+    flatten_modules, resolve, resolve_with_externs, resolve_with_prelude, DefKind, Res, ResolveError, Resolutions,
+};
+
+/// Helper function to parse and resolve a source string
+fn resolve_source(source: &str) -> (Resolutions, Vec<ResolveError>) {
+    let mut parser = Parser::from_source(source);
+    let ast = parser.parse().expect("test source should parse");
+    resolve(&ast)
+}
+
+fn errors(source: &str) -> Vec<ResolveError> {
+    resolve_source(source).1
+}
+
+fn messages(source: &str) -> Vec<String> {
+    errors(source).iter().map(ToString::to_string).collect()
+}
+
+// ========== Successful Resolution ==========
+
+#[test]
+fn test_items_used_before_definition() {
+    let source = r#"
+        fn main() -> i32 {
+            let p: Point = make();
+            helper(p.x) + LIMIT
+        }
+
+        fn helper(x: i32) -> i32 { x }
+
+        fn make() -> Point { make() }
+
+        struct Point { x: i32, y: i32 }
+
+        const LIMIT: i32 = 10;
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_shadowing_and_scopes() {
+    let source = r#"
+        fn main() -> i32 {
+            let x = 1;
+            let x = x + 1;
+            {
+                let y = x;
+            }
+            if let Some(z) = maybe() { z } else { x }
+        }
+
+        enum Option { Some(i32), None }
+        use Option::Some;
+
+        fn maybe() -> Option { Option::None }
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_nested_modules_and_paths() {
+    let source = r#"
+        mod geometry {
+            pub struct Point { x: i32, y: i32 }
+
+            pub mod shapes {
+                use super::Point;
+
+                pub fn origin() -> Point {
+                    origin()
+                }
+            }
+        }
+
+        use geometry::shapes;
+
+        fn main() {
+            let p = shapes::origin();
+            let q: geometry::Point = crate::geometry::shapes::origin();
+        }
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_use_lists_globs_aliases_and_reexports() {
+    let source = r#"
+        mod outer {
+            pub use self::inner::deep;
+
+            pub mod inner {
+                pub fn deep() -> i32 { 1 }
+                pub(super) fn for_outer() -> i32 { 2 }
+                pub(in crate::outer) fn also_for_outer() -> i32 { 3 }
+                pub(crate) fn anywhere() -> i32 { 4 }
+            }
+
+            fn uses_restricted() -> i32 {
+                inner::for_outer() + inner::also_for_outer()
+            }
+        }
+
+        mod math {
+            pub fn add(a: i32, b: i32) -> i32 { a + b }
+            pub fn sub(a: i32, b: i32) -> i32 { a - b }
+            pub const ONE: i32 = 1;
+        }
+
+        use math::{add, sub};
+        use math::ONE as UNIT;
+        use outer::inner::*;
+
+        fn main() -> i32 {
+            add(sub(UNIT, 1), outer::deep() + anywhere())
+        }
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_impl_functions_and_self() {
+    let source = r#"
+        struct Counter { count: i32 }
+
+        impl Counter {
+            fn new() -> Self {
+                Self::with_count(0)
+            }
+
+            fn with_count(count: i32) -> Counter {
+                Counter::with_count(count)
+            }
+        }
+
+        fn main() {
+            let c = Counter::new();
+        }
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_generics_and_match_bindings() {
+    let source = r#"
+        enum Shape { Circle(f64), Square(f64), Empty }
+
+        fn identity<T>(value: T) -> T { value }
+
+        fn area(shape: Shape) -> f64 {
+            match shape {
+                Shape::Circle(r) => r * r * 3.14,
+                Shape::Square(side) => identity(side * side),
+                Shape::Empty => 0.0,
+            }
+        }
+    "#;
+
+    assert_eq!(errors(source), Vec::new());
+}
+
+#[test]
+fn test_definitions_have_kinds() {
+    let source = r#"
+        mod util {
+            pub fn helper() {}
+        }
+
+        fn main() {
+            let value = 1;
+        }
+    "#;
+
+    let (resolutions, errors) = resolve_source(source);
+    assert_eq!(errors, Vec::new());
+
+    let kinds: Vec<(String, DefKind)> = resolutions
+        .definitions()
+        .map(|(id, definition)| (resolutions.path_str(id), definition.kind))
+        .collect();
+    assert!(kinds.contains(&("util".to_string(), DefKind::Module)));
+    assert!(kinds.contains(&("util::helper".to_string(), DefKind::Function)));
+    assert!(kinds.contains(&("main::value".to_string(), DefKind::Local)));
+}
+
+#[test]
+fn test_compiler_externs_resolve_unless_shadowed() {
+    let source = r#"
+        fn main() {
+            printf("%d\n", 1);
+            let printf = 2;
+            printf;
+        }
+    "#;
+    let ast = Parser::from_source(source).parse().unwrap();
+
+    let (resolutions, errors) = resolve_with_externs(&ast, ["printf"]);
+    assert_eq!(errors, Vec::new());
+
+    let ItemKind::Function(main) = &ast.items[0].kind else { unreachable!() };
+    let StatementKind::Expr(call) = &main.body.statements[0].kind else { unreachable!() };
+    let ExpressionKind::Call(callee, _) = &call.kind else { unreachable!() };
+    let ExpressionKind::Path(path) = &callee.kind else { unreachable!() };
+    assert_eq!(resolutions.res(&path[0]), Some(&Res::Intrinsic("printf".to_string())));
+
+    let (_, errors) = resolve(&ast);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0],
+        ResolveError::NotFound { name, .. } if name == "printf"
+    ));
+}
+
+// ========== Errors ==========
+
+#[test]
+fn test_undefined_variable_suggests_similar_name() {
+    let source = r#"
+        fn main() -> i32 {
+            let counter = 1;
+            countr
+        }
+    "#;
+
+    let errors = errors(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "cannot find value `countr` in this scope");
+    let similar = errors[0].similar().expect("a similar name");
+    assert_eq!(similar.name, "counter");
+    assert_eq!(similar.descr, "local variable");
+}
+
+#[test]
+fn test_undefined_type_and_function() {
+    let source = r#"
+        fn main() {
+            let p: Pointt = missing();
+        }
+
+        struct Point { x: i32 }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec![
+            "cannot find type `Pointt` in this scope".to_string(),
+            "cannot find function `missing` in this scope".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_binding_out_of_scope() {
+    let source = r#"
+        fn main() -> i32 {
+            {
+                let inner = 1;
+            }
+            inner
+        }
+    "#;
+
+    assert_eq!(messages(source), vec!["cannot find value `inner` in this scope".to_string()]);
+}
+
+#[test]
+fn test_duplicate_definitions() {
+    let source = r#"
+        fn helper() {}
+        fn helper() {}
+
+        struct Helper {}
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec!["the name `helper` is defined multiple times".to_string()]
+    );
+}
+
+#[test]
+fn test_duplicate_parameter() {
+    let source = r#"
+        fn add(x: i32, x: i32) -> i32 { x }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec!["identifier `x` is bound more than once in this parameter list".to_string()]
+    );
+}
+
+#[test]
+fn test_unresolved_import() {
+    let source = r#"
+        mod math {
+            pub fn sqrt(x: f64) -> f64 { x }
+        }
+
+        use math::sqr;
+    "#;
+
+    let errors = errors(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "unresolved import `math::sqr`");
+    assert_eq!(errors[0].similar().map(|similar| similar.name.as_str()), Some("sqrt"));
+}
+
+#[test]
+fn test_missing_associated_function() {
+    let source = r#"
+        struct Point { x: i32 }
+
+        impl Point {
+            fn origin() -> Point { Point::origin() }
+        }
+
+        fn main() {
+            let p = Point::orign();
+        }
+    "#;
+
+    let errors = errors(source);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        "no function or associated item named `orign` found for struct `Point`"
+    );
+    assert_eq!(errors[0].similar().map(|similar| similar.name.as_str()), Some("origin"));
+}
+
+#[test]
+fn test_nested_function_cannot_capture_locals() {
+    let source = r#"
+        fn outer() -> i32 {
+            let x = 1;
+            fn inner() -> i32 { x }
+            inner()
+        }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec!["can't capture dynamic environment in a fn item".to_string()]
+    );
+}
+
+#[test]
+fn test_or_pattern_must_bind_same_names() {
+    let source = r#"
+        enum Pair { Left(i32), Right(i32) }
+
+        fn get(pair: Pair) -> i32 {
+            match pair {
+                Pair::Left(a) | Pair::Right(b) => 0,
+            }
+        }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec![
+            "variable `a` is not bound in all patterns".to_string(),
+            "variable `b` is not bound in all patterns".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_struct_with_fields_is_not_a_value() {
+    let source = r#"
+        struct Point { x: i32 }
+
+        fn main() {
+            let p = Point;
+        }
+    "#;
+
+    assert_eq!(messages(source), vec!["expected value, found struct `Point`".to_string()]);
+}
+
+#[test]
+fn test_private_items_are_not_visible_outside_their_module() {
+    let source = r#"
+        mod bank {
+            fn secret() -> i32 { 42 }
+            pub fn balance() -> i32 { secret() }
+
+            pub mod vault {
+                pub(super) fn open() -> i32 { super::secret() }
+            }
+        }
+
+        use bank::secret;
+
+        fn main() -> i32 {
+            bank::secret() + bank::vault::open() + bank::balance()
+        }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec![
+            "function `secret` is private".to_string(),
+            "function `secret` is private".to_string(),
+            "function `open` is private".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_globs_skip_private_items() {
+    let source = r#"
+        mod config {
+            pub fn load() -> i32 { 1 }
+            fn parse() -> i32 { 2 }
+        }
+
+        use config::*;
+
+        fn main() -> i32 {
+            load() + parse()
+        }
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec!["cannot find function `parse` in this scope".to_string()]
+    );
+}
+
+#[test]
+fn test_visibility_must_name_an_ancestor() {
+    let source = r#"
+        mod a {
+            pub(in crate::b) fn f() {}
+        }
+
+        mod b {}
+    "#;
+
+    assert_eq!(
+        messages(source),
+        vec!["visibilities can only be restricted to ancestor modules".to_string()]
+    );
+}
+
+// ========== Prelude ==========
+
+const PRELUDE: &str = r#"
+    pub enum Option { Some(i32), None }
+    pub use Option::{Some, None};
+    pub trait Clone {}
+    fn hidden() {}
+"#;
+
+/// Parse a program and a prelude with lines after the program's
+fn parse_with_prelude(source: &str) -> (zulon_parser::ast::Ast, zulon_parser::ast::Ast) {
+    let ast = Parser::from_source(source).parse().expect("test source should parse");
+    let padding = "\n".repeat(source.lines().count());
+    let prelude = Parser::from_source(&format!("{}{}", padding, PRELUDE)).parse().expect("prelude should parse");
+    (ast, prelude)
+}
+
+#[test]
+fn test_prelude_names_are_in_every_module() {
+    let source = r#"
+        fn copy<T: Clone>(value: T) -> T { value }
+
+        mod inner {
+            use Option::None;
+
+            pub fn get() -> Option { if true { Some(1) } else { None } }
+        }
+
+        fn main() -> Option { inner::get() }
+    "#;
+    let (ast, prelude) = parse_with_prelude(source);
+    let (resolutions, errors) = resolve_with_prelude(&ast, &prelude);
+    assert_eq!(errors, Vec::new());
+
+    let option = resolutions
+        .definitions()
+        .find(|(_, definition)| definition.name == "Option")
+        .map(|(id, _)| id)
+        .unwrap();
+    assert_eq!(resolutions.definition(option).parent, resolutions.prelude());
+    assert_eq!(resolutions.path_str(option), "prelude::Option");
+}
+
+#[test]
+fn test_crate_items_shadow_the_prelude() {
+    let source = r#"
+        enum Option { Some(i32), Nothing }
+
+        fn main() -> Option { Option::Nothing }
+    "#;
+    let (ast, prelude) = parse_with_prelude(source);
+    let (_, errors) = resolve_with_prelude(&ast, &prelude);
+    assert_eq!(errors, Vec::new());
+}
+
+#[test]
+fn test_private_prelude_items_and_prelude_paths_are_not_visible() {
+    let source = r#"
+        fn main() {
+            hidden();
+            prelude::Option::None;
+        }
+    "#;
+    let (ast, prelude) = parse_with_prelude(source);
+    let (_, errors) = resolve_with_prelude(&ast, &prelude);
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "cannot find function `hidden` in this scope".to_string(),
+            "failed to resolve: use of undeclared type or module `prelude`".to_string(),
+        ]
+    );
+}
+
+// ========== Flattening ==========
+
+#[test]
+fn test_flatten_modules_renames_items_and_paths() {
+    let source = r#"
+        mod geometry {
+            pub struct Point { x: i32, y: i32 }
+
+            pub fn area(p: Point) -> i32 { helper(p) }
+
+            fn helper(p: Point) -> i32 { 0 }
+        }
+
+        use geometry::area;
+
+        fn main() -> i32 {
+            area(make()) + geometry::area(make())
+        }
+
+        fn make() -> geometry::Point { make() }
+    "#;
+
+    let mut ast = Parser::from_source(source).parse().expect("test source should parse");
+    let (resolutions, errors) = resolve(&ast);
+    assert_eq!(errors, Vec::new());
+    flatten_modules(&mut ast, &resolutions);
+
+    let names: Vec<String> = ast
+        .items
+        .iter()
+        .map(|item| match &item.kind {
+            ItemKind::Struct(struct_def) => struct_def.name.name.clone(),
+            ItemKind::Function(func) => func.name.name.clone(),
+            other => panic!("unexpected item {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec!["geometry__Point", "geometry__area", "geometry__helper", "main", "make"]
+    );
+
+    // The flattened program resolves on its own
+    let (_, errors) = resolve(&ast);
+    assert_eq!(errors, Vec::new());
+}
+
+#[test]
+fn test_flatten_prelude_renames_items_the_root_shadows() {
+    let source = r#"
+        enum Option { Some(i32), Nothing }
+
+        mod inner {
+            pub fn get() -> Option { None }
+        }
+
+        fn main() -> Option { Option::Nothing }
+    "#;
+    let (mut ast, prelude) = parse_with_prelude(source);
+    let (resolutions, errors) = resolve_with_prelude(&ast, &prelude);
+    assert_eq!(errors, Vec::new());
+    ast.items.extend(prelude.items);
+    flatten_modules(&mut ast, &resolutions);
+
+    let enums: Vec<&str> = ast
+        .items
+        .iter()
+        .filter_map(|item| match &item.kind {
+            ItemKind::Enum(enum_def) => Some(enum_def.name.name.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(enums, vec!["Option", "prelude__Option"]);
+
+    // `None` in `inner` is the prelude's, spelled out with its enum
+    let get = ast
+        .items
+        .iter()
+        .find_map(|item| match &item.kind {
+            ItemKind::Function(func) if func.name.name == "inner__get" => Some(func),
+            _ => None,
+        })
+        .unwrap();
+    let ExpressionKind::Path(path) = &get.body.trailing_expr.as_ref().unwrap().kind else { unreachable!() };
+    let names: Vec<&str> = path.iter().map(|segment| segment.name.as_str()).collect();
+    assert_eq!(names, vec!["prelude__Option", "None"]);
+}
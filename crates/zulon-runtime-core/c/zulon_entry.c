@@ -6,6 +6,10 @@
 // This file provides the C runtime entry point for ZULON programs
 // and basic I/O operations.
 
+#include <math.h>
+#include <stdbool.h>
+#include <stdint.h>
+
 #ifndef _WIN32
 #include <stdlib.h>
 #include <stdio.h>
@@ -275,6 +279,400 @@ int zulon_strcmp(const char* str1, const char* str2) {
     return strcmp(str1, str2);
 }
 
+// ============================================================================
+// Formatting
+// ============================================================================
+
+// Template string interpolations and format! placeholders are lowered to
+// calls to these helpers. Each one takes the value followed by the spec
+// encoded as (fill, align, flags, width, precision, kind), where width and
+// precision are -1 when absent. Results are newly allocated strings.
+
+#define ZULON_ALIGN_NONE 0
+#define ZULON_ALIGN_LEFT 1
+#define ZULON_ALIGN_CENTER 2
+#define ZULON_ALIGN_RIGHT 3
+
+#define ZULON_FLAG_PLUS 1
+#define ZULON_FLAG_MINUS 2
+#define ZULON_FLAG_ALTERNATE 4
+#define ZULON_FLAG_ZERO 8
+
+#define ZULON_KIND_DISPLAY 0
+#define ZULON_KIND_DEBUG 1
+#define ZULON_KIND_LOWER_HEX 2
+#define ZULON_KIND_UPPER_HEX 3
+#define ZULON_KIND_BINARY 4
+#define ZULON_KIND_OCTAL 5
+#define ZULON_KIND_LOWER_EXP 6
+#define ZULON_KIND_UPPER_EXP 7
+
+// Concatenate two strings into a newly allocated string
+char* string_concat(const char* str1, const char* str2) {
+    if (str1 == NULL) {
+        str1 = "";
+    }
+    if (str2 == NULL) {
+        str2 = "";
+    }
+
+    size_t len1 = strlen(str1);
+    size_t len2 = strlen(str2);
+    char* result = malloc(len1 + len2 + 1);
+    if (result == NULL) {
+        return NULL;
+    }
+    memcpy(result, str1, len1);
+    memcpy(result + len1, str2, len2 + 1);
+    return result;
+}
+
+// Count the UTF-8 characters in the first len bytes of str
+static size_t zulon_utf8_count(const char* str, size_t len) {
+    size_t count = 0;
+    for (size_t i = 0; i < len; i++) {
+        if (((unsigned char)str[i] & 0xC0) != 0x80) {
+            count++;
+        }
+    }
+    return count;
+}
+
+// Byte length of the first chars UTF-8 characters of str
+static size_t zulon_utf8_prefix(const char* str, size_t chars) {
+    size_t i = 0;
+    while (str[i] != '\0') {
+        if (((unsigned char)str[i] & 0xC0) != 0x80) {
+            if (chars == 0) {
+                break;
+            }
+            chars--;
+        }
+        i++;
+    }
+    return i;
+}
+
+// Encode a character as UTF-8
+// Returns: number of bytes written (at most 4)
+static int zulon_utf8_encode(uint32_t c, char* out) {
+    if (c < 0x80) {
+        out[0] = (char)c;
+        return 1;
+    }
+    if (c < 0x800) {
+        out[0] = (char)(0xC0 | (c >> 6));
+        out[1] = (char)(0x80 | (c & 0x3F));
+        return 2;
+    }
+    if (c < 0x10000) {
+        out[0] = (char)(0xE0 | (c >> 12));
+        out[1] = (char)(0x80 | ((c >> 6) & 0x3F));
+        out[2] = (char)(0x80 | (c & 0x3F));
+        return 3;
+    }
+    out[0] = (char)(0xF0 | (c >> 18));
+    out[1] = (char)(0x80 | ((c >> 12) & 0x3F));
+    out[2] = (char)(0x80 | ((c >> 6) & 0x3F));
+    out[3] = (char)(0x80 | (c & 0x3F));
+    return 4;
+}
+
+// Pad prefix + body to width characters
+// With the zero flag, zeros go between the prefix (sign, 0x) and the body
+// and the fill and alignment are ignored
+static char* zulon_pad(const char* prefix, const char* body, size_t body_len,
+                       int32_t fill, int32_t align, int32_t flags, int32_t width,
+                       int32_t default_align) {
+    size_t prefix_len = strlen(prefix);
+    size_t chars = zulon_utf8_count(prefix, prefix_len) + zulon_utf8_count(body, body_len);
+    size_t padding = (width > 0 && (size_t)width > chars) ? (size_t)width - chars : 0;
+
+    char fill_bytes[4];
+    int fill_len = zulon_utf8_encode((uint32_t)fill, fill_bytes);
+    size_t before = 0;
+    size_t after = 0;
+    size_t zeros = 0;
+
+    if (flags & ZULON_FLAG_ZERO) {
+        zeros = padding;
+    } else {
+        switch (align == ZULON_ALIGN_NONE ? default_align : align) {
+            case ZULON_ALIGN_LEFT:
+                after = padding;
+                break;
+            case ZULON_ALIGN_CENTER:
+                before = padding / 2;
+                after = padding - before;
+                break;
+            default:
+                before = padding;
+                break;
+        }
+    }
+
+    char* result = malloc(prefix_len + zeros + body_len + (before + after) * fill_len + 1);
+    if (result == NULL) {
+        return NULL;
+    }
+
+    char* out = result;
+    for (size_t i = 0; i < before; i++) {
+        memcpy(out, fill_bytes, fill_len);
+        out += fill_len;
+    }
+    memcpy(out, prefix, prefix_len);
+    out += prefix_len;
+    memset(out, '0', zeros);
+    out += zeros;
+    memcpy(out, body, body_len);
+    out += body_len;
+    for (size_t i = 0; i < after; i++) {
+        memcpy(out, fill_bytes, fill_len);
+        out += fill_len;
+    }
+    *out = '\0';
+    return result;
+}
+
+// Quote and escape the first len bytes of str for Debug output
+static char* zulon_escape_debug(const char* str, size_t len, char quote) {
+    char* result = malloc(len * 2 + 3);
+    if (result == NULL) {
+        return NULL;
+    }
+
+    char* out = result;
+    *out++ = quote;
+    for (size_t i = 0; i < len; i++) {
+        char c = str[i];
+        switch (c) {
+            case '\n': *out++ = '\\'; *out++ = 'n'; break;
+            case '\r': *out++ = '\\'; *out++ = 'r'; break;
+            case '\t': *out++ = '\\'; *out++ = 't'; break;
+            case '\\': *out++ = '\\'; *out++ = '\\'; break;
+            default:
+                if (c == quote) {
+                    *out++ = '\\';
+                }
+                *out++ = c;
+                break;
+        }
+    }
+    *out++ = quote;
+    *out = '\0';
+    return result;
+}
+
+// Format a double with printf, into a newly allocated string
+static char* zulon_printf_double(const char* format, int32_t digits, double value) {
+    int len = snprintf(NULL, 0, format, digits, value);
+    char* result = malloc(len + 1);
+    if (result != NULL) {
+        snprintf(result, len + 1, format, digits, value);
+    }
+    return result;
+}
+
+// The fewest digits after the point that still read back as value
+static int32_t zulon_shortest_digits(const char* format, double value, int32_t max_digits) {
+    for (int32_t digits = 0; digits < max_digits; digits++) {
+        char* text = zulon_printf_double(format, digits, value);
+        int exact = text != NULL && strtod(text, NULL) == value;
+        free(text);
+        if (exact) {
+            return digits;
+        }
+    }
+    return max_digits;
+}
+
+// Format a float in scientific notation (1.5e3, like Rust rather than C's 1.5e+03)
+static char* zulon_float_exp(double magnitude, int32_t precision, int upper) {
+    int32_t digits = precision >= 0 ? precision : zulon_shortest_digits("%.*e", magnitude, 17);
+    char* text = zulon_printf_double("%.*e", digits, magnitude);
+    if (text == NULL) {
+        return NULL;
+    }
+
+    char* e = strchr(text, 'e');
+    int exponent = atoi(e + 1);
+    sprintf(e, "%c%d", upper ? 'E' : 'e', exponent);
+    return text;
+}
+
+// Format a number from its sign, magnitude and two's complement bits
+static char* zulon_format_integer(int negative, uint64_t magnitude, uint64_t bits,
+                                  int32_t fill, int32_t align, int32_t flags,
+                                  int32_t width, int32_t precision, int32_t kind) {
+    const char* sign = negative ? "-" : ((flags & ZULON_FLAG_PLUS) ? "+" : "");
+    const char* radix_prefix = "";
+    const char* digit_chars = "0123456789abcdef";
+    uint64_t value = magnitude;
+    unsigned base = 10;
+
+    switch (kind) {
+        case ZULON_KIND_LOWER_EXP:
+        case ZULON_KIND_UPPER_EXP: {
+            char* body = zulon_float_exp((double)magnitude, precision, kind == ZULON_KIND_UPPER_EXP);
+            if (body == NULL) {
+                return NULL;
+            }
+            char* result = zulon_pad(sign, body, strlen(body), fill, align, flags, width, ZULON_ALIGN_RIGHT);
+            free(body);
+            return result;
+        }
+        case ZULON_KIND_UPPER_HEX:
+            digit_chars = "0123456789ABCDEF";
+            /* fall through */
+        case ZULON_KIND_LOWER_HEX:
+            base = 16;
+            radix_prefix = "0x";
+            break;
+        case ZULON_KIND_BINARY:
+            base = 2;
+            radix_prefix = "0b";
+            break;
+        case ZULON_KIND_OCTAL:
+            base = 8;
+            radix_prefix = "0o";
+            break;
+        default:
+            break;
+    }
+
+    // Other radixes print the bits, so negative numbers have no minus sign
+    if (base != 10) {
+        value = bits;
+        sign = (flags & ZULON_FLAG_PLUS) ? "+" : "";
+    }
+
+    char digits[65];
+    char* end = digits + sizeof(digits) - 1;
+    char* start = end;
+    *end = '\0';
+    do {
+        *--start = digit_chars[value % base];
+        value /= base;
+    } while (value != 0);
+
+    char prefix[4];
+    snprintf(prefix, sizeof(prefix), "%s%s", sign, (flags & ZULON_FLAG_ALTERNATE) ? radix_prefix : "");
+    return zulon_pad(prefix, start, (size_t)(end - start), fill, align, flags, width, ZULON_ALIGN_RIGHT);
+}
+
+char* zulon_format_i32(int32_t value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    int64_t wide = value;
+    uint64_t magnitude = wide < 0 ? (uint64_t)(-wide) : (uint64_t)wide;
+    return zulon_format_integer(wide < 0, magnitude, (uint32_t)value,
+                                fill, align, flags, width, precision, kind);
+}
+
+char* zulon_format_i64(int64_t value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    uint64_t magnitude = value < 0 ? (uint64_t)0 - (uint64_t)value : (uint64_t)value;
+    return zulon_format_integer(value < 0, magnitude, (uint64_t)value,
+                                fill, align, flags, width, precision, kind);
+}
+
+char* zulon_format_u32(uint32_t value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    return zulon_format_integer(0, value, value, fill, align, flags, width, precision, kind);
+}
+
+char* zulon_format_u64(uint64_t value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    return zulon_format_integer(0, value, value, fill, align, flags, width, precision, kind);
+}
+
+char* zulon_format_f64(double value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    const char* sign = signbit(value) ? "-" : ((flags & ZULON_FLAG_PLUS) ? "+" : "");
+    double magnitude = signbit(value) ? -value : value;
+    char* body;
+
+    if (isnan(value)) {
+        sign = "";
+        flags &= ~ZULON_FLAG_ZERO;
+        body = malloc(4);
+        if (body != NULL) {
+            strcpy(body, "NaN");
+        }
+    } else if (isinf(value)) {
+        flags &= ~ZULON_FLAG_ZERO;
+        body = malloc(4);
+        if (body != NULL) {
+            strcpy(body, "inf");
+        }
+    } else if (kind == ZULON_KIND_LOWER_EXP || kind == ZULON_KIND_UPPER_EXP) {
+        body = zulon_float_exp(magnitude, precision, kind == ZULON_KIND_UPPER_EXP);
+    } else {
+        // Without a precision, print the shortest digits that read back exactly
+        int32_t digits = precision >= 0 ? precision : zulon_shortest_digits("%.*f", magnitude, 1100);
+        body = zulon_printf_double("%.*f", digits, magnitude);
+
+        // Debug always shows the fractional part: 1.0 rather than 1
+        if (body != NULL && kind == ZULON_KIND_DEBUG && precision < 0 && strchr(body, '.') == NULL) {
+            char* with_point = string_concat(body, ".0");
+            free(body);
+            body = with_point;
+        }
+    }
+
+    if (body == NULL) {
+        return NULL;
+    }
+    char* result = zulon_pad(sign, body, strlen(body), fill, align, flags, width, ZULON_ALIGN_RIGHT);
+    free(body);
+    return result;
+}
+
+char* zulon_format_bool(bool value, int32_t fill, int32_t align, int32_t flags,
+                        int32_t width, int32_t precision, int32_t kind) {
+    (void)kind;
+    const char* text = value ? "true" : "false";
+    size_t len = precision >= 0 ? zulon_utf8_prefix(text, (size_t)precision) : strlen(text);
+    return zulon_pad("", text, len, fill, align, flags & ~ZULON_FLAG_ZERO, width, ZULON_ALIGN_LEFT);
+}
+
+char* zulon_format_char(uint32_t value, int32_t fill, int32_t align, int32_t flags,
+                        int32_t width, int32_t precision, int32_t kind) {
+    (void)precision;
+    char bytes[4];
+    int len = zulon_utf8_encode(value, bytes);
+
+    if (kind == ZULON_KIND_DEBUG) {
+        char* quoted = zulon_escape_debug(bytes, (size_t)len, '\'');
+        if (quoted == NULL) {
+            return NULL;
+        }
+        char* result = zulon_pad("", quoted, strlen(quoted), fill, align, flags & ~ZULON_FLAG_ZERO, width, ZULON_ALIGN_LEFT);
+        free(quoted);
+        return result;
+    }
+    return zulon_pad("", bytes, (size_t)len, fill, align, flags & ~ZULON_FLAG_ZERO, width, ZULON_ALIGN_LEFT);
+}
+
+char* zulon_format_str(const char* value, int32_t fill, int32_t align, int32_t flags,
+                       int32_t width, int32_t precision, int32_t kind) {
+    if (value == NULL) {
+        value = "";
+    }
+    size_t len = precision >= 0 ? zulon_utf8_prefix(value, (size_t)precision) : strlen(value);
+
+    if (kind == ZULON_KIND_DEBUG) {
+        char* quoted = zulon_escape_debug(value, len, '"');
+        if (quoted == NULL) {
+            return NULL;
+        }
+        char* result = zulon_pad("", quoted, strlen(quoted), fill, align, flags & ~ZULON_FLAG_ZERO, width, ZULON_ALIGN_LEFT);
+        free(quoted);
+        return result;
+    }
+    return zulon_pad("", value, len, fill, align, flags & ~ZULON_FLAG_ZERO, width, ZULON_ALIGN_LEFT);
+}
+
 // ============================================================================
 // Reference Counting (for Arc<T>)
 // ============================================================================
@@ -7,13 +7,17 @@
 //! Multiple `Arc` pointers can point to the same data, and the data
 //! is automatically freed when the last `Arc` is dropped.
 
+use std::alloc::{self, Layout};
 use std::sync::atomic;
 use std::sync::atomic::Ordering;
 use std::fmt;
+use std::ptr;
 
 /// Internal data structure for Arc
 ///
-/// Contains the reference counts and the actual data
+/// Contains the reference counts and the actual data. The layout is fixed
+/// so that an `ArcData` for an unsized value can be built by hand.
+#[repr(C)]
 pub(super) struct ArcData<T: ?Sized> {
     /// Number of strong references (Arc pointers)
     pub(super) strong: atomic::AtomicUsize,
@@ -268,6 +272,59 @@ impl<T: ?Sized> Clone for Arc<T> {
     }
 }
 
+impl<T: ?Sized> From<Box<T>> for Arc<T> {
+    /// Move a boxed value into a new Arc
+    ///
+    /// The value may be unsized, which is how `Arc<dyn Trait>` and
+    /// `Arc<[T]>` values are made.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use zulon_runtime_memory::Arc;
+    /// use std::fmt::Display;
+    ///
+    /// let arc: Arc<dyn Display> = Arc::from(Box::new(42) as Box<dyn Display>);
+    /// assert_eq!(arc.to_string(), "42");
+    /// ```
+    fn from(boxed: Box<T>) -> Self {
+        let value_layout = Layout::for_value(&*boxed);
+        let layout = Layout::new::<ArcData<()>>()
+            .extend(value_layout)
+            .expect("Arc allocation too large")
+            .0
+            .pad_to_align();
+
+        unsafe {
+            let mem = alloc::alloc(layout);
+            if mem.is_null() {
+                alloc::handle_alloc_error(layout);
+            }
+
+            // Move the box's pointer to the new allocation, so that it
+            // keeps the value's metadata (its vtable or length)
+            let value = Box::into_raw(boxed);
+            let inner = (value as *mut ArcData<T>)
+                .wrapping_byte_offset((mem as isize).wrapping_sub(value as *mut u8 as isize));
+
+            ptr::addr_of_mut!((*inner).strong).write(atomic::AtomicUsize::new(1));
+            ptr::addr_of_mut!((*inner).weak).write(atomic::AtomicUsize::new(1));
+            ptr::copy_nonoverlapping(
+                value as *const u8,
+                ptr::addr_of_mut!((*inner).data) as *mut u8,
+                value_layout.size(),
+            );
+
+            // Free the box without dropping the value, which was moved
+            if value_layout.size() != 0 {
+                alloc::dealloc(value as *mut u8, value_layout);
+            }
+
+            Arc { ptr: inner }
+        }
+    }
+}
+
 impl<T: ?Sized> Drop for Arc<T> {
     /// Drop the Arc, decrementing the strong reference count
     ///
@@ -425,6 +482,92 @@ mod tests {
         assert_eq!(*clone, vec![1, 2, 3]);
     }
 
+    trait Handler {
+        fn handle(&self, request: i32) -> i32;
+    }
+
+    struct Doubler;
+
+    struct Offset(i32);
+
+    impl Handler for Doubler {
+        fn handle(&self, request: i32) -> i32 {
+            request * 2
+        }
+    }
+
+    impl Handler for Offset {
+        fn handle(&self, request: i32) -> i32 {
+            request + self.0
+        }
+    }
+
+    #[test]
+    fn test_arc_from_boxed_trait_object() {
+        let handler: Arc<dyn Handler> = Arc::from(Box::new(Offset(5)) as Box<dyn Handler>);
+        assert_eq!(handler.handle(1), 6);
+
+        let clone = handler.clone();
+        assert_eq!(Arc::strong_count(&handler), 2);
+        assert_eq!(clone.handle(2), 7);
+    }
+
+    #[test]
+    fn test_arc_trait_object_collection() {
+        let handlers: Vec<Arc<dyn Handler>> = vec![
+            Arc::from(Box::new(Doubler) as Box<dyn Handler>),
+            Arc::from(Box::new(Offset(10)) as Box<dyn Handler>),
+        ];
+
+        let results: Vec<i32> = handlers.iter().map(|handler| handler.handle(4)).collect();
+        assert_eq!(results, vec![8, 14]);
+    }
+
+    #[test]
+    fn test_arc_trait_object_downgrade() {
+        let handler: Arc<dyn Handler> = Arc::from(Box::new(Offset(3)) as Box<dyn Handler>);
+        let weak = Arc::downgrade(&handler);
+        assert_eq!(weak.upgrade().map(|handler| handler.handle(1)), Some(4));
+
+        drop(handler);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_arc_from_box_drops_value_once() {
+        use std::sync::atomic::AtomicUsize;
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted(#[allow(dead_code)] String);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        impl Handler for Counted {
+            fn handle(&self, request: i32) -> i32 {
+                request
+            }
+        }
+
+        let handler: Arc<dyn Handler> = Arc::from(Box::new(Counted("counted".to_string())) as Box<dyn Handler>);
+        let clone = handler.clone();
+        drop(handler);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+
+        drop(clone);
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_arc_from_boxed_slice() {
+        let arc: Arc<[i32]> = Arc::from(vec![1, 2, 3].into_boxed_slice());
+        assert_eq!(&*arc, &[1, 2, 3]);
+    }
+
     #[test]
     fn test_arc_as_ptr() {
         let arc = Arc::new(42);
@@ -237,7 +237,7 @@ impl<T: ?Sized + fmt::Debug> fmt::Debug for Weak<T> {
     }
 }
 
-impl<T> Arc<T> {
+impl<T: ?Sized> Arc<T> {
     /// Create a weak reference to this Arc
     ///
     /// The weak reference doesn't prevent the data from being dropped,
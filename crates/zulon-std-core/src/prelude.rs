@@ -7,17 +7,21 @@
 //! automatically injected into every ZULON program by the compiler.
 
 // NOTE: This is a Rust module for documentation purposes only.
-// The actual prelude content is defined in compiler/src/compiler.rs
-// as a string literal that gets prepended to all user code.
+// The actual prelude is ZULON source, crates/zulon-compiler/src/prelude.zl,
+// built into the compiler.
 
 /// The ZULON prelude contains:
-/// - extern function declarations (printf, scanf, etc.)
-/// - Core type imports when module system is implemented
+/// - extern function declarations for the runtime (printf, scanf,
+///   string_concat, the async scheduler, etc.)
+/// - the core types `Option`, `Result` and `Ordering`, with the variants
+///   `Some`, `None`, `Ok` and `Err` in scope
+/// - the core traits `Clone`, `Copy`, `PartialEq`, `Eq`, `PartialOrd`,
+///   `Ord`, `Hash` and `Debug`
 ///
 /// Users don't need to manually declare `extern fn printf` - it's
-/// automatically included by the compiler.
+/// automatically included by the compiler. A crate whose root file starts
+/// with `#![no_prelude]` gets none of this.
 pub mod prelude_doc {
     //! This module documents what the compiler automatically injects.
-    //! The actual injection happens in compiler/src/compiler.rs.
+    //! The injection happens in crates/zulon-compiler/src/prelude.rs.
 }
-
@@ -0,0 +1,338 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Grammar-aware AST generator
+//!
+//! Builds well-formed [`Ast`] values from fuzzer input with [`arbitrary`].
+//! Every generated tree is syntactically valid: printing it with
+//! [`crate::print`] yields source the parser accepts and parses back to the
+//! same tree. Programs are not required to type check.
+//!
+//! Recursion is bounded by [`MAX_DEPTH`].
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::fmt;
+use zulon_parser::ast::*;
+use zulon_parser::{Position, Span};
+
+/// Maximum nesting depth of generated expressions and blocks
+pub const MAX_DEPTH: usize = 4;
+
+/// Variable and parameter names
+const NAMES: &[&str] = &["a", "b", "c", "x", "y", "value", "count", "total", "item", "名字"];
+
+/// Field and method names
+const MEMBERS: &[&str] = &["len", "get", "first", "next", "size"];
+
+/// Primitive type names
+const TYPES: &[&str] = &["i32", "i64", "u8", "f64", "bool", "char", "str"];
+
+const BINARY_OPS: &[BinaryOp] = &[
+    BinaryOp::Add,
+    BinaryOp::Sub,
+    BinaryOp::Mul,
+    BinaryOp::Div,
+    BinaryOp::Mod,
+    BinaryOp::Eq,
+    BinaryOp::NotEq,
+    BinaryOp::Less,
+    BinaryOp::LessEq,
+    BinaryOp::Greater,
+    BinaryOp::GreaterEq,
+    BinaryOp::And,
+    BinaryOp::Or,
+];
+
+const ASSIGN_OPS: &[BinaryOp] = &[BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul, BinaryOp::Div, BinaryOp::Mod];
+
+/// A generated, well-formed AST
+///
+/// `Debug` prints the program source, which is what you want to see in a
+/// fuzzer crash report.
+pub struct GeneratedAst(pub Ast);
+
+impl<'a> Arbitrary<'a> for GeneratedAst {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        generate_ast(u).map(GeneratedAst)
+    }
+}
+
+impl fmt::Debug for GeneratedAst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&crate::print::print_ast(&self.0))
+    }
+}
+
+/// Generate a compilation unit
+pub fn generate_ast(u: &mut Unstructured<'_>) -> Result<Ast> {
+    let mut generator = Generator { u, depth: 0 };
+    let count = generator.u.int_in_range(1..=4)?;
+    let items = (0..count)
+        .map(|index| generator.item(index))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Ast::new(items))
+}
+
+fn span() -> Span {
+    Span::new(Position::new(1, 1), Position::new(1, 1))
+}
+
+fn ident(name: impl Into<String>) -> Identifier {
+    Identifier::new(span(), name)
+}
+
+fn expr(kind: ExpressionKind) -> Expression {
+    Expression { span: span(), kind }
+}
+
+struct Generator<'u, 'a> {
+    u: &'u mut Unstructured<'a>,
+    depth: usize,
+}
+
+impl Generator<'_, '_> {
+    fn name(&mut self) -> Result<Identifier> {
+        Ok(ident(*self.u.choose(NAMES)?))
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn at_max_depth(&self) -> bool {
+        self.depth >= MAX_DEPTH
+    }
+
+    fn item(&mut self, index: usize) -> Result<Item> {
+        let kind = match self.u.int_in_range(0..=5)? {
+            0 => ItemKind::Struct(Struct {
+                name: ident(format!("S{}", index)),
+                generics: None,
+                fields: (0..self.u.int_in_range(0..=3)?)
+                    .map(|i| {
+                        Ok(StructField {
+                            span: span(),
+                            attributes: Vec::new(),
+                            name: ident(format!("field{}", i)),
+                            type_annotation: self.ty()?,
+                            default_value: None,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            }),
+            1 => ItemKind::Enum(Enum {
+                name: ident(format!("E{}", index)),
+                generics: None,
+                variants: (0..self.u.int_in_range(1..=3)?)
+                    .map(|i| {
+                        let fields = (0..self.u.int_in_range(0..=2)?)
+                            .map(|_| Ok(VariantField::Unnamed(self.ty()?)))
+                            .collect::<Result<_>>()?;
+                        Ok(EnumVariant {
+                            span: span(),
+                            attributes: Vec::new(),
+                            name: ident(format!("V{}", i)),
+                            fields,
+                            discriminant: None,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            }),
+            2 => ItemKind::Const(Const {
+                name: ident(format!("C{}", index)),
+                type_annotation: self.ty()?,
+                value: self.expression()?,
+                is_mutable: false,
+            }),
+            _ => ItemKind::Function(self.function(format!("f{}", index))?),
+        };
+
+        Ok(Item {
+            span: span(),
+            attributes: Vec::new(),
+            visibility: Visibility::Private,
+            kind,
+        })
+    }
+
+    fn function(&mut self, name: String) -> Result<Function> {
+        let params = (0..self.u.int_in_range(0..=3)?)
+            .map(|_| {
+                Ok(Param {
+                    span: span(),
+                    name: self.name()?,
+                    type_annotation: Some(self.ty()?),
+                    default_value: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let return_type = if self.u.arbitrary()? { Some(self.ty()?) } else { None };
+
+        Ok(Function {
+            name: ident(name),
+            generics: None,
+            params,
+            return_type,
+            error_type: None,
+            effects: Vec::new(),
+            is_variadic: false,
+            body: self.block()?,
+            is_async: false,
+            is_unsafe: false,
+            is_const: false,
+            attributes: Vec::new(),
+        })
+    }
+
+    fn ty(&mut self) -> Result<Type> {
+        if self.at_max_depth() {
+            return Ok(Type::Simple(ident(*self.u.choose(TYPES)?)));
+        }
+
+        self.nested(|g| {
+            Ok(match g.u.int_in_range(0..=7)? {
+                0 => Type::Tuple((0..g.u.int_in_range(2..=3)?).map(|_| g.ty()).collect::<Result<_>>()?),
+                1 => Type::Slice(Box::new(g.ty()?)),
+                2 => Type::Ref(Box::new(g.ty()?), g.u.arbitrary()?),
+                _ => Type::Simple(ident(*g.u.choose(TYPES)?)),
+            })
+        })
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        self.nested(|g| {
+            let count = if g.at_max_depth() { 0 } else { g.u.int_in_range(0..=4)? };
+            let statements = (0..count).map(|_| g.statement()).collect::<Result<_>>()?;
+            let trailing_expr = if g.u.arbitrary()? {
+                Some(Box::new(g.expression()?))
+            } else {
+                None
+            };
+
+            Ok(Block {
+                span: span(),
+                statements,
+                trailing_expr,
+            })
+        })
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        let kind = match self.u.int_in_range(0..=4)? {
+            0 | 1 => StatementKind::Local(Local {
+                name: self.name()?,
+                type_annotation: if self.u.arbitrary()? { Some(self.ty()?) } else { None },
+                init: Some(Box::new(self.expression()?)),
+                is_mutable: self.u.arbitrary()?,
+            }),
+            2 => {
+                let target = expr(ExpressionKind::Path(vec![self.name()?]));
+                let value = Box::new(self.expression()?);
+                StatementKind::Expr(expr(if self.u.arbitrary()? {
+                    ExpressionKind::Assign(Box::new(target), value)
+                } else {
+                    ExpressionKind::AssignOp(*self.u.choose(ASSIGN_OPS)?, Box::new(target), value)
+                }))
+            }
+            3 if !self.at_max_depth() => StatementKind::Expr(expr(ExpressionKind::While(
+                Box::new(self.operand()?),
+                self.block()?,
+                None,
+            ))),
+            _ => StatementKind::Expr(self.expression()?),
+        };
+
+        Ok(Statement { span: span(), kind })
+    }
+
+    /// Any expression
+    fn expression(&mut self) -> Result<Expression> {
+        if self.at_max_depth() {
+            return self.atom();
+        }
+
+        self.nested(|g| {
+            Ok(match g.u.int_in_range(0..=9)? {
+                0 | 1 => expr(ExpressionKind::Binary(
+                    *g.u.choose(BINARY_OPS)?,
+                    Box::new(g.operand()?),
+                    Box::new(g.operand()?),
+                )),
+                2 => {
+                    let op = *g.u.choose(&[UnaryOp::Neg, UnaryOp::Not])?;
+                    expr(ExpressionKind::Unary(op, Box::new(g.operand()?)))
+                }
+                3 => expr(ExpressionKind::If(
+                    Box::new(g.operand()?),
+                    g.block()?,
+                    if g.u.arbitrary()? { Some(g.block()?) } else { None },
+                )),
+                4 => expr(ExpressionKind::Block(g.block()?)),
+                5 => expr(ExpressionKind::Array(g.arguments()?.into_iter().map(Box::new).collect())),
+                6 => expr(ExpressionKind::Tuple(
+                    (0..g.u.int_in_range(2..=3)?)
+                        .map(|_| Ok(Box::new(g.expression()?)))
+                        .collect::<Result<_>>()?,
+                )),
+                _ => g.postfix()?,
+            })
+        })
+    }
+
+    /// An operand of an operator or a condition, biased towards small expressions
+    fn operand(&mut self) -> Result<Expression> {
+        if self.at_max_depth() {
+            return self.atom();
+        }
+
+        match self.u.int_in_range(0..=2)? {
+            0 => self.atom(),
+            1 => self.nested(|g| g.postfix()),
+            _ => self.expression(),
+        }
+    }
+
+    /// A call, method call, field access or index on a path
+    fn postfix(&mut self) -> Result<Expression> {
+        let base = expr(ExpressionKind::Path(vec![self.name()?]));
+        Ok(expr(match self.u.int_in_range(0..=3)? {
+            0 => ExpressionKind::Call(Box::new(base), self.arguments()?.into_iter().map(Box::new).collect()),
+            1 => {
+                let method = ident(*self.u.choose(MEMBERS)?);
+                ExpressionKind::MethodCall(Box::new(base), method, self.arguments()?.into_iter().map(Box::new).collect())
+            }
+            2 => ExpressionKind::FieldAccess(Box::new(base), ident(*self.u.choose(MEMBERS)?)),
+            _ => ExpressionKind::Index(Box::new(base), Box::new(self.expression()?)),
+        }))
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expression>> {
+        (0..self.u.int_in_range(0..=3)?)
+            .map(|_| self.expression())
+            .collect()
+    }
+
+    /// A literal or a variable
+    fn atom(&mut self) -> Result<Expression> {
+        let literal = match self.u.int_in_range(0..=5)? {
+            0 => Literal::Int(u64::arbitrary(self.u)? as u128, None),
+            1 => Literal::Float(f64::from(self.u.int_in_range(0..=4096u32)?) / 8.0, None),
+            2 => Literal::Bool(self.u.arbitrary()?),
+            3 => Literal::String(self.text()?),
+            4 => Literal::Char(*self.u.choose(&['a', 'z', '0', ' ', '\n', '\'', '"', '\\', 'é'])?),
+            _ => return Ok(expr(ExpressionKind::Path(vec![self.name()?]))),
+        };
+        Ok(expr(ExpressionKind::Literal(literal)))
+    }
+
+    /// String literal contents, including characters that need escaping
+    fn text(&mut self) -> Result<String> {
+        let len = self.u.int_in_range(0..=8)?;
+        (0..len)
+            .map(|_| Ok(*self.u.choose(&['a', 'b', 'Z', '1', ' ', '\n', '\t', '"', '\\', 'ü', '中'])?))
+            .collect()
+    }
+}
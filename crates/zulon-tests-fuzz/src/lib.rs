@@ -3,8 +3,81 @@
 
 //! # zulon-tests-fuzz
 //!
-//! TODO: Add documentation
+//! Fuzzing support for the ZULON front end.
+//!
+//! The invariants live here as plain functions so that the libFuzzer targets
+//! in `fuzz/` and the regression tests in `tests/` check exactly the same
+//! thing:
+//!
+//! - [`check_lexer`]: lexing arbitrary input never panics
+//! - [`check_parser`]: parsing arbitrary input never panics, in strict and
+//!   recovering mode
+//! - [`check_typeck`]: type checking whatever the parser accepts never panics
+//! - [`check_round_trip`]: a generated AST prints to source that parses back
+//!   to the same AST
+//!
+//! Each function panics when its invariant is violated. To run the fuzzers
+//! (requires `cargo install cargo-fuzz` and a nightly toolchain):
+//!
+//! ```text
+//! cd crates/zulon-tests-fuzz
+//! cargo +nightly fuzz run parser
+//! ```
 
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 
+pub mod generate;
+pub mod print;
+
+use arbitrary::Unstructured;
+use zulon_parser::{Lexer, Parser};
+use zulon_typeck::TypeChecker;
+
+pub use generate::{generate_ast, GeneratedAst};
+pub use print::print_ast;
+
+/// Lex the input, if it is UTF-8
+pub fn check_lexer(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = Lexer::new(source).lex_all();
+    }
+}
+
+/// Parse the input, if it is UTF-8, with and without error recovery
+pub fn check_parser(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let _ = Parser::from_source(source).parse();
+        let _ = Parser::from_source(source).parse_with_recovery();
+    }
+}
+
+/// Type check the input, if it is UTF-8 and parses
+pub fn check_typeck(data: &[u8]) {
+    if let Ok(source) = std::str::from_utf8(data) {
+        if let Ok(ast) = Parser::from_source(source).parse() {
+            let _ = TypeChecker::new().check(&ast);
+        }
+    }
+}
+
+/// Generate an AST from the input and check that it survives a print/parse
+/// round trip
+///
+/// Also type checks the generated program, which must not panic either.
+pub fn check_round_trip(data: &[u8]) {
+    let mut u = Unstructured::new(data);
+    let Ok(ast) = generate_ast(&mut u) else {
+        return;
+    };
+
+    let source = print_ast(&ast);
+    let reparsed = match Parser::from_source(&source).parse() {
+        Ok(reparsed) => reparsed,
+        Err(error) => panic!("generated program does not parse: {}\n{}", error, source),
+    };
+    let reprinted = print_ast(&reparsed);
+    assert_eq!(source, reprinted, "round trip changed the program");
+
+    let _ = TypeChecker::new().check(&reparsed);
+}
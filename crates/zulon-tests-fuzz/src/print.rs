@@ -0,0 +1,1375 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! AST pretty-printer
+//!
+//! Prints an [`Ast`] back to ZULON source. Output is deterministic, so two
+//! trees that print the same are equal up to spans; the round-trip invariant
+//! compares printed forms for that reason.
+//!
+//! The parser does not keep parentheses, so the printer inserts them wherever
+//! operator precedence requires. Every node is printed: those the parser
+//! never produces (pointer and function types, `perform`, `extern crate`)
+//! use the syntax their documentation gives, and error nodes left by
+//! recovery print as `/* error */`.
+
+use zulon_parser::ast::*;
+
+/// Print a whole compilation unit
+pub fn print_ast(ast: &Ast) -> String {
+    let mut printer = Printer::default();
+    printer.inner_attributes(&ast.attributes);
+    if !ast.attributes.is_empty() && !ast.items.is_empty() {
+        printer.out.push('\n');
+    }
+    for (i, item) in ast.items.iter().enumerate() {
+        if i > 0 {
+            printer.out.push('\n');
+        }
+        printer.item(item);
+    }
+    printer.out
+}
+
+/// Print a single expression
+pub fn print_expression(expr: &Expression) -> String {
+    let mut printer = Printer::default();
+    printer.expression(expr);
+    printer.out
+}
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+    indent: usize,
+    /// Set in the condition of an `if` or `while`, a `match` scrutinee and a
+    /// `for` iterator, where a struct literal must be parenthesized
+    no_struct_literals: bool,
+}
+
+impl Printer {
+    fn line_start(&mut self) {
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+    }
+
+    /// Print outer attributes, one per line
+    fn attributes(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            self.line_start();
+            match attribute.doc_text() {
+                Some(text) if !text.contains('\n') => {
+                    self.out.push_str("///");
+                    self.out.push_str(text);
+                }
+                _ => {
+                    self.out.push_str("#[");
+                    self.attribute_body(attribute);
+                    self.out.push(']');
+                }
+            }
+            self.out.push('\n');
+        }
+    }
+
+    /// Print inner attributes, one per line
+    fn inner_attributes(&mut self, attributes: &[Attribute]) {
+        for attribute in attributes {
+            self.line_start();
+            match attribute.doc_text() {
+                Some(text) if !text.contains('\n') => {
+                    self.out.push_str("//!");
+                    self.out.push_str(text);
+                }
+                _ => {
+                    self.out.push_str("#![");
+                    self.attribute_body(attribute);
+                    self.out.push(']');
+                }
+            }
+            self.out.push('\n');
+        }
+    }
+
+    fn attribute_body(&mut self, attribute: &Attribute) {
+        self.out.push_str(&attribute.name.name);
+        match attribute.args.as_slice() {
+            [] => {}
+            [AttributeArg::String(value)] => {
+                self.out.push_str(" = ");
+                self.string(value);
+            }
+            args => self.attribute_args(args),
+        }
+    }
+
+    fn attribute_args(&mut self, args: &[AttributeArg]) {
+        self.out.push('(');
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            match arg {
+                AttributeArg::Ident(name) => self.out.push_str(&name.name),
+                AttributeArg::KeyValue { key, value } => {
+                    self.out.push_str(&key.name);
+                    self.out.push_str(" = ");
+                    self.string(value);
+                }
+                AttributeArg::String(value) => self.string(value),
+                AttributeArg::List(name, args) => {
+                    self.out.push_str(&name.name);
+                    self.attribute_args(args);
+                }
+            }
+        }
+        self.out.push(')');
+    }
+
+    fn visibility(&mut self, visibility: &Visibility) {
+        match visibility {
+            Visibility::Private => {}
+            Visibility::Public => self.out.push_str("pub "),
+            Visibility::Crate => self.out.push_str("pub(crate) "),
+            Visibility::Restricted(path) => {
+                self.out.push_str("pub(");
+                if !matches!(path.as_slice(), [name] if name.name == "self" || name.name == "super") {
+                    self.out.push_str("in ");
+                }
+                self.path(path);
+                self.out.push_str(") ");
+            }
+        }
+    }
+
+    fn item(&mut self, item: &Item) {
+        self.attributes(&item.attributes);
+        self.line_start();
+        self.visibility(&item.visibility);
+        match &item.kind {
+            ItemKind::Function(func) => self.function(func),
+            ItemKind::ExternFunction(func) => {
+                self.out.push_str("extern ");
+                self.signature(func);
+                self.out.push(';');
+            }
+            ItemKind::Struct(struct_def) => {
+                self.out.push_str("struct ");
+                self.out.push_str(&struct_def.name.name);
+                self.generics(struct_def.generics.as_ref());
+                self.where_clause(struct_def.generics.as_ref());
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for field in &struct_def.fields {
+                    self.attributes(&field.attributes);
+                    self.line_start();
+                    self.out.push_str(&field.name.name);
+                    self.out.push_str(": ");
+                    self.ty(&field.type_annotation);
+                    if let Some(default) = &field.default_value {
+                        self.out.push_str(" = ");
+                        self.expression(default);
+                    }
+                    self.out.push_str(",\n");
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::Enum(enum_def) => {
+                self.out.push_str("enum ");
+                self.out.push_str(&enum_def.name.name);
+                self.generics(enum_def.generics.as_ref());
+                self.where_clause(enum_def.generics.as_ref());
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for variant in &enum_def.variants {
+                    self.attributes(&variant.attributes);
+                    self.line_start();
+                    self.out.push_str(&variant.name.name);
+                    self.variant_fields(&variant.fields);
+                    if let Some(discriminant) = &variant.discriminant {
+                        self.out.push_str(" = ");
+                        self.expression(discriminant);
+                    }
+                    self.out.push_str(",\n");
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::Trait(trait_def) => {
+                self.out.push_str("trait ");
+                self.out.push_str(&trait_def.name.name);
+                self.generics(trait_def.generics.as_ref());
+                if !trait_def.super_traits.is_empty() {
+                    self.out.push_str(": ");
+                    self.types(&trait_def.super_traits, " + ");
+                }
+                self.where_clause(trait_def.generics.as_ref());
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for trait_item in &trait_def.items {
+                    self.attributes(&trait_item.attributes);
+                    self.line_start();
+                    match &trait_item.kind {
+                        TraitItemKind::Method(func) => self.function(func),
+                        TraitItemKind::RequiredMethod(func) => {
+                            self.signature(func);
+                            self.where_clause(func.generics.as_ref());
+                            self.out.push(';');
+                        }
+                        TraitItemKind::AssociatedType(name, bounds) => {
+                            self.out.push_str("type ");
+                            self.out.push_str(&name.name);
+                            if !bounds.is_empty() {
+                                self.out.push_str(": ");
+                                self.types(bounds, " + ");
+                            }
+                            self.out.push(';');
+                        }
+                        TraitItemKind::Const(const_def) => self.constant(const_def),
+                    }
+                    self.out.push('\n');
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::Impl(impl_def) => {
+                self.out.push_str("impl");
+                self.generics(impl_def.generics.as_ref());
+                self.out.push(' ');
+                if let Some(trait_name) = &impl_def.trait_name {
+                    self.ty(trait_name);
+                    self.out.push_str(" for ");
+                }
+                self.ty(&impl_def.self_type);
+                self.where_clause(impl_def.generics.as_ref());
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for (name, ty) in &impl_def.assoc_types {
+                    self.line_start();
+                    self.out.push_str("type ");
+                    self.out.push_str(&name.name);
+                    self.out.push_str(" = ");
+                    self.ty(ty);
+                    self.out.push_str(";\n");
+                }
+                for func in &impl_def.items {
+                    self.attributes(&func.attributes);
+                    self.line_start();
+                    self.function(func);
+                    self.out.push('\n');
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::Const(const_def) => self.constant(const_def),
+            ItemKind::Static(static_def) => {
+                self.out.push_str("static ");
+                if static_def.is_mutable {
+                    self.out.push_str("mut ");
+                }
+                self.out.push_str(&static_def.name.name);
+                self.out.push_str(": ");
+                self.ty(&static_def.type_annotation);
+                self.out.push_str(" = ");
+                self.expression(&static_def.value);
+                self.out.push(';');
+            }
+            ItemKind::TypeAlias(alias) => {
+                self.out.push_str("type ");
+                self.out.push_str(&alias.name.name);
+                self.generics(alias.generics.as_ref());
+                self.out.push_str(" = ");
+                self.ty(&alias.type_annotation);
+                self.out.push(';');
+            }
+            ItemKind::Module(module) => {
+                self.out.push_str("mod ");
+                self.out.push_str(&module.name.name);
+                match &module.items {
+                    None => self.out.push(';'),
+                    Some(items) => {
+                        self.out.push_str(" {\n");
+                        self.indent += 1;
+                        self.inner_attributes(&module.attributes);
+                        for item in items {
+                            self.item(item);
+                        }
+                        self.indent -= 1;
+                        self.line_start();
+                        self.out.push('}');
+                    }
+                }
+            }
+            ItemKind::Use(use_def) => {
+                self.out.push_str("use ");
+                match &use_def.path {
+                    UsePath::Simple(path) => self.path(path),
+                    UsePath::Glob(path) => {
+                        self.path(path);
+                        self.out.push_str("::*");
+                    }
+                    UsePath::List(path, names) => {
+                        self.path(path);
+                        self.out.push_str("::{");
+                        self.path_list(names);
+                        self.out.push('}');
+                    }
+                }
+                if let Some(alias) = &use_def.alias {
+                    self.out.push_str(" as ");
+                    self.out.push_str(&alias.name);
+                }
+                self.out.push(';');
+            }
+            ItemKind::ExternCrate(extern_crate) => {
+                self.out.push_str("extern crate ");
+                self.out.push_str(&extern_crate.name.name);
+                if let Some(rename) = &extern_crate.rename {
+                    self.out.push_str(" as ");
+                    self.out.push_str(&rename.name);
+                }
+                self.out.push(';');
+            }
+            ItemKind::Effect(effect) => {
+                self.out.push_str("effect ");
+                self.out.push_str(&effect.name.name);
+                self.generics(effect.generics.as_ref());
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for operation in &effect.operations {
+                    self.attributes(&operation.attributes);
+                    self.line_start();
+                    self.out.push_str(&operation.name.name);
+                    self.params(&operation.params, false);
+                    if let Some(ty) = &operation.return_type {
+                        self.out.push_str(" -> ");
+                        self.ty(ty);
+                    }
+                    self.out.push_str(",\n");
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::MacroDef(macro_def) => {
+                // The rule delimiters are not kept, so use `()` and `{}`
+                self.out.push_str("macro ");
+                self.out.push_str(&macro_def.name.name);
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for rule in &macro_def.rules {
+                    self.line_start();
+                    self.out.push('(');
+                    self.tokens(&rule.pattern);
+                    self.out.push_str(") => {");
+                    self.tokens(&rule.template);
+                    self.out.push_str("};\n");
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ItemKind::Error => self.out.push_str("/* error */"),
+        }
+        self.out.push('\n');
+    }
+
+    fn constant(&mut self, const_def: &Const) {
+        self.out.push_str("const ");
+        self.out.push_str(&const_def.name.name);
+        self.out.push_str(": ");
+        self.ty(&const_def.type_annotation);
+        self.out.push_str(" = ");
+        self.expression(&const_def.value);
+        self.out.push(';');
+    }
+
+    fn variant_fields(&mut self, fields: &[VariantField]) {
+        if fields.is_empty() {
+            return;
+        }
+        let named = matches!(fields[0], VariantField::Named(..));
+        self.out.push_str(if named { " { " } else { "(" });
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            match field {
+                VariantField::Unnamed(ty) => self.ty(ty),
+                VariantField::Named(name, ty) => {
+                    self.out.push_str(&name.name);
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+            }
+        }
+        self.out.push_str(if named { " }" } else { ")" });
+    }
+
+    /// Print macro rule tokens separated by spaces
+    fn tokens(&mut self, tokens: &[zulon_parser::Token]) {
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 {
+                self.out.push(' ');
+            }
+            self.out.push_str(&token.kind.to_source());
+        }
+    }
+
+    /// Print generic parameters; their bounds are printed by [`Self::where_clause`]
+    fn generics(&mut self, generics: Option<&Generics>) {
+        let Some(generics) = generics else {
+            return;
+        };
+        if generics.params.is_empty() {
+            return;
+        }
+        self.out.push('<');
+        for (i, param) in generics.params.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            match param {
+                GenericParam::Type(name) => self.out.push_str(&name.name),
+                GenericParam::Const(name, ty) => {
+                    self.out.push_str("const ");
+                    self.out.push_str(&name.name);
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+                GenericParam::Lifetime(name) => {
+                    self.out.push('\'');
+                    self.out.push_str(&name.name);
+                }
+            }
+        }
+        self.out.push('>');
+    }
+
+    /// Print every bound, inline or not, as a `where` clause
+    fn where_clause(&mut self, generics: Option<&Generics>) {
+        let Some(generics) = generics else {
+            return;
+        };
+        for (i, clause) in generics.where_clause.iter().enumerate() {
+            self.out.push_str(if i == 0 { " where " } else { ", " });
+            self.out.push_str(&clause.type_param.name);
+            self.out.push_str(": ");
+            for (j, bound) in clause.bounds.iter().enumerate() {
+                if j > 0 {
+                    self.out.push_str(" + ");
+                }
+                match bound {
+                    TraitBound::Trait(ty) => self.ty(ty),
+                    TraitBound::Lifetime(name) => {
+                        self.out.push('\'');
+                        self.out.push_str(&name.name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn signature(&mut self, func: &Function) {
+        if func.is_const {
+            self.out.push_str("const ");
+        }
+        if func.is_async {
+            self.out.push_str("async ");
+        }
+        if func.is_unsafe {
+            self.out.push_str("unsafe ");
+        }
+        self.out.push_str("fn ");
+        self.out.push_str(&func.name.name);
+        self.generics(func.generics.as_ref());
+        self.params(&func.params, func.is_variadic);
+        if let Some(ty) = &func.return_type {
+            self.out.push_str(" -> ");
+            self.ty(ty);
+        }
+        if let Some(ty) = &func.error_type {
+            self.out.push_str(" | ");
+            self.ty(ty);
+        }
+        if !func.effects.is_empty() {
+            self.out.push_str(" | ");
+            self.types(&func.effects, " + ");
+        }
+    }
+
+    fn params(&mut self, params: &[Param], is_variadic: bool) {
+        self.out.push('(');
+        for (i, param) in params.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.out.push_str(&param.name.name);
+            if let Some(ty) = &param.type_annotation {
+                self.out.push_str(": ");
+                self.ty(ty);
+            }
+            if let Some(default) = &param.default_value {
+                self.out.push_str(" = ");
+                self.expression(default);
+            }
+        }
+        if is_variadic {
+            self.out.push_str(if params.is_empty() { "..." } else { ", ..." });
+        }
+        self.out.push(')');
+    }
+
+    fn function(&mut self, func: &Function) {
+        self.signature(func);
+        self.where_clause(func.generics.as_ref());
+        self.out.push(' ');
+        self.block(&func.body);
+    }
+
+    fn block(&mut self, block: &Block) {
+        if block.statements.is_empty() && block.trailing_expr.is_none() {
+            self.out.push_str("{}");
+            return;
+        }
+
+        let no_struct_literals = std::mem::replace(&mut self.no_struct_literals, false);
+        self.out.push_str("{\n");
+        self.indent += 1;
+        for stmt in &block.statements {
+            self.statement(stmt);
+        }
+        if let Some(expr) = &block.trailing_expr {
+            self.line_start();
+            self.expression(expr);
+            self.out.push('\n');
+        }
+        self.indent -= 1;
+        self.line_start();
+        self.out.push('}');
+        self.no_struct_literals = no_struct_literals;
+    }
+
+    fn statement(&mut self, stmt: &Statement) {
+        if let StatementKind::Item(item) = &stmt.kind {
+            return self.item(item);
+        }
+        self.line_start();
+        self.statement_body(stmt);
+        self.out.push('\n');
+    }
+
+    /// Print a statement without indentation or the final newline
+    fn statement_body(&mut self, stmt: &Statement) {
+        match &stmt.kind {
+            StatementKind::Item(item) => {
+                // Items end their own line
+                self.item(item);
+                self.out.pop();
+                return;
+            }
+            StatementKind::Local(local) => {
+                self.out.push_str("let ");
+                if local.is_mutable {
+                    self.out.push_str("mut ");
+                }
+                self.out.push_str(&local.name.name);
+                if let Some(ty) = &local.type_annotation {
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+                if let Some(init) = &local.init {
+                    self.out.push_str(" = ");
+                    self.expression(init);
+                }
+            }
+            StatementKind::LetElse(let_else) => {
+                self.out.push_str("let ");
+                if let_else.is_mutable {
+                    self.out.push_str("mut ");
+                }
+                self.pattern(&let_else.pattern);
+                if let Some(ty) = &let_else.type_annotation {
+                    self.out.push_str(": ");
+                    self.ty(ty);
+                }
+                self.out.push_str(" = ");
+                self.expression(&let_else.init);
+                self.out.push_str(" else ");
+                self.block(&let_else.else_block);
+            }
+            StatementKind::Expr(expr) => self.expression(expr),
+            // `defer` takes a statement without its `;`
+            StatementKind::Defer(inner) => return self.defer(inner),
+            StatementKind::Empty => {}
+            StatementKind::Error => return self.out.push_str("/* error */"),
+        }
+        self.out.push(';');
+    }
+
+    fn defer(&mut self, stmt: &Statement) {
+        self.out.push_str("defer ");
+        match &stmt.kind {
+            StatementKind::Expr(expr) => self.expression(expr),
+            _ => self.statement_body(stmt),
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => self.literal(literal),
+            ExpressionKind::Path(path) => self.path(path),
+            ExpressionKind::Block(block) => self.block(block),
+            ExpressionKind::Grouped(inner) => self.operand(inner, POSTFIX),
+            ExpressionKind::Binary(op, left, right) => {
+                // Binary operators are left-associative
+                let prec = binary_precedence(*op);
+                self.operand(left, prec);
+                self.out.push(' ');
+                self.out.push_str(binary_op(*op));
+                self.out.push(' ');
+                self.operand(right, prec + 1);
+            }
+            ExpressionKind::Unary(op, operand) => {
+                self.out.push_str(match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                    UnaryOp::BitNot => "^",
+                    UnaryOp::Ref | UnaryOp::Borrow => "&",
+                    UnaryOp::BorrowMut => "&mut ",
+                    UnaryOp::Deref => "*",
+                });
+                // Parenthesize nested unary operators so `- -x` does not lex as `--x`
+                let min = if matches!(operand.kind, ExpressionKind::Unary(..)) { POSTFIX } else { UNARY };
+                self.operand(operand, min);
+            }
+            ExpressionKind::Assign(target, value) => {
+                self.operand(target, LOWEST + 1);
+                self.out.push_str(" = ");
+                self.expression(value);
+            }
+            ExpressionKind::AssignOp(op, target, value) => {
+                self.operand(target, LOWEST + 1);
+                self.out.push(' ');
+                self.out.push_str(binary_op(*op));
+                self.out.push_str("= ");
+                self.expression(value);
+            }
+            ExpressionKind::Call(callee, args) => {
+                self.operand(callee, POSTFIX);
+                self.arguments(args);
+            }
+            ExpressionKind::MethodCall(receiver, method, args) => {
+                self.operand(receiver, POSTFIX);
+                self.out.push('.');
+                self.out.push_str(&method.name);
+                self.arguments(args);
+            }
+            ExpressionKind::FieldAccess(object, field) => {
+                self.operand(object, POSTFIX);
+                self.out.push('.');
+                self.out.push_str(&field.name);
+            }
+            ExpressionKind::TupleIndex(object, index) => {
+                self.operand(object, POSTFIX);
+                self.out.push('.');
+                self.out.push_str(&index.to_string());
+            }
+            ExpressionKind::Index(object, index) => {
+                self.operand(object, POSTFIX);
+                self.out.push('[');
+                self.expression(index);
+                self.out.push(']');
+            }
+            ExpressionKind::Array(elements) => {
+                self.out.push('[');
+                self.list(elements);
+                self.out.push(']');
+            }
+            ExpressionKind::Tuple(elements) => {
+                self.out.push('(');
+                self.list(elements);
+                if elements.len() == 1 {
+                    self.out.push(',');
+                }
+                self.out.push(')');
+            }
+            ExpressionKind::Struct(literal) => {
+                let parenthesize = self.no_struct_literals;
+                if parenthesize {
+                    self.out.push('(');
+                }
+                self.no_struct_literals = false;
+                self.struct_literal(literal);
+                self.no_struct_literals = parenthesize;
+                if parenthesize {
+                    self.out.push(')');
+                }
+            }
+            ExpressionKind::If(condition, then_block, else_block) => {
+                self.out.push_str("if ");
+                self.condition(condition);
+                self.out.push(' ');
+                self.block(then_block);
+                if let Some(else_block) = else_block {
+                    self.out.push_str(" else ");
+                    self.block(else_block);
+                }
+            }
+            ExpressionKind::Match(scrutinee, arms) => {
+                self.out.push_str("match ");
+                self.condition(scrutinee);
+                if arms.is_empty() {
+                    self.out.push_str(" {}");
+                    return;
+                }
+                self.out.push_str(" {\n");
+                self.indent += 1;
+                for arm in arms {
+                    self.line_start();
+                    self.patterns(&arm.patterns, " | ");
+                    if let Some(guard) = &arm.guard {
+                        self.out.push_str(" if ");
+                        self.expression(guard);
+                    }
+                    self.out.push_str(" => ");
+                    self.expression(&arm.body);
+                    self.out.push_str(",\n");
+                }
+                self.indent -= 1;
+                self.line_start();
+                self.out.push('}');
+            }
+            ExpressionKind::While(condition, body, _) => {
+                self.out.push_str("while ");
+                self.condition(condition);
+                self.out.push(' ');
+                self.block(body);
+            }
+            ExpressionKind::Loop(body, _) => {
+                self.out.push_str("loop ");
+                self.block(body);
+            }
+            ExpressionKind::For(local, iter, body, _) => {
+                self.out.push_str("for ");
+                self.out.push_str(&local.name.name);
+                self.out.push_str(" in ");
+                self.condition(iter);
+                self.out.push(' ');
+                self.block(body);
+            }
+            ExpressionKind::Let(pattern, value) => {
+                self.out.push_str("let ");
+                self.pattern(pattern);
+                self.out.push_str(" = ");
+                self.expression(value);
+            }
+            ExpressionKind::Break(_) => self.out.push_str("break"),
+            ExpressionKind::Continue(_) => self.out.push_str("continue"),
+            ExpressionKind::Return(value) => {
+                self.out.push_str("return");
+                if let Some(value) = value {
+                    self.out.push(' ');
+                    self.expression(value);
+                }
+            }
+            ExpressionKind::Closure { params, return_type, body } => {
+                self.out.push('|');
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+                    self.out.push_str(&param.name.name);
+                    if let Some(ty) = &param.type_annotation {
+                        self.out.push_str(": ");
+                        self.ty(ty);
+                    }
+                }
+                self.out.push('|');
+                if let Some(ty) = return_type {
+                    self.out.push_str(" -> ");
+                    self.ty(ty);
+                }
+                self.out.push(' ');
+                self.expression(body);
+            }
+            ExpressionKind::Defer(stmt) => self.defer(stmt),
+            ExpressionKind::Throw(value) => {
+                self.out.push_str("throw ");
+                self.expression(value);
+            }
+            ExpressionKind::QuestionMark(value) => {
+                self.operand(value, POSTFIX);
+                self.out.push('?');
+            }
+            ExpressionKind::Try(block, handlers) => {
+                self.out.push_str("try ");
+                self.block(block);
+                for handler in handlers {
+                    self.out.push_str(" with ");
+                    self.out.push_str(&handler.effect_name.name);
+                    if handler.methods.is_empty() {
+                        self.out.push_str(" {}");
+                        continue;
+                    }
+                    self.out.push_str(" {\n");
+                    self.indent += 1;
+                    for method in &handler.methods {
+                        self.line_start();
+                        self.out.push_str(&method.name.name);
+                        self.params(&method.params, false);
+                        self.out.push(' ');
+                        self.block(&method.body);
+                        self.out.push_str(",\n");
+                    }
+                    self.indent -= 1;
+                    self.line_start();
+                    self.out.push('}');
+                }
+            }
+            ExpressionKind::Perform(operation, args) => {
+                self.out.push_str("perform ");
+                self.out.push_str(&operation.name);
+                self.arguments(args);
+            }
+            ExpressionKind::Cast(value, ty) => {
+                self.operand(value, CAST);
+                self.out.push_str(" as ");
+                self.ty(ty);
+            }
+            ExpressionKind::Range(start, kind, end) => {
+                self.operand(start, RANGE + 1);
+                self.out.push_str(match kind {
+                    RangeKind::Exclusive => "..",
+                    RangeKind::Inclusive => "..=",
+                });
+                self.operand(end, RANGE + 1);
+            }
+            ExpressionKind::TemplateString(template) => {
+                self.out.push('`');
+                for part in &template.parts {
+                    match part {
+                        TemplateStringPart::Static(text) => {
+                            for c in text.chars() {
+                                self.escaped_char(c, '`');
+                            }
+                        }
+                        TemplateStringPart::Expr(expr, spec) => {
+                            self.out.push_str("${");
+                            self.expression(expr);
+                            let spec = spec.to_string();
+                            if !spec.is_empty() {
+                                self.out.push(':');
+                                self.out.push_str(&spec);
+                            }
+                            self.out.push('}');
+                        }
+                    }
+                }
+                self.out.push('`');
+            }
+            ExpressionKind::MacroInvocation { macro_name, args, delimiter } => {
+                self.out.push_str(&macro_name.name);
+                self.out.push('!');
+                let (open, close) = match delimiter {
+                    MacroDelimiter::Paren => ('(', ')'),
+                    MacroDelimiter::Brace => ('{', '}'),
+                    MacroDelimiter::Bracket => ('[', ']'),
+                };
+                self.out.push(open);
+                self.list(args);
+                self.out.push(close);
+            }
+            ExpressionKind::Await(value) => {
+                self.operand(value, POSTFIX);
+                self.out.push_str(".await");
+            }
+            ExpressionKind::Error => self.out.push_str("/* error */"),
+        }
+    }
+
+    /// Print an expression in a position where a struct literal would be
+    /// taken for the block that follows
+    fn condition(&mut self, expr: &Expression) {
+        let no_struct_literals = std::mem::replace(&mut self.no_struct_literals, true);
+        self.expression(expr);
+        self.no_struct_literals = no_struct_literals;
+    }
+
+    fn struct_literal(&mut self, literal: &StructLiteral) {
+        self.path(&literal.path);
+        if literal.fields.is_empty() && literal.base.is_none() {
+            self.out.push_str(" {}");
+            return;
+        }
+        self.out.push_str(" { ");
+        for (i, field) in literal.fields.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.out.push_str(&field.name.name);
+            self.out.push_str(": ");
+            self.expression(&field.value);
+        }
+        if let Some(base) = &literal.base {
+            if !literal.fields.is_empty() {
+                self.out.push_str(", ");
+            }
+            self.out.push_str("..");
+            self.expression(base);
+        }
+        self.out.push_str(" }");
+    }
+
+    /// Print an operand, parenthesized if it binds less tightly than `min`
+    fn operand(&mut self, expr: &Expression, min: u8) {
+        if precedence(expr) < min {
+            let no_struct_literals = std::mem::replace(&mut self.no_struct_literals, false);
+            self.out.push('(');
+            self.expression(expr);
+            self.out.push(')');
+            self.no_struct_literals = no_struct_literals;
+        } else {
+            self.expression(expr);
+        }
+    }
+
+    fn arguments(&mut self, args: &[Box<Expression>]) {
+        self.out.push('(');
+        self.list(args);
+        self.out.push(')');
+    }
+
+    fn list(&mut self, elements: &[Box<Expression>]) {
+        let no_struct_literals = std::mem::replace(&mut self.no_struct_literals, false);
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.expression(element);
+        }
+        self.no_struct_literals = no_struct_literals;
+    }
+
+    /// Print a path; a leading empty segment prints as the `::` of a global path
+    fn path(&mut self, path: &[Identifier]) {
+        for (i, segment) in path.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str("::");
+            }
+            self.out.push_str(&segment.name);
+        }
+    }
+
+    fn path_list(&mut self, names: &[Identifier]) {
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+            self.out.push_str(&name.name);
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::Int(value, suffix) => {
+                self.out.push_str(&value.to_string());
+                if let Some(suffix) = suffix {
+                    self.out.push_str(suffix.as_str());
+                }
+            }
+            Literal::Float(value, suffix) => {
+                self.out.push_str(&format!("{:?}", value));
+                if let Some(suffix) = suffix {
+                    self.out.push_str(suffix.as_str());
+                }
+            }
+            Literal::Bool(value) => self.out.push_str(&value.to_string()),
+            Literal::String(value) => self.string(value),
+            Literal::Char(value) => {
+                self.out.push('\'');
+                self.escaped_char(*value, '\'');
+                self.out.push('\'');
+            }
+            Literal::ByteString(bytes) => {
+                self.out.push_str("b\"");
+                self.out.push_str(&bytes.escape_ascii().to_string());
+                self.out.push('"');
+            }
+            Literal::Byte(byte) => {
+                self.out.push_str("b'");
+                self.out.push_str(&byte.escape_ascii().to_string());
+                self.out.push('\'');
+            }
+            Literal::Null => self.out.push_str("null"),
+        }
+    }
+
+    fn string(&mut self, value: &str) {
+        self.out.push('"');
+        for c in value.chars() {
+            self.escaped_char(c, '"');
+        }
+        self.out.push('"');
+    }
+
+    fn escaped_char(&mut self, c: char, quote: char) {
+        match c {
+            '\n' => self.out.push_str("\\n"),
+            '\t' => self.out.push_str("\\t"),
+            '\\' => self.out.push_str("\\\\"),
+            c if c == quote => {
+                self.out.push('\\');
+                self.out.push(c);
+            }
+            c => self.out.push(c),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Wildcard => self.out.push('_'),
+            Pattern::Literal(literal) => self.literal(literal),
+            Pattern::Identifier(name) => self.out.push_str(&name.name),
+            Pattern::Struct(path, fields) => {
+                self.path(path);
+                // A unit variant pattern such as `Outcome::None` has no braces
+                if fields.is_empty() {
+                    if path.len() == 1 {
+                        self.out.push_str(" {}");
+                    }
+                    return;
+                }
+                self.out.push_str(" { ");
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+                    match field {
+                        StructPatternField::Field(name, pattern) => {
+                            self.out.push_str(&name.name);
+                            self.out.push_str(": ");
+                            self.pattern(pattern);
+                        }
+                        StructPatternField::Shorthand(name) => self.out.push_str(&name.name),
+                    }
+                }
+                self.out.push_str(" }");
+            }
+            Pattern::Tuple(elements) => {
+                self.out.push('(');
+                self.patterns(elements, ", ");
+                self.out.push(')');
+            }
+            Pattern::TupleVariant(path, elements) => {
+                self.path(path);
+                self.out.push('(');
+                self.patterns(elements, ", ");
+                self.out.push(')');
+            }
+            Pattern::Array(elements) => {
+                self.out.push('[');
+                self.patterns(elements, ", ");
+                self.out.push(']');
+            }
+            Pattern::Slice(before, middle, after) => {
+                self.out.push('[');
+                for pattern in before {
+                    self.pattern(pattern);
+                    self.out.push_str(", ");
+                }
+                self.out.push_str("..");
+                for pattern in middle.iter().chain(after) {
+                    self.out.push_str(", ");
+                    self.pattern(pattern);
+                }
+                self.out.push(']');
+            }
+            Pattern::Range(start, kind, end) => {
+                self.pattern(start);
+                self.out.push_str(match kind {
+                    RangeKind::Exclusive => "..",
+                    RangeKind::Inclusive => "..=",
+                });
+                self.pattern(end);
+            }
+            Pattern::Or(alternatives) => self.patterns(alternatives, " | "),
+        }
+    }
+
+    fn patterns(&mut self, patterns: &[Pattern], separator: &str) {
+        for (i, pattern) in patterns.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(separator);
+            }
+            self.pattern(pattern);
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match ty {
+            Type::Simple(name) => self.out.push_str(&name.name),
+            Type::Path(path) => self.path(path),
+            Type::PathGeneric(path, args) => {
+                self.path(path);
+                if let Some(args) = args {
+                    self.out.push('<');
+                    self.types(args, ", ");
+                    self.out.push('>');
+                }
+            }
+            Type::Unit => self.out.push_str("()"),
+            Type::Never => self.out.push('!'),
+            Type::Tuple(elements) => {
+                self.out.push('(');
+                self.types(elements, ", ");
+                if elements.len() == 1 {
+                    self.out.push(',');
+                }
+                self.out.push(')');
+            }
+            Type::Slice(element) => {
+                self.out.push('[');
+                self.ty(element);
+                self.out.push(']');
+            }
+            Type::Array(element, len) => {
+                self.out.push('[');
+                self.ty(element);
+                if let Some(len) = len {
+                    self.out.push_str("; ");
+                    self.expression(len);
+                }
+                self.out.push(']');
+            }
+            Type::Ref(inner, is_mutable) => {
+                self.out.push_str(if *is_mutable { "&mut " } else { "&" });
+                self.ty(inner);
+            }
+            Type::Pointer(inner, is_mutable) => {
+                self.out.push_str(if *is_mutable { "*mut " } else { "*const " });
+                self.ty(inner);
+            }
+            Type::Function(params, ret) => {
+                self.out.push_str("fn(");
+                self.types(params, ", ");
+                self.out.push_str(") -> ");
+                self.ty(ret);
+            }
+            Type::TraitObject(inner) => {
+                self.out.push_str("dyn ");
+                self.ty(inner);
+            }
+            Type::ImplTrait(inner) => {
+                self.out.push_str("impl ");
+                self.ty(inner);
+            }
+            Type::Optional(inner) => {
+                self.ty(inner);
+                self.out.push('?');
+            }
+            Type::Pipe(ok, error) => {
+                self.ty(ok);
+                self.out.push_str(" | ");
+                self.ty(error);
+            }
+        }
+    }
+
+    fn types(&mut self, types: &[Type], separator: &str) {
+        for (i, ty) in types.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(separator);
+            }
+            self.ty(ty);
+        }
+    }
+}
+
+/// Assignment, `return` and other expressions that extend as far right as possible
+const LOWEST: u8 = 0;
+/// Ranges, `a..b`
+const RANGE: u8 = 1;
+/// Casts, `value as Type`
+const CAST: u8 = 8;
+/// Prefix operators
+const UNARY: u8 = 9;
+/// Calls, field access, indexing and primary expressions
+const POSTFIX: u8 = 10;
+
+/// How tightly an expression binds, following the parser's precedence climbing
+fn precedence(expr: &Expression) -> u8 {
+    match &expr.kind {
+        ExpressionKind::Assign(..)
+        | ExpressionKind::AssignOp(..)
+        | ExpressionKind::Return(_)
+        | ExpressionKind::Throw(_)
+        | ExpressionKind::Closure { .. }
+        | ExpressionKind::Let(..) => LOWEST,
+        ExpressionKind::Range(..) => RANGE,
+        ExpressionKind::Binary(op, _, _) => binary_precedence(*op),
+        ExpressionKind::Cast(..) => CAST,
+        ExpressionKind::Unary(..) => UNARY,
+        _ => POSTFIX,
+    }
+}
+
+fn binary_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Or => 2,
+        BinaryOp::And => 3,
+        BinaryOp::Eq | BinaryOp::NotEq => 4,
+        BinaryOp::Less | BinaryOp::LessEq | BinaryOp::Greater | BinaryOp::GreaterEq => 5,
+        BinaryOp::Add | BinaryOp::Sub => 6,
+        // Bitwise operators are not parsed yet; give them the tightest binary precedence
+        _ => 7,
+    }
+}
+
+fn binary_op(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Mod => "%",
+        BinaryOp::BitAnd => "&",
+        BinaryOp::BitOr => "|",
+        BinaryOp::BitXor => "^",
+        BinaryOp::LeftShift => "<<",
+        BinaryOp::RightShift => ">>",
+        BinaryOp::Eq => "==",
+        BinaryOp::NotEq => "!=",
+        BinaryOp::Less => "<",
+        BinaryOp::LessEq => "<=",
+        BinaryOp::Greater => ">",
+        BinaryOp::GreaterEq => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zulon_parser::Parser;
+
+    #[test]
+    fn test_print_is_stable() {
+        let source = "fn add(a: i32, b: i32) -> i32 {\n    let mut x: i32 = (a + b) * 2;\n    x += 1;\n    if x > 0 {\n        x\n    } else {\n        -x\n    }\n}\n";
+        let ast = Parser::from_source(source).parse().unwrap();
+        assert_eq!(print_ast(&ast), source);
+    }
+
+    #[test]
+    fn test_print_every_construct() {
+        let source = r#"//! Module docs
+#![allow(dead_code)]
+
+/// A point
+#[derive(Clone, Debug)]
+pub struct Point<T> where T: Copy {
+    x: T,
+    y: T = 0,
+}
+
+pub(crate) enum Shape<T> {
+    Circle { radius: T },
+    Rect(T, T),
+    Empty = 3,
+}
+
+trait Area<T>: Clone + Debug where T: Copy {
+    type Output: Display;
+    const SIDES: i32 = 4;
+    fn area(&self) -> T;
+    fn scaled(&self, by: T) -> T {
+        self.area() * by
+    }
+}
+
+impl<T> Area<T> for Shape<T> where T: Copy {
+    type Output = T;
+    fn area(&self) -> T {
+        match self {
+            Shape::Circle { radius } => radius,
+            Shape::Rect(w, h) | Shape::Rect(h, w) if w > h => w * h,
+            Shape::Empty => 0,
+            _ => 1,
+        }
+    }
+}
+
+use std::collections::{Map, Set};
+use std::io::*;
+use std::fmt::Display as Show;
+extern fn printf(format: &u8, ...) -> i32;
+const LIMIT: u8 = 255u8;
+static mut COUNT: f32 = 1.5f32;
+type Pair = (i32, Vec<dyn Show>);
+
+effect Log {
+    write(message: str) -> (),
+}
+
+macro twice {
+    ($x:expr) => {$x + $x};
+}
+
+mod inner {
+    //! Inner docs
+    fn hidden() {}
+}
+
+async fn run<I>(items: [i32; 3], bytes: &mut [u8]) -> i32 | IoError | Log + Alloc where I: Iterator {
+    let mut total = 0;
+    let (a, [first, .., last]) = pair else {
+        return 0;
+    };
+    let point = Point { x: 1, ..origin };
+    for item in items {
+        total += item;
+    }
+    if (Point { x: 1, y: 2 }).x == 1 {
+        total = -total;
+    }
+    while let Some(x) = next() {
+        continue;
+    }
+    match value {
+        1..=5 | 'a'..'z' => b'x',
+        Point { x: 0, y } => y,
+        _ => null,
+    }
+    let f = |a, b: i32| -> i32 a + b;
+    let s = `total: ${total:>8} of ${LIMIT}`;
+    let raw = b"bytes\n";
+    let t = (1,);
+    defer cleanup()
+    let r = try {
+        perform_write()?
+    } with Log {
+        write(message) {
+            print(message)
+        },
+    };
+    let v = vec![1, 2, 3];
+    let w = fetch().await;
+    ::builtin::exit(0);
+    throw Error::new()
+}
+"#;
+        let ast = Parser::from_source(source).parse().unwrap();
+        let printed = print_ast(&ast);
+        let reparsed = Parser::from_source(&printed)
+            .parse()
+            .unwrap_or_else(|error| panic!("printed source does not parse: {}\n{}", error, printed));
+        assert_eq!(print_ast(&reparsed), printed);
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Deterministic runs of the fuzz invariants
+//!
+//! These do not replace the fuzzers, but keep the invariants (and inputs the
+//! fuzzers once found) checked by `cargo test`.
+
+use std::fs;
+use std::path::Path;
+use zulon_tests_fuzz::{check_lexer, check_parser, check_round_trip, check_typeck};
+
+/// Deterministic pseudo-random input bytes
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn bytes(&mut self, max_len: u64) -> Vec<u8> {
+        let len = self.next() % max_len;
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+fn check_all(data: &[u8]) {
+    check_lexer(data);
+    check_parser(data);
+    check_typeck(data);
+}
+
+/// Run on a thread with a main-thread-sized stack, as the fuzzers do
+fn on_large_stack(f: impl FnOnce() + Send + 'static) {
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_round_trip_random_inputs() {
+    let mut rng = XorShift(0x1234_5678_9abc_def0);
+    for _ in 0..2000 {
+        check_round_trip(&rng.bytes(600));
+    }
+}
+
+#[test]
+fn test_random_bytes() {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..2000 {
+        check_all(&rng.bytes(200));
+    }
+}
+
+#[test]
+fn test_truncated_examples() {
+    let examples = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+    let mut paths: Vec<_> = fs::read_dir(examples)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "zl"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let source = fs::read(&path).unwrap();
+        let step = source.len() / 50 + 1;
+        for end in (0..=source.len()).step_by(step) {
+            check_all(&source[..end]);
+        }
+    }
+}
+
+#[test]
+fn test_regressions() {
+    let inputs = [
+        // Non-ASCII identifiers
+        "fn 名字() -> i32 { let ü = 1; ü }",
+        // `&&T` is a single token
+        "fn f(x: &&i64) {}",
+        // Blocks in expression position
+        "fn f() { let x = {}; (1, {}) }",
+        // Unterminated constructs
+        "fn f() { #[",
+        "#![cfg(",
+        "fn f() { match x { 1 => ",
+        "fn f() { |x| ",
+        "struct S { a: [i32; ",
+    ];
+
+    for input in inputs {
+        check_all(input.as_bytes());
+    }
+}
+
+#[test]
+fn test_deep_nesting() {
+    on_large_stack(|| {
+        for depth in [100, 10_000] {
+            check_all(format!("fn f() {{ {}1{} }}", "(".repeat(depth), ")".repeat(depth)).as_bytes());
+            check_all(format!("fn f() {}{}", "{".repeat(depth), "}".repeat(depth)).as_bytes());
+            check_all(format!("fn f() {{ {}x }}", "!".repeat(depth)).as_bytes());
+            check_all(format!("fn f(x: {}i32) {{}}", "&".repeat(depth)).as_bytes());
+            check_all("mod m { ".repeat(depth).as_bytes());
+            check_all(format!("fn main() -> i32 {{ {}1 }}", "1 + ".repeat(depth)).as_bytes());
+            check_all(format!("fn f() {{ x{} }}", ".f()".repeat(depth)).as_bytes());
+        }
+    });
+}
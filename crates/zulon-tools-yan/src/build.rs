@@ -11,7 +11,9 @@ use std::io::Cursor;
 
 /// Build a single ZULON source file to executable
 /// Returns the path to the generated executable
-pub fn build_zulon_file(source_file: &str, release: bool) -> Result<String> {
+///
+/// `features` enables the matching `#[cfg(feature = "...")]` items.
+pub fn build_zulon_file(source_file: &str, release: bool, features: &[String]) -> Result<String> {
     println!("🔨 Building ZULON file: {}", source_file);
 
     // Check if file exists
@@ -28,8 +30,15 @@ pub fn build_zulon_file(source_file: &str, release: bool) -> Result<String> {
     // Parse
     println!("\n   [1/5] Parsing...");
     let mut parser = zulon_parser::Parser::from_source(&source);
-    let ast = parser.parse()
+    let mut ast = parser.parse()
         .with_context(|| "Parsing failed")?;
+
+    let cfg = features
+        .iter()
+        .fold(zulon_parser::cfg::CfgOptions::host(), |cfg, feature| cfg.with_feature(feature.as_str()));
+    if let Some(error) = zulon_parser::cfg::strip_unconfigured(&mut ast, &cfg).into_iter().next() {
+        return Err(anyhow::anyhow!("{}", error));
+    }
     println!("      ✅ Parsed {} items", ast.items.len());
 
     // HIR
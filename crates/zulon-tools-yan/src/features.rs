@@ -0,0 +1,152 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Feature resolution for yan builds
+//!
+//! A project declares its features in the `[features]` table of `yan.toml`,
+//! each listing the other features it enables:
+//!
+//! ```toml
+//! [features]
+//! default = ["std"]
+//! std = []
+//! simd = ["std"]
+//! ```
+//!
+//! The features given with `--features` are combined with `default` and
+//! closed over these dependencies before they reach `cfg(feature = "...")`.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Name of the project manifest read for features
+pub const MANIFEST: &str = "yan.toml";
+
+/// The `[features]` table of a project manifest
+#[derive(Debug, Clone, Default)]
+pub struct FeatureTable {
+    /// Each feature, with the features it enables
+    features: BTreeMap<String, Vec<String>>,
+    /// Whether the manifest has a `[features]` table at all
+    declared: bool,
+}
+
+impl FeatureTable {
+    /// Read the features of the project in `dir`; a project without a
+    /// manifest declares none
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let manifest = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&manifest).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Parse the `[features]` table of a manifest
+    pub fn parse(manifest: &str) -> Result<Self> {
+        let manifest: toml::Table = manifest.parse()?;
+        let Some(table) = manifest.get("features") else {
+            return Ok(Self::default());
+        };
+        let table = table
+            .as_table()
+            .ok_or_else(|| anyhow::anyhow!("`features` must be a table"))?;
+
+        let mut features = BTreeMap::new();
+        for (name, enables) in table {
+            let enables = enables
+                .as_array()
+                .and_then(|enables| {
+                    enables.iter().map(|feature| feature.as_str().map(String::from)).collect()
+                })
+                .ok_or_else(|| anyhow::anyhow!("feature `{}` must be a list of feature names", name))?;
+            features.insert(name.clone(), enables);
+        }
+
+        Ok(Self { features, declared: true })
+    }
+
+    /// The features enabled by `requested`, plus `default` unless
+    /// `default_features` is false, with everything they enable
+    ///
+    /// If the manifest declares features, only those may be requested.
+    /// The result is sorted and does not contain `default` itself.
+    pub fn resolve(&self, requested: &[String], default_features: bool) -> Result<Vec<String>> {
+        let mut pending: Vec<&str> = requested.iter().map(String::as_str).collect();
+        if default_features && self.features.contains_key("default") {
+            pending.push("default");
+        }
+
+        let mut enabled = BTreeSet::new();
+        while let Some(feature) = pending.pop() {
+            if !enabled.insert(feature) {
+                continue;
+            }
+            match self.features.get(feature) {
+                Some(enables) => pending.extend(enables.iter().map(String::as_str)),
+                None if self.declared => {
+                    return Err(anyhow::anyhow!("Unknown feature `{}` (not in [features] of {})", feature, MANIFEST));
+                }
+                None => {}
+            }
+        }
+
+        enabled.remove("default");
+        Ok(enabled.into_iter().map(String::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST_WITH_FEATURES: &str = r#"
+        [workspace]
+        root = "."
+
+        [features]
+        default = ["std"]
+        std = []
+        simd = ["std", "fast-math"]
+        fast-math = []
+        tracing = []
+    "#;
+
+    fn features(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_defaults_and_dependencies() {
+        let table = FeatureTable::parse(MANIFEST_WITH_FEATURES).unwrap();
+
+        assert_eq!(table.resolve(&[], true).unwrap(), features(&["std"]));
+        assert_eq!(table.resolve(&[], false).unwrap(), features(&[]));
+        assert_eq!(
+            table.resolve(&features(&["simd"]), false).unwrap(),
+            features(&["fast-math", "simd", "std"])
+        );
+        assert_eq!(
+            table.resolve(&features(&["tracing"]), true).unwrap(),
+            features(&["std", "tracing"])
+        );
+    }
+
+    #[test]
+    fn test_unknown_feature() {
+        let table = FeatureTable::parse(MANIFEST_WITH_FEATURES).unwrap();
+        assert!(table.resolve(&features(&["gpu"]), true).is_err());
+
+        // Without a [features] table any feature may be requested
+        let table = FeatureTable::parse("[workspace]\nroot = \".\"\n").unwrap();
+        assert_eq!(table.resolve(&features(&["gpu"]), true).unwrap(), features(&["gpu"]));
+    }
+
+    #[test]
+    fn test_invalid_features_table() {
+        assert!(FeatureTable::parse("[features]\nstd = \"yes\"\n").is_err());
+    }
+}
@@ -8,6 +8,8 @@
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 
+pub mod features;
 pub mod test_runner;
 
+pub use features::FeatureTable;
 pub use test_runner::{Test, TestResult, TestRunner, TestSummary};
@@ -6,11 +6,12 @@
 //! The yan tool provides a command-line interface for managing ZULON projects,
 //! including building, running, and creating new projects.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use anyhow::{Result, Context};
 use std::path::Path;
 
 mod build;
+mod features;
 mod test_runner;
 
 #[derive(Parser)]
@@ -22,6 +23,25 @@ struct Cli {
     command: Commands,
 }
 
+/// Features of yan.toml to enable for `cfg(feature = "...")`
+#[derive(Args)]
+struct FeatureArgs {
+    /// Features to enable for `cfg(feature = "...")` (comma separated)
+    #[arg(long, value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Do not enable the `default` feature of yan.toml
+    #[arg(long)]
+    no_default_features: bool,
+}
+
+impl FeatureArgs {
+    /// The enabled features, resolved against the project's yan.toml
+    fn resolve(&self) -> Result<Vec<String>> {
+        features::FeatureTable::load(Path::new("."))?.resolve(&self.features, !self.no_default_features)
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Build a ZULON project
@@ -49,6 +69,9 @@ enum Commands {
         /// Automatically run the compiled executable
         #[arg(long)]
         run: bool,
+
+        #[command(flatten)]
+        features: FeatureArgs,
     },
 
     /// Run a ZULON project or example
@@ -61,6 +84,10 @@ enum Commands {
         #[arg(long)]
         example: Option<String>,
 
+        /// Compile and run a ZULON source file
+        #[arg(long)]
+        file: Option<String>,
+
         /// Arguments to pass to the binary
         #[arg(trailing_var_arg = true)]
         args: Vec<String>,
@@ -68,6 +95,9 @@ enum Commands {
         /// Run in release mode
         #[arg(short, long)]
         release: bool,
+
+        #[command(flatten)]
+        features: FeatureArgs,
     },
 
     /// Create a new ZULON project
@@ -104,6 +134,9 @@ enum Commands {
         /// Compile in release mode
         #[arg(short, long)]
         release: bool,
+
+        #[command(flatten)]
+        features: FeatureArgs,
     },
 }
 
@@ -111,11 +144,11 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Build { release, package: _, jobs: _, example, file, run } => {
+        Commands::Build { release, package: _, jobs: _, example, file, run, features } => {
             build::check_project_dir()?;
 
             let executable_path = if let Some(source_file) = &file {
-                Some(build::build_zulon_file(source_file, release)?)
+                Some(build::build_zulon_file(source_file, release, &features.resolve()?)?)
             } else if let Some(ex) = &example {
                 Some(build::build_example(ex, release)?)
             } else {
@@ -149,10 +182,14 @@ fn main() -> Result<()> {
             Ok(())
         }
 
-        Commands::Run { bin, example, args, release } => {
+        Commands::Run { bin, example, file, args, release, features } => {
             println!("🚀 Running ZULON project...");
 
-            if let Some(ex) = example {
+            if let Some(source_file) = file {
+                let exe_path = build::build_zulon_file(&source_file, release, &features.resolve()?)?;
+                println!();
+                run_executable(&exe_path, &args)?;
+            } else if let Some(ex) = example {
                 println!("   Example: {}", ex);
                 run_example(&ex, &args, release)?;
             } else if let Some(b) = bin {
@@ -175,8 +212,8 @@ fn main() -> Result<()> {
             Ok(())
         }
 
-        Commands::Test { filter, verbose, release } => {
-            run_tests(filter, verbose, release)?;
+        Commands::Test { filter, verbose, release, features } => {
+            run_tests(filter, verbose, release, features.resolve()?)?;
             Ok(())
         }
     }
@@ -188,29 +225,13 @@ fn run_binary(bin: &str, args: &[String], release: bool) -> Result<()> {
 
     let binary_path = format!("target/{}/{}", profile, bin);
 
-    println!("   Running: {}", binary_path);
-    if !args.is_empty() {
-        println!("   Args: {:?}", args);
-    }
-    println!();
-
     if !std::path::Path::new(&binary_path).exists() {
         return Err(anyhow::anyhow!(
             "Binary not found: {}. Run `yan build` first.", binary_path
         ));
     }
 
-    let status = std::process::Command::new(&binary_path)
-        .args(args)
-        .status()
-        .with_context(|| format!("Failed to run binary: {}", binary_path))?;
-
-    if status.success() {
-        println!("✅ Run complete!");
-        Ok(())
-    } else {
-        Err(anyhow::anyhow!("Run failed with exit code: {:?}", status.code()))
-    }
+    run_executable(&binary_path, args)
 }
 
 /// Run an example
@@ -219,12 +240,6 @@ fn run_example(example: &str, args: &[String], release: bool) -> Result<()> {
 
     let example_path = format!("target/{}/examples/{}", profile, example);
 
-    println!("   Running: {}", example_path);
-    if !args.is_empty() {
-        println!("   Args: {:?}", args);
-    }
-    println!();
-
     if !std::path::Path::new(&example_path).exists() {
         return Err(anyhow::anyhow!(
             "Example not found: {}. Run `yan build --example {}` first.",
@@ -232,10 +247,21 @@ fn run_example(example: &str, args: &[String], release: bool) -> Result<()> {
         ));
     }
 
-    let status = std::process::Command::new(&example_path)
+    run_executable(&example_path, args)
+}
+
+/// Run an executable with `args`
+fn run_executable(path: &str, args: &[String]) -> Result<()> {
+    println!("   Running: {}", path);
+    if !args.is_empty() {
+        println!("   Args: {:?}", args);
+    }
+    println!();
+
+    let status = std::process::Command::new(path)
         .args(args)
         .status()
-        .with_context(|| format!("Failed to run example: {}", example_path))?;
+        .with_context(|| format!("Failed to run {}", path))?;
 
     if status.success() {
         println!("✅ Run complete!");
@@ -424,7 +450,9 @@ fn clean_project(all: bool, package: Option<&str>) -> Result<()> {
 }
 
 /// Run tests
-fn run_tests(filter: Option<String>, verbose: bool, _release: bool) -> Result<()> {
+///
+/// The tests are compiled with `features` enabled.
+fn run_tests(filter: Option<String>, verbose: bool, _release: bool, features: Vec<String>) -> Result<()> {
     println!("🧪 Running tests...");
     if let Some(f) = &filter {
         println!("   Filter: {}", f);
@@ -443,7 +471,7 @@ fn run_tests(filter: Option<String>, verbose: bool, _release: bool) -> Result<()
     }
 
     // Load test metadata
-    let mut runner = test_runner::TestRunner::new();
+    let mut runner = test_runner::TestRunner::new().with_features(features);
     let mut total_loaded = 0;
 
     for test_file in &test_files {
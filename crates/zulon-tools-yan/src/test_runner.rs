@@ -36,6 +36,8 @@ pub struct Test {
 /// Test runner
 pub struct TestRunner {
     tests: Vec<Test>,
+    /// Features enabled for `cfg(feature = "...")` when compiling tests
+    features: Vec<String>,
 }
 
 impl TestRunner {
@@ -43,9 +45,16 @@ impl TestRunner {
     pub fn new() -> Self {
         TestRunner {
             tests: Vec::new(),
+            features: Vec::new(),
         }
     }
 
+    /// Compile the tests with `features` enabled
+    pub fn with_features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
+
     /// Load tests from JSON metadata file
     pub fn load_from_json(&mut self, json_path: &Path) -> Result<usize, String> {
         use std::fs;
@@ -257,8 +266,12 @@ impl TestRunner {
             // Compile merged source to LLVM IR
             println!("    📦 Compiling merged source to LLVM IR...");
             let merged_ll = temp_dir.join(format!("zulon_test_{}.ll", base_name));
-            let result1 = Command::new("cargo")
-                .args(["run", "-p", "zulon-compiler", "--"])
+            let mut compile = Command::new("cargo");
+            compile.args(["run", "-p", "zulon-compiler", "--", "--test"]);
+            if !self.features.is_empty() {
+                compile.arg("--features").arg(self.features.join(","));
+            }
+            let result1 = compile
                 .arg("-o").arg(&merged_ll)
                 .arg(&merged_source_file)
                 .output();
@@ -5,14 +5,136 @@
 //!
 //! This module implements type checking for ZULON.
 
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use crate::const_eval::{self, ConstValue};
 use crate::env::Env;
-use crate::error::{Result, TypeError};
-use crate::ty::Ty;
+use crate::error::{Result, TypeError, Warning};
+use crate::exhaustive::MatchChecker;
+use crate::ty::{subst_params, Ty};
 use crate::infer::Substitution;
 use crate::effect::EffectSet;
 use crate::effect_inference::EffectInference;
 use zulon_parser::ast::{self, Ast};
-use zulon_parser::ast::{Expression, Statement, Item, ItemKind, Type, Identifier};
+use zulon_parser::ast::visit::{self, Visitor};
+use zulon_parser::ast::{Expression, Statement, Item, ItemKind, Type, Identifier, Span};
+use zulon_parser::format::{Argument, Count, FormatKind};
+
+/// The core traits the primitive scalar types implement without an impl
+/// block, when the prelude declares them; floats implement none of `Eq`,
+/// `Ord` and `Hash`, and strings implement `Display` and `Debug` too
+const BUILTIN_TRAITS: &[&str] = &["Clone", "Copy", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "Display", "Debug"];
+
+/// A struct definition, with field types in terms of its type parameters
+#[derive(Debug, Clone)]
+pub(crate) struct StructDef {
+    pub(crate) generics: Vec<String>,
+    pub(crate) fields: Vec<(String, Ty)>,
+}
+
+/// An enum definition, with the payload types of each variant
+#[derive(Debug, Clone)]
+pub(crate) struct EnumDef {
+    pub(crate) generics: Vec<String>,
+    pub(crate) variants: Vec<(String, Vec<Ty>)>,
+    /// Field names of the variants declared with named fields, whose
+    /// payloads hold the fields in declaration order
+    pub(crate) named_fields: HashMap<String, Vec<String>>,
+    /// The discriminant of each variant, in declaration order
+    pub(crate) discriminants: Vec<i128>,
+}
+
+/// A `const` or `static` item
+#[derive(Debug)]
+struct GlobalDef {
+    type_annotation: Type,
+    value: Expression,
+    is_static: bool,
+    /// Whether this is a `static mut`, which may be assigned to
+    is_mutable: bool,
+}
+
+/// A type alias, with its type parameters
+#[derive(Debug, Clone)]
+struct AliasDef {
+    generics: Vec<String>,
+    type_annotation: Type,
+}
+
+/// The signature of a method or associated function
+#[derive(Debug, Clone)]
+struct MethodSig {
+    /// The method's own type parameters
+    generics: Vec<String>,
+    /// Traits bounding the method's own type parameters
+    bounds: HashMap<String, Vec<String>>,
+    /// Parameter types, starting with the receiver for methods taking `self`
+    params: Vec<Ty>,
+    return_type: Ty,
+    /// Whether the first parameter is `self`
+    has_receiver: bool,
+    span: Span,
+}
+
+/// A trait declaration; `Self` is `Ty::Param("Self")` in its signatures
+#[derive(Debug, Clone)]
+struct TraitDef {
+    super_traits: Vec<String>,
+    /// Associated types, with the traits bounding them
+    assoc_types: Vec<(String, Vec<String>)>,
+    /// Every method, with whether it has a default body
+    methods: Vec<(String, MethodSig, bool)>,
+}
+
+/// An impl block, inherent or of a trait
+#[derive(Debug, Clone)]
+struct ImplDef {
+    generics: Vec<String>,
+    bounds: HashMap<String, Vec<String>>,
+    trait_name: Option<String>,
+    /// The implementing type, in terms of `generics`
+    self_ty: Ty,
+    /// Associated type definitions, in terms of `generics`
+    assoc_types: Vec<(String, Ty)>,
+    /// Methods defined in the block, with `Self` replaced by `self_ty`
+    methods: Vec<(String, MethodSig)>,
+    /// Prefix of the names of the block's functions: `Point` or
+    /// `<Point as Shape>`
+    path: String,
+    span: Span,
+}
+
+/// The function a method call or associated function call resolves to
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodCallee {
+    /// A function of an impl block, with the type arguments of the impl
+    /// followed by those of the method
+    Impl {
+        function: String,
+        generic_args: Vec<Ty>,
+    },
+    /// A trait method called on a type parameter, whose impl is only known
+    /// once the parameter is substituted, on a primitive type with a
+    /// builtin impl of the trait, or by a comparison operator
+    Trait {
+        trait_name: String,
+        self_ty: Ty,
+        method: String,
+        generic_args: Vec<Ty>,
+    },
+}
+
+/// A resolved method call
+///
+/// The receiver is dereferenced `autoderefs` times and then, if `autoref`
+/// is set, borrowed (mutably for `Some(true)`) to give the `self` argument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodResolution {
+    pub callee: MethodCallee,
+    pub autoderefs: usize,
+    pub autoref: Option<bool>,
+}
 
 /// Type checker with type inference support
 pub struct TypeChecker {
@@ -39,6 +161,115 @@ pub struct TypeChecker {
 
     /// Type substitution from inference
     subst: Substitution,
+
+    /// Type parameters in scope, for the generic item being checked
+    generic_params: Vec<String>,
+
+    /// Type parameters of each generic function
+    fn_generics: HashMap<String, Vec<String>>,
+
+    /// Traits bounding the type parameters of each generic function
+    fn_bounds: HashMap<String, HashMap<String, Vec<String>>>,
+
+    /// Types that must implement a trait, with the span of the call whose
+    /// bounds require it; each is checked once its type is known
+    bound_obligations: Vec<(Ty, String, Span)>,
+
+    /// Pairs of types found equal while one of them still contained a
+    /// projection that could not be normalized, unified again once it can
+    pending_projections: Vec<(Ty, Ty, Span)>,
+
+    /// The type each associated type path such as `T::Item` names, by the
+    /// span of the associated type's name
+    assoc_type_paths: HashMap<Span, Ty>,
+
+    /// Errors found converting written types, which cannot fail on the
+    /// spot; the first is reported at the end of the pass
+    type_errors: Vec<TypeError>,
+
+    /// Struct definitions by name
+    structs: HashMap<String, StructDef>,
+
+    /// Enum definitions by name
+    enums: HashMap<String, EnumDef>,
+
+    /// Type of each checked expression, by the expression's span
+    expr_types: HashMap<Span, Ty>,
+
+    /// Type arguments of each call to a generic function, by the call's span
+    call_generics: HashMap<Span, Vec<Ty>>,
+
+    /// The type `Self` stands for, inside a trait or impl
+    self_ty: Option<Ty>,
+
+    /// Traits bounding each type parameter in scope, including `Self`
+    /// inside a trait
+    param_bounds: HashMap<String, Vec<String>>,
+
+    /// Trait declarations by name
+    traits: HashMap<String, TraitDef>,
+
+    /// Impl blocks, in source order
+    impls: Vec<ImplDef>,
+
+    /// Resolution of each method call and associated function call, by the
+    /// call's span
+    method_calls: HashMap<Span, MethodResolution>,
+
+    /// The `&dyn Trait` type each reference coerced to a trait object is
+    /// converted to, by the span of the coerced expression
+    coercions: HashMap<Span, Ty>,
+
+    /// Type of each name bound by a pattern, by the name's span
+    pattern_bindings: HashMap<Span, Ty>,
+
+    /// Enum variants imported by name, as enum and variant names
+    variant_imports: HashSet<(String, String)>,
+
+    /// Warnings found so far, in source order
+    warnings: Vec<Warning>,
+
+    /// Integer literals without a suffix, as span, value, whether negated
+    /// and type, checked against their types once those are inferred
+    int_literals: Vec<(Span, u128, bool, Ty)>,
+
+    /// Types of float literals without a suffix
+    float_literals: Vec<Ty>,
+
+    /// `const` and `static` items by name
+    globals: HashMap<String, Rc<GlobalDef>>,
+
+    /// Values of the `const` and `static` items evaluated so far
+    global_values: HashMap<String, ConstValue>,
+
+    /// The constants being evaluated, innermost last, to report constants
+    /// defined in terms of themselves
+    evaluating: Vec<String>,
+
+    /// `const fn`s by name, which constant expressions may call
+    const_fns: HashMap<String, Rc<ast::Function>>,
+
+    /// The `const` or `static` item each path names, by the span of the
+    /// path's identifier
+    global_refs: HashMap<Span, String>,
+
+    /// The length of each array type, by the span of its length expression;
+    /// `None` if the length could not be evaluated
+    array_lens: HashMap<Span, Option<u64>>,
+
+    /// Type aliases by name
+    type_aliases: HashMap<String, AliasDef>,
+
+    /// The types type aliases stand for, in terms of their type parameters
+    alias_types: HashMap<String, Ty>,
+
+    /// The type each use of a type alias stands for, by the span of the
+    /// alias's name
+    alias_uses: HashMap<Span, Ty>,
+
+    /// The type aliases being expanded, innermost last, to report aliases
+    /// defined in terms of themselves
+    expanding_aliases: Vec<String>,
 }
 
 impl TypeChecker {
@@ -53,52 +284,536 @@ impl TypeChecker {
             declared_effects: EffectSet::new(),
             effect_inference: EffectInference::new(),
             subst: Substitution::new(),
+            generic_params: Vec::new(),
+            fn_generics: HashMap::new(),
+            fn_bounds: HashMap::new(),
+            bound_obligations: Vec::new(),
+            pending_projections: Vec::new(),
+            assoc_type_paths: HashMap::new(),
+            type_errors: Vec::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            expr_types: HashMap::new(),
+            call_generics: HashMap::new(),
+            self_ty: None,
+            param_bounds: HashMap::new(),
+            traits: HashMap::new(),
+            impls: Vec::new(),
+            method_calls: HashMap::new(),
+            coercions: HashMap::new(),
+            pattern_bindings: HashMap::new(),
+            variant_imports: HashSet::new(),
+            warnings: Vec::new(),
+            int_literals: Vec::new(),
+            float_literals: Vec::new(),
+            globals: HashMap::new(),
+            global_values: HashMap::new(),
+            evaluating: Vec::new(),
+            const_fns: HashMap::new(),
+            global_refs: HashMap::new(),
+            array_lens: HashMap::new(),
+            type_aliases: HashMap::new(),
+            alias_types: HashMap::new(),
+            alias_uses: HashMap::new(),
+            expanding_aliases: Vec::new(),
         }
     }
 
+    /// The type inferred for the expression at `span`
+    ///
+    /// Available after [`check`](Self::check) succeeds. Type variables
+    /// nothing constrained are left in the result.
+    pub fn expr_type(&self, span: &Span) -> Option<&Ty> {
+        self.expr_types.get(span)
+    }
+
+    /// The type arguments inferred for the call of a generic function at
+    /// `span`, in the order of the function's type parameters
+    pub fn call_generic_args(&self, span: &Span) -> Option<&[Ty]> {
+        self.call_generics.get(span).map(Vec::as_slice)
+    }
+
+    /// What the method call `receiver.name(args)` or the associated
+    /// function call `Type::name(args)` at `span` resolved to
+    pub fn method_resolution(&self, span: &Span) -> Option<&MethodResolution> {
+        self.method_calls.get(span)
+    }
+
+    /// The trait object type the expression at `span` is coerced to, if it
+    /// is a reference to a concrete type used where a `&dyn Trait` is
+    /// expected
+    pub fn coercion(&self, span: &Span) -> Option<&Ty> {
+        self.coercions.get(span)
+    }
+
+    /// The type of the name bound by a pattern at `span`
+    pub fn binding_type(&self, span: &Span) -> Option<&Ty> {
+        self.pattern_bindings.get(span)
+    }
+
+    /// The warnings found while checking, like unreachable match arms
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// The type `Self` stands for in the impl block starting at `impl_span`
+    pub fn impl_self_ty(&self, impl_span: &Span) -> Option<&Ty> {
+        self.impl_def(impl_span).map(|imp| &imp.self_ty)
+    }
+
+    /// The name of the function that `method` of the impl block starting at
+    /// `impl_span` is lowered to: `Point::norm` for an inherent method,
+    /// `<Point as Shape>::area` for a trait method
+    pub fn impl_function_name(&self, impl_span: &Span, method: &str) -> Option<String> {
+        self.impl_def(impl_span).map(|imp| format!("{}::{}", imp.path, method))
+    }
+
+    /// The associated types the trait impl starting at `impl_span` defines
+    pub fn impl_assoc_types(&self, impl_span: &Span) -> &[(String, Ty)] {
+        self.impl_def(impl_span).map(|imp| imp.assoc_types.as_slice()).unwrap_or_default()
+    }
+
+    /// The type an associated type path such as `T::Item` or `Self::Item`
+    /// names, by the span of the associated type's name
+    ///
+    /// For a type parameter this is a [`Ty::Projection`], normalized to
+    /// the impl's type once the parameter is substituted.
+    pub fn assoc_type_path(&self, name_span: &Span) -> Option<&Ty> {
+        self.assoc_type_paths.get(name_span)
+    }
+
+    /// The value of the `const` item `name`
+    pub fn const_value(&self, name: &str) -> Option<&ConstValue> {
+        self.globals.get(name)
+            .filter(|global| !global.is_static)
+            .and_then(|_| self.global_values.get(name))
+    }
+
+    /// The initial value of the `static` item `name`
+    pub fn static_value(&self, name: &str) -> Option<&ConstValue> {
+        self.globals.get(name)
+            .filter(|global| global.is_static)
+            .and_then(|_| self.global_values.get(name))
+    }
+
+    /// The `const` or `static` item the path whose identifier is at `span`
+    /// names, unless a local variable shadows it
+    pub fn global_ref(&self, span: &Span) -> Option<&str> {
+        self.global_refs.get(span).map(String::as_str)
+    }
+
+    /// The length of the array type whose length expression is at `span`
+    pub fn array_len(&self, span: &Span) -> Option<u64> {
+        self.array_lens.get(span).copied().flatten()
+    }
+
+    /// The fields of the struct type `ty` in declaration order, with their
+    /// types for its type arguments
+    pub fn struct_field_types(&self, ty: &Ty) -> Option<Vec<(String, Ty)>> {
+        let Ty::Struct { name, generics } = ty else {
+            return None;
+        };
+        let struct_def = self.structs.get(&name.name)?;
+        let params = struct_def.generics.iter().cloned().zip(generics.iter().cloned()).collect();
+        Some(struct_def.fields.iter().map(|(field, ty)| (field.clone(), subst_params(&params, ty))).collect())
+    }
+
+    /// The discriminants of the variants of the enum `name`, in declaration
+    /// order
+    pub fn enum_discriminants(&self, name: &str) -> Option<&[i128]> {
+        self.enums.get(name).map(|enum_def| enum_def.discriminants.as_slice())
+    }
+
+    /// The type a use of a type alias stands for, by the span of the
+    /// alias's name
+    pub fn alias_type(&self, name_span: &Span) -> Option<&Ty> {
+        self.alias_uses.get(name_span)
+    }
+
+    fn impl_def(&self, impl_span: &Span) -> Option<&ImplDef> {
+        self.impls.iter().find(|imp| imp.span == *impl_span)
+    }
+
     /// Type check an entire AST
     pub fn check(&mut self, ast: &Ast) -> Result<()> {
-        // Pass 1: Collect all function and extern function signatures
+        // Pass 1: Collect all type definitions, then all function and extern
+        // function signatures
         // This enables forward declarations - functions can call functions
-        // that are defined later in the file
+        // and name types that are defined later in the file
+        for item in &ast.items {
+            match &item.kind {
+                ItemKind::Struct(struct_def) => self.declare_type_def(&struct_def.name, &struct_def.generics, false),
+                ItemKind::Enum(enum_def) => self.declare_type_def(&enum_def.name, &enum_def.generics, true),
+                _ => {}
+            }
+        }
+        // Aliases, constants and `const fn`s are declared before any type
+        // is converted, since array lengths can name constants
         for item in &ast.items {
             match &item.kind {
+                ItemKind::TypeAlias(alias) => self.declare_type_alias(alias),
+                ItemKind::Const(const_def) => self.declare_global(&const_def.name, &const_def.type_annotation, &const_def.value, false, false),
+                ItemKind::Static(static_def) => self.declare_global(&static_def.name, &static_def.type_annotation, &static_def.value, true, static_def.is_mutable),
+                ItemKind::Function(func) if func.is_const => {
+                    self.const_fns.insert(func.name.name.clone(), Rc::new(func.clone()));
+                }
+                _ => {}
+            }
+        }
+        // Traits are declared before any signature, so that bounds and
+        // associated type paths can name traits defined later
+        for item in &ast.items {
+            if let ItemKind::Trait(trait_def) = &item.kind {
+                self.declare_trait(trait_def);
+            }
+        }
+        for item in &ast.items {
+            match &item.kind {
+                ItemKind::Struct(struct_def) => self.check_struct(struct_def)?,
+                ItemKind::Enum(enum_def) => self.check_enum(enum_def)?,
                 ItemKind::Function(func) => self.collect_function_signature(func)?,
                 ItemKind::ExternFunction(func) => self.collect_function_signature(func)?,
+                ItemKind::Trait(trait_def) => self.declare_trait_methods(trait_def),
+                _ => {}
+            }
+        }
+        for item in &ast.items {
+            match &item.kind {
+                ItemKind::Impl(impl_block) => self.declare_impl(impl_block)?,
+                ItemKind::Use(use_item) => self.declare_variant_imports(use_item),
                 _ => {}
             }
         }
+        self.report_type_errors()?;
+        self.check_coherence()?;
+        self.check_trait_objects(ast)?;
 
         // Pass 2: Type check all items (including function bodies)
         for item in &ast.items {
             self.check_item(item)?;
         }
+        self.report_type_errors()?;
+
+        self.resolve_pending_projections()?;
+        self.default_literal_types()?;
+        self.resolve_pending_projections()?;
+        if let Some((ty, _, span)) = self.pending_projections.first() {
+            return Err(TypeError::InferenceError {
+                message: format!("type annotations needed to resolve `{}`", ty),
+                span: *span,
+            });
+        }
+        self.check_bound_obligations(true)?;
+        self.resolve_recorded_types()
+    }
+
+    /// Fail with the first error recorded while converting written types
+    fn report_type_errors(&mut self) -> Result<()> {
+        match self.type_errors.drain(..).next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Give unconstrained integer and float literals the types `i32` and
+    /// `f64`, then check that each integer literal fits its type
+    fn default_literal_types(&mut self) -> Result<()> {
+        for ty in self.int_literals.iter().map(|(.., ty)| ty).chain(&self.float_literals) {
+            match self.subst.apply(ty) {
+                Ty::IntVar(id) => self.subst.bind(id, &Ty::I32),
+                Ty::FloatVar(id) => self.subst.bind(id, &Ty::F64),
+                _ => {}
+            }
+        }
+
+        for (span, value, negated, ty) in &self.int_literals {
+            let ty = self.subst.apply(ty);
+            if ty.int_max_magnitude(*negated).is_some_and(|max| *value > max) {
+                return Err(TypeError::LiteralOutOfRange { ty: Box::new(ty), span: *span });
+            }
+        }
         Ok(())
     }
 
+    /// Apply the final substitution to the recorded expression types,
+    /// binding types and generic arguments
+    ///
+    /// Every generic call must have had its type arguments inferred.
+    fn resolve_recorded_types(&mut self) -> Result<()> {
+        let mut expr_types = std::mem::take(&mut self.expr_types);
+        let mut coercions = std::mem::take(&mut self.coercions);
+        let mut pattern_bindings = std::mem::take(&mut self.pattern_bindings);
+        for ty in expr_types.values_mut()
+            .chain(coercions.values_mut())
+            .chain(pattern_bindings.values_mut())
+        {
+            *ty = self.normalize(ty);
+        }
+        self.expr_types = expr_types;
+        self.coercions = coercions;
+        self.pattern_bindings = pattern_bindings;
+
+        let mut uninferred = None;
+        let mut call_generics = std::mem::take(&mut self.call_generics);
+        for (span, args) in &mut call_generics {
+            for arg in args.iter_mut() {
+                *arg = self.normalize(arg);
+            }
+            if args.iter().any(Ty::has_ty_vars) {
+                uninferred = Some(*span);
+            }
+        }
+        self.call_generics = call_generics;
+        let mut method_calls = std::mem::take(&mut self.method_calls);
+        for (span, resolution) in &mut method_calls {
+            let args = match &mut resolution.callee {
+                MethodCallee::Impl { generic_args, .. } => generic_args,
+                MethodCallee::Trait { self_ty, generic_args, .. } => {
+                    *self_ty = self.normalize(self_ty);
+                    generic_args
+                }
+            };
+            for arg in args.iter_mut() {
+                *arg = self.normalize(arg);
+            }
+            if args.iter().any(Ty::has_ty_vars) {
+                uninferred = Some(*span);
+            }
+        }
+        self.method_calls = method_calls;
+        match uninferred {
+            Some(span) => Err(TypeError::InferenceError {
+                message: "cannot infer the type arguments of this generic call".to_string(),
+                span,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Register a struct or enum by name, so that types can refer to it
+    /// before its fields are known
+    fn declare_type_def(&mut self, name: &Identifier, generics: &Option<ast::Generics>, is_enum: bool) {
+        let generics = type_param_names(generics).into_iter().map(Ty::Param).collect();
+        let ty = if is_enum {
+            Ty::Enum { name: name.clone(), generics }
+        } else {
+            Ty::Struct { name: name.clone(), generics }
+        };
+        self.env.insert_type_def(name.name.clone(), ty);
+    }
+
+    /// Register a type alias by name; the type it stands for is converted
+    /// where the alias is used
+    fn declare_type_alias(&mut self, alias: &ast::TypeAlias) {
+        self.type_aliases.insert(alias.name.name.clone(), AliasDef {
+            generics: type_param_names(&alias.generics),
+            type_annotation: alias.type_annotation.clone(),
+        });
+    }
+
+    /// Register a `const` or `static` item by name; its value is evaluated
+    /// when it is checked, or earlier if a constant expression needs it
+    fn declare_global(
+        &mut self,
+        name: &Identifier,
+        type_annotation: &Type,
+        value: &Expression,
+        is_static: bool,
+        is_mutable: bool,
+    ) {
+        self.globals.insert(name.name.clone(), Rc::new(GlobalDef {
+            type_annotation: type_annotation.clone(),
+            value: value.clone(),
+            is_static,
+            is_mutable,
+        }));
+    }
+
+    /// The declared type of the `const` or `static` item `name`
+    fn global_ty(&mut self, name: &str) -> Option<Ty> {
+        let global = self.globals.get(name)?.clone();
+        Some(self.with_generic_params(Vec::new(), |this| this.ast_type_to_ty(&global.type_annotation)))
+    }
+
+    /// The value and type of the `const` item `name`, evaluating it if
+    /// this has not been done yet, or `None` if there is no such item
+    pub(crate) fn global_value(&mut self, name: &str, span: &Span) -> Result<Option<(ConstValue, Ty)>> {
+        let Some(global) = self.globals.get(name).cloned() else {
+            return Ok(None);
+        };
+        if global.is_static {
+            return Err(TypeError::NotConst {
+                what: format!("referring to static `{}`", name),
+                span: *span,
+            });
+        }
+        if self.evaluating.iter().any(|evaluating| evaluating == name) {
+            return Err(TypeError::ConstCycle { name: name.to_string(), span: *span });
+        }
+
+        // The type may itself contain array lengths naming the constant
+        self.evaluating.push(name.to_string());
+        let ty = self.with_generic_params(Vec::new(), |this| this.ast_type_to_ty(&global.type_annotation));
+        let result = match self.global_values.get(name) {
+            Some(value) => Ok(value.clone()),
+            None => const_eval::evaluate(self, &global.value, &ty),
+        };
+        self.evaluating.pop();
+
+        let value = result?;
+        self.global_values.insert(name.to_string(), value.clone());
+        Ok(Some((value, ty)))
+    }
+
+    /// The `const fn` `name`: `Some(None)` if `name` is a function that is
+    /// not `const`, `None` if there is no such function
+    pub(crate) fn const_fn(&self, name: &str) -> Option<Option<Rc<ast::Function>>> {
+        match self.const_fns.get(name) {
+            Some(func) => Some(Some(func.clone())),
+            None => self.env.lookup_function(name).map(|_| None),
+        }
+    }
+
+    /// The fields of the struct `name` in declaration order, with the types
+    /// of those whose type does not depend on the struct's type parameters
+    pub(crate) fn struct_fields(&self, name: &str) -> Vec<(String, Option<Ty>)> {
+        let Some(struct_def) = self.structs.get(name) else {
+            return Vec::new();
+        };
+        struct_def.fields.iter()
+            .map(|(field, ty)| {
+                let is_generic = struct_def.generics.iter().any(|param| ty.mentions_param(param));
+                (field.clone(), (!is_generic).then(|| ty.clone()))
+            })
+            .collect()
+    }
+
+    /// The length of the array type whose length expression is `len`
+    ///
+    /// Failures are recorded in `type_errors`, giving an array of unknown
+    /// length.
+    fn array_type_len(&mut self, len: &Expression) -> Option<u64> {
+        if let Some(len) = self.array_lens.get(&len.span) {
+            return *len;
+        }
+        let value = match const_eval::evaluate(self, len, &Ty::USize) {
+            Ok(ConstValue::Int(value)) => u64::try_from(value).ok(),
+            Ok(_) => None,
+            Err(error) => {
+                self.type_errors.push(error);
+                None
+            }
+        };
+        self.array_lens.insert(len.span, value);
+        value
+    }
+
+    /// The type the alias `name` stands for with `args` for its type
+    /// parameters, or `None` if `name` is not a type alias
+    fn expand_type_alias(&mut self, name: &Identifier, args: Vec<Ty>) -> Option<Ty> {
+        let alias = self.type_aliases.get(&name.name)?.clone();
+        if self.expanding_aliases.contains(&name.name) {
+            self.type_errors.push(TypeError::RecursiveType {
+                ty: Box::new(Ty::Param(name.name.clone())),
+                span: name.span,
+            });
+            return Some(self.env.fresh_ty_var());
+        }
+        if args.len() != alias.generics.len() {
+            self.type_errors.push(TypeError::ArityMismatch {
+                expected: alias.generics.len(),
+                found: args.len(),
+                span: name.span,
+            });
+            return Some(self.env.fresh_ty_var());
+        }
+
+        let ty = match self.alias_types.get(&name.name) {
+            Some(ty) => ty.clone(),
+            None => {
+                self.expanding_aliases.push(name.name.clone());
+                let ty = self.with_generic_params(alias.generics.clone(), |this| this.ast_type_to_ty(&alias.type_annotation));
+                self.expanding_aliases.pop();
+                ty
+            }
+        };
+        let params = alias.generics.iter().cloned().zip(args).collect();
+        let ty = subst_params(&params, &ty);
+        self.alias_uses.insert(name.span, ty.clone());
+        Some(ty)
+    }
+
+    /// Run `f` with `params` as the type parameters in scope
+    fn with_generic_params<T>(&mut self, params: Vec<String>, f: impl FnOnce(&mut Self) -> T) -> T {
+        let prev_params = std::mem::replace(&mut self.generic_params, params);
+        let result = f(self);
+        self.generic_params = prev_params;
+        result
+    }
+
+    /// Run `f` with `params` as the type parameters in scope, bounded by
+    /// the traits in `bounds`
+    fn with_bounded_params<T>(
+        &mut self,
+        params: Vec<String>,
+        bounds: HashMap<String, Vec<String>>,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let prev_bounds = std::mem::replace(&mut self.param_bounds, bounds);
+        let result = self.with_generic_params(params, f);
+        self.param_bounds = prev_bounds;
+        result
+    }
+
+    /// Run `f` with `Self` standing for `self_ty`
+    fn with_self_ty<T>(&mut self, self_ty: Ty, f: impl FnOnce(&mut Self) -> T) -> T {
+        let prev_self_ty = self.self_ty.replace(self_ty);
+        let result = f(self);
+        self.self_ty = prev_self_ty;
+        result
+    }
+
+    /// A fresh type variable for each of `params`
+    fn instantiate(&mut self, params: &[String]) -> HashMap<String, Ty> {
+        params.iter()
+            .map(|param| (param.clone(), self.env.fresh_ty_var()))
+            .collect()
+    }
+
     /// Collect function signature (for forward declarations)
     /// This is called in Pass 1 to register all functions before checking bodies
     fn collect_function_signature(&mut self, func: &ast::Function) -> Result<()> {
-        // Create function type from signature
-        let param_types: Vec<Ty> = func.params.iter()
-            .map(|p| {
-                p.type_annotation.as_ref()
-                    .map(|ty| self.ast_type_to_ty(ty))
-                    .unwrap_or(Ty::Unit)
-            })
-            .collect();
+        let generics = type_param_names(&func.generics);
+        if !generics.is_empty() {
+            self.fn_generics.insert(func.name.name.clone(), generics.clone());
+        }
+        let bounds = self.trait_bounds(&func.generics)?;
+        if !bounds.is_empty() {
+            self.fn_bounds.insert(func.name.name.clone(), bounds.clone());
+        }
 
-        let return_type = func.return_type.as_ref()
-            .map(|ty| self.ast_type_to_ty(ty))
-            .unwrap_or(Ty::Unit);
+        // Create function type from signature; type parameters stay
+        // `Ty::Param` until each call instantiates them
+        let (param_types, return_type) = self.with_bounded_params(generics, bounds, |this| {
+            let param_types: Vec<Ty> = func.params.iter()
+                .map(|p| {
+                    p.type_annotation.as_ref()
+                        .map(|ty| this.ast_type_to_ty(ty))
+                        .unwrap_or(Ty::Unit)
+                })
+                .collect();
+
+            let return_type = func.return_type.as_ref()
+                .map(|ty| this.ast_type_to_ty(ty))
+                .unwrap_or(Ty::Unit);
 
-        // Mark known variadic C functions
-        let is_varadic = matches!(func.name.name.as_str(), "printf" | "scanf");
+            (param_types, return_type)
+        });
 
         let func_ty = Ty::Function {
             params: param_types,
             return_type: Box::new(return_type),
-            variadic: is_varadic,
+            variadic: func.is_variadic,
         };
 
         // Insert function into environment (signature only, no body yet)
@@ -123,11 +838,29 @@ impl TypeChecker {
             ItemKind::Use(use_stmt) => self.check_use(use_stmt),
             ItemKind::ExternCrate(extern_crate) => self.check_extern_crate(extern_crate),
             ItemKind::Effect(effect) => self.check_effect(effect),
+            // Invocations were expanded before parsing
+            ItemKind::MacroDef(_) => Ok(()),
+            // Recovered parse errors have already been reported
+            ItemKind::Error => Ok(()),
         }
     }
 
     /// Type check a function
     fn check_function(&mut self, func: &ast::Function) -> Result<()> {
+        // The body is checked once, with its type parameters left abstract
+        let generics = type_param_names(&func.generics);
+        if !generics.is_empty() {
+            self.fn_generics.insert(func.name.name.clone(), generics.clone());
+        }
+        let bounds = self.trait_bounds(&func.generics)?;
+        self.with_bounded_params(generics, bounds, |this| this.check_function_body(func, &func.name.name))
+    }
+
+    /// Type check a function's signature and body
+    ///
+    /// The function's type and effects are recorded under `name`, which
+    /// for methods is the name of the function they are lowered to.
+    fn check_function_body(&mut self, func: &ast::Function, name: &str) -> Result<()> {
         // Create function type from signature
         let param_types: Vec<Ty> = func.params.iter()
             .map(|p| {
@@ -142,11 +875,7 @@ impl TypeChecker {
             .unwrap_or(Ty::Unit);
 
         // Extract error type if present (from `-> Type | Error` syntax)
-        let error_type = if let Some(ast_error_type) = &func.error_type {
-            Some(self.ast_type_to_ty(ast_error_type))
-        } else {
-            None
-        };
+        let error_type = func.error_type.as_ref().map(|ty| self.ast_type_to_ty(ty));
 
         let func_ty = Ty::Function {
             params: param_types.clone(),
@@ -155,7 +884,7 @@ impl TypeChecker {
         };
 
         // Insert function into environment
-        self.env.insert_function(func.name.name.clone(), func_ty);
+        self.env.insert_function(name.to_string(), func_ty);
 
         // Enter function scope
         let mut func_env = self.env.enter_scope();
@@ -184,7 +913,7 @@ impl TypeChecker {
         // Process effects from function signature (e.g., `-> i32 | Log`)
         for effect_ty in &func.effects {
             let (effect_name, span) = match &effect_ty {
-                Type::Simple(ident) => (ident.name.clone(), ident.span.clone()),
+                Type::Simple(ident) => (ident.name.clone(), ident.span),
                 _ => continue, // Skip complex types for now
             };
 
@@ -227,18 +956,26 @@ impl TypeChecker {
 
         // Store the function's effect set in the environment
         self.env.insert_function_effects(
-            func.name.name.clone(),
+            name.to_string(),
             self.current_effect_set.clone(),
         );
 
         // Validate that the body's result type matches the declared return type
-        if &body_result_ty != &return_type {
+        let body_result_ty = self.apply_subst(&body_result_ty);
+        let coerced = match &func.body.trailing_expr {
+            Some(trailing) if body_result_ty.is_ref() && is_trait_object_ref(&return_type) => {
+                self.coerce(&body_result_ty, &return_type, &trailing.span)?;
+                true
+            }
+            _ => false,
+        };
+        if !coerced && self.unify(&return_type, &body_result_ty, &func.body.span).is_err() {
             // Allow Never type (throw/return) in any position
             if !matches!(body_result_ty, Ty::Never) {
                 return Err(TypeError::TypeMismatch {
-                    expected: return_type.clone(),
-                    found: body_result_ty,
-                    span: func.body.span.clone(),
+                    expected: Box::new(return_type.clone()),
+                    found: Box::new(body_result_ty),
+                    span: func.body.span,
                 });
             }
         }
@@ -250,8 +987,10 @@ impl TypeChecker {
         self.current_effect_set = prev_effect_set;
         self.declared_effects = prev_declared_effects;
 
-        // Exit function scope - swap back to parent environment
+        // Exit function scope - swap back to parent environment, keeping
+        // type variable IDs unique
         std::mem::swap(&mut self.env, &mut func_env);
+        self.env.advance_ty_vars_to(func_env.peek_next_ty_var());
 
         Ok(())
     }
@@ -271,13 +1010,10 @@ impl TypeChecker {
             .map(|ty| self.ast_type_to_ty(ty))
             .unwrap_or(Ty::Unit);
 
-        // Mark known C variadic functions
-        let is_varadic = matches!(func.name.name.as_str(), "printf" | "scanf");
-
         let func_ty = Ty::Function {
             params: param_types.clone(),
             return_type: Box::new(return_type.clone()),
-            variadic: is_varadic,
+            variadic: func.is_variadic,
         };
 
         // Insert extern function into environment
@@ -286,71 +1022,590 @@ impl TypeChecker {
         Ok(())
     }
 
-    /// Type check a struct
+    /// Type check a struct, recording its field types
     fn check_struct(&mut self, struct_def: &ast::Struct) -> Result<()> {
-        // For now, just register the struct type
-        // TODO: Handle generics, field types, etc.
-        let struct_ty = Ty::Struct {
-            name: struct_def.name.clone(),
-            generics: vec![],
-        };
+        self.declare_type_def(&struct_def.name, &struct_def.generics, false);
 
-        self.env.insert_type_def(struct_def.name.name.clone(), struct_ty);
+        let generics = type_param_names(&struct_def.generics);
+        let fields = self.with_generic_params(generics.clone(), |this| {
+            struct_def.fields.iter()
+                .map(|field| (field.name.name.clone(), this.ast_type_to_ty(&field.type_annotation)))
+                .collect()
+        });
+
+        self.structs.insert(struct_def.name.name.clone(), StructDef { generics, fields });
         Ok(())
     }
 
-    /// Type check an enum
+    /// Type check an enum, recording the payload types of its variants
     fn check_enum(&mut self, enum_def: &ast::Enum) -> Result<()> {
-        // For now, just register the enum type
-        // TODO: Handle generics, variant types, etc.
-        let enum_ty = Ty::Enum {
-            name: enum_def.name.clone(),
-            generics: vec![],
+        self.declare_type_def(&enum_def.name, &enum_def.generics, true);
+
+        let generics = type_param_names(&enum_def.generics);
+        let variants = self.with_generic_params(generics.clone(), |this| {
+            enum_def.variants.iter()
+                .map(|variant| {
+                    let payload = variant.fields.iter()
+                        .map(|field| match field {
+                            ast::VariantField::Named(_, ty) | ast::VariantField::Unnamed(ty) => this.ast_type_to_ty(ty),
+                        })
+                        .collect();
+                    (variant.name.name.clone(), payload)
+                })
+                .collect()
+        });
+        let named_fields = enum_def.variants.iter()
+            .filter(|variant| matches!(variant.fields.first(), Some(ast::VariantField::Named(..))))
+            .map(|variant| {
+                let names = variant.fields.iter()
+                    .filter_map(|field| match field {
+                        ast::VariantField::Named(name, _) => Some(name.name.clone()),
+                        ast::VariantField::Unnamed(_) => None,
+                    })
+                    .collect();
+                (variant.name.name.clone(), names)
+            })
+            .collect();
+
+        // Variants without an explicit discriminant count up from the
+        // previous one, starting at 0
+        let mut discriminants: Vec<i128> = Vec::new();
+        for variant in &enum_def.variants {
+            let value = match &variant.discriminant {
+                Some(expr) => match const_eval::evaluate(self, expr, &Ty::ISize)? {
+                    ConstValue::Int(value) => value,
+                    _ => unreachable!("discriminants are evaluated as `isize`"),
+                },
+                None => match discriminants.last() {
+                    Some(previous) => {
+                        let max = Ty::ISize.int_max_magnitude(false).unwrap_or_default() as i128;
+                        if *previous >= max {
+                            return Err(TypeError::ConstOverflow { ty: Box::new(Ty::ISize), span: variant.name.span });
+                        }
+                        previous + 1
+                    }
+                    None => 0,
+                },
+            };
+            if discriminants.contains(&value) {
+                let span = variant.discriminant.as_ref().map_or(variant.name.span, |expr| expr.span);
+                return Err(TypeError::DuplicateDiscriminant { value, span });
+            }
+            discriminants.push(value);
+        }
+
+        self.enums.insert(enum_def.name.name.clone(), EnumDef { generics, variants, named_fields, discriminants });
+        Ok(())
+    }
+
+    /// The signature of a method or associated function of a trait or impl;
+    /// the parameters of the enclosing item must be in scope
+    fn method_sig(&mut self, func: &ast::Function) -> MethodSig {
+        let generics = type_param_names(&func.generics);
+        let mut params = self.generic_params.clone();
+        params.extend(generics.iter().cloned());
+        // Undeclared traits are reported when the body is checked
+        let bounds = self.trait_bounds(&func.generics).unwrap_or_default();
+        let mut all_bounds = self.param_bounds.clone();
+        for (param, traits) in &bounds {
+            all_bounds.entry(param.clone()).or_default().extend(traits.iter().cloned());
+        }
+
+        self.with_bounded_params(params, all_bounds, |this| MethodSig {
+            generics,
+            bounds,
+            params: func.params.iter()
+                .map(|p| p.type_annotation.as_ref().map(|ty| this.ast_type_to_ty(ty)).unwrap_or(Ty::Unit))
+                .collect(),
+            return_type: func.return_type.as_ref().map(|ty| this.ast_type_to_ty(ty)).unwrap_or(Ty::Unit),
+            has_receiver: func.params.first().is_some_and(|p| p.name.name == "self"),
+            span: func.name.span,
+        })
+    }
+
+    /// Record the enum variants a `use` item brings into scope, like the
+    /// prelude's `use Option::{Some, None}`
+    fn declare_variant_imports(&mut self, use_item: &ast::Use) {
+        let (enum_name, variants): (&Identifier, Vec<String>) = match &use_item.path {
+            ast::UsePath::Simple(path) if path.len() >= 2 && use_item.alias.is_none() => {
+                (&path[path.len() - 2], vec![path[path.len() - 1].name.clone()])
+            }
+            ast::UsePath::List(prefix, names) if !prefix.is_empty() => {
+                (&prefix[prefix.len() - 1], names.iter().map(|name| name.name.clone()).collect())
+            }
+            ast::UsePath::Glob(prefix) if !prefix.is_empty() => {
+                let enum_name = &prefix[prefix.len() - 1];
+                let variants = self.enums.get(&enum_name.name)
+                    .map(|def| def.variants.iter().map(|(variant, _)| variant.clone()).collect())
+                    .unwrap_or_default();
+                (enum_name, variants)
+            }
+            _ => return,
+        };
+        let Some(enum_def) = self.enums.get(&enum_name.name) else {
+            return;
         };
+        let imported: Vec<(String, String)> = variants.into_iter()
+            .filter(|variant| enum_def.variants.iter().any(|(name, _)| name == variant))
+            .map(|variant| (enum_name.name.clone(), variant))
+            .collect();
+        self.variant_imports.extend(imported);
+    }
+
+    /// Record a trait's supertraits and associated types
+    ///
+    /// Its methods are recorded by
+    /// [`declare_trait_methods`](Self::declare_trait_methods) once every
+    /// trait is known.
+    fn declare_trait(&mut self, trait_def: &ast::Trait) {
+        let super_traits = trait_def.super_traits.iter()
+            .filter_map(trait_ref_name)
+            .map(|name| name.name.clone())
+            .collect();
+        let assoc_types = trait_def.items.iter()
+            .filter_map(|item| match &item.kind {
+                ast::TraitItemKind::AssociatedType(name, bounds) => {
+                    let bounds = bounds.iter().filter_map(trait_ref_name).map(|bound| bound.name.clone()).collect();
+                    Some((name.name.clone(), bounds))
+                }
+                _ => None,
+            })
+            .collect();
+        let def = TraitDef { super_traits, assoc_types, methods: Vec::new() };
+        self.traits.insert(trait_def.name.name.clone(), def);
+    }
+
+    /// Record the method signatures of a trait, in which `Self` is a type
+    /// parameter bounded by the trait
+    fn declare_trait_methods(&mut self, trait_def: &ast::Trait) {
+        let generics = type_param_names(&trait_def.generics);
+        let bounds = HashMap::from([("Self".to_string(), vec![trait_def.name.name.clone()])]);
+        let methods = self.with_self_ty(Ty::Param("Self".to_string()), |this| {
+            this.with_bounded_params(generics, bounds, |this| {
+                trait_def.items.iter()
+                    .filter_map(|item| match &item.kind {
+                        ast::TraitItemKind::Method(func) => Some((func.name.name.clone(), this.method_sig(func), true)),
+                        ast::TraitItemKind::RequiredMethod(func) => {
+                            Some((func.name.name.clone(), this.method_sig(func), false))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+        });
+        if let Some(def) = self.traits.get_mut(&trait_def.name.name) {
+            def.methods = methods;
+        }
+    }
+
+    /// Record an impl block, checking it against its trait
+    ///
+    /// Inherent impls are only allowed for structs and enums. A trait impl
+    /// must define every method of the trait that has no default body, and
+    /// only methods of the trait, with the trait's signatures. It must
+    /// define exactly the associated types of the trait.
+    fn declare_impl(&mut self, impl_block: &ast::Impl) -> Result<()> {
+        let generics = type_param_names(&impl_block.generics);
+        let bounds = self.trait_bounds(&impl_block.generics)?;
+        let self_ty = self.with_generic_params(generics.clone(), |this| this.ast_type_to_ty(&impl_block.self_type));
+
+        let trait_name = match &impl_block.trait_name {
+            Some(trait_ref) => {
+                let Some(name) = trait_ref_name(trait_ref) else {
+                    return Err(TypeError::InferenceError {
+                        message: "expected a trait name".to_string(),
+                        span: impl_block.impl_span,
+                    });
+                };
+                if !self.traits.contains_key(&name.name) {
+                    return Err(TypeError::UndefinedTrait {
+                        name: name.name.clone(),
+                        span: name.span,
+                    });
+                }
+                Some(name.name.clone())
+            }
+            None => {
+                if !matches!(self_ty, Ty::Struct { .. } | Ty::Enum { .. }) {
+                    return Err(TypeError::OrphanImpl {
+                        ty: Box::new(self_ty),
+                        span: impl_block.impl_span,
+                    });
+                }
+                None
+            }
+        };
+
+        let assoc_types: Vec<(String, Ty)> = self.with_self_ty(self_ty.clone(), |this| {
+            this.with_bounded_params(generics.clone(), bounds.clone(), |this| {
+                impl_block.assoc_types.iter().map(|(name, ty)| (name.name.clone(), this.ast_type_to_ty(ty))).collect()
+            })
+        });
+        self.check_impl_assoc_types(trait_name.as_deref(), impl_block)?;
+
+        // The impl is recorded before its methods, so that `Self::Item`
+        // in their signatures resolves to its associated type
+        let path = match &trait_name {
+            Some(trait_name) => format!("<{} as {}>", self_ty, trait_name),
+            None => self_ty.to_string(),
+        };
+        self.impls.push(ImplDef {
+            generics: generics.clone(),
+            bounds: bounds.clone(),
+            trait_name: trait_name.clone(),
+            self_ty: self_ty.clone(),
+            assoc_types,
+            methods: Vec::new(),
+            path,
+            span: impl_block.impl_span,
+        });
+
+        let methods: Vec<(String, MethodSig)> = self.with_self_ty(self_ty.clone(), |this| {
+            this.with_bounded_params(generics, bounds, |this| {
+                impl_block.items.iter().map(|func| (func.name.name.clone(), this.method_sig(func))).collect()
+            })
+        });
+        for (i, (name, sig)) in methods.iter().enumerate() {
+            if methods[..i].iter().any(|(other, _)| other == name) {
+                return Err(TypeError::DuplicateMethod {
+                    method: name.clone(),
+                    ty: Box::new(self_ty),
+                    span: sig.span,
+                });
+            }
+        }
+
+        if let Some(trait_name) = &trait_name {
+            self.check_impl_against_trait(trait_name, &self_ty, &methods, impl_block.impl_span)?;
+        }
+        if let Some(imp) = self.impls.last_mut() {
+            imp.methods = methods;
+        }
+        Ok(())
+    }
+
+    /// Check that an impl of `trait_name` defines each associated type of
+    /// the trait once, and no others; inherent impls define none
+    fn check_impl_assoc_types(&self, trait_name: Option<&str>, impl_block: &ast::Impl) -> Result<()> {
+        let Some(trait_name) = trait_name else {
+            return match impl_block.assoc_types.first() {
+                Some((name, _)) => Err(TypeError::InferenceError {
+                    message: format!("associated type `{}` outside of a trait impl", name.name),
+                    span: name.span,
+                }),
+                None => Ok(()),
+            };
+        };
+
+        let declared = &self.traits[trait_name].assoc_types;
+        for (i, (name, _)) in impl_block.assoc_types.iter().enumerate() {
+            if impl_block.assoc_types[..i].iter().any(|(other, _)| other.name == name.name) {
+                return Err(TypeError::InferenceError {
+                    message: format!("associated type `{}` is defined more than once", name.name),
+                    span: name.span,
+                });
+            }
+            if !declared.iter().any(|(assoc, _)| *assoc == name.name) {
+                return Err(TypeError::AssocTypeNotInTrait {
+                    name: name.name.clone(),
+                    trait_name: trait_name.to_string(),
+                    span: name.span,
+                });
+            }
+        }
+        for (assoc, _) in declared {
+            if !impl_block.assoc_types.iter().any(|(name, _)| name.name == *assoc) {
+                return Err(TypeError::MissingAssocType {
+                    name: assoc.clone(),
+                    trait_name: trait_name.to_string(),
+                    span: impl_block.impl_span,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the methods of an impl of `trait_name` for `self_ty` match
+    /// the trait's
+    fn check_impl_against_trait(
+        &mut self,
+        trait_name: &str,
+        self_ty: &Ty,
+        methods: &[(String, MethodSig)],
+        span: Span,
+    ) -> Result<()> {
+        let trait_def = self.traits[trait_name].clone();
+
+        for (name, sig) in methods {
+            let Some((_, trait_sig, _)) = trait_def.methods.iter().find(|(method, ..)| method == name) else {
+                return Err(TypeError::MethodNotInTrait {
+                    method: name.clone(),
+                    trait_name: trait_name.to_string(),
+                    span: sig.span,
+                });
+            };
+
+            // The trait's signature with `Self` and the method's type
+            // parameters named as in the impl
+            let mut args: HashMap<String, Ty> = trait_sig.generics.iter().cloned()
+                .zip(sig.generics.iter().cloned().map(Ty::Param))
+                .collect();
+            args.insert("Self".to_string(), self_ty.clone());
+            let expected = self.normalize(&Ty::Function {
+                params: trait_sig.params.iter().map(|ty| subst_params(&args, ty)).collect(),
+                return_type: Box::new(subst_params(&args, &trait_sig.return_type)),
+                variadic: false,
+            });
+            let found = Ty::Function {
+                params: sig.params.clone(),
+                return_type: Box::new(sig.return_type.clone()),
+                variadic: false,
+            };
+            if trait_sig.generics.len() != sig.generics.len() || expected != found {
+                return Err(TypeError::TypeMismatch { expected: Box::new(expected), found: Box::new(found), span: sig.span });
+            }
+        }
+
+        for (name, _, has_default) in &trait_def.methods {
+            if !has_default && !methods.iter().any(|(method, _)| method == name) {
+                return Err(TypeError::MissingTraitMethod {
+                    method: name.clone(),
+                    trait_name: trait_name.to_string(),
+                    span,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that no two impls overlap, and that every trait impl comes
+    /// with impls of the trait's supertraits and associated types
+    /// implementing the traits that bound them
+    ///
+    /// Two impls of a trait overlap when some type matches both of their
+    /// self types; two inherent impls may only overlap if they define
+    /// different methods.
+    fn check_coherence(&mut self) -> Result<()> {
+        for j in 0..self.impls.len() {
+            for i in 0..j {
+                let (a, b) = (self.impls[i].clone(), self.impls[j].clone());
+                if a.trait_name != b.trait_name || !self.impls_overlap(&a, &b) {
+                    continue;
+                }
+                match &b.trait_name {
+                    Some(trait_name) => {
+                        return Err(TypeError::ConflictingImpls {
+                            trait_name: trait_name.clone(),
+                            ty: Box::new(b.self_ty),
+                            span: b.span,
+                        });
+                    }
+                    None => {
+                        let duplicate = b.methods.iter().find(|(name, _)| a.methods.iter().any(|(other, _)| other == name));
+                        if let Some((name, sig)) = duplicate {
+                            return Err(TypeError::DuplicateMethod {
+                                method: name.clone(),
+                                ty: Box::new(b.self_ty),
+                                span: sig.span,
+                            });
+                        }
+                    }
+                }
+            }
+        }
 
-        self.env.insert_type_def(enum_def.name.name.clone(), enum_ty);
+        for imp in self.impls.clone() {
+            let Some(trait_name) = &imp.trait_name else { continue };
+            for super_trait in self.traits[trait_name].super_traits.clone() {
+                let implemented = self.impls.clone().iter()
+                    .any(|other| other.trait_name.as_ref() == Some(&super_trait) && self.impls_overlap(&imp, other));
+                if !implemented {
+                    return Err(TypeError::TraitBoundNotSatisfied {
+                        trait_name: super_trait,
+                        ty: Box::new(imp.self_ty),
+                        span: imp.span,
+                    });
+                }
+            }
+
+            for (name, bounds) in self.traits[trait_name].assoc_types.clone() {
+                let Some((_, ty)) = imp.assoc_types.iter().find(|(assoc, _)| *assoc == name) else { continue };
+                for bound in bounds {
+                    let implemented = self.with_bounded_params(imp.generics.clone(), imp.bounds.clone(), |this| {
+                        this.implements_trait(ty, &bound, &imp.span)
+                    });
+                    if !implemented {
+                        return Err(TypeError::TraitBoundNotSatisfied {
+                            trait_name: bound,
+                            ty: Box::new(ty.clone()),
+                            span: imp.span,
+                        });
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
-    /// Type check a trait
-    fn check_trait(&mut self, _trait_def: &ast::Trait) -> Result<()> {
-        // TODO: Implement trait checking
+    /// Check that every `dyn Trait` type names a declared, object-safe trait
+    ///
+    /// A trait is object safe if it and its supertraits only have methods
+    /// taking `&self` or `&mut self`, without type parameters and without
+    /// `Self` anywhere else in their signatures, so that each can be called
+    /// through a vtable.
+    fn check_trait_objects(&self, ast: &Ast) -> Result<()> {
+        struct TraitObjects<'ast>(Vec<&'ast Identifier>);
+
+        impl<'ast> Visitor<'ast> for TraitObjects<'ast> {
+            fn visit_type(&mut self, ty: &'ast Type) {
+                if let Type::TraitObject(inner) = ty {
+                    self.0.extend(trait_ref_name(inner));
+                }
+                visit::walk_type(self, ty);
+            }
+        }
+
+        let mut objects = TraitObjects(Vec::new());
+        objects.visit_ast(ast);
+        for name in objects.0 {
+            if !self.traits.contains_key(&name.name) {
+                return Err(TypeError::UndefinedTrait {
+                    name: name.name.clone(),
+                    span: name.span,
+                });
+            }
+            for trait_name in self.with_super_traits(vec![name.name.clone()]) {
+                for (method, sig, _) in &self.traits[&trait_name].methods {
+                    let reason = if !sig.has_receiver {
+                        format!("associated function `{}` has no `self` parameter", method)
+                    } else if !matches!(&sig.params[0], Ty::Ref { inner, .. } if **inner == Ty::Param("Self".to_string())) {
+                        format!("method `{}` takes `self` by value", method)
+                    } else if !sig.generics.is_empty() {
+                        format!("method `{}` has type parameters", method)
+                    } else if sig.params[1..].iter().chain([&sig.return_type]).any(|ty| ty.mentions_param("Self")) {
+                        format!("method `{}` refers to the `Self` type in its signature", method)
+                    } else {
+                        continue;
+                    };
+                    return Err(TypeError::NotObjectSafe {
+                        trait_name: name.name.clone(),
+                        reason,
+                        span: name.span,
+                    });
+                }
+            }
+        }
         Ok(())
     }
 
-    /// Type check an impl block
-    fn check_impl(&mut self, _impl_block: &ast::Impl) -> Result<()> {
-        // TODO: Implement impl checking
+    /// Whether some type matches the self types of both `a` and `b`
+    fn impls_overlap(&mut self, a: &ImplDef, b: &ImplDef) -> bool {
+        let a_args = self.instantiate(&a.generics);
+        let b_args = self.instantiate(&b.generics);
+        let a_ty = subst_params(&a_args, &a.self_ty);
+        let b_ty = subst_params(&b_args, &b.self_ty);
+        self.types_unify(&a_ty, &b_ty, &b.span)
+    }
+
+    /// Type check the default method bodies of a trait
+    ///
+    /// They are checked once, with `Self` a type parameter bounded by the
+    /// trait.
+    fn check_trait(&mut self, trait_def: &ast::Trait) -> Result<()> {
+        let trait_name = &trait_def.name.name;
+        let trait_generics = type_param_names(&trait_def.generics);
+
+        for item in &trait_def.items {
+            let ast::TraitItemKind::Method(func) = &item.kind else { continue };
+            let mut generics = trait_generics.clone();
+            generics.extend(type_param_names(&func.generics));
+            let mut bounds = self.trait_bounds(&func.generics)?;
+            bounds.entry("Self".to_string()).or_default().push(trait_name.clone());
+
+            let name = format!("{}::{}", trait_name, func.name.name);
+            self.with_self_ty(Ty::Param("Self".to_string()), |this| {
+                this.with_bounded_params(generics, bounds, |this| this.check_function_body(func, &name))
+            })?;
+        }
         Ok(())
     }
 
-    /// Type check a type alias
-    fn check_type_alias(&mut self, _type_alias: &ast::TypeAlias) -> Result<()> {
-        // TODO: Implement type alias checking
+    /// Type check the method bodies of an impl block
+    fn check_impl(&mut self, impl_block: &ast::Impl) -> Result<()> {
+        let Some(imp) = self.impl_def(&impl_block.impl_span).cloned() else {
+            return Ok(());
+        };
+
+        for func in &impl_block.items {
+            let mut generics = imp.generics.clone();
+            generics.extend(type_param_names(&func.generics));
+            let mut bounds = imp.bounds.clone();
+            for (param, traits) in self.trait_bounds(&func.generics)? {
+                bounds.entry(param).or_default().extend(traits);
+            }
+
+            let name = format!("{}::{}", imp.path, func.name.name);
+            self.with_self_ty(imp.self_ty.clone(), |this| {
+                this.with_bounded_params(generics, bounds, |this| this.check_function_body(func, &name))
+            })?;
+        }
         Ok(())
     }
 
-    /// Type check a const
-    fn check_const(&mut self, _const_def: &ast::Const) -> Result<()> {
-        // TODO: Implement const checking
+    /// Type check a type alias, recording the type it stands for
+    fn check_type_alias(&mut self, type_alias: &ast::TypeAlias) -> Result<()> {
+        let name = &type_alias.name;
+        let params = type_param_names(&type_alias.generics);
+        let args = params.iter().cloned().map(Ty::Param).collect();
+        let ty = self.with_generic_params(params, |this| this.expand_type_alias(name, args));
+        self.report_type_errors()?;
+        if let Some(ty) = ty {
+            self.alias_types.insert(name.name.clone(), ty);
+        }
         Ok(())
     }
 
-    /// Type check a static
-    fn check_static(&mut self, _static_def: &ast::Static) -> Result<()> {
-        // TODO: Implement static checking
+    /// Type check a const, evaluating its value
+    fn check_const(&mut self, const_def: &ast::Const) -> Result<()> {
+        self.check_global(&const_def.name, &const_def.value)
+    }
+
+    /// Type check a static, evaluating its initial value
+    fn check_static(&mut self, static_def: &ast::Static) -> Result<()> {
+        self.check_global(&static_def.name, &static_def.value)
+    }
+
+    /// Type check the value of a `const` or `static` item against its
+    /// declared type, then evaluate it
+    ///
+    /// A constant may already have been evaluated for an array length;
+    /// evaluating it again now that every struct is known gives struct
+    /// literals their declared field order.
+    fn check_global(&mut self, name: &Identifier, value: &Expression) -> Result<()> {
+        let Some(ty) = self.global_ty(&name.name) else {
+            return Ok(());
+        };
+        let found = self.check_expression(value)?;
+        self.coerce(&found, &ty, &value.span)?;
+        self.report_type_errors()?;
+
+        self.evaluating.push(name.name.clone());
+        let result = const_eval::evaluate(self, value, &ty);
+        self.evaluating.pop();
+        self.global_values.insert(name.name.clone(), result?);
         Ok(())
     }
 
     /// Type check a module
     fn check_module(&mut self, _module: &ast::Module) -> Result<()> {
-        // TODO: Implement module checking
+        // The compiler moves module items to the crate root before type
+        // checking (see `zulon_resolver::flatten_modules`)
         Ok(())
     }
 
     /// Type check a use statement
     fn check_use(&mut self, _use_stmt: &ast::Use) -> Result<()> {
-        // TODO: Implement use checking
+        // Imports are resolved by zulon-resolver; nothing is left to check
         Ok(())
     }
 
@@ -387,108 +1642,425 @@ impl TypeChecker {
                     param_types,
                     return_type,
                 }
-            })
-            .collect();
-
-        // Register effect in environment
-        self.env.insert_effect(
-            effect.name.name.clone(),
-            crate::ty::Effect {
-                name: effect.name.name.clone(),
-                operations,
+            })
+            .collect();
+
+        // Register effect in environment
+        self.env.insert_effect(
+            effect.name.name.clone(),
+            crate::ty::Effect {
+                name: effect.name.name.clone(),
+                operations,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Type check a block
+    fn check_block(&mut self, block: &ast::Block) -> Result<Ty> {
+        self.in_scope(|this| {
+            // Check statements
+            for stmt in &block.statements {
+                this.check_statement(stmt)?;
+            }
+
+            // Check trailing expression
+            if let Some(expr) = &block.trailing_expr {
+                this.check_expression(expr)
+            } else {
+                Ok(Ty::Unit)
+            }
+        })
+    }
+
+    /// Run `f` in a new scope; its bindings are dropped afterwards
+    fn in_scope<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let mut scope_env = self.env.enter_scope();
+        std::mem::swap(&mut self.env, &mut scope_env);
+
+        let result = f(self);
+
+        // Swap back to the parent environment, keeping type variable IDs unique
+        std::mem::swap(&mut self.env, &mut scope_env);
+        self.env.advance_ty_vars_to(scope_env.peek_next_ty_var());
+
+        result
+    }
+
+    /// Type check a statement
+    fn check_statement(&mut self, stmt: &Statement) -> Result<()> {
+        match &stmt.kind {
+            ast::StatementKind::Local(local) => self.check_local(local),
+            ast::StatementKind::LetElse(let_else) => self.check_let_else(let_else),
+            ast::StatementKind::Item(item) => self.check_item(item),
+            ast::StatementKind::Expr(expr) => {
+                self.check_expression(expr)?;
+                Ok(())
+            }
+            ast::StatementKind::Defer(stmt) => {
+                // Defer statements are checked normally
+                // The runtime behavior (execution at scope exit) is handled later
+                self.check_statement(stmt)
+            }
+            ast::StatementKind::Empty | ast::StatementKind::Error => Ok(()),
+        }
+    }
+
+    /// Type check a local variable with type inference
+    fn check_local(&mut self, local: &ast::Local) -> Result<()> {
+        // Type check initializer if present
+        let init_ty = if let Some(init) = &local.init {
+            self.check_expression(init)?
+        } else {
+            // No initializer, use type annotation or create fresh type variable
+            local.type_annotation.as_ref()
+                .map(|ty| self.ast_type_to_ty(ty))
+                .unwrap_or_else(|| self.env.fresh_ty_var())
+        };
+
+        // Handle type annotation
+        if let Some(type_ann) = &local.type_annotation {
+            let declared_ty = self.ast_type_to_ty(type_ann);
+
+            // Unify declared type with inferred type
+            match &local.init {
+                Some(init) => self.coerce(&init_ty, &declared_ty, &init.span)?,
+                None => self.unify(&declared_ty, &init_ty, &local.name.span)?,
+            }
+
+            // Use the declared type (after unification)
+            let final_ty = self.apply_subst(&declared_ty);
+            self.env.insert_binding(local.name.name.clone(), final_ty);
+        } else {
+            // No type annotation - use inferred type
+            let final_ty = self.apply_subst(&init_ty);
+            self.env.insert_binding(local.name.name.clone(), final_ty);
+        }
+
+        Ok(())
+    }
+
+    /// Type check `let PAT = value else { ... };`
+    ///
+    /// The pattern's bindings go into the current scope; the `else` block is
+    /// checked without them and must diverge.
+    fn check_let_else(&mut self, let_else: &ast::LetElse) -> Result<()> {
+        let init_ty = self.check_expression(&let_else.init)?;
+
+        let expected = match &let_else.type_annotation {
+            Some(type_ann) => {
+                let declared_ty = self.ast_type_to_ty(type_ann);
+                self.unify(&declared_ty, &init_ty, &let_else.init.span)?;
+                declared_ty
+            }
+            None => init_ty,
+        };
+
+        let else_ty = self.check_block(&let_else.else_block)?;
+        if !matches!(self.apply_subst(&else_ty), Ty::Never) && !block_diverges(&let_else.else_block) {
+            return Err(TypeError::LetElseNotDiverging {
+                span: let_else.else_block.span,
+            });
+        }
+
+        let expected = self.apply_subst(&expected);
+        self.check_pattern(&let_else.pattern, &expected, &let_else.init.span)?;
+        reject_array_patterns(std::slice::from_ref(&let_else.pattern), &let_else.init.span)
+    }
+
+    /// Type check a pattern against the type of the value it matches,
+    /// binding the names it introduces in the current scope
+    ///
+    /// Patterns other than bindings match through references, so `Some(x)`
+    /// matches a `&T?` and binds `x: T`.
+    fn check_pattern(&mut self, pattern: &ast::Pattern, expected: &Ty, span: &ast::Span) -> Result<()> {
+        let expected = self.apply_subst(expected);
+
+        if let Ty::Ref { inner, .. } = &expected {
+            let matches_ref = match pattern {
+                ast::Pattern::Wildcard | ast::Pattern::Or(_) | ast::Pattern::Literal(ast::Literal::String(_)) => true,
+                ast::Pattern::Identifier(ident) => self.unit_variant_ty(ident, inner).is_none(),
+                _ => false,
+            };
+            if !matches_ref {
+                return self.check_pattern(pattern, inner, span);
+            }
+        }
+
+        match pattern {
+            ast::Pattern::Wildcard => Ok(()),
+            ast::Pattern::Identifier(ident) => {
+                match self.unit_variant_ty(ident, &expected) {
+                    Some(variant_ty) => self.unify(&expected, &variant_ty, span)?,
+                    None => self.bind_pattern_name(ident, expected),
+                }
+                Ok(())
+            }
+            ast::Pattern::Literal(literal) => {
+                let literal_ty = self.check_literal(literal, false, span)?;
+                self.unify(&expected, &literal_ty, span)
+            }
+            ast::Pattern::Tuple(patterns) => {
+                let element_tys = match &expected {
+                    Ty::Tuple(tys) if tys.len() == patterns.len() => tys.clone(),
+                    _ => {
+                        let tys: Vec<Ty> = patterns.iter().map(|_| self.env.fresh_ty_var()).collect();
+                        self.unify(&expected, &Ty::Tuple(tys.clone()), span)?;
+                        tys
+                    }
+                };
+                for (pattern, ty) in patterns.iter().zip(&element_tys) {
+                    self.check_pattern(pattern, ty, span)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::Array(_) | ast::Pattern::Slice(..) => {
+                let element_ty = match &expected {
+                    Ty::Array { inner, .. } | Ty::Slice(inner) => (**inner).clone(),
+                    _ => self.env.fresh_ty_var(),
+                };
+                let (patterns, fixed_len): (Vec<&ast::Pattern>, bool) = match pattern {
+                    ast::Pattern::Slice(before, middle, after) => (before.iter().chain(middle).chain(after).collect(), false),
+                    ast::Pattern::Array(patterns) => (patterns.iter().collect(), true),
+                    _ => unreachable!(),
+                };
+                // An array's length must fit the pattern: exactly, or at
+                // least the elements around a `..`
+                if let Ty::Array { len: Some(len), .. } = &expected {
+                    let len = *len as usize;
+                    if (fixed_len && patterns.len() != len) || patterns.len() > len {
+                        return Err(TypeError::ArityMismatch { expected: len, found: patterns.len(), span: *span });
+                    }
+                }
+                for pattern in patterns {
+                    self.check_pattern(pattern, &element_ty, span)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::TupleVariant(path, patterns) => {
+                // Variants of enums the checker does not know bind fresh
+                // type variables
+                let payload_tys = match self.variant_pattern_payload(path, &expected, span)? {
+                    Some(tys) => tys,
+                    None => patterns.iter().map(|_| self.env.fresh_ty_var()).collect(),
+                };
+                if payload_tys.len() != patterns.len() {
+                    return Err(TypeError::ArityMismatch {
+                        expected: payload_tys.len(),
+                        found: patterns.len(),
+                        span: *span,
+                    });
+                }
+                for (pattern, ty) in patterns.iter().zip(&payload_tys) {
+                    self.check_pattern(pattern, ty, span)?;
+                }
+                Ok(())
+            }
+            ast::Pattern::Struct(path, fields) => {
+                let field_tys = self.struct_pattern_fields(path, &expected, span)?;
+                for field in fields {
+                    let (ast::StructPatternField::Field(name, _) | ast::StructPatternField::Shorthand(name)) = field;
+                    let ty = match &field_tys {
+                        Some(field_tys) => match field_tys.iter().find(|(field, _)| *field == name.name) {
+                            Some((_, ty)) => ty.clone(),
+                            None => {
+                                return Err(TypeError::UnknownField {
+                                    field: name.name.clone(),
+                                    ty: Box::new(self.apply_subst(&expected)),
+                                    span: name.span,
+                                });
+                            }
+                        },
+                        None => self.env.fresh_ty_var(),
+                    };
+                    match field {
+                        ast::StructPatternField::Field(_, pattern) => self.check_pattern(pattern, &ty, span)?,
+                        ast::StructPatternField::Shorthand(name) => self.bind_pattern_name(name, ty),
+                    }
+                }
+                Ok(())
+            }
+            ast::Pattern::Range(start, kind, end) => {
+                self.check_pattern(start, &expected, span)?;
+                self.check_pattern(end, &expected, span)?;
+
+                let bound = |pattern: &ast::Pattern| match pattern {
+                    ast::Pattern::Literal(ast::Literal::Int(value, _)) => Some(*value),
+                    ast::Pattern::Literal(ast::Literal::Char(c)) => Some(*c as u128),
+                    ast::Pattern::Literal(ast::Literal::Byte(b)) => Some(*b as u128),
+                    _ => None,
+                };
+                let inclusive = matches!(kind, ast::RangeKind::Inclusive);
+                if let (Some(lo), Some(hi)) = (bound(start), bound(end)) {
+                    if lo > hi || (lo == hi && !inclusive) {
+                        return Err(TypeError::InvalidRangePattern { inclusive, span: *span });
+                    }
+                }
+                Ok(())
             }
-        );
+            ast::Pattern::Or(patterns) => self.check_alternatives(patterns, &expected, span),
+        }
+    }
 
+    /// Type check the alternatives of an or-pattern, or of a match arm;
+    /// a name bound in each of them has one type
+    fn check_alternatives(&mut self, patterns: &[ast::Pattern], expected: &Ty, span: &ast::Span) -> Result<()> {
+        let mut first_bindings: Vec<(String, Ty)> = Vec::new();
+        for (i, pattern) in patterns.iter().enumerate() {
+            self.check_pattern(pattern, expected, span)?;
+
+            let bindings: Vec<(String, Ty)> = pattern.bindings().into_iter()
+                .filter_map(|ident| Some((ident.name.clone(), self.pattern_bindings.get(&ident.span)?.clone())))
+                .collect();
+            if i == 0 {
+                first_bindings = bindings;
+                continue;
+            }
+            for (name, ty) in bindings {
+                if let Some((_, first_ty)) = first_bindings.iter().find(|(first, _)| *first == name) {
+                    let first_ty = first_ty.clone();
+                    self.unify(&first_ty, &ty, span)?;
+                }
+            }
+        }
         Ok(())
     }
 
-    /// Type check a block
-    fn check_block(&mut self, block: &ast::Block) -> Result<Ty> {
-        // Enter block scope
-        let mut block_env = self.env.enter_scope();
-        std::mem::swap(&mut self.env, &mut block_env);
+    /// Bind a name introduced by a pattern, recording its type
+    fn bind_pattern_name(&mut self, ident: &Identifier, ty: Ty) {
+        self.pattern_bindings.insert(ident.span, ty.clone());
+        self.env.insert_binding(ident.name.clone(), ty);
+    }
 
-        // Check statements
-        for stmt in &block.statements {
-            self.check_statement(stmt)?;
+    /// The type of the unit variant `name`, if it names one of the enum or
+    /// optional type `expected`, or an imported one when the type is not
+    /// known yet, rather than introducing a binding
+    fn unit_variant_ty(&mut self, ident: &Identifier, expected: &Ty) -> Option<Ty> {
+        let name = ident.name.as_str();
+        match expected {
+            Ty::Enum { name: enum_name, generics } => self.variant_payload(&enum_name.name, generics, name)
+                .filter(|payload| payload.is_empty())
+                .map(|_| expected.clone()),
+            Ty::Optional(_) if name == "None" => Some(expected.clone()),
+            Ty::TyVar(_) => {
+                let enum_name = self.imported_variant_enum(ident)?;
+                let enum_ty = self.instantiate_enum(&enum_name);
+                let Ty::Enum { generics, .. } = &enum_ty else { unreachable!() };
+                self.variant_payload(&enum_name.name, generics, name)
+                    .filter(|payload| payload.is_empty())
+                    .map(|_| enum_ty)
+            }
+            _ => None,
         }
+    }
 
-        // Check trailing expression
-        let result_ty = if let Some(expr) = &block.trailing_expr {
-            self.check_expression(expr)?
-        } else {
-            Ty::Unit
-        };
-
-        // Exit block scope - swap back to parent environment
-        std::mem::swap(&mut self.env, &mut block_env);
+    /// The enum whose variant `variant` is imported by name
+    fn imported_variant_enum(&self, variant: &Identifier) -> Option<Identifier> {
+        self.variant_imports.iter()
+            .find(|(_, imported)| *imported == variant.name)
+            .map(|(enum_name, _)| Identifier::new(variant.span, enum_name.clone()))
+    }
 
-        Ok(result_ty)
+    /// The enum `name` with a fresh type variable for each type parameter
+    fn instantiate_enum(&mut self, name: &Identifier) -> Ty {
+        let params = self.enums[&name.name].generics.clone();
+        let args = self.instantiate(&params);
+        Ty::Enum {
+            name: name.clone(),
+            generics: params.iter().map(|param| args[param].clone()).collect(),
+        }
     }
 
-    /// Type check a statement
-    fn check_statement(&mut self, stmt: &Statement) -> Result<()> {
-        match &stmt.kind {
-            ast::StatementKind::Local(local) => self.check_local(local),
-            ast::StatementKind::Item(item) => self.check_item(item),
-            ast::StatementKind::Expr(expr) => {
-                self.check_expression(expr)?;
-                Ok(())
-            }
-            ast::StatementKind::Defer(stmt) => {
-                // Defer statements are checked normally
-                // The runtime behavior (execution at scope exit) is handled later
-                self.check_statement(stmt)
+    /// The payload types of the variant a variant pattern names, unifying
+    /// `expected` with its enum; `None` when the variant belongs to no
+    /// known enum
+    fn variant_pattern_payload(&mut self, path: &[Identifier], expected: &Ty, span: &ast::Span) -> Result<Option<Vec<Ty>>> {
+        let variant = &path[path.len() - 1].name;
+
+        if let ([_], Ty::Optional(inner)) = (path, expected) {
+            match variant.as_str() {
+                "Some" => return Ok(Some(vec![(**inner).clone()])),
+                "None" => return Ok(Some(Vec::new())),
+                _ => {}
             }
-            ast::StatementKind::Empty => Ok(()),
         }
-    }
 
-    /// Type check a local variable with type inference
-    fn check_local(&mut self, local: &ast::Local) -> Result<()> {
-        // Type check initializer if present
-        let init_ty = if let Some(init) = &local.init {
-            self.check_expression(init)?
-        } else {
-            // No initializer, use type annotation or create fresh type variable
-            local.type_annotation.as_ref()
-                .map(|ty| self.ast_type_to_ty(ty))
-                .unwrap_or_else(|| self.env.fresh_ty_var())
+        let enum_name = match (path, expected) {
+            ([.., enum_name, _], _) if self.enums.contains_key(&enum_name.name) => enum_name.clone(),
+            ([_], Ty::Enum { name, .. }) => name.clone(),
+            ([variant], _) => match self.imported_variant_enum(variant) {
+                Some(enum_name) => enum_name,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        let enum_ty = match expected {
+            Ty::Enum { name, .. } if *name == enum_name => expected.clone(),
+            _ => {
+                let enum_ty = self.instantiate_enum(&enum_name);
+                self.unify(expected, &enum_ty, span)?;
+                enum_ty
+            }
         };
 
-        // Handle type annotation
-        if let Some(type_ann) = &local.type_annotation {
-            let declared_ty = self.ast_type_to_ty(type_ann);
-
-            // Unify declared type with inferred type
-            self.unify(&declared_ty, &init_ty, &local.name.span)?;
+        let Ty::Enum { generics, .. } = &enum_ty else { unreachable!() };
+        match self.variant_payload(&enum_name.name, generics, variant) {
+            Some(payload) => Ok(Some(payload)),
+            None => Err(TypeError::UnknownVariant { variant: variant.clone(), ty: Box::new(enum_ty), span: *span }),
+        }
+    }
 
-            // Use the declared type (after unification)
-            let final_ty = self.apply_subst(&declared_ty);
-            self.env.insert_binding(local.name.name.clone(), final_ty);
-        } else {
-            // No type annotation - use inferred type
-            let final_ty = self.apply_subst(&init_ty);
-            self.env.insert_binding(local.name.name.clone(), final_ty);
+    /// The fields a struct pattern names, with their types: those of a
+    /// struct, or of a variant with named fields, unifying `expected` with
+    /// the struct or enum; `None` when the path names neither
+    fn struct_pattern_fields(&mut self, path: &[Identifier], expected: &Ty, span: &ast::Span) -> Result<Option<Vec<(String, Ty)>>> {
+        if let [name] = path {
+            if let Some(def) = self.structs.get(&name.name).cloned() {
+                let args = self.instantiate(&def.generics);
+                let generics = def.generics.iter().map(|param| args[param].clone()).collect();
+                self.unify(expected, &Ty::Struct { name: name.clone(), generics }, span)?;
+                return Ok(Some(def.fields.iter().map(|(field, ty)| (field.clone(), subst_params(&args, ty))).collect()));
+            }
         }
 
-        Ok(())
+        let Some(payload) = self.variant_pattern_payload(path, expected, span)? else {
+            return Ok(None);
+        };
+        let variant = &path[path.len() - 1].name;
+        let names = match self.apply_subst(expected) {
+            Ty::Enum { name, .. } => self.enums.get(&name.name).and_then(|def| def.named_fields.get(variant).cloned()),
+            _ => None,
+        };
+        let names = names.unwrap_or_else(|| (0..payload.len()).map(|i| i.to_string()).collect());
+        Ok(Some(names.into_iter().zip(payload).collect()))
     }
 
-    /// Type check an expression
+    /// Type check an expression, recording its type
     pub fn check_expression(&mut self, expr: &Expression) -> Result<Ty> {
+        let ty = self.check_expression_kind(expr)?;
+        self.expr_types.insert(expr.span, ty.clone());
+        Ok(ty)
+    }
+
+    /// Type check an expression by its kind
+    fn check_expression_kind(&mut self, expr: &Expression) -> Result<Ty> {
         match &expr.kind {
-            ast::ExpressionKind::Literal(literal) => self.check_literal(literal),
+            ast::ExpressionKind::Literal(literal) => self.check_literal(literal, false, &expr.span),
             ast::ExpressionKind::Path(path) => self.check_path(path),
             ast::ExpressionKind::Block(block) => self.check_block(block),
             ast::ExpressionKind::Binary(op, left, right) => {
-                self.check_binary_op(op, left, right)
+                self.check_binary_op(op, left, right, &expr.span)
             }
             ast::ExpressionKind::Unary(op, operand) => {
                 self.check_unary_op(op, operand)
             }
+            ast::ExpressionKind::Cast(value, target) => self.check_cast(value, target, &expr.span),
             ast::ExpressionKind::Call(func, args) => {
-                self.check_call(func, args)
+                self.check_call(func, args, &expr.span)
+            }
+            ast::ExpressionKind::MethodCall(receiver, method, args) => {
+                self.check_method_call(receiver, method, args, &expr.span)
             }
             ast::ExpressionKind::FieldAccess(obj, field) => {
                 self.check_field_access(obj, field)
@@ -525,7 +2097,8 @@ impl TypeChecker {
             ast::ExpressionKind::Return(value) => self.check_return(value),
             ast::ExpressionKind::Throw(error_expr) => self.check_throw(error_expr),
             ast::ExpressionKind::QuestionMark(expr) => self.check_question_mark(expr),
-            ast::ExpressionKind::Struct(struct_lit) => self.check_struct_literal(struct_lit),
+            ast::ExpressionKind::Struct(struct_lit) => self.check_struct_literal(struct_lit, &expr.span),
+            ast::ExpressionKind::TemplateString(template) => self.check_template_string(template),
             ast::ExpressionKind::Assign(target, value) => self.check_assign(target, value),
             ast::ExpressionKind::AssignOp(op, target, value) => {
                 self.check_assign_op(op, target, value)
@@ -547,6 +2120,15 @@ impl TypeChecker {
                     }
                 }
             }
+            ast::ExpressionKind::Let(pattern, value) => {
+                // Only valid as an `if`/`while` condition, which provide the scope
+                let value_ty = self.check_expression(value)?;
+                self.check_pattern(pattern, &value_ty, &value.span)?;
+                reject_array_patterns(std::slice::from_ref(pattern), &value.span)?;
+                Ok(Ty::Bool)
+            }
+            // A recovered parse error unifies with anything
+            ast::ExpressionKind::Error => Ok(self.env.fresh_ty_var()),
             _ => {
                 // TODO: Implement remaining expression kinds
                 Ok(Ty::Unit)
@@ -555,21 +2137,64 @@ impl TypeChecker {
     }
 
     /// Type check a literal
-    fn check_literal(&mut self, literal: &ast::Literal) -> Result<Ty> {
+    ///
+    /// `negated` is set when the literal is the operand of a unary `-`, so that
+    /// `-128i8` is accepted while `128i8` overflows.
+    fn check_literal(&mut self, literal: &ast::Literal, negated: bool, span: &ast::Span) -> Result<Ty> {
         match literal {
-            ast::Literal::Int(_) => Ok(Ty::I32),  // Default to i32
-            ast::Literal::Float(_) => Ok(Ty::F64),
+            ast::Literal::Int(value, Some(suffix)) => {
+                if *value > suffix.max_magnitude(negated) {
+                    return Err(TypeError::IntegerOverflow { span: *span });
+                }
+                Ok(Self::int_suffix_ty(*suffix))
+            }
+            // The type of an unsuffixed literal comes from its context
+            ast::Literal::Int(value, None) => {
+                let ty = self.env.fresh_int_var();
+                self.int_literals.push((*span, *value, negated, ty.clone()));
+                Ok(ty)
+            }
+            ast::Literal::Float(_, Some(ast::FloatSuffix::F32)) => Ok(Ty::F32),
+            ast::Literal::Float(_, Some(ast::FloatSuffix::F64)) => Ok(Ty::F64),
+            ast::Literal::Float(_, None) => {
+                let ty = self.env.fresh_float_var();
+                self.float_literals.push(ty.clone());
+                Ok(ty)
+            }
             // String literals are pointers to u8 (for C compatibility)
             ast::Literal::String(_) => Ok(Ty::Ref {
                 inner: Box::new(Ty::U8),
                 mutable: false,
             }),
             ast::Literal::Char(_) => Ok(Ty::Char),
+            ast::Literal::ByteString(bytes) => Ok(Ty::Array {
+                inner: Box::new(Ty::U8),
+                len: Some(bytes.len() as u64),
+            }),
+            ast::Literal::Byte(_) => Ok(Ty::U8),
             ast::Literal::Bool(_) => Ok(Ty::Bool),
             ast::Literal::Null => Ok(Ty::Unit),
         }
     }
 
+    /// The type named by an integer literal suffix
+    pub(crate) fn int_suffix_ty(suffix: ast::IntSuffix) -> Ty {
+        match suffix {
+            ast::IntSuffix::I8 => Ty::I8,
+            ast::IntSuffix::I16 => Ty::I16,
+            ast::IntSuffix::I32 => Ty::I32,
+            ast::IntSuffix::I64 => Ty::I64,
+            ast::IntSuffix::I128 => Ty::I128,
+            ast::IntSuffix::ISize => Ty::ISize,
+            ast::IntSuffix::U8 => Ty::U8,
+            ast::IntSuffix::U16 => Ty::U16,
+            ast::IntSuffix::U32 => Ty::U32,
+            ast::IntSuffix::U64 => Ty::U64,
+            ast::IntSuffix::U128 => Ty::U128,
+            ast::IntSuffix::USize => Ty::USize,
+        }
+    }
+
     /// Type check a path (variable or function reference)
     fn check_path(&mut self, path: &[Identifier]) -> Result<Ty> {
         if path.len() == 1 {
@@ -581,6 +2206,12 @@ impl TypeChecker {
                 return Ok(ty);
             }
 
+            // Look up as `const` or `static` item
+            if let Some(ty) = self.global_ty(name) {
+                self.global_refs.insert(path[0].span, name.clone());
+                return Ok(ty);
+            }
+
             // Look up as function
             if let Some(ty) = self.env.lookup_function(name) {
                 return Ok(ty);
@@ -612,7 +2243,27 @@ impl TypeChecker {
         } else if path.len() == 2 {
             // Qualified path: Type::Variant or Type::Field
             let type_name = &path[0].name;
-            let _variant_name = &path[1].name;
+            let variant_name = &path[1].name;
+
+            // An enum variant: a constructor function, or the value itself
+            // for variants without a payload
+            if let Some(enum_def) = self.enums.get(type_name).cloned() {
+                if let Some((_, payload)) = enum_def.variants.iter().find(|(name, _)| name == variant_name) {
+                    let args = self.instantiate(&enum_def.generics);
+                    let enum_ty = Ty::Enum {
+                        name: path[0].clone(),
+                        generics: enum_def.generics.iter().map(|param| args[param].clone()).collect(),
+                    };
+                    if payload.is_empty() {
+                        return Ok(enum_ty);
+                    }
+                    return Ok(Ty::Function {
+                        params: payload.iter().map(|ty| subst_params(&args, ty)).collect(),
+                        return_type: Box::new(enum_ty),
+                        variadic: false,
+                    });
+                }
+            }
 
             // Look up as enum type
             if let Some(enum_ty) = self.env.lookup_type_def(type_name) {
@@ -622,23 +2273,27 @@ impl TypeChecker {
             // Not found
             Err(TypeError::UndefinedVariable {
                 name: type_name.clone(),
-                span: path[0].span.clone(),
+                span: path[0].span,
             })
         } else {
             // Longer paths (module::Type::Variant, etc.) - not yet supported
             Err(TypeError::UndefinedVariable {
                 name: path.last().unwrap().name.clone(),
-                span: path.last().unwrap().span.clone(),
+                span: path.last().unwrap().span,
             })
         }
     }
 
     /// Type check a binary operation with type inference
+    ///
+    /// Comparisons of values other than scalars and strings call a method
+    /// of `PartialEq` or `PartialOrd`; see [`Self::check_comparison_impl`].
     fn check_binary_op(
         &mut self,
         op: &ast::BinaryOp,
         left: &Expression,
         right: &Expression,
+        span: &Span,
     ) -> Result<Ty> {
         let left_ty = self.check_expression(left)?;
         let right_ty = self.check_expression(right)?;
@@ -660,8 +2315,8 @@ impl TypeChecker {
                 // Check that it's numeric
                 if !unified.is_numeric() {
                     return Err(TypeError::TypeMismatch {
-                        expected: Ty::I32, // Any numeric type
-                        found: unified.clone(),
+                        expected: Box::new(Ty::I32), // Any numeric type
+                        found: Box::new(unified.clone()),
                         span: left.span,
                     });
                 }
@@ -681,12 +2336,15 @@ impl TypeChecker {
 
                 // Check that operands are comparable (numeric or other comparable types)
                 let unified = self.apply_subst(&left_ty);
-                if !unified.is_numeric() && !matches!(unified, Ty::Bool | Ty::Char | Ty::String) {
+                if let Ty::TyVar(_) = unified {
                     return Err(TypeError::InferenceError {
                         message: format!("cannot compare {} values", unified),
                         span: left.span,
                     });
                 }
+                if !unified.is_numeric() && !matches!(unified, Ty::Bool | Ty::Char | Ty::String) {
+                    self.check_comparison_impl(op, &unified, span)?;
+                }
 
                 Ty::Bool
             }
@@ -711,8 +2369,8 @@ impl TypeChecker {
                 let unified = self.apply_subst(&left_ty);
                 if !unified.is_integer() {
                     return Err(TypeError::TypeMismatch {
-                        expected: Ty::I32, // Any integer type
-                        found: unified.clone(),
+                        expected: Box::new(Ty::I32), // Any integer type
+                        found: Box::new(unified.clone()),
                         span: left.span,
                     });
                 }
@@ -724,15 +2382,110 @@ impl TypeChecker {
         Ok(result_ty)
     }
 
+    /// Resolve the comparison `op` at `span` of two values of `ty` to the
+    /// method it calls: `eq` for `==` and `!=`, `lt` for `<`, and
+    /// `partial_cmp` for the others
+    fn check_comparison_impl(&mut self, op: &ast::BinaryOp, ty: &Ty, span: &Span) -> Result<()> {
+        let (trait_name, method) = match op {
+            ast::BinaryOp::Eq | ast::BinaryOp::NotEq => ("PartialEq", "eq"),
+            ast::BinaryOp::Less => ("PartialOrd", "lt"),
+            _ => ("PartialOrd", "partial_cmp"),
+        };
+        if !self.traits.contains_key(trait_name) || !self.implements_trait(ty, trait_name, span) {
+            return Err(TypeError::TraitBoundNotSatisfied {
+                trait_name: trait_name.to_string(),
+                ty: Box::new(ty.clone()),
+                span: *span,
+            });
+        }
+
+        let callee = MethodCallee::Trait {
+            trait_name: trait_name.to_string(),
+            self_ty: ty.clone(),
+            method: method.to_string(),
+            generic_args: Vec::new(),
+        };
+        self.method_calls.insert(*span, MethodResolution { callee, autoderefs: 0, autoref: Some(false) });
+        Ok(())
+    }
+
     /// Type check a unary operation
-    fn check_unary_op(&mut self, _op: &ast::UnaryOp, operand: &Expression) -> Result<Ty> {
-        self.check_expression(operand)?;
-        Ok(Ty::I32)
+    fn check_unary_op(&mut self, op: &ast::UnaryOp, operand: &Expression) -> Result<Ty> {
+        // Negative literals are range-checked against the negated bound
+        if let (ast::UnaryOp::Neg, ast::ExpressionKind::Literal(literal)) = (op, &operand.kind) {
+            let ty = self.check_literal(literal, true, &operand.span)?;
+            self.expr_types.insert(operand.span, ty.clone());
+            return Ok(ty);
+        }
+
+        let operand_ty = self.check_expression(operand)?;
+        match op {
+            ast::UnaryOp::Ref | ast::UnaryOp::Borrow => Ok(Ty::Ref { inner: Box::new(operand_ty), mutable: false }),
+            ast::UnaryOp::BorrowMut => Ok(Ty::Ref { inner: Box::new(operand_ty), mutable: true }),
+            ast::UnaryOp::Deref => match self.apply_subst(&operand_ty) {
+                Ty::Ref { inner, .. } | Ty::Ptr { inner, .. } => Ok(*inner),
+                _ => Ok(self.env.fresh_ty_var()),
+            },
+            ast::UnaryOp::Neg | ast::UnaryOp::Not | ast::UnaryOp::BitNot => Ok(operand_ty),
+        }
+    }
+
+    /// Type check `value as Type`
+    ///
+    /// Numbers convert to each other, `bool` and `char` to integers and
+    /// `u8` to `char`; any other cast is an error.
+    fn check_cast(&mut self, value: &Expression, target: &ast::Type, span: &ast::Span) -> Result<Ty> {
+        let from = self.check_expression(value)?;
+        let from = self.apply_subst(&from);
+        let to = self.ast_type_to_ty(target);
+
+        let allowed = match (&from, &to) {
+            (from, to) if from.is_numeric() && to.is_numeric() => true,
+            (Ty::Bool | Ty::Char, to) => to.is_integer(),
+            (Ty::IntVar(_), Ty::Char) => {
+                self.unify(&Ty::U8, &from, span)?;
+                true
+            }
+            (Ty::U8, Ty::Char) => true,
+            (from, to) => from == to,
+        };
+        if !allowed {
+            return Err(TypeError::CannotConvert { from: Box::new(from), to: Box::new(to), span: *span });
+        }
+        Ok(to)
     }
 
     /// Type check a function call with type inference
-    fn check_call(&mut self, func: &Expression, args: &[Box<Expression>]) -> Result<Ty> {
-        let func_ty = self.check_expression(func)?;
+    ///
+    /// A call of a generic function instantiates its type parameters with
+    /// fresh type variables; what they are inferred to be is recorded for
+    /// the call's `span`.
+    fn check_call(&mut self, func: &Expression, args: &[Box<Expression>], span: &Span) -> Result<Ty> {
+        let assoc_fn_ty = match &func.kind {
+            ast::ExpressionKind::Path(path) => self.check_assoc_fn(path, span)?,
+            _ => None,
+        };
+        let mut func_ty = match assoc_fn_ty {
+            Some(ty) => {
+                self.expr_types.insert(func.span, ty.clone());
+                ty
+            }
+            None => self.check_expression(func)?,
+        };
+
+        if let ast::ExpressionKind::Path(path) = &func.kind {
+            let callee = match path.as_slice() {
+                [name] if self.env.lookup_binding(&name.name).is_none() => Some(&name.name),
+                _ => None,
+            };
+            if let Some(generics) = callee.and_then(|name| self.fn_generics.get(name)).cloned() {
+                let type_args = self.instantiate(&generics);
+                func_ty = subst_params(&type_args, &func_ty);
+                self.call_generics.insert(*span, generics.iter().map(|param| type_args[param].clone()).collect());
+                let bounds = callee.and_then(|name| self.fn_bounds.get(name)).cloned().unwrap_or_default();
+                self.require_bounds(&bounds, &type_args, span);
+            }
+        }
 
         match func_ty {
             Ty::Function { params, return_type, variadic } => {
@@ -755,11 +2508,16 @@ impl TypeChecker {
                 }
 
                 // Check arguments and unify with parameter types
-                // Only check up to params.len() - variadic args aren't type-checked
+                // Arguments passed to `...` may have any type
                 for (arg, param_ty) in args.iter().zip(params.iter()) {
                     let arg_ty = self.check_expression(arg)?;
-                    self.unify(&arg_ty, param_ty, &arg.span)?;
+                    self.coerce(&arg_ty, param_ty, &arg.span)?;
+                }
+                for arg in args.iter().skip(params.len()) {
+                    self.check_expression(arg)?;
                 }
+                self.resolve_pending_projections()?;
+                self.check_bound_obligations(false)?;
 
                 // EFFECT CHECKING: Propagate effects from callee to caller
                 // Extract function name if it's a path expression
@@ -767,53 +2525,496 @@ impl TypeChecker {
                     kind: ast::ExpressionKind::Path(path),
                     ..
                 } = func {
-                    if !path.is_empty() {
-                        let func_name = &path[0].name;
-
-                        // Look up function's effect set
-                        if let Some(callee_effects) = self.env.lookup_function_effects(func_name) {
-                            // Check purity: pure function cannot call impure function
-                            if self.declared_effects.is_pure() && !callee_effects.is_pure() {
-                                return Err(TypeError::InferenceError {
-                                    message: format!(
-                                        "Pure function '{}' cannot call impure function '{}' with effects: {}",
-                                        "current_function",  // TODO: Track current function name
-                                        func_name,
-                                        callee_effects
-                                    ),
-                                    span: func.span,
-                                });
-                            }
+                    let func_name = match self.method_calls.get(span) {
+                        Some(MethodResolution { callee: MethodCallee::Impl { function, .. }, .. }) => Some(function.clone()),
+                        _ => path.first().map(|name| name.name.clone()),
+                    };
+                    if let Some(func_name) = func_name {
+                        self.propagate_call_effects(&func_name, &func.span)?;
+                    }
+                }
+
+                // Apply substitution to return type
+                Ok(self.normalize(&return_type))
+            }
+            _ => Err(TypeError::NotCallable {
+                ty: Box::new(func_ty),
+                span: func.span,
+            }),
+        }
+    }
+
+    /// Add the effects of the function `func_name` to those of the
+    /// function being checked
+    fn propagate_call_effects(&mut self, func_name: &str, span: &Span) -> Result<()> {
+        // Look up function's effect set
+        if let Some(callee_effects) = self.env.lookup_function_effects(func_name) {
+            // Check purity: pure function cannot call impure function
+            if self.declared_effects.is_pure() && !callee_effects.is_pure() {
+                return Err(TypeError::InferenceError {
+                    message: format!(
+                        "Pure function '{}' cannot call impure function '{}' with effects: {}",
+                        "current_function",  // TODO: Track current function name
+                        func_name,
+                        callee_effects
+                    ),
+                    span: *span,
+                });
+            }
+
+            // Propagate effects to current function
+            self.effect_inference.propagate_call_effects(
+                &mut self.current_effect_set,
+                func_name,
+                &callee_effects,
+            );
+
+            // Update environment's current effects
+            for effect in callee_effects.to_vec() {
+                self.env.add_effect(effect);
+            }
+        }
+        Ok(())
+    }
+
+    /// Type check a call of an associated function, `Type::name(args)`
+    ///
+    /// `Type` is a struct or enum, a type parameter or `Self`. Returns
+    /// `None` for paths that name something else, such as enum variants.
+    fn check_assoc_fn(&mut self, path: &[Identifier], span: &Span) -> Result<Option<Ty>> {
+        let [head, name] = path else {
+            return Ok(None);
+        };
+        if self.variant_payload(&head.name, &[], &name.name).is_some() {
+            return Ok(None);
+        }
+
+        let ty = if head.name == "Self" && self.self_ty.is_some() {
+            self.self_ty.clone().unwrap()
+        } else if self.generic_params.contains(&head.name) {
+            Ty::Param(head.name.clone())
+        } else if let Some(generics) = self.structs.get(&head.name).map(|def| def.generics.clone()) {
+            let args = self.instantiate(&generics);
+            Ty::Struct { name: head.clone(), generics: generics.iter().map(|param| args[param].clone()).collect() }
+        } else if let Some(generics) = self.enums.get(&head.name).map(|def| def.generics.clone()) {
+            let args = self.instantiate(&generics);
+            Ty::Enum { name: head.clone(), generics: generics.iter().map(|param| args[param].clone()).collect() }
+        } else {
+            return Ok(None);
+        };
+
+        let Some((callee, sig)) = self.lookup_method(&ty, &name.name, &name.span)? else {
+            return Err(TypeError::NoMethod {
+                method: name.name.clone(),
+                ty: Box::new(ty),
+                span: name.span,
+            });
+        };
+        self.method_calls.insert(*span, MethodResolution { callee, autoderefs: 0, autoref: None });
+        Ok(Some(Ty::Function {
+            params: sig.params,
+            return_type: Box::new(sig.return_type),
+            variadic: false,
+        }))
+    }
+
+    /// Type check a method call, `receiver.method(args)`
+    ///
+    /// The receiver is dereferenced until a type with a method of that name
+    /// is found, then borrowed if the method takes `&self` or `&mut self`.
+    /// Inherent methods take precedence over trait methods.
+    fn check_method_call(
+        &mut self,
+        receiver: &Expression,
+        method: &Identifier,
+        args: &[Box<Expression>],
+        span: &Span,
+    ) -> Result<Ty> {
+        let receiver_ty = self.check_expression(receiver)?;
+        // Methods are looked up on the type a literal defaults to
+        let mut ty = match self.normalize(&receiver_ty) {
+            Ty::IntVar(id) => {
+                self.subst.bind(id, &Ty::I32);
+                Ty::I32
+            }
+            Ty::FloatVar(id) => {
+                self.subst.bind(id, &Ty::F64);
+                Ty::F64
+            }
+            ty => ty,
+        };
+        // The type of the receiver before each dereference
+        let mut derefed = Vec::new();
+
+        let (callee, sig) = loop {
+            if let Some(found) = self.lookup_method(&ty, &method.name, &method.span)? {
+                break found;
+            }
+            match ty {
+                Ty::Ref { ref inner, .. } | Ty::Ptr { ref inner, .. } => {
+                    let inner = (**inner).clone();
+                    derefed.push(std::mem::replace(&mut ty, inner));
+                }
+                _ => {
+                    return Err(TypeError::NoMethod {
+                        method: method.name.clone(),
+                        ty: Box::new(self.apply_subst(&receiver_ty)),
+                        span: method.span,
+                    });
+                }
+            }
+        };
+        if !sig.has_receiver {
+            return Err(TypeError::InferenceError {
+                message: format!("`{}` is an associated function, not a method", method.name),
+                span: method.span,
+            });
+        }
+
+        let mut autoref = None;
+        match &sig.params[0] {
+            Ty::Ref { inner, mutable } if !matches!(ty, Ty::Ref { .. }) => {
+                self.unify(inner, &ty, &method.span)?;
+                autoref = Some(*mutable);
+            }
+            self_param => self.unify(self_param, &ty, &method.span)?,
+        }
+
+        // `&*r` and `&mut *r` for a reference `r` of the same kind are `r`
+        let mut autoderefs = derefed.len();
+        if let (Some(mutable), Some(Ty::Ref { mutable: ref_mutable, .. })) = (autoref, derefed.last()) {
+            if mutable && !ref_mutable {
+                return Err(TypeError::CannotBorrowMut {
+                    ty: Box::new(ty.clone()),
+                    span: receiver.span,
+                });
+            }
+            if mutable == *ref_mutable {
+                autoderefs -= 1;
+                autoref = None;
+            }
+        }
 
-                            // Propagate effects to current function
-                            self.effect_inference.propagate_call_effects(
-                                &mut self.current_effect_set,
-                                func_name,
-                                &callee_effects,
-                            );
+        let params = &sig.params[1..];
+        if params.len() != args.len() {
+            return Err(TypeError::ArityMismatch {
+                expected: params.len(),
+                found: args.len(),
+                span: method.span,
+            });
+        }
+        for (arg, param_ty) in args.iter().zip(params) {
+            let arg_ty = self.check_expression(arg)?;
+            self.coerce(&arg_ty, param_ty, &arg.span)?;
+        }
+        self.resolve_pending_projections()?;
+        self.check_bound_obligations(false)?;
+
+        if let MethodCallee::Impl { function, .. } = &callee {
+            self.propagate_call_effects(&function.clone(), &method.span)?;
+        }
+        self.method_calls.insert(*span, MethodResolution { callee, autoderefs, autoref });
+        Ok(self.normalize(&sig.return_type))
+    }
+
+    /// Find the method `name` of `ty`, instantiated for a call: the type
+    /// parameters of its impl and its own become fresh type variables
+    ///
+    /// The methods of a type parameter are those of the traits bounding it,
+    /// including in where clauses, those of an associated type of one are
+    /// those of the traits bounding the associated type, and those of a
+    /// trait object are those of its trait and supertraits. Other types
+    /// have the methods of their inherent impls or, if none of those has
+    /// one named `name`, of the trait impls for them.
+    ///
+    /// The bounds of the impl and the method become obligations on the
+    /// type arguments.
+    fn lookup_method(&mut self, ty: &Ty, name: &str, span: &Span) -> Result<Option<(MethodCallee, MethodSig)>> {
+        let ty = self.normalize(ty);
+
+        let bounding_traits = match &ty {
+            Ty::Param(param) => Some(self.bounded_traits(param)),
+            Ty::Projection { trait_name, name: assoc, .. } => Some(self.assoc_type_bounds(trait_name, assoc)),
+            Ty::TraitObject(trait_name) => Some(self.with_super_traits(vec![trait_name.clone()])),
+            _ => None,
+        };
+        if let Some(bounding_traits) = bounding_traits {
+            let mut found = Vec::new();
+            for trait_name in bounding_traits {
+                let methods = &self.traits[&trait_name].methods;
+                if let Some((_, sig, _)) = methods.iter().find(|(method, ..)| method == name) {
+                    found.push((trait_name, sig.clone()));
+                }
+            }
+            if found.len() > 1 {
+                return Err(TypeError::AmbiguousMethod { method: name.to_string(), ty: Box::new(ty), span: *span });
+            }
+            let Some((trait_name, sig)) = found.pop() else {
+                return Ok(None);
+            };
+
+            let mut args = self.instantiate(&sig.generics);
+            self.require_bounds(&sig.bounds, &args, span);
+            let generic_args = sig.generics.iter().map(|param| args[param].clone()).collect();
+            args.insert("Self".to_string(), ty.clone());
+            let callee = MethodCallee::Trait {
+                trait_name,
+                self_ty: ty.clone(),
+                method: name.to_string(),
+                generic_args,
+            };
+            return Ok(Some((callee, instantiate_sig(&args, &sig))));
+        }
+        if let Ty::TyVar(_) = ty {
+            return Err(TypeError::InferenceError {
+                message: format!("type annotations needed to call method `{}`", name),
+                span: *span,
+            });
+        }
+
+        // Each impl with a method `name` whose self type matches, with the
+        // method's signature in terms of the impl's type parameters
+        let mut found = Vec::new();
+        for inherent in [true, false] {
+            for imp in self.impls.clone() {
+                if imp.trait_name.is_none() != inherent {
+                    continue;
+                }
+                let sig = match (imp.methods.iter().find(|(method, _)| method == name), &imp.trait_name) {
+                    (Some((_, sig)), _) => sig.clone(),
+                    (None, Some(trait_name)) => {
+                        let methods = &self.traits[trait_name].methods;
+                        let Some((_, sig, _)) = methods.iter().find(|(method, ..)| method == name) else {
+                            continue;
+                        };
+                        let args = HashMap::from([("Self".to_string(), imp.self_ty.clone())]);
+                        MethodSig { generics: sig.generics.clone(), bounds: sig.bounds.clone(), ..instantiate_sig(&args, sig) }
+                    }
+                    (None, None) => continue,
+                };
+                let args = self.instantiate(&imp.generics);
+                if self.types_unify(&subst_params(&args, &imp.self_ty), &ty, span) {
+                    found.push((imp, sig));
+                }
+            }
+            if !found.is_empty() {
+                break;
+            }
+        }
+        if found.len() > 1 {
+            return Err(TypeError::AmbiguousMethod { method: name.to_string(), ty: Box::new(ty), span: *span });
+        }
+        let Some((imp, sig)) = found.pop() else {
+            return Ok(self.builtin_method(&ty, name));
+        };
+
+        let mut args = self.instantiate(&imp.generics);
+        self.unify(&subst_params(&args, &imp.self_ty), &ty, span)?;
+        self.require_bounds(&imp.bounds, &args, span);
+        let mut generic_args: Vec<Ty> = imp.generics.iter().map(|param| args[param].clone()).collect();
+        let method_args = self.instantiate(&sig.generics);
+        self.require_bounds(&sig.bounds, &method_args, span);
+        generic_args.extend(sig.generics.iter().map(|param| method_args[param].clone()));
+        args.extend(method_args);
+
+        let callee = MethodCallee::Impl {
+            function: format!("{}::{}", imp.path, name),
+            generic_args,
+        };
+        Ok(Some((callee, instantiate_sig(&args, &sig))))
+    }
+
+    /// The method `name` of a builtin impl for `ty`, if `ty` is a primitive
+    /// scalar type; see [`BUILTIN_TRAITS`]
+    fn builtin_method(&self, ty: &Ty, name: &str) -> Option<(MethodCallee, MethodSig)> {
+        BUILTIN_TRAITS.iter()
+            .filter(|trait_name| has_builtin_impl(ty, trait_name))
+            .find_map(|trait_name| {
+                let methods = &self.traits.get(*trait_name)?.methods;
+                let (_, sig, _) = methods.iter().find(|(method, ..)| method == name)?;
+                let args = HashMap::from([("Self".to_string(), ty.clone())]);
+                let callee = MethodCallee::Trait {
+                    trait_name: trait_name.to_string(),
+                    self_ty: ty.clone(),
+                    method: name.to_string(),
+                    generic_args: Vec::new(),
+                };
+                Some((callee, instantiate_sig(&args, sig)))
+            })
+    }
+
+    /// The traits bounding each type parameter in `generics`, which must
+    /// all be declared
+    fn trait_bounds(&self, generics: &Option<ast::Generics>) -> Result<HashMap<String, Vec<String>>> {
+        let mut bounds: HashMap<String, Vec<String>> = HashMap::new();
+        for clause in generics.iter().flat_map(|generics| &generics.where_clause) {
+            for bound in &clause.bounds {
+                let ast::TraitBound::Trait(ty) = bound else { continue };
+                let Some(trait_name) = trait_ref_name(ty) else { continue };
+                if !self.traits.contains_key(&trait_name.name) {
+                    return Err(TypeError::UndefinedTrait {
+                        name: trait_name.name.clone(),
+                        span: trait_name.span,
+                    });
+                }
+                bounds.entry(clause.type_param.name.clone()).or_default().push(trait_name.name.clone());
+            }
+        }
+        Ok(bounds)
+    }
+
+    /// The traits bounding the type parameter `param`, with their
+    /// supertraits
+    fn bounded_traits(&self, param: &str) -> Vec<String> {
+        self.with_super_traits(self.param_bounds.get(param).cloned().unwrap_or_default())
+    }
+
+    /// The traits bounding the associated type `name` of `trait_name`,
+    /// with their supertraits
+    fn assoc_type_bounds(&self, trait_name: &str, name: &str) -> Vec<String> {
+        let bounds = self.traits.get(trait_name)
+            .and_then(|def| def.assoc_types.iter().find(|(assoc, _)| assoc == name))
+            .map(|(_, bounds)| bounds.clone())
+            .unwrap_or_default();
+        self.with_super_traits(bounds)
+    }
+
+    /// Require the type arguments `args` to implement the traits bounding
+    /// their parameters, for the call at `span`
+    fn require_bounds(&mut self, bounds: &HashMap<String, Vec<String>>, args: &HashMap<String, Ty>, span: &Span) {
+        for (param, traits) in bounds {
+            let Some(arg) = args.get(param) else { continue };
+            for trait_name in traits {
+                self.bound_obligations.push((arg.clone(), trait_name.clone(), *span));
+            }
+        }
+    }
+
+    /// Check the obligations whose types are known, or with `all` every
+    /// remaining one
+    fn check_bound_obligations(&mut self, all: bool) -> Result<()> {
+        for (ty, trait_name, span) in std::mem::take(&mut self.bound_obligations) {
+            let ty = self.normalize(&ty);
+            if ty.has_ty_vars() && !all {
+                self.bound_obligations.push((ty, trait_name, span));
+            } else if !self.implements_trait(&ty, &trait_name, &span) {
+                return Err(TypeError::TraitBoundNotSatisfied { trait_name, ty: Box::new(ty), span });
+            }
+        }
+        Ok(())
+    }
+
+    /// `traits` followed by all their supertraits, leaving out undeclared
+    /// traits
+    fn with_super_traits(&self, mut traits: Vec<String>) -> Vec<String> {
+        let mut i = 0;
+        while i < traits.len() {
+            if let Some(trait_def) = self.traits.get(&traits[i]) {
+                for super_trait in &trait_def.super_traits {
+                    if !traits.contains(super_trait) {
+                        traits.push(super_trait.clone());
+                    }
+                }
+            }
+            i += 1;
+        }
+        traits.retain(|trait_name| self.traits.contains_key(trait_name));
+        traits
+    }
+
+    /// Whether `ty` implements the trait `trait_name`
+    ///
+    /// Type parameters, their associated types and trait objects implement
+    /// their bounding traits; other types those of the impls matching them
+    /// whose own bounds hold, and their builtin impls.
+    fn implements_trait(&mut self, ty: &Ty, trait_name: &str, span: &Span) -> bool {
+        match ty {
+            // Not known yet; the obligation is checked again once it is
+            Ty::TyVar(_) => true,
+            Ty::Param(param) => self.bounded_traits(param).iter().any(|name| name == trait_name),
+            Ty::Projection { trait_name: owner, name, .. } => {
+                self.assoc_type_bounds(owner, name).iter().any(|bound| bound == trait_name)
+            }
+            Ty::TraitObject(object) => self.with_super_traits(vec![object.clone()]).iter().any(|name| name == trait_name),
+            _ => {
+                if has_builtin_impl(ty, trait_name) {
+                    return true;
+                }
+                self.impls.clone().iter()
+                    .filter(|imp| imp.trait_name.as_deref() == Some(trait_name))
+                    .any(|imp| {
+                        let saved = (self.subst.clone(), self.pending_projections.len());
+                        let args = self.instantiate(&imp.generics);
+                        let implements = self.unify(&subst_params(&args, &imp.self_ty), ty, span).is_ok()
+                            && imp.bounds.iter().all(|(param, traits)| {
+                                let arg = self.apply_subst(&args[param]);
+                                traits.iter().all(|bound| self.implements_trait(&arg, bound, span))
+                            });
+                        self.subst = saved.0;
+                        self.pending_projections.truncate(saved.1);
+                        implements
+                    })
+            }
+        }
+    }
 
-                            // Update environment's current effects
-                            for effect in callee_effects.to_vec() {
-                                self.env.add_effect(effect);
-                            }
-                        }
+    /// Check that the expression at `span`, of type `found`, can be used
+    /// where `expected` is expected
+    ///
+    /// Besides types that unify, a reference to a type implementing `Trait`
+    /// coerces to `&dyn Trait`, and a mutable one to `&mut dyn Trait`.
+    fn coerce(&mut self, found: &Ty, expected: &Ty, span: &Span) -> Result<()> {
+        let found = self.apply_subst(found);
+        let expected = self.apply_subst(expected);
+        if let (Ty::Ref { inner, mutable }, Ty::Ref { inner: target, mutable: target_mutable }) = (&found, &expected) {
+            if let Ty::TraitObject(trait_name) = &**target {
+                if !matches!(**inner, Ty::TraitObject(_) | Ty::TyVar(_)) && (*mutable || !*target_mutable) {
+                    if !self.implements_trait(inner, trait_name, span) {
+                        return Err(TypeError::TraitBoundNotSatisfied {
+                            trait_name: trait_name.clone(),
+                            ty: Box::new((**inner).clone()),
+                            span: *span,
+                        });
                     }
+                    self.coercions.insert(*span, expected);
+                    return Ok(());
                 }
-
-                // Apply substitution to return type
-                Ok(self.apply_subst(&*return_type))
             }
-            _ => Err(TypeError::NotCallable {
-                ty: func_ty,
-                span: func.span,
-            }),
         }
+        self.unify(&expected, &found, span)
     }
 
     /// Type check field access
-    fn check_field_access(&mut self, _obj: &Expression, _field: &Identifier) -> Result<Ty> {
-        // TODO: Implement field access checking
-        Ok(Ty::I32)
+    ///
+    /// The field's type is instantiated with the struct's type arguments.
+    /// References to structs are dereferenced automatically.
+    fn check_field_access(&mut self, obj: &Expression, field: &Identifier) -> Result<Ty> {
+        let mut obj_ty = self.check_expression(obj)?;
+        obj_ty = self.apply_subst(&obj_ty);
+        while let Ty::Ref { inner, .. } | Ty::Ptr { inner, .. } = obj_ty {
+            obj_ty = *inner;
+        }
+
+        let (name, generics) = match &obj_ty {
+            Ty::Struct { name, generics } => (name, generics),
+            // Fields of types defined elsewhere are not tracked
+            _ => return Ok(self.env.fresh_ty_var()),
+        };
+        let Some(struct_def) = self.structs.get(&name.name) else {
+            return Ok(self.env.fresh_ty_var());
+        };
+
+        match struct_def.fields.iter().find(|(field_name, _)| *field_name == field.name) {
+            Some((_, field_ty)) => {
+                let args = struct_def.generics.iter().cloned().zip(generics.iter().cloned()).collect();
+                Ok(subst_params(&args, field_ty))
+            }
+            None => Err(TypeError::UnknownField {
+                field: field.name.clone(),
+                ty: Box::new(obj_ty.clone()),
+                span: field.span,
+            }),
+        }
     }
 
     /// Type check array indexing
@@ -837,17 +3038,17 @@ impl TypeChecker {
         // Check all elements have the same type
         for elem in &elements[1..] {
             let ty = self.check_expression(elem)?;
-            if ty != elem_ty {
+            if self.unify(&elem_ty, &ty, &elem.span).is_err() {
                 return Err(TypeError::TypeMismatch {
-                    expected: elem_ty,
-                    found: ty,
+                    expected: Box::new(elem_ty),
+                    found: Box::new(ty),
                     span: elem.span,
                 });
             }
         }
 
         Ok(Ty::Array {
-            inner: Box::new(elem_ty),
+            inner: Box::new(self.apply_subst(&elem_ty)),
             len: Some(elements.len() as u64),
         })
     }
@@ -862,6 +3063,36 @@ impl TypeChecker {
         Ok(Ty::Tuple(elem_tys))
     }
 
+    /// Type check a template string, which is a `str`
+    ///
+    /// Each interpolated value must implement the formatting trait of its
+    /// spec, references formatting as the value they point to. Widths and
+    /// precisions taken from variables are `i32`s.
+    fn check_template_string(&mut self, template: &ast::TemplateString) -> Result<Ty> {
+        for part in &template.parts {
+            let ast::TemplateStringPart::Expr(value, spec) = part else { continue };
+            let mut ty = self.check_expression(value)?;
+            ty = self.apply_subst(&ty);
+            while let Ty::Ref { inner, .. } = &ty {
+                if formatted_by_runtime(&ty) {
+                    break;
+                }
+                ty = (**inner).clone();
+            }
+            self.bound_obligations.push((ty, spec.kind.trait_name().to_string(), value.span));
+
+            for count in [&spec.width, &spec.precision] {
+                if let Some(Count::Argument(Argument::Name(name))) = count {
+                    let name = Identifier { span: value.span, name: name.clone() };
+                    let count_ty = self.check_path(std::slice::from_ref(&name))?;
+                    self.unify(&Ty::I32, &count_ty, &value.span)?;
+                }
+            }
+        }
+        self.check_bound_obligations(false)?;
+        Ok(Ty::String)
+    }
+
     /// Type check an if expression with type inference
     fn check_if(
         &mut self,
@@ -870,12 +3101,13 @@ impl TypeChecker {
         else_block: &Option<ast::Block>,
     ) -> Result<Ty> {
         eprintln!("DEBUG check_if called!");
-        // Condition must be bool
-        let cond_ty = self.check_expression(condition)?;
-        self.unify(&cond_ty, &Ty::Bool, &condition.span)?;
+        // Condition must be bool; `if let` bindings are only visible in the then branch
+        let then_ty = self.in_scope(|this| {
+            let cond_ty = this.check_expression(condition)?;
+            this.unify(&cond_ty, &Ty::Bool, &condition.span)?;
 
-        // Check both branches
-        let then_ty = self.check_block(then_block)?;
+            this.check_block(then_block)
+        })?;
 
         let else_ty = match else_block {
             Some(block) => self.check_block(block)?,
@@ -915,9 +3147,52 @@ impl TypeChecker {
     }
 
     /// Type check a match expression
-    fn check_match(&mut self, _scrutinee: &Expression, _arms: &[ast::MatchArm]) -> Result<Ty> {
-        // TODO: Implement match expression checking
-        Ok(Ty::I32)
+    ///
+    /// Each arm's patterns are checked against the scrutinee's type and its
+    /// guard must be a `bool`; the arms' types unify, leaving out arms that
+    /// diverge. The arms must cover every value of the scrutinee, and arms
+    /// no value reaches are reported as warnings. Array and slice patterns
+    /// are rejected only then, as they cannot be compiled yet.
+    fn check_match(&mut self, scrutinee: &Expression, arms: &[ast::MatchArm]) -> Result<Ty> {
+        let scrutinee_ty = self.check_expression(scrutinee)?;
+
+        let mut match_ty: Option<Ty> = None;
+        for arm in arms {
+            let arm_ty = self.in_scope(|this| {
+                this.check_alternatives(&arm.patterns, &scrutinee_ty, &arm.span)?;
+                if let Some(guard) = &arm.guard {
+                    let guard_ty = this.check_expression(guard)?;
+                    this.unify(&Ty::Bool, &guard_ty, &guard.span)?;
+                }
+                this.check_expression(&arm.body)
+            })?;
+
+            let arm_ty = self.apply_subst(&arm_ty);
+            if arm_ty == Ty::Never {
+                continue;
+            }
+            match &match_ty {
+                Some(ty) => {
+                    let ty = ty.clone();
+                    self.unify(&ty, &arm_ty, &arm.body.span)?;
+                }
+                None => match_ty = Some(arm_ty),
+            }
+        }
+
+        let scrutinee_ty = self.apply_subst(&scrutinee_ty);
+        let report = MatchChecker::new(&self.structs, &self.enums, &self.variant_imports).check(arms, &scrutinee_ty);
+        for i in report.unreachable {
+            self.warnings.push(Warning::UnreachablePattern { span: arms[i].span });
+        }
+        if !report.missing.is_empty() {
+            return Err(TypeError::NonExhaustivePatterns { missing: report.missing, span: scrutinee.span });
+        }
+        for arm in arms {
+            reject_array_patterns(&arm.patterns, &arm.span)?;
+        }
+
+        Ok(match_ty.map_or(Ty::Never, |ty| self.apply_subst(&ty)))
     }
 
     /// Type check a loop expression
@@ -927,12 +3202,21 @@ impl TypeChecker {
     }
 
     /// Type check a while loop
-    fn check_while(&mut self, condition: &Expression, _body: &ast::Block) -> Result<Ty> {
+    fn check_while(&mut self, condition: &Expression, body: &ast::Block) -> Result<Ty> {
+        if let ast::ExpressionKind::Let(..) = condition.kind {
+            // `while let` bindings are visible in the body
+            return self.in_scope(|this| {
+                this.check_expression(condition)?;
+                this.check_block(body)?;
+                Ok(Ty::Unit)
+            });
+        }
+
         let cond_ty = self.check_expression(condition)?;
         if cond_ty != Ty::Bool {
             return Err(TypeError::TypeMismatch {
-                expected: Ty::Bool,
-                found: cond_ty,
+                expected: Box::new(Ty::Bool),
+                found: Box::new(cond_ty),
                 span: condition.span,
             });
         }
@@ -969,12 +3253,10 @@ impl TypeChecker {
                 let ty = self.ast_type_to_ty(type_ann);
 
                 // If it's a type variable, create a fresh one
-                let ty = match &ty {
+                match &ty {
                     Ty::TyVar(_) => self.env.fresh_ty_var(),
                     _ => ty,
-                };
-
-                ty
+                }
             } else {
                 // No type annotation - create fresh type variable for inference
                 self.env.fresh_ty_var()
@@ -1003,8 +3285,10 @@ impl TypeChecker {
             self.apply_subst(&body_ty)
         };
 
-        // Exit closure scope - swap back to parent environment
+        // Exit closure scope - swap back to parent environment, keeping
+        // type variable IDs unique
         std::mem::swap(&mut self.env, &mut closure_env);
+        self.env.advance_ty_vars_to(closure_env.peek_next_ty_var());
 
         // Construct the closure's function type
         // Closures are represented as function types: fn(params) -> return_type
@@ -1025,21 +3309,27 @@ impl TypeChecker {
         };
 
         // Check against current return type
-        if let Some(expected_ty) = &self.current_return_type {
-            if &value_ty != expected_ty {
-                let span = value.as_ref()
-                    .map(|v| v.span)
-                    .unwrap_or_else(|| {
-                        // Use a default span if expression is None
-                        zulon_parser::lexer::Span::new(
-                            zulon_parser::lexer::Position::new(1, 1),
-                            zulon_parser::lexer::Position::new(1, 1),
-                        )
-                    });
+        if let (Some(expected_ty), Some(expr)) = (self.current_return_type.clone(), value) {
+            if value_ty.is_ref() && is_trait_object_ref(&expected_ty) {
+                self.coerce(&value_ty, &expected_ty, &expr.span)?;
+                return Ok(Ty::Never);
+            }
+        }
+        if let Some(expected_ty) = self.current_return_type.clone() {
+            let span = value.as_ref()
+                .map(|v| v.span)
+                .unwrap_or_else(|| {
+                    // Use a default span if expression is None
+                    zulon_parser::lexer::Span::new(
+                        zulon_parser::lexer::Position::new(1, 1),
+                        zulon_parser::lexer::Position::new(1, 1),
+                    )
+                });
 
+            if self.unify(&expected_ty, &value_ty, &span).is_err() {
                 return Err(TypeError::TypeMismatch {
-                    expected: expected_ty.clone(),
-                    found: value_ty,
+                    expected: Box::new(expected_ty),
+                    found: Box::new(self.apply_subst(&value_ty)),
                     span,
                 });
             }
@@ -1059,18 +3349,18 @@ impl TypeChecker {
 
         // Check against the cached error type
         if let Some(expected_error_ty) = &expected_error_type {
-            if &error_ty != expected_error_ty {
+            if self.unify(expected_error_ty, &error_ty, &error_expr.span).is_err() {
                 return Err(TypeError::TypeMismatch {
-                    expected: expected_error_ty.clone(),
-                    found: error_ty,
-                    span: error_expr.span.clone(),
+                    expected: Box::new(expected_error_ty.clone()),
+                    found: Box::new(error_ty),
+                    span: error_expr.span,
                 });
             }
         } else {
             // Function doesn't have an error type but we're trying to throw
             return Err(TypeError::InferenceError {
                 message: "throw statement used in function without error type".to_string(),
-                span: error_expr.span.clone(),
+                span: error_expr.span,
             });
         }
 
@@ -1089,7 +3379,7 @@ impl TypeChecker {
             None => {
                 return Err(TypeError::InferenceError {
                     message: "? operator used in function without error type".to_string(),
-                    span: expr.span.clone(),
+                    span: expr.span,
                 });
             }
         };
@@ -1105,14 +3395,78 @@ impl TypeChecker {
     }
 
     /// Type check a struct literal
-    fn check_struct_literal(&mut self, _struct_lit: &ast::StructLiteral) -> Result<Ty> {
-        // TODO: Implement struct literal checking
-        Ok(Ty::Unit)
+    ///
+    /// A generic struct's type arguments are inferred from its field values.
+    fn check_struct_literal(&mut self, struct_lit: &ast::StructLiteral, span: &Span) -> Result<Ty> {
+        let Some(name) = struct_lit.path.last() else {
+            return Ok(self.env.fresh_ty_var());
+        };
+        let Some(struct_def) = self.structs.get(&name.name).cloned() else {
+            // Struct-like enum variants and unknown types: check the values only
+            for field in &struct_lit.fields {
+                self.check_expression(&field.value)?;
+            }
+            return match self.check_path(&struct_lit.path)? {
+                Ty::Function { return_type, .. } => Ok(*return_type),
+                ty => Ok(ty),
+            };
+        };
+
+        let args = self.instantiate(&struct_def.generics);
+        let struct_ty = Ty::Struct {
+            name: name.clone(),
+            generics: struct_def.generics.iter().map(|param| args[param].clone()).collect(),
+        };
+
+        for field in &struct_lit.fields {
+            let Some((_, field_ty)) = struct_def.fields.iter().find(|(field_name, _)| *field_name == field.name.name) else {
+                return Err(TypeError::UnknownField {
+                    field: field.name.name.clone(),
+                    ty: Box::new(struct_ty),
+                    span: field.name.span,
+                });
+            };
+            let value_ty = self.check_expression(&field.value)?;
+            self.coerce(&value_ty, &subst_params(&args, field_ty), &field.value.span)?;
+        }
+
+        match &struct_lit.base {
+            Some(base) => {
+                let base_ty = self.check_expression(base)?;
+                self.unify(&struct_ty, &base_ty, &base.span)?;
+            }
+            None => {
+                let missing = struct_def.fields.iter()
+                    .find(|(field_name, _)| !struct_lit.fields.iter().any(|field| field.name.name == *field_name));
+                if let Some((field_name, _)) = missing {
+                    return Err(TypeError::InferenceError {
+                        message: format!("missing field `{}` in initializer of `{}`", field_name, name.name),
+                        span: *span,
+                    });
+                }
+            }
+        }
+
+        Ok(self.apply_subst(&struct_ty))
     }
 
     /// Type check an assignment
-    fn check_assign(&mut self, _target: &Expression, _value: &Expression) -> Result<Ty> {
-        // TODO: Implement assignment checking
+    ///
+    /// Only the global items written to are checked for mutability so far:
+    /// a `const` or an immutable `static` cannot be assigned to.
+    fn check_assign(&mut self, target: &Expression, value: &Expression) -> Result<Ty> {
+        let target_ty = self.check_expression(target)?;
+        let value_ty = self.check_expression(value)?;
+
+        if let ast::ExpressionKind::Path(path) = &target.kind {
+            if let Some(name) = path.first().and_then(|segment| self.global_refs.get(&segment.span)) {
+                if !self.globals.get(name).is_some_and(|global| global.is_mutable) {
+                    return Err(TypeError::CannotAssignImmutable { span: target.span });
+                }
+            }
+        }
+
+        self.coerce(&value_ty, &target_ty, &value.span)?;
         Ok(Ty::Unit)
     }
 
@@ -1127,27 +3481,197 @@ impl TypeChecker {
         Ok(Ty::Unit)
     }
 
+    /// The payload types of `variant` of the enum `enum_name`, instantiated
+    /// with the enum's type arguments
+    fn variant_payload(&self, enum_name: &str, generics: &[Ty], variant: &str) -> Option<Vec<Ty>> {
+        let enum_def = self.enums.get(enum_name)?;
+        let (_, payload) = enum_def.variants.iter().find(|(name, _)| name == variant)?;
+        let args = enum_def.generics.iter().cloned().zip(generics.iter().cloned()).collect();
+        Some(payload.iter().map(|ty| subst_params(&args, ty)).collect())
+    }
+
     /// Apply current substitution to a type
     fn apply_subst(&self, ty: &Ty) -> Ty {
         self.subst.apply(ty)
     }
 
     /// Unify two types and update substitution
+    ///
+    /// If they differ while one contains a projection whose base is not
+    /// known yet, they are unified again once it is.
     fn unify(&mut self, ty1: &Ty, ty2: &Ty, span: &ast::Span) -> Result<()> {
         use crate::infer::unify_with_subst;
 
-        // Apply current substitution first
-        let ty1 = self.apply_subst(ty1);
-        let ty2 = self.apply_subst(ty2);
+        // Apply current substitution and normalize projections first
+        let ty1 = self.normalize(ty1);
+        let ty2 = self.normalize(ty2);
 
         // Unify and update substitution
-        unify_with_subst(&ty1, &ty2, span, &mut self.subst)
+        match unify_with_subst(&ty1, &ty2, span, &mut self.subst) {
+            Err(_) if ty1.has_unresolved_projection() || ty2.has_unresolved_projection() => {
+                self.pending_projections.push((ty1, ty2, *span));
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Unify the pairs of types deferred by [`unify`](Self::unify) whose
+    /// projections can now be normalized
+    fn resolve_pending_projections(&mut self) -> Result<()> {
+        for (ty1, ty2, span) in std::mem::take(&mut self.pending_projections) {
+            self.unify(&ty1, &ty2, &span)?;
+        }
+        Ok(())
+    }
+
+    /// Whether two types unify, leaving the substitution unchanged
+    fn types_unify(&mut self, ty1: &Ty, ty2: &Ty, span: &ast::Span) -> bool {
+        let saved = (self.subst.clone(), self.pending_projections.len());
+        let unifies = self.unify(ty1, ty2, span).is_ok();
+        self.subst = saved.0;
+        self.pending_projections.truncate(saved.1);
+        unifies
+    }
+
+    /// `ty` with the substitution applied and each projection whose base
+    /// matches exactly one impl of its trait replaced by the impl's
+    /// associated type
+    fn normalize(&mut self, ty: &Ty) -> Ty {
+        let normalize_all = |this: &mut Self, tys: &[Ty]| tys.iter().map(|ty| this.normalize(ty)).collect();
+        match self.apply_subst(ty) {
+            Ty::Projection { base, trait_name, name } => {
+                let base = self.normalize(&base);
+                match self.impl_assoc_type(&base, &trait_name, &name) {
+                    Some(ty) => self.normalize(&ty),
+                    None => Ty::Projection { base: Box::new(base), trait_name, name },
+                }
+            }
+            Ty::Ref { inner, mutable } => Ty::Ref { inner: Box::new(self.normalize(&inner)), mutable },
+            Ty::Ptr { inner, mutable } => Ty::Ptr { inner: Box::new(self.normalize(&inner)), mutable },
+            Ty::Array { inner, len } => Ty::Array { inner: Box::new(self.normalize(&inner)), len },
+            Ty::Slice(inner) => Ty::Slice(Box::new(self.normalize(&inner))),
+            Ty::Optional(inner) => Ty::Optional(Box::new(self.normalize(&inner))),
+            Ty::Tuple(tys) => Ty::Tuple(normalize_all(self, &tys)),
+            Ty::Function { params, return_type, variadic } => Ty::Function {
+                params: normalize_all(self, &params),
+                return_type: Box::new(self.normalize(&return_type)),
+                variadic,
+            },
+            Ty::Struct { name, generics } => Ty::Struct { name, generics: normalize_all(self, &generics) },
+            Ty::Enum { name, generics } => Ty::Enum { name, generics: normalize_all(self, &generics) },
+            ty => ty,
+        }
+    }
+
+    /// The associated type `name` of the one impl of `trait_name` whose
+    /// self type matches `base`, binding the type variables of `base` the
+    /// match determines
+    fn impl_assoc_type(&mut self, base: &Ty, trait_name: &str, name: &str) -> Option<Ty> {
+        use crate::infer::unify_with_subst;
+
+        if matches!(base, Ty::Param(_) | Ty::TyVar(_) | Ty::Projection { .. } | Ty::TraitObject(_)) {
+            return None;
+        }
+        let mut found = Vec::new();
+        for imp in self.impls.clone() {
+            if imp.trait_name.as_deref() != Some(trait_name) {
+                continue;
+            }
+            let Some((_, assoc)) = imp.assoc_types.iter().find(|(assoc, _)| assoc == name) else { continue };
+            let args = self.instantiate(&imp.generics);
+            let mut subst = self.subst.clone();
+            if unify_with_subst(&subst_params(&args, &imp.self_ty), base, &imp.span, &mut subst).is_ok() {
+                found.push((subst, subst_params(&args, assoc)));
+            }
+        }
+        if found.len() != 1 {
+            return None;
+        }
+        let (subst, ty) = found.pop()?;
+        self.subst = subst;
+        Some(ty)
+    }
+
+    /// The type `head::name` names, if `head` is `Self`, a type parameter
+    /// in scope or another type
+    ///
+    /// The associated type is looked up in the traits bounding a type
+    /// parameter, or those implemented for another type.
+    fn resolve_assoc_type_path(&mut self, head: &Identifier, name: &Identifier) -> Option<Ty> {
+        let base = if head.name == "Self" && self.self_ty.is_some() {
+            self.self_ty.clone()?
+        } else if self.generic_params.contains(&head.name) {
+            Ty::Param(head.name.clone())
+        } else {
+            self.env.lookup_type_def(&head.name)?
+        };
+
+        let traits = match &base {
+            Ty::Param(param) => self.bounded_traits(param),
+            _ => {
+                let mut traits = Vec::new();
+                for imp in self.impls.clone() {
+                    let Some(trait_name) = imp.trait_name else { continue };
+                    let args = self.instantiate(&imp.generics);
+                    if !traits.contains(&trait_name) && self.types_unify(&subst_params(&args, &imp.self_ty), &base, &name.span) {
+                        traits.push(trait_name);
+                    }
+                }
+                traits
+            }
+        };
+        let declaring: Vec<String> = traits.into_iter()
+            .filter(|trait_name| self.traits[trait_name].assoc_types.iter().any(|(assoc, _)| *assoc == name.name))
+            .collect();
+
+        let ty = match declaring.as_slice() {
+            [trait_name] => {
+                let projection = Ty::Projection {
+                    base: Box::new(base),
+                    trait_name: trait_name.clone(),
+                    name: name.name.clone(),
+                };
+                self.normalize(&projection)
+            }
+            [] => {
+                self.type_errors.push(TypeError::UndefinedAssocType { name: name.name.clone(), ty: Box::new(base), span: name.span });
+                self.env.fresh_ty_var()
+            }
+            _ => {
+                self.type_errors.push(TypeError::AmbiguousAssocType { name: name.name.clone(), ty: Box::new(base), span: name.span });
+                self.env.fresh_ty_var()
+            }
+        };
+        self.assoc_type_paths.insert(name.span, ty.clone());
+        Some(ty)
     }
 
     /// Convert AST type to Ty
-    fn ast_type_to_ty(&self, ty: &Type) -> Ty {
+    pub(crate) fn ast_type_to_ty(&mut self, ty: &Type) -> Ty {
+        // An associated type: `T::Item` or `Self::Item`
+        if let Type::Path(path) | Type::PathGeneric(path, None) = ty {
+            if let [head, name] = path.as_slice() {
+                if let Some(ty) = self.resolve_assoc_type_path(head, name) {
+                    return ty;
+                }
+            }
+        }
+
         match ty {
             Type::Simple(ident) => {
+                if ident.name == "Self" {
+                    if let Some(self_ty) = &self.self_ty {
+                        return self_ty.clone();
+                    }
+                }
+                if self.generic_params.contains(&ident.name) {
+                    return Ty::Param(ident.name.clone());
+                }
+                if let Some(ty) = self.expand_type_alias(ident, Vec::new()) {
+                    return ty;
+                }
+
                 // Check if this is an effect type (by looking up in effects)
                 if self.env.lookup_effect(&ident.name).is_some() {
                     return Ty::Effect(ident.name.clone());
@@ -1155,7 +3679,7 @@ impl TypeChecker {
 
                 // Look up type in environment
                 self.env.lookup_type_def(&ident.name)
-                    .unwrap_or(Ty::TyVar(self.env.peek_next_ty_var()))
+                    .unwrap_or_else(|| self.env.fresh_ty_var())
             }
             Type::Tuple(types) => {
                 let elem_tys: Vec<Ty> = types.iter()
@@ -1166,10 +3690,7 @@ impl TypeChecker {
             Type::Array(inner, size) => {
                 Ty::Array {
                     inner: Box::new(self.ast_type_to_ty(inner)),
-                    len: size.as_ref().map(|_s| {
-                        // TODO: Evaluate constant expression
-                        42  // Placeholder
-                    }),
+                    len: size.as_ref().and_then(|size| self.array_type_len(size)),
                 }
             }
             Type::Slice(inner) => {
@@ -1217,7 +3738,10 @@ impl TypeChecker {
             }
             Type::Never => Ty::Never,
             Type::Unit => Ty::Unit,
-            Type::TraitObject(inner) => Ty::TraitObject(Box::new(self.ast_type_to_ty(inner))),
+            Type::TraitObject(inner) => match trait_ref_name(inner) {
+                Some(name) => Ty::TraitObject(name.name.clone()),
+                None => self.env.fresh_ty_var(),
+            },
             Type::ImplTrait(inner) => Ty::ImplTrait(Box::new(self.ast_type_to_ty(inner))),
             Type::Pointer(inner, mutable) => {
                 Ty::Ref {
@@ -1229,25 +3753,34 @@ impl TypeChecker {
                 // For now, treat paths as simple types (first component)
                 if let Some(ident) = path.first() {
                     self.env.lookup_type_def(&ident.name)
-                        .unwrap_or(Ty::TyVar(self.env.peek_next_ty_var()))
+                        .unwrap_or_else(|| self.env.fresh_ty_var())
                 } else {
-                    Ty::TyVar(self.env.peek_next_ty_var())
+                    self.env.fresh_ty_var()
                 }
             }
             Type::PathGeneric(path, generic_args) => {
-                // Handle generic types like Outcome<i32, Error>
-                // For now, treat as struct with generic parameters
+                // Handle generic types like Outcome<i32, Error>; anything
+                // not declared as an enum is taken to be a struct
                 if let Some(ident) = path.first() {
-                    let args = generic_args.as_ref()
+                    let args: Vec<Ty> = generic_args.as_ref()
                         .map(|args| args.iter().map(|t| self.ast_type_to_ty(t)).collect())
                         .unwrap_or_default();
+                    if let Some(ty) = self.expand_type_alias(ident, args.clone()) {
+                        return ty;
+                    }
 
-                    Ty::Struct {
-                        name: ident.clone(),
-                        generics: args,
+                    match self.env.lookup_type_def(&ident.name) {
+                        Some(Ty::Enum { .. }) => Ty::Enum {
+                            name: ident.clone(),
+                            generics: args,
+                        },
+                        _ => Ty::Struct {
+                            name: ident.clone(),
+                            generics: args,
+                        },
                     }
                 } else {
-                    Ty::TyVar(self.env.peek_next_ty_var())
+                    self.env.fresh_ty_var()
                 }
             }
         }
@@ -1260,6 +3793,111 @@ impl Default for TypeChecker {
     }
 }
 
+/// The names of the type parameters in `generics`
+pub(crate) fn type_param_names(generics: &Option<ast::Generics>) -> Vec<String> {
+    generics.iter()
+        .flat_map(|generics| &generics.params)
+        .filter_map(|param| match param {
+            ast::GenericParam::Type(name) => Some(name.name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `sig` with its type parameters replaced by `args`
+fn instantiate_sig(args: &HashMap<String, Ty>, sig: &MethodSig) -> MethodSig {
+    MethodSig {
+        generics: Vec::new(),
+        bounds: HashMap::new(),
+        params: sig.params.iter().map(|ty| subst_params(args, ty)).collect(),
+        return_type: subst_params(args, &sig.return_type),
+        has_receiver: sig.has_receiver,
+        span: sig.span,
+    }
+}
+
+/// Fail if one of `patterns`, at `span`, destructures an array or a slice:
+/// those patterns type check, but later stages cannot lower them yet
+fn reject_array_patterns(patterns: &[ast::Pattern], span: &Span) -> Result<()> {
+    struct ArrayPatterns(bool);
+
+    impl Visitor<'_> for ArrayPatterns {
+        fn visit_pattern(&mut self, pattern: &ast::Pattern) {
+            self.0 |= matches!(pattern, ast::Pattern::Array(_) | ast::Pattern::Slice(..));
+            visit::walk_pattern(self, pattern);
+        }
+    }
+
+    let mut found = ArrayPatterns(false);
+    for pattern in patterns {
+        found.visit_pattern(pattern);
+    }
+    if found.0 {
+        return Err(TypeError::UnsupportedArrayPattern { span: *span });
+    }
+    Ok(())
+}
+
+/// Whether `ty` is `&dyn Trait` or `&mut dyn Trait`
+fn is_trait_object_ref(ty: &Ty) -> bool {
+    matches!(ty, Ty::Ref { inner, .. } if matches!(**inner, Ty::TraitObject(_)))
+}
+
+/// Whether the primitive type `ty` implements `trait_name` without an impl
+/// block: one of the [`BUILTIN_TRAITS`], or a trait of a format spec kind
+fn has_builtin_impl(ty: &Ty, trait_name: &str) -> bool {
+    if FormatKind::ALL.iter().any(|kind| kind.trait_name() == trait_name) {
+        return formatted_by_runtime(ty);
+    }
+    let is_float = ty.is_float();
+    BUILTIN_TRAITS.contains(&trait_name)
+        && (ty.is_numeric() || matches!(ty, Ty::Bool | Ty::Char))
+        && !(is_float && matches!(trait_name, "Eq" | "Ord" | "Hash"))
+}
+
+/// Whether the runtime's `zulon_format_*` functions format values of `ty`,
+/// for every formatting trait: the scalars up to 64 bits and strings
+fn formatted_by_runtime(ty: &Ty) -> bool {
+    match ty {
+        Ty::I128 | Ty::U128 => false,
+        Ty::Ref { inner, .. } => matches!(**inner, Ty::U8),
+        _ => ty.is_numeric() || matches!(ty, Ty::Bool | Ty::Char | Ty::String),
+    }
+}
+
+/// The name of the trait a type naming a trait refers to
+fn trait_ref_name(ty: &Type) -> Option<&Identifier> {
+    match ty {
+        Type::Simple(name) => Some(name),
+        Type::Path(path) | Type::PathGeneric(path, _) => path.last(),
+        _ => None,
+    }
+}
+
+/// Whether a block always leaves its enclosing control flow
+///
+/// This is a syntactic check: the block ends in (or contains at the top
+/// level) a `return`, `break`, `continue` or `throw`.
+fn block_diverges(block: &ast::Block) -> bool {
+    fn expr_diverges(expr: &Expression) -> bool {
+        match &expr.kind {
+            ast::ExpressionKind::Return(_)
+            | ast::ExpressionKind::Break(_)
+            | ast::ExpressionKind::Continue(_)
+            | ast::ExpressionKind::Throw(_) => true,
+            ast::ExpressionKind::Block(block) => block_diverges(block),
+            ast::ExpressionKind::Grouped(inner) => expr_diverges(inner),
+            ast::ExpressionKind::If(_, then_block, Some(else_block)) => {
+                block_diverges(then_block) && block_diverges(else_block)
+            }
+            _ => false,
+        }
+    }
+
+    block.statements.iter().any(|stmt| matches!(&stmt.kind, ast::StatementKind::Expr(expr) if expr_diverges(expr)))
+        || block.trailing_expr.as_deref().is_some_and(expr_diverges)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1582,4 +4220,173 @@ mod tests {
         let mut checker = TypeChecker::new();
         assert!(checker.check(&ast).is_ok());
     }
+
+    #[test]
+    fn test_generic_function_instantiation() {
+        let source = r#"
+            fn identity<T>(x: T) -> T { x }
+
+            fn main() -> i64 {
+                let b: bool = identity(true);
+                identity(7i64)
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check(&ast).unwrap();
+
+        let ItemKind::Function(main) = &ast.items[1].kind else {
+            panic!("expected main");
+        };
+        let call = main.body.trailing_expr.as_ref().unwrap();
+        assert_eq!(checker.call_generic_args(&call.span), Some(&[Ty::I64][..]));
+        assert_eq!(checker.expr_type(&call.span), Some(&Ty::I64));
+    }
+
+    #[test]
+    fn test_generic_function_return_mismatch() {
+        let source = r#"
+            fn identity<T>(x: T) -> T { x }
+
+            fn main() -> bool {
+                identity(1i64)
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        assert!(TypeChecker::new().check(&ast).is_err());
+    }
+
+    #[test]
+    fn test_generic_struct_fields() {
+        let source = r#"
+            struct Pair<T> {
+                first: T,
+                second: T,
+            }
+
+            fn main() -> i64 {
+                let p = Pair { first: 1i64, second: 2i64 };
+                p.second
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        assert!(TypeChecker::new().check(&ast).is_ok());
+
+        let mismatched = source.replace("second: 2i64", "second: true");
+        let ast = Parser::from_source(&mismatched).parse().unwrap();
+        assert!(TypeChecker::new().check(&ast).is_err());
+
+        let unknown = source.replace("p.second", "p.third");
+        let ast = Parser::from_source(&unknown).parse().unwrap();
+        assert!(matches!(TypeChecker::new().check(&ast), Err(TypeError::UnknownField { .. })));
+    }
+
+    #[test]
+    fn test_generic_enum_constructor() {
+        let source = r#"
+            fn main() -> Maybe<bool> {
+                Maybe::Just(true)
+            }
+
+            enum Maybe<T> {
+                Just(T),
+                Nothing,
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        assert!(TypeChecker::new().check(&ast).is_ok());
+
+        let mismatched = source.replace("Just(true)", "Just(1i64)");
+        let ast = Parser::from_source(&mismatched).parse().unwrap();
+        assert!(TypeChecker::new().check(&ast).is_err());
+    }
+
+    #[test]
+    fn test_uninferred_generic_call() {
+        let source = r#"
+            fn nothing<T>() -> i32 { 0 }
+
+            fn main() -> i32 {
+                nothing()
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        assert!(matches!(TypeChecker::new().check(&ast), Err(TypeError::InferenceError { .. })));
+    }
+
+    #[test]
+    fn test_method_calls_and_associated_functions() {
+        let source = r#"
+            struct Point { x: i32, y: i32 }
+
+            impl Point {
+                fn new(x: i32, y: i32) -> Self {
+                    Point { x: x, y: y }
+                }
+
+                fn sum(&self) -> i32 {
+                    self.x + self.y
+                }
+
+                fn shift(&mut self, dx: i32) {}
+            }
+
+            fn main() -> i32 {
+                let p = Point::new(1, 2);
+                p.shift(3);
+                p.sum()
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check(&ast).unwrap();
+
+        let resolutions: Vec<_> = checker.method_calls.values().collect();
+        assert_eq!(resolutions.len(), 3);
+        assert!(resolutions.iter().any(|r| r.autoref == Some(true)
+            && matches!(&r.callee, MethodCallee::Impl { function, .. } if function == "Point::shift")));
+        assert!(resolutions.iter().any(|r| r.autoref.is_none()
+            && matches!(&r.callee, MethodCallee::Impl { function, .. } if function == "Point::new")));
+
+        let missing = source.replace("p.sum()", "p.product()");
+        let ast = Parser::from_source(&missing).parse().unwrap();
+        assert!(matches!(TypeChecker::new().check(&ast), Err(TypeError::NoMethod { .. })));
+    }
+
+    #[test]
+    fn test_trait_method_through_reference() {
+        let source = r#"
+            trait Shape {
+                fn area(&self) -> i32;
+                fn double(&self) -> i32 { self.area() * 2 }
+            }
+
+            struct Square { side: i32 }
+
+            impl Shape for Square {
+                fn area(&self) -> i32 { self.side * self.side }
+            }
+
+            fn total<T: Shape>(shape: &T) -> i32 {
+                shape.area() + shape.double()
+            }
+        "#;
+
+        let ast = Parser::from_source(source).parse().unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check(&ast).unwrap();
+
+        // `shape.area()` on `&T` reuses the reference instead of reborrowing
+        let bound_calls: Vec<_> = checker.method_calls.values()
+            .filter(|r| matches!(&r.callee, MethodCallee::Trait { self_ty: Ty::Param(param), .. } if param == "T"))
+            .collect();
+        assert_eq!(bound_calls.len(), 2);
+        assert!(bound_calls.iter().all(|r| r.autoderefs == 0 && r.autoref.is_none()));
+    }
 }
@@ -0,0 +1,775 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Compile-time evaluation of constant expressions
+//!
+//! The initializers of `const` and `static` items, array lengths and enum
+//! discriminants are evaluated while type checking, since types depend on
+//! their values. The evaluator interprets the expression directly: literals,
+//! arithmetic, comparisons, casts, tuples, arrays, struct literals, blocks
+//! with `let`, `if`, loops, and calls of `const fn`s. Anything else, like a
+//! call of an ordinary function, is rejected.
+//!
+//! Integers are computed as `i128` and checked against the range of their
+//! type after every operation, so overflow, like division by zero, is a
+//! compile-time error. An integer literal without a suffix takes the type
+//! expected where it appears, or the type of the other operand; one whose
+//! type is still unknown is only checked once it reaches a typed place,
+//! like a `let` with a type annotation or a function's return value.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use crate::checker::{type_param_names, TypeChecker};
+use crate::error::{Result, TypeError};
+use crate::ty::Ty;
+use zulon_parser::ast::{self, BinaryOp, Expression, ExpressionKind, Identifier, Span, UnaryOp};
+use zulon_parser::Position;
+
+/// How many expressions one evaluation may evaluate, so that a `const fn`
+/// that does not terminate is reported rather than hanging the compiler
+const STEP_LIMIT: usize = 1_000_000;
+
+/// How deeply `const fn` calls may nest
+const CALL_DEPTH_LIMIT: usize = 128;
+
+/// The value of a constant expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    /// An integer, a `char` cast or a `u8` byte, of any integer type
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Unit,
+    Tuple(Vec<ConstValue>),
+    Array(Vec<ConstValue>),
+    /// A struct, with its fields in declaration order
+    Struct {
+        name: String,
+        fields: Vec<(String, ConstValue)>,
+    },
+}
+
+/// A value with its type, when known; integer literals without a suffix
+/// have none until one is inferred for them
+#[derive(Debug, Clone)]
+struct Typed {
+    value: ConstValue,
+    ty: Option<Ty>,
+}
+
+impl Typed {
+    fn new(value: ConstValue, ty: Option<Ty>) -> Self {
+        Typed { value, ty }
+    }
+
+    fn unit() -> Self {
+        Typed::new(ConstValue::Unit, Some(Ty::Unit))
+    }
+
+    /// The type of the value, for error messages
+    fn describe(&self) -> Ty {
+        if let Some(ty) = &self.ty {
+            return ty.clone();
+        }
+        match &self.value {
+            ConstValue::Int(_) => Ty::I32,
+            ConstValue::Float(_) => Ty::F64,
+            ConstValue::Bool(_) => Ty::Bool,
+            ConstValue::Char(_) => Ty::Char,
+            ConstValue::Str(_) => Ty::String,
+            ConstValue::Unit => Ty::Unit,
+            ConstValue::Tuple(values) => Ty::Tuple(values.iter().map(|value| Typed::new(value.clone(), None).describe()).collect()),
+            ConstValue::Array(values) => Ty::Array {
+                inner: Box::new(values.first().map(|value| Typed::new(value.clone(), None).describe()).unwrap_or(Ty::Unit)),
+                len: Some(values.len() as u64),
+            },
+            ConstValue::Struct { name, .. } => Ty::Struct {
+                name: Identifier::new(Span::new(Position::new(0, 0), Position::new(0, 0)), name.clone()),
+                generics: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Why evaluation stopped before producing a value: an error, or control
+/// flow leaving the expression
+enum Interrupt {
+    Error(Box<TypeError>),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(Box<Typed>),
+}
+
+impl From<TypeError> for Interrupt {
+    fn from(error: TypeError) -> Self {
+        Interrupt::Error(Box::new(error))
+    }
+}
+
+type Eval<T> = std::result::Result<T, Interrupt>;
+
+/// Evaluate `expr` as a value of type `ty`
+pub(crate) fn evaluate(checker: &mut TypeChecker, expr: &Expression, ty: &Ty) -> Result<ConstValue> {
+    let mut evaluator = ConstEvaluator {
+        checker,
+        scopes: Vec::new(),
+        steps: 0,
+        depth: 0,
+    };
+    let result = evaluator.eval(expr, Some(ty)).and_then(|value| Ok(evaluator.coerce(value, ty, &expr.span)?));
+    match result {
+        Ok(value) => Ok(value),
+        Err(Interrupt::Error(error)) => Err(*error),
+        // The type checker rejects `break`, `continue` and `return` outside
+        // of a loop or function
+        Err(_) => Err(not_const("control flow outside of a loop or function", &expr.span)),
+    }
+}
+
+/// Interpreter state for one evaluation
+struct ConstEvaluator<'a> {
+    checker: &'a mut TypeChecker,
+    /// The local variables of the `const fn` being evaluated, innermost
+    /// block last
+    scopes: Vec<HashMap<String, Typed>>,
+    steps: usize,
+    depth: usize,
+}
+
+impl ConstEvaluator<'_> {
+    fn eval(&mut self, expr: &Expression, expected: Option<&Ty>) -> Eval<Typed> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return Err(TypeError::ConstEvalFailed {
+                message: format!("exceeded the limit of {} steps", STEP_LIMIT),
+                span: expr.span,
+            }.into());
+        }
+
+        let span = &expr.span;
+        match &expr.kind {
+            ExpressionKind::Literal(literal) => self.eval_literal(literal, expected, span),
+            ExpressionKind::Grouped(inner) => self.eval(inner, expected),
+            ExpressionKind::Path(path) => self.eval_path(path, span),
+            ExpressionKind::Binary(op, left, right) => self.eval_binary(*op, left, right, expected, span),
+            ExpressionKind::Unary(op, operand) => self.eval_unary(*op, operand, expected, span),
+            ExpressionKind::Cast(operand, target) => {
+                let value = self.eval(operand, None)?;
+                let target = self.checker.ast_type_to_ty(target);
+                self.cast(value, &target, span)
+            }
+            ExpressionKind::Block(block) => self.eval_block(block, expected),
+            ExpressionKind::If(condition, then_block, else_block) => {
+                if self.eval_bool(condition)? {
+                    self.eval_block(then_block, expected)
+                } else if let Some(else_block) = else_block {
+                    self.eval_block(else_block, expected)
+                } else {
+                    Ok(Typed::unit())
+                }
+            }
+            ExpressionKind::Loop(body, label) => {
+                self.eval_loop(label, |_| Ok(true), |this| this.eval_block(body, None).map(|_| ()))
+            }
+            ExpressionKind::While(condition, body, label) => {
+                self.eval_loop(label, |this| this.eval_bool(condition), |this| this.eval_block(body, None).map(|_| ()))
+            }
+            ExpressionKind::For(local, iter, body, label) => self.eval_for(local, iter, body, label, span),
+            ExpressionKind::Break(label) => Err(Interrupt::Break(label.as_ref().map(|label| label.name.clone()))),
+            ExpressionKind::Continue(label) => Err(Interrupt::Continue(label.as_ref().map(|label| label.name.clone()))),
+            ExpressionKind::Return(value) => {
+                let value = match value {
+                    Some(value) => self.eval(value, None)?,
+                    None => Typed::unit(),
+                };
+                Err(Interrupt::Return(Box::new(value)))
+            }
+            ExpressionKind::Assign(target, value) => {
+                let name = self.assign_target(target)?;
+                let expected = self.lookup(&name).and_then(|local| local.ty.clone());
+                let value = self.eval(value, expected.as_ref())?;
+                self.assign(&name, value);
+                Ok(Typed::unit())
+            }
+            ExpressionKind::AssignOp(op, target, value) => {
+                let name = self.assign_target(target)?;
+                let result = self.eval_binary(*op, target, value, None, span)?;
+                self.assign(&name, result);
+                Ok(Typed::unit())
+            }
+            ExpressionKind::Call(callee, args) => self.eval_call(callee, args, span),
+            ExpressionKind::Tuple(elements) => {
+                let expected_elements = match expected {
+                    Some(Ty::Tuple(tys)) if tys.len() == elements.len() => tys.iter().map(Some).collect(),
+                    _ => vec![None; elements.len()],
+                };
+                let values = elements.iter()
+                    .zip(expected_elements)
+                    .map(|(element, ty)| self.eval_typed(element, ty))
+                    .collect::<Eval<_>>()?;
+                Ok(Typed::new(ConstValue::Tuple(values), None))
+            }
+            ExpressionKind::Array(elements) => {
+                let inner = match expected {
+                    Some(Ty::Array { inner, .. }) => Some(inner.as_ref().clone()),
+                    _ => None,
+                };
+                let values = elements.iter()
+                    .map(|element| self.eval_typed(element, inner.as_ref()))
+                    .collect::<Eval<_>>()?;
+                Ok(Typed::new(ConstValue::Array(values), None))
+            }
+            ExpressionKind::Index(base, index) => {
+                let base = self.eval(base, None)?;
+                let index = self.eval(index, Some(&Ty::USize))?;
+                match (base.value, index.value) {
+                    (ConstValue::Array(values), ConstValue::Int(index)) => {
+                        let len = values.len();
+                        let element = usize::try_from(index).ok().and_then(|index| values.into_iter().nth(index));
+                        element.map(|value| Typed::new(value, None)).ok_or_else(|| TypeError::ConstEvalFailed {
+                            message: format!("index out of bounds: the length is {} but the index is {}", len, index),
+                            span: *span,
+                        }.into())
+                    }
+                    _ => Err(not_const("this indexing", span).into()),
+                }
+            }
+            ExpressionKind::TupleIndex(base, index) => match self.eval(base, None)?.value {
+                ConstValue::Tuple(mut values) if *index < values.len() => Ok(Typed::new(values.swap_remove(*index), None)),
+                _ => Err(not_const("this field access", span).into()),
+            },
+            ExpressionKind::FieldAccess(base, field) => match self.eval(base, None)?.value {
+                ConstValue::Struct { name, fields } => {
+                    let ty = self.field_ty(&name, &field.name);
+                    fields.into_iter()
+                        .find(|(name, _)| *name == field.name)
+                        .map(|(_, value)| Typed::new(value, ty))
+                        .ok_or_else(|| not_const("this field access", span).into())
+                }
+                _ => Err(not_const("this field access", span).into()),
+            },
+            ExpressionKind::Struct(literal) => self.eval_struct(literal, span),
+            ExpressionKind::Match(..) => Err(not_const("a `match` expression", span).into()),
+            ExpressionKind::MethodCall(_, method, _) => {
+                Err(not_const(&format!("calling method `{}`", method.name), span).into())
+            }
+            _ => Err(not_const("this expression", span).into()),
+        }
+    }
+
+    /// Evaluate `expr` and give it the type `ty` if known
+    fn eval_typed(&mut self, expr: &Expression, ty: Option<&Ty>) -> Eval<ConstValue> {
+        let value = self.eval(expr, ty)?;
+        match ty {
+            Some(ty) => Ok(self.coerce(value, ty, &expr.span)?),
+            None => Ok(value.value),
+        }
+    }
+
+    fn eval_bool(&mut self, expr: &Expression) -> Eval<bool> {
+        match self.eval(expr, Some(&Ty::Bool))? {
+            Typed { value: ConstValue::Bool(value), .. } => Ok(value),
+            other => Err(mismatch(&Ty::Bool, &other, &expr.span).into()),
+        }
+    }
+
+    fn eval_literal(&mut self, literal: &ast::Literal, expected: Option<&Ty>, span: &Span) -> Eval<Typed> {
+        Ok(match literal {
+            ast::Literal::Int(value, suffix) => {
+                let ty = suffix.map(TypeChecker::int_suffix_ty)
+                    .or_else(|| expected.filter(|ty| ty.int_bits().is_some()).cloned());
+                let value = i128::try_from(*value).map_err(|_| TypeError::LiteralOutOfRange {
+                    ty: Box::new(ty.clone().unwrap_or(Ty::I128)),
+                    span: *span,
+                })?;
+                Typed::new(ConstValue::Int(fit(value, ty.as_ref(), span)?), ty)
+            }
+            ast::Literal::Float(value, suffix) => {
+                let ty = match suffix {
+                    Some(ast::FloatSuffix::F32) => Some(Ty::F32),
+                    Some(ast::FloatSuffix::F64) => Some(Ty::F64),
+                    None => expected.filter(|ty| ty.is_float()).cloned(),
+                };
+                Typed::new(ConstValue::Float(round_float(*value, ty.as_ref())), ty)
+            }
+            ast::Literal::Bool(value) => Typed::new(ConstValue::Bool(*value), Some(Ty::Bool)),
+            ast::Literal::Char(value) => Typed::new(ConstValue::Char(*value), Some(Ty::Char)),
+            ast::Literal::Byte(value) => Typed::new(ConstValue::Int(*value as i128), Some(Ty::U8)),
+            ast::Literal::String(value) => Typed::new(ConstValue::Str(value.clone()), Some(Ty::String)),
+            ast::Literal::ByteString(bytes) => Typed::new(
+                ConstValue::Array(bytes.iter().map(|byte| ConstValue::Int(*byte as i128)).collect()),
+                Some(Ty::Array { inner: Box::new(Ty::U8), len: Some(bytes.len() as u64) }),
+            ),
+            ast::Literal::Null => return Err(not_const("`null`", span).into()),
+        })
+    }
+
+    /// A local variable, a `const` item, or the `MIN` or `MAX` of an
+    /// integer type
+    fn eval_path(&mut self, path: &[Identifier], span: &Span) -> Eval<Typed> {
+        match path {
+            [name] => {
+                if let Some(local) = self.lookup(&name.name) {
+                    return Ok(local.clone());
+                }
+                match self.checker.global_value(&name.name, span)? {
+                    Some((value, ty)) => Ok(Typed::new(value, Some(ty))),
+                    None => Err(TypeError::UndefinedVariable { name: name.name.clone(), span: name.span }.into()),
+                }
+            }
+            [ty, bound] if matches!(bound.name.as_str(), "MIN" | "MAX") => {
+                let ty = self.checker.ast_type_to_ty(&ast::Type::Simple(ty.clone()));
+                let is_max = bound.name == "MAX";
+                match ty.int_max_magnitude(!is_max) {
+                    Some(magnitude) => {
+                        let value = if is_max { i128::try_from(magnitude).unwrap_or(i128::MAX) } else { -(magnitude as i128) };
+                        Ok(Typed::new(ConstValue::Int(value), Some(ty)))
+                    }
+                    None => Err(not_const(&format!("`{}::{}`", path[0].name, bound.name), span).into()),
+                }
+            }
+            _ => Err(not_const(&format!("`{}`", join_path(path)), span).into()),
+        }
+    }
+
+    fn eval_binary(&mut self, op: BinaryOp, left: &Expression, right: &Expression, expected: Option<&Ty>, span: &Span) -> Eval<Typed> {
+        match op {
+            BinaryOp::And | BinaryOp::Or => {
+                let left = self.eval_bool(left)?;
+                let value = if left == (op == BinaryOp::Or) { left } else { self.eval_bool(right)? };
+                return Ok(Typed::new(ConstValue::Bool(value), Some(Ty::Bool)));
+            }
+            BinaryOp::Eq | BinaryOp::NotEq | BinaryOp::Less | BinaryOp::LessEq | BinaryOp::Greater | BinaryOp::GreaterEq => {
+                let left = self.eval(left, None)?;
+                let right = self.eval(right, left.ty.as_ref())?;
+                return self.compare(op, left, right, span);
+            }
+            _ => {}
+        }
+
+        let is_shift = matches!(op, BinaryOp::LeftShift | BinaryOp::RightShift);
+        let left = self.eval(left, expected)?;
+        let right_expected = if is_shift { None } else { left.ty.clone().or_else(|| expected.cloned()) };
+        let right = self.eval(right, right_expected.as_ref())?;
+        let ty = if is_shift { left.ty.clone() } else { left.ty.clone().or_else(|| right.ty.clone()) };
+
+        match (&left.value, &right.value) {
+            (ConstValue::Int(a), ConstValue::Int(b)) => {
+                let (a, b) = (*a, *b);
+                let overflow = || TypeError::ConstOverflow { ty: Box::new(ty.clone().unwrap_or(Ty::I128)), span: *span };
+                let value = match op {
+                    BinaryOp::Add => a.checked_add(b).ok_or_else(overflow)?,
+                    BinaryOp::Sub => a.checked_sub(b).ok_or_else(overflow)?,
+                    BinaryOp::Mul => a.checked_mul(b).ok_or_else(overflow)?,
+                    BinaryOp::Div | BinaryOp::Mod if b == 0 => {
+                        return Err(TypeError::ConstDivisionByZero { span: *span }.into());
+                    }
+                    BinaryOp::Div => a.checked_div(b).ok_or_else(overflow)?,
+                    BinaryOp::Mod => a.checked_rem(b).ok_or_else(overflow)?,
+                    BinaryOp::BitAnd => a & b,
+                    BinaryOp::BitOr => a | b,
+                    BinaryOp::BitXor => a ^ b,
+                    BinaryOp::LeftShift | BinaryOp::RightShift => {
+                        let bits = ty.as_ref().and_then(Ty::int_bits).map_or(128, |(bits, _)| bits);
+                        let amount = u32::try_from(b).ok().filter(|amount| *amount < bits).ok_or_else(overflow)?;
+                        if op == BinaryOp::LeftShift {
+                            wrap(a << amount, ty.as_ref())
+                        } else {
+                            a >> amount
+                        }
+                    }
+                    _ => unreachable!("comparisons and logical operators are handled above"),
+                };
+                Ok(Typed::new(ConstValue::Int(fit(value, ty.as_ref(), span)?), ty))
+            }
+            (ConstValue::Float(a), ConstValue::Float(b)) => {
+                let value = match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Mod => a % b,
+                    _ => return Err(not_const("this operation on floats", span).into()),
+                };
+                Ok(Typed::new(ConstValue::Float(round_float(value, ty.as_ref())), ty))
+            }
+            (ConstValue::Bool(a), ConstValue::Bool(b)) => {
+                let value = match op {
+                    BinaryOp::BitAnd => a & b,
+                    BinaryOp::BitOr => a | b,
+                    BinaryOp::BitXor => a ^ b,
+                    _ => return Err(not_const("this operation on booleans", span).into()),
+                };
+                Ok(Typed::new(ConstValue::Bool(value), Some(Ty::Bool)))
+            }
+            _ => Err(mismatch(&left.describe(), &right, span).into()),
+        }
+    }
+
+    fn compare(&mut self, op: BinaryOp, left: Typed, right: Typed, span: &Span) -> Eval<Typed> {
+        use std::cmp::Ordering;
+
+        let ordering = match (&left.value, &right.value) {
+            (ConstValue::Int(a), ConstValue::Int(b)) => Some(a.cmp(b)),
+            (ConstValue::Float(a), ConstValue::Float(b)) => a.partial_cmp(b),
+            (ConstValue::Bool(a), ConstValue::Bool(b)) => Some(a.cmp(b)),
+            (ConstValue::Char(a), ConstValue::Char(b)) => Some(a.cmp(b)),
+            (ConstValue::Str(a), ConstValue::Str(b)) => Some(a.cmp(b)),
+            (a, b) if matches!(op, BinaryOp::Eq | BinaryOp::NotEq) => {
+                if std::mem::discriminant(a) != std::mem::discriminant(b) {
+                    return Err(mismatch(&left.describe(), &right, span).into());
+                }
+                Some(if a == b { Ordering::Equal } else { Ordering::Less })
+            }
+            _ => return Err(mismatch(&left.describe(), &right, span).into()),
+        };
+        let value = match op {
+            BinaryOp::Eq => ordering == Some(Ordering::Equal),
+            BinaryOp::NotEq => ordering != Some(Ordering::Equal),
+            BinaryOp::Less => ordering == Some(Ordering::Less),
+            BinaryOp::LessEq => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            BinaryOp::Greater => ordering == Some(Ordering::Greater),
+            BinaryOp::GreaterEq => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            _ => unreachable!("only comparisons are compared"),
+        };
+        Ok(Typed::new(ConstValue::Bool(value), Some(Ty::Bool)))
+    }
+
+    fn eval_unary(&mut self, op: UnaryOp, operand: &Expression, expected: Option<&Ty>, span: &Span) -> Eval<Typed> {
+        let operand = self.eval(operand, expected)?;
+        let ty = operand.ty.clone();
+        let value = match (op, operand.value) {
+            (UnaryOp::Neg, ConstValue::Int(value)) => {
+                let negated = value.checked_neg().ok_or_else(|| TypeError::ConstOverflow {
+                    ty: Box::new(ty.clone().unwrap_or(Ty::I128)),
+                    span: *span,
+                })?;
+                ConstValue::Int(fit(negated, ty.as_ref(), span)?)
+            }
+            (UnaryOp::Neg, ConstValue::Float(value)) => ConstValue::Float(-value),
+            (UnaryOp::Not, ConstValue::Bool(value)) => ConstValue::Bool(!value),
+            (UnaryOp::Not | UnaryOp::BitNot, ConstValue::Int(value)) => ConstValue::Int(wrap(!value, ty.as_ref())),
+            _ => return Err(not_const("this unary operation", span).into()),
+        };
+        Ok(Typed::new(value, ty))
+    }
+
+    /// `value as target`: integers wrap to the target's width and floats
+    /// saturate to its range, as at run time
+    fn cast(&mut self, value: Typed, target: &Ty, span: &Span) -> Eval<Typed> {
+        let converted = match (&value.value, target) {
+            (ConstValue::Int(value), ty) if ty.int_bits().is_some() => ConstValue::Int(wrap(*value, Some(ty))),
+            (ConstValue::Int(value), ty) if ty.is_float() => ConstValue::Float(round_float(*value as f64, Some(ty))),
+            (ConstValue::Int(int), Ty::Char) => match u8::try_from(*int) {
+                Ok(byte) if value.ty.as_ref().is_none_or(|ty| *ty == Ty::U8) => ConstValue::Char(byte as char),
+                _ => return Err(TypeError::CannotConvert { from: Box::new(value.describe()), to: Box::new(Ty::Char), span: *span }.into()),
+            },
+            (ConstValue::Float(value), ty) if ty.is_float() => ConstValue::Float(round_float(*value, Some(ty))),
+            (ConstValue::Float(value), ty) if ty.int_bits().is_some() => {
+                let min = ty.int_max_magnitude(true).map_or(0.0, |magnitude| -(magnitude as f64));
+                let max = ty.int_max_magnitude(false).map_or(0.0, |magnitude| magnitude as f64);
+                let value = if value.is_nan() { 0.0 } else { value.trunc().clamp(min, max) };
+                ConstValue::Int(fit(value as i128, Some(ty), span)?)
+            }
+            (ConstValue::Bool(value), ty) if ty.int_bits().is_some() => ConstValue::Int(*value as i128),
+            (ConstValue::Char(value), ty) if ty.int_bits().is_some() => ConstValue::Int(wrap(*value as i128, Some(ty))),
+            (ConstValue::Char(_), Ty::Char) | (ConstValue::Bool(_), Ty::Bool) => value.value.clone(),
+            _ => return Err(TypeError::CannotConvert { from: Box::new(value.describe()), to: Box::new(target.clone()), span: *span }.into()),
+        };
+        Ok(Typed::new(converted, Some(target.clone())))
+    }
+
+    fn eval_block(&mut self, block: &ast::Block, expected: Option<&Ty>) -> Eval<Typed> {
+        self.scopes.push(HashMap::new());
+        let result = self.eval_block_in_scope(block, expected);
+        self.scopes.pop();
+        result
+    }
+
+    fn eval_block_in_scope(&mut self, block: &ast::Block, expected: Option<&Ty>) -> Eval<Typed> {
+        for statement in &block.statements {
+            match &statement.kind {
+                ast::StatementKind::Local(local) => {
+                    let ty = local.type_annotation.as_ref().map(|ty| self.checker.ast_type_to_ty(ty));
+                    let Some(init) = &local.init else {
+                        return Err(not_const("a `let` without an initializer", &statement.span).into());
+                    };
+                    let value = self.eval(init, ty.as_ref())?;
+                    let value = match &ty {
+                        Some(ty) => Typed::new(self.coerce(value, ty, &init.span)?, Some(ty.clone())),
+                        None => value,
+                    };
+                    self.bind(&local.name.name, value);
+                }
+                ast::StatementKind::Expr(expr) => {
+                    self.eval(expr, None)?;
+                }
+                // Nested items are checked on their own
+                ast::StatementKind::Item(_) | ast::StatementKind::Empty => {}
+                _ => return Err(not_const("this statement", &statement.span).into()),
+            }
+        }
+        match &block.trailing_expr {
+            Some(expr) => self.eval(expr, expected),
+            None => Ok(Typed::unit()),
+        }
+    }
+
+    /// Run a loop while `condition` holds, stopping at a `break` of this
+    /// loop
+    fn eval_loop(
+        &mut self,
+        label: &Option<Identifier>,
+        mut condition: impl FnMut(&mut Self) -> Eval<bool>,
+        mut body: impl FnMut(&mut Self) -> Eval<()>,
+    ) -> Eval<Typed> {
+        let is_ours = |target: &Option<String>| match (target, label) {
+            (None, _) => true,
+            (Some(target), Some(label)) => *target == label.name,
+            (Some(_), None) => false,
+        };
+        while condition(self)? {
+            match body(self) {
+                Ok(()) => {}
+                Err(Interrupt::Break(target)) if is_ours(&target) => break,
+                Err(Interrupt::Continue(target)) if is_ours(&target) => continue,
+                Err(interrupt) => return Err(interrupt),
+            }
+        }
+        Ok(Typed::unit())
+    }
+
+    /// `for i in start..end`, over an integer range
+    fn eval_for(&mut self, local: &ast::Local, iter: &Expression, body: &ast::Block, label: &Option<Identifier>, span: &Span) -> Eval<Typed> {
+        let ExpressionKind::Range(start, kind, end) = &iter.kind else {
+            return Err(not_const("a `for` loop over anything but an integer range", span).into());
+        };
+        let start = self.eval(start, None)?;
+        let end = self.eval(end, start.ty.as_ref())?;
+        let ty = start.ty.clone().or_else(|| end.ty.clone());
+        let (ConstValue::Int(start), ConstValue::Int(end)) = (start.value, end.value) else {
+            return Err(not_const("a `for` loop over anything but an integer range", span).into());
+        };
+        let end = if *kind == ast::RangeKind::Inclusive { end.saturating_add(1) } else { end };
+
+        let next = Cell::new(start);
+        self.eval_loop(
+            label,
+            |_| Ok(next.get() < end),
+            |this| {
+                let value = Typed::new(ConstValue::Int(next.get()), ty.clone());
+                this.scopes.push(HashMap::from([(local.name.name.clone(), value)]));
+                next.set(next.get() + 1);
+                let result = this.eval_block(body, None);
+                this.scopes.pop();
+                result.map(|_| ())
+            },
+        )
+    }
+
+    /// A call of a `const fn` by name
+    fn eval_call(&mut self, callee: &Expression, args: &[Box<Expression>], span: &Span) -> Eval<Typed> {
+        let ExpressionKind::Path(path) = &callee.kind else {
+            return Err(not_const("calling this expression", span).into());
+        };
+        let [name] = path.as_slice() else {
+            return Err(not_const(&format!("calling `{}`", join_path(path)), span).into());
+        };
+        let func = match self.checker.const_fn(&name.name) {
+            Some(Some(func)) => func,
+            Some(None) => return Err(not_const(&format!("calling non-const fn `{}`", name.name), span).into()),
+            None => return Err(TypeError::UndefinedFunction { name: name.name.clone(), span: name.span }.into()),
+        };
+        if !type_param_names(&func.generics).is_empty() {
+            return Err(not_const(&format!("calling generic fn `{}`", name.name), span).into());
+        }
+        if args.len() != func.params.len() {
+            return Err(TypeError::ArityMismatch { expected: func.params.len(), found: args.len(), span: *span }.into());
+        }
+        if self.depth >= CALL_DEPTH_LIMIT {
+            return Err(TypeError::ConstEvalFailed {
+                message: format!("`const fn` calls nested more than {} deep", CALL_DEPTH_LIMIT),
+                span: *span,
+            }.into());
+        }
+
+        let mut frame = HashMap::new();
+        for (param, arg) in func.params.iter().zip(args) {
+            let ty = param.type_annotation.as_ref().map(|ty| self.checker.ast_type_to_ty(ty));
+            let value = self.eval(arg, ty.as_ref())?;
+            let value = match &ty {
+                Some(ty) => Typed::new(self.coerce(value, ty, &arg.span)?, Some(ty.clone())),
+                None => value,
+            };
+            frame.insert(param.name.name.clone(), value);
+        }
+        let return_ty = func.return_type.as_ref().map_or(Ty::Unit, |ty| self.checker.ast_type_to_ty(ty));
+
+        let caller_scopes = std::mem::replace(&mut self.scopes, vec![frame]);
+        self.depth += 1;
+        let result = match self.eval_block_in_scope(&func.body, Some(&return_ty)) {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Return(value)) => Ok(*value),
+            Err(interrupt) => Err(interrupt),
+        };
+        self.depth -= 1;
+        self.scopes = caller_scopes;
+
+        let value = self.coerce(result?, &return_ty, span)?;
+        Ok(Typed::new(value, Some(return_ty)))
+    }
+
+    /// A struct literal, with its fields in the order the struct declares
+    /// them
+    fn eval_struct(&mut self, literal: &ast::StructLiteral, span: &Span) -> Eval<Typed> {
+        let Some(name) = literal.path.last() else {
+            return Err(not_const("this struct literal", span).into());
+        };
+        let declared = self.checker.struct_fields(&name.name);
+        let base = match &literal.base {
+            Some(base) => match self.eval(base, None)?.value {
+                ConstValue::Struct { fields, .. } => fields,
+                _ => return Err(not_const("this struct update", &base.span).into()),
+            },
+            None => Vec::new(),
+        };
+
+        let mut fields = Vec::new();
+        for field in &literal.fields {
+            let ty = declared.iter().find(|(name, _)| *name == field.name.name).and_then(|(_, ty)| ty.clone());
+            let value = self.eval_typed(&field.value, ty.as_ref())?;
+            fields.push((field.name.name.clone(), value));
+        }
+        if !declared.is_empty() {
+            let mut ordered = Vec::new();
+            for (field, _) in &declared {
+                let value = fields.iter()
+                    .chain(&base)
+                    .find(|(name, _)| name == field)
+                    .map(|(_, value)| value.clone())
+                    .ok_or_else(|| TypeError::ConstEvalFailed {
+                        message: format!("missing field `{}` in struct `{}`", field, name.name),
+                        span: *span,
+                    })?;
+                ordered.push((field.clone(), value));
+            }
+            fields = ordered;
+        }
+        Ok(Typed::new(ConstValue::Struct { name: name.name.clone(), fields }, None))
+    }
+
+    /// Give `value` the type `ty`, checking that an integer fits it
+    fn coerce(&self, value: Typed, ty: &Ty, span: &Span) -> Result<ConstValue> {
+        match (value.value, ty) {
+            (ConstValue::Int(int), ty) if ty.int_bits().is_some() => {
+                if value.ty.as_ref().is_some_and(|found| found != ty && found.int_bits().is_some()) {
+                    return Err(TypeError::TypeMismatch { expected: Box::new(ty.clone()), found: Box::new(value.ty.unwrap()), span: *span });
+                }
+                Ok(ConstValue::Int(fit(int, Some(ty), span)?))
+            }
+            (ConstValue::Float(float), ty) if ty.is_float() => Ok(ConstValue::Float(round_float(float, Some(ty)))),
+            (value @ (ConstValue::Bool(_) | ConstValue::Char(_) | ConstValue::Str(_) | ConstValue::Unit), _)
+                if !is_scalar(ty) || Typed::new(value.clone(), None).describe() == *ty =>
+            {
+                Ok(value)
+            }
+            (value @ (ConstValue::Tuple(_) | ConstValue::Array(_) | ConstValue::Struct { .. }), ty) if !is_scalar(ty) => Ok(value),
+            (value, ty) => Err(mismatch(ty, &Typed::new(value, None), span)),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Typed> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn bind(&mut self, name: &str, value: Typed) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+
+    /// The local variable an assignment assigns to
+    fn assign_target(&self, target: &Expression) -> Eval<String> {
+        match &target.kind {
+            ExpressionKind::Path(path) if path.len() == 1 && self.lookup(&path[0].name).is_some() => Ok(path[0].name.clone()),
+            _ => Err(not_const("assigning to anything but a local variable", &target.span).into()),
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: Typed) {
+        if let Some(local) = self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name)) {
+            let ty = local.ty.take().or(value.ty);
+            *local = Typed::new(value.value, ty);
+        }
+    }
+
+    /// The declared type of a struct's field, if it is a number
+    fn field_ty(&self, struct_name: &str, field: &str) -> Option<Ty> {
+        self.checker.struct_fields(struct_name)
+            .into_iter()
+            .find(|(name, _)| name == field)
+            .and_then(|(_, ty)| ty)
+            .filter(|ty| ty.int_bits().is_some() || ty.is_float())
+    }
+}
+
+/// Check that `value` is in the range of the integer type `ty`, if known
+fn fit(value: i128, ty: Option<&Ty>, span: &Span) -> Result<i128> {
+    let Some(ty) = ty else {
+        return Ok(value);
+    };
+    let (Some(min), Some(max)) = (ty.int_max_magnitude(true), ty.int_max_magnitude(false)) else {
+        return Ok(value);
+    };
+    let in_range = if value < 0 { value.unsigned_abs() <= min } else { value.unsigned_abs() <= max };
+    if in_range {
+        Ok(value)
+    } else {
+        Err(TypeError::ConstOverflow { ty: Box::new(ty.clone()), span: *span })
+    }
+}
+
+/// Truncate `value` to the width of the integer type `ty`, if known
+fn wrap(value: i128, ty: Option<&Ty>) -> i128 {
+    let Some((bits, signed)) = ty.and_then(Ty::int_bits) else {
+        return value;
+    };
+    if bits == 128 {
+        return value;
+    }
+    let truncated = value.rem_euclid(1 << bits);
+    if signed && truncated >= 1 << (bits - 1) {
+        truncated - (1 << bits)
+    } else {
+        truncated
+    }
+}
+
+/// Round a float to `f32` precision if that is its type
+fn round_float(value: f64, ty: Option<&Ty>) -> f64 {
+    if ty == Some(&Ty::F32) {
+        value as f32 as f64
+    } else {
+        value
+    }
+}
+
+/// Whether values of `ty` are integers, floats, booleans, chars or strings
+fn is_scalar(ty: &Ty) -> bool {
+    ty.int_bits().is_some() || ty.is_float() || matches!(ty, Ty::Bool | Ty::Char | Ty::String | Ty::Unit)
+}
+
+fn mismatch(expected: &Ty, found: &Typed, span: &Span) -> TypeError {
+    TypeError::TypeMismatch { expected: Box::new(expected.clone()), found: Box::new(found.describe()), span: *span }
+}
+
+fn not_const(what: &str, span: &Span) -> TypeError {
+    TypeError::NotConst { what: what.to_string(), span: *span }
+}
+
+fn join_path(path: &[Identifier]) -> String {
+    path.iter().map(|segment| segment.name.as_str()).collect::<Vec<_>>().join("::")
+}
@@ -3,7 +3,8 @@
 
 //! Integration with zulon-diagnostic
 
-use crate::TypeError;
+use crate::error::list_patterns;
+use crate::{TypeError, Warning};
 use zulon_diagnostic::{Diagnostic, Span, Suggestion, Loc};
 use zulon_parser::Span as ParserSpan;
 use std::path::PathBuf;
@@ -16,20 +17,20 @@ impl TypeError {
                 let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
 
                 let mut diagnostic = Diagnostic::error()
-                    .message(format!("type mismatch"))
+                    .message("type mismatch".to_string())
                     .span(diagnostic_span.clone())
                     .code("E0308")
-                    .label(diagnostic_span.clone(), &format!("expected {}", expected))
-                    .label(diagnostic_span.clone(), &format!("found {}", found))
-                    .note(&format!("expected type: {}", expected))
-                    .note(&format!("found type: {}", found));
+                    .label(diagnostic_span.clone(), format!("expected {}", expected))
+                    .label(diagnostic_span.clone(), format!("found {}", found))
+                    .note(format!("expected type: {}", expected))
+                    .note(format!("found type: {}", found));
 
                 // Add suggestions for common type mismatches
                 if expected.is_integer() && found.is_integer() {
                     diagnostic = diagnostic.suggestion(Suggestion::new(
-                        &format!("consider explicitly converting {} to {}", found, expected),
+                        format!("consider explicitly converting {} to {}", found, expected),
                         diagnostic_span.clone(),
-                        &format!("{} as {}", found, expected),
+                        format!("{} as {}", found, expected),
                     ));
                 }
 
@@ -43,8 +44,8 @@ impl TypeError {
                     .message(format!("cannot find type `{}` in this scope", name))
                     .span(diagnostic_span.clone())
                     .code("E0412")
-                    .label(diagnostic_span.clone(), &format!("not found in this scope"))
-                    .note(&format!("did you mean `{}`?", suggest_similar(name)))
+                    .label(diagnostic_span.clone(), "not found in this scope".to_string())
+                    .note(format!("did you mean `{}`?", suggest_similar(name)))
                     .build()
             }
 
@@ -75,10 +76,10 @@ impl TypeError {
                 let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
 
                 Diagnostic::error()
-                    .message(format!("cannot call non-function type"))
+                    .message("cannot call non-function type".to_string())
                     .span(diagnostic_span.clone())
                     .code("E0618")
-                    .label(diagnostic_span.clone(), &format!("{} is not a function", ty))
+                    .label(diagnostic_span.clone(), format!("{} is not a function", ty))
                     .build()
             }
 
@@ -91,7 +92,7 @@ impl TypeError {
                     .span(diagnostic_span.clone())
                     .code("E0061")
                     .label(diagnostic_span.clone(),
-                        &format!("expected {} argument{}", expected,
+                        format!("expected {} argument{}", expected,
                             if *expected == 1 { "" } else { "s" }))
                     .build()
             }
@@ -104,7 +105,7 @@ impl TypeError {
                     .span(diagnostic_span.clone())
                     .code("E0609")
                     .label(diagnostic_span.clone(), "unknown field")
-                    .note(&format!("available fields: ...")) // TODO: list actual fields
+                    .note("available fields: ...".to_string()) // TODO: list actual fields
                     .build()
             }
 
@@ -176,7 +177,7 @@ impl TypeError {
                     .message(format!("the trait `{}` is not implemented for `{}`", trait_name, ty))
                     .span(diagnostic_span.clone())
                     .code("E0277")
-                    .label(diagnostic_span.clone(), &format!("{} doesn't implement {}", ty, trait_name))
+                    .label(diagnostic_span.clone(), format!("{} doesn't implement {}", ty, trait_name))
                     .build()
             }
 
@@ -203,6 +204,32 @@ impl TypeError {
                     .build()
             }
 
+            TypeError::LiteralOutOfRange { ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+                let mut builder = Diagnostic::error()
+                    .message(format!("literal out of range for `{}`", ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0200")
+                    .label(diagnostic_span.clone(), format!("this literal does not fit in `{}`", ty));
+                if let (Some(min), Some(max)) = (ty.int_max_magnitude(true), ty.int_max_magnitude(false)) {
+                    let min = if min == 0 { "0".to_string() } else { format!("-{}", min) };
+                    builder = builder.note(format!("the type `{}` ranges from `{}` to `{}`", ty, min, max));
+                }
+                builder.build()
+            }
+
+            TypeError::LetElseNotDiverging { span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message("`else` block of `let...else` does not diverge")
+                    .span(diagnostic_span.clone())
+                    .code("E0308")
+                    .label(diagnostic_span.clone(), "must end in `return`, `break`, `continue` or `throw`")
+                    .note("the `else` block runs when the pattern does not match, so it cannot fall through")
+                    .build()
+            }
+
             TypeError::CannotConvert { from, to, span } => {
                 let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
 
@@ -210,11 +237,11 @@ impl TypeError {
                     .message(format!("cannot convert {} to {}", from, to))
                     .span(diagnostic_span.clone())
                     .code("E0604")
-                    .label(diagnostic_span.clone(), &format!("cannot convert"))
+                    .label(diagnostic_span.clone(), "cannot convert".to_string())
                     .suggestion(Suggestion::new(
-                        &format!("consider using `{} as {}`", from, to),
+                        format!("consider using `{} as {}`", from, to),
                         diagnostic_span.clone(),
-                        &format!("{} as {}", from, to),
+                        format!("{} as {}", from, to),
                     ))
                     .build()
             }
@@ -224,18 +251,305 @@ impl TypeError {
                     .message(format!("cannot find effect {} in this scope", name))
                     .span(diagnostic_span.clone())
                     .code("E0605")
-                    .label(diagnostic_span.clone(), &format!("undefined effect"))
+                    .label(diagnostic_span.clone(), "undefined effect".to_string())
                     .suggestion(Suggestion::new(
-                        &format!("effect {} {{ ... }} must be declared before use", name),
+                        format!("effect {} {{ ... }} must be declared before use", name),
                         diagnostic_span.clone(),
-                        &format!("effect {} {{ ... }}", name),
+                        format!("effect {} {{ ... }}", name),
                     ))
                     .build()
             }
+
+            TypeError::UndefinedTrait { name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("cannot find trait `{}` in this scope", name))
+                    .span(diagnostic_span.clone())
+                    .code("E0405")
+                    .label(diagnostic_span.clone(), "not found in this scope")
+                    .build()
+            }
+
+            TypeError::NoMethod { method, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("no method named `{}` found for type `{}`", method, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0599")
+                    .label(diagnostic_span.clone(), "method not found")
+                    .note("methods come from impl blocks of the type, or of traits it implements")
+                    .build()
+            }
+
+            TypeError::AmbiguousMethod { method, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("multiple applicable methods named `{}` found for type `{}`", method, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0034")
+                    .label(diagnostic_span.clone(), "multiple methods found")
+                    .note("use a fully qualified path such as `Trait::method(value)`")
+                    .build()
+            }
+
+            TypeError::DuplicateMethod { method, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("duplicate definitions of method `{}` for type `{}`", method, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0592")
+                    .label(diagnostic_span.clone(), "duplicate definition")
+                    .build()
+            }
+
+            TypeError::MethodNotInTrait { method, trait_name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("method `{}` is not a member of trait `{}`", method, trait_name))
+                    .span(diagnostic_span.clone())
+                    .code("E0407")
+                    .label(diagnostic_span.clone(), format!("not a member of trait `{}`", trait_name))
+                    .build()
+            }
+
+            TypeError::MissingTraitMethod { method, trait_name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("not all trait items implemented, missing: `{}`", method))
+                    .span(diagnostic_span.clone())
+                    .code("E0046")
+                    .label(diagnostic_span.clone(), format!("missing `{}` in implementation", method))
+                    .note(format!("`{}` has no default body in trait `{}`", method, trait_name))
+                    .build()
+            }
+
+            TypeError::AssocTypeNotInTrait { name, trait_name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("type `{}` is not a member of trait `{}`", name, trait_name))
+                    .span(diagnostic_span.clone())
+                    .code("E0437")
+                    .label(diagnostic_span.clone(), format!("not a member of trait `{}`", trait_name))
+                    .build()
+            }
+
+            TypeError::MissingAssocType { name, trait_name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("not all trait items implemented, missing: `{}`", name))
+                    .span(diagnostic_span.clone())
+                    .code("E0046")
+                    .label(diagnostic_span.clone(), format!("missing `{}` in implementation", name))
+                    .note(format!("trait `{}` declares the associated type `{}`", trait_name, name))
+                    .build()
+            }
+
+            TypeError::UndefinedAssocType { name, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("associated type `{}` not found for `{}`", name, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0220")
+                    .label(diagnostic_span.clone(), format!("associated type `{}` not found", name))
+                    .note("the associated type must be declared by a trait bounding the type, or implemented for it")
+                    .build()
+            }
+
+            TypeError::AmbiguousAssocType { name, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("ambiguous associated type `{}` of `{}`", name, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0221")
+                    .label(diagnostic_span.clone(), format!("ambiguous associated type `{}`", name))
+                    .note(format!("more than one trait of `{}` declares an associated type `{}`", ty, name))
+                    .build()
+            }
+
+            TypeError::ConflictingImpls { trait_name, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("conflicting implementations of trait `{}` for type `{}`", trait_name, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0119")
+                    .label(diagnostic_span.clone(), format!("conflicting implementation for `{}`", ty))
+                    .build()
+            }
+
+            TypeError::OrphanImpl { ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("cannot define inherent `impl` for type `{}`", ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0390")
+                    .label(diagnostic_span.clone(), "impl for a type defined outside of this crate")
+                    .note("define and implement a trait instead")
+                    .build()
+            }
+
+            TypeError::NotObjectSafe { trait_name, reason, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("the trait `{}` cannot be made into an object", trait_name))
+                    .span(diagnostic_span.clone())
+                    .code("E0038")
+                    .label(diagnostic_span.clone(), format!("`{}` cannot be made into an object", trait_name))
+                    .note(reason.clone())
+                    .build()
+            }
+
+            TypeError::UnknownVariant { variant, ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(format!("no variant named `{}` found for `{}`", variant, ty))
+                    .span(diagnostic_span.clone())
+                    .code("E0599")
+                    .label(diagnostic_span.clone(), "variant not found")
+                    .build()
+            }
+
+            TypeError::InvalidRangePattern { inclusive, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(self.to_string())
+                    .span(diagnostic_span.clone())
+                    .code(if *inclusive { "E0030" } else { "E0579" })
+                    .label(diagnostic_span.clone(), "lower bound larger than upper bound")
+                    .build()
+            }
+
+            TypeError::UnsupportedArrayPattern { span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(self.to_string())
+                    .span(diagnostic_span.clone())
+                    .label(diagnostic_span.clone(), "destructures an array or a slice")
+                    .note("the pattern's types and coverage are checked, but it cannot be compiled yet")
+                    .build()
+            }
+
+            TypeError::NonExhaustivePatterns { missing, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+                let patterns = list_patterns(missing);
+
+                Diagnostic::error()
+                    .message(format!("non-exhaustive patterns: {} not covered", patterns))
+                    .span(diagnostic_span.clone())
+                    .code("E0004")
+                    .label(diagnostic_span.clone(), format!("pattern{} {} not covered",
+                        if missing.len() == 1 { "" } else { "s" }, patterns))
+                    .note("ensure that all possible cases are being handled by adding a match arm \
+                           with a wildcard pattern, a match arm with multiple or-patterns, or \
+                           multiple match arms")
+                    .build()
+            }
+
+            TypeError::ConstOverflow { ty, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+                let mut builder = Diagnostic::error()
+                    .message("evaluation of constant value failed")
+                    .span(diagnostic_span.clone())
+                    .code("E0080")
+                    .label(diagnostic_span.clone(), format!("attempt to compute a value out of range for `{}`", ty));
+                if let (Some(min), Some(max)) = (ty.int_max_magnitude(true), ty.int_max_magnitude(false)) {
+                    let min = if min == 0 { "0".to_string() } else { format!("-{}", min) };
+                    builder = builder.note(format!("the type `{}` ranges from `{}` to `{}`", ty, min, max));
+                }
+                builder.build()
+            }
+
+            TypeError::ConstDivisionByZero { span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message("evaluation of constant value failed")
+                    .span(diagnostic_span.clone())
+                    .code("E0080")
+                    .label(diagnostic_span.clone(), "attempt to divide by zero")
+                    .build()
+            }
+
+            TypeError::ConstEvalFailed { message, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message("evaluation of constant value failed")
+                    .span(diagnostic_span.clone())
+                    .code("E0080")
+                    .label(diagnostic_span.clone(), message.as_str())
+                    .build()
+            }
+
+            TypeError::NotConst { span, .. } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(self.to_string())
+                    .span(diagnostic_span.clone())
+                    .code("E0015")
+                    .label(diagnostic_span.clone(), "not a constant expression")
+                    .note("constants, statics, array lengths and enum discriminants are evaluated at \
+                           compile time, and may only call `const fn`s")
+                    .build()
+            }
+
+            TypeError::ConstCycle { name, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(self.to_string())
+                    .span(diagnostic_span.clone())
+                    .code("E0391")
+                    .label(diagnostic_span.clone(), format!("`{}` depends on its own value", name))
+                    .build()
+            }
+
+            TypeError::DuplicateDiscriminant { value, span } => {
+                let diagnostic_span = parser_span_to_diagnostic_span(span, source_code);
+
+                Diagnostic::error()
+                    .message(self.to_string())
+                    .span(diagnostic_span.clone())
+                    .code("E0081")
+                    .label(diagnostic_span.clone(), format!("`{}` assigned here again", value))
+                    .build()
+            }
         }
     }
 }
 
+impl Warning {
+    /// Convert to a warning Diagnostic
+    pub fn to_diagnostic(&self, source_code: &str) -> Diagnostic {
+        let span = parser_span_to_diagnostic_span(&self.span(), source_code);
+        let diagnostic = Diagnostic::warning().message(self.to_string()).span(span.clone());
+        let (diagnostic, lint) = match self {
+            Warning::UnreachablePattern { .. } => {
+                (diagnostic.label(span, "no value can reach this arm"), "unreachable_patterns")
+            }
+        };
+        diagnostic
+            .note(format!("`#[warn({})]` on by default", lint))
+            .build()
+    }
+}
+
 /// Convert Parser Span to Diagnostic Span
 fn parser_span_to_diagnostic_span(span: &ParserSpan, source_code: &str) -> Span {
     let file = Some(PathBuf::from("input.zl"));
@@ -281,7 +595,7 @@ fn estimate_byte_offset(source_code: &str, line: usize, column: usize) -> usize
 /// Suggest a similar name (placeholder for now)
 fn suggest_similar(_name: &str) -> String {
     // TODO: Implement actual fuzzy matching
-    format!("a similar type")
+    "a similar type".to_string()
 }
 
 #[cfg(test)]
@@ -298,8 +612,8 @@ mod tests {
         );
 
         let error = TypeError::TypeMismatch {
-            expected: Ty::I32,
-            found: Ty::String,
+            expected: Box::new(Ty::I32),
+            found: Box::new(Ty::String),
             span,
         };
 
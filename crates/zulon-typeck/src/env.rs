@@ -58,6 +58,7 @@ impl Env {
 
     /// Create a new environment with a parent
     pub fn with_parent(parent: Env) -> Self {
+        let next_ty_var = parent.next_ty_var;
         Env {
             bindings: HashMap::new(),
             type_defs: HashMap::new(),
@@ -66,7 +67,7 @@ impl Env {
             effects: HashMap::new(),
             current_effects: EffectSet::new(),
             parent: Some(Box::new(parent)),
-            next_ty_var: 0,
+            next_ty_var,
         }
     }
 
@@ -214,6 +215,25 @@ impl Env {
         Ty::TyVar(id)
     }
 
+    /// Create a fresh variable for the type of an integer literal
+    pub fn fresh_int_var(&mut self) -> Ty {
+        let id = self.next_ty_var;
+        self.next_ty_var += 1;
+        Ty::IntVar(id)
+    }
+
+    /// Create a fresh variable for the type of a float literal
+    pub fn fresh_float_var(&mut self) -> Ty {
+        let id = self.next_ty_var;
+        self.next_ty_var += 1;
+        Ty::FloatVar(id)
+    }
+
+    /// Skip type variable IDs already handed out by a child scope
+    pub fn advance_ty_vars_to(&mut self, next: TyVarId) {
+        self.next_ty_var = self.next_ty_var.max(next);
+    }
+
     /// Get the next type variable ID without consuming it
     pub fn peek_next_ty_var(&self) -> TyVarId {
         self.next_ty_var
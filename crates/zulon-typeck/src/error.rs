@@ -10,12 +10,14 @@ use zulon_parser::ast::Span;
 use thiserror::Error;
 
 /// Type checking error
+///
+/// Types are boxed so the error stays small on the `Err` path.
 #[derive(Debug, Clone, Error)]
 pub enum TypeError {
     #[error("type mismatch: expected {expected}, found {found}")]
     TypeMismatch {
-        expected: Ty,
-        found: Ty,
+        expected: Box<Ty>,
+        found: Box<Ty>,
         span: Span,
     },
 
@@ -45,7 +47,7 @@ pub enum TypeError {
 
     #[error("cannot call non-function type")]
     NotCallable {
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
@@ -59,13 +61,13 @@ pub enum TypeError {
     #[error("field {field} does not exist on type {ty}")]
     UnknownField {
         field: String,
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
     #[error("type {ty} is not indexable")]
     NotIndexable {
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
@@ -76,7 +78,7 @@ pub enum TypeError {
 
     #[error("cannot borrow {ty} as mutable")]
     CannotBorrowMut {
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
@@ -95,13 +97,13 @@ pub enum TypeError {
     #[error("trait bound not satisfied")]
     TraitBoundNotSatisfied {
         trait_name: String,
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
     #[error("recursive type: {ty} contains itself")]
     RecursiveType {
-        ty: Ty,
+        ty: Box<Ty>,
         span: Span,
     },
 
@@ -110,13 +112,205 @@ pub enum TypeError {
         span: Span,
     },
 
+    #[error("literal out of range for `{ty}`")]
+    LiteralOutOfRange {
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("`else` block of `let...else` does not diverge")]
+    LetElseNotDiverging {
+        span: Span,
+    },
+
     #[error("cannot convert {from} to {to}")]
     CannotConvert {
-        from: Ty,
-        to: Ty,
+        from: Box<Ty>,
+        to: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("cannot find trait {name} in this scope")]
+    UndefinedTrait {
+        name: String,
+        span: Span,
+    },
+
+    #[error("no method named {method} found for type {ty}")]
+    NoMethod {
+        method: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("multiple applicable methods named {method} found for type {ty}")]
+    AmbiguousMethod {
+        method: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("duplicate definitions of method {method} for type {ty}")]
+    DuplicateMethod {
+        method: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("method {method} is not a member of trait {trait_name}")]
+    MethodNotInTrait {
+        method: String,
+        trait_name: String,
+        span: Span,
+    },
+
+    #[error("not all trait items implemented, missing: {method} of trait {trait_name}")]
+    MissingTraitMethod {
+        method: String,
+        trait_name: String,
+        span: Span,
+    },
+
+    #[error("type {name} is not a member of trait {trait_name}")]
+    AssocTypeNotInTrait {
+        name: String,
+        trait_name: String,
+        span: Span,
+    },
+
+    #[error("not all trait items implemented, missing: type {name} of trait {trait_name}")]
+    MissingAssocType {
+        name: String,
+        trait_name: String,
+        span: Span,
+    },
+
+    #[error("associated type {name} not found for {ty}")]
+    UndefinedAssocType {
+        name: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("ambiguous associated type {name} of {ty}")]
+    AmbiguousAssocType {
+        name: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("conflicting implementations of trait {trait_name} for type {ty}")]
+    ConflictingImpls {
+        trait_name: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("cannot define inherent impl for type {ty} outside of the crate where it is defined")]
+    OrphanImpl {
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("the trait {trait_name} cannot be made into an object: {reason}")]
+    NotObjectSafe {
+        trait_name: String,
+        reason: String,
+        span: Span,
+    },
+
+    #[error("no variant named {variant} found for {ty}")]
+    UnknownVariant {
+        variant: String,
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("lower range bound must be {} upper", if *.inclusive { "less than or equal to" } else { "less than" })]
+    InvalidRangePattern {
+        inclusive: bool,
+        span: Span,
+    },
+
+    #[error("array and slice patterns are not supported yet")]
+    UnsupportedArrayPattern {
+        span: Span,
+    },
+
+    #[error("non-exhaustive patterns: {} not covered", list_patterns(.missing))]
+    NonExhaustivePatterns {
+        /// Examples of the values no arm matches, as patterns
+        missing: Vec<String>,
+        span: Span,
+    },
+
+    #[error("evaluation of constant value failed: attempt to compute a value out of range for `{ty}`")]
+    ConstOverflow {
+        ty: Box<Ty>,
+        span: Span,
+    },
+
+    #[error("evaluation of constant value failed: attempt to divide by zero")]
+    ConstDivisionByZero {
+        span: Span,
+    },
+
+    #[error("evaluation of constant value failed: {message}")]
+    ConstEvalFailed {
+        message: String,
+        span: Span,
+    },
+
+    #[error("{what} is not allowed in constants")]
+    NotConst {
+        /// What the constant expression does, like "calling non-const fn `f`"
+        what: String,
+        span: Span,
+    },
+
+    #[error("cycle detected when evaluating constant {name}")]
+    ConstCycle {
+        name: String,
+        span: Span,
+    },
+
+    #[error("discriminant value `{value}` assigned more than once")]
+    DuplicateDiscriminant {
+        value: i128,
+        span: Span,
+    },
+}
+
+/// A warning found during type checking
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum Warning {
+    /// A match arm pattern that only matches values earlier arms match
+    #[error("unreachable pattern")]
+    UnreachablePattern {
+        /// The arm
         span: Span,
     },
 }
 
+impl Warning {
+    /// Where the warning points
+    pub fn span(&self) -> Span {
+        match self {
+            Warning::UnreachablePattern { span } => *span,
+        }
+    }
+}
+
+/// `` `A` ``, `` `A` and `B` `` or `` `A`, `B`, `C` and 2 more ``
+pub(crate) fn list_patterns(patterns: &[String]) -> String {
+    let quoted: Vec<String> = patterns.iter().map(|pattern| format!("`{}`", pattern)).collect();
+    match quoted.as_slice() {
+        [] => String::new(),
+        [only] => only.clone(),
+        [init @ .., last] if quoted.len() <= 3 => format!("{} and {}", init.join(", "), last),
+        _ => format!("{} and {} more", quoted[..3].join(", "), quoted.len() - 3),
+    }
+}
+
 /// Result type for type checking
 pub type Result<T> = std::result::Result<T, TypeError>;
@@ -0,0 +1,744 @@
+// Copyright 2026 ZULON Language Team
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Match exhaustiveness and arm reachability
+//!
+//! A pattern is *useful* after a list of patterns when some value matches
+//! it and none of them. A match is exhaustive when a wildcard is not useful
+//! after its unguarded arms, and an arm is unreachable when its pattern is
+//! not useful after the unguarded arms before it.
+//!
+//! Usefulness is computed over a matrix with a row of patterns per arm, by
+//! specializing the matrix to each constructor of the type of its first
+//! column in turn (Maranget, "Warnings for pattern matching", 2007). Integer
+//! and `char` ranges in a column are split at each other's bounds so that
+//! every piece is either inside or outside each range, and slice lengths
+//! are grouped the same way. The values a wildcard is still useful for are
+//! rebuilt into example patterns for the error message.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::checker::{EnumDef, StructDef};
+use crate::ty::{subst_params, Ty};
+use zulon_parser::ast;
+
+/// What a pattern checks a value against at its outermost level
+#[derive(Debug, Clone, PartialEq)]
+enum Constructor {
+    /// The only constructor of a tuple, struct or reference
+    Single,
+    /// An enum variant, by index; `None` is 0 and `Some` is 1
+    Variant(usize),
+    Bool(bool),
+    /// The integers or chars in a range
+    IntRange(IntRange),
+    /// Arrays or slices of some lengths
+    Slice(SliceKind),
+    /// A string literal
+    Str(String),
+    /// A float literal, by its bits
+    Float(u64),
+    /// Values that are not compared with others, like float ranges: an
+    /// opaque pattern covers no other constructor and none covers it
+    Opaque,
+}
+
+impl Constructor {
+    /// Whether every value `other` matches is matched by `self`; the
+    /// constructors of a column are split first, so a range or slice
+    /// length either covers another or does not overlap it
+    fn covers(&self, other: &Constructor) -> bool {
+        match (self, other) {
+            (Constructor::IntRange(a), Constructor::IntRange(b)) => a.lo <= b.lo && b.hi <= a.hi,
+            (Constructor::Slice(a), Constructor::Slice(b)) => a.covers(*b),
+            (Constructor::Opaque, _) | (_, Constructor::Opaque) => false,
+            _ => self == other,
+        }
+    }
+}
+
+/// An inclusive range of integers or chars, with signed integers biased by
+/// their type's minimum so that ranges of every type order as `u128`s
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IntRange {
+    lo: u128,
+    hi: u128,
+}
+
+impl IntRange {
+    /// Split into pieces that are each inside or outside every range of
+    /// `column`
+    fn split(self, column: &[IntRange]) -> Vec<IntRange> {
+        let mut borders = vec![self.lo];
+        for range in column {
+            if self.lo < range.lo && range.lo <= self.hi {
+                borders.push(range.lo);
+            }
+            if let Some(after) = range.hi.checked_add(1) {
+                if self.lo < after && after <= self.hi {
+                    borders.push(after);
+                }
+            }
+        }
+        borders.sort_unstable();
+        borders.dedup();
+
+        let mut pieces: Vec<IntRange> = borders.windows(2)
+            .map(|pair| IntRange { lo: pair[0], hi: pair[1] - 1 })
+            .collect();
+        pieces.push(IntRange { lo: borders[borders.len() - 1], hi: self.hi });
+        pieces
+    }
+}
+
+/// The lengths an array or slice pattern matches
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SliceKind {
+    /// Exactly this many elements
+    Fixed(usize),
+    /// At least `prefix + suffix` elements, of which the patterns match the
+    /// first `prefix` and the last `suffix`
+    Var(usize, usize),
+}
+
+impl SliceKind {
+    /// The number of element patterns
+    fn arity(self) -> usize {
+        match self {
+            SliceKind::Fixed(len) => len,
+            SliceKind::Var(prefix, suffix) => prefix + suffix,
+        }
+    }
+
+    /// Whether every length `other` matches is matched by `self`, with
+    /// `other`'s elements lined up against `self`'s patterns
+    fn covers(self, other: SliceKind) -> bool {
+        match (self, other) {
+            (SliceKind::Fixed(a), SliceKind::Fixed(b)) => a == b,
+            (SliceKind::Var(prefix, suffix), SliceKind::Fixed(len)) => prefix + suffix <= len,
+            (SliceKind::Var(prefix, suffix), SliceKind::Var(other_prefix, other_suffix)) => {
+                prefix <= other_prefix && suffix <= other_suffix
+            }
+            (SliceKind::Fixed(_), SliceKind::Var(..)) => false,
+        }
+    }
+}
+
+/// A pattern reduced to constructors and their fields
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Constructor, Vec<Pat>),
+    Or(Vec<Pat>),
+}
+
+/// What checking the arms of a match found
+#[derive(Debug, Default)]
+pub(crate) struct MatchReport {
+    /// Examples of values no arm matches, as patterns
+    pub(crate) missing: Vec<String>,
+    /// Indices of the arms with a pattern that no value reaches
+    pub(crate) unreachable: Vec<usize>,
+}
+
+/// Computes exhaustiveness over the struct and enum definitions of a crate
+pub(crate) struct MatchChecker<'a> {
+    structs: &'a HashMap<String, StructDef>,
+    enums: &'a HashMap<String, EnumDef>,
+    /// Variants imported by name, which examples name without their enum
+    variant_imports: &'a HashSet<(String, String)>,
+}
+
+impl<'a> MatchChecker<'a> {
+    pub(crate) fn new(
+        structs: &'a HashMap<String, StructDef>,
+        enums: &'a HashMap<String, EnumDef>,
+        variant_imports: &'a HashSet<(String, String)>,
+    ) -> Self {
+        MatchChecker { structs, enums, variant_imports }
+    }
+
+    /// Check the arms of a match on a value of type `ty`, which must have
+    /// the inference substitution applied
+    pub(crate) fn check(&self, arms: &[ast::MatchArm], ty: &Ty) -> MatchReport {
+        let tys = std::slice::from_ref(ty);
+        let mut report = MatchReport::default();
+        let mut rows: Vec<Vec<Pat>> = Vec::new();
+
+        for (i, arm) in arms.iter().enumerate() {
+            for pattern in &arm.patterns {
+                let row = vec![self.lower(pattern, ty)];
+                if self.useful(&rows, &row, tys).is_empty() && !report.unreachable.contains(&i) {
+                    report.unreachable.push(i);
+                }
+                // A guard may fail, so a guarded arm matches nothing for sure
+                if arm.guard.is_none() {
+                    rows.push(row);
+                }
+            }
+        }
+
+        for witness in self.useful(&rows, &[Pat::Wild], tys) {
+            let missing = self.display(&witness[0], ty);
+            if !report.missing.contains(&missing) {
+                report.missing.push(missing);
+            }
+        }
+        report
+    }
+
+    /// Reduce `pattern`, matching values of type `ty`, to constructors
+    fn lower(&self, pattern: &ast::Pattern, ty: &Ty) -> Pat {
+        match pattern {
+            ast::Pattern::Wildcard => return Pat::Wild,
+            ast::Pattern::Identifier(ident) if self.unit_variant(&ident.name, ty).is_none() => return Pat::Wild,
+            ast::Pattern::Or(patterns) => {
+                return Pat::Or(patterns.iter().map(|pattern| self.lower(pattern, ty)).collect());
+            }
+            ast::Pattern::Literal(ast::Literal::String(s)) => {
+                return Pat::Ctor(Constructor::Str(s.clone()), Vec::new());
+            }
+            _ => {}
+        }
+
+        // Other patterns match through references
+        if let Ty::Ref { inner, .. } = ty {
+            return Pat::Ctor(Constructor::Single, vec![self.lower(pattern, inner)]);
+        }
+
+        let opaque = Pat::Ctor(Constructor::Opaque, Vec::new());
+        match pattern {
+            ast::Pattern::Identifier(ident) => match self.unit_variant(&ident.name, ty) {
+                Some(index) => Pat::Ctor(Constructor::Variant(index), Vec::new()),
+                None => Pat::Wild,
+            },
+            ast::Pattern::Literal(literal) => match self.literal_ctor(literal, ty) {
+                Some(ctor) => Pat::Ctor(ctor, Vec::new()),
+                None => opaque,
+            },
+            ast::Pattern::Range(start, kind, end) => {
+                let bound = |pattern: &ast::Pattern| match pattern {
+                    ast::Pattern::Literal(literal) => match self.literal_ctor(literal, ty) {
+                        Some(Constructor::IntRange(range)) => Some(range.lo),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let hi = match (bound(end), kind) {
+                    (Some(hi), ast::RangeKind::Inclusive) => Some(hi),
+                    (Some(hi), _) => hi.checked_sub(1),
+                    (None, _) => None,
+                };
+                match (bound(start), hi) {
+                    (Some(lo), Some(hi)) if lo <= hi => Pat::Ctor(Constructor::IntRange(IntRange { lo, hi }), Vec::new()),
+                    _ => opaque,
+                }
+            }
+            ast::Pattern::Tuple(patterns) => match ty {
+                Ty::Tuple(tys) if tys.len() == patterns.len() => Pat::Ctor(
+                    Constructor::Single,
+                    patterns.iter().zip(tys).map(|(pattern, ty)| self.lower(pattern, ty)).collect(),
+                ),
+                _ => opaque,
+            },
+            ast::Pattern::Struct(path, fields) => {
+                let field = |name: &str| fields.iter().find_map(|field| match field {
+                    ast::StructPatternField::Field(ident, pattern) if ident.name == name => Some(&**pattern),
+                    _ => None,
+                });
+                let field_names = match ty {
+                    Ty::Struct { name, .. } => match self.structs.get(&name.name) {
+                        Some(def) => def.fields.iter().map(|(name, _)| name.clone()).collect(),
+                        None => return opaque,
+                    },
+                    _ => match self.variant_index(&path[path.len() - 1].name, ty) {
+                        Some(index) => self.variant_field_names(index, ty),
+                        None => return opaque,
+                    },
+                };
+                let ctor = match ty {
+                    Ty::Struct { .. } => Constructor::Single,
+                    _ => Constructor::Variant(self.variant_index(&path[path.len() - 1].name, ty).unwrap_or_default()),
+                };
+                let field_tys = self.field_tys(&ctor, ty);
+                let fields = field_names.iter().zip(&field_tys)
+                    .map(|(name, ty)| field(name).map_or(Pat::Wild, |pattern| self.lower(pattern, ty)))
+                    .collect();
+                Pat::Ctor(ctor, fields)
+            }
+            ast::Pattern::TupleVariant(path, patterns) => match self.variant_index(&path[path.len() - 1].name, ty) {
+                Some(index) => {
+                    let ctor = Constructor::Variant(index);
+                    let field_tys = self.field_tys(&ctor, ty);
+                    if field_tys.len() != patterns.len() {
+                        return opaque;
+                    }
+                    let fields = patterns.iter().zip(&field_tys).map(|(pattern, ty)| self.lower(pattern, ty)).collect();
+                    Pat::Ctor(ctor, fields)
+                }
+                None => opaque,
+            },
+            ast::Pattern::Array(patterns) => self.lower_slice(SliceKind::Fixed(patterns.len()), patterns.iter(), ty),
+            ast::Pattern::Slice(before, _, after) => {
+                self.lower_slice(SliceKind::Var(before.len(), after.len()), before.iter().chain(after), ty)
+            }
+            ast::Pattern::Wildcard | ast::Pattern::Or(_) => unreachable!("handled above"),
+        }
+    }
+
+    /// Reduce an array or slice pattern with the given element patterns
+    fn lower_slice<'p>(&self, kind: SliceKind, patterns: impl Iterator<Item = &'p ast::Pattern>, ty: &Ty) -> Pat {
+        match ty {
+            Ty::Array { inner, .. } | Ty::Slice(inner) => {
+                Pat::Ctor(Constructor::Slice(kind), patterns.map(|pattern| self.lower(pattern, inner)).collect())
+            }
+            _ => Pat::Ctor(Constructor::Opaque, Vec::new()),
+        }
+    }
+
+    /// The constructor of a literal pattern, if it can be compared with
+    /// other patterns of type `ty`
+    fn literal_ctor(&self, literal: &ast::Literal, ty: &Ty) -> Option<Constructor> {
+        let int = |value: u128| {
+            let value = match int_bits(ty)? {
+                (bits, true) => value.checked_add(1 << (bits - 1))?,
+                _ => value,
+            };
+            Some(Constructor::IntRange(IntRange { lo: value, hi: value }))
+        };
+        match literal {
+            ast::Literal::Bool(b) => Some(Constructor::Bool(*b)),
+            ast::Literal::Int(value, _) => int(*value),
+            ast::Literal::Byte(value) => int(*value as u128),
+            ast::Literal::Char(c) if *ty == Ty::Char => {
+                let value = *c as u128;
+                Some(Constructor::IntRange(IntRange { lo: value, hi: value }))
+            }
+            ast::Literal::Float(value, _) => Some(Constructor::Float(value.to_bits())),
+            _ => None,
+        }
+    }
+
+    /// The index of the unit variant `name` of `ty`, if it names one
+    fn unit_variant(&self, name: &str, ty: &Ty) -> Option<usize> {
+        match ty {
+            Ty::Ref { inner, .. } => self.unit_variant(name, inner),
+            _ => self.variant_index(name, ty)
+                .filter(|&index| self.field_tys(&Constructor::Variant(index), ty).is_empty()),
+        }
+    }
+
+    /// The index of the variant `name` of the enum or optional type `ty`
+    fn variant_index(&self, name: &str, ty: &Ty) -> Option<usize> {
+        match ty {
+            Ty::Optional(_) => ["None", "Some"].iter().position(|variant| *variant == name),
+            Ty::Enum { name: enum_name, .. } => {
+                self.enums.get(&enum_name.name)?.variants.iter().position(|(variant, _)| variant == name)
+            }
+            _ => None,
+        }
+    }
+
+    /// The field names of the variant at `index` of `ty`, positional ones
+    /// numbered
+    fn variant_field_names(&self, index: usize, ty: &Ty) -> Vec<String> {
+        let arity = self.field_tys(&Constructor::Variant(index), ty).len();
+        let named = match ty {
+            Ty::Enum { name, .. } => self.enums.get(&name.name).and_then(|def| {
+                def.named_fields.get(&def.variants[index].0).cloned()
+            }),
+            _ => None,
+        };
+        named.unwrap_or_else(|| (0..arity).map(|i| i.to_string()).collect())
+    }
+
+    /// The field types of `ctor` as a constructor of `ty`
+    fn field_tys(&self, ctor: &Constructor, ty: &Ty) -> Vec<Ty> {
+        match (ctor, ty) {
+            (Constructor::Single, Ty::Ref { inner, .. }) => vec![(**inner).clone()],
+            (Constructor::Single, Ty::Tuple(tys)) => tys.clone(),
+            (Constructor::Single, Ty::Struct { name, generics }) => match self.structs.get(&name.name) {
+                Some(def) => {
+                    let args = def.generics.iter().cloned().zip(generics.iter().cloned()).collect();
+                    def.fields.iter().map(|(_, ty)| subst_params(&args, ty)).collect()
+                }
+                None => Vec::new(),
+            },
+            (Constructor::Variant(1), Ty::Optional(inner)) => vec![(**inner).clone()],
+            (Constructor::Variant(index), Ty::Enum { name, generics }) => match self.enums.get(&name.name) {
+                Some(def) => {
+                    let args: HashMap<String, Ty> = def.generics.iter().cloned().zip(generics.iter().cloned()).collect();
+                    def.variants[*index].1.iter().map(|ty| subst_params(&args, ty)).collect()
+                }
+                None => Vec::new(),
+            },
+            (Constructor::Slice(kind), Ty::Array { inner, .. } | Ty::Slice(inner)) => vec![(**inner).clone(); kind.arity()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Every constructor of `ty`, split against the constructors in a
+    /// column of the matrix; `None` for types whose values cannot be
+    /// listed, like strings and floats
+    fn all_ctors(&self, ty: &Ty, column: &[&Constructor]) -> Option<Vec<Constructor>> {
+        let ctors = match ty {
+            Ty::Bool => vec![Constructor::Bool(false), Constructor::Bool(true)],
+            Ty::Char | Ty::I8 | Ty::I16 | Ty::I32 | Ty::I64 | Ty::I128 | Ty::ISize
+            | Ty::U8 | Ty::U16 | Ty::U32 | Ty::U64 | Ty::U128 | Ty::USize => {
+                let ranges = column_ranges(column);
+                int_ranges(ty)?.into_iter()
+                    .flat_map(|range| range.split(&ranges))
+                    .map(Constructor::IntRange)
+                    .collect()
+            }
+            Ty::Optional(_) => vec![Constructor::Variant(0), Constructor::Variant(1)],
+            Ty::Enum { name, .. } => (0..self.enums.get(&name.name)?.variants.len()).map(Constructor::Variant).collect(),
+            Ty::Tuple(_) | Ty::Ref { .. } => vec![Constructor::Single],
+            Ty::Struct { name, .. } if self.structs.contains_key(&name.name) => vec![Constructor::Single],
+            Ty::Array { .. } | Ty::Slice(_) => {
+                split_slices(ty, column, None).into_iter().map(Constructor::Slice).collect()
+            }
+            Ty::Never => Vec::new(),
+            _ => return None,
+        };
+        Some(ctors)
+    }
+
+    /// The witnesses that the row `q` is useful after `rows`: rows of
+    /// patterns matching values that `q` matches and no row of `rows` does
+    fn useful(&self, rows: &[Vec<Pat>], q: &[Pat], tys: &[Ty]) -> Vec<Vec<Pat>> {
+        let Some((head, rest)) = q.split_first() else {
+            return if rows.is_empty() { vec![Vec::new()] } else { Vec::new() };
+        };
+        let rows = expand_or_heads(rows);
+        let ty = &tys[0];
+
+        match head {
+            Pat::Or(alternatives) => alternatives.iter()
+                .flat_map(|alternative| {
+                    let mut q = vec![alternative.clone()];
+                    q.extend_from_slice(rest);
+                    self.useful(&rows, &q, tys)
+                })
+                .collect(),
+            Pat::Ctor(Constructor::Opaque, _) => {
+                with_head(self.useful(&default_rows(&rows), rest, &tys[1..]), Pat::Wild)
+            }
+            Pat::Ctor(ctor, _) => {
+                let column = column_ctors(&rows);
+                let ctors = match ctor {
+                    Constructor::IntRange(range) => {
+                        range.split(&column_ranges(&column)).into_iter().map(Constructor::IntRange).collect()
+                    }
+                    Constructor::Slice(kind) => {
+                        split_slices(ty, &column, Some(*kind)).into_iter().map(Constructor::Slice).collect()
+                    }
+                    _ => vec![ctor.clone()],
+                };
+                ctors.iter().flat_map(|ctor| self.useful_specialized(&rows, q, tys, ctor)).collect()
+            }
+            Pat::Wild => {
+                let column = column_ctors(&rows);
+                let Some(all) = self.all_ctors(ty, &column) else {
+                    return with_head(self.useful(&default_rows(&rows), rest, &tys[1..]), Pat::Wild);
+                };
+                let missing: Vec<Constructor> = all.iter()
+                    .filter(|ctor| !column.iter().any(|head| head.covers(ctor)))
+                    .cloned()
+                    .collect();
+                if missing.is_empty() {
+                    return all.iter().flat_map(|ctor| self.useful_specialized(&rows, q, tys, ctor)).collect();
+                }
+
+                // Values of a missing constructor are only matched by the
+                // rows starting with a wildcard
+                let witnesses = self.useful(&default_rows(&rows), rest, &tys[1..]);
+                if column.is_empty() {
+                    return with_head(witnesses, Pat::Wild);
+                }
+                merge_ranges(missing).into_iter()
+                    .flat_map(|ctor| {
+                        let fields = vec![Pat::Wild; self.field_tys(&ctor, ty).len()];
+                        with_head(witnesses.clone(), Pat::Ctor(ctor, fields))
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Usefulness of `q` among the values built with `ctor`, with the
+    /// witnesses' fields folded back under the constructor
+    fn useful_specialized(&self, rows: &[Vec<Pat>], q: &[Pat], tys: &[Ty], ctor: &Constructor) -> Vec<Vec<Pat>> {
+        let mut field_tys = self.field_tys(ctor, &tys[0]);
+        let arity = field_tys.len();
+        field_tys.extend_from_slice(&tys[1..]);
+
+        let specialize_row = |row: &[Pat]| {
+            specialize(&row[0], ctor, arity).map(|mut fields| {
+                fields.extend_from_slice(&row[1..]);
+                fields
+            })
+        };
+        let rows: Vec<Vec<Pat>> = rows.iter().filter_map(|row| specialize_row(row)).collect();
+        let Some(q) = specialize_row(q) else {
+            return Vec::new();
+        };
+
+        self.useful(&rows, &q, &field_tys).into_iter()
+            .map(|mut witness| {
+                let rest = witness.split_off(arity);
+                let mut row = vec![Pat::Ctor(ctor.clone(), witness)];
+                row.extend(rest);
+                row
+            })
+            .collect()
+    }
+
+    /// Write a witness pattern of type `ty` as source
+    fn display(&self, pat: &Pat, ty: &Ty) -> String {
+        let Pat::Ctor(ctor, fields) = pat else {
+            return "_".to_string();
+        };
+        let field_tys = self.field_tys(ctor, ty);
+        let fields: Vec<String> = fields.iter().zip(&field_tys).map(|(pat, ty)| self.display(pat, ty)).collect();
+
+        match (ctor, ty) {
+            (Constructor::Single, Ty::Ref { .. }) => fields[0].clone(),
+            (Constructor::Single, Ty::Tuple(_)) if fields.len() == 1 => format!("({},)", fields[0]),
+            (Constructor::Single, Ty::Tuple(_)) => format!("({})", fields.join(", ")),
+            (Constructor::Single, Ty::Struct { name, .. }) => {
+                let names: Vec<String> = self.structs.get(&name.name)
+                    .map(|def| def.fields.iter().map(|(name, _)| name.clone()).collect())
+                    .unwrap_or_default();
+                with_named_fields(&name.name, &names, &fields)
+            }
+            (Constructor::Variant(0), Ty::Optional(_)) => "None".to_string(),
+            (Constructor::Variant(_), Ty::Optional(_)) => format!("Some({})", fields[0]),
+            (Constructor::Variant(index), Ty::Enum { name, .. }) => {
+                let def = &self.enums[&name.name];
+                let variant = &def.variants[*index].0;
+                let path = if self.variant_imports.contains(&(name.name.clone(), variant.clone())) {
+                    variant.clone()
+                } else {
+                    format!("{}::{}", name.name, variant)
+                };
+                match def.named_fields.get(&def.variants[*index].0) {
+                    Some(names) => with_named_fields(&path, names, &fields),
+                    None if fields.is_empty() => path,
+                    None => format!("{}({})", path, fields.join(", ")),
+                }
+            }
+            (Constructor::Bool(b), _) => b.to_string(),
+            (Constructor::IntRange(range), _) => display_range(*range, ty),
+            (Constructor::Slice(SliceKind::Fixed(_)), _) => format!("[{}]", fields.join(", ")),
+            (Constructor::Slice(SliceKind::Var(prefix, _)), _) => {
+                let elements: Vec<&str> = fields[..*prefix].iter()
+                    .map(String::as_str)
+                    .chain(std::iter::once(".."))
+                    .chain(fields[*prefix..].iter().map(String::as_str))
+                    .collect();
+                format!("[{}]", elements.join(", "))
+            }
+            _ => "_".to_string(),
+        }
+    }
+}
+
+/// The fields of `head`, expanded to `arity` patterns, if it matches values
+/// built with `ctor`
+fn specialize(head: &Pat, ctor: &Constructor, arity: usize) -> Option<Vec<Pat>> {
+    match head {
+        Pat::Wild => Some(vec![Pat::Wild; arity]),
+        Pat::Ctor(head_ctor, fields) if head_ctor.covers(ctor) => match head_ctor {
+            // The elements between a slice pattern's prefix and suffix
+            // match anything
+            Constructor::Slice(SliceKind::Var(prefix, suffix)) => {
+                let mut expanded = fields[..*prefix].to_vec();
+                expanded.extend(vec![Pat::Wild; arity - prefix - suffix]);
+                expanded.extend_from_slice(&fields[*prefix..]);
+                Some(expanded)
+            }
+            _ => Some(fields.clone()),
+        },
+        _ => None,
+    }
+}
+
+/// `rows` with each row starting with an or-pattern replaced by a row per
+/// alternative
+fn expand_or_heads(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+    let mut expanded = Vec::new();
+    for row in rows {
+        match row.first() {
+            Some(Pat::Or(alternatives)) => {
+                let alternatives: Vec<Vec<Pat>> = alternatives.iter()
+                    .map(|alternative| {
+                        let mut row = row.clone();
+                        row[0] = alternative.clone();
+                        row
+                    })
+                    .collect();
+                expanded.extend(expand_or_heads(&alternatives));
+            }
+            _ => expanded.push(row.clone()),
+        }
+    }
+    expanded
+}
+
+/// The rest of each row starting with a wildcard
+fn default_rows(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+    rows.iter()
+        .filter(|row| matches!(row[0], Pat::Wild))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// The constructors in the first column of `rows`
+fn column_ctors(rows: &[Vec<Pat>]) -> Vec<&Constructor> {
+    rows.iter()
+        .filter_map(|row| match &row[0] {
+            Pat::Ctor(ctor, _) => Some(ctor),
+            _ => None,
+        })
+        .collect()
+}
+
+fn column_ranges(column: &[&Constructor]) -> Vec<IntRange> {
+    column.iter()
+        .filter_map(|ctor| match ctor {
+            Constructor::IntRange(range) => Some(*range),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Each witness with `head` put in front
+fn with_head(witnesses: Vec<Vec<Pat>>, head: Pat) -> Vec<Vec<Pat>> {
+    witnesses.into_iter()
+        .map(|witness| {
+            let mut row = vec![head.clone()];
+            row.extend(witness);
+            row
+        })
+        .collect()
+}
+
+/// `ctors` with adjacent ranges joined, so that the missing values of an
+/// integer are reported as few ranges
+fn merge_ranges(ctors: Vec<Constructor>) -> Vec<Constructor> {
+    let mut merged: Vec<Constructor> = Vec::new();
+    for ctor in ctors {
+        if let (Some(Constructor::IntRange(last)), Constructor::IntRange(range)) = (merged.last_mut(), &ctor) {
+            if last.hi.checked_add(1) == Some(range.lo) {
+                last.hi = range.hi;
+                continue;
+            }
+        }
+        merged.push(ctor);
+    }
+    merged
+}
+
+/// The lengths an array or slice of type `ty` is split into against the
+/// slice patterns of a column and, if given, the one being checked
+///
+/// Lengths past the longest fixed-length pattern and every prefix and
+/// suffix together are all matched by the same patterns, so they are
+/// grouped into one variable-length constructor. Only the lengths `q`
+/// covers are kept.
+fn split_slices(ty: &Ty, column: &[&Constructor], q: Option<SliceKind>) -> Vec<SliceKind> {
+    let kinds = column.iter()
+        .filter_map(|ctor| match ctor {
+            Constructor::Slice(kind) => Some(*kind),
+            _ => None,
+        })
+        .chain(q);
+
+    let all = match ty {
+        Ty::Array { len: Some(len), .. } => vec![SliceKind::Fixed(*len as usize)],
+        _ => {
+            let (mut max_fixed, mut max_prefix, mut max_suffix) = (None, 0, 0);
+            for kind in kinds {
+                match kind {
+                    SliceKind::Fixed(len) => max_fixed = max_fixed.max(Some(len)),
+                    SliceKind::Var(prefix, suffix) => {
+                        max_prefix = max_prefix.max(prefix);
+                        max_suffix = max_suffix.max(suffix);
+                    }
+                }
+            }
+            let len = max_fixed.map_or(0, |len| len + 1).max(max_prefix + max_suffix);
+            (0..len).map(SliceKind::Fixed)
+                .chain(std::iter::once(SliceKind::Var(len - max_suffix, max_suffix)))
+                .collect()
+        }
+    };
+    match q {
+        Some(q) => all.into_iter().filter(|&kind| q.covers(kind)).collect(),
+        None => all,
+    }
+}
+
+/// The width and signedness of an integer type; integer literals whose
+/// type is still open are checked as `i32`, what they default to
+fn int_bits(ty: &Ty) -> Option<(u32, bool)> {
+    match ty {
+        Ty::IntVar(_) => Some((32, true)),
+        ty => ty.int_bits(),
+    }
+}
+
+/// The values of an integer type or `char`, which leaves out surrogates
+fn int_ranges(ty: &Ty) -> Option<Vec<IntRange>> {
+    if *ty == Ty::Char {
+        return Some(vec![IntRange { lo: 0, hi: 0xD7FF }, IntRange { lo: 0xE000, hi: 0x10FFFF }]);
+    }
+    let (bits, _) = int_bits(ty)?;
+    let hi = if bits == 128 { u128::MAX } else { (1 << bits) - 1 };
+    Some(vec![IntRange { lo: 0, hi }])
+}
+
+/// Write one biased value of an integer type or `char` as source
+fn display_value(value: u128, ty: &Ty) -> String {
+    let ty = match ty {
+        Ty::IntVar(_) => &Ty::I32,
+        ty => ty,
+    };
+    if *ty == Ty::Char {
+        return match char::from_u32(value as u32) {
+            Some(c) => format!("{:?}", c),
+            None => format!("'\\u{{{:x}}}'", value),
+        };
+    }
+    let Some((bits, signed)) = int_bits(ty) else {
+        return value.to_string();
+    };
+    let max = if bits == 128 { u128::MAX } else { (1 << bits) - 1 };
+    if value == max {
+        return format!("{}::MAX", ty);
+    }
+    match signed {
+        true if value == 0 => format!("{}::MIN", ty),
+        true => (value.wrapping_sub(1 << (bits - 1)) as i128).to_string(),
+        false => value.to_string(),
+    }
+}
+
+fn display_range(range: IntRange, ty: &Ty) -> String {
+    if range.lo == range.hi {
+        display_value(range.lo, ty)
+    } else {
+        format!("{}..={}", display_value(range.lo, ty), display_value(range.hi, ty))
+    }
+}
+
+/// `Name { a: _, b: _ }`
+fn with_named_fields(path: &str, names: &[String], fields: &[String]) -> String {
+    let fields: Vec<String> = names.iter().zip(fields).map(|(name, field)| format!("{}: {}", name, field)).collect();
+    if fields.is_empty() {
+        format!("{} {{}}", path)
+    } else {
+        format!("{} {{ {} }}", path, fields.join(", "))
+    }
+}
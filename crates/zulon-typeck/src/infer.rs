@@ -38,8 +38,24 @@ impl Substitution {
     }
 
     /// Apply substitution to a type
+    ///
+    /// Bindings are followed transitively: with `?0 -> ?1` and `?1 -> i32`,
+    /// `?0` resolves to `i32`.
     pub fn apply(&self, ty: &Ty) -> Ty {
-        subst_ty(&self.substs, ty)
+        let mut ty = subst_ty(&self.substs, ty);
+        // The occurs check keeps bindings acyclic, so chains are at most
+        // as long as the substitution
+        for _ in 0..self.substs.len() {
+            if !ty.has_ty_vars() {
+                break;
+            }
+            let next = subst_ty(&self.substs, &ty);
+            if next == ty {
+                break;
+            }
+            ty = next;
+        }
+        ty
     }
 
     /// Compose two substitutions: (self ∘ other)
@@ -104,6 +120,20 @@ pub fn unify_with_subst(
             bind_type_var(id, &ty, span, subst)?;
         }
 
+        // Literal variables unify with each other and with the types of
+        // their kind
+        (Ty::IntVar(id1), ty2 @ Ty::IntVar(id2)) | (Ty::FloatVar(id1), ty2 @ Ty::FloatVar(id2)) => {
+            if id1 != id2 {
+                subst.bind(id1, &ty2);
+            }
+        }
+        (Ty::IntVar(id), ty) | (ty, Ty::IntVar(id)) if ty.is_integer() => {
+            subst.bind(id, &ty);
+        }
+        (Ty::FloatVar(id), ty) | (ty, Ty::FloatVar(id)) if ty.is_float() => {
+            subst.bind(id, &ty);
+        }
+
         // Primitive types - must be exactly equal
         (Ty::Bool, Ty::Bool) |
         (Ty::I8, Ty::I8) |
@@ -127,6 +157,18 @@ pub fn unify_with_subst(
             // Equal, nothing to do
         }
 
+        // Generic parameters only unify with themselves
+        (Ty::Param(name1), Ty::Param(name2)) if name1 == name2 => {}
+
+        // Projections the checker could not normalize are only equal to
+        // the same projection
+        (
+            Ty::Projection { base: base1, trait_name: trait1, name: name1 },
+            Ty::Projection { base: base2, trait_name: trait2, name: name2 },
+        ) if trait1 == trait2 && name1 == name2 => {
+            unify_with_subst(&base1, &base2, span, subst)?;
+        }
+
         // Never type (diverging) unifies with any type
         // This allows expressions like `throw` or `return` to work in any context
         (Ty::Never, _) | (_, Ty::Never) => {
@@ -137,9 +179,9 @@ pub fn unify_with_subst(
         (Ty::Ref { inner: inner1, mutable: mut1 }, Ty::Ref { inner: inner2, mutable: mut2 }) => {
             if mut1 != mut2 {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Ref { inner: inner1.clone(), mutable: mut1 },
-                    found: Ty::Ref { inner: inner2.clone(), mutable: mut2 },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Ref { inner: inner1.clone(), mutable: mut1 }),
+                    found: Box::new(Ty::Ref { inner: inner2.clone(), mutable: mut2 }),
+                    span: *span,
                 });
             }
             unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
@@ -149,9 +191,9 @@ pub fn unify_with_subst(
         (Ty::Ptr { inner: inner1, mutable: mut1 }, Ty::Ptr { inner: inner2, mutable: mut2 }) => {
             if mut1 != mut2 {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Ptr { inner: inner1.clone(), mutable: mut1 },
-                    found: Ty::Ptr { inner: inner2.clone(), mutable: mut2 },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Ptr { inner: inner1.clone(), mutable: mut1 }),
+                    found: Box::new(Ty::Ptr { inner: inner2.clone(), mutable: mut2 }),
+                    span: *span,
                 });
             }
             unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
@@ -161,9 +203,9 @@ pub fn unify_with_subst(
         (Ty::Array { inner: inner1, len: len1 }, Ty::Array { inner: inner2, len: len2 }) => {
             if len1 != len2 {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Array { inner: inner1.clone(), len: len1 },
-                    found: Ty::Array { inner: inner2.clone(), len: len2 },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Array { inner: inner1.clone(), len: len1 }),
+                    found: Box::new(Ty::Array { inner: inner2.clone(), len: len2 }),
+                    span: *span,
                 });
             }
             unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
@@ -178,9 +220,9 @@ pub fn unify_with_subst(
         (Ty::Tuple(tys1), Ty::Tuple(tys2)) => {
             if tys1.len() != tys2.len() {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Tuple(tys1.clone()),
-                    found: Ty::Tuple(tys2.clone()),
-                    span: span.clone(),
+                    expected: Box::new(Ty::Tuple(tys1.clone())),
+                    found: Box::new(Ty::Tuple(tys2.clone())),
+                    span: *span,
                 });
             }
             for (ty1, ty2) in tys1.iter().zip(tys2.iter()) {
@@ -193,16 +235,16 @@ pub fn unify_with_subst(
             // Variadic flags must match
             if var1 != var2 {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Function { params: params1.clone(), return_type: ret1.clone(), variadic: var1 },
-                    found: Ty::Function { params: params2.clone(), return_type: ret2.clone(), variadic: var2 },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Function { params: params1.clone(), return_type: ret1.clone(), variadic: var1 }),
+                    found: Box::new(Ty::Function { params: params2.clone(), return_type: ret2.clone(), variadic: var2 }),
+                    span: *span,
                 });
             }
             if params1.len() != params2.len() {
                 return Err(TypeError::ArityMismatch {
                     expected: params1.len(),
                     found: params2.len(),
-                    span: span.clone(),
+                    span: *span,
                 });
             }
             for (param1, param2) in params1.iter().zip(params2.iter()) {
@@ -215,16 +257,16 @@ pub fn unify_with_subst(
         (Ty::Struct { name: name1, generics: gens1 }, Ty::Struct { name: name2, generics: gens2 }) => {
             if name1.name != name2.name {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Struct { name: name1.clone(), generics: gens1.clone() },
-                    found: Ty::Struct { name: name2.clone(), generics: gens2.clone() },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Struct { name: name1.clone(), generics: gens1.clone() }),
+                    found: Box::new(Ty::Struct { name: name2.clone(), generics: gens2.clone() }),
+                    span: *span,
                 });
             }
             if gens1.len() != gens2.len() {
                 return Err(TypeError::ArityMismatch {
                     expected: gens1.len(),
                     found: gens2.len(),
-                    span: span.clone(),
+                    span: *span,
                 });
             }
             for (gen1, gen2) in gens1.iter().zip(gens2.iter()) {
@@ -236,16 +278,16 @@ pub fn unify_with_subst(
         (Ty::Enum { name: name1, generics: gens1 }, Ty::Enum { name: name2, generics: gens2 }) => {
             if name1.name != name2.name {
                 return Err(TypeError::TypeMismatch {
-                    expected: Ty::Enum { name: name1.clone(), generics: gens1.clone() },
-                    found: Ty::Enum { name: name2.clone(), generics: gens2.clone() },
-                    span: span.clone(),
+                    expected: Box::new(Ty::Enum { name: name1.clone(), generics: gens1.clone() }),
+                    found: Box::new(Ty::Enum { name: name2.clone(), generics: gens2.clone() }),
+                    span: *span,
                 });
             }
             if gens1.len() != gens2.len() {
                 return Err(TypeError::ArityMismatch {
                     expected: gens1.len(),
                     found: gens2.len(),
-                    span: span.clone(),
+                    span: *span,
                 });
             }
             for (gen1, gen2) in gens1.iter().zip(gens2.iter()) {
@@ -258,10 +300,8 @@ pub fn unify_with_subst(
             unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
         }
 
-        // Trait objects and impl trait - structural equality for now
-        (Ty::TraitObject(inner1), Ty::TraitObject(inner2)) => {
-            unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
-        }
+        // Trait objects unify when they name the same trait
+        (Ty::TraitObject(t1), Ty::TraitObject(t2)) if t1 == t2 => {}
         (Ty::ImplTrait(inner1), Ty::ImplTrait(inner2)) => {
             unify_with_subst(inner1.as_ref(), inner2.as_ref(), span, subst)?;
         }
@@ -269,9 +309,9 @@ pub fn unify_with_subst(
         // Type mismatch
         (ty1, ty2) => {
             return Err(TypeError::TypeMismatch {
-                expected: ty1,
-                found: ty2,
-                span: span.clone(),
+                expected: Box::new(ty1),
+                found: Box::new(ty2),
+                span: *span,
             });
         }
     }
@@ -301,7 +341,7 @@ fn bind_type_var(ty_var: TyVarId, ty: &Ty, span: &Span, subst: &mut Substitution
     if occurs_in(ty_var, ty) {
         return Err(TypeError::InferenceError {
             message: format!("infinite type: type variable ?{} occurs in {}", ty_var, ty),
-            span: span.clone(),
+            span: *span,
         });
     }
 
@@ -327,9 +367,10 @@ fn occurs_in(ty_var: TyVarId, ty: &Ty) -> bool {
         Ty::Struct { generics, .. } | Ty::Enum { generics, .. } => {
             generics.iter().any(|t| occurs_in(ty_var, t))
         }
-        Ty::TraitObject(inner) | Ty::ImplTrait(inner) | Ty::Optional(inner) => {
+        Ty::ImplTrait(inner) | Ty::Optional(inner) => {
             occurs_in(ty_var, inner)
         }
+        Ty::Projection { base, .. } => occurs_in(ty_var, base),
 
         // Primitive types don't contain type variables
         _ => false,
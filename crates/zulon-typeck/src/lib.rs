@@ -43,11 +43,14 @@ pub mod infer;
 pub mod diagnostic;
 pub mod effect;
 pub mod effect_inference;
+mod exhaustive;
+mod const_eval;
 
-pub use ty::{Ty, TyVarId, GenericParam, TraitBound, subst_ty};
+pub use ty::{Ty, TyVarId, GenericParam, TraitBound, subst_ty, subst_params};
 pub use env::Env;
-pub use error::{TypeError, Result};
-pub use checker::TypeChecker;
+pub use error::{TypeError, Warning, Result};
+pub use checker::{TypeChecker, MethodCallee, MethodResolution};
+pub use const_eval::ConstValue;
 pub use infer::{Substitution, unify};
 pub use effect::{Effect, EffectSet};
 pub use effect_inference::EffectInference;
@@ -56,6 +56,24 @@ pub enum Ty {
     /// Type variable (for type inference)
     TyVar(TyVarId),
 
+    /// Type of an integer literal, some integer type not yet known
+    IntVar(TyVarId),
+
+    /// Type of a float literal, some float type not yet known
+    FloatVar(TyVarId),
+
+    /// Generic type parameter `T`, replaced at each instantiation
+    Param(String),
+
+    /// Associated type `name` of the impl of `trait_name` for `base`,
+    /// `<I as Iterator>::Item`, until `base` is known well enough to
+    /// pick the impl
+    Projection {
+        base: Box<Ty>,
+        trait_name: String,
+        name: String,
+    },
+
     /// Reference type
     Ref {
         inner: Box<Ty>,
@@ -99,8 +117,8 @@ pub enum Ty {
         generics: Vec<Ty>,
     },
 
-    /// Trait object type
-    TraitObject(Box<Ty>),
+    /// Trait object type (`dyn Trait`), named by its trait
+    TraitObject(String),
 
     /// Impl Trait type
     ImplTrait(Box<Ty>),
@@ -128,6 +146,7 @@ impl Ty {
             Ty::I8 | Ty::I16 | Ty::I32 | Ty::I64 | Ty::I128 | Ty::ISize => true,
             Ty::U8 | Ty::U16 | Ty::U32 | Ty::U64 | Ty::U128 | Ty::USize => true,
             Ty::F32 | Ty::F64 => true,
+            Ty::IntVar(_) | Ty::FloatVar(_) => true,
 
             // References and pointers are Copy
             Ty::Ref { .. } | Ty::Ptr { .. } => true,
@@ -149,7 +168,7 @@ impl Ty {
             self,
             Ty::I8 | Ty::I16 | Ty::I32 | Ty::I64 | Ty::I128 | Ty::ISize |
                 Ty::U8 | Ty::U16 | Ty::U32 | Ty::U64 | Ty::U128 | Ty::USize |
-                Ty::F32 | Ty::F64
+                Ty::F32 | Ty::F64 | Ty::IntVar(_) | Ty::FloatVar(_)
         )
     }
 
@@ -169,9 +188,43 @@ impl Ty {
         )
     }
 
-    /// Check if type is integer (signed or unsigned)
+    /// Check if type is integer (signed or unsigned, or not yet known)
     pub fn is_integer(&self) -> bool {
-        self.is_signed_integer() || self.is_unsigned_integer()
+        self.is_signed_integer() || self.is_unsigned_integer() || matches!(self, Ty::IntVar(_))
+    }
+
+    /// The width and signedness of an integer type
+    pub fn int_bits(&self) -> Option<(u32, bool)> {
+        match self {
+            Ty::I8 => Some((8, true)),
+            Ty::I16 => Some((16, true)),
+            Ty::I32 => Some((32, true)),
+            Ty::I64 | Ty::ISize => Some((64, true)),
+            Ty::I128 => Some((128, true)),
+            Ty::U8 => Some((8, false)),
+            Ty::U16 => Some((16, false)),
+            Ty::U32 => Some((32, false)),
+            Ty::U64 | Ty::USize => Some((64, false)),
+            Ty::U128 => Some((128, false)),
+            _ => None,
+        }
+    }
+
+    /// The largest magnitude an integer type holds, of a negative value if
+    /// `negated`
+    pub fn int_max_magnitude(&self, negated: bool) -> Option<u128> {
+        let (bits, signed) = self.int_bits()?;
+        Some(match (signed, negated) {
+            (true, true) => 1u128 << (bits - 1),
+            (true, false) => (1u128 << (bits - 1)) - 1,
+            (false, true) => 0,
+            (false, false) => u128::MAX >> (128 - bits),
+        })
+    }
+
+    /// Check if type is a floating point type
+    pub fn is_float(&self) -> bool {
+        matches!(self, Ty::F32 | Ty::F64 | Ty::FloatVar(_))
     }
 
     /// Check if type is a reference
@@ -179,6 +232,60 @@ impl Ty {
         matches!(self, Ty::Ref { .. })
     }
 
+    /// Check if the type mentions the type parameter `param`
+    pub fn mentions_param(&self, param: &str) -> bool {
+        match self {
+            Ty::Param(name) => name == param,
+            Ty::Projection { base, .. } => base.mentions_param(param),
+            Ty::Ref { inner, .. } | Ty::Ptr { inner, .. } | Ty::Array { inner, .. } => inner.mentions_param(param),
+            Ty::Slice(inner) | Ty::ImplTrait(inner) | Ty::Optional(inner) => inner.mentions_param(param),
+            Ty::Tuple(tys) => tys.iter().any(|ty| ty.mentions_param(param)),
+            Ty::Function { params, return_type, .. } => {
+                params.iter().any(|ty| ty.mentions_param(param)) || return_type.mentions_param(param)
+            }
+            Ty::Struct { generics, .. } | Ty::Enum { generics, .. } => {
+                generics.iter().any(|ty| ty.mentions_param(param))
+            }
+            _ => false,
+        }
+    }
+
+    /// Check if the type still contains unresolved type variables
+    pub fn has_ty_vars(&self) -> bool {
+        match self {
+            Ty::TyVar(_) | Ty::IntVar(_) | Ty::FloatVar(_) => true,
+            Ty::Ref { inner, .. } | Ty::Ptr { inner, .. } | Ty::Array { inner, .. } => inner.has_ty_vars(),
+            Ty::Projection { base, .. } => base.has_ty_vars(),
+            Ty::Slice(inner) | Ty::ImplTrait(inner) | Ty::Optional(inner) => {
+                inner.has_ty_vars()
+            }
+            Ty::Tuple(tys) => tys.iter().any(Ty::has_ty_vars),
+            Ty::Function { params, return_type, .. } => {
+                params.iter().any(Ty::has_ty_vars) || return_type.has_ty_vars()
+            }
+            Ty::Struct { generics, .. } | Ty::Enum { generics, .. } => generics.iter().any(Ty::has_ty_vars),
+            _ => false,
+        }
+    }
+
+    /// Check if the type contains a projection whose base still contains
+    /// type variables
+    pub fn has_unresolved_projection(&self) -> bool {
+        match self {
+            Ty::Projection { base, .. } => base.has_ty_vars(),
+            Ty::Ref { inner, .. } | Ty::Ptr { inner, .. } | Ty::Array { inner, .. } => inner.has_unresolved_projection(),
+            Ty::Slice(inner) | Ty::ImplTrait(inner) | Ty::Optional(inner) => inner.has_unresolved_projection(),
+            Ty::Tuple(tys) => tys.iter().any(Ty::has_unresolved_projection),
+            Ty::Function { params, return_type, .. } => {
+                params.iter().any(Ty::has_unresolved_projection) || return_type.has_unresolved_projection()
+            }
+            Ty::Struct { generics, .. } | Ty::Enum { generics, .. } => {
+                generics.iter().any(Ty::has_unresolved_projection)
+            }
+            _ => false,
+        }
+    }
+
     /// Get inner type of reference or pointer
     pub fn inner_ty(&self) -> Option<&Ty> {
         match self {
@@ -214,6 +321,10 @@ impl fmt::Display for Ty {
             Ty::Unit => write!(f, "()"),
             Ty::Never => write!(f, "!"),
             Ty::TyVar(id) => write!(f, "?{}", id),
+            Ty::IntVar(_) => write!(f, "{{integer}}"),
+            Ty::FloatVar(_) => write!(f, "{{float}}"),
+            Ty::Param(name) => write!(f, "{}", name),
+            Ty::Projection { base, trait_name, name } => write!(f, "<{} as {}>::{}", base, trait_name, name),
             Ty::Ref { inner, mutable: false } => write!(f, "&{}", inner),
             Ty::Ref { inner, mutable: true } => write!(f, "&mut {}", inner),
             Ty::Ptr { inner, mutable: false } => write!(f, "*const {}", inner),
@@ -324,6 +435,8 @@ pub fn subst_ty(substs: &Substs, ty: &Ty) -> Ty {
     match ty {
         // Type variable - look up in substitution
         Ty::TyVar(id) => substs.get(id).cloned().unwrap_or_else(|| Ty::TyVar(*id)),
+        Ty::IntVar(id) => substs.get(id).cloned().unwrap_or(Ty::IntVar(*id)),
+        Ty::FloatVar(id) => substs.get(id).cloned().unwrap_or(Ty::FloatVar(*id)),
 
         // Recursive types
         Ty::Ref { inner, mutable } => Ty::Ref {
@@ -353,15 +466,61 @@ pub fn subst_ty(substs: &Substs, ty: &Ty) -> Ty {
             name: name.clone(),
             generics: generics.iter().map(|t| subst_ty(substs, t)).collect(),
         },
-        Ty::TraitObject(inner) => Ty::TraitObject(Box::new(subst_ty(substs, inner))),
         Ty::ImplTrait(inner) => Ty::ImplTrait(Box::new(subst_ty(substs, inner))),
+        Ty::Projection { base, trait_name, name } => Ty::Projection {
+            base: Box::new(subst_ty(substs, base)),
+            trait_name: trait_name.clone(),
+            name: name.clone(),
+        },
         Ty::Optional(inner) => Ty::Optional(Box::new(subst_ty(substs, inner))),
 
         // Non-recursive types - return as is
         Ty::Bool | Ty::I8 | Ty::I16 | Ty::I32 | Ty::I64 | Ty::I128 | Ty::ISize |
         Ty::U8 | Ty::U16 | Ty::U32 | Ty::U64 | Ty::U128 | Ty::USize |
         Ty::F32 | Ty::F64 | Ty::Char | Ty::String | Ty::Unit | Ty::Never |
-        Ty::Effect(_) => ty.clone(),
+        Ty::Param(_) | Ty::Effect(_) | Ty::TraitObject(_) => ty.clone(),
+    }
+}
+
+/// Replace generic parameters by name, instantiating a generic signature
+pub fn subst_params(params: &HashMap<String, Ty>, ty: &Ty) -> Ty {
+    match ty {
+        Ty::Param(name) => params.get(name).cloned().unwrap_or_else(|| ty.clone()),
+        Ty::Ref { inner, mutable } => Ty::Ref {
+            inner: Box::new(subst_params(params, inner)),
+            mutable: *mutable,
+        },
+        Ty::Ptr { inner, mutable } => Ty::Ptr {
+            inner: Box::new(subst_params(params, inner)),
+            mutable: *mutable,
+        },
+        Ty::Array { inner, len } => Ty::Array {
+            inner: Box::new(subst_params(params, inner)),
+            len: *len,
+        },
+        Ty::Slice(inner) => Ty::Slice(Box::new(subst_params(params, inner))),
+        Ty::Tuple(tys) => Ty::Tuple(tys.iter().map(|t| subst_params(params, t)).collect()),
+        Ty::Function { params: fn_params, return_type, variadic } => Ty::Function {
+            params: fn_params.iter().map(|t| subst_params(params, t)).collect(),
+            return_type: Box::new(subst_params(params, return_type)),
+            variadic: *variadic,
+        },
+        Ty::Struct { name, generics } => Ty::Struct {
+            name: name.clone(),
+            generics: generics.iter().map(|t| subst_params(params, t)).collect(),
+        },
+        Ty::Enum { name, generics } => Ty::Enum {
+            name: name.clone(),
+            generics: generics.iter().map(|t| subst_params(params, t)).collect(),
+        },
+        Ty::ImplTrait(inner) => Ty::ImplTrait(Box::new(subst_params(params, inner))),
+        Ty::Projection { base, trait_name, name } => Ty::Projection {
+            base: Box::new(subst_params(params, base)),
+            trait_name: trait_name.clone(),
+            name: name.clone(),
+        },
+        Ty::Optional(inner) => Ty::Optional(Box::new(subst_params(params, inner))),
+        _ => ty.clone(),
     }
 }
 
@@ -400,6 +559,14 @@ mod tests {
         assert!(!Ty::String.is_numeric());
     }
 
+    #[test]
+    fn test_int_max_magnitude() {
+        assert_eq!(Ty::U8.int_max_magnitude(false), Some(255));
+        assert_eq!(Ty::I8.int_max_magnitude(true), Some(128));
+        assert_eq!(Ty::U64.int_max_magnitude(true), Some(0));
+        assert_eq!(Ty::F64.int_max_magnitude(false), None);
+    }
+
     #[test]
     fn test_is_copy() {
         assert!(Ty::I32.is_copy());
@@ -416,4 +583,16 @@ mod tests {
         let result = subst_ty(&substs, &ty_var);
         assert_eq!(result, Ty::I32);
     }
+
+    #[test]
+    fn test_subst_params() {
+        let mut params = HashMap::new();
+        params.insert("T".to_string(), Ty::I64);
+
+        let ty = Ty::Tuple(vec![Ty::Param("T".to_string()), Ty::Param("U".to_string())]);
+        assert_eq!(
+            subst_params(&params, &ty),
+            Ty::Tuple(vec![Ty::I64, Ty::Param("U".to_string())])
+        );
+    }
 }
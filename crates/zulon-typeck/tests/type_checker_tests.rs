@@ -6,7 +6,7 @@
 //! Comprehensive tests for the ZULON type checker.
 
 use zulon_parser::Parser;
-use zulon_typeck::TypeChecker;
+use zulon_typeck::{ConstValue, TypeChecker};
 
 /// Helper function to parse source code
 fn parse(source: &str) -> zulon_parser::ast::Ast {
@@ -90,6 +90,230 @@ fn test_primitive_string() {
     assert_type_check_passes(source);
 }
 
+#[test]
+fn test_template_string_arguments() {
+    let source = r#"
+        trait Display {
+            fn fmt(self: &Self) -> str;
+        }
+
+        struct Point { x: i32, y: i32 }
+
+        impl Display for Point {
+            fn fmt(self: &Self) -> str {
+                `(${self.x}, ${self.y})`
+            }
+        }
+
+        fn show<T: Display>(value: T) -> str {
+            `<${value}>`
+        }
+
+        fn main() -> str {
+            let p = Point { x: 1, y: 2 };
+            let width = 8;
+            let small = 7u8;
+            `${p} ${&p} ${small:>width$} ${1.5f32:.2} ${show(p)} ${show(true)}`
+        }
+    "#;
+
+    assert_type_check_passes(source);
+    assert_type_error(&source.replace("-> str {\n            let p", "-> i32 {\n            let p"));
+    assert!(matches!(
+        type_check(&source.replace("fn show<T: Display>", "fn show<T>")),
+        Err(zulon_typeck::TypeError::TraitBoundNotSatisfied { .. })
+    ));
+    assert!(matches!(
+        type_check(&source.replace("impl Display for Point", "impl Point")),
+        Err(zulon_typeck::TypeError::TraitBoundNotSatisfied { .. })
+    ));
+    assert_type_error(&source.replace("let width = 8;", "let width = true;"));
+}
+
+#[test]
+fn test_suffixed_literals_have_suffix_type() {
+    let source = r#"
+        fn main() -> i32 {
+            let a: u8 = 0xFF_u8;
+            let b: i64 = 1_000_000i64;
+            let c: f32 = 2.5f32;
+            let d: i8 = -128i8;
+            0
+        }
+    "#;
+
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_suffixed_literal_type_mismatch() {
+    let source = r#"
+        fn main() -> i32 {
+            let x: i32 = 42u8;
+            x
+        }
+    "#;
+
+    assert_type_error(source);
+}
+
+#[test]
+fn test_suffixed_literal_overflow() {
+    for source in [
+        "fn main() -> i32 { let x = 256u8; 0 }",
+        "fn main() -> i32 { let x = 128i8; 0 }",
+        "fn main() -> i32 { let x = -1u32; 0 }",
+    ] {
+        match type_check(source) {
+            Err(zulon_typeck::TypeError::IntegerOverflow { .. }) => {}
+            other => panic!("expected overflow for {:?}, got {:?}", source, other),
+        }
+    }
+}
+
+#[test]
+fn test_unsuffixed_literals_take_type_from_context() {
+    let source = r#"
+        fn wide(x: i64) -> i64 {
+            x * 3000000000
+        }
+
+        fn main() -> i32 {
+            let a: u64 = 5;
+            let b: i8 = -128;
+            let c = wide(2) + 1;
+            let d: f32 = 1.5;
+            let e = [1u16, 2, 3];
+            let f = 7;
+            f
+        }
+    "#;
+
+    let ast = parse(source);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("Type checking failed");
+    // `3000000000` is an i64, unconstrained literals are i32 and f64
+    let literal_ty = |text: &str| {
+        let line = source.lines().position(|line| line.contains(text)).unwrap() + 1;
+        let column = source.lines().nth(line - 1).unwrap().find(text).unwrap() + 1;
+        let span = zulon_parser::Span::new(
+            zulon_parser::Position::new(line, column),
+            zulon_parser::Position::new(line, column + text.len()),
+        );
+        checker.expr_type(&span).cloned()
+    };
+    assert_eq!(literal_ty("3000000000"), Some(zulon_typeck::Ty::I64));
+    assert_eq!(literal_ty("7"), Some(zulon_typeck::Ty::I32));
+}
+
+#[test]
+fn test_unsuffixed_literal_default_and_mismatch() {
+    assert_type_check_passes("fn main() -> i32 { let x = 2.5; let y = 1; y }");
+    assert_type_error("fn main() -> i32 { let x: f64 = 5; 0 }");
+    assert_type_error("fn main() -> i32 { let x: bool = 1; 0 }");
+    assert_type_error("fn main() -> i32 { let x: i64 = 1; let y: i32 = x + 1; y }");
+}
+
+#[test]
+fn test_unsuffixed_literal_out_of_range() {
+    for (source, expected) in [
+        ("fn main() -> i32 { let x: u8 = 256; 0 }", zulon_typeck::Ty::U8),
+        ("fn main() -> i32 { let x: i8 = -129; 0 }", zulon_typeck::Ty::I8),
+        ("fn main() -> i32 { let x: u32 = -1; 0 }", zulon_typeck::Ty::U32),
+        ("fn main() -> i32 { let x = 3000000000; 0 }", zulon_typeck::Ty::I32),
+    ] {
+        match type_check(source) {
+            Err(zulon_typeck::TypeError::LiteralOutOfRange { ty, .. }) => assert_eq!(*ty, expected),
+            other => panic!("expected out of range literal for {:?}, got {:?}", source, other),
+        }
+    }
+}
+
+#[test]
+fn test_byte_literals() {
+    let source = r#"
+        fn main() -> i32 {
+            let b: u8 = b'x';
+            let header = b"GET";
+            let pattern = r"\d+";
+            0
+        }
+    "#;
+
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_if_let_binds_in_then_branch_only() {
+    let source = r#"
+        fn main() -> i32 {
+            let pair = (1, 2);
+            if let (a, b) = pair { a + b } else { 0 }
+        }
+    "#;
+    assert_type_check_passes(source);
+
+    let source = r#"
+        fn main() -> i32 {
+            let pair = (1, 2);
+            if let (a, b) = pair { a } else { b }
+        }
+    "#;
+    assert_type_error(source);
+}
+
+#[test]
+fn test_if_let_pattern_type_mismatch() {
+    let source = r#"
+        fn main() -> i32 {
+            if let true = 1 { 1 } else { 0 }
+        }
+    "#;
+    assert_type_error(source);
+}
+
+#[test]
+fn test_while_let_binds_in_body() {
+    let source = r#"
+        fn main() -> i32 {
+            let pair = (1, 2);
+            while let (n, k) = pair {
+                let total: i32 = n + k;
+            }
+            0
+        }
+    "#;
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_let_else() {
+    let source = r#"
+        fn main() -> i32 {
+            let pair = (1, 2);
+            let (a, b) = pair else {
+                return 0;
+            };
+            a + b
+        }
+    "#;
+    assert_type_check_passes(source);
+
+    let source = r#"
+        fn main() -> i32 {
+            let pair = (1, 2);
+            let (a, b) = pair else {
+                let fallback = 0;
+            };
+            a
+        }
+    "#;
+    match type_check(source) {
+        Err(zulon_typeck::TypeError::LetElseNotDiverging { .. }) => {}
+        other => panic!("expected LetElseNotDiverging, got {:?}", other),
+    }
+}
+
 //
 // Function Type Tests
 //
@@ -157,6 +381,23 @@ fn test_function_type_mismatch() {
     assert_type_check_passes(source);
 }
 
+#[test]
+fn test_mismatch_reports_annotation_as_expected() {
+    let source = r#"
+        fn first(c: i32) -> i32 {
+            c
+        }
+
+        fn main() -> i32 {
+            let x: bool = first(1);
+            0
+        }
+    "#;
+
+    let err = type_check(source).expect_err("Expected a type mismatch");
+    assert_eq!(err.to_string(), "type mismatch: expected bool, found i32");
+}
+
 //
 // Tuple Type Tests
 //
@@ -329,6 +570,222 @@ fn test_match_wildcard() {
     assert_type_check_passes(source);
 }
 
+/// The examples of missing patterns a match in `source` reports
+fn missing_patterns(source: &str) -> Vec<String> {
+    match type_check(source) {
+        Err(zulon_typeck::TypeError::NonExhaustivePatterns { missing, .. }) => missing,
+        other => panic!("Expected a non-exhaustive match, got {:?}", other),
+    }
+}
+
+/// The number of unreachable arms reported in `source`, which must check
+fn unreachable_arms(source: &str) -> usize {
+    let ast = parse(source);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("Type checking failed");
+    checker.warnings().iter()
+        .filter(|warning| matches!(warning, zulon_typeck::Warning::UnreachablePattern { .. }))
+        .count()
+}
+
+#[test]
+fn test_match_non_exhaustive_optional() {
+    let source = r#"
+        enum Option<T> { Some(T), None }
+        use Option::{Some, None};
+
+        fn unwrap_or_zero(value: Option<i32>) -> i32 {
+            match value {
+                None => 0
+            }
+        }
+    "#;
+
+    assert_eq!(missing_patterns(source), vec!["Some(_)"]);
+    assert!(type_check(source).unwrap_err().to_string().contains("`Some(_)` not covered"));
+}
+
+#[test]
+fn test_match_non_exhaustive_enum_and_tuple() {
+    let colors = r#"
+        enum Color { Red, Green, Blue }
+
+        fn code(color: Color) -> i32 {
+            match color {
+                Color::Red => 1,
+                Green => 2
+            }
+        }
+    "#;
+    let pairs = r#"
+        fn both(pair: (bool, bool)) -> i32 {
+            match pair {
+                (true, _) => 1,
+                (_, true) => 2
+            }
+        }
+    "#;
+
+    assert_eq!(missing_patterns(colors), vec!["Color::Blue"]);
+    assert_eq!(missing_patterns(pairs), vec!["(false, false)"]);
+}
+
+#[test]
+fn test_match_integer_ranges() {
+    let gaps = r#"
+        fn digit(n: i32) -> i32 {
+            match n {
+                0..=9 => 1,
+                10 => 2,
+                20..30 => 3
+            }
+        }
+    "#;
+    let covered = r#"
+        fn flags(pair: (bool, char)) -> i32 {
+            match pair {
+                (true, _) => 0,
+                (false, '\0'..='m') => 1,
+                (false, 'n'..='\u{10FFFF}') => 2
+            }
+        }
+    "#;
+
+    assert_eq!(missing_patterns(gaps), vec!["i32::MIN..=-1", "11..=19", "30..=i32::MAX"]);
+    assert_type_check_passes(covered);
+    assert_type_error(r#"
+        fn backwards(n: i32) -> i32 {
+            match n {
+                5..=1 => 0,
+                _ => 1
+            }
+        }
+    "#);
+}
+
+#[test]
+fn test_match_slice_patterns() {
+    let source = r#"
+        fn first(values: &[i32]) -> i32 {
+            match values {
+                [] => 0,
+                [x] => x,
+                [x, _, ..] => x
+            }
+        }
+    "#;
+    let missing_long = r#"
+        fn first(values: &[i32]) -> i32 {
+            match values {
+                [] => 0,
+                [x] => x
+            }
+        }
+    "#;
+
+    // Coverage is checked, but the patterns cannot be compiled yet
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::UnsupportedArrayPattern { .. })));
+    assert_eq!(missing_patterns(missing_long), vec!["[_, _, ..]"]);
+}
+
+#[test]
+fn test_array_patterns_are_rejected() {
+    let if_let = r#"
+        fn first(values: [i32; 2]) -> i32 {
+            if let [x, _] = values { x } else { 0 }
+        }
+    "#;
+    let nested = r#"
+        fn first(pair: (i32, [i32; 1])) -> i32 {
+            match pair {
+                (_, [x]) => x
+            }
+        }
+    "#;
+    let wrong_len = r#"
+        fn first(values: [i32; 2]) -> i32 {
+            match values {
+                [x] => x
+            }
+        }
+    "#;
+
+    for source in [if_let, nested] {
+        assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::UnsupportedArrayPattern { .. })));
+    }
+    // Type errors in the patterns come first
+    assert!(matches!(type_check(wrong_len), Err(zulon_typeck::TypeError::ArityMismatch { .. })));
+}
+
+#[test]
+fn test_match_struct_and_or_patterns() {
+    let source = r#"
+        struct Point { x: i32, y: i32 }
+
+        fn axis(point: Point) -> i32 {
+            match point {
+                Point { x: 0, y } | Point { x: y, y: 0 } => y,
+                Point { x, y: _ } => x
+            }
+        }
+    "#;
+    let unknown_field = r#"
+        struct Point { x: i32, y: i32 }
+
+        fn depth(point: Point) -> i32 {
+            match point {
+                Point { z, y } => z
+            }
+        }
+    "#;
+
+    assert_type_check_passes(source);
+    assert!(matches!(type_check(unknown_field), Err(zulon_typeck::TypeError::UnknownField { .. })));
+}
+
+#[test]
+fn test_match_unreachable_arms() {
+    let source = r#"
+        fn classify(n: i32) -> i32 {
+            match n {
+                1 => 10,
+                1 | 2 => 20,
+                _ => 0,
+                3 => 30
+            }
+        }
+    "#;
+
+    // The arm repeating `1` and the arm after the wildcard
+    assert_eq!(unreachable_arms(source), 2);
+}
+
+#[test]
+fn test_match_guards() {
+    let guard_not_bool = r#"
+        fn positive(n: i32) -> i32 {
+            match n {
+                x if x => 1,
+                _ => 0
+            }
+        }
+    "#;
+    let guarded_only = r#"
+        enum Option<T> { Some(T), None }
+        use Option::{Some, None};
+
+        fn positive(value: Option<i32>) -> i32 {
+            match value {
+                Some(n) if n > 0 => n,
+                None => 0
+            }
+        }
+    "#;
+
+    assert!(matches!(type_check(guard_not_bool), Err(zulon_typeck::TypeError::TypeMismatch { .. })));
+    assert_eq!(missing_patterns(guarded_only), vec!["Some(_)"]);
+}
+
 //
 // Binary Operator Tests
 //
@@ -1061,3 +1518,552 @@ fn test_complex_control_flow() {
 
     assert_type_check_passes(source);
 }
+
+// ============================================================================
+// Traits and Impls
+// ============================================================================
+
+#[test]
+fn test_trait_impl_with_default_method() {
+    let source = r#"
+        trait Describe {
+            fn id(&self) -> i32;
+            fn twice(&self) -> i32 { self.id() + self.id() }
+        }
+
+        struct Item { id: i32 }
+
+        impl Describe for Item {
+            fn id(&self) -> i32 { self.id }
+        }
+
+        fn main() -> i32 {
+            let item = Item { id: 7 };
+            item.twice()
+        }
+    "#;
+
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_trait_bound_provides_methods() {
+    let source = r#"
+        trait Named {
+            fn name(&self) -> i32;
+        }
+
+        fn get<T: Named>(value: T) -> i32 {
+            value.name()
+        }
+
+        fn get_unbounded<T>(value: T) -> i32 {
+            value.name()
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::NoMethod { .. })));
+}
+
+#[test]
+fn test_missing_trait_method() {
+    let source = r#"
+        trait Shape {
+            fn area(&self) -> i32;
+        }
+
+        struct Circle { r: i32 }
+
+        impl Shape for Circle {}
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::MissingTraitMethod { .. })));
+}
+
+#[test]
+fn test_overlapping_impls() {
+    let source = r#"
+        trait Show {
+            fn show(&self) -> i32;
+        }
+
+        struct Wrapper<T> { value: T }
+
+        impl<T> Show for Wrapper<T> {
+            fn show(&self) -> i32 { 0 }
+        }
+
+        impl Show for Wrapper<i32> {
+            fn show(&self) -> i32 { 1 }
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::ConflictingImpls { .. })));
+}
+
+#[test]
+fn test_impl_of_undefined_trait() {
+    let source = r#"
+        struct Point { x: i32 }
+
+        impl Display for Point {
+            fn fmt(&self) -> i32 { self.x }
+        }
+    "#;
+
+    assert_type_error(source);
+}
+
+// ============================================================================
+// Trait Objects
+// ============================================================================
+
+#[test]
+fn test_reference_coerces_to_trait_object() {
+    let source = r#"
+        trait Named {
+            fn id(&self) -> i32;
+        }
+
+        trait Shape: Named {
+            fn area(&self) -> i32;
+        }
+
+        struct Square { side: i32 }
+
+        impl Named for Square {
+            fn id(&self) -> i32 { 1 }
+        }
+
+        impl Shape for Square {
+            fn area(&self) -> i32 { self.side * self.side }
+        }
+
+        fn measure(shape: &dyn Shape) -> i32 {
+            shape.area() + shape.id()
+        }
+
+        fn main() -> i32 {
+            let square = Square { side: 3 };
+            let shape: &dyn Shape = &square;
+            measure(shape) + measure(&square)
+        }
+    "#;
+
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_trait_object_of_unimplemented_trait() {
+    let source = r#"
+        trait Shape {
+            fn area(&self) -> i32;
+        }
+
+        struct Point { x: i32 }
+
+        fn measure(shape: &dyn Shape) -> i32 {
+            shape.area()
+        }
+
+        fn main() -> i32 {
+            let point = Point { x: 1 };
+            measure(&point)
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::TraitBoundNotSatisfied { .. })));
+}
+
+#[test]
+fn test_trait_object_of_non_object_safe_trait() {
+    let returns_self = r#"
+        trait Duplicate {
+            fn duplicate(&self) -> Self;
+        }
+
+        fn copy_of(value: &dyn Duplicate) -> i32 { 0 }
+    "#;
+    let no_receiver = r#"
+        trait Make {
+            fn make() -> i32;
+        }
+
+        fn made(value: &dyn Make) -> i32 { 0 }
+    "#;
+    let generic_method = r#"
+        trait Visit {
+            fn visit<T>(&self, value: T) -> i32;
+        }
+
+        fn visited(value: &dyn Visit) -> i32 { 0 }
+    "#;
+
+    for source in [returns_self, no_receiver, generic_method] {
+        assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::NotObjectSafe { .. })));
+    }
+}
+
+#[test]
+fn test_associated_type_projection() {
+    let source = r#"
+        enum Option<T> { Some(T), None }
+        use Option::{Some, None};
+
+        trait Iterator {
+            type Item;
+            fn next(&mut self) -> Option<Self::Item>;
+        }
+
+        struct Counter { count: i64 }
+
+        impl Iterator for Counter {
+            type Item = i64;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                Option::Some(self.count)
+            }
+        }
+
+        fn first<I>(iter: I) -> Option<I::Item> where I: Iterator {
+            let mut it = iter;
+            it.next()
+        }
+
+        fn main() -> i64 {
+            let counter = Counter { count: 1 };
+            match first(counter) {
+                Some(value) => value,
+                None => 0,
+            }
+        }
+    "#;
+
+    assert_type_check_passes(source);
+}
+
+#[test]
+fn test_associated_type_mismatch() {
+    let source = r#"
+        enum Option<T> { Some(T), None }
+        use Option::{Some, None};
+
+        trait Iterator {
+            type Item;
+            fn next(&mut self) -> Option<Self::Item>;
+        }
+
+        struct Counter { count: i64 }
+
+        impl Iterator for Counter {
+            type Item = i64;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                Option::Some(self.count)
+            }
+        }
+
+        fn first<I>(iter: I) -> Option<I::Item> where I: Iterator {
+            let mut it = iter;
+            it.next()
+        }
+
+        fn main() -> bool {
+            let counter = Counter { count: 1 };
+            match first(counter) {
+                Some(value) => value,
+                None => false,
+            }
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::TypeMismatch { .. })));
+}
+
+#[test]
+fn test_missing_associated_type() {
+    let source = r#"
+        trait Iterator {
+            type Item;
+            fn next(&mut self) -> Option<Self::Item>;
+        }
+
+        struct Counter { count: i64 }
+
+        impl Iterator for Counter {
+            fn next(&mut self) -> Option<i64> {
+                None
+            }
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::MissingAssocType { .. })));
+}
+
+#[test]
+fn test_trait_bound_checked_at_call_site() {
+    let source = r#"
+        trait Named {
+            fn name(&self) -> i32;
+        }
+
+        struct Person { id: i32 }
+        struct Rock { weight: i32 }
+
+        impl Named for Person {
+            fn name(&self) -> i32 { self.id }
+        }
+
+        fn show<T>(value: T) -> i32 where T: Named {
+            value.name()
+        }
+
+        fn main() -> i32 {
+            let person = Person { id: 1 };
+            let rock = Rock { weight: 2 };
+            show(person) + show(rock)
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::TraitBoundNotSatisfied { .. })));
+}
+
+#[test]
+fn test_undefined_associated_type() {
+    let source = r#"
+        trait Named {
+            fn name(&self) -> i32;
+        }
+
+        fn get<T: Named>(value: T) -> T::Item {
+            value.name()
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::UndefinedAssocType { .. })));
+}
+
+#[test]
+fn test_const_values_are_evaluated() {
+    let source = r#"
+        struct Limits { low: i32, high: i32 }
+
+        const fn square(x: u64) -> u64 {
+            x * x
+        }
+
+        const fn sum_to(n: i32) -> i32 {
+            let mut total = 0;
+            let mut i = 0;
+            while i <= n {
+                total += i;
+                i += 1;
+            }
+            total
+        }
+
+        const SIDE: u64 = 3;
+        const AREA: u64 = square(SIDE) + 1;
+        const TOTAL: i32 = sum_to(4) * 2 - (7 % 4);
+        const LIMITS: Limits = Limits { high: TOTAL, low: -1 };
+        const SMALL: u8 = u8::MAX - 11;
+        static mut COUNT: i64 = i64::MAX / 2 + 1;
+    "#;
+
+    let ast = parse(source);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("type checking failed");
+
+    assert_eq!(checker.const_value("AREA"), Some(&ConstValue::Int(10)));
+    assert_eq!(checker.const_value("TOTAL"), Some(&ConstValue::Int(17)));
+    assert_eq!(checker.const_value("SMALL"), Some(&ConstValue::Int(244)));
+    assert_eq!(checker.const_value("LIMITS"), Some(&ConstValue::Struct {
+        name: "Limits".to_string(),
+        fields: vec![
+            ("low".to_string(), ConstValue::Int(-1)),
+            ("high".to_string(), ConstValue::Int(17)),
+        ],
+    }));
+    assert_eq!(checker.static_value("COUNT"), Some(&ConstValue::Int(1 << 62)));
+    assert_eq!(checker.const_value("COUNT"), None);
+}
+
+#[test]
+fn test_array_length_from_constant() {
+    let source = r#"
+        const N: usize = 2 * 2;
+
+        fn same(values: [i32; N]) -> [i32; N] {
+            values
+        }
+
+        fn main() -> [i32; 4] {
+            same([1, 2, 3, 4])
+        }
+    "#;
+
+    assert_type_check_passes(source);
+    assert!(matches!(
+        type_check(&source.replace("[1, 2, 3, 4]", "[1, 2, 3]")),
+        Err(zulon_typeck::TypeError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_const_casts() {
+    let source = r#"
+        const N: i32 = 2;
+        const A: i64 = 3 as i64;
+        const TRUNCATED: u8 = 300 as u8;
+        const WRAPPED: u8 = -1 as u8;
+        const SHRUNK: i8 = 200 as i8;
+        const TOWARD_ZERO: i32 = -3.9 as i32;
+        const SATURATED: i8 = 1000.0 as i8;
+        const LETTER: char = 65 as char;
+        const FLAG: u32 = true as u32;
+
+        fn first(values: [i32; N as usize]) -> i32 {
+            values[0]
+        }
+
+        fn main() -> i32 {
+            first([1, 2])
+        }
+    "#;
+
+    let ast = parse(source);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("type checking failed");
+
+    assert_eq!(checker.const_value("A"), Some(&ConstValue::Int(3)));
+    assert_eq!(checker.const_value("TRUNCATED"), Some(&ConstValue::Int(44)));
+    assert_eq!(checker.const_value("WRAPPED"), Some(&ConstValue::Int(255)));
+    assert_eq!(checker.const_value("SHRUNK"), Some(&ConstValue::Int(-56)));
+    assert_eq!(checker.const_value("TOWARD_ZERO"), Some(&ConstValue::Int(-3)));
+    assert_eq!(checker.const_value("SATURATED"), Some(&ConstValue::Int(127)));
+    assert_eq!(checker.const_value("LETTER"), Some(&ConstValue::Char('A')));
+    assert_eq!(checker.const_value("FLAG"), Some(&ConstValue::Int(1)));
+
+    // The array length is evaluated through the cast
+    assert!(matches!(
+        type_check(&source.replace("first([1, 2])", "first([1, 2, 3])")),
+        Err(zulon_typeck::TypeError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_cast() {
+    assert!(matches!(
+        type_check("fn main() -> bool { 1 as bool }"),
+        Err(zulon_typeck::TypeError::CannotConvert { .. })
+    ));
+    assert!(matches!(
+        type_check("fn main() -> char { 300 as char }"),
+        Err(zulon_typeck::TypeError::LiteralOutOfRange { ty, .. }) if *ty == zulon_typeck::Ty::U8
+    ));
+}
+
+#[test]
+fn test_const_overflow() {
+    let source = r#"
+        const BIG: u8 = 200;
+        const SUM: u8 = BIG + 100;
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::ConstOverflow { .. })));
+}
+
+#[test]
+fn test_const_division_by_zero() {
+    let source = r#"
+        const ZERO: i32 = 1 - 1;
+        const RATIO: i32 = 10 / ZERO;
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::ConstDivisionByZero { .. })));
+}
+
+#[test]
+fn test_const_calling_non_const_fn() {
+    let source = r#"
+        fn three() -> i32 {
+            3
+        }
+
+        const THREE: i32 = three();
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::NotConst { .. })));
+}
+
+#[test]
+fn test_const_cycle() {
+    let source = r#"
+        const A: i32 = B + 1;
+        const B: i32 = A + 1;
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::ConstCycle { .. })));
+}
+
+#[test]
+fn test_assign_to_static() {
+    let source = r#"
+        static mut COUNTER: i32 = 0;
+        static LIMIT: i32 = 10;
+
+        fn bump() {
+            COUNTER = COUNTER + 1;
+        }
+
+        fn reset() {
+            LIMIT = 0;
+        }
+    "#;
+
+    assert!(matches!(type_check(source), Err(zulon_typeck::TypeError::CannotAssignImmutable { .. })));
+}
+
+#[test]
+fn test_enum_discriminants() {
+    let source = r#"
+        const BASE: isize = 10;
+
+        enum Status {
+            Idle,
+            Busy = BASE * 2,
+            Done,
+            Failed = -1,
+        }
+    "#;
+
+    let ast = parse(source);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("type checking failed");
+    assert_eq!(checker.enum_discriminants("Status"), Some(&[0, 20, 21, -1][..]));
+
+    let duplicate = source.replace("Failed = -1", "Failed = 21");
+    assert!(matches!(
+        type_check(&duplicate),
+        Err(zulon_typeck::TypeError::DuplicateDiscriminant { value: 21, .. })
+    ));
+}
+
+#[test]
+fn test_type_alias() {
+    let source = r#"
+        type Meters = u64;
+        type Pair<T> = [T; 2];
+
+        fn twice(length: Meters) -> Pair<Meters> {
+            [length, length]
+        }
+
+        fn run() -> [u64; 2] {
+            twice(1)
+        }
+    "#;
+
+    assert_type_check_passes(source);
+    assert_type_error(&source.replace("[length, length]", "[length, length, length]"));
+}